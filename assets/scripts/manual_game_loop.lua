@@ -0,0 +1,3 @@
+function on_fixed_step(step)
+    print(string.format("fixed step %d", step))
+end
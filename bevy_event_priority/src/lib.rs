@@ -2,7 +2,10 @@ use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
 use std::marker::PhantomData;
 use std::sync::atomic::Ordering::Relaxed;
-use std::{collections::BinaryHeap, sync::atomic::AtomicU32};
+use std::{
+    collections::{BTreeMap, BinaryHeap},
+    sync::atomic::AtomicU32,
+};
 
 pub trait PriorityEvent: Send + Sync + 'static {}
 impl<E: Send + Sync + 'static> PriorityEvent for E {}
@@ -58,6 +61,22 @@ impl<E: Clone> Clone for EventInstance<E> {
     }
 }
 
+/// When an event is handled
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Priority {
+    /// handled by the next reader draining immediate events, regardless of the priority range it reads,
+    /// e.g. for responses to input where waiting for a later stage or frame is noticeable
+    Immediate,
+    /// handled by the readers of priority ranges containing the priority (0 is highest, inf is lowest)
+    Staged(u32),
+}
+
+impl From<u32> for Priority {
+    fn from(prio: u32) -> Self {
+        Priority::Staged(prio)
+    }
+}
+
 /// An event priority queue.
 /// Used when the ordering of events should be influenced by other factors.
 /// This implementation does NOT provide double buffering.
@@ -66,16 +85,37 @@ impl<E: Clone> Clone for EventInstance<E> {
 #[derive(Debug, Resource)]
 pub struct PriorityEvents<E> {
     events: BinaryHeap<EventInstance<E>>,
+    /// events sent with [`Priority::Immediate`], in the order they were sent
+    immediate: Vec<E>,
+    /// the number of events ever sent at each priority
+    sent: BTreeMap<u32, u64>,
 }
 
 impl<E> Default for PriorityEvents<E> {
     fn default() -> Self {
         Self {
             events: BinaryHeap::new(),
+            immediate: Vec::new(),
+            sent: BTreeMap::new(),
         }
     }
 }
 
+impl<E> PriorityEvents<E> {
+    /// The number of events ever sent with priorities in the range [max,min] (inclusive)
+    pub fn sent_in_range(&self, max: u32, min: u32) -> u64 {
+        if max > min {
+            return 0;
+        }
+        self.sent.range(max..=min).map(|(_, count)| count).sum()
+    }
+
+    fn push(&mut self, event: E, prio: u32) {
+        *self.sent.entry(prio).or_default() += 1;
+        self.events.push(EventInstance::new(event, prio));
+    }
+}
+
 #[derive(SystemParam)]
 pub struct PriorityEventReader<'w, 's, E: PriorityEvent> {
     events: ResMut<'w, PriorityEvents<E>>,
@@ -89,10 +129,8 @@ pub struct PriorityIterator<'w, E: PriorityEvent> {
     events: &'w mut PriorityEvents<E>,
 }
 
-impl<'w, E: PriorityEvent> Iterator for PriorityIterator<'w, E> {
-    type Item = E;
-
-    fn next(&mut self) -> Option<Self::Item> {
+impl<'w, E: PriorityEvent> PriorityIterator<'w, E> {
+    fn next_instance(&mut self) -> Option<EventInstance<E>> {
         while let Some(e) = self.events.events.peek() {
             if e.prio > self.min {
                 return None;
@@ -104,7 +142,15 @@ impl<'w, E: PriorityEvent> Iterator for PriorityIterator<'w, E> {
             };
         }
 
-        self.events.events.pop().map(|e| e.event)
+        self.events.events.pop()
+    }
+}
+
+impl<'w, E: PriorityEvent> Iterator for PriorityIterator<'w, E> {
+    type Item = E;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_instance().map(|e| e.event)
     }
 }
 
@@ -121,9 +167,29 @@ impl<'s, E: PriorityEvent> PriorityEventReader<'_, 's, E> {
         }
     }
 
+    /// Like [`Self::iter_prio_range`] but also yields the priority each event was sent with,
+    /// e.g. to send them again later
+    pub fn iter_prio_range_with_priority(
+        &mut self,
+        max: u32,
+        min: u32,
+    ) -> impl Iterator<Item = (u32, E)> + '_ {
+        let mut events = PriorityIterator {
+            min,
+            max,
+            events: self.events.as_mut(),
+        };
+        std::iter::from_fn(move || events.next_instance().map(|e| (e.prio, e.event)))
+    }
+
+    /// Iterates over the events sent with [`Priority::Immediate`] in the order they were sent, while also clearing them
+    pub fn drain_immediate(&mut self) -> impl Iterator<Item = E> + '_ {
+        self.events.immediate.drain(..)
+    }
+
     /// Determines the number of events available to be read, without consuming any
     pub fn len(&self) -> usize {
-        self.events.events.len()
+        self.events.events.len() + self.events.immediate.len()
     }
 
     /// Determines if there are any events to be read, without consuming any.
@@ -142,22 +208,32 @@ pub struct PriorityEventWriter<'w, 's, E: PriorityEvent> {
 
 impl<'w, 's, E: PriorityEvent> PriorityEventWriter<'w, 's, E> {
     pub fn send(&mut self, event: E, prio: u32) {
-        self.events.events.push(EventInstance::new(event, prio));
+        self.events.push(event, prio);
     }
 
     pub fn send_batch(&mut self, events: impl Iterator<Item = E>, prio: u32) {
-        self.events
-            .events
-            .extend(events.map(|v| EventInstance::new(v, prio)))
+        for event in events {
+            self.events.push(event, prio);
+        }
+    }
+
+    /// Sends an event handled by the next reader draining immediate events, see [`Priority::Immediate`]
+    pub fn send_immediate(&mut self, event: E) {
+        self.events.immediate.push(event);
+    }
+
+    pub fn send_with_priority(&mut self, event: E, priority: Priority) {
+        match priority {
+            Priority::Immediate => self.send_immediate(event),
+            Priority::Staged(prio) => self.send(event, prio),
+        }
     }
 
     pub fn send_default(&mut self, prio: u32)
     where
         E: Default,
     {
-        self.events
-            .events
-            .push(EventInstance::new(E::default(), prio))
+        self.events.push(E::default(), prio)
     }
 }
 
@@ -409,4 +485,68 @@ mod tests {
             vec![]
         );
     }
+
+    #[test]
+    fn test_prio_range_with_priority() {
+        let mut world = World::new();
+        let mut state_writer: SystemState<PriorityEventWriter<TestEvent>> =
+            SystemState::new(&mut world);
+        let mut state_reader: SystemState<PriorityEventReader<TestEvent>> =
+            SystemState::new(&mut world);
+
+        world.init_resource::<PriorityEvents<TestEvent>>();
+
+        {
+            let mut w = state_writer.get_mut(&mut world);
+            w.send(TestEvent(0), 0);
+            w.send(TestEvent(1), 2);
+            w.send(TestEvent(2), 1);
+            w.send(TestEvent(3), 3);
+        }
+
+        {
+            let mut r = state_reader.get_mut(&mut world);
+
+            // 0 is discarded, 3 is left for a later range
+            assert_eq!(
+                r.iter_prio_range_with_priority(1, 2).collect::<Vec<_>>(),
+                vec![(1, TestEvent(2)), (2, TestEvent(1))]
+            );
+            assert_eq!(r.len(), 1);
+        }
+    }
+
+    #[test]
+    fn test_immediate_events() {
+        let mut world = World::new();
+        let mut state_writer: SystemState<PriorityEventWriter<TestEvent>> =
+            SystemState::new(&mut world);
+        let mut state_reader: SystemState<PriorityEventReader<TestEvent>> =
+            SystemState::new(&mut world);
+
+        world.init_resource::<PriorityEvents<TestEvent>>();
+
+        {
+            let mut w = state_writer.get_mut(&mut world);
+            w.send(TestEvent(0), 0);
+            w.send_with_priority(TestEvent(1), Priority::Immediate);
+            w.send_immediate(TestEvent(2));
+        }
+
+        {
+            let mut r = state_reader.get_mut(&mut world);
+            assert_eq!(r.len(), 3);
+
+            // immediate events are read in the order they were sent, outside of any range
+            assert_eq!(
+                r.drain_immediate().collect::<Vec<_>>(),
+                vec![TestEvent(1), TestEvent(2)]
+            );
+            assert_eq!(r.len(), 1);
+            assert_eq!(
+                r.iter_prio_range(0, 0).collect::<Vec<_>>(),
+                vec![TestEvent(0)]
+            );
+        }
+    }
 }
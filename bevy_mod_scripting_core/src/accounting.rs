@@ -0,0 +1,235 @@
+//! Per-script CPU time accounting and throttling policies
+use bevy::{
+    prelude::*,
+    utils::{Duration, HashMap},
+};
+use std::marker::PhantomData;
+
+use crate::hosts::{ScriptData, ScriptHost, ScriptId};
+
+/// Cumulative CPU time statistics of a single script instance
+#[derive(Debug, Clone, Default)]
+pub struct ScriptCpuStats {
+    /// total time spent handling events since the script was loaded
+    pub total: Duration,
+    /// time spent handling events during the last full frame
+    pub last_frame: Duration,
+    /// time spent handling events so far in the current frame
+    pub current_frame: Duration,
+    /// the number of times the script was given events to handle
+    pub calls: u64,
+}
+
+/// Tracks the CPU time spent by each script of the host `H` while handling events.
+///
+/// Timings are recorded by every script handler stage of the host and rolled over at the start of each frame.
+#[derive(Resource)]
+pub struct ScriptCpuUsage<H: ScriptHost> {
+    frame: u64,
+    scripts: HashMap<ScriptId, ScriptCpuStats>,
+    _ph: PhantomData<H>,
+}
+
+impl<H: ScriptHost> Default for ScriptCpuUsage<H> {
+    fn default() -> Self {
+        Self {
+            frame: 0,
+            scripts: Default::default(),
+            _ph: Default::default(),
+        }
+    }
+}
+
+impl<H: ScriptHost> ScriptCpuUsage<H> {
+    /// The number of frames elapsed since the host was registered
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    /// Retrieves the statistics of the given script, if it ever handled any events
+    pub fn get(&self, script_id: ScriptId) -> Option<&ScriptCpuStats> {
+        self.scripts.get(&script_id)
+    }
+
+    /// Iterates over the statistics of all scripts which handled events
+    pub fn iter(&self) -> impl Iterator<Item = (ScriptId, &ScriptCpuStats)> {
+        self.scripts.iter().map(|(sid, s)| (*sid, s))
+    }
+
+    /// Total time spent by all scripts of this host during the last full frame
+    pub fn last_frame_total(&self) -> Duration {
+        self.scripts.values().map(|s| s.last_frame).sum()
+    }
+
+    /// The fraction of the last frame's script time used by the given script, in the range [0,1]
+    pub fn last_frame_share(&self, script_id: ScriptId) -> f32 {
+        let total = self.last_frame_total().as_secs_f32();
+        if total == 0.0 {
+            return 0.0;
+        }
+
+        self.get(script_id)
+            .map(|s| s.last_frame.as_secs_f32() / total)
+            .unwrap_or_default()
+    }
+
+    /// Adds the given time to the statistics of the script
+    pub fn record(&mut self, script_id: ScriptId, elapsed: Duration) {
+        let stats = self.scripts.entry(script_id).or_default();
+        stats.total += elapsed;
+        stats.current_frame += elapsed;
+        stats.calls += 1;
+    }
+
+    /// Removes statistics of scripts for which the predicate returns false
+    pub fn retain<F: FnMut(ScriptId) -> bool>(&mut self, mut f: F) {
+        self.scripts.retain(|sid, _| f(*sid))
+    }
+
+    /// Finishes the current frame, moving current frame timings to `last_frame`
+    pub fn advance_frame(&mut self) {
+        self.frame += 1;
+        for stats in self.scripts.values_mut() {
+            stats.last_frame = std::mem::take(&mut stats.current_frame);
+        }
+    }
+}
+
+/// Rolls over the per-frame timings of all scripts of the given host
+pub fn advance_script_cpu_usage<H: ScriptHost>(mut usage: ResMut<ScriptCpuUsage<H>>) {
+    usage.advance_frame();
+}
+
+/// Describes the context in which a script is about to receive events
+#[derive(Debug)]
+pub struct ThrottleContext<'a> {
+    /// the number of frames elapsed since the host was registered
+    pub frame: u64,
+    /// the highest priority (lowest number) handled by the current handler stage
+    pub max_priority: u32,
+    /// the lowest priority (highest number) handled by the current handler stage
+    pub min_priority: u32,
+    /// CPU statistics of the script, None if the script never handled any events
+    pub stats: Option<&'a ScriptCpuStats>,
+    /// the fraction of the last frame's script time used by this script, in the range [0,1]
+    pub last_frame_share: f32,
+}
+
+/// A policy deciding which scripts receive events in a given handler stage run.
+///
+/// Events are consumed by handler stages regardless of if a script was throttled,
+/// so a throttled script will simply never see the events it missed.
+pub trait ScriptThrottlePolicy: Send + Sync + 'static {
+    /// Returns false if the script should not receive events this time around
+    fn should_deliver(&self, script: &ScriptData, ctx: &ThrottleContext) -> bool;
+}
+
+/// A throttle policy which de-prioritizes scripts exceeding their fair share of the script frame time,
+/// such scripts receive events from handler stages dealing exclusively with low priority events only every Nth frame.
+#[derive(Debug, Clone)]
+pub struct FairShareThrottle {
+    /// the largest fraction of the total script frame time a script can use before being throttled
+    pub max_share: f32,
+    /// scripts which take up less than this much time per frame are never throttled
+    pub min_frame_time: Duration,
+    /// handler stages whose highest priority is at or above this number are considered low priority
+    pub low_priority: u32,
+    /// throttled scripts receive low priority events only every Nth frame
+    pub every_nth_frame: u64,
+}
+
+impl Default for FairShareThrottle {
+    fn default() -> Self {
+        Self {
+            max_share: 0.5,
+            min_frame_time: Duration::from_millis(1),
+            low_priority: 1,
+            every_nth_frame: 4,
+        }
+    }
+}
+
+impl ScriptThrottlePolicy for FairShareThrottle {
+    fn should_deliver(&self, _script: &ScriptData, ctx: &ThrottleContext) -> bool {
+        if ctx.max_priority < self.low_priority {
+            return true;
+        }
+
+        let over_budget = ctx
+            .stats
+            .map(|s| s.last_frame > self.min_frame_time)
+            .unwrap_or(false)
+            && ctx.last_frame_share > self.max_share;
+
+        !over_budget || ctx.frame % self.every_nth_frame.max(1) == 0
+    }
+}
+
+/// Resource holding the throttle policy used by all script handler stages.
+/// If not present, all scripts always receive their events.
+#[derive(Resource)]
+pub struct ScriptThrottle(pub Box<dyn ScriptThrottlePolicy>);
+
+impl ScriptThrottle {
+    pub fn new<P: ScriptThrottlePolicy>(policy: P) -> Self {
+        Self(Box::new(policy))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hosts::test_host::TestHost;
+
+    #[test]
+    fn cpu_usage_rolls_over_frames() {
+        let mut usage = ScriptCpuUsage::<TestHost>::default();
+        usage.record(0, Duration::from_millis(3));
+        usage.record(1, Duration::from_millis(1));
+        usage.record(0, Duration::from_millis(3));
+        assert_eq!(usage.last_frame_total(), Duration::ZERO);
+
+        usage.advance_frame();
+        assert_eq!(usage.frame(), 1);
+        assert_eq!(usage.get(0).unwrap().calls, 2);
+        assert_eq!(usage.last_frame_total(), Duration::from_millis(7));
+        assert!((usage.last_frame_share(0) - 6.0 / 7.0).abs() < 1e-6);
+        assert_eq!(usage.last_frame_share(2), 0.0);
+
+        usage.advance_frame();
+        assert_eq!(usage.get(0).unwrap().last_frame, Duration::ZERO);
+        assert_eq!(usage.get(0).unwrap().total, Duration::from_millis(6));
+
+        usage.retain(|sid| sid != 1);
+        assert!(usage.get(1).is_none());
+    }
+
+    #[test]
+    fn fair_share_throttle_delivers_low_priority_events_every_nth_frame() {
+        let throttle = FairShareThrottle::default();
+        let script = ScriptData {
+            sid: 0,
+            entity: Entity::from_raw(0),
+            name: "script.lua",
+            groups: &[],
+        };
+        let stats = ScriptCpuStats {
+            last_frame: Duration::from_millis(10),
+            ..Default::default()
+        };
+        let ctx = |frame, max_priority, last_frame_share| ThrottleContext {
+            frame,
+            max_priority,
+            min_priority: max_priority,
+            stats: Some(&stats),
+            last_frame_share,
+        };
+
+        // high priority events are always delivered
+        assert!(throttle.should_deliver(&script, &ctx(1, 0, 0.9)));
+        // scripts within their share are not throttled
+        assert!(throttle.should_deliver(&script, &ctx(1, 1, 0.3)));
+        assert!(!throttle.should_deliver(&script, &ctx(1, 1, 0.9)));
+        assert!(throttle.should_deliver(&script, &ctx(4, 1, 0.9)));
+    }
+}
@@ -1,8 +1,78 @@
 use bevy::asset::Asset;
+use std::ops::Range;
 
 /// All code assets share this common interface.
 /// When adding a new code asset don't forget to implement asset loading
 /// and inserting appropriate systems when registering with the app
 pub trait CodeAsset: Asset {
     fn bytes(&self) -> &[u8];
+
+    /// Errors found while loading the asset which keep it from running, such as type errors reported by a compiler.
+    /// Scripts using the asset report these as their load error instead of being loaded
+    fn load_error(&self) -> Option<&str> {
+        None
+    }
+}
+
+/// Splits a script file into labeled sections, each starting on a line of the form `{marker} <label>`
+/// and lasting until the next such line or the end of the file.
+/// Code before the first marker does not belong to any section, and the marker has to be followed by whitespace,
+/// so that e.g. `--#scripted` is not taken for the marker `--#script`.
+///
+/// Returns the label and byte range of every section, used by asset loaders to create labeled sub-assets
+/// (e.g. `scripts/enemies.lua#goblin`) which can be attached as independent scripts.
+pub fn script_sections(source: &[u8], marker: &str) -> Vec<(String, Range<usize>)> {
+    let mut sections = Vec::default();
+    let mut current: Option<(String, usize)> = None;
+    let mut line_start = 0;
+
+    while line_start < source.len() {
+        let line_end = source[line_start..]
+            .iter()
+            .position(|b| *b == b'\n')
+            .map(|i| line_start + i + 1)
+            .unwrap_or(source.len());
+
+        let line = String::from_utf8_lossy(&source[line_start..line_end]);
+        let label = line
+            .trim_start()
+            .strip_prefix(marker)
+            .filter(|rest| rest.starts_with(char::is_whitespace))
+            .map(str::trim)
+            .filter(|label| !label.is_empty());
+
+        if let Some(label) = label {
+            if let Some((prev, start)) = current.take() {
+                sections.push((prev, start..line_start));
+            }
+            current = Some((label.to_owned(), line_end));
+        }
+
+        line_start = line_end;
+    }
+
+    if let Some((label, start)) = current {
+        sections.push((label, start..source.len()));
+    }
+
+    sections
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_sections_at_markers() {
+        let source = b"shared()\n--#script a\none()\n--#scripted\n--#script\n  --#script b \ntwo()";
+        let sections = script_sections(source, "--#script");
+        let text = |range: &Range<usize>| std::str::from_utf8(&source[range.clone()]).unwrap();
+
+        assert_eq!(sections.len(), 2);
+        assert_eq!(sections[0].0, "a");
+        // markers without whitespace or a label are part of the section
+        assert_eq!(text(&sections[0].1), "one()\n--#scripted\n--#script\n");
+        assert_eq!(sections[1].0, "b");
+        assert_eq!(text(&sections[1].1), "two()");
+    }
 }
@@ -0,0 +1,43 @@
+//! Registering API providers from anywhere in the codebase through the `#[script_api_provider]` attribute
+use std::any::TypeId;
+
+use bevy::prelude::App;
+
+use crate::hosts::ScriptHost;
+
+pub use inventory;
+
+/// An API provider submitted by the `#[script_api_provider(host = ..)]` attribute of `bevy_mod_scripting_derive`,
+/// which adds it to its host when the host is added with [`AddScriptHost::add_script_host`](crate::AddScriptHost::add_script_host):
+/// ```rust,ignore
+/// #[script_api_provider(host = LuaScriptHost<MyArgs>)]
+/// pub struct InventoryAPIProvider;
+///
+/// impl APIProvider for InventoryAPIProvider { .. }
+/// ```
+/// This keeps bindings next to the systems they expose, without a central list of providers.
+/// Providers with fields must implement `Default`.
+///
+/// Registrations are collected by the linker, so the order in which providers of one host are added is unspecified.
+/// Providers which depend on each other should be added by hand.
+pub struct ScriptApiProviderRegistration {
+    host: fn() -> TypeId,
+    register: fn(&mut App),
+}
+
+impl ScriptApiProviderRegistration {
+    pub const fn new(host: fn() -> TypeId, register: fn(&mut App)) -> Self {
+        Self { host, register }
+    }
+}
+
+inventory::collect!(ScriptApiProviderRegistration);
+
+/// Adds every provider submitted for the host `H`, called by [`AddScriptHost::add_script_host`](crate::AddScriptHost::add_script_host)
+pub fn register_submitted_api_providers<H: ScriptHost>(app: &mut App) {
+    for registration in inventory::iter::<ScriptApiProviderRegistration> {
+        if (registration.host)() == TypeId::of::<H>() {
+            (registration.register)(app);
+        }
+    }
+}
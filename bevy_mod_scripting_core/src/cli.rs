@@ -0,0 +1,408 @@
+//! Command line flags for script tooling, so that a game binary can generate documentation or check scripts
+//! without a separate tool. Requires the `cli` feature.
+use std::{
+    ffi::OsString,
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+use bevy::prelude::*;
+use clap::{Args, Command, FromArgMatches};
+
+use crate::{
+    docs::{DocFragment, HookRegistry},
+    event::ScriptErrorEvent,
+    hosts::{APIProviders, ScriptData, ScriptHost, ScriptId},
+    testing::discover_script_tests,
+};
+
+/// The scripting flags, which can be flattened into the game's own clap parser with `#[command(flatten)]`
+#[derive(Args, Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScriptingArgs {
+    /// Generates script documentation and exits. The format is the name of a script host, e.g. `lua`,
+    /// or `all` for every host, the path overrides `SCRIPT_DOC_DIR`
+    #[arg(long, num_args = 0..=2, value_names = ["FORMAT", "PATH"])]
+    pub gen_script_docs: Option<Vec<String>>,
+
+    /// Loads every script under the directory with the host handling its extension, runs their validation hook
+    /// if the host has one, and exits with a nonzero status if any script fails
+    #[arg(long, value_name = "DIR")]
+    pub validate_scripts: Option<PathBuf>,
+
+    /// Lists the API providers and hooks of every script host and exits
+    #[arg(long)]
+    pub list_api: bool,
+}
+
+impl ScriptingArgs {
+    /// Parses the scripting flags out of the given arguments, ignoring any other arguments and flags
+    pub fn parse_from<I: IntoIterator<Item = T>, T: Into<OsString> + Clone>(args: I) -> Self {
+        let mut known = Vec::default();
+        let mut args = args.into_iter().map(Into::<OsString>::into);
+        // the binary name
+        known.extend(args.next());
+
+        // the number of values the last scripting flag can still take
+        let mut values_left = 0;
+        for arg in args {
+            let text = arg.to_str().unwrap_or_default();
+            if let Some(flag) = text.strip_prefix("--") {
+                let (name, inline_value) = match flag.split_once('=') {
+                    Some((name, _)) => (name, 1),
+                    None => (flag, 0),
+                };
+                let max_values = match name {
+                    "gen-script-docs" => 2,
+                    "validate-scripts" => 1,
+                    "list-api" => 0,
+                    _ => {
+                        values_left = 0;
+                        continue;
+                    }
+                };
+                values_left = max_values - inline_value.min(max_values);
+                known.push(arg);
+            } else if text.starts_with('-') {
+                values_left = 0;
+            } else if values_left > 0 {
+                values_left -= 1;
+                known.push(arg);
+            }
+        }
+
+        let command = Self::augment_args(Command::new("scripting"));
+        command
+            .try_get_matches_from(known)
+            .ok()
+            .and_then(|matches| Self::from_arg_matches(&matches).ok())
+            .unwrap_or_default()
+    }
+
+    /// True if any tooling was requested, in which case the game should exit once the hosts are processed instead of running
+    pub fn is_requested(&self) -> bool {
+        self.gen_script_docs.is_some() || self.validate_scripts.is_some() || self.list_api
+    }
+
+    fn docs_format(&self) -> Option<&str> {
+        self.gen_script_docs
+            .as_ref()
+            .map(|args| args.first().map(String::as_str).unwrap_or("all"))
+    }
+
+    fn docs_path(&self) -> Option<&str> {
+        self.gen_script_docs
+            .as_ref()
+            .and_then(|args| args.get(1))
+            .map(String::as_str)
+    }
+}
+
+/// Runs the tooling requested on the command line against the script hosts of an app.
+///
+/// Each host is processed separately since hosts are generic, once all are processed [`ScriptingCli::finish`] tells the game whether to exit:
+/// ```rust,ignore
+/// let args = ScriptingArgs::parse_from(std::env::args_os());
+/// let mut app = App::new();
+/// // no window is needed to run the tooling, e.g. when validating mods on a server or in a pre-commit hook
+/// if args.is_requested() {
+///     app.add_plugins(MinimalPlugins).add_plugin(AssetPlugin::default());
+/// } else {
+///     app.add_plugins(DefaultPlugins);
+/// }
+/// app.add_plugin(ScriptingPlugin) /* .. hosts and providers .. */;
+///
+/// if let Some(exit) = ScriptingCli::new(&mut app, args)
+///     .host_with_validation::<LuaScriptHost<()>>("lua", &["lua"], || LuaEvent {
+///         hook_name: "on_validate".into(),
+///         args: (),
+///         recipients: Recipients::All,
+///     })
+///     .host::<RhaiScriptHost<()>>("rhai", &["rhai"])
+///     .finish()
+/// {
+///     return exit;
+/// }
+/// app.run();
+/// ```
+pub struct ScriptingCli<'a> {
+    app: &'a mut App,
+    pub args: ScriptingArgs,
+    failures: Vec<String>,
+    startup_ran: bool,
+}
+
+impl<'a> ScriptingCli<'a> {
+    /// Reads the scripting flags from the arguments of the process, other arguments are left to the game
+    pub fn from_args(app: &'a mut App) -> Self {
+        Self::new(app, ScriptingArgs::parse_from(std::env::args_os()))
+    }
+
+    /// Uses flags parsed by the game, e.g. flattened into its own parser
+    pub fn new(app: &'a mut App, args: ScriptingArgs) -> Self {
+        Self {
+            app,
+            args,
+            failures: Vec::default(),
+            startup_ran: false,
+        }
+    }
+
+    /// Runs the requested tooling for the script host, which was added to the app under the given name.
+    /// Scripts to validate are picked by the file extensions the host loads, and only need to load to pass.
+    pub fn host<H: ScriptHost>(self, name: &str, extensions: &[&str]) -> Self {
+        self.host_inner::<H>(name, extensions, None)
+    }
+
+    /// Like [`ScriptingCli::host`], but each validated script is also sent the event, which should call the hook
+    /// scripts can use to check themselves, e.g. `on_validate`. Scripts raising any errors while handling it fail,
+    /// scripts not implementing the hook pass as long as they load.
+    pub fn host_with_validation<H: ScriptHost>(
+        self,
+        name: &str,
+        extensions: &[&str],
+        event: impl Fn() -> H::ScriptEvent,
+    ) -> Self {
+        self.host_inner::<H>(name, extensions, Some(&event))
+    }
+
+    fn host_inner<H: ScriptHost>(
+        mut self,
+        name: &str,
+        extensions: &[&str],
+        event: Option<&dyn Fn() -> H::ScriptEvent>,
+    ) -> Self {
+        if !self.args.is_requested() {
+            return self;
+        }
+
+        if !self.app.world.contains_resource::<APIProviders<H>>() {
+            self.failures.push(format!(
+                "The `{name}` script host was never added to the app"
+            ));
+            return self;
+        }
+
+        if self.args.list_api {
+            self.list_api::<H>(name);
+        }
+
+        if matches!(self.args.docs_format(), Some(format) if format == "all" || format == name) {
+            self.gen_docs::<H>(name);
+        }
+
+        if let Some(dir) = self.args.validate_scripts.clone() {
+            self.validate::<H>(name, &dir, extensions, event);
+        }
+
+        self
+    }
+
+    /// The exit code of the process if any tooling was requested, `None` if the game should run as usual
+    pub fn finish(self) -> Option<ExitCode> {
+        if !self.args.is_requested() {
+            return None;
+        }
+
+        if self.failures.is_empty() {
+            Some(ExitCode::SUCCESS)
+        } else {
+            eprintln!("\nfailures:\n");
+            for failure in &self.failures {
+                eprintln!("{failure}\n");
+            }
+            eprintln!("{} failure(s)", self.failures.len());
+            Some(ExitCode::FAILURE)
+        }
+    }
+
+    fn list_api<H: ScriptHost>(&self, name: &str) {
+        let providers = self.app.world.resource::<APIProviders<H>>();
+        let documented: Vec<&'static str> = providers
+            .providers
+            .iter()
+            .filter_map(|p| p.get_doc_fragment())
+            .map(|d| d.name())
+            .collect();
+
+        println!(
+            "{name}: {} API provider(s), documented as: {}",
+            providers.providers.len(),
+            if documented.is_empty() {
+                "-".to_owned()
+            } else {
+                documented.join(", ")
+            }
+        );
+
+        if let Some(hooks) = self.app.world.get_resource::<HookRegistry>() {
+            for hook in hooks.iter() {
+                println!("  hook {} - {}", hook.signature(), hook.when);
+            }
+        }
+    }
+
+    fn gen_docs<H: ScriptHost>(&mut self, name: &str) {
+        // hosts read the output directory from the environment
+        if let Some(path) = self.args.docs_path() {
+            std::env::set_var("SCRIPT_DOC_DIR", path);
+        }
+
+        let world = &self.app.world;
+        if let Err(e) = world
+            .resource::<APIProviders<H>>()
+            .gen_all_with_hooks(world.get_resource::<HookRegistry>())
+        {
+            self.failures
+                .push(format!("Could not generate `{name}` documentation: {e}"));
+        }
+    }
+
+    fn validate<H: ScriptHost>(
+        &mut self,
+        name: &str,
+        dir: &Path,
+        extensions: &[&str],
+        event: Option<&dyn Fn() -> H::ScriptEvent>,
+    ) {
+        // API providers may be attached by startup systems
+        if !self.startup_ran {
+            self.app.update();
+            self.startup_ran = true;
+        }
+
+        let mut scripts = Vec::default();
+        for extension in extensions {
+            match discover_script_tests(dir, &format!(".{extension}")) {
+                Ok(found) => scripts.extend(found),
+                Err(e) => {
+                    self.failures
+                        .push(format!("Could not read `{}`: {e}", dir.display()));
+                    return;
+                }
+            }
+        }
+        scripts.sort();
+
+        let mut host = self
+            .app
+            .world
+            .remove_resource::<H>()
+            .expect("Script host was removed");
+
+        let mut failed = 0;
+        for path in &scripts {
+            let script_name = path.to_string_lossy();
+            let result = std::fs::read(path)
+                .map_err(|e| e.to_string())
+                .and_then(|script| {
+                    Self::validate_script(self.app, &mut host, &script, &script_name, event)
+                });
+
+            match result {
+                Ok(()) => println!("validating {name} script {script_name} ... ok"),
+                Err(e) => {
+                    println!("validating {name} script {script_name} ... FAILED");
+                    self.failures.push(format!("---- {script_name} ----\n{e}"));
+                    failed += 1;
+                }
+            }
+        }
+
+        self.app.world.insert_resource(host);
+
+        println!(
+            "validated {} {name} script(s): {} ok, {failed} failed",
+            scripts.len(),
+            scripts.len() - failed
+        );
+    }
+
+    /// Loads and sets up a single script, then sends it the validation event if there is one.
+    /// Fails with the load error or with every [`ScriptErrorEvent`] raised while handling the event.
+    fn validate_script<H: ScriptHost>(
+        app: &mut App,
+        host: &mut H,
+        script: &[u8],
+        script_name: &str,
+        event: Option<&dyn Fn() -> H::ScriptEvent>,
+    ) -> Result<(), String> {
+        let world = &mut app.world;
+        let entity = world.spawn_empty().id();
+
+        let result = match event {
+            Some(event) => {
+                let mut errors = world
+                    .resource::<Events<ScriptErrorEvent>>()
+                    .get_reader_current();
+
+                host.run_one_shot(script, script_name, entity, world, event())
+                    .map_err(|e| e.to_string())
+                    .and_then(|_| {
+                        let errors: Vec<String> = errors
+                            .iter(world.resource::<Events<ScriptErrorEvent>>())
+                            .map(|e| e.error.to_string())
+                            .collect();
+                        if errors.is_empty() {
+                            Ok(())
+                        } else {
+                            Err(errors.join("\n"))
+                        }
+                    })
+            }
+            None => {
+                let mut providers = world
+                    .remove_resource::<APIProviders<H>>()
+                    .expect("API providers were removed");
+                let script_data = ScriptData {
+                    name: script_name,
+                    sid: ScriptId::MAX,
+                    entity,
+                    groups: &[],
+                };
+                let result = host
+                    .load_script(script, &script_data, &mut providers)
+                    .and_then(|mut ctx| host.setup_script(&script_data, &mut ctx, &mut providers))
+                    .map_err(|e| e.to_string());
+                world.insert_resource(providers);
+                result
+            }
+        };
+
+        world.despawn(entity);
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scripting_flags_are_picked_out_of_other_arguments() {
+        let args = ScriptingArgs::parse_from([
+            "game",
+            "--windowed",
+            "--gen-script-docs",
+            "lua",
+            "docs/",
+            "--level",
+            "3",
+            "--list-api",
+        ]);
+        assert_eq!(
+            args,
+            ScriptingArgs {
+                gen_script_docs: Some(vec!["lua".to_owned(), "docs/".to_owned()]),
+                validate_scripts: None,
+                list_api: true,
+            }
+        );
+        assert_eq!(args.docs_format(), Some("lua"));
+
+        let args = ScriptingArgs::parse_from(["game", "--gen-script-docs"]);
+        assert_eq!(args.docs_format(), Some("all"));
+        assert_eq!(args.docs_path(), None);
+
+        assert!(!ScriptingArgs::parse_from(["game", "--fullscreen"]).is_requested());
+    }
+}
@@ -0,0 +1,107 @@
+//! Deferred world changes issued by scripts
+use bevy::{
+    ecs::system::{Command, CommandQueue},
+    prelude::*,
+};
+
+/// Commands queued by scripts and API providers, applied once the script which queued them finished handling
+/// its events, or earlier if the script flushes the queue itself (e.g. `world:flush()` in Lua).
+///
+/// Changes made directly through the world (spawning entities, inserting components) are always visible immediately,
+/// only changes queued here are deferred. A script therefore sees its queued changes after flushing, and every script
+/// sees the changes queued by the scripts which handled events before it.
+#[derive(Resource, Default)]
+pub struct ScriptCommands {
+    queue: CommandQueue,
+    len: usize,
+}
+
+impl ScriptCommands {
+    /// Queues a command to be applied on the next flush
+    pub fn push<C: Command>(&mut self, command: C) {
+        self.queue.push(command);
+        self.len += 1;
+    }
+
+    /// The number of commands waiting to be applied
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+/// Applies all queued [`ScriptCommands`] to the world, commands queued while applying are applied on the next flush
+pub fn flush_script_commands(world: &mut World) {
+    let mut queue = match world.get_resource_mut::<ScriptCommands>() {
+        Some(mut commands) if !commands.is_empty() => {
+            commands.len = 0;
+            std::mem::take(&mut commands.queue)
+        }
+        _ => return,
+    };
+    queue.apply(world);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Component)]
+    struct Marker;
+
+    struct InsertMarker(Entity);
+
+    impl Command for InsertMarker {
+        fn write(self, world: &mut World) {
+            world.entity_mut(self.0).insert(Marker);
+        }
+    }
+
+    /// Queues another marker command while being applied
+    struct QueueMarker(Entity);
+
+    impl Command for QueueMarker {
+        fn write(self, world: &mut World) {
+            world
+                .resource_mut::<ScriptCommands>()
+                .push(InsertMarker(self.0));
+        }
+    }
+
+    #[test]
+    fn commands_are_deferred_until_flushed() {
+        let mut world = World::new();
+        let entity = world.spawn(()).id();
+        world.init_resource::<ScriptCommands>();
+
+        world
+            .resource_mut::<ScriptCommands>()
+            .push(InsertMarker(entity));
+        assert_eq!(world.resource::<ScriptCommands>().len(), 1);
+        assert!(world.get::<Marker>(entity).is_none());
+
+        flush_script_commands(&mut world);
+        assert!(world.get::<Marker>(entity).is_some());
+        assert!(world.resource::<ScriptCommands>().is_empty());
+    }
+
+    #[test]
+    fn commands_queued_while_flushing_wait_for_the_next_flush() {
+        let mut world = World::new();
+        let entity = world.spawn(()).id();
+        world.init_resource::<ScriptCommands>();
+
+        world
+            .resource_mut::<ScriptCommands>()
+            .push(QueueMarker(entity));
+        flush_script_commands(&mut world);
+        assert!(world.get::<Marker>(entity).is_none());
+        assert_eq!(world.resource::<ScriptCommands>().len(), 1);
+
+        flush_script_commands(&mut world);
+        assert!(world.get::<Marker>(entity).is_some());
+    }
+}
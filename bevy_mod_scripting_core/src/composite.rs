@@ -0,0 +1,242 @@
+//! Grouping and conditionally including API providers
+use bevy::prelude::App;
+
+use crate::{
+    docs::DocFragment,
+    error::ScriptError,
+    hosts::{APIProvider, APIProviders, ScriptData, ScriptHost},
+    world::WorldPointer,
+};
+
+/// A boxed API provider working with the given API target, script context and documentation fragment
+pub type BoxedAPIProvider<A, C, D> =
+    Box<dyn APIProvider<APITarget = A, ScriptContext = C, DocTarget = D>>;
+
+/// Attaches the APIs of the given closure under a namespace of the API target, see [`CompositeAPIProvider::namespaced`]
+pub type NamespaceFn<A> =
+    fn(&mut A, &str, &mut dyn FnMut(&mut A) -> Result<(), ScriptError>) -> Result<(), ScriptError>;
+
+/// Groups many API providers into one, which is attached, documented and registered as a single provider.
+///
+/// Sub-providers can be included conditionally with [`Self::when`], for example on a cargo feature:
+/// ```rust,ignore
+/// CompositeAPIProvider::new("physics")
+///     .with(RaycastAPIProvider)
+///     .with(DebugDrawAPIProvider)
+///     .when(cfg!(feature = "debug_draw"))
+/// ```
+/// The documentation fragments of all sub-providers are merged into one section.
+pub struct CompositeAPIProvider<A, C, D> {
+    name: &'static str,
+    providers: Vec<BoxedAPIProvider<A, C, D>>,
+    namespace: Option<NamespaceFn<A>>,
+}
+
+impl<A, C, D> CompositeAPIProvider<A, C, D>
+where
+    A: Send + Sync + 'static,
+    C: Send + Sync + 'static,
+    D: DocFragment,
+{
+    pub fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            providers: Vec::default(),
+            namespace: None,
+        }
+    }
+
+    /// The name of this group, used as the namespace if the group is namespaced
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Adds a sub-provider to the group
+    pub fn with<P>(mut self, provider: P) -> Self
+    where
+        P: APIProvider<APITarget = A, ScriptContext = C, DocTarget = D>,
+    {
+        self.providers.push(Box::new(provider));
+        self
+    }
+
+    /// Keeps the sub-provider added last only if `enabled` is true
+    pub fn when(mut self, enabled: bool) -> Self {
+        if !enabled {
+            self.providers.pop();
+        }
+        self
+    }
+
+    /// Attaches the APIs of all sub-providers under a namespace named after the group instead of globally,
+    /// using the given namespacing function of the script host (e.g. `lua_namespace` for Lua).
+    ///
+    /// Only APIs attached with [`APIProvider::attach_api`] are namespaced.
+    pub fn namespaced(mut self, namespace: NamespaceFn<A>) -> Self {
+        self.namespace = Some(namespace);
+        self
+    }
+
+    /// The number of sub-providers in the group
+    pub fn len(&self) -> usize {
+        self.providers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.providers.is_empty()
+    }
+}
+
+impl<A, C, D> APIProvider for CompositeAPIProvider<A, C, D>
+where
+    A: Send + Sync + 'static,
+    C: Send + Sync + 'static,
+    D: DocFragment,
+{
+    type APITarget = A;
+    type ScriptContext = C;
+    type DocTarget = D;
+
+    fn attach_api(&mut self, api: &mut Self::APITarget) -> Result<(), ScriptError> {
+        let providers = &mut self.providers;
+        let mut attach = |api: &mut A| -> Result<(), ScriptError> {
+            for p in providers.iter_mut() {
+                p.attach_api(api)?;
+            }
+            Ok(())
+        };
+
+        match self.namespace {
+            Some(namespace) => namespace(api, self.name, &mut attach),
+            None => attach(api),
+        }
+    }
+
+    fn setup_script_runtime(
+        &mut self,
+        world_ptr: WorldPointer,
+        script_data: &ScriptData,
+        ctx: &mut Self::ScriptContext,
+    ) -> Result<(), ScriptError> {
+        for p in self.providers.iter_mut() {
+            p.setup_script_runtime(world_ptr.clone(), script_data, ctx)?;
+        }
+        Ok(())
+    }
+
+    fn setup_script(
+        &mut self,
+        script_data: &ScriptData,
+        ctx: &mut Self::ScriptContext,
+    ) -> Result<(), ScriptError> {
+        for p in self.providers.iter_mut() {
+            p.setup_script(script_data, ctx)?;
+        }
+        Ok(())
+    }
+
+    fn get_doc_fragment(&self) -> Option<Self::DocTarget> {
+        self.providers
+            .iter()
+            .filter_map(|p| p.get_doc_fragment())
+            .reduce(|a, b| a.merge(b))
+    }
+
+    fn script_variables(&self) -> Vec<&'static str> {
+        self.providers
+            .iter()
+            .flat_map(|p| p.script_variables())
+            .collect()
+    }
+
+    fn register_with_app(&self, app: &mut App) {
+        for p in self.providers.iter() {
+            p.register_with_app(app);
+        }
+    }
+}
+
+impl<T: ScriptHost> APIProviders<T> {
+    /// Adds a provider, for building up a set of providers to add with [`crate::AddScriptApiProvider::add_api_providers`]
+    pub fn with<P>(mut self, provider: P) -> Self
+    where
+        P: APIProvider<
+            APITarget = T::APITarget,
+            ScriptContext = T::ScriptContext,
+            DocTarget = T::DocTarget,
+        >,
+    {
+        self.providers.push(Box::new(provider));
+        self
+    }
+
+    /// Keeps the provider added last only if `enabled` is true
+    pub fn when(mut self, enabled: bool) -> Self {
+        if !enabled {
+            self.providers.pop();
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hosts::test_host::TestDocs;
+
+    /// Records its name in the API target when attached
+    struct Named(&'static str);
+
+    impl APIProvider for Named {
+        type APITarget = Vec<String>;
+        type ScriptContext = ();
+        type DocTarget = TestDocs;
+
+        fn attach_api(&mut self, api: &mut Self::APITarget) -> Result<(), ScriptError> {
+            api.push(self.0.to_owned());
+            Ok(())
+        }
+
+        fn script_variables(&self) -> Vec<&'static str> {
+            vec![self.0]
+        }
+    }
+
+    fn namespace(
+        api: &mut Vec<String>,
+        name: &str,
+        attach: &mut dyn FnMut(&mut Vec<String>) -> Result<(), ScriptError>,
+    ) -> Result<(), ScriptError> {
+        api.push(format!("begin {name}"));
+        attach(api)?;
+        api.push(format!("end {name}"));
+        Ok(())
+    }
+
+    #[test]
+    fn attaches_the_included_providers_in_order() {
+        let mut composite = CompositeAPIProvider::new("physics")
+            .with(Named("raycast"))
+            .with(Named("debug_draw"))
+            .when(false)
+            .with(Named("joints"))
+            .when(true);
+        assert_eq!(composite.len(), 2);
+        assert_eq!(composite.script_variables(), vec!["raycast", "joints"]);
+
+        let mut api = Vec::default();
+        composite.attach_api(&mut api).unwrap();
+        assert_eq!(api, vec!["raycast", "joints"]);
+    }
+
+    #[test]
+    fn namespaced_groups_attach_inside_their_namespace() {
+        let mut composite = CompositeAPIProvider::new("physics")
+            .with(Named("raycast"))
+            .namespaced(namespace);
+
+        let mut api = Vec::default();
+        composite.attach_api(&mut api).unwrap();
+        assert_eq!(api, vec!["begin physics", "raycast", "end physics"]);
+    }
+}
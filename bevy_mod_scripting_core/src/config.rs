@@ -0,0 +1,275 @@
+//! Validating the scripting setup of an app, catching common misconfigurations on startup
+use std::{collections::BTreeMap, fmt};
+
+use bevy::prelude::*;
+use bevy_event_priority::PriorityEvents;
+
+use crate::{error::ScriptError, hosts::ScriptHost, priorities::ScriptHandlerPriorities};
+
+/// The number of frames after which handler stages which never received an event are reported
+pub const HANDLER_WRITER_CHECK_FRAMES: u32 = 600;
+
+/// Checks if any event was ever sent to the host within the priority range [max,min]
+type WriterCheck = fn(&World, u32, u32) -> bool;
+
+fn events_sent<T: ScriptHost>(world: &World, max: u32, min: u32) -> bool {
+    world
+        .get_resource::<PriorityEvents<T::ScriptEvent>>()
+        .map_or(false, |events| events.sent_in_range(max, min) > 0)
+}
+
+/// What was set up for a single script host
+#[derive(Default)]
+pub(crate) struct HostConfig {
+    pub(crate) registered: bool,
+    /// the priority ranges [max,min] of every handler stage
+    pub(crate) handlers: Vec<(u32, u32)>,
+    /// the names of the handler stages whose ranges are kept in [`ScriptHandlerPriorities`]
+    pub(crate) named_handlers: Vec<String>,
+    pub(crate) providers: usize,
+    /// the number of providers present each time documentation was generated
+    pub(crate) documented: Vec<usize>,
+    writer_check: Option<WriterCheck>,
+}
+
+/// Records the setup of every script host as the app is built, keyed by the type name of the host
+#[derive(Resource, Default)]
+pub(crate) struct ScriptingConfig {
+    hosts: BTreeMap<&'static str, HostConfig>,
+}
+
+impl HostConfig {
+    /// The priority ranges [max,min] of every handler stage, named ones as currently set in [`ScriptHandlerPriorities`]
+    fn ranges(&self, world: &World) -> Vec<(u32, u32)> {
+        let priorities = world.get_resource::<ScriptHandlerPriorities>();
+        let named = self
+            .named_handlers
+            .iter()
+            .filter_map(|name| priorities.and_then(|p| p.get(name)))
+            .map(|range| (range.max, range.min));
+        self.handlers.iter().copied().chain(named).collect()
+    }
+
+    fn has_handlers(&self) -> bool {
+        !self.handlers.is_empty() || !self.named_handlers.is_empty()
+    }
+}
+
+impl ScriptingConfig {
+    /// Records part of the setup of the host `T`
+    pub(crate) fn record<T: ScriptHost>(app: &mut App, f: impl FnOnce(&mut HostConfig)) {
+        let mut config = app
+            .world
+            .get_resource_or_insert_with(ScriptingConfig::default);
+        let host = config.hosts.entry(std::any::type_name::<T>()).or_default();
+        host.writer_check = Some(events_sent::<T>);
+        f(host);
+    }
+}
+
+/// The priority ranges [max,min] of every handler stage added for the host `T`
+pub fn handler_priority_ranges<T: ScriptHost>(world: &World) -> Vec<(u32, u32)> {
+    world
+        .get_resource::<ScriptingConfig>()
+        .and_then(|config| config.hosts.get(std::any::type_name::<T>()))
+        .map(|host| host.ranges(world))
+        .unwrap_or_default()
+}
+
+/// Checks that events of the host `T` sent with the given priority are handled by one of its handler stages,
+/// the error names the valid priority ranges otherwise.
+///
+/// Hosts without handler stages handle their events through [`ScriptHost::handle_events_manual`], so accept any priority.
+pub fn validate_handler_priority<T: ScriptHost>(
+    world: &World,
+    priority: u32,
+) -> Result<(), ScriptError> {
+    let ranges = handler_priority_ranges::<T>(world);
+    if ranges.is_empty()
+        || ranges
+            .iter()
+            .any(|(max, min)| (*max..=*min).contains(&priority))
+    {
+        return Ok(());
+    }
+
+    let valid = ranges
+        .iter()
+        .map(|(max, min)| format!("[{max},{min}]"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    Err(ScriptError::Other(format!(
+        "No handler stage handles events with priority {priority}, valid priority ranges are {valid}"
+    )))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ConfigSeverity {
+    /// the setup is likely unintended, but scripts can still work
+    Warning,
+    /// part of the setup has no effect
+    Error,
+}
+
+/// A single misconfiguration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigIssue {
+    pub severity: ConfigSeverity,
+    /// the type name of the script host the issue concerns
+    pub host: &'static str,
+    pub message: String,
+}
+
+impl fmt::Display for ConfigIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.host, self.message)
+    }
+}
+
+/// Every misconfiguration of the scripting setup found on startup, inserted and logged by the [`crate::ScriptingPlugin`].
+///
+/// Detected issues are:
+/// - script hosts with no handler stage
+/// - API providers added for hosts which were never registered
+/// - documentation generated for hosts without any API providers
+/// - handler stages which did not receive a single event during the first [`HANDLER_WRITER_CHECK_FRAMES`] frames,
+///   these are added to the report once that many frames have passed
+///
+/// Apps which handle events with [`ScriptHost::handle_events_manual`] can ignore the handler stage warnings.
+#[derive(Resource, Debug, Default, Clone, PartialEq, Eq)]
+pub struct ScriptingConfigReport {
+    pub issues: Vec<ConfigIssue>,
+}
+
+impl ScriptingConfigReport {
+    pub fn warnings(&self) -> impl Iterator<Item = &ConfigIssue> {
+        self.with_severity(ConfigSeverity::Warning)
+    }
+
+    pub fn errors(&self) -> impl Iterator<Item = &ConfigIssue> {
+        self.with_severity(ConfigSeverity::Error)
+    }
+
+    /// Returns true if no errors were found, warnings are allowed
+    pub fn is_ok(&self) -> bool {
+        self.errors().next().is_none()
+    }
+
+    fn with_severity(&self, severity: ConfigSeverity) -> impl Iterator<Item = &ConfigIssue> {
+        self.issues.iter().filter(move |i| i.severity == severity)
+    }
+
+    fn push(&mut self, severity: ConfigSeverity, host: &'static str, message: String) {
+        self.issues.push(ConfigIssue {
+            severity,
+            host,
+            message,
+        })
+    }
+
+    fn log(issues: &[ConfigIssue]) {
+        for issue in issues {
+            match issue.severity {
+                ConfigSeverity::Warning => warn!("Scripting configuration: {}", issue),
+                ConfigSeverity::Error => error!("Scripting configuration: {}", issue),
+            }
+        }
+    }
+}
+
+/// Builds the [`ScriptingConfigReport`] from the setup recorded while building the app and logs it
+pub fn validate_scripting_config(world: &mut World) {
+    let mut report = ScriptingConfigReport::default();
+
+    if let Some(config) = world.get_resource::<ScriptingConfig>() {
+        for (&name, host) in config.hosts.iter() {
+            if !host.registered {
+                if host.providers > 0 {
+                    report.push(
+                        ConfigSeverity::Error,
+                        name,
+                        format!(
+                            "{} API provider(s) were added but the host was never registered with `add_script_host`",
+                            host.providers
+                        ),
+                    );
+                }
+                if host.has_handlers() {
+                    report.push(
+                        ConfigSeverity::Error,
+                        name,
+                        "handler stages were added but the host was never registered with `add_script_host`".to_owned(),
+                    );
+                }
+                continue;
+            }
+
+            if !host.has_handlers() {
+                report.push(
+                    ConfigSeverity::Warning,
+                    name,
+                    "the host has no handler stage, its events are only handled through `handle_events_manual`".to_owned(),
+                );
+            }
+
+            if host.documented.iter().any(|providers| *providers == 0) {
+                report.push(
+                    ConfigSeverity::Warning,
+                    name,
+                    "documentation was generated before any API provider was added".to_owned(),
+                );
+            }
+        }
+    }
+
+    ScriptingConfigReport::log(&report.issues);
+    if report.issues.is_empty() {
+        info!("Scripting configuration: no issues found");
+    } else {
+        info!(
+            "Scripting configuration: {} error(s), {} warning(s)",
+            report.errors().count(),
+            report.warnings().count()
+        );
+    }
+    world.insert_resource(report);
+}
+
+/// Adds handler stages which have not received any events in the first [`HANDLER_WRITER_CHECK_FRAMES`] frames
+/// to the [`ScriptingConfigReport`]
+pub fn check_handler_writers(world: &mut World, mut frames: Local<u32>) {
+    if *frames > HANDLER_WRITER_CHECK_FRAMES {
+        return;
+    }
+    *frames += 1;
+    if *frames <= HANDLER_WRITER_CHECK_FRAMES {
+        return;
+    }
+
+    let mut issues = Vec::default();
+    if let Some(config) = world.get_resource::<ScriptingConfig>() {
+        for (&name, host) in config.hosts.iter().filter(|(_, h)| h.registered) {
+            let check = match host.writer_check {
+                Some(check) => check,
+                None => continue,
+            };
+            for (max, min) in host.ranges(world) {
+                if !check(world, max, min) {
+                    issues.push(ConfigIssue {
+                        severity: ConfigSeverity::Warning,
+                        host: name,
+                        message: format!(
+                            "no events were sent with priorities in [{max},{min}] during the first {HANDLER_WRITER_CHECK_FRAMES} frames, the handler may have no writer"
+                        ),
+                    });
+                }
+            }
+        }
+    }
+
+    ScriptingConfigReport::log(&issues);
+    world
+        .get_resource_or_insert_with(ScriptingConfigReport::default)
+        .issues
+        .extend(issues);
+}
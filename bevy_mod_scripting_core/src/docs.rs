@@ -1,3 +1,5 @@
+use bevy::prelude::Resource;
+
 use crate::error::ScriptError;
 
 /// A documentation piece exported by an `APIProvider`
@@ -7,4 +9,160 @@ pub trait DocFragment: 'static {
 
     /// Retrieves the name of the documentation fragment, most likely the name of your game!
     fn name(&self) -> &'static str;
+
+    /// Includes documentation of the hooks scripts can implement, by default hooks are not documented
+    fn with_hooks(self, _hooks: &HookRegistry) -> Self
+    where
+        Self: Sized,
+    {
+        self
+    }
+}
+
+/// Describes a single argument passed to a hook
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HookArg {
+    pub name: String,
+    /// the type of the argument as seen by scripts, e.g. `Entity` or `number`
+    pub ty: String,
+    pub description: String,
+}
+
+/// Describes a callback which scripts can implement and which is emitted by the game
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HookDoc {
+    /// the name of the function scripts need to define, e.g. `on_update`
+    pub name: String,
+    /// when the hook is called, e.g. `every frame`
+    pub when: String,
+    pub args: Vec<HookArg>,
+}
+
+impl HookDoc {
+    pub fn new(name: impl Into<String>, when: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            when: when.into(),
+            args: Vec::default(),
+        }
+    }
+
+    /// Adds an argument to the hook, arguments are passed in the order they were added
+    pub fn with_arg(
+        mut self,
+        name: impl Into<String>,
+        ty: impl Into<String>,
+        description: impl Into<String>,
+    ) -> Self {
+        self.args.push(HookArg {
+            name: name.into(),
+            ty: ty.into(),
+            description: description.into(),
+        });
+        self
+    }
+
+    /// The signature of the hook, e.g. `on_collision(entity: Entity, other: Entity)`
+    pub fn signature(&self) -> String {
+        let args = self
+            .args
+            .iter()
+            .map(|a| format!("{}: {}", a.name, a.ty))
+            .collect::<Vec<_>>();
+        format!("{}({})", self.name, args.join(", "))
+    }
+}
+
+/// The set of hooks emitted by the game, used to document the callbacks mods can implement
+/// alongside the API functions when generating documentation.
+#[derive(Resource, Debug, Default, Clone)]
+pub struct HookRegistry {
+    hooks: Vec<HookDoc>,
+}
+
+impl HookRegistry {
+    /// Registers a hook, replacing any previously registered hook with the same name
+    pub fn register(&mut self, hook: HookDoc) {
+        match self.hooks.iter_mut().find(|h| h.name == hook.name) {
+            Some(existing) => *existing = hook,
+            None => self.hooks.push(hook),
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&HookDoc> {
+        self.hooks.iter().find(|h| h.name == name)
+    }
+
+    /// Iterates over all hooks in the order they were registered
+    pub fn iter(&self) -> impl Iterator<Item = &HookDoc> {
+        self.hooks.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.hooks.is_empty()
+    }
+
+    /// Renders all hooks as a markdown page
+    pub fn to_markdown(&self, title: &str) -> String {
+        let mut out = format!(
+            "# {title} hooks\n\nCallbacks scripts can implement, these are called by the game.\n"
+        );
+
+        for hook in &self.hooks {
+            out.push_str(&format!(
+                "\n## `{}`\n\nCalled {}.\n",
+                hook.signature(),
+                hook.when
+            ));
+
+            if !hook.args.is_empty() {
+                out.push_str("\n| Argument | Type | Description |\n| --- | --- | --- |\n");
+                for arg in &hook.args {
+                    out.push_str(&format!(
+                        "| `{}` | `{}` | {} |\n",
+                        arg.name, arg.ty, arg.description
+                    ));
+                }
+            }
+        }
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registering_a_hook_again_replaces_it() {
+        let mut hooks = HookRegistry::default();
+        hooks.register(HookDoc::new("on_update", "every frame"));
+        hooks.register(HookDoc::new("on_collision", "on contact"));
+        hooks.register(HookDoc::new("on_update", "every fixed step"));
+
+        let names = hooks.iter().map(|h| h.name.as_str()).collect::<Vec<_>>();
+        assert_eq!(names, vec!["on_update", "on_collision"]);
+        assert_eq!(hooks.get("on_update").unwrap().when, "every fixed step");
+    }
+
+    #[test]
+    fn renders_hooks_as_markdown() {
+        let mut hooks = HookRegistry::default();
+        hooks.register(
+            HookDoc::new("on_collision", "when two entities touch")
+                .with_arg("entity", "Entity", "the entity running the script")
+                .with_arg("other", "Entity", "the entity it touched"),
+        );
+
+        assert_eq!(
+            hooks.get("on_collision").unwrap().signature(),
+            "on_collision(entity: Entity, other: Entity)"
+        );
+        let markdown = hooks.to_markdown("Game");
+        assert!(markdown.starts_with("# Game hooks\n"));
+        assert!(markdown.contains("## `on_collision(entity: Entity, other: Entity)`"));
+        assert!(markdown.contains("Called when two entities touch."));
+        assert!(markdown.contains("| `other` | `Entity` | the entity it touched |"));
+    }
 }
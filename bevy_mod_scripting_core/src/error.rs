@@ -1,11 +1,31 @@
 use thiserror::Error;
 
+/// The broad kind of a [`ScriptError`], for handling errors by policy rather than by variant,
+/// e.g. only disabling scripts after repeated runtime errors but always showing compile errors to the user
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ScriptErrorCategory {
+    /// the script could not be loaded or parsed
+    CompileError,
+    /// the script raised an error while running
+    RuntimeError,
+    /// a value passed between the script and Rust had the wrong type
+    ConversionError,
+    /// the script or host used an API incorrectly, i.e. called an invalid hook or exceeded a quota
+    ApiMisuse,
+    /// the script ran for longer than it was allowed to
+    Timeout,
+    /// the script used more memory than it was allowed to
+    MemoryLimit,
+    /// errors not caused by a particular script
+    Other,
+}
+
 #[derive(Error, Debug, Clone)]
 pub enum ScriptError {
     #[error("Runtime error in script `{script}` {msg}")]
     RuntimeError { script: String, msg: String },
-    #[error("Failed to load script asset for `{script}`")]
-    FailedToLoad { script: String },
+    #[error("Failed to load script asset for `{script}` {msg}")]
+    FailedToLoad { script: String, msg: String },
     #[error("Syntax error for script `{script}` {msg}")]
     SyntaxError { script: String, msg: String },
     #[error("Callback method `{callback}` invalid for script `{script}` {msg}")]
@@ -18,6 +38,14 @@ pub enum ScriptError {
     FailedToAttachAPI { script: String, msg: String },
     #[error("Failed to generate documentation `{0}`")]
     DocGenError(String),
+    #[error("Type conversion error in script `{script}` {msg}")]
+    ConversionError { script: String, msg: String },
+    #[error("Script `{script}` exceeded its time limit {msg}")]
+    Timeout { script: String, msg: String },
+    #[error("Script `{script}` exceeded its memory limit {msg}")]
+    MemoryLimit { script: String, msg: String },
+    #[error("Exceeded the quota of {limit} `{api}` calls per frame")]
+    QuotaExceeded { api: String, limit: u32 },
     #[error("{0}")]
     Other(String),
 }
@@ -27,4 +55,51 @@ impl ScriptError {
     pub fn new_other<T: std::error::Error>(other: T) -> Self {
         Self::Other(other.to_string())
     }
+
+    pub fn category(&self) -> ScriptErrorCategory {
+        match self {
+            Self::FailedToLoad { .. } | Self::SyntaxError { .. } => {
+                ScriptErrorCategory::CompileError
+            }
+            Self::RuntimeError { .. } => ScriptErrorCategory::RuntimeError,
+            Self::ConversionError { .. } => ScriptErrorCategory::ConversionError,
+            Self::InvalidCallback { .. }
+            | Self::FailedToAttachAPI { .. }
+            | Self::QuotaExceeded { .. } => ScriptErrorCategory::ApiMisuse,
+            Self::Timeout { .. } => ScriptErrorCategory::Timeout,
+            Self::MemoryLimit { .. } => ScriptErrorCategory::MemoryLimit,
+            Self::DocGenError(_) | Self::Other(_) => ScriptErrorCategory::Other,
+        }
+    }
+
+    /// The name of the script which caused the error, if the error was caused by a particular script
+    pub fn script(&self) -> Option<&str> {
+        match self {
+            Self::RuntimeError { script, .. }
+            | Self::FailedToLoad { script, .. }
+            | Self::SyntaxError { script, .. }
+            | Self::InvalidCallback { script, .. }
+            | Self::FailedToAttachAPI { script, .. }
+            | Self::ConversionError { script, .. }
+            | Self::Timeout { script, .. }
+            | Self::MemoryLimit { script, .. } => Some(script),
+            Self::DocGenError(_) | Self::QuotaExceeded { .. } | Self::Other(_) => None,
+        }
+    }
+
+    pub fn is_compile_error(&self) -> bool {
+        self.category() == ScriptErrorCategory::CompileError
+    }
+
+    pub fn is_runtime_error(&self) -> bool {
+        self.category() == ScriptErrorCategory::RuntimeError
+    }
+
+    /// True for errors caused by the script exceeding its time or memory limits
+    pub fn is_limit_exceeded(&self) -> bool {
+        matches!(
+            self.category(),
+            ScriptErrorCategory::Timeout | ScriptErrorCategory::MemoryLimit
+        )
+    }
 }
@@ -1,8 +1,13 @@
-use crate::{error::ScriptError, hosts::Recipients};
+use crate::{
+    error::ScriptError,
+    hosts::{Recipients, ScriptId},
+};
 
 /// An error coming from a script
 #[derive(Debug)]
 pub struct ScriptErrorEvent {
+    /// the ID of the script instance which caused the error
+    pub sid: ScriptId,
     pub error: ScriptError,
 }
 
@@ -10,7 +15,10 @@ pub struct ScriptErrorEvent {
 /// guaranteed to be sent for every script at least once and immediately after it's loaded.
 #[derive(Clone, Debug)]
 pub struct ScriptLoaded {
-    pub sid: u32,
+    pub sid: ScriptId,
+    /// the initialized script the context was cloned from, scripts cloned from another one carry over its state
+    /// and should not be initialized again, e.g. by skipping their `on_init` hook
+    pub cloned_from: Option<ScriptId>,
 }
 
 /// A trait for events to be handled by scripts
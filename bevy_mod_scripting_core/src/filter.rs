@@ -0,0 +1,36 @@
+//! Restricting which API providers are attached to which scripts
+use bevy::{prelude::*, utils::HashSet};
+
+use crate::hosts::ScriptData;
+
+/// Tags describing the role of the scripts attached to an entity, e.g. `ui` or `server`,
+/// which provider filters can match against, see [`crate::AddScriptApiProvider::add_filtered_api_provider`]
+#[derive(Component, Debug, Clone, Default)]
+pub struct ScriptTags(pub HashSet<String>);
+
+impl ScriptTags {
+    pub fn new<I: IntoIterator<Item = S>, S: Into<String>>(tags: I) -> Self {
+        Self(tags.into_iter().map(Into::into).collect())
+    }
+
+    pub fn has(&self, tag: &str) -> bool {
+        self.0.contains(tag)
+    }
+}
+
+/// The information provider filters decide on
+pub struct ProviderFilterContext<'a> {
+    pub script: &'a ScriptData<'a>,
+    /// the tags of the entity the script is attached to, if it has any
+    pub tags: Option<&'a ScriptTags>,
+}
+
+impl ProviderFilterContext<'_> {
+    /// Returns true if the script's entity has the given tag
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.map_or(false, |t| t.has(tag))
+    }
+}
+
+/// Decides whether a provider is attached to a script
+pub type ProviderFilter = Box<dyn Fn(&ProviderFilterContext) -> bool + Send + Sync + 'static>;
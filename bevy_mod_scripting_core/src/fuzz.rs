@@ -0,0 +1,66 @@
+//! Feeding arbitrary input through script hosts, for fuzzing the parsing and binding layers
+use bevy::{prelude::*, utils::Duration};
+
+use crate::{error::ScriptError, hosts::ScriptHost};
+
+/// Caps on the resources used by scripts run through [`fuzz_script`], so that inputs which loop forever
+/// or allocate without bound end in an error rather than hanging or exhausting the fuzzer.
+#[derive(Debug, Clone, Copy)]
+pub struct FuzzLimits {
+    /// the time a script may run for, including loading it
+    pub max_duration: Duration,
+    /// the memory a script may allocate in bytes, a best effort where the host cannot limit its memory exactly
+    pub max_memory: usize,
+}
+
+impl Default for FuzzLimits {
+    fn default() -> Self {
+        Self {
+            max_duration: Duration::from_millis(100),
+            max_memory: 16 * 1024 * 1024,
+        }
+    }
+}
+
+/// Script hosts which can apply [`FuzzLimits`] to the scripts they load
+pub trait FuzzTarget: ScriptHost {
+    /// Applies the limits to every script loaded from now on, the time limit counts from the moment this is called
+    fn set_fuzz_limits(&mut self, limits: FuzzLimits);
+}
+
+/// Loads the given bytes as a script on a fresh entity and sends it the given event with [`ScriptHost::run_one_shot`],
+/// within the given limits. The app must have the host `H` added.
+///
+/// Invalid scripts are expected to produce errors, a panic or crash in here means a bug in the host or its bindings.
+/// For example a `cargo fuzz` target could look like:
+/// ```rust,ignore
+/// fuzz_target!(|data: &[u8]| {
+///     let mut app = App::new();
+///     app.add_plugin(ScriptingPlugin)
+///         .add_script_host::<LuaScriptHost<()>, _>(CoreStage::PostUpdate)
+///         .add_api_provider::<LuaScriptHost<()>>(Box::new(LuaBevyAPIProvider));
+///     let _ = fuzz_script::<LuaScriptHost<()>>(&mut app, data, FuzzLimits::default(), LuaEvent {
+///         hook_name: "on_update".into(),
+///         args: (),
+///         recipients: Recipients::All,
+///     });
+/// });
+/// ```
+pub fn fuzz_script<H: FuzzTarget>(
+    app: &mut App,
+    data: &[u8],
+    limits: FuzzLimits,
+    event: H::ScriptEvent,
+) -> Result<(), ScriptError> {
+    let mut host = app
+        .world
+        .remove_resource::<H>()
+        .expect("The script host was not added to the app");
+    host.set_fuzz_limits(limits);
+
+    let entity = app.world.spawn_empty().id();
+    let result = host.run_one_shot(data, "fuzz", entity, &mut app.world, event);
+    app.world.insert_resource(host);
+    app.world.despawn(entity);
+    result
+}
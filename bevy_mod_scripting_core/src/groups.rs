@@ -0,0 +1,62 @@
+//! Named groups of scripts, letting events be broadcast to logical sets of scripts
+use bevy::{prelude::*, utils::HashMap};
+
+use crate::hosts::ScriptId;
+
+/// The groups each script has joined, scripts join and leave groups themselves (e.g. `script:join_group("ui")`)
+/// and events sent to [`Recipients::Group`](crate::hosts::Recipients::Group) are handled by every member.
+///
+/// Scripts leave all their groups once unloaded.
+#[derive(Resource, Debug, Default, Clone)]
+pub struct ScriptGroups {
+    memberships: HashMap<ScriptId, Vec<String>>,
+}
+
+impl ScriptGroups {
+    /// Adds the script to the group, returns false if it already was a member
+    pub fn join(&mut self, sid: ScriptId, group: &str) -> bool {
+        let groups = self.memberships.entry(sid).or_default();
+        if groups.iter().any(|g| g == group) {
+            return false;
+        }
+        groups.push(group.to_owned());
+        true
+    }
+
+    /// Removes the script from the group, returns false if it was not a member
+    pub fn leave(&mut self, sid: ScriptId, group: &str) -> bool {
+        let groups = match self.memberships.get_mut(&sid) {
+            Some(groups) => groups,
+            None => return false,
+        };
+        let len = groups.len();
+        groups.retain(|g| g != group);
+        let left = groups.len() != len;
+        if groups.is_empty() {
+            self.memberships.remove(&sid);
+        }
+        left
+    }
+
+    /// Removes the script from every group it joined
+    pub fn leave_all(&mut self, sid: ScriptId) {
+        self.memberships.remove(&sid);
+    }
+
+    /// The groups the script is a member of
+    pub fn groups_of(&self, sid: ScriptId) -> &[String] {
+        self.memberships.get(&sid).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn is_member(&self, sid: ScriptId, group: &str) -> bool {
+        self.groups_of(sid).iter().any(|g| g == group)
+    }
+
+    /// The scripts which are members of the group
+    pub fn members<'a>(&'a self, group: &'a str) -> impl Iterator<Item = ScriptId> + 'a {
+        self.memberships
+            .iter()
+            .filter(move |(_, groups)| groups.iter().any(|g| g == group))
+            .map(|(sid, _)| *sid)
+    }
+}
@@ -0,0 +1,175 @@
+//! Moving and copying scripts between entities, e.g. for instantiating prefabs or possessing other entities
+use std::marker::PhantomData;
+
+use bevy::{asset::Asset, ecs::system::Command, prelude::*, utils::HashMap};
+
+use crate::hosts::{Script, ScriptCollection, ScriptId};
+
+/// Moves all scripts from one entity to another, leaving the collection of `from` empty.
+///
+/// The moved scripts keep their IDs and contexts, including any state, and are appended to the scripts of `to`
+/// (which gets a [`ScriptCollection`] if it has none). Hosts pick up the new owner of the contexts the next time
+/// their scripts are synchronized, until then the scripts keep handling events on behalf of `from`.
+/// Does nothing if `from` has no scripts.
+pub struct MoveScripts<T: Asset> {
+    pub from: Entity,
+    pub to: Entity,
+    _ph: PhantomData<T>,
+}
+
+impl<T: Asset> MoveScripts<T> {
+    pub fn new(from: Entity, to: Entity) -> Self {
+        Self {
+            from,
+            to,
+            _ph: Default::default(),
+        }
+    }
+}
+
+impl<T: Asset> Command for MoveScripts<T> {
+    fn write(self, world: &mut World) {
+        if self.from == self.to || world.get_entity(self.to).is_none() {
+            return;
+        }
+        let scripts = match world.get_mut::<ScriptCollection<T>>(self.from) {
+            Some(mut collection) if !collection.scripts.is_empty() => {
+                std::mem::take(&mut collection.scripts)
+            }
+            _ => return,
+        };
+        append_scripts(world, self.to, scripts);
+    }
+}
+
+/// Attaches new instances of all scripts of one entity to another, appending them to the scripts of `to`.
+///
+/// Without state the copies are loaded like any newly attached script. With state their contexts start out as
+/// copies of the contexts of the originals, the same way [`WarmCloneScripts`](crate::hosts::WarmCloneScripts)
+/// clones them, with [`ScriptLoaded::cloned_from`](crate::event::ScriptLoaded::cloned_from) set to the original.
+/// Hosts which cannot clone contexts load the copies anew either way.
+pub struct CopyScripts<T: Asset> {
+    pub from: Entity,
+    pub to: Entity,
+    pub with_state: bool,
+    _ph: PhantomData<T>,
+}
+
+impl<T: Asset> CopyScripts<T> {
+    pub fn new(from: Entity, to: Entity, with_state: bool) -> Self {
+        Self {
+            from,
+            to,
+            with_state,
+            _ph: Default::default(),
+        }
+    }
+}
+
+impl<T: Asset> Command for CopyScripts<T> {
+    fn write(self, world: &mut World) {
+        if world.get_entity(self.to).is_none() {
+            return;
+        }
+        let (copies, sources): (Vec<_>, Vec<_>) = match world.get::<ScriptCollection<T>>(self.from)
+        {
+            Some(collection) => collection
+                .scripts
+                .iter()
+                .map(|s| {
+                    let copy = Script::new(s.name().to_owned(), s.handle().clone());
+                    let source = (copy.id(), s.id());
+                    (copy, source)
+                })
+                .unzip(),
+            None => return,
+        };
+        if copies.is_empty() {
+            return;
+        }
+        if self.with_state {
+            world
+                .get_resource_or_insert_with(ScriptCopies::default)
+                .sources
+                .extend(sources);
+        }
+        append_scripts(world, self.to, copies);
+    }
+}
+
+fn append_scripts<T: Asset>(world: &mut World, entity: Entity, scripts: Vec<Script<T>>) {
+    match world.get_mut::<ScriptCollection<T>>(entity) {
+        Some(mut collection) => collection.scripts.extend(scripts),
+        None => {
+            world
+                .entity_mut(entity)
+                .insert(ScriptCollection { scripts });
+        }
+    }
+}
+
+/// The scripts copied with [`CopyScripts`] whose contexts are still to be cloned, mapped to the scripts they copy
+#[derive(Resource, Default, Debug)]
+pub struct ScriptCopies {
+    sources: HashMap<ScriptId, ScriptId>,
+}
+
+impl ScriptCopies {
+    /// The script whose context the given script copies, if it was not cloned yet
+    pub fn source_of(&self, copy: ScriptId) -> Option<ScriptId> {
+        self.sources.get(&copy).copied()
+    }
+
+    pub(crate) fn take(&mut self, copy: ScriptId) -> Option<ScriptId> {
+        self.sources.remove(&copy)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::reflect::TypeUuid;
+
+    use super::*;
+
+    #[derive(TypeUuid)]
+    #[uuid = "0a8c5f4e-7a36-4c2b-9d8e-3f1b2e6c9a71"]
+    struct TestScript;
+
+    #[test]
+    fn moves_and_copies_collections() {
+        let mut world = World::new();
+        let handle = Handle::<TestScript>::default();
+        let from = world
+            .spawn(ScriptCollection {
+                scripts: vec![Script::new("a.lua".to_owned(), handle.clone())],
+            })
+            .id();
+        let to = world.spawn(()).id();
+        let id = world
+            .get::<ScriptCollection<TestScript>>(from)
+            .unwrap()
+            .scripts[0]
+            .id();
+
+        MoveScripts::<TestScript>::new(from, to).write(&mut world);
+        let moved = world.get::<ScriptCollection<TestScript>>(to).unwrap();
+        assert_eq!(
+            moved.scripts.iter().map(|s| s.id()).collect::<Vec<_>>(),
+            vec![id]
+        );
+        assert!(world
+            .get::<ScriptCollection<TestScript>>(from)
+            .unwrap()
+            .scripts
+            .is_empty());
+
+        CopyScripts::<TestScript>::new(to, from, true).write(&mut world);
+        let copy = world
+            .get::<ScriptCollection<TestScript>>(from)
+            .unwrap()
+            .scripts[0]
+            .id();
+        assert_ne!(copy, id);
+        assert_eq!(world.resource::<ScriptCopies>().source_of(copy), Some(id));
+    }
+}
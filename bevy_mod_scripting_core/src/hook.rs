@@ -0,0 +1,204 @@
+//! Interned hook names
+use std::{
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    ops::Deref,
+};
+
+use bevy::utils::HashMap;
+use parking_lot::{const_mutex, Mutex};
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::error::ScriptError;
+
+#[derive(Default)]
+struct HookNames {
+    indices: HashMap<&'static str, u32>,
+    names: Vec<&'static str>,
+}
+
+/// Every hook name interned so far, names are never freed since a game only ever uses a small set of hooks.
+///
+/// Only locked when interning, each [`HookName`] carries its interned string
+static HOOK_NAMES: Mutex<Option<HookNames>> = const_mutex(None);
+
+/// The most hook names which can be interned, so that names coming from outside of the app,
+/// e.g. deserialized events, cannot grow the interner without bounds
+pub const MAX_HOOK_NAMES: usize = 4096;
+
+/// The name of a script hook such as `on_update`, interned so that it is cheap to copy and compare,
+/// and so that script hosts can cache the function behind each hook by its [`HookName::index`].
+///
+/// Converts from string types, so events can still be created with `hook_name: "on_update".into()`.
+/// These conversions panic once [`MAX_HOOK_NAMES`] names were interned, names coming from scripts or other
+/// untrusted sources should be interned with [`HookName::try_new`] or [`HookName::new_checked`] instead.
+#[derive(Clone, Copy)]
+pub struct HookName {
+    index: u32,
+    name: &'static str,
+}
+
+impl HookName {
+    /// Interns the given hook name, returning the same hook name for equal strings.
+    ///
+    /// Panics if the name is new and [`MAX_HOOK_NAMES`] names were interned already, see [`HookName::try_new`].
+    pub fn new(name: &str) -> Self {
+        Self::new_checked(name).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// Like [`HookName::new`] but returns `None` instead of interning a new name once [`MAX_HOOK_NAMES`] names were interned
+    pub fn try_new(name: &str) -> Option<Self> {
+        let mut names = HOOK_NAMES.lock();
+        let names = names.get_or_insert_with(Default::default);
+
+        if let Some(index) = names.indices.get(name) {
+            return Some(Self {
+                index: *index,
+                name: names.names[*index as usize],
+            });
+        }
+        if names.names.len() >= MAX_HOOK_NAMES {
+            return None;
+        }
+
+        let index = names.names.len() as u32;
+        let name: &'static str = Box::leak(name.to_owned().into_boxed_str());
+        names.names.push(name);
+        names.indices.insert(name, index);
+        Some(Self { index, name })
+    }
+
+    /// Like [`HookName::try_new`] but fails with a [`ScriptError`] naming the hook,
+    /// for interning hook names given by scripts
+    pub fn new_checked(name: &str) -> Result<Self, ScriptError> {
+        Self::try_new(name).ok_or_else(|| {
+            ScriptError::Other(format!(
+                "Cannot intern the hook name `{name}`, already interned {MAX_HOOK_NAMES} hook names"
+            ))
+        })
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        self.name
+    }
+
+    /// A small index unique to this hook name, interned hook names are numbered from 0 upwards
+    pub fn index(&self) -> usize {
+        self.index as usize
+    }
+}
+
+/// Equal names are interned under the same index, so hook names compare and hash by index
+impl PartialEq for HookName {
+    fn eq(&self, other: &Self) -> bool {
+        self.index == other.index
+    }
+}
+
+impl Eq for HookName {}
+
+impl Hash for HookName {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.index.hash(state)
+    }
+}
+
+impl PartialOrd for HookName {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for HookName {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.index.cmp(&other.index)
+    }
+}
+
+impl Deref for HookName {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        self.as_str()
+    }
+}
+
+impl AsRef<str> for HookName {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl From<&str> for HookName {
+    fn from(name: &str) -> Self {
+        Self::new(name)
+    }
+}
+
+impl From<String> for HookName {
+    fn from(name: String) -> Self {
+        Self::new(&name)
+    }
+}
+
+impl From<&String> for HookName {
+    fn from(name: &String) -> Self {
+        Self::new(name)
+    }
+}
+
+impl PartialEq<str> for HookName {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for HookName {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl fmt::Display for HookName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl fmt::Debug for HookName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+/// Hook names are serialized as strings, since indices differ between apps
+impl Serialize for HookName {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+/// Fails instead of panicking once [`MAX_HOOK_NAMES`] names were interned
+impl<'de> Deserialize<'de> for HookName {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Self::new_checked(&name).map_err(D::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn equal_names_intern_to_the_same_hook() {
+        let a = HookName::new("on_hook_name_test");
+        let b = HookName::new_checked(&"on_hook_name_test".to_owned()).unwrap();
+
+        assert_eq!(a, b);
+        assert_eq!(a.index(), b.index());
+        assert_eq!(b.as_str(), "on_hook_name_test");
+        assert_ne!(a, HookName::new("on_other_hook_name_test"));
+    }
+}
@@ -1,39 +1,69 @@
 //! All script host related stuff
 use bevy::{asset::Asset, prelude::*, reflect::FromReflect};
+use serde::{Deserialize, Serialize};
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     iter::once,
-    sync::atomic::{AtomicU32, Ordering},
+    marker::PhantomData,
+    sync::atomic::{AtomicU64, Ordering},
 };
 
 use crate::{
     asset::CodeAsset,
-    docs::DocFragment,
+    docs::{DocFragment, HookRegistry},
     error::ScriptError,
     event::{ScriptEvent, ScriptLoaded},
+    filter::{ProviderFilter, ProviderFilterContext, ScriptTags},
+    groups::ScriptGroups,
+    systems::handle_script_events,
     world::WorldPointer,
+    ScriptErrorEvent,
 };
 
+/// Uniquely identifies a script instance, scripts using the same asset never share an ID
+pub type ScriptId = u64;
+
 /// Describes the target set of scripts this event should
 /// be handled by
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum Recipients {
     /// Send to all scripts
     All,
     /// Send only to scripts on the given entity
-    Entity(Entity),
+    Entity(#[serde(with = "entity_bits")] Entity),
     /// Send to script with the given ID
-    ScriptID(u32),
+    Id(ScriptId),
     // Send to script with the given name
     ScriptName(String),
+    /// Send to every member of the given script group, see [`ScriptGroups`](crate::groups::ScriptGroups)
+    Group(String),
+    /// Send to every script matched by any of the given recipients,
+    /// scripts matched by more than one recipient still only handle the event once
+    Many(Vec<Recipients>),
+}
+
+/// Serializes entities by their bits, entities are only meaningful to other apps keeping their entities in sync
+mod entity_bits {
+    use bevy::prelude::Entity;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(entity: &Entity, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(entity.to_bits())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Entity, D::Error> {
+        u64::deserialize(deserializer).map(Entity::from_bits)
+    }
 }
 
 #[derive(Debug)]
 /// Data used to describe a script instance.
 pub struct ScriptData<'a> {
-    pub sid: u32,
+    pub sid: ScriptId,
     pub entity: Entity,
     pub name: &'a str,
+    /// the groups the script was a member of when it started handling events, see [`ScriptGroups`](crate::groups::ScriptGroups)
+    pub groups: &'a [String],
 }
 
 impl Recipients {
@@ -42,8 +72,10 @@ impl Recipients {
         match self {
             Recipients::All => true,
             Recipients::Entity(e) => e == &c.entity,
-            Recipients::ScriptID(i) => i == &c.sid,
+            Recipients::Id(i) => i == &c.sid,
             Recipients::ScriptName(n) => n == c.name,
+            Recipients::Group(g) => c.groups.iter().any(|m| m == g),
+            Recipients::Many(r) => r.iter().any(|r| r.is_recipient(c)),
         }
     }
 }
@@ -86,6 +118,22 @@ pub trait ScriptHost: Send + Sync + 'static + Default + Resource {
         providers: &mut APIProviders<Self>,
     ) -> Result<(), ScriptError>;
 
+    /// Creates the context of a new instance of a script from the context of an already initialized instance,
+    /// for entities marked with [`WarmCloneScripts`]. The new context is set up with [`ScriptHost::setup_script`]
+    /// afterwards as usual, but since it carries over the state of the initialized instance, the script does not
+    /// need to be initialized again, see [`ScriptLoaded::cloned_from`].
+    ///
+    /// Returns `None` if the host cannot clone contexts, the script is then loaded with [`ScriptHost::load_script`].
+    fn clone_context(
+        &mut self,
+        _source: &mut Self::ScriptContext,
+        _script: &[u8],
+        _script_data: &ScriptData,
+        _providers: &mut APIProviders<Self>,
+    ) -> Option<Result<Self::ScriptContext, ScriptError>> {
+        None
+    }
+
     /// the main point of contact with the bevy world.
     /// Scripts are called with appropriate events in the event order
     fn handle_events<'a>(
@@ -96,8 +144,27 @@ pub trait ScriptHost: Send + Sync + 'static + Default + Resource {
         providers: &mut APIProviders<Self>,
     );
 
+    /// Lets every script handle the pending script events with priorities from `max` to `min`, both inclusive,
+    /// where `max` is the highest priority and therefore the smallest number, the same as in [`crate::priorities::PriorityRange`].
+    /// Inverted ranges, with `max` greater than `min`, handle no events. Otherwise this behaves exactly like a handler stage added with [`crate::AddScriptHostHandler::add_script_handler_stage`] would.
+    ///
+    /// For running scripts from schedules the handler stages cannot be added to, such as custom fixed step loops
+    /// or rollback resimulation, the host must still be added to the app with [`crate::AddScriptHost::add_script_host`]:
+    /// ```rust,ignore
+    /// fn fixed_step(world: &mut World) {
+    ///     for _ in 0..steps_due(world) {
+    ///         simulate(world);
+    ///         // handles the script events sent during this step
+    ///         LuaScriptHost::<()>::handle_events_manual(world, 0, 0);
+    ///     }
+    /// }
+    /// ```
+    fn handle_events_manual(world: &mut World, max: u32, min: u32) {
+        handle_script_events::<Self>(world, max, min)
+    }
+
     /// Loads and runs script instantaneously without storing any script data into the world.
-    /// The script id is set to `u32::MAX`.
+    /// The script id is set to `ScriptId::MAX`.
     fn run_one_shot(
         &mut self,
         script: &[u8],
@@ -106,19 +173,39 @@ pub trait ScriptHost: Send + Sync + 'static + Default + Resource {
         world: &mut World,
         event: Self::ScriptEvent,
     ) -> Result<(), ScriptError> {
+        let groups = world
+            .get_resource::<ScriptGroups>()
+            .map(|g| g.groups_of(ScriptId::MAX).to_vec())
+            .unwrap_or_default();
         let fd = ScriptData {
             name: script_name,
-            sid: u32::MAX,
+            sid: ScriptId::MAX,
             entity,
+            groups: &groups,
         };
 
         let mut providers: APIProviders<Self> = world.remove_resource().unwrap();
-        let mut ctx = self.load_script(script, &fd, &mut providers).unwrap();
-        self.setup_script(&fd, &mut ctx, &mut providers)?;
+        // filtered providers apply to one shot scripts as they would to the scripts of the entity
+        let tags = world.get::<ScriptTags>(entity).cloned();
+        providers.select_for(&fd, tags.as_ref());
+        let mut ctx = match self
+            .load_script(script, &fd, &mut providers)
+            .and_then(|mut ctx| {
+                self.setup_script(&fd, &mut ctx, &mut providers)
+                    .map(|_| ctx)
+            }) {
+            Ok(ctx) => ctx,
+            Err(e) => {
+                providers.forget(fd.sid);
+                world.insert_resource(providers);
+                return Err(e);
+            }
+        };
         let events = [event; 1];
 
         self.handle_events(world, &events, once((fd, &mut ctx)), &mut providers);
 
+        providers.forget(fd.sid);
         world.insert_resource(providers);
 
         Ok(())
@@ -172,6 +259,12 @@ pub trait APIProvider: 'static + Send + Sync {
         None
     }
 
+    /// The names of the variables this provider sets in every script it is attached to, e.g. in `Self::setup_script_runtime`,
+    /// for hosts which need variables to be declared before scripts are compiled, such as Rhai with strict variables
+    fn script_variables(&self) -> Vec<&'static str> {
+        Vec::default()
+    }
+
     /// Some providers might provide additional types which need to be registered
     /// with the reflection API to work.
     fn register_with_app(&self, _app: &mut App) {}
@@ -189,19 +282,98 @@ pub struct APIProviders<T: ScriptHost> {
             >,
         >,
     >,
+    /// filters of the providers at the given indices, unfiltered providers are attached to every script
+    filters: HashMap<usize, ProviderFilter>,
+    /// the indices of the providers filtered out for each script
+    excluded: HashMap<ScriptId, HashSet<usize>>,
+    /// the script whose context is being loaded
+    current: Option<ScriptId>,
 }
 
 impl<T: ScriptHost> Default for APIProviders<T> {
     fn default() -> Self {
         Self {
             providers: Default::default(),
+            filters: Default::default(),
+            excluded: Default::default(),
+            current: None,
         }
     }
 }
 
 impl<T: ScriptHost> APIProviders<T> {
+    /// Adds a provider which is only attached to scripts accepted by the filter
+    pub fn push_filtered(
+        &mut self,
+        provider: Box<
+            dyn APIProvider<
+                APITarget = T::APITarget,
+                DocTarget = T::DocTarget,
+                ScriptContext = T::ScriptContext,
+            >,
+        >,
+        filter: ProviderFilter,
+    ) {
+        self.filters.insert(self.providers.len(), filter);
+        self.providers.push(provider);
+    }
+
+    /// Evaluates the provider filters for the given script,
+    /// subsequent calls to [`Self::attach_all`] attach only the providers accepted for it
+    pub fn select_for(&mut self, script_data: &ScriptData, tags: Option<&ScriptTags>) {
+        let ctx = ProviderFilterContext {
+            script: script_data,
+            tags,
+        };
+        let excluded = self
+            .filters
+            .iter()
+            .filter(|(_, filter)| !filter(&ctx))
+            .map(|(idx, _)| *idx)
+            .collect::<HashSet<_>>();
+
+        if excluded.is_empty() {
+            self.excluded.remove(&script_data.sid);
+        } else {
+            self.excluded.insert(script_data.sid, excluded);
+        }
+        self.current = Some(script_data.sid);
+    }
+
+    /// Forgets the providers selected for the given script with [`Self::select_for`], once its context was removed
+    pub fn forget(&mut self, script_id: ScriptId) {
+        self.excluded.remove(&script_id);
+        if self.current == Some(script_id) {
+            self.current = None;
+        }
+    }
+
+    /// Returns false if the provider at the given index was filtered out for the given script
+    pub fn is_attached_to(&self, script_id: ScriptId, provider: usize) -> bool {
+        self.excluded
+            .get(&script_id)
+            .map_or(true, |excluded| !excluded.contains(&provider))
+    }
+
+    /// The variables set by the providers attached to the given script, see [`APIProvider::script_variables`]
+    pub fn script_variables(&self, script_id: ScriptId) -> impl Iterator<Item = &'static str> + '_ {
+        self.providers
+            .iter()
+            .enumerate()
+            .filter(move |(idx, _)| self.is_attached_to(script_id, *idx))
+            .flat_map(|(_, p)| p.script_variables())
+    }
+
+    /// Attaches every provider to the given target, except those filtered out for the script selected with [`Self::select_for`].
+    ///
+    /// Hosts which attach APIs to an engine shared by all scripts do so before any script is selected,
+    /// in which case filters only apply to the per script setup.
     pub fn attach_all(&mut self, ctx: &mut T::APITarget) -> Result<(), ScriptError> {
-        for p in self.providers.iter_mut() {
+        let excluded = self.current.and_then(|sid| self.excluded.get(&sid));
+        for (idx, p) in self.providers.iter_mut().enumerate() {
+            if excluded.map_or(false, |e| e.contains(&idx)) {
+                continue;
+            }
             p.attach_api(ctx)?;
         }
 
@@ -214,7 +386,11 @@ impl<T: ScriptHost> APIProviders<T> {
         script_data: &ScriptData,
         ctx: &mut T::ScriptContext,
     ) -> Result<(), ScriptError> {
-        for p in self.providers.iter_mut() {
+        let excluded = self.excluded.get(&script_data.sid);
+        for (idx, p) in self.providers.iter_mut().enumerate() {
+            if excluded.map_or(false, |e| e.contains(&idx)) {
+                continue;
+            }
             p.setup_script_runtime(world_ptr.clone(), script_data, ctx)?;
         }
 
@@ -226,7 +402,11 @@ impl<T: ScriptHost> APIProviders<T> {
         script_data: &ScriptData,
         ctx: &mut T::ScriptContext,
     ) -> Result<(), ScriptError> {
-        for p in self.providers.iter_mut() {
+        let excluded = self.excluded.get(&script_data.sid);
+        for (idx, p) in self.providers.iter_mut().enumerate() {
+            if excluded.map_or(false, |e| e.contains(&idx)) {
+                continue;
+            }
             p.setup_script(script_data, ctx)?;
         }
 
@@ -234,6 +414,11 @@ impl<T: ScriptHost> APIProviders<T> {
     }
 
     pub fn gen_all(&self) -> Result<(), ScriptError> {
+        self.gen_all_with_hooks(None)
+    }
+
+    /// Like `gen_all` but also documents the given hooks
+    pub fn gen_all_with_hooks(&self, hooks: Option<&HookRegistry>) -> Result<(), ScriptError> {
         let mut d: Option<T::DocTarget> = None;
         for p in self.providers.iter() {
             if let Some(f) = p.get_doc_fragment() {
@@ -244,6 +429,9 @@ impl<T: ScriptHost> APIProviders<T> {
                 }
             }
         }
+        if let Some(hooks) = hooks {
+            d = d.map(|d| d.with_hooks(hooks));
+        }
         d.map(|d| d.gen_docs()).unwrap_or_else(|| Ok(()))
     }
 }
@@ -259,32 +447,110 @@ impl<T: ScriptHost> APIProviders<T> {
 pub struct ScriptContexts<C> {
     /// holds script contexts for all scripts given their instance ids.
     /// This also stores contexts which are not fully loaded hence the Option
-    pub context_entities: HashMap<u32, (Entity, Option<C>, String)>,
+    pub context_entities: HashMap<ScriptId, (Entity, Option<C>, String)>,
+    /// scripts whose last attempt at loading failed
+    failed: HashSet<ScriptId>,
+    /// the scripts in `context_entities` attached to each entity, so syncing one entity does not visit every script
+    entity_scripts: HashMap<Entity, HashSet<ScriptId>>,
 }
 
 impl<C> Default for ScriptContexts<C> {
     fn default() -> Self {
         Self {
             context_entities: Default::default(),
+            failed: Default::default(),
+            entity_scripts: Default::default(),
         }
     }
 }
 
 impl<C> ScriptContexts<C> {
-    pub fn script_owner(&self, script_id: u32) -> Option<Entity> {
+    pub fn script_owner(&self, script_id: ScriptId) -> Option<Entity> {
         self.context_entities.get(&script_id).map(|(e, _c, _n)| *e)
     }
 
     pub fn insert_context(&mut self, fd: ScriptData, ctx: Option<C>) {
+        self.failed.remove(&fd.sid);
+        if let Some((previous, _, _)) = self
+            .context_entities
+            .insert(fd.sid, (fd.entity, ctx, fd.name.to_owned()))
+        {
+            self.unindex(previous, fd.sid);
+        }
+        self.entity_scripts
+            .entry(fd.entity)
+            .or_default()
+            .insert(fd.sid);
+    }
+
+    /// Attaches the context of the script to another entity, keeping the context itself.
+    /// Returns false if the script has no context
+    pub fn set_script_owner(&mut self, script_id: ScriptId, entity: Entity) -> bool {
+        let previous = match self.context_entities.get_mut(&script_id) {
+            Some((owner, _, _)) => std::mem::replace(owner, entity),
+            None => return false,
+        };
+        self.unindex(previous, script_id);
+        self.entity_scripts
+            .entry(entity)
+            .or_default()
+            .insert(script_id);
+        true
+    }
+
+    fn unindex(&mut self, entity: Entity, script_id: ScriptId) {
+        if let Some(scripts) = self.entity_scripts.get_mut(&entity) {
+            scripts.remove(&script_id);
+            if scripts.is_empty() {
+                self.entity_scripts.remove(&entity);
+            }
+        }
+    }
+
+    /// Inserts an empty context for a script which failed to load, it stays empty until the script is reloaded
+    pub fn insert_failed_context(&mut self, fd: ScriptData) {
+        let sid = fd.sid;
+        self.insert_context(fd, None);
+        self.failed.insert(sid);
+    }
+
+    pub fn remove_context(&mut self, script_id: ScriptId) {
+        self.failed.remove(&script_id);
+        if let Some((entity, _, _)) = self.context_entities.remove(&script_id) {
+            self.unindex(entity, script_id);
+        }
+    }
+
+    /// The scripts with contexts attached to the given entity, loaded or not
+    pub fn entity_scripts(&self, entity: Entity) -> impl Iterator<Item = ScriptId> + '_ {
+        self.entity_scripts
+            .get(&entity)
+            .into_iter()
+            .flat_map(|scripts| scripts.iter().copied())
+    }
+
+    /// Returns an instance of the script with the given name which has a loaded context, if there is one
+    pub fn loaded_instance(&self, name: &str) -> Option<ScriptId> {
         self.context_entities
-            .insert(fd.sid, (fd.entity, ctx, fd.name.to_owned()));
+            .iter()
+            .find(|(_, (_, ctx, n))| ctx.is_some() && n == name)
+            .map(|(sid, _)| *sid)
+    }
+
+    /// Removes the contexts of all scripts attached to the given entity
+    pub fn remove_entity_contexts(&mut self, entity: Entity) {
+        for sid in self.entity_scripts.remove(&entity).unwrap_or_default() {
+            self.context_entities.remove(&sid);
+            self.failed.remove(&sid);
+        }
     }
 
-    pub fn remove_context(&mut self, script_id: u32) {
-        self.context_entities.remove(&script_id);
+    /// Returns true if the last attempt at loading the given script failed
+    pub fn has_failed(&self, script_id: ScriptId) -> bool {
+        self.failed.contains(&script_id)
     }
 
-    pub fn has_context(&self, script_id: u32) -> bool {
+    pub fn has_context(&self, script_id: ScriptId) -> bool {
         self.context_entities
             .get(&script_id)
             .map_or(false, |(_, c, _)| c.is_some())
@@ -306,10 +572,15 @@ pub struct Script<T: Asset> {
     name: String,
 
     /// uniquely identifies the script instance (scripts which use the same asset don't necessarily have the same ID)
-    id: u32,
+    id: ScriptId,
 }
 
-static COUNTER: AtomicU32 = AtomicU32::new(0);
+static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Generates a new unique script ID, used for script instances which do not come from a [`Script`]
+pub fn next_script_id() -> ScriptId {
+    COUNTER.fetch_add(1, Ordering::Relaxed)
+}
 
 impl<T: Asset> Script<T> {
     /// creates a new script instance with the given name and asset handle
@@ -319,7 +590,7 @@ impl<T: Asset> Script<T> {
         Self {
             handle,
             name,
-            id: COUNTER.fetch_add(1, Ordering::Relaxed),
+            id: next_script_id(),
         }
     }
 
@@ -337,19 +608,24 @@ impl<T: Asset> Script<T> {
 
     #[inline(always)]
     /// returns the unique ID of this script instance
-    pub fn id(&self) -> u32 {
+    pub fn id(&self) -> ScriptId {
         self.id
     }
 
     /// reloads the script by deleting the old context and inserting a new one
     /// if the script context never existed, it will after this call.
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn reload_script<H: ScriptHost>(
         host: &mut H,
         script: &Script<H::ScriptAsset>,
+        tags: Option<&ScriptTags>,
+        groups: Option<&ScriptGroups>,
+        warm_clone: bool,
         script_assets: &Assets<H::ScriptAsset>,
         providers: &mut APIProviders<H>,
         contexts: &mut ScriptContexts<H::ScriptContext>,
         event_writer: &mut EventWriter<ScriptLoaded>,
+        error_writer: &mut EventWriter<ScriptErrorEvent>,
     ) {
         debug!("reloading script {}", script.id);
         // retrieve owning entity
@@ -363,29 +639,81 @@ impl<T: Asset> Script<T> {
             host,
             script,
             entity,
+            tags,
+            groups,
+            warm_clone,
+            None,
             script_assets,
             providers,
             contexts,
             event_writer,
+            error_writer,
         );
     }
 
-    /// checks if a script has loaded, and if so loads (`ScriptHost::load_script`),
+    /// Re-attaches the context of a script moved to another entity with [`MoveScripts`](crate::handover::MoveScripts),
+    /// setting it up again (`ScriptHost::setup_script`) so it refers to its new entity.
+    /// Sends a ScriptErrorEvent and marks the script as failed if setting it up fails
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn move_script_context<H: ScriptHost>(
+        host: &mut H,
+        script: &Script<H::ScriptAsset>,
+        entity: Entity,
+        tags: Option<&ScriptTags>,
+        groups: Option<&ScriptGroups>,
+        providers: &mut APIProviders<H>,
+        contexts: &mut ScriptContexts<H::ScriptContext>,
+        error_writer: &mut EventWriter<ScriptErrorEvent>,
+    ) {
+        debug!("moving script {} to {:?}", script.id, entity);
+        contexts.set_script_owner(script.id(), entity);
+
+        let fd = ScriptData {
+            sid: script.id(),
+            entity,
+            name: script.name(),
+            groups: groups.map_or(&[], |g| g.groups_of(script.id())),
+        };
+        if let Some((_, Some(ctx), _)) = contexts.context_entities.get_mut(&script.id()) {
+            providers.select_for(&fd, tags);
+            if let Err(e) = host.setup_script(&fd, ctx, providers) {
+                warn! {"Error in setting up moved script {}:\n{}", &script.name, e}
+                error_writer.send(ScriptErrorEvent {
+                    sid: script.id(),
+                    error: e,
+                });
+                // the context may still refer to the previous entity, so it is not run again
+                contexts.insert_failed_context(fd);
+            }
+        }
+    }
+
+    /// checks if a script has loaded, and if so loads (`ScriptHost::load_script`) or, with `warm_clone`,
+    /// clones the context of an instance of the same script (`ScriptHost::clone_context`),
     /// sets up (`ScriptHost::setup_script`) and inserts its new context into the contexts resource
-    /// otherwise inserts None. Sends ScriptLoaded event if the script was loaded
+    /// otherwise inserts None. With `copied_from` the context of that script is cloned instead, for copies made with
+    /// [`CopyScripts`](crate::handover::CopyScripts). Sends ScriptLoaded event if the script was loaded, and ScriptErrorEvent if it failed to,
+    /// including when the asset itself reports errors (`CodeAsset::load_error`)
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn insert_new_script_context<H: ScriptHost>(
         host: &mut H,
         new_script: &Script<H::ScriptAsset>,
         entity: Entity,
+        tags: Option<&ScriptTags>,
+        groups: Option<&ScriptGroups>,
+        warm_clone: bool,
+        copied_from: Option<ScriptId>,
         script_assets: &Assets<H::ScriptAsset>,
         providers: &mut APIProviders<H>,
         contexts: &mut ScriptContexts<H::ScriptContext>,
         event_writer: &mut EventWriter<ScriptLoaded>,
+        error_writer: &mut EventWriter<ScriptErrorEvent>,
     ) {
         let fd = ScriptData {
             sid: new_script.id(),
             entity,
             name: new_script.name(),
+            groups: groups.map_or(&[], |g| g.groups_of(new_script.id())),
         };
 
         let script = match script_assets.get(&new_script.handle) {
@@ -399,25 +727,113 @@ impl<T: Asset> Script<T> {
         };
         debug!("Inserted script {:?}", fd);
 
-        match host.load_script(script.bytes(), &fd, providers) {
+        providers.select_for(&fd, tags);
+
+        let cloned = script
+            .load_error()
+            .is_none()
+            .then(|| {
+                copied_from
+                    .filter(|source| contexts.has_context(*source))
+                    .or_else(|| {
+                        warm_clone
+                            .then(|| contexts.loaded_instance(new_script.name()))
+                            .flatten()
+                    })
+            })
+            .flatten()
+            .and_then(|source| {
+                let (_, source_ctx, _) = contexts.context_entities.get_mut(&source)?;
+                match host.clone_context(source_ctx.as_mut()?, script.bytes(), &fd, providers)? {
+                    Ok(ctx) => Some((source, ctx)),
+                    Err(e) => {
+                        // fall back to loading the script normally
+                        warn! {"Error in cloning script {}:\n{}", &new_script.name, e}
+                        None
+                    }
+                }
+            });
+
+        let (cloned_from, ctx) = match (cloned, script.load_error()) {
+            // e.g. type errors, the script is never run
+            (None, Some(msg)) => (
+                None,
+                Err(ScriptError::SyntaxError {
+                    script: new_script.name().to_owned(),
+                    msg: msg.to_owned(),
+                }),
+            ),
+            (Some((source, ctx)), _) => (Some(source), Ok(ctx)),
+            (None, None) => (None, host.load_script(script.bytes(), &fd, providers)),
+        };
+
+        match ctx {
             Ok(mut ctx) => {
                 host.setup_script(&fd, &mut ctx, providers)
                     .expect("Failed to setup script");
                 contexts.insert_context(fd, Some(ctx));
                 event_writer.send(ScriptLoaded {
                     sid: new_script.id(),
+                    cloned_from,
                 });
             }
             Err(e) => {
                 warn! {"Error in loading script {}:\n{}", &new_script.name,e}
+                error_writer.send(ScriptErrorEvent {
+                    sid: new_script.id(),
+                    error: e,
+                });
                 // this script will now never execute, unless manually reloaded
                 // but contexts are left in a valid state
-                contexts.insert_context(fd, None);
+                contexts.insert_failed_context(fd);
             }
         }
     }
 }
 
+/// The scripts of the host `H` attached to an entity, kept up to date alongside their contexts.
+///
+/// Lets game systems find the scripts of entities through their own queries, e.g. `Query<&ScriptContextHandle<LuaScriptHost<()>>, With<Enemy>>`,
+/// without going through [`ScriptContexts`], and is removed along with the entity's scripts or the entity itself.
+///
+/// The contexts themselves stay in [`ScriptContexts`]: scripts get mutable access to the whole world while handling events,
+/// so their contexts cannot live in components of that same world while they run. Existing code using
+/// [`ScriptContexts`] keeps working unchanged, and can move lookups of an entity's scripts to this component over time.
+#[derive(Component)]
+pub struct ScriptContextHandle<H: ScriptHost> {
+    scripts: Vec<ScriptId>,
+    _ph: PhantomData<H>,
+}
+
+impl<H: ScriptHost> ScriptContextHandle<H> {
+    pub(crate) fn new(scripts: Vec<ScriptId>) -> Self {
+        Self {
+            scripts,
+            _ph: PhantomData,
+        }
+    }
+
+    /// The IDs of the scripts attached to the entity, in no particular order.
+    /// Use them with [`ScriptContexts`] to reach the contexts
+    pub fn scripts(&self) -> &[ScriptId] {
+        &self.scripts
+    }
+
+    pub fn contains(&self, script_id: ScriptId) -> bool {
+        self.scripts.contains(&script_id)
+    }
+}
+
+/// Marks entities whose newly attached scripts start out with a copy of the context of an already initialized
+/// instance of the same script, where the host supports it (see [`ScriptHost::clone_context`]).
+///
+/// For games spawning waves of identically scripted entities, where loading and initializing every script is wasteful.
+/// Instances are matched by script name, scripts reloaded after their asset was modified are always loaded anew.
+///
+/// Of the bundled hosts only Rhai clones contexts, scripts of other hosts are loaded anew as if not marked.
+#[derive(Component, Debug, Default, Clone, Copy)]
+pub struct WarmCloneScripts;
+
 #[derive(Component, Debug, FromReflect, Reflect)]
 #[reflect(Component, Default)]
 /// The component storing many scripts.
@@ -435,3 +851,300 @@ impl<T: Asset> Default for ScriptCollection<T> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn indexes_contexts_by_entity() {
+        let (a, b) = (Entity::from_raw(0), Entity::from_raw(1));
+        let data = |sid, entity| ScriptData {
+            sid,
+            entity,
+            name: "script.lua",
+            groups: &[],
+        };
+        let mut contexts = ScriptContexts::<()>::default();
+        contexts.insert_context(data(0, a), Some(()));
+        contexts.insert_context(data(1, a), None);
+        contexts.insert_failed_context(data(2, b));
+
+        let mut on_a = contexts.entity_scripts(a).collect::<Vec<_>>();
+        on_a.sort_unstable();
+        assert_eq!(on_a, vec![0, 1]);
+
+        // moving a script to another entity re-indexes it
+        contexts.insert_context(data(1, b), None);
+        assert_eq!(contexts.entity_scripts(a).collect::<Vec<_>>(), vec![0]);
+
+        // moved contexts are kept
+        assert!(contexts.set_script_owner(0, b));
+        assert!(contexts.has_context(0));
+        assert_eq!(contexts.entity_scripts(a).count(), 0);
+        assert!(!contexts.set_script_owner(3, b));
+        assert!(contexts.set_script_owner(0, a));
+
+        contexts.remove_entity_contexts(b);
+        assert_eq!(contexts.entity_scripts(b).count(), 0);
+        assert!(!contexts.has_failed(2));
+        assert_eq!(contexts.script_owner(0), Some(a));
+        assert_eq!(contexts.script_owner(1), None);
+    }
+
+    #[test]
+    fn script_instances_never_share_ids() {
+        let handle = Handle::<test_host::TestScript>::default();
+        let ids = (0..4)
+            .map(|_| Script::new("script.test".to_owned(), handle.clone()).id())
+            .collect::<HashSet<_>>();
+        assert_eq!(ids.len(), 4);
+        assert!(
+            !ids.contains(&ScriptId::MAX),
+            "reserved for one shot scripts"
+        );
+    }
+
+    #[test]
+    fn many_recipients_match_any_of_their_recipients() {
+        let (a, b) = (Entity::from_raw(0), Entity::from_raw(1));
+        let groups = ["enemies".to_owned()];
+        let script = ScriptData {
+            sid: 3,
+            entity: a,
+            name: "goblin.lua",
+            groups: &groups,
+        };
+
+        let many = Recipients::Many(vec![Recipients::Entity(b), Recipients::Id(3)]);
+        assert!(many.is_recipient(&script));
+        assert!(
+            !Recipients::Many(vec![Recipients::Entity(b), Recipients::Id(4)]).is_recipient(&script)
+        );
+        assert!(!Recipients::Many(Vec::default()).is_recipient(&script));
+        // nested recipients are matched too
+        assert!(Recipients::Many(vec![
+            Recipients::Group("enemies".to_owned()),
+            Recipients::Many(vec![Recipients::ScriptName("goblin.lua".to_owned())]),
+        ])
+        .is_recipient(&script));
+    }
+
+    /// Records its name in the contexts it sets up
+    struct Named(&'static str);
+
+    impl APIProvider for Named {
+        type APITarget = ();
+        type ScriptContext = test_host::TestContext;
+        type DocTarget = test_host::TestDocs;
+
+        fn attach_api(&mut self, _api: &mut Self::APITarget) -> Result<(), ScriptError> {
+            Ok(())
+        }
+
+        fn setup_script(
+            &mut self,
+            _script_data: &ScriptData,
+            ctx: &mut Self::ScriptContext,
+        ) -> Result<(), ScriptError> {
+            ctx.handled.push(self.0);
+            Ok(())
+        }
+
+        fn script_variables(&self) -> Vec<&'static str> {
+            vec![self.0]
+        }
+    }
+
+    #[test]
+    fn filtered_providers_are_attached_to_accepted_scripts_only() {
+        let mut providers = APIProviders::<test_host::TestHost>::default();
+        providers.providers.push(Box::new(Named("core")));
+        providers.push_filtered(
+            Box::new(Named("ui")),
+            Box::new(|ctx: &ProviderFilterContext| ctx.has_tag("ui")),
+        );
+
+        let entity = Entity::from_raw(0);
+        let script = |sid| ScriptData {
+            sid,
+            entity,
+            name: "menu.lua",
+            groups: &[],
+        };
+        let setup = |providers: &mut APIProviders<test_host::TestHost>, sid, tags| {
+            let mut ctx = test_host::TestContext::default();
+            providers.select_for(&script(sid), tags);
+            providers.setup_all(&script(sid), &mut ctx).unwrap();
+            ctx.handled
+        };
+
+        let tags = ScriptTags::new(["ui"]);
+        assert_eq!(setup(&mut providers, 0, Some(&tags)), vec!["core", "ui"]);
+        assert_eq!(setup(&mut providers, 1, None), vec!["core"]);
+        assert!(providers.is_attached_to(0, 1));
+        assert!(!providers.is_attached_to(1, 1));
+        assert_eq!(
+            providers.script_variables(1).collect::<Vec<_>>(),
+            vec!["core"]
+        );
+
+        providers.forget(1);
+        assert!(providers.is_attached_to(1, 1));
+    }
+}
+
+/// A script host which records the hooks every script handled, for testing the systems shared by all hosts
+#[cfg(test)]
+pub(crate) mod test_host {
+    use bevy::{ecs::system::SystemState, reflect::TypeUuid};
+    use bevy_event_priority::{AddPriorityEvent, PriorityEventWriter, PriorityEvents};
+
+    use super::*;
+    use crate::{quota::ScriptQuotas, systems::CachedScriptState};
+
+    #[derive(Debug, TypeUuid)]
+    #[uuid = "5b0d6c1e-93f4-4a57-b0c2-8e4f1d7a3c29"]
+    pub(crate) struct TestScript;
+
+    impl CodeAsset for TestScript {
+        fn bytes(&self) -> &[u8] {
+            &[]
+        }
+    }
+
+    #[derive(Debug, Clone)]
+    pub(crate) struct TestEvent {
+        pub hook: &'static str,
+        pub recipients: Recipients,
+    }
+
+    impl ScriptEvent for TestEvent {
+        fn recipients(&self) -> &Recipients {
+            &self.recipients
+        }
+    }
+
+    pub(crate) struct TestDocs;
+
+    impl DocFragment for TestDocs {
+        fn merge(self, _o: Self) -> Self {
+            self
+        }
+
+        fn gen_docs(self) -> Result<(), ScriptError> {
+            Ok(())
+        }
+
+        fn name(&self) -> &'static str {
+            "test"
+        }
+    }
+
+    #[derive(Debug, Default)]
+    pub(crate) struct TestContext {
+        /// the hooks of the events the script handled, in order
+        pub handled: Vec<&'static str>,
+        /// the number of events refused because the script exceeded its query quota
+        pub refused: u32,
+    }
+
+    /// Handles each event by recording its hook, charging a query to the [`ScriptQuotas`] if present
+    #[derive(Default, Resource)]
+    pub(crate) struct TestHost;
+
+    impl ScriptHost for TestHost {
+        type ScriptContext = TestContext;
+        type ScriptEvent = TestEvent;
+        type ScriptAsset = TestScript;
+        type APITarget = ();
+        type DocTarget = TestDocs;
+
+        fn load_script(
+            &mut self,
+            _script: &[u8],
+            _script_data: &ScriptData,
+            _providers: &mut APIProviders<Self>,
+        ) -> Result<Self::ScriptContext, ScriptError> {
+            Ok(TestContext::default())
+        }
+
+        fn setup_script(
+            &mut self,
+            script_data: &ScriptData,
+            ctx: &mut Self::ScriptContext,
+            providers: &mut APIProviders<Self>,
+        ) -> Result<(), ScriptError> {
+            providers.setup_all(script_data, ctx)
+        }
+
+        fn handle_events<'a>(
+            &self,
+            world: &mut World,
+            events: &[Self::ScriptEvent],
+            ctxs: impl Iterator<Item = (ScriptData<'a>, &'a mut Self::ScriptContext)>,
+            _providers: &mut APIProviders<Self>,
+        ) {
+            for (script_data, ctx) in ctxs {
+                for event in events
+                    .iter()
+                    .filter(|e| e.recipients.is_recipient(&script_data))
+                {
+                    let charged = world
+                        .get_resource_mut::<ScriptQuotas>()
+                        .map_or(Ok(()), |mut quotas| quotas.charge(ScriptQuotas::QUERY));
+                    match charged {
+                        Ok(()) => ctx.handled.push(event.hook),
+                        Err(_) => ctx.refused += 1,
+                    }
+                }
+            }
+        }
+
+        fn register_with_app(app: &mut App, _stage: impl StageLabel) {
+            app.add_priority_event::<TestEvent>()
+                .init_resource::<CachedScriptState<Self>>()
+                .init_resource::<ScriptContexts<TestContext>>()
+                .init_resource::<APIProviders<Self>>();
+        }
+    }
+
+    /// A world holding the resources [`handle_script_events`] needs, with a loaded script for each of the given scripts
+    pub(crate) fn world_with_scripts(scripts: &[(ScriptId, Entity)]) -> World {
+        let mut world = World::new();
+        world.init_resource::<PriorityEvents<TestEvent>>();
+        world.init_resource::<Events<ScriptErrorEvent>>();
+        world.init_resource::<Events<ScriptLoaded>>();
+        world.init_resource::<CachedScriptState<TestHost>>();
+        world.init_resource::<TestHost>();
+        world.init_resource::<APIProviders<TestHost>>();
+
+        let mut contexts = ScriptContexts::<TestContext>::default();
+        for (sid, entity) in scripts {
+            let fd = ScriptData {
+                sid: *sid,
+                entity: *entity,
+                name: "script.test",
+                groups: &[],
+            };
+            contexts.insert_context(fd, Some(TestContext::default()));
+        }
+        world.insert_resource(contexts);
+        world
+    }
+
+    pub(crate) fn send_event(world: &mut World, event: TestEvent, priority: u32) {
+        let mut state = SystemState::<PriorityEventWriter<TestEvent>>::new(world);
+        state.get_mut(world).send(event, priority);
+    }
+
+    /// The context of the given script
+    pub(crate) fn context(world: &World, script_id: ScriptId) -> &TestContext {
+        world
+            .resource::<ScriptContexts<TestContext>>()
+            .context_entities[&script_id]
+            .1
+            .as_ref()
+            .expect("test scripts are loaded")
+    }
+}
@@ -0,0 +1,285 @@
+//! Launching scripts by host name at runtime, for example from an in-game console
+use bevy::{
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
+
+use crate::{
+    error::ScriptError,
+    hosts::{Script, ScriptCollection, ScriptHost},
+    status::{list_host_scripts, ScriptStatus},
+};
+
+type LaunchFn = Box<dyn Fn(&mut World, &str, Option<Entity>) -> Entity + Send + Sync + 'static>;
+
+struct ScriptLauncher {
+    extensions: HashSet<String>,
+    launch: LaunchFn,
+    list: fn(&World, &str) -> Vec<ScriptStatus>,
+}
+
+/// Parameters passed to scripts launched via [`ScriptLaunchers::launch`], keyed by script name.
+///
+/// Parameters are stored in their raw form (e.g. a JSON string), API providers are free to parse and expose them to scripts.
+#[derive(Component, Debug, Default, Clone)]
+pub struct ScriptParams {
+    pub params: HashMap<String, String>,
+}
+
+/// Describes which script to launch, on which host and with what parameters
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScriptLaunchRequest {
+    /// the name of the host the script was registered with, e.g. `lua`
+    pub host: String,
+    /// the path to the script relative to the launcher root, e.g. `/foo.lua`
+    pub path: String,
+    /// the entity to attach the script to, a new entity is spawned if none is given
+    pub entity: Option<Entity>,
+    /// raw parameters stored in the [`ScriptParams`] component of the script's entity
+    pub params: Option<String>,
+}
+
+/// The outcome of a successful launch
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScriptLaunched {
+    /// the name of the host which handled the script
+    pub host: String,
+    /// the full asset path of the script
+    pub asset_path: String,
+    /// the entity the script was attached to
+    pub entity: Entity,
+}
+
+/// A registry of script hosts which can launch and list scripts by name,
+/// useful once multiple hosts are registered and scripts are started from user input.
+///
+/// ```rust,ignore
+/// app.insert_resource(
+///     ScriptLaunchers::default()
+///         .with_host::<LuaScriptHost<()>>("lua", &["lua"])
+///         .with_host::<RhaiScriptHost<()>>("rhai", &["rhai"]),
+/// );
+/// ```
+#[derive(Resource)]
+pub struct ScriptLaunchers {
+    /// the asset directory script paths are relative to
+    pub root: String,
+    hosts: HashMap<String, ScriptLauncher>,
+}
+
+impl Default for ScriptLaunchers {
+    fn default() -> Self {
+        Self {
+            root: "scripts".to_owned(),
+            hosts: Default::default(),
+        }
+    }
+}
+
+impl ScriptLaunchers {
+    /// Lets the host `H` launch scripts under the given name, scripts must have one of the given extensions
+    pub fn register<H: ScriptHost>(&mut self, name: &str, extensions: &[&str]) {
+        self.hosts.insert(
+            name.to_owned(),
+            ScriptLauncher {
+                extensions: extensions.iter().map(|e| e.to_string()).collect(),
+                launch: Box::new(launch_script::<H>),
+                list: list_host_scripts::<H>,
+            },
+        );
+    }
+
+    /// Builder version of [`ScriptLaunchers::register`]
+    pub fn with_host<H: ScriptHost>(mut self, name: &str, extensions: &[&str]) -> Self {
+        self.register::<H>(name, extensions);
+        self
+    }
+
+    /// The names of all registered hosts
+    pub fn host_names(&self) -> impl Iterator<Item = &str> {
+        self.hosts.keys().map(String::as_str)
+    }
+
+    /// Lists the scripts of every registered host, ordered by host name
+    pub fn list_scripts(&self, world: &World) -> Vec<ScriptStatus> {
+        let mut hosts = self.hosts.iter().collect::<Vec<_>>();
+        hosts.sort_unstable_by_key(|(name, _)| name.as_str());
+        hosts
+            .into_iter()
+            .flat_map(|(name, launcher)| (launcher.list)(world, name))
+            .collect()
+    }
+
+    /// Attaches the requested script to its entity on the chosen host.
+    ///
+    /// Fails if the host is not registered, the extension of the script does not match the host
+    /// or the requested entity does not exist.
+    pub fn launch(
+        &self,
+        world: &mut World,
+        request: ScriptLaunchRequest,
+    ) -> Result<ScriptLaunched, ScriptError> {
+        let launcher = self.hosts.get(&request.host).ok_or_else(|| {
+            let mut known = self.host_names().collect::<Vec<_>>();
+            known.sort_unstable();
+            ScriptError::Other(format!(
+                "Unknown script host `{}`, expected one of: {}",
+                request.host,
+                known.join(", ")
+            ))
+        })?;
+
+        let path = request.path.trim_start_matches('/');
+        let extension = path
+            .rsplit_once('.')
+            .map(|(_, ext)| ext)
+            .unwrap_or_default();
+        if !launcher.extensions.contains(extension) {
+            let mut expected = launcher
+                .extensions
+                .iter()
+                .map(String::as_str)
+                .collect::<Vec<_>>();
+            expected.sort_unstable();
+            return Err(ScriptError::Other(format!(
+                "Script `{path}` cannot be handled by host `{}`, expected extensions: {}",
+                request.host,
+                expected.join(", ")
+            )));
+        }
+
+        if let Some(entity) = request.entity {
+            if world.get_entity(entity).is_none() {
+                return Err(ScriptError::Other(format!(
+                    "Cannot attach script `{path}` to missing entity {entity:?}"
+                )));
+            }
+        }
+
+        let asset_path = format!("{}/{path}", self.root.trim_end_matches('/'));
+        let entity = (launcher.launch)(world, &asset_path, request.entity);
+
+        if let Some(params) = request.params {
+            let mut entity_mut = world.entity_mut(entity);
+            match entity_mut.get_mut::<ScriptParams>() {
+                Some(mut existing) => {
+                    existing.params.insert(asset_path.clone(), params);
+                }
+                None => {
+                    entity_mut.insert(ScriptParams {
+                        params: HashMap::from_iter([(asset_path.clone(), params)]),
+                    });
+                }
+            }
+        }
+
+        Ok(ScriptLaunched {
+            host: request.host,
+            asset_path,
+            entity,
+        })
+    }
+}
+
+fn launch_script<H: ScriptHost>(
+    world: &mut World,
+    asset_path: &str,
+    entity: Option<Entity>,
+) -> Entity {
+    let handle = world
+        .resource::<AssetServer>()
+        .load::<H::ScriptAsset, _>(asset_path);
+    let script = Script::<H::ScriptAsset>::new(asset_path.to_owned(), handle);
+
+    let entity = match entity {
+        Some(entity) => entity,
+        None => {
+            return world
+                .spawn(ScriptCollection {
+                    scripts: vec![script],
+                })
+                .id()
+        }
+    };
+
+    let mut entity_mut = world.entity_mut(entity);
+    match entity_mut.get_mut::<ScriptCollection<H::ScriptAsset>>() {
+        Some(mut collection) => collection.scripts.push(script),
+        None => {
+            entity_mut.insert(ScriptCollection {
+                scripts: vec![script],
+            });
+        }
+    };
+    entity
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hosts::test_host::{TestHost, TestScript};
+
+    fn launchers() -> ScriptLaunchers {
+        ScriptLaunchers::default().with_host::<TestHost>("test", &["test"])
+    }
+
+    fn request(host: &str, path: &str, entity: Option<Entity>) -> ScriptLaunchRequest {
+        ScriptLaunchRequest {
+            host: host.to_owned(),
+            path: path.to_owned(),
+            entity,
+            params: Some("{\"speed\": 2}".to_owned()),
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_hosts_extensions_and_entities() {
+        let mut world = World::new();
+        let missing = world.spawn(()).id();
+        world.despawn(missing);
+        let launchers = launchers();
+
+        let errors = [
+            request("lua", "/foo.test", None),
+            request("test", "/foo.lua", None),
+            request("test", "/foo.test", Some(missing)),
+        ]
+        .map(|r| launchers.launch(&mut world, r).unwrap_err().to_string());
+
+        assert!(errors[0].contains("one of: test"), "{}", errors[0]);
+        assert!(
+            errors[1].contains("expected extensions: test"),
+            "{}",
+            errors[1]
+        );
+        assert!(errors[2].contains("missing entity"), "{}", errors[2]);
+    }
+
+    #[test]
+    fn launches_scripts_with_their_params() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugin(AssetPlugin::default());
+        let world = &mut app.world;
+        let entity = world.spawn(()).id();
+
+        let launched = launchers()
+            .launch(world, request("test", "/foo.test", Some(entity)))
+            .unwrap();
+
+        assert_eq!(
+            launched,
+            ScriptLaunched {
+                host: "test".to_owned(),
+                asset_path: "scripts/foo.test".to_owned(),
+                entity,
+            }
+        );
+        let collection = world.get::<ScriptCollection<TestScript>>(entity).unwrap();
+        assert_eq!(collection.scripts[0].name(), "scripts/foo.test");
+        assert_eq!(
+            world.get::<ScriptParams>(entity).unwrap().params["scripts/foo.test"],
+            "{\"speed\": 2}"
+        );
+    }
+}
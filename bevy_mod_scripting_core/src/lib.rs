@@ -1,38 +1,121 @@
 use crate::{
+    accounting::{advance_script_cpu_usage, ScriptCpuUsage},
+    docs::{HookDoc, HookRegistry},
     event::ScriptErrorEvent,
     hosts::{APIProvider, APIProviders, ScriptHost},
 };
-use bevy::{ecs::schedule::IntoRunCriteria, prelude::*};
+use bevy::{
+    ecs::schedule::{IntoRunCriteria, ShouldRun, StateData},
+    prelude::*,
+};
+use commands::ScriptCommands;
+use config::{
+    check_handler_writers, validate_scripting_config, ScriptingConfig, ScriptingConfigReport,
+};
 use event::ScriptLoaded;
-use systems::{script_event_handler, ScriptSystemLabel};
+use filter::ProviderFilterContext;
+use groups::ScriptGroups;
+use metrics::{advance_script_sync_metrics, ScriptSyncMetrics};
+use platform::{log_platform_capabilities, PlatformCapabilities};
+use priorities::{named_script_event_handler, PriorityRange, ScriptHandlerPriorities};
+use quota::reset_script_quotas;
+use registry::{sync_script_registry, ScriptRegistry};
+use retention::retain_script_assets;
+use status::{record_last_script_errors, DisabledScripts, LastScriptErrors};
+use std::sync::Mutex;
+use systems::{
+    script_event_dropper, script_event_handler, script_event_resender, script_event_retainer,
+    OutOfStateEvents, RetainedScriptEvents, ScriptSystemLabel,
+};
 
+pub mod accounting;
 pub mod asset;
+#[cfg(feature = "auto_register")]
+pub mod auto_register;
+#[cfg(feature = "cli")]
+pub mod cli;
+pub mod commands;
+pub mod composite;
+pub mod config;
 pub mod docs;
 pub mod error;
 pub mod event;
+pub mod filter;
+pub mod fuzz;
+pub mod groups;
+pub mod handover;
+pub mod hook;
 pub mod hosts;
+pub mod launch;
+pub mod load_order;
+pub mod metrics;
+pub mod mirror;
+pub mod platform;
+pub mod priorities;
+pub mod quota;
+pub mod registry;
+pub mod repl;
+pub mod retention;
+pub mod rollback;
+pub mod routing;
+pub mod safe_mode;
+pub mod status;
 pub mod systems;
+pub mod testing;
 pub mod world;
 pub mod prelude {
     // general
     pub use {
+        crate::accounting::{
+            FairShareThrottle, ScriptCpuStats, ScriptCpuUsage, ScriptThrottle,
+            ScriptThrottlePolicy, ThrottleContext,
+        },
         crate::asset::CodeAsset,
-        crate::docs::DocFragment,
-        crate::error::ScriptError,
+        crate::commands::{flush_script_commands, ScriptCommands},
+        crate::composite::{BoxedAPIProvider, CompositeAPIProvider, NamespaceFn},
+        crate::config::{ConfigIssue, ConfigSeverity, ScriptingConfigReport},
+        crate::docs::{DocFragment, HookArg, HookDoc, HookRegistry},
+        crate::error::{ScriptError, ScriptErrorCategory},
         crate::event::{ScriptErrorEvent, ScriptEvent},
+        crate::filter::{ProviderFilterContext, ScriptTags},
+        crate::fuzz::{fuzz_script, FuzzLimits, FuzzTarget},
+        crate::groups::ScriptGroups,
+        crate::handover::{CopyScripts, MoveScripts, ScriptCopies},
+        crate::hook::HookName,
         crate::hosts::{
-            APIProvider, APIProviders, Recipients, Script, ScriptCollection, ScriptContexts,
-            ScriptData, ScriptHost,
+            APIProvider, APIProviders, Recipients, Script, ScriptCollection, ScriptContextHandle,
+            ScriptContexts, ScriptData, ScriptHost, ScriptId, WarmCloneScripts,
+        },
+        crate::launch::{ScriptLaunchRequest, ScriptLaunched, ScriptLaunchers, ScriptParams},
+        crate::load_order::{AddScriptLoadOrder, ScriptLoadOrder, DEFAULT_LOAD_ORDER_PATH},
+        crate::metrics::{ScriptSyncCounts, ScriptSyncMetrics},
+        crate::mirror::{
+            AddScriptEventMirror, MirroredEventWriter, ScriptEventMirror, ScriptEventTransport,
         },
+        crate::platform::PlatformCapabilities,
+        crate::priorities::{PriorityRange, ScriptHandlerPriorities},
+        crate::quota::ScriptQuotas,
+        crate::registry::{RegisteredScript, ScriptRegistry},
+        crate::repl::{script_repl_system, ReplEval, ReplHost, ReplOutput, ScriptRepl},
+        crate::retention::{ScriptHandlePolicy, ScriptRetention},
+        crate::rollback::{RollbackHost, ScriptSnapshots},
+        crate::routing::ScriptHookRoutes,
+        crate::safe_mode::{SafeModeActivated, SafeModePlugin},
+        crate::status::{DisabledScripts, LastScriptErrors, ScriptLoadState, ScriptStatus},
+        crate::systems::{OutOfStateEvents, RetainedScriptEvents},
+        crate::testing::{ScriptTestOutput, ScriptTestReport},
         crate::{
             AddScriptApiProvider, AddScriptHost, AddScriptHostHandler, GenDocumentation,
-            ScriptingPlugin,
+            RegisterScriptHook, ScriptingPlugin, ScriptingPluginBuilder,
         },
         bevy_event_priority::{
-            AddPriorityEvent, PriorityEvent, PriorityEventReader, PriorityEventWriter,
+            AddPriorityEvent, Priority, PriorityEvent, PriorityEventReader, PriorityEventWriter,
             PriorityEvents, PriorityIterator,
         },
     };
+
+    #[cfg(feature = "cli")]
+    pub use crate::cli::{ScriptingArgs, ScriptingCli};
 }
 pub use bevy_event_priority as events;
 
@@ -42,7 +125,162 @@ pub struct ScriptingPlugin;
 
 impl Plugin for ScriptingPlugin {
     fn build(&self, app: &mut bevy::prelude::App) {
-        app.add_event::<ScriptErrorEvent>();
+        if !app.world.contains_resource::<Events<ScriptErrorEvent>>() {
+            app.add_event::<ScriptErrorEvent>();
+        }
+
+        if !app.world.contains_resource::<LastScriptErrors>() {
+            app.init_resource::<LastScriptErrors>()
+                .init_resource::<DisabledScripts>()
+                .init_resource::<ScriptGroups>()
+                .init_resource::<ScriptCommands>()
+                .add_system_to_stage(CoreStage::First, reset_script_quotas)
+                .add_system_to_stage(CoreStage::Last, record_last_script_errors);
+        }
+
+        if !app.world.contains_resource::<PlatformCapabilities>() {
+            app.init_resource::<PlatformCapabilities>()
+                .add_startup_system(log_platform_capabilities);
+        }
+
+        if !app.world.contains_resource::<ScriptingConfigReport>() {
+            app.init_resource::<ScriptingConfigReport>()
+                .add_startup_system(validate_scripting_config.at_end())
+                .add_system_to_stage(CoreStage::Last, check_handler_writers);
+        }
+    }
+}
+
+impl ScriptingPlugin {
+    /// Begins building a scripting plugin which also registers script hosts, providers, handler stages and documentation
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new() -> ScriptingPluginBuilder {
+        ScriptingPluginBuilder::default()
+    }
+}
+
+type AppSetup = Box<dyn FnOnce(&mut App) + Send + 'static>;
+
+/// A bevy plugin registering every part of the scripting setup in one place.
+///
+/// Setup steps are applied in the following order regardless of the order they were specified in:
+/// hosts, API providers, handler stages and finally documentation generation.
+/// ```rust,ignore
+/// app.add_plugin(
+///     ScriptingPlugin::new()
+///         .with_host::<LuaScriptHost<MyLuaArg>, _>(CoreStage::PostUpdate)
+///         .with_api_provider::<LuaScriptHost<MyLuaArg>>(Box::new(LuaBevyAPIProvider))
+///         .with_handler_stage::<LuaScriptHost<MyLuaArg>, _, 0, 0>(CoreStage::PostUpdate)
+///         .with_documentation::<LuaScriptHost<MyLuaArg>>(),
+/// );
+/// ```
+#[derive(Default)]
+pub struct ScriptingPluginBuilder {
+    hosts: Mutex<Vec<AppSetup>>,
+    providers: Mutex<Vec<AppSetup>>,
+    handlers: Mutex<Vec<AppSetup>>,
+    docs: Mutex<Vec<AppSetup>>,
+}
+
+impl ScriptingPluginBuilder {
+    fn push(steps: &mut Mutex<Vec<AppSetup>>, step: impl FnOnce(&mut App) + Send + 'static) {
+        steps
+            .get_mut()
+            .expect("Poison error in plugin builder")
+            .push(Box::new(step));
+    }
+
+    /// Registers the given script host, see [`AddScriptHost::add_script_host`]
+    pub fn with_host<T: ScriptHost, S: StageLabel>(mut self, stage: S) -> Self {
+        let stage = stage.as_label();
+        Self::push(&mut self.hosts, move |app| {
+            app.add_script_host::<T, _>(stage);
+        });
+        self
+    }
+
+    /// Registers the given script host and modifies its settings right after it's initialized
+    pub fn with_host_settings<T: ScriptHost, S: StageLabel>(
+        mut self,
+        stage: S,
+        settings: impl FnOnce(&mut T) + Send + 'static,
+    ) -> Self {
+        let stage = stage.as_label();
+        Self::push(&mut self.hosts, move |app| {
+            app.add_script_host::<T, _>(stage);
+            settings(&mut app.world.resource_mut::<T>());
+        });
+        self
+    }
+
+    /// Adds an API provider to the given script host, see [`AddScriptApiProvider::add_api_provider`]
+    pub fn with_api_provider<T: ScriptHost>(
+        mut self,
+        provider: Box<
+            dyn APIProvider<
+                APITarget = T::APITarget,
+                DocTarget = T::DocTarget,
+                ScriptContext = T::ScriptContext,
+            >,
+        >,
+    ) -> Self {
+        Self::push(&mut self.providers, move |app| {
+            app.add_api_provider::<T>(provider);
+        });
+        self
+    }
+
+    /// Adds a script handler stage to the given script host, see [`AddScriptHostHandler::add_script_handler_stage`]
+    pub fn with_handler_stage<T: ScriptHost, S: StageLabel, const MAX: u32, const MIN: u32>(
+        mut self,
+        stage: S,
+    ) -> Self {
+        let stage = stage.as_label();
+        Self::push(&mut self.handlers, move |app| {
+            app.add_script_handler_stage::<T, _, MAX, MIN>(stage);
+        });
+        self
+    }
+
+    /// Adds a state bound script handler stage to the given script host, see [`AddScriptHostHandler::add_script_handler_in_state`]
+    pub fn with_handler_in_state<
+        T: ScriptHost,
+        L: StageLabel,
+        S: StateData,
+        const MAX: u32,
+        const MIN: u32,
+    >(
+        mut self,
+        stage: L,
+        state: S,
+        out_of_state: OutOfStateEvents,
+    ) -> Self {
+        let stage = stage.as_label();
+        Self::push(&mut self.handlers, move |app| {
+            app.add_script_handler_in_state::<T, _, S, MAX, MIN>(stage, state, out_of_state);
+        });
+        self
+    }
+
+    /// Generates documentation for the given script host once all providers are registered, see [`GenDocumentation::update_documentation`]
+    pub fn with_documentation<T: ScriptHost>(mut self) -> Self {
+        Self::push(&mut self.docs, |app| {
+            app.update_documentation::<T>();
+        });
+        self
+    }
+}
+
+impl Plugin for ScriptingPluginBuilder {
+    fn build(&self, app: &mut App) {
+        ScriptingPlugin.build(app);
+
+        for steps in [&self.hosts, &self.providers, &self.handlers, &self.docs] {
+            let steps = std::mem::take(&mut *steps.lock().expect("Poison error in plugin builder"));
+            for step in steps {
+                step(app);
+            }
+        }
     }
 }
 
@@ -51,14 +289,27 @@ pub trait GenDocumentation {
 }
 
 impl GenDocumentation for App {
-    /// Updates/Generates documentation and any other artifacts required for script API's. Disabled in optimized builds unless `doc_always` feature is enabled.
+    /// Updates/Generates documentation and any other artifacts required for script API's. Disabled in optimized builds unless `doc_always` feature is enabled,
+    /// and always disabled on `wasm32` targets.
     fn update_documentation<T: ScriptHost>(&mut self) -> &mut Self {
-        #[cfg(any(debug_assertions, feature = "doc_always"))]
+        // there is no filesystem to write documentation to on the web
+        #[cfg(all(
+            any(debug_assertions, feature = "doc_always"),
+            not(target_arch = "wasm32")
+        ))]
         {
             info!("Generating documentation");
+            let provider_count = match self.world.get_resource::<APIProviders<T>>() {
+                Some(providers) => providers.providers.len(),
+                None => {
+                    error!("Cannot generate documentation for a script host which was never registered");
+                    return self;
+                }
+            };
+            ScriptingConfig::record::<T>(self, |c| c.documented.push(provider_count));
             let w = &mut self.world;
             let providers: &APIProviders<T> = w.resource();
-            if let Err(e) = providers.gen_all() {
+            if let Err(e) = providers.gen_all_with_hooks(w.get_resource::<HookRegistry>()) {
                 error!("{}", e);
             }
             info!("Documentation generated");
@@ -68,6 +319,21 @@ impl GenDocumentation for App {
     }
 }
 
+pub trait RegisterScriptHook {
+    /// Documents a hook emitted by the game, so that generated documentation also describes the callbacks scripts can implement.
+    /// Hooks need to be registered before any `update_documentation` calls.
+    fn register_script_hook(&mut self, hook: HookDoc) -> &mut Self;
+}
+
+impl RegisterScriptHook for App {
+    fn register_script_hook(&mut self, hook: HookDoc) -> &mut Self {
+        self.world
+            .get_resource_or_insert_with(HookRegistry::default)
+            .register(hook);
+        self
+    }
+}
+
 /// Trait for app builder notation
 pub trait AddScriptHost {
     /// registers the given script host with your app,
@@ -85,7 +351,21 @@ impl AddScriptHost for App {
     {
         T::register_with_app(self, stage);
         self.init_resource::<T>();
+        self.init_resource::<ScriptCpuUsage<T>>();
+        self.init_resource::<ScriptSyncMetrics<T>>();
+        self.init_resource::<ScriptRegistry>();
         self.add_event::<ScriptLoaded>();
+        self.add_system_to_stage(CoreStage::First, advance_script_cpu_usage::<T>);
+        self.add_system_to_stage(CoreStage::First, advance_script_sync_metrics::<T>);
+        self.add_system_to_stage(CoreStage::Last, sync_script_registry::<T>);
+        self.add_system_to_stage(CoreStage::Last, retain_script_assets::<T::ScriptAsset>);
+        self.world
+            .get_resource_or_insert_with(PlatformCapabilities::current)
+            .hosts
+            .push(std::any::type_name::<T>());
+        ScriptingConfig::record::<T>(self, |c| c.registered = true);
+        #[cfg(feature = "auto_register")]
+        auto_register::register_submitted_api_providers::<T>(self);
         self
     }
 }
@@ -101,6 +381,29 @@ pub trait AddScriptApiProvider {
             >,
         >,
     ) -> &mut Self;
+
+    /// Adds every provider of the given set, see [`APIProviders::with`]
+    fn add_api_providers<T: ScriptHost>(&mut self, providers: APIProviders<T>) -> &mut Self;
+
+    /// Adds a provider which is only attached to the scripts accepted by the filter, based on the script
+    /// and the [`ScriptTags`](crate::filter::ScriptTags) of its entity:
+    /// ```rust,ignore
+    /// app.add_filtered_api_provider::<LuaScriptHost<MyArgs>>(Box::new(UiAPIProvider), |ctx| ctx.has_tag("ui"));
+    /// ```
+    ///
+    /// Hosts sharing one engine between all scripts (e.g. Rhai) attach APIs to every script regardless,
+    /// there filters only apply to the per script setup of the provider.
+    fn add_filtered_api_provider<T: ScriptHost>(
+        &mut self,
+        provider: Box<
+            dyn APIProvider<
+                APITarget = T::APITarget,
+                DocTarget = T::DocTarget,
+                ScriptContext = T::ScriptContext,
+            >,
+        >,
+        filter: impl Fn(&ProviderFilterContext) -> bool + Send + Sync + 'static,
+    ) -> &mut Self;
 }
 
 impl AddScriptApiProvider for App {
@@ -115,9 +418,38 @@ impl AddScriptApiProvider for App {
         >,
     ) -> &mut Self {
         provider.register_with_app(self);
-        let w = &mut self.world;
-        let providers: &mut APIProviders<T> = &mut w.resource_mut();
-        providers.providers.push(provider);
+        ScriptingConfig::record::<T>(self, |c| c.providers += 1);
+        // the host may still be registered later on, and is reported as missing on startup otherwise
+        self.world
+            .get_resource_or_insert_with(APIProviders::<T>::default)
+            .providers
+            .push(provider);
+        self
+    }
+
+    fn add_api_providers<T: ScriptHost>(&mut self, providers: APIProviders<T>) -> &mut Self {
+        for provider in providers.providers {
+            self.add_api_provider::<T>(provider);
+        }
+        self
+    }
+
+    fn add_filtered_api_provider<T: ScriptHost>(
+        &mut self,
+        provider: Box<
+            dyn APIProvider<
+                APITarget = T::APITarget,
+                DocTarget = T::DocTarget,
+                ScriptContext = T::ScriptContext,
+            >,
+        >,
+        filter: impl Fn(&ProviderFilterContext) -> bool + Send + Sync + 'static,
+    ) -> &mut Self {
+        provider.register_with_app(self);
+        ScriptingConfig::record::<T>(self, |c| c.providers += 1);
+        self.world
+            .get_resource_or_insert_with(APIProviders::<T>::default)
+            .push_filtered(provider, Box::new(filter));
         self
     }
 }
@@ -164,6 +496,52 @@ pub trait AddScriptHostHandler {
         stage: S,
         criteria: C,
     ) -> &mut Self;
+
+    /// Like `add_script_handler_stage` but the priority range is kept in the [`ScriptHandlerPriorities`] resource under the given name,
+    /// and read every time the handler runs, so it can be changed at runtime, e.g. from an inspector.
+    ///
+    /// The given range is only the initial one and does not replace a range already set for the name.
+    fn add_named_script_handler_stage<T: ScriptHost, S: StageLabel>(
+        &mut self,
+        stage: S,
+        name: &str,
+        range: PriorityRange,
+    ) -> &mut Self;
+
+    /// Like `add_script_handler_stage` but events are only handled while the given state is the current state.
+    ///
+    /// While outside of the state, events in this handler's priority range are either set aside in [`RetainedScriptEvents`]
+    /// and handled once the state is entered again, or discarded, depending on `out_of_state`.
+    /// Either way they are taken out of the queue at the end of the stage, so handlers of lower priority ranges
+    /// in later stages do not discard them as having already run.
+    fn add_script_handler_in_state<
+        T: ScriptHost,
+        L: StageLabel,
+        S: StateData,
+        const MAX: u32,
+        const MIN: u32,
+    >(
+        &mut self,
+        stage: L,
+        state: S,
+        out_of_state: OutOfStateEvents,
+    ) -> &mut Self;
+}
+
+/// Run criteria which runs only if the current state of type `S` is equal to `state`,
+/// or the opposite when `inverted` is set.
+fn in_state<S: StateData>(
+    state: S,
+    inverted: bool,
+) -> impl FnMut(Option<Res<State<S>>>) -> ShouldRun {
+    move |current: Option<Res<State<S>>>| {
+        let in_state = current.map(|c| c.current() == &state).unwrap_or(false);
+        if in_state != inverted {
+            ShouldRun::Yes
+        } else {
+            ShouldRun::No
+        }
+    }
 }
 
 impl AddScriptHostHandler for App {
@@ -177,6 +555,7 @@ impl AddScriptHostHandler for App {
                 .label(ScriptSystemLabel::EventHandling)
                 .at_end(),
         );
+        ScriptingConfig::record::<T>(self, |c| c.handlers.push((MAX, MIN)));
         self
     }
 
@@ -199,6 +578,130 @@ impl AddScriptHostHandler for App {
                 .at_end()
                 .with_run_criteria(criteria),
         );
+        ScriptingConfig::record::<T>(self, |c| c.handlers.push((MAX, MIN)));
+        self
+    }
+
+    fn add_named_script_handler_stage<T: ScriptHost, S: StageLabel>(
+        &mut self,
+        stage: S,
+        name: &str,
+        range: PriorityRange,
+    ) -> &mut Self {
+        self.register_type::<PriorityRange>()
+            .register_type::<ScriptHandlerPriorities>();
+        self.world
+            .get_resource_or_insert_with(ScriptHandlerPriorities::default)
+            .ranges
+            .entry(name.to_owned())
+            .or_insert(range);
+        self.add_system_to_stage(
+            stage,
+            named_script_event_handler::<T>(name.to_owned())
+                .label(ScriptSystemLabel::EventHandling)
+                .at_end(),
+        );
+        ScriptingConfig::record::<T>(self, |c| c.named_handlers.push(name.to_owned()));
         self
     }
+
+    fn add_script_handler_in_state<
+        T: ScriptHost,
+        L: StageLabel,
+        S: StateData,
+        const MAX: u32,
+        const MIN: u32,
+    >(
+        &mut self,
+        stage: L,
+        state: S,
+        out_of_state: OutOfStateEvents,
+    ) -> &mut Self {
+        let stage = stage.as_label();
+        self.add_script_handler_stage_with_criteria::<T, _, _, _, MAX, MIN>(
+            stage,
+            in_state(state.clone(), false),
+        );
+
+        match out_of_state {
+            OutOfStateEvents::Drop => self.add_system_to_stage(
+                stage,
+                script_event_dropper::<T, MAX, MIN>
+                    .label(ScriptSystemLabel::EventHandling)
+                    .at_end()
+                    .with_run_criteria(in_state(state, true)),
+            ),
+            OutOfStateEvents::Retain => self
+                .init_resource::<RetainedScriptEvents<T, MAX, MIN>>()
+                // runs before the exclusive handler at the end of the stage
+                .add_system_to_stage(
+                    stage,
+                    script_event_resender::<T, MAX, MIN>
+                        .with_run_criteria(in_state(state.clone(), false)),
+                )
+                .add_system_to_stage(
+                    stage,
+                    script_event_retainer::<T, MAX, MIN>
+                        .label(ScriptSystemLabel::EventHandling)
+                        .at_end()
+                        .with_run_criteria(in_state(state, true)),
+                ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::handler_priority_ranges,
+        error::ScriptError,
+        hosts::test_host::{TestContext, TestDocs, TestHost},
+    };
+
+    #[derive(Resource)]
+    struct HostRegisteredFirst(bool);
+
+    /// Records whether the host was registered before the provider
+    struct CheckHostProvider;
+
+    impl APIProvider for CheckHostProvider {
+        type APITarget = ();
+        type ScriptContext = TestContext;
+        type DocTarget = TestDocs;
+
+        fn attach_api(&mut self, _api: &mut Self::APITarget) -> Result<(), ScriptError> {
+            Ok(())
+        }
+
+        fn register_with_app(&self, app: &mut App) {
+            let registered = app.world.contains_resource::<TestHost>();
+            app.insert_resource(HostRegisteredFirst(registered));
+        }
+    }
+
+    #[test]
+    fn plugin_builder_registers_hosts_first() {
+        let mut app = App::new();
+        app.add_plugin(
+            ScriptingPlugin::new()
+                .with_handler_stage::<TestHost, _, 0, 5>(CoreStage::PostUpdate)
+                .with_api_provider::<TestHost>(Box::new(CheckHostProvider))
+                .with_host::<TestHost, _>(CoreStage::PostUpdate),
+        );
+
+        assert!(app.world.contains_resource::<Events<ScriptErrorEvent>>());
+        assert!(app.world.resource::<HostRegisteredFirst>().0);
+        assert_eq!(
+            app.world
+                .resource::<APIProviders<TestHost>>()
+                .providers
+                .len(),
+            1
+        );
+        assert_eq!(
+            handler_priority_ranges::<TestHost>(&app.world),
+            vec![(0, 5)]
+        );
+    }
 }
@@ -0,0 +1,235 @@
+//! Player editable load order and enabled state of scripts and mod packs
+use bevy::{
+    asset::{Asset, AssetLoader, LoadContext, LoadedAsset},
+    prelude::*,
+    reflect::TypeUuid,
+    utils::BoxedFuture,
+};
+use serde::Deserialize;
+
+use crate::{
+    hosts::{Script, ScriptCollection, ScriptHost},
+    status::DisabledScripts,
+};
+
+/// The default path of the load order file, relative to the assets directory
+pub const DEFAULT_LOAD_ORDER_PATH: &str = "script_load_order.toml";
+
+/// Pins the order of scripts within each [`ScriptCollection`] and which scripts are disabled.
+///
+/// Entries refer either to a single script by name (e.g. `scripts/enemies.lua`, which also covers its labeled
+/// sub-assets such as `scripts/enemies.lua#goblin`) or to a whole mod pack by directory (e.g. `mods/goblins`).
+/// ```toml
+/// # scripts matched by earlier entries come first, unmatched scripts keep their order after all matched ones
+/// order = ["mods/core", "scripts/enemies.lua"]
+/// # disabled scripts stay attached but never receive events
+/// disabled = ["mods/cheats"]
+/// ```
+#[derive(Debug, Clone, Default, Deserialize, TypeUuid)]
+#[uuid = "c949ba63-046f-4ed4-abe1-20378f015251"]
+pub struct ScriptLoadOrder {
+    #[serde(default)]
+    pub order: Vec<String>,
+    #[serde(default)]
+    pub disabled: Vec<String>,
+}
+
+impl ScriptLoadOrder {
+    /// Returns true if the entry refers to the given script or to a mod pack containing it
+    pub fn matches(entry: &str, script_name: &str) -> bool {
+        let entry = entry.trim_start_matches('/').trim_end_matches('/');
+        let name = script_name.trim_start_matches('/');
+
+        match name.strip_prefix(entry) {
+            Some(rest) => rest.is_empty() || rest.starts_with('/') || rest.starts_with('#'),
+            None => false,
+        }
+    }
+
+    /// The position of the first `order` entry matching the script, if any
+    pub fn rank(&self, script_name: &str) -> Option<usize> {
+        self.order
+            .iter()
+            .position(|entry| Self::matches(entry, script_name))
+    }
+
+    pub fn is_enabled(&self, script_name: &str) -> bool {
+        !self
+            .disabled
+            .iter()
+            .any(|entry| Self::matches(entry, script_name))
+    }
+
+    /// Sorts the scripts according to the load order and updates which of them are disabled
+    pub fn apply<T: Asset>(&self, scripts: &mut [Script<T>], disabled: &mut DisabledScripts) {
+        // stable, so unmatched scripts keep their relative order
+        scripts.sort_by_key(|s| self.rank(s.name()).unwrap_or(usize::MAX));
+
+        for script in scripts.iter() {
+            if self.is_enabled(script.name()) {
+                disabled.enable(script.id());
+            } else {
+                disabled.disable(script.id());
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+/// Asset loader for load order files, claims the `toml` extension
+pub struct ScriptLoadOrderLoader;
+
+impl AssetLoader for ScriptLoadOrderLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let order: ScriptLoadOrder = toml::from_slice(bytes)?;
+            load_context.set_default_asset(LoadedAsset::new(order));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["toml"]
+    }
+}
+
+/// The load order file applied to script collections, see [`AddScriptLoadOrder::add_script_load_order`]
+#[derive(Resource, Debug, Clone)]
+pub struct ScriptLoadOrderHandle(pub Handle<ScriptLoadOrder>);
+
+/// Applies the load order to newly added script collections,
+/// or to all collections once the load order file is loaded or modified
+pub fn apply_script_load_order<H: ScriptHost>(
+    mut events: EventReader<AssetEvent<ScriptLoadOrder>>,
+    handle: Option<Res<ScriptLoadOrderHandle>>,
+    orders: Res<Assets<ScriptLoadOrder>>,
+    mut disabled: ResMut<DisabledScripts>,
+    mut collections: Query<(
+        &mut ScriptCollection<H::ScriptAsset>,
+        ChangeTrackers<ScriptCollection<H::ScriptAsset>>,
+    )>,
+) {
+    let handle = match handle {
+        Some(h) => h,
+        None => return,
+    };
+
+    let reapply = events.iter().any(|e| match e {
+        AssetEvent::Created { handle: h } | AssetEvent::Modified { handle: h } => h == &handle.0,
+        AssetEvent::Removed { .. } => false,
+    });
+
+    let order = match orders.get(&handle.0) {
+        Some(o) => o,
+        None => return,
+    };
+
+    for (mut collection, tracker) in collections.iter_mut() {
+        if reapply || tracker.is_added() {
+            order.apply(&mut collection.scripts, &mut disabled);
+        }
+    }
+}
+
+pub trait AddScriptLoadOrder {
+    /// Loads the load order file at the given asset path and applies it to the script collections of host `T`
+    /// before they are loaded. The stage must be the one the host was registered with.
+    ///
+    /// Note that this registers an asset loader for all `toml` files.
+    fn add_script_load_order<T: ScriptHost, S: StageLabel>(
+        &mut self,
+        stage: S,
+        path: &str,
+    ) -> &mut Self;
+}
+
+impl AddScriptLoadOrder for App {
+    fn add_script_load_order<T: ScriptHost, S: StageLabel>(
+        &mut self,
+        stage: S,
+        path: &str,
+    ) -> &mut Self {
+        if !self.world.contains_resource::<ScriptLoadOrderHandle>() {
+            self.add_asset::<ScriptLoadOrder>()
+                .init_asset_loader::<ScriptLoadOrderLoader>();
+            let handle = self.world.resource::<AssetServer>().load(path);
+            self.insert_resource(ScriptLoadOrderHandle(handle));
+        }
+
+        self.init_resource::<DisabledScripts>().add_system_to_stage(
+            stage,
+            apply_script_load_order::<T>.before(crate::systems::script_add_synchronizer::<T>),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hosts::test_host::TestScript;
+
+    #[test]
+    fn entries_match_scripts_and_mod_packs() {
+        assert!(ScriptLoadOrder::matches(
+            "scripts/enemies.lua",
+            "scripts/enemies.lua"
+        ));
+        assert!(ScriptLoadOrder::matches(
+            "/scripts/enemies.lua",
+            "scripts/enemies.lua#goblin"
+        ));
+        assert!(ScriptLoadOrder::matches(
+            "mods/goblins/",
+            "mods/goblins/ai.lua"
+        ));
+        assert!(!ScriptLoadOrder::matches(
+            "mods/goblins",
+            "mods/goblins_extra/ai.lua"
+        ));
+        assert!(!ScriptLoadOrder::matches(
+            "scripts/enemies",
+            "scripts/enemies.lua"
+        ));
+    }
+
+    #[test]
+    fn sorts_and_disables_scripts() {
+        let order: ScriptLoadOrder = toml::from_str(
+            r#"
+            order = ["mods/core", "scripts/enemies.lua"]
+            disabled = ["mods/cheats"]
+            "#,
+        )
+        .unwrap();
+
+        let mut scripts = [
+            "mods/cheats/god.lua",
+            "scripts/enemies.lua",
+            "mods/core/init.lua",
+        ]
+        .map(|name| Script::<TestScript>::new(name.to_owned(), Handle::default()));
+        let mut disabled = DisabledScripts::default();
+        disabled.disable(scripts[1].id());
+
+        order.apply(&mut scripts, &mut disabled);
+
+        let names = scripts.iter().map(|s| s.name()).collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            vec![
+                "mods/core/init.lua",
+                "scripts/enemies.lua",
+                "mods/cheats/god.lua"
+            ]
+        );
+        let enabled = scripts
+            .iter()
+            .map(|s| disabled.is_enabled(s.id()))
+            .collect::<Vec<_>>();
+        assert_eq!(enabled, vec![true, true, false]);
+    }
+}
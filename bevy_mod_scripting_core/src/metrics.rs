@@ -0,0 +1,73 @@
+//! Counting the work done keeping script contexts in sync with script components and assets
+use std::marker::PhantomData;
+
+use bevy::prelude::*;
+
+use crate::hosts::ScriptHost;
+
+/// The work done by the synchronizing systems of a host
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ScriptSyncCounts {
+    /// script collections which changed and were diffed against the contexts of their entity
+    pub collections_synced: u64,
+    /// script instances looked at while diffing collections or matching asset events
+    pub scripts_visited: u64,
+    /// asset events of the host's script asset
+    pub asset_events: u64,
+    /// contexts loaded, or inserted empty while their asset is still loading
+    pub contexts_inserted: u64,
+    /// contexts removed along with their script or entity
+    pub contexts_removed: u64,
+    /// contexts reloaded after their asset was created or modified
+    pub contexts_reloaded: u64,
+}
+
+impl std::ops::AddAssign for ScriptSyncCounts {
+    fn add_assign(&mut self, rhs: Self) {
+        self.collections_synced += rhs.collections_synced;
+        self.scripts_visited += rhs.scripts_visited;
+        self.asset_events += rhs.asset_events;
+        self.contexts_inserted += rhs.contexts_inserted;
+        self.contexts_removed += rhs.contexts_removed;
+        self.contexts_reloaded += rhs.contexts_reloaded;
+    }
+}
+
+/// Counts the work done by the systems keeping the contexts of the host `H` in sync with its script components and assets.
+///
+/// Syncing is driven by change detection and asset events, so on frames where no scripts are attached, detached or modified,
+/// no scripts are visited regardless of how many exist.
+#[derive(Resource)]
+pub struct ScriptSyncMetrics<H: ScriptHost> {
+    /// the work done so far in the current frame
+    pub current_frame: ScriptSyncCounts,
+    /// the work done in the previous frame
+    pub last_frame: ScriptSyncCounts,
+    /// the work done since the host was registered
+    pub total: ScriptSyncCounts,
+    _ph: PhantomData<H>,
+}
+
+impl<H: ScriptHost> Default for ScriptSyncMetrics<H> {
+    fn default() -> Self {
+        Self {
+            current_frame: Default::default(),
+            last_frame: Default::default(),
+            total: Default::default(),
+            _ph: Default::default(),
+        }
+    }
+}
+
+impl<H: ScriptHost> ScriptSyncMetrics<H> {
+    /// Adds the given work to the current frame and the total
+    pub fn record(&mut self, counts: ScriptSyncCounts) {
+        self.current_frame += counts;
+        self.total += counts;
+    }
+}
+
+/// Rolls the counts of the current frame over to the last frame, runs at the start of each frame
+pub fn advance_script_sync_metrics<H: ScriptHost>(mut metrics: ResMut<ScriptSyncMetrics<H>>) {
+    metrics.last_frame = std::mem::take(&mut metrics.current_frame);
+}
@@ -0,0 +1,109 @@
+//! Mirroring script events to other apps, e.g. from an authoritative server to its clients
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_event_priority::{PriorityEvent, PriorityEventWriter};
+
+/// A channel script events are mirrored through, such as a network socket.
+///
+/// Implementors decide how events are encoded, script events like `LuaEvent` are serde serializable whenever their arguments are.
+pub trait ScriptEventTransport<E>: Resource {
+    /// Sends an event to be handled at the given priority on the other end
+    fn send(&mut self, event: &E, priority: u32);
+
+    /// Returns the next event received from the other end along with its priority, if any arrived
+    fn receive(&mut self) -> Option<(E, u32)>;
+}
+
+/// Selects which script events sent with a [`MirroredEventWriter`] are mirrored, and holds them until they are sent
+#[derive(Resource)]
+pub struct ScriptEventMirror<E> {
+    filter: Box<dyn Fn(&E, u32) -> bool + Send + Sync>,
+    outgoing: Vec<(E, u32)>,
+}
+
+impl<E> ScriptEventMirror<E> {
+    /// Mirrors the events for which the filter, given the event and its priority, returns true
+    pub fn new(filter: impl Fn(&E, u32) -> bool + Send + Sync + 'static) -> Self {
+        Self {
+            filter: Box::new(filter),
+            outgoing: Vec::default(),
+        }
+    }
+
+    /// Mirrors every event
+    pub fn all() -> Self {
+        Self::new(|_, _| true)
+    }
+
+    /// The number of events waiting to be sent
+    pub fn len(&self) -> usize {
+        self.outgoing.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.outgoing.is_empty()
+    }
+}
+
+/// Sends script events locally just like a [`PriorityEventWriter`], additionally queuing the ones
+/// selected by the [`ScriptEventMirror`] to be mirrored through the transport
+#[derive(SystemParam)]
+pub struct MirroredEventWriter<'w, 's, E: PriorityEvent + Clone> {
+    events: PriorityEventWriter<'w, 's, E>,
+    mirror: ResMut<'w, ScriptEventMirror<E>>,
+}
+
+impl<'w, 's, E: PriorityEvent + Clone> MirroredEventWriter<'w, 's, E> {
+    pub fn send(&mut self, event: E, prio: u32) {
+        if (self.mirror.filter)(&event, prio) {
+            self.mirror.outgoing.push((event.clone(), prio));
+        }
+        self.events.send(event, prio);
+    }
+}
+
+/// Sends all queued mirrored events through the transport
+pub fn send_mirrored_script_events<E: PriorityEvent, T: ScriptEventTransport<E>>(
+    mut mirror: ResMut<ScriptEventMirror<E>>,
+    mut transport: ResMut<T>,
+) {
+    for (event, prio) in mirror.outgoing.drain(..) {
+        transport.send(&event, prio);
+    }
+}
+
+/// Sends every event received through the transport to the local scripts
+pub fn receive_mirrored_script_events<E: PriorityEvent, T: ScriptEventTransport<E>>(
+    mut transport: ResMut<T>,
+    mut events: PriorityEventWriter<E>,
+) {
+    while let Some((event, prio)) = transport.receive() {
+        events.send(event, prio);
+    }
+}
+
+pub trait AddScriptEventMirror {
+    /// Mirrors the script events selected by the mirror through the transport resource `T`, which must be inserted separately.
+    ///
+    /// Events received through the transport are sent to local scripts in [`CoreStage::First`],
+    /// events sent with a [`MirroredEventWriter`] are sent through the transport in [`CoreStage::Last`].
+    /// On a server authoritative setup, the server mirrors events to the clients whose transports never send anything back:
+    /// ```rust,ignore
+    /// app.insert_resource(ServerChannel::bind(addr))
+    ///     .add_script_event_mirror::<LuaEvent<NetArgs>, ServerChannel>(ScriptEventMirror::new(|e, _| e.hook_name == "on_spawn"));
+    /// ```
+    fn add_script_event_mirror<E: PriorityEvent + Clone, T: ScriptEventTransport<E>>(
+        &mut self,
+        mirror: ScriptEventMirror<E>,
+    ) -> &mut Self;
+}
+
+impl AddScriptEventMirror for App {
+    fn add_script_event_mirror<E: PriorityEvent + Clone, T: ScriptEventTransport<E>>(
+        &mut self,
+        mirror: ScriptEventMirror<E>,
+    ) -> &mut Self {
+        self.insert_resource(mirror)
+            .add_system_to_stage(CoreStage::First, receive_mirrored_script_events::<E, T>)
+            .add_system_to_stage(CoreStage::Last, send_mirrored_script_events::<E, T>)
+    }
+}
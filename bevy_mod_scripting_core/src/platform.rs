@@ -0,0 +1,80 @@
+//! Reporting which scripting features are available on the current platform
+use bevy::prelude::*;
+
+/// Describes which platform dependent scripting features are available at runtime,
+/// inserted by the [`crate::ScriptingPlugin`] and logged on startup.
+///
+/// On `wasm32` targets there is no filesystem access or process spawning, so documentation generation,
+/// teal compilation, hot reloading and safe mode markers are disabled, while scripts themselves keep working.
+#[derive(Resource, Debug, Clone, PartialEq, Eq)]
+pub struct PlatformCapabilities {
+    /// scripts can be handled off the main thread
+    pub threads: bool,
+    /// the native filesystem can be accessed, used for safe mode markers and documentation
+    pub filesystem: bool,
+    /// external tools can be run, used for documentation generation and teal compilation
+    pub processes: bool,
+    /// script assets can be hot reloaded from disk
+    pub hot_reload: bool,
+    /// the type names of all script hosts registered with the app
+    pub hosts: Vec<&'static str>,
+}
+
+impl Default for PlatformCapabilities {
+    fn default() -> Self {
+        Self::current()
+    }
+}
+
+impl PlatformCapabilities {
+    /// The capabilities of the platform this was compiled for
+    pub const fn current() -> Self {
+        let native = !cfg!(target_arch = "wasm32");
+        Self {
+            threads: native,
+            filesystem: native,
+            processes: native && !cfg!(target_os = "android") && !cfg!(target_os = "ios"),
+            hot_reload: native && !cfg!(target_os = "android"),
+            hosts: Vec::new(),
+        }
+    }
+
+    /// A human readable summary of the capabilities
+    pub fn report(&self) -> String {
+        let flag = |b: bool| if b { "yes" } else { "no" };
+        format!(
+            "Scripting platform capabilities: threads: {}, filesystem: {}, processes: {}, hot reload: {}, hosts: [{}]",
+            flag(self.threads),
+            flag(self.filesystem),
+            flag(self.processes),
+            flag(self.hot_reload),
+            self.hosts.join(", ")
+        )
+    }
+}
+
+/// Logs the capability report once all hosts are registered
+pub fn log_platform_capabilities(capabilities: Res<PlatformCapabilities>) {
+    info!("{}", capabilities.report());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{hosts::test_host::TestHost, AddScriptHost};
+
+    #[test]
+    fn reports_capabilities_and_registered_hosts() {
+        let mut app = App::new();
+        app.add_script_host::<TestHost, _>(CoreStage::PostUpdate);
+
+        let capabilities = app.world.resource::<PlatformCapabilities>();
+        assert_eq!(capabilities.hosts, vec![std::any::type_name::<TestHost>()]);
+
+        // tests only run natively
+        assert!(capabilities.threads && capabilities.filesystem);
+        let report = capabilities.report();
+        assert!(report.contains("threads: yes, filesystem: yes"), "{report}");
+        assert!(report.ends_with(&format!("hosts: [{}]", std::any::type_name::<TestHost>())));
+    }
+}
@@ -0,0 +1,89 @@
+//! Priority ranges of handler stages which can be changed at runtime
+use bevy::{prelude::*, reflect::FromReflect, utils::HashMap};
+
+use crate::{hosts::ScriptHost, systems::handle_script_events};
+
+/// The priorities `[max,min]` (inclusive) handled by a handler stage, lower priorities are handled first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect, FromReflect)]
+pub struct PriorityRange {
+    pub max: u32,
+    pub min: u32,
+}
+
+impl PriorityRange {
+    pub fn new(max: u32, min: u32) -> Self {
+        Self { max, min }
+    }
+
+    pub fn contains(&self, priority: u32) -> bool {
+        (self.max..=self.min).contains(&priority)
+    }
+}
+
+/// The priority ranges of the handler stages added with
+/// [`AddScriptHostHandler::add_named_script_handler_stage`](crate::AddScriptHostHandler::add_named_script_handler_stage),
+/// keyed by the name given to each stage.
+///
+/// Handlers read their range every time they run, so event timing can be tuned at runtime,
+/// e.g. from an inspector or console, without recompiling:
+/// ```rust,ignore
+/// world.resource_mut::<ScriptHandlerPriorities>().set("lua_post_physics", PriorityRange::new(2, 10));
+/// ```
+/// Handlers whose name has no range, or whose `max` is greater than their `min`, do not handle any events.
+#[derive(Resource, Debug, Default, Clone, Reflect, FromReflect)]
+#[reflect(Resource)]
+pub struct ScriptHandlerPriorities {
+    pub ranges: HashMap<String, PriorityRange>,
+}
+
+impl ScriptHandlerPriorities {
+    pub fn get(&self, handler: &str) -> Option<PriorityRange> {
+        self.ranges.get(handler).copied()
+    }
+
+    /// Changes the range of the handler with the given name, taking effect the next time it runs
+    pub fn set(&mut self, handler: impl Into<String>, range: PriorityRange) {
+        self.ranges.insert(handler.into(), range);
+    }
+
+    /// The name of the handler which handles the given priority, if any
+    pub fn handler_for(&self, priority: u32) -> Option<&str> {
+        self.ranges
+            .iter()
+            .find(|(_, range)| range.contains(priority))
+            .map(|(name, _)| name.as_str())
+    }
+}
+
+/// Lets the host `H` handle all script events with priorities in the current range of the named handler
+pub fn named_script_event_handler<H: ScriptHost>(
+    handler: String,
+) -> impl FnMut(&mut World) + Send + Sync + 'static {
+    move |world: &mut World| {
+        let range = world
+            .get_resource::<ScriptHandlerPriorities>()
+            .and_then(|p| p.get(&handler));
+        if let Some(range) = range.filter(|r| r.max <= r.min) {
+            handle_script_events::<H>(world, range.max, range.min);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_the_handler_of_a_priority() {
+        let mut priorities = ScriptHandlerPriorities::default();
+        priorities.set("pre_physics", PriorityRange::new(0, 1));
+        priorities.set("post_physics", PriorityRange::new(2, 4));
+
+        assert_eq!(priorities.handler_for(1), Some("pre_physics"));
+        assert_eq!(priorities.handler_for(4), Some("post_physics"));
+        assert_eq!(priorities.handler_for(5), None);
+
+        priorities.set("post_physics", PriorityRange::new(2, 10));
+        assert_eq!(priorities.handler_for(5), Some("post_physics"));
+    }
+}
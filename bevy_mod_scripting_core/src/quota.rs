@@ -0,0 +1,177 @@
+//! Per-frame call quotas for expensive script API functions
+use bevy::{prelude::*, utils::HashMap};
+
+use crate::{error::ScriptError, hosts::ScriptId};
+
+/// Limits how many times each script may call expensive API functions per frame,
+/// bounding the damage a poorly written script can do to frame time.
+///
+/// API functions charge a call with [`ScriptQuotas::charge`] under a name such as [`ScriptQuotas::QUERY`],
+/// which fails with [`ScriptError::QuotaExceeded`] once the script used up its quota for that function this frame.
+/// Functions without a limit are never refused, calls are counted for the script currently handling events only.
+/// ```rust,ignore
+/// app.insert_resource(ScriptQuotas::default().with_limit(ScriptQuotas::SPAWN, 100));
+/// ```
+#[derive(Resource, Debug, Default)]
+pub struct ScriptQuotas {
+    limits: HashMap<&'static str, u32>,
+    calls: HashMap<(ScriptId, &'static str), u32>,
+    exceeded: HashMap<(ScriptId, &'static str), u64>,
+    current: Option<ScriptId>,
+}
+
+impl ScriptQuotas {
+    /// Entity queries
+    pub const QUERY: &'static str = "query";
+    /// Entity and scene spawns
+    pub const SPAWN: &'static str = "spawn";
+    /// Raycasts and other spatial queries
+    pub const RAYCAST: &'static str = "raycast";
+
+    /// Limits the calls to the given API function to `per_frame` per script
+    pub fn with_limit(mut self, api: &'static str, per_frame: u32) -> Self {
+        self.set_limit(api, Some(per_frame));
+        self
+    }
+
+    /// Sets or removes the per frame limit of the given API function
+    pub fn set_limit(&mut self, api: &'static str, per_frame: Option<u32>) {
+        match per_frame {
+            Some(limit) => self.limits.insert(api, limit),
+            None => self.limits.remove(api),
+        };
+    }
+
+    pub fn limit(&self, api: &str) -> Option<u32> {
+        self.limits.get(api).copied()
+    }
+
+    /// The script currently handling events, if any
+    pub fn current_script(&self) -> Option<ScriptId> {
+        self.current
+    }
+
+    /// Counts a call to the given API function by the current script,
+    /// returns an error without counting it if the script used up its quota this frame
+    pub fn charge(&mut self, api: &'static str) -> Result<(), ScriptError> {
+        let (sid, limit) = match (self.current, self.limits.get(api)) {
+            (Some(sid), Some(limit)) => (sid, *limit),
+            _ => return Ok(()),
+        };
+
+        let calls = self.calls.entry((sid, api)).or_default();
+        if *calls >= limit {
+            *self.exceeded.entry((sid, api)).or_default() += 1;
+            return Err(ScriptError::QuotaExceeded {
+                api: api.to_owned(),
+                limit,
+            });
+        }
+        *calls += 1;
+        Ok(())
+    }
+
+    /// The number of calls to the given API function the script made this frame
+    pub fn calls(&self, script_id: ScriptId, api: &'static str) -> u32 {
+        self.calls
+            .get(&(script_id, api))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// The number of calls to the given API function refused to the script since the app started
+    pub fn times_exceeded(&self, script_id: ScriptId, api: &'static str) -> u64 {
+        self.exceeded
+            .get(&(script_id, api))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn enter(&mut self, script_id: ScriptId) {
+        self.current = Some(script_id);
+    }
+
+    pub(crate) fn exit(&mut self) {
+        self.current = None;
+    }
+}
+
+/// Starts a new quota period for every script
+pub fn reset_script_quotas(quotas: Option<ResMut<ScriptQuotas>>) {
+    if let Some(mut quotas) = quotas {
+        quotas.calls.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::{IntoSystem, System};
+
+    use super::*;
+    use crate::{
+        hosts::{
+            test_host::{context, send_event, world_with_scripts, TestEvent, TestHost},
+            Recipients,
+        },
+        systems::handle_script_events,
+    };
+
+    #[test]
+    fn refuses_calls_over_the_limit_of_the_current_script() {
+        let mut quotas = ScriptQuotas::default().with_limit(ScriptQuotas::SPAWN, 1);
+
+        // calls outside of scripts and to unlimited functions are never refused
+        quotas.charge(ScriptQuotas::SPAWN).unwrap();
+        quotas.enter(0);
+        quotas.charge(ScriptQuotas::QUERY).unwrap();
+        quotas.charge(ScriptQuotas::QUERY).unwrap();
+
+        quotas.charge(ScriptQuotas::SPAWN).unwrap();
+        assert!(matches!(
+            quotas.charge(ScriptQuotas::SPAWN),
+            Err(ScriptError::QuotaExceeded { limit: 1, .. })
+        ));
+        assert_eq!(quotas.calls(0, ScriptQuotas::SPAWN), 1);
+        assert_eq!(quotas.times_exceeded(0, ScriptQuotas::SPAWN), 1);
+
+        // quotas are per script
+        quotas.enter(1);
+        quotas.charge(ScriptQuotas::SPAWN).unwrap();
+        quotas.exit();
+        assert_eq!(quotas.current_script(), None);
+
+        quotas.set_limit(ScriptQuotas::SPAWN, None);
+        assert_eq!(quotas.limit(ScriptQuotas::SPAWN), None);
+    }
+
+    #[test]
+    fn scripts_are_charged_while_handling_events() {
+        let entity = Entity::from_raw(0);
+        let mut world = world_with_scripts(&[(0, entity), (1, entity)]);
+        world.insert_resource(ScriptQuotas::default().with_limit(ScriptQuotas::QUERY, 1));
+        let update = TestEvent {
+            hook: "on_update",
+            recipients: Recipients::All,
+        };
+
+        for _ in 0..2 {
+            send_event(&mut world, update.clone(), 0);
+        }
+        handle_script_events::<TestHost>(&mut world, 0, 10);
+
+        for sid in [0, 1] {
+            assert_eq!(context(&world, sid).handled, vec!["on_update"]);
+            assert_eq!(context(&world, sid).refused, 1);
+        }
+        let quotas = world.resource::<ScriptQuotas>();
+        assert_eq!(quotas.times_exceeded(1, ScriptQuotas::QUERY), 1);
+        assert_eq!(quotas.current_script(), None);
+
+        let mut system = IntoSystem::into_system(reset_script_quotas);
+        system.initialize(&mut world);
+        system.run((), &mut world);
+        send_event(&mut world, update, 0);
+        handle_script_events::<TestHost>(&mut world, 0, 10);
+        assert_eq!(context(&world, 0).handled, vec!["on_update", "on_update"]);
+    }
+}
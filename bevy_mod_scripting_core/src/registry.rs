@@ -0,0 +1,157 @@
+//! Enumerating the scripts running on every host at once
+use bevy::{prelude::*, utils::HashMap};
+
+use crate::{
+    hosts::{ScriptContexts, ScriptHost, ScriptId},
+    status::ScriptLoadState,
+};
+
+/// A script attached to an entity on one of the registered hosts
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegisteredScript {
+    pub sid: ScriptId,
+    /// the name of the script, usually its asset path
+    pub name: String,
+    /// the language of the host running the script, e.g. `Lua` for the `LuaScriptHost`
+    pub language: String,
+    /// the entity the script is attached to
+    pub entity: Entity,
+    pub load_state: ScriptLoadState,
+}
+
+/// Every script attached to an entity across all hosts added with [`AddScriptHost::add_script_host`](crate::AddScriptHost::add_script_host).
+///
+/// Kept up to date from the contexts of each host at the end of every frame by [`sync_script_registry`].
+#[derive(Resource, Debug, Default)]
+pub struct ScriptRegistry {
+    scripts: HashMap<ScriptId, RegisteredScript>,
+}
+
+impl ScriptRegistry {
+    pub fn get(&self, script_id: ScriptId) -> Option<&RegisteredScript> {
+        self.scripts.get(&script_id)
+    }
+
+    /// All scripts in no particular order
+    pub fn iter(&self) -> impl Iterator<Item = &RegisteredScript> {
+        self.scripts.values()
+    }
+
+    /// The scripts attached to the given entity, on any host
+    pub fn scripts_for_entity(&self, entity: Entity) -> impl Iterator<Item = &RegisteredScript> {
+        self.iter().filter(move |s| s.entity == entity)
+    }
+
+    /// The instances of the script with the given name, e.g. `ai.lua`, the same script can be attached to many entities.
+    ///
+    /// Names are matched in full or by their file name, so `ai.lua` also matches `scripts/enemies/ai.lua`
+    pub fn by_name<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a RegisteredScript> {
+        self.iter().filter(move |s| {
+            s.name == name
+                || s.name
+                    .rsplit_once('/')
+                    .map_or(false, |(_, file)| file == name)
+        })
+    }
+
+    /// The scripts run by the host of the given language
+    pub fn by_language<'a>(
+        &'a self,
+        language: &'a str,
+    ) -> impl Iterator<Item = &'a RegisteredScript> {
+        self.iter().filter(move |s| s.language == language)
+    }
+
+    pub fn len(&self) -> usize {
+        self.scripts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.scripts.is_empty()
+    }
+}
+
+/// The language of the host `H` as listed in the [`ScriptRegistry`], its type name without the `ScriptHost` suffix,
+/// e.g. `Lua` for `LuaScriptHost<MyArgs>`
+pub fn host_language<H: ScriptHost>() -> String {
+    let name = std::any::type_name::<H>();
+    let name = name.split_once('<').map_or(name, |(name, _)| name);
+    let name = name.rsplit_once("::").map_or(name, |(_, name)| name);
+    name.strip_suffix("ScriptHost").unwrap_or(name).to_owned()
+}
+
+/// Replaces the entries of the host `H` in the [`ScriptRegistry`] whenever its contexts changed
+pub fn sync_script_registry<H: ScriptHost>(
+    contexts: Res<ScriptContexts<H::ScriptContext>>,
+    mut registry: ResMut<ScriptRegistry>,
+) {
+    if !contexts.is_changed() {
+        return;
+    }
+
+    let language = host_language::<H>();
+    registry.scripts.retain(|sid, script| {
+        script.language != language || contexts.context_entities.contains_key(sid)
+    });
+
+    for (sid, (entity, ctx, name)) in contexts.context_entities.iter() {
+        let load_state = if ctx.is_some() {
+            ScriptLoadState::Loaded
+        } else if contexts.has_failed(*sid) {
+            ScriptLoadState::Failed
+        } else {
+            ScriptLoadState::Pending
+        };
+        registry.scripts.insert(
+            *sid,
+            RegisteredScript {
+                sid: *sid,
+                name: name.clone(),
+                language: language.clone(),
+                entity: *entity,
+                load_state,
+            },
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_scripts_by_file_name() {
+        let mut registry = ScriptRegistry::default();
+        for (sid, name) in [
+            (0, "scripts/enemies/ai.lua"),
+            (1, "ai.lua"),
+            (2, "scripts/ui.lua"),
+        ] {
+            registry.scripts.insert(
+                sid,
+                RegisteredScript {
+                    sid,
+                    name: name.to_owned(),
+                    language: "Lua".to_owned(),
+                    entity: Entity::from_raw(sid as u32),
+                    load_state: ScriptLoadState::Loaded,
+                },
+            );
+        }
+
+        let mut found = registry
+            .by_name("ai.lua")
+            .map(|s| s.sid)
+            .collect::<Vec<_>>();
+        found.sort_unstable();
+        assert_eq!(found, vec![0, 1]);
+        assert_eq!(registry.by_name("enemies/ai.lua").count(), 0);
+        assert_eq!(
+            registry
+                .scripts_for_entity(Entity::from_raw(2))
+                .map(|s| s.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["scripts/ui.lua"]
+        );
+    }
+}
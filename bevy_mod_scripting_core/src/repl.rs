@@ -0,0 +1,270 @@
+//! Interactive evaluation of script snippets, i.e. for in-game consoles
+use std::collections::VecDeque;
+
+use bevy::prelude::{Entity, Resource, World};
+
+use crate::{
+    error::ScriptError,
+    hosts::{next_script_id, APIProviders, ScriptData, ScriptHost, ScriptId},
+};
+
+/// The name given to the script context of a REPL
+pub const REPL_SCRIPT_NAME: &str = "repl";
+
+/// The outcome of evaluating a snippet
+#[derive(Debug)]
+pub enum ReplEval {
+    /// the snippet is not a complete chunk of code yet, more lines are expected
+    Incomplete,
+    /// the snippet was run, carrying its pretty-printed return value if it returned anything
+    Done(Result<Option<String>, ScriptError>),
+}
+
+/// Script hosts which can evaluate snippets of code in a persistent context
+pub trait ReplHost: ScriptHost {
+    /// Evaluates the snippet in the given context, API providers have not yet been set up for this run
+    fn eval_snippet(
+        &mut self,
+        world: &mut World,
+        code: &str,
+        script_data: &ScriptData,
+        ctx: &mut Self::ScriptContext,
+        providers: &mut APIProviders<Self>,
+    ) -> ReplEval;
+}
+
+/// The result of a complete snippet ran by a [`ScriptRepl`]
+#[derive(Debug)]
+pub struct ReplOutput {
+    /// the full snippet, possibly spanning multiple lines
+    pub input: String,
+    /// the pretty-printed return value if any, or the error message
+    pub result: Result<Option<String>, String>,
+}
+
+/// A read-eval-print loop for the script host `H`, lines submitted here are evaluated by [`script_repl_system`]
+/// in a context which persists between snippets, so variables set by one snippet can be read by the next one.
+///
+/// Lines are accumulated until they form a complete chunk of code, which allows for multi-line input.
+#[derive(Resource)]
+pub struct ScriptRepl<H: ScriptHost> {
+    /// the number of snippets kept in the history
+    pub max_history: usize,
+    lines: VecDeque<String>,
+    buffer: String,
+    history: VecDeque<String>,
+    output: Vec<ReplOutput>,
+    context: Option<(ScriptId, Entity, H::ScriptContext)>,
+}
+
+impl<H: ScriptHost> Default for ScriptRepl<H> {
+    fn default() -> Self {
+        Self {
+            max_history: 100,
+            lines: Default::default(),
+            buffer: Default::default(),
+            history: Default::default(),
+            output: Default::default(),
+            context: None,
+        }
+    }
+}
+
+impl<H: ScriptHost> ScriptRepl<H> {
+    /// Queues a line of input for evaluation
+    pub fn submit(&mut self, line: impl Into<String>) {
+        self.lines.push_back(line.into());
+    }
+
+    /// True if the previous lines did not form a complete snippet and more input is expected
+    pub fn awaiting_more(&self) -> bool {
+        !self.buffer.is_empty()
+    }
+
+    /// Discards the incomplete snippet, if any
+    pub fn cancel(&mut self) {
+        self.buffer.clear();
+    }
+
+    /// The previously evaluated snippets, most recent last
+    pub fn history(&self) -> impl DoubleEndedIterator<Item = &str> {
+        self.history.iter().map(String::as_str)
+    }
+
+    /// Takes the results of all snippets evaluated since the last call
+    pub fn drain_output(&mut self) -> impl Iterator<Item = ReplOutput> + '_ {
+        self.output.drain(..)
+    }
+
+    /// Drops the REPL context, the next snippet will be evaluated in a fresh one.
+    /// The entity of the old context is left alive.
+    pub fn reset(&mut self) {
+        self.buffer.clear();
+        self.context = None;
+    }
+
+    /// The entity the REPL context runs on, if the context was created
+    pub fn entity(&self) -> Option<Entity> {
+        self.context.as_ref().map(|(_, e, _)| *e)
+    }
+
+    fn push_history(&mut self, snippet: String) {
+        self.history.push_back(snippet);
+        while self.history.len() > self.max_history {
+            self.history.pop_front();
+        }
+    }
+}
+
+/// Evaluates lines submitted to the [`ScriptRepl`] of the host `H`.
+///
+/// The REPL context is created on first use, on its own empty entity, with all API providers of the host attached.
+pub fn script_repl_system<H: ReplHost>(world: &mut World) {
+    // avoid triggering change detection when there is nothing to evaluate
+    if world
+        .get_resource::<ScriptRepl<H>>()
+        .map_or(true, |repl| repl.lines.is_empty())
+    {
+        return;
+    }
+
+    let mut repl: ScriptRepl<H> = world.remove_resource().unwrap();
+
+    let mut host: H = world.remove_resource().unwrap();
+    let mut providers: APIProviders<H> = world.remove_resource().unwrap();
+
+    while let Some(line) = repl.lines.pop_front() {
+        if !repl.buffer.is_empty() {
+            repl.buffer.push('\n');
+        }
+        repl.buffer.push_str(&line);
+
+        if repl.context.is_none() {
+            let sid = next_script_id();
+            let entity = world.spawn(()).id();
+            let script_data = ScriptData {
+                sid,
+                entity,
+                name: REPL_SCRIPT_NAME,
+                groups: &[],
+            };
+
+            let ctx = host
+                .load_script(&[], &script_data, &mut providers)
+                .and_then(|mut ctx| {
+                    host.setup_script(&script_data, &mut ctx, &mut providers)
+                        .map(|_| ctx)
+                });
+
+            match ctx {
+                Ok(ctx) => repl.context = Some((sid, entity, ctx)),
+                Err(e) => {
+                    world.despawn(entity);
+                    let input = std::mem::take(&mut repl.buffer);
+                    repl.output.push(ReplOutput {
+                        input,
+                        result: Err(e.to_string()),
+                    });
+                    continue;
+                }
+            }
+        }
+
+        let (sid, entity, ctx) = repl.context.as_mut().unwrap();
+        let script_data = ScriptData {
+            sid: *sid,
+            entity: *entity,
+            name: REPL_SCRIPT_NAME,
+            groups: &[],
+        };
+
+        match host.eval_snippet(world, &repl.buffer, &script_data, ctx, &mut providers) {
+            ReplEval::Incomplete => continue,
+            ReplEval::Done(result) => {
+                let input = std::mem::take(&mut repl.buffer);
+                repl.push_history(input.clone());
+                repl.output.push(ReplOutput {
+                    input,
+                    result: result.map_err(|e| e.to_string()),
+                });
+            }
+        }
+    }
+
+    world.insert_resource(repl);
+    world.insert_resource(host);
+    world.insert_resource(providers);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hosts::test_host::TestHost;
+
+    /// Snippets are complete once their parentheses are balanced, and evaluate to their own code
+    impl ReplHost for TestHost {
+        fn eval_snippet(
+            &mut self,
+            _world: &mut World,
+            code: &str,
+            _script_data: &ScriptData,
+            ctx: &mut Self::ScriptContext,
+            _providers: &mut APIProviders<Self>,
+        ) -> ReplEval {
+            if code.matches('(').count() > code.matches(')').count() {
+                return ReplEval::Incomplete;
+            }
+            ctx.handled.push("eval");
+            ReplEval::Done(Ok(Some(code.to_owned())))
+        }
+    }
+
+    fn repl_world() -> World {
+        let mut world = World::new();
+        world.init_resource::<TestHost>();
+        world.init_resource::<APIProviders<TestHost>>();
+        world.init_resource::<ScriptRepl<TestHost>>();
+        world
+    }
+
+    #[test]
+    fn accumulates_lines_until_snippets_are_complete() {
+        let mut world = repl_world();
+        let mut repl = world.resource_mut::<ScriptRepl<TestHost>>();
+        repl.submit("f(");
+        repl.submit("1)");
+        repl.submit("g(");
+
+        script_repl_system::<TestHost>(&mut world);
+
+        let mut repl = world.resource_mut::<ScriptRepl<TestHost>>();
+        assert!(repl.awaiting_more());
+        let output: Vec<_> = repl.drain_output().collect();
+        assert_eq!(output.len(), 1);
+        assert_eq!(output[0].input, "f(\n1)");
+        assert_eq!(output[0].result, Ok(Some("f(\n1)".to_owned())));
+
+        repl.cancel();
+        assert!(!repl.awaiting_more());
+        let entity = repl.entity().expect("the context was created");
+        assert!(world.get_entity(entity).is_some());
+    }
+
+    #[test]
+    fn keeps_the_most_recent_snippets_in_the_history() {
+        let mut world = repl_world();
+        let mut repl = world.resource_mut::<ScriptRepl<TestHost>>();
+        repl.max_history = 2;
+        for snippet in ["a", "b", "c"] {
+            repl.submit(snippet);
+        }
+
+        script_repl_system::<TestHost>(&mut world);
+
+        let repl = world.resource::<ScriptRepl<TestHost>>();
+        assert_eq!(repl.history().collect::<Vec<_>>(), vec!["b", "c"]);
+        // every snippet ran in the same context
+        let (_, _, ctx) = repl.context.as_ref().unwrap();
+        assert_eq!(ctx.handled, vec!["eval"; 3]);
+    }
+}
@@ -0,0 +1,124 @@
+//! Keeping script assets loaded after the last script using them is detached
+use std::time::Duration;
+
+use bevy::{
+    asset::{Asset, HandleId},
+    prelude::*,
+    utils::HashMap,
+};
+
+use crate::hosts::ScriptCollection;
+
+/// How long script assets stay loaded once no live entity has a script using them
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScriptHandlePolicy {
+    /// every script asset stays loaded once attached, so attaching it again never reloads it
+    Strong,
+    /// script assets are unloaded once they have not been attached to any live entity for the given duration,
+    /// keeping memory bounded in games streaming scripted levels in and out.
+    ///
+    /// Loading a script through the asset server again after it was unloaded loads it anew, as if it was never loaded.
+    Weak { unload_after: Duration },
+}
+
+/// Keeps the script assets of type `A` loaded after the last script using them is detached, according to its policy.
+///
+/// Without this resource, script assets are unloaded as soon as no [`ScriptCollection`] holds a handle to them,
+/// compiled contexts are always dropped as soon as their script is detached.
+/// ```rust,ignore
+/// app.insert_resource(ScriptRetention::<LuaFile>::new(ScriptHandlePolicy::Weak {
+///     unload_after: Duration::from_secs(30),
+/// }));
+/// ```
+#[derive(Resource)]
+pub struct ScriptRetention<A: Asset> {
+    pub policy: ScriptHandlePolicy,
+    /// the retained assets alongside the time at which they were last attached
+    retained: HashMap<HandleId, (Handle<A>, Duration)>,
+}
+
+impl<A: Asset> ScriptRetention<A> {
+    pub fn new(policy: ScriptHandlePolicy) -> Self {
+        Self {
+            policy,
+            retained: Default::default(),
+        }
+    }
+
+    /// The number of script assets currently kept loaded, attached or not
+    pub fn len(&self) -> usize {
+        self.retained.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.retained.is_empty()
+    }
+
+    pub fn is_retained(&self, handle: &Handle<A>) -> bool {
+        self.retained.contains_key(&handle.id())
+    }
+
+    /// Releases every retained asset, which is unloaded unless still attached
+    pub fn clear(&mut self) {
+        self.retained.clear();
+    }
+
+    /// Retains the given attached assets, and releases those detached for longer than the policy allows
+    fn update<'a>(&mut self, attached: impl Iterator<Item = &'a Handle<A>>, now: Duration) {
+        for handle in attached {
+            self.retained
+                .entry(handle.id())
+                .and_modify(|(_, last_attached)| *last_attached = now)
+                .or_insert_with(|| (handle.clone(), now));
+        }
+
+        if let ScriptHandlePolicy::Weak { unload_after } = self.policy {
+            self.retained
+                .retain(|_, (_, last_attached)| now.saturating_sub(*last_attached) <= unload_after);
+        }
+    }
+}
+
+/// Updates the [`ScriptRetention`] of the asset `A` from the scripts attached to live entities
+pub fn retain_script_assets<A: Asset>(
+    time: Res<Time>,
+    retention: Option<ResMut<ScriptRetention<A>>>,
+    collections: Query<&ScriptCollection<A>>,
+) {
+    if let Some(mut retention) = retention {
+        let attached = collections
+            .iter()
+            .flat_map(|c| c.scripts.iter().map(|s| s.handle()));
+        retention.update(attached, time.elapsed());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::reflect::TypeUuid;
+
+    use super::*;
+
+    #[derive(TypeUuid)]
+    #[uuid = "6c1f8e2a-4d3b-4f7a-9b0e-2a5c7d9e1f34"]
+    struct TestScript;
+
+    #[test]
+    fn releases_assets_detached_for_too_long() {
+        let a = Handle::<TestScript>::weak(HandleId::random::<TestScript>());
+        let b = Handle::<TestScript>::weak(HandleId::random::<TestScript>());
+        let mut retention = ScriptRetention::new(ScriptHandlePolicy::Weak {
+            unload_after: Duration::from_secs(10),
+        });
+
+        retention.update([&a, &b].into_iter(), Duration::from_secs(0));
+        retention.update([&a].into_iter(), Duration::from_secs(5));
+        retention.update(std::iter::empty(), Duration::from_secs(12));
+        assert!(retention.is_retained(&a));
+        assert!(!retention.is_retained(&b));
+
+        retention.policy = ScriptHandlePolicy::Strong;
+        retention.update(std::iter::empty(), Duration::from_secs(100));
+        assert_eq!(retention.len(), 1);
+    }
+}
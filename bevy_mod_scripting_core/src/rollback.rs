@@ -0,0 +1,140 @@
+//! Snapshotting and restoring script state for rollback netcode
+use std::collections::{BTreeMap, HashMap};
+
+use bevy::prelude::*;
+
+use crate::{
+    error::ScriptError,
+    hosts::{ScriptContexts, ScriptHost, ScriptId},
+};
+
+/// Script hosts whose script contexts can be snapshotted and later restored,
+/// so that scripted gameplay logic participates in rollback (e.g. with GGRS).
+///
+/// A rollback setup snapshots every frame it may need to return to and restores before resimulating:
+/// ```rust,ignore
+/// fn save(world: &mut World, frame: u64) {
+///     LuaScriptHost::<()>::snapshot(world, frame).expect("Could not snapshot scripts");
+/// }
+///
+/// fn load(world: &mut World, frame: u64) {
+///     LuaScriptHost::<()>::restore(world, frame).expect("Could not restore scripts");
+/// }
+/// ```
+pub trait RollbackHost: ScriptHost {
+    /// The saved state of a single script context
+    type Snapshot: Send + Sync + 'static;
+
+    /// Saves the state of the given context
+    fn snapshot_context(
+        &self,
+        ctx: &mut Self::ScriptContext,
+    ) -> Result<Self::Snapshot, ScriptError>;
+
+    /// Returns the given context to the saved state
+    fn restore_context(
+        &self,
+        ctx: &mut Self::ScriptContext,
+        snapshot: &Self::Snapshot,
+    ) -> Result<(), ScriptError>;
+
+    /// Snapshots every loaded script of this host under the given frame, replacing any snapshot previously taken at that frame
+    fn snapshot(world: &mut World, frame: u64) -> Result<(), ScriptError> {
+        let snapshots = world.resource_scope(
+            |world, mut contexts: Mut<ScriptContexts<Self::ScriptContext>>| {
+                let host = world.resource::<Self>();
+                contexts
+                    .context_entities
+                    .iter_mut()
+                    .filter_map(|(sid, (_, ctx, _))| ctx.as_mut().map(|ctx| (*sid, ctx)))
+                    .map(|(sid, ctx)| host.snapshot_context(ctx).map(|s| (sid, s)))
+                    .collect::<Result<HashMap<_, _>, _>>()
+            },
+        )?;
+
+        world
+            .get_resource_or_insert_with(ScriptSnapshots::<Self>::default)
+            .insert(frame, snapshots);
+        Ok(())
+    }
+
+    /// Restores every script of this host to its state at the given frame, snapshots of later frames are discarded.
+    ///
+    /// Scripts loaded after the frame keep their current state.
+    fn restore(world: &mut World, frame: u64) -> Result<(), ScriptError> {
+        let mut snapshots = world
+            .remove_resource::<ScriptSnapshots<Self>>()
+            .unwrap_or_default();
+
+        let result = match snapshots.frames.get(&frame) {
+            Some(saved) => world.resource_scope(
+                |world, mut contexts: Mut<ScriptContexts<Self::ScriptContext>>| {
+                    let host = world.resource::<Self>();
+                    for (sid, (_, ctx, _)) in contexts.context_entities.iter_mut() {
+                        if let (Some(ctx), Some(snapshot)) = (ctx.as_mut(), saved.get(sid)) {
+                            host.restore_context(ctx, snapshot)?;
+                        }
+                    }
+                    Ok(())
+                },
+            ),
+            None => Err(ScriptError::Other(format!(
+                "No script snapshot was taken at frame {frame}"
+            ))),
+        };
+
+        if result.is_ok() {
+            // the frames after this one will be resimulated
+            snapshots.frames.split_off(&(frame + 1));
+        }
+        world.insert_resource(snapshots);
+        result
+    }
+}
+
+/// Script snapshots taken with [`RollbackHost::snapshot`], keyed by frame.
+///
+/// Only the most recent `max_frames` frames are kept, 16 by default.
+#[derive(Resource)]
+pub struct ScriptSnapshots<H: RollbackHost> {
+    frames: BTreeMap<u64, HashMap<ScriptId, H::Snapshot>>,
+    max_frames: usize,
+}
+
+impl<H: RollbackHost> Default for ScriptSnapshots<H> {
+    fn default() -> Self {
+        Self {
+            frames: Default::default(),
+            max_frames: 16,
+        }
+    }
+}
+
+impl<H: RollbackHost> ScriptSnapshots<H> {
+    pub fn with_max_frames(mut self, max_frames: usize) -> Self {
+        self.max_frames = max_frames;
+        self
+    }
+
+    /// Returns true if a snapshot was taken at the given frame
+    pub fn has_frame(&self, frame: u64) -> bool {
+        self.frames.contains_key(&frame)
+    }
+
+    /// The frames snapshots are kept for, oldest first
+    pub fn frames(&self) -> impl Iterator<Item = u64> + '_ {
+        self.frames.keys().copied()
+    }
+
+    pub fn clear(&mut self) {
+        self.frames.clear();
+    }
+
+    fn insert(&mut self, frame: u64, snapshots: HashMap<ScriptId, H::Snapshot>) {
+        self.frames.insert(frame, snapshots);
+        while self.frames.len() > self.max_frames {
+            let oldest = *self.frames.keys().next().unwrap();
+            self.frames.remove(&oldest);
+        }
+    }
+}
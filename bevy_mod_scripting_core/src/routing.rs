@@ -0,0 +1,105 @@
+//! Routing hooks to script functions of a different name
+use bevy::{prelude::Resource, utils::HashMap};
+use serde::Deserialize;
+
+use crate::{hook::HookName, load_order::ScriptLoadOrder};
+
+/// Maps hooks to the functions handling them per script, so that a mod can implement a game's `on_update` hook
+/// under its own naming, or handle several hooks with the same function.
+///
+/// Entries refer either to a single script by name or to a whole mod pack by directory, the same way
+/// [`ScriptLoadOrder`] entries do. If several entries matching a script route the same hook, the most specific one wins.
+/// Hooks which are not routed call the function of the same name as always.
+///
+/// Routes can be changed at any time and apply to the next events handled, they can also be declared in mod metadata:
+/// ```toml
+/// [routes."mods/goblins"]
+/// on_update = "tick"
+/// on_level_start = "reset"
+/// on_respawn = "reset"
+/// ```
+#[derive(Resource, Debug, Clone, Default, Deserialize)]
+pub struct ScriptHookRoutes {
+    #[serde(default)]
+    pub routes: HashMap<String, HashMap<HookName, HookName>>,
+}
+
+impl ScriptHookRoutes {
+    /// Makes the hook call the given function in the scripts matched by the entry
+    pub fn route(
+        &mut self,
+        entry: impl Into<String>,
+        hook: impl Into<HookName>,
+        function: impl Into<HookName>,
+    ) -> &mut Self {
+        self.routes
+            .entry(entry.into())
+            .or_default()
+            .insert(hook.into(), function.into());
+        self
+    }
+
+    /// Makes the hook call the function of the same name again in the scripts matched by the entry
+    pub fn unroute(&mut self, entry: &str, hook: impl Into<HookName>) {
+        if let Some(routes) = self.routes.get_mut(entry) {
+            routes.remove(&hook.into());
+            if routes.is_empty() {
+                self.routes.remove(entry);
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.routes.is_empty()
+    }
+
+    /// The function handling the hook in the given script
+    pub fn function_for(&self, script_name: &str, hook: HookName) -> HookName {
+        self.routes
+            .iter()
+            .filter(|(entry, _)| ScriptLoadOrder::matches(entry, script_name))
+            .filter_map(|(entry, routes)| Some((entry, *routes.get(&hook)?)))
+            .max_by_key(|(entry, _)| entry.trim_matches('/').len())
+            .map(|(_, function)| function)
+            .unwrap_or(hook)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn most_specific_entry_routes_hooks() {
+        let mut routes = ScriptHookRoutes::default();
+        routes
+            .route("mods/goblins", "on_update", "tick")
+            .route("mods/goblins/boss.lua", "on_update", "boss_tick")
+            .route("mods/goblins", "on_respawn", "reset")
+            .route("mods/goblins", "on_level_start", "reset");
+
+        let on_update = HookName::new("on_update");
+        assert_eq!(
+            routes.function_for("mods/goblins/grunt.lua", on_update),
+            "tick"
+        );
+        assert_eq!(
+            routes.function_for("mods/goblins/boss.lua", on_update),
+            "boss_tick"
+        );
+        assert_eq!(
+            routes.function_for("mods/orcs/grunt.lua", on_update),
+            "on_update"
+        );
+        assert_eq!(
+            routes.function_for("mods/goblins/grunt.lua", HookName::new("on_respawn")),
+            routes.function_for("mods/goblins/grunt.lua", HookName::new("on_level_start"))
+        );
+
+        routes.unroute("mods/goblins/boss.lua", "on_update");
+        assert_eq!(
+            routes.function_for("mods/goblins/boss.lua", on_update),
+            "tick"
+        );
+    }
+}
@@ -0,0 +1,232 @@
+//! Safe mode startup, disabling scripts which crashed the previous run
+use bevy::{app::AppExit, prelude::*};
+use std::{
+    fs,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+};
+
+use crate::{platform::PlatformCapabilities, status::DisabledScripts};
+
+static TRACKING: AtomicBool = AtomicBool::new(false);
+static EXECUTING_SCRIPT: Mutex<Option<String>> = Mutex::new(None);
+
+/// Marks a script as executing until dropped, so that a crash can be attributed to it
+pub(crate) struct ExecutingScript;
+
+impl ExecutingScript {
+    pub(crate) fn enter(name: &str) -> Option<Self> {
+        if !TRACKING.load(Ordering::Relaxed) {
+            return None;
+        }
+        *EXECUTING_SCRIPT.lock().unwrap_or_else(|e| e.into_inner()) = Some(name.to_owned());
+        Some(Self)
+    }
+}
+
+impl Drop for ExecutingScript {
+    fn drop(&mut self) {
+        // keep the name around while panicking, the panic hook has already recorded it by now
+        if !std::thread::panicking() {
+            *EXECUTING_SCRIPT.lock().unwrap_or_else(|e| e.into_inner()) = None;
+        }
+    }
+}
+
+/// Sent once at startup if the previous run did not shut down cleanly
+#[derive(Debug, Clone)]
+pub struct SafeModeActivated {
+    /// the name of the script which was executing when the previous run crashed, if known
+    pub crashed_script: Option<String>,
+    /// true if every script was disabled
+    pub all_disabled: bool,
+}
+
+/// Persists a marker file while the app is running, which is removed on clean shutdown.
+///
+/// If the marker is still present on startup, the previous run crashed: the script which was executing
+/// at the time is disabled (see [`DisabledScripts`]), or all scripts if the crash could not be attributed to one
+/// or `disable_all` is set, and a [`SafeModeActivated`] event is sent so the game can show a Safe Mode dialog.
+/// Scripts can be re-enabled with [`DisabledScripts::enable_all`].
+///
+/// Crashes are attributed to scripts only if they panic while handling events,
+/// hard crashes (e.g. aborts) always disable all scripts.
+pub struct SafeModePlugin {
+    /// where the marker file is stored
+    pub marker_path: PathBuf,
+    /// disable all scripts after a crash, not just the one which caused it
+    pub disable_all: bool,
+}
+
+impl Default for SafeModePlugin {
+    fn default() -> Self {
+        Self {
+            marker_path: PathBuf::from("bevy_mod_scripting.running"),
+            disable_all: false,
+        }
+    }
+}
+
+const RUNNING: &str = "running";
+
+impl Plugin for SafeModePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<SafeModeActivated>()
+            .init_resource::<DisabledScripts>();
+
+        if !PlatformCapabilities::current().filesystem {
+            info!("Safe mode is unavailable without filesystem access");
+            return;
+        }
+
+        let previous = fs::read_to_string(&self.marker_path).ok();
+        let crashed = previous.is_some();
+        let crashed_script = previous.and_then(|p| {
+            p.lines()
+                .nth(1)
+                .map(str::trim)
+                .filter(|l| !l.is_empty())
+                .map(str::to_owned)
+        });
+
+        if let Err(e) = fs::write(&self.marker_path, RUNNING) {
+            warn!(
+                "Could not write safe mode marker `{}`: {e}",
+                self.marker_path.display()
+            );
+        }
+
+        // record the executing script if we panic, the marker is left over for the next run
+        TRACKING.store(true, Ordering::Relaxed);
+        let marker_path = self.marker_path.clone();
+        let previous_hook = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            let executing = EXECUTING_SCRIPT
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .clone();
+            if let Some(name) = executing {
+                let _ = fs::write(&marker_path, format!("{RUNNING}\n{name}"));
+            }
+            previous_hook(info)
+        }));
+
+        app.insert_resource(SafeModeMarker(self.marker_path.clone()))
+            .add_system_to_stage(CoreStage::Last, remove_marker_on_exit);
+
+        if crashed {
+            let all_disabled = self.disable_all || crashed_script.is_none();
+            let mut disabled = app.world.resource_mut::<DisabledScripts>();
+            match &crashed_script {
+                Some(name) if !all_disabled => disabled.disable_name(name),
+                _ => disabled.set_all_disabled(true),
+            }
+
+            warn!(
+                "Previous run did not shut down cleanly, starting in safe mode with {}",
+                match &crashed_script {
+                    Some(name) if !all_disabled => format!("script `{name}` disabled"),
+                    _ => "all scripts disabled".to_owned(),
+                }
+            );
+
+            app.world
+                .resource_mut::<Events<SafeModeActivated>>()
+                .send(SafeModeActivated {
+                    crashed_script,
+                    all_disabled,
+                });
+        }
+    }
+}
+
+#[derive(Resource)]
+struct SafeModeMarker(PathBuf);
+
+/// Removes the marker on clean shutdown
+fn remove_marker_on_exit(mut exits: EventReader<AppExit>, marker: Res<SafeModeMarker>) {
+    if exits.iter().next().is_some() {
+        let _ = fs::remove_file(&marker.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::hosts::ScriptData;
+
+    fn marker_path(test: &str) -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "bevy_mod_scripting.{}.{test}.running",
+            std::process::id()
+        ))
+    }
+
+    fn start(marker_path: &Path) -> App {
+        let mut app = App::new();
+        app.add_plugin(SafeModePlugin {
+            marker_path: marker_path.to_path_buf(),
+            disable_all: false,
+        });
+        app
+    }
+
+    fn activations(app: &App) -> Vec<SafeModeActivated> {
+        let events = app.world.resource::<Events<SafeModeActivated>>();
+        events.get_reader().iter(events).cloned().collect()
+    }
+
+    fn is_enabled(app: &App, name: &str) -> bool {
+        app.world
+            .resource::<DisabledScripts>()
+            .is_script_enabled(&ScriptData {
+                sid: 0,
+                entity: Entity::from_raw(0),
+                name,
+                groups: &[],
+            })
+    }
+
+    #[test]
+    fn clean_starts_leave_scripts_enabled() {
+        let marker = marker_path("clean");
+        let _ = fs::remove_file(&marker);
+
+        let app = start(&marker);
+        assert!(activations(&app).is_empty());
+        assert!(is_enabled(&app, "scripts/mod.lua"));
+        assert_eq!(fs::read_to_string(&marker).unwrap(), RUNNING);
+
+        let _ = fs::remove_file(&marker);
+    }
+
+    #[test]
+    fn crashes_disable_the_executing_script() {
+        let marker = marker_path("crashed");
+        fs::write(&marker, format!("{RUNNING}\nscripts/bad.lua")).unwrap();
+
+        let app = start(&marker);
+        let activated = activations(&app);
+        assert_eq!(activated.len(), 1);
+        assert_eq!(
+            activated[0].crashed_script.as_deref(),
+            Some("scripts/bad.lua")
+        );
+        assert!(!activated[0].all_disabled);
+        assert!(!is_enabled(&app, "scripts/bad.lua"));
+        assert!(is_enabled(&app, "scripts/good.lua"));
+
+        // crashes which cannot be attributed to a script disable all of them
+        fs::write(&marker, RUNNING).unwrap();
+        let app = start(&marker);
+        assert!(activations(&app)[0].all_disabled);
+        assert!(!is_enabled(&app, "scripts/good.lua"));
+
+        let _ = fs::remove_file(&marker);
+    }
+}
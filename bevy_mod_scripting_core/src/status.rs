@@ -0,0 +1,284 @@
+//! Inspecting and toggling attached scripts at runtime
+use bevy::{
+    prelude::*,
+    utils::{Duration, HashMap, HashSet},
+};
+
+use crate::{
+    accounting::ScriptCpuUsage,
+    error::ScriptError,
+    event::ScriptErrorEvent,
+    hosts::{ScriptContexts, ScriptData, ScriptHost, ScriptId},
+};
+
+/// The load state of a script instance
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScriptLoadState {
+    /// the script asset has not loaded yet
+    Pending,
+    /// the script has a context and is receiving events
+    Loaded,
+    /// the script failed to load and will not run until it's reloaded
+    Failed,
+}
+
+/// A snapshot of the state of a single attached script
+#[derive(Debug, Clone)]
+pub struct ScriptStatus {
+    pub sid: ScriptId,
+    /// the name of the script, usually its asset path
+    pub name: String,
+    /// the entity the script is attached to
+    pub entity: Entity,
+    /// the name of the host running the script
+    pub host: String,
+    pub load_state: ScriptLoadState,
+    /// false if the script was disabled via [`DisabledScripts`]
+    pub enabled: bool,
+    /// the last error the script produced, if any
+    pub last_error: Option<ScriptError>,
+    /// the average time spent per call to the script's event handlers, None if it never handled any events
+    pub average_hook_time: Option<Duration>,
+}
+
+/// Scripts in this set are skipped by all script handler stages,
+/// events sent to them while disabled are not delivered later.
+///
+/// Scripts can be disabled by ID, or by name which also covers scripts attached in the future.
+#[derive(Resource, Debug, Default)]
+pub struct DisabledScripts {
+    scripts: HashSet<ScriptId>,
+    names: HashSet<String>,
+    all: bool,
+}
+
+impl DisabledScripts {
+    /// Stops the given script from receiving events
+    pub fn disable(&mut self, script_id: ScriptId) {
+        self.scripts.insert(script_id);
+    }
+
+    /// Lets the given script receive events again
+    pub fn enable(&mut self, script_id: ScriptId) {
+        self.scripts.remove(&script_id);
+    }
+
+    /// Stops every script with the given name from receiving events
+    pub fn disable_name(&mut self, name: &str) {
+        self.names.insert(name.to_owned());
+    }
+
+    /// Lets scripts with the given name receive events again, unless they were disabled by ID
+    pub fn enable_name(&mut self, name: &str) {
+        self.names.remove(name);
+    }
+
+    /// Stops or resumes the delivery of events to all scripts at once
+    pub fn set_all_disabled(&mut self, disabled: bool) {
+        self.all = disabled;
+    }
+
+    /// Re-enables every script, however it was disabled
+    pub fn enable_all(&mut self) {
+        self.scripts.clear();
+        self.names.clear();
+        self.all = false;
+    }
+
+    /// Returns false if the script was disabled by ID, only use this when the script's name is unknown
+    pub fn is_enabled(&self, script_id: ScriptId) -> bool {
+        !self.all && !self.scripts.contains(&script_id)
+    }
+
+    /// Returns false if the script was disabled by ID or name
+    pub fn is_script_enabled(&self, script: &ScriptData) -> bool {
+        self.is_enabled(script.sid) && !self.names.contains(script.name)
+    }
+}
+
+/// The last error produced by each script, kept up to date from [`ScriptErrorEvent`]s
+#[derive(Resource, Debug, Default)]
+pub struct LastScriptErrors {
+    errors: HashMap<ScriptId, ScriptError>,
+}
+
+impl LastScriptErrors {
+    pub fn get(&self, script_id: ScriptId) -> Option<&ScriptError> {
+        self.errors.get(&script_id)
+    }
+
+    /// Forgets the error of the given script
+    pub fn clear(&mut self, script_id: ScriptId) {
+        self.errors.remove(&script_id);
+    }
+}
+
+/// Records the last error of every script
+pub fn record_last_script_errors(
+    mut events: EventReader<ScriptErrorEvent>,
+    mut errors: ResMut<LastScriptErrors>,
+) {
+    for e in events.iter() {
+        errors.errors.insert(e.sid, e.error.clone());
+    }
+}
+
+/// Lists every script attached to an entity on the host `H`, ordered by entity and script ID.
+///
+/// `host` is only used to label the returned statuses.
+pub fn list_host_scripts<H: ScriptHost>(world: &World, host: &str) -> Vec<ScriptStatus> {
+    let contexts = match world.get_resource::<ScriptContexts<H::ScriptContext>>() {
+        Some(c) => c,
+        None => return Vec::default(),
+    };
+    let usage = world.get_resource::<ScriptCpuUsage<H>>();
+    let disabled = world.get_resource::<DisabledScripts>();
+    let errors = world.get_resource::<LastScriptErrors>();
+
+    let mut statuses = contexts
+        .context_entities
+        .iter()
+        .map(|(sid, (entity, ctx, name))| ScriptStatus {
+            sid: *sid,
+            name: name.clone(),
+            entity: *entity,
+            host: host.to_owned(),
+            load_state: if ctx.is_some() {
+                ScriptLoadState::Loaded
+            } else if contexts.has_failed(*sid) {
+                ScriptLoadState::Failed
+            } else {
+                ScriptLoadState::Pending
+            },
+            enabled: disabled.map_or(true, |d| {
+                d.is_script_enabled(&ScriptData {
+                    sid: *sid,
+                    entity: *entity,
+                    name,
+                    groups: &[],
+                })
+            }),
+            last_error: errors.and_then(|e| e.get(*sid)).cloned(),
+            average_hook_time: usage
+                .and_then(|u| u.get(*sid))
+                .filter(|s| s.calls > 0)
+                .map(|s| Duration::from_secs_f64(s.total.as_secs_f64() / s.calls as f64)),
+        })
+        .collect::<Vec<_>>();
+
+    statuses.sort_by_key(|s| (s.entity.index(), s.sid));
+    statuses
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::{IntoSystem, System};
+
+    use super::*;
+    use crate::{
+        hosts::{
+            test_host::{
+                context, send_event, world_with_scripts, TestContext, TestEvent, TestHost,
+            },
+            Recipients,
+        },
+        systems::handle_script_events,
+    };
+
+    fn update(world: &mut World) {
+        send_event(
+            world,
+            TestEvent {
+                hook: "on_update",
+                recipients: Recipients::All,
+            },
+            0,
+        );
+        handle_script_events::<TestHost>(world, 0, 10);
+    }
+
+    #[test]
+    fn disabled_scripts_miss_events() {
+        let entity = Entity::from_raw(0);
+        let mut world = world_with_scripts(&[(0, entity), (1, entity)]);
+        let mut disabled = DisabledScripts::default();
+        disabled.disable(1);
+        world.insert_resource(disabled);
+
+        update(&mut world);
+        assert_eq!(context(&world, 0).handled, vec!["on_update"]);
+        assert!(context(&world, 1).handled.is_empty());
+
+        let mut disabled = world.resource_mut::<DisabledScripts>();
+        disabled.enable(1);
+        disabled.disable_name("script.test");
+        update(&mut world);
+        assert_eq!(context(&world, 0).handled, vec!["on_update"]);
+        assert!(context(&world, 1).handled.is_empty());
+
+        world.resource_mut::<DisabledScripts>().enable_all();
+        update(&mut world);
+        assert_eq!(context(&world, 1).handled, vec!["on_update"]);
+    }
+
+    #[test]
+    fn lists_the_state_of_every_script() {
+        let entity = Entity::from_raw(0);
+        let mut world = world_with_scripts(&[(0, entity)]);
+        world.init_resource::<LastScriptErrors>();
+        let mut disabled = DisabledScripts::default();
+        disabled.disable(2);
+        world.insert_resource(disabled);
+
+        let mut contexts = world.resource_mut::<ScriptContexts<TestContext>>();
+        for sid in [1, 2] {
+            let fd = ScriptData {
+                sid,
+                entity,
+                name: "script.test",
+                groups: &[],
+            };
+            if sid == 1 {
+                contexts.insert_failed_context(fd);
+            } else {
+                contexts.insert_context(fd, None);
+            }
+        }
+
+        let mut usage = ScriptCpuUsage::<TestHost>::default();
+        usage.record(0, Duration::from_millis(2));
+        usage.record(0, Duration::from_millis(4));
+        world.insert_resource(usage);
+
+        world.send_event(ScriptErrorEvent {
+            sid: 1,
+            error: ScriptError::Other("boom".to_owned()),
+        });
+        let mut system = IntoSystem::into_system(record_last_script_errors);
+        system.initialize(&mut world);
+        system.run((), &mut world);
+
+        let statuses = list_host_scripts::<TestHost>(&world, "test");
+        let summary = statuses
+            .iter()
+            .map(|s| (s.sid, s.load_state, s.enabled, s.host.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            vec![
+                (0, ScriptLoadState::Loaded, true, "test"),
+                (1, ScriptLoadState::Failed, true, "test"),
+                (2, ScriptLoadState::Pending, false, "test"),
+            ]
+        );
+        assert_eq!(
+            statuses[0].average_hook_time,
+            Some(Duration::from_millis(3))
+        );
+        assert_eq!(statuses[1].average_hook_time, None);
+        assert_eq!(
+            statuses[1].last_error.as_ref().map(ToString::to_string),
+            Some("boom".to_owned())
+        );
+    }
+}
@@ -1,17 +1,35 @@
-use std::collections::HashSet;
+use std::{
+    collections::{HashMap, HashSet},
+    iter::once,
+};
 
 use bevy::{
+    asset::HandleId,
     ecs::system::SystemState,
     prelude::{
-        debug, AssetEvent, Assets, ChangeTrackers, Changed, Entity, EventReader, EventWriter,
-        FromWorld, Query, RemovedComponents, Res, ResMut, Resource, SystemLabel, World,
+        debug, warn, AssetEvent, Assets, ChangeTrackers, Changed, Commands, Entity, EventReader,
+        EventWriter, FromWorld, Query, RemovedComponents, Res, ResMut, Resource, SystemLabel,
+        World,
     },
+    utils::Instant,
 };
-use bevy_event_priority::PriorityEventReader;
+use bevy_event_priority::{PriorityEventReader, PriorityEventWriter};
 
 use crate::{
+    accounting::{ScriptCpuUsage, ScriptThrottle, ThrottleContext},
+    commands::flush_script_commands,
     event::ScriptLoaded,
-    prelude::{APIProviders, Script, ScriptCollection, ScriptContexts, ScriptData, ScriptHost},
+    filter::ScriptTags,
+    groups::ScriptGroups,
+    handover::ScriptCopies,
+    hosts::{ScriptContextHandle, WarmCloneScripts},
+    metrics::{ScriptSyncCounts, ScriptSyncMetrics},
+    prelude::{
+        APIProviders, Script, ScriptCollection, ScriptContexts, ScriptData, ScriptHost, ScriptId,
+    },
+    quota::ScriptQuotas,
+    safe_mode::ExecutingScript,
+    status::DisabledScripts,
     ScriptErrorEvent,
 };
 
@@ -31,6 +49,8 @@ pub fn script_add_synchronizer<H: ScriptHost + 'static>(
             Entity,
             &ScriptCollection<H::ScriptAsset>,
             ChangeTrackers<ScriptCollection<H::ScriptAsset>>,
+            Option<&ScriptTags>,
+            Option<&WarmCloneScripts>,
         ),
         Changed<ScriptCollection<H::ScriptAsset>>,
     >,
@@ -39,22 +59,63 @@ pub fn script_add_synchronizer<H: ScriptHost + 'static>(
     script_assets: Res<Assets<H::ScriptAsset>>,
     mut contexts: ResMut<ScriptContexts<H::ScriptContext>>,
     mut event_writer: EventWriter<ScriptLoaded>,
+    mut error_writer: EventWriter<ScriptErrorEvent>,
+    mut groups: Option<ResMut<ScriptGroups>>,
+    mut copies: Option<ResMut<ScriptCopies>>,
+    metrics: Option<ResMut<ScriptSyncMetrics<H>>>,
+    mut commands: Commands,
 ) {
     debug!("Handling addition/modification of scripts");
+    let mut counts = ScriptSyncCounts::default();
 
-    query.for_each(|(entity, new_scripts, tracker)| {
-        if tracker.is_added() {
-            new_scripts.scripts.iter().for_each(|new_script| {
-                Script::<H::ScriptAsset>::insert_new_script_context::<H>(
+    // scripts moved between entities keep their contexts, these are re-attached before
+    // the collections they were moved out of are synchronized and would drop them
+    query.for_each(|(entity, scripts, _, tags, _)| {
+        for script in &scripts.scripts {
+            if contexts
+                .script_owner(script.id())
+                .map_or(false, |owner| owner != entity)
+            {
+                Script::<H::ScriptAsset>::move_script_context::<H>(
                     &mut host,
-                    new_script,
+                    script,
                     entity,
-                    &script_assets,
+                    tags,
+                    groups.as_deref(),
                     &mut providers,
                     &mut contexts,
-                    &mut event_writer,
-                )
-            })
+                    &mut error_writer,
+                );
+            }
+        }
+    });
+
+    query.for_each(|(entity, new_scripts, tracker, tags, warm_clone)| {
+        counts.collections_synced += 1;
+        counts.scripts_visited += new_scripts.scripts.len() as u64;
+
+        if tracker.is_added() {
+            new_scripts
+                .scripts
+                .iter()
+                .filter(|new_script| contexts.script_owner(new_script.id()) != Some(entity))
+                .for_each(|new_script| {
+                    counts.contexts_inserted += 1;
+                    Script::<H::ScriptAsset>::insert_new_script_context::<H>(
+                        &mut host,
+                        new_script,
+                        entity,
+                        tags,
+                        groups.as_deref(),
+                        warm_clone.is_some(),
+                        copies.as_mut().and_then(|c| c.take(new_script.id())),
+                        &script_assets,
+                        &mut providers,
+                        &mut contexts,
+                        &mut event_writer,
+                        &mut error_writer,
+                    )
+                })
         } else {
             // changed but structure already exists in contexts
             // find out what's changed
@@ -62,105 +123,185 @@ pub fn script_add_synchronizer<H: ScriptHost + 'static>(
             // if the script asset gets changed we deal with that elsewhere
 
             let context_ids = contexts
-                .context_entities
-                .iter()
-                .filter_map(|(sid, (e, _, _))| if *e == entity { Some(sid) } else { None })
-                .cloned()
-                .collect::<HashSet<u32>>();
+                .entity_scripts(entity)
+                .collect::<HashSet<ScriptId>>();
             let script_ids = new_scripts
                 .scripts
                 .iter()
                 .map(|s| s.id())
-                .collect::<HashSet<u32>>();
+                .collect::<HashSet<ScriptId>>();
 
             let removed_scripts = context_ids.difference(&script_ids);
             let added_scripts = script_ids.difference(&context_ids);
 
             for r in removed_scripts {
+                counts.contexts_removed += 1;
                 contexts.remove_context(*r);
+                providers.forget(*r);
+                if let Some(groups) = groups.as_mut() {
+                    groups.leave_all(*r);
+                }
             }
 
             for a in added_scripts {
+                counts.contexts_inserted += 1;
                 let script = new_scripts.scripts.iter().find(|e| &e.id() == a).unwrap();
                 Script::<H::ScriptAsset>::insert_new_script_context::<H>(
                     &mut host,
                     script,
                     entity,
+                    tags,
+                    groups.as_deref(),
+                    warm_clone.is_some(),
+                    copies.as_mut().and_then(|c| c.take(*a)),
                     &script_assets,
                     &mut providers,
                     &mut contexts,
                     &mut event_writer,
+                    &mut error_writer,
                 )
             }
         }
-    })
+
+        // the entity is checked when the command is applied rather than with `commands.get_entity`,
+        // since a command queued before this one may despawn it
+        let handle = ScriptContextHandle::<H>::new(contexts.entity_scripts(entity).collect());
+        commands.add(move |world: &mut World| {
+            if let Some(mut entity) = world.get_entity_mut(entity) {
+                entity.insert(handle);
+            }
+        });
+    });
+
+    if let Some(mut metrics) = metrics {
+        metrics.record(counts);
+    }
 }
 
 /// Handles the removal of script components and their contexts
 pub fn script_remove_synchronizer<H: ScriptHost>(
     query: RemovedComponents<ScriptCollection<H::ScriptAsset>>,
     mut contexts: ResMut<ScriptContexts<H::ScriptContext>>,
+    mut providers: ResMut<APIProviders<H>>,
+    mut groups: Option<ResMut<ScriptGroups>>,
+    metrics: Option<ResMut<ScriptSyncMetrics<H>>>,
+    mut commands: Commands,
 ) {
+    let mut counts = ScriptSyncCounts::default();
     query.iter().for_each(|v| {
+        // the entity was either despawned, taking the handle with it, or only lost its scripts
+        if let Some(mut entity) = commands.get_entity(v) {
+            entity.remove::<ScriptContextHandle<H>>();
+        }
+        let sids = contexts.entity_scripts(v).collect::<Vec<_>>();
+        counts.contexts_removed += sids.len() as u64;
+        if let Some(groups) = groups.as_mut() {
+            sids.iter().for_each(|sid| groups.leave_all(*sid));
+        }
+        sids.iter().for_each(|sid| providers.forget(*sid));
         // we know that this entity used to have a script component
         // ergo a script context must exist in ctxts, remove all scripts on the entity
-        contexts.remove_context(v.index());
-    })
+        contexts.remove_entity_contexts(v);
+    });
+
+    if let Some(mut metrics) = metrics {
+        metrics.record(counts);
+    }
 }
 
 /// Reloads hot-reloaded scripts, or loads missing contexts for scripts which were added but not loaded
 pub fn script_hot_reload_handler<H: ScriptHost>(
     mut events: EventReader<AssetEvent<H::ScriptAsset>>,
     mut host: ResMut<H>,
-    scripts: Query<&ScriptCollection<H::ScriptAsset>>,
+    scripts: Query<(
+        &ScriptCollection<H::ScriptAsset>,
+        Option<&ScriptTags>,
+        Option<&WarmCloneScripts>,
+    )>,
     script_assets: Res<Assets<H::ScriptAsset>>,
     mut providers: ResMut<APIProviders<H>>,
     mut contexts: ResMut<ScriptContexts<H::ScriptContext>>,
     mut event_writer: EventWriter<ScriptLoaded>,
+    mut error_writer: EventWriter<ScriptErrorEvent>,
+    groups: Option<Res<ScriptGroups>>,
+    metrics: Option<ResMut<ScriptSyncMetrics<H>>>,
 ) {
+    let mut counts = ScriptSyncCounts::default();
+
+    // whether each created or modified asset was only created this frame
+    let mut changed: HashMap<HandleId, bool> = HashMap::default();
     for e in events.iter() {
+        counts.asset_events += 1;
         let (handle, created) = match e {
             AssetEvent::Modified { handle } => (handle, false),
             AssetEvent::Created { handle } => (handle, true),
             _ => continue,
         };
+        *changed.entry(handle.id()).or_insert(true) &= created;
+    }
 
-        // find script using this handle by handle id
-        // whether this script was modified or created
+    // scripts are only visited on frames where their assets changed, and then only once
+    if !changed.is_empty() {
+        // find scripts using the changed handles by handle id
+        // whether the script was modified or created
         // if a script exists with this handle, we should reload it to load in a new context
         // which at this point will be either None or Some(outdated context)
         // both ways are fine
-        for scripts in scripts.iter() {
+        for (scripts, tags, warm_clone) in scripts.iter() {
+            counts.scripts_visited += scripts.scripts.len() as u64;
             for script in &scripts.scripts {
+                let created = match changed.get(&script.handle().id()) {
+                    Some(created) => *created,
+                    None => continue,
+                };
                 // the script could have well loaded in the same frame that it was added
                 // in that case it will have a context attached and we do not want to reload it
-                if script.handle() == handle && !(contexts.has_context(script.id()) && created) {
+                if !(contexts.has_context(script.id()) && created) {
+                    counts.contexts_reloaded += 1;
                     Script::<H::ScriptAsset>::reload_script::<H>(
                         &mut host,
                         script,
+                        tags,
+                        groups.as_deref(),
+                        // contexts of scripts whose asset was modified are outdated, so only fresh ones are cloned
+                        created && warm_clone.is_some(),
                         &script_assets,
                         &mut providers,
                         &mut contexts,
                         &mut event_writer,
+                        &mut error_writer,
                     );
                 }
             }
         }
     }
+
+    if let Some(mut metrics) = metrics {
+        metrics.record(counts);
+    }
 }
 
 /// Lets the script host handle all script events
 pub fn script_event_handler<H: ScriptHost, const MAX: u32, const MIN: u32>(world: &mut World) {
+    handle_script_events::<H>(world, MAX, MIN);
+}
+
+/// Lets the script host handle all script events with priorities from `max` to `min` (`max <= priority <= min`),
+/// see [`ScriptHost::handle_events_manual`]
+pub fn handle_script_events<H: ScriptHost>(world: &mut World, max: u32, min: u32) {
+    if max > min {
+        warn!("Ignoring the inverted script event priority range {max}..={min}, `max` must not be greater than `min`");
+        return;
+    }
+
     // we need to collect the events to drop the borrow of the world
 
     let mut state: CachedScriptState<H> = world.remove_resource().unwrap();
 
-    let events = state
-        .event_state
-        .get_mut(world)
-        .0
-        .iter_prio_range(MAX, MIN)
-        .collect::<Vec<H::ScriptEvent>>();
+    let mut reader = state.event_state.get_mut(world).0;
+    // immediate events are handled by whichever handler runs first, before those in its range
+    let mut events = reader.drain_immediate().collect::<Vec<H::ScriptEvent>>();
+    events.extend(reader.iter_prio_range(max, min));
 
     world.insert_resource(state);
 
@@ -173,36 +314,143 @@ pub fn script_event_handler<H: ScriptHost, const MAX: u32, const MIN: u32>(world
 
     let host: H = world.remove_resource().unwrap();
     let mut providers: APIProviders<H> = world.remove_resource().unwrap();
+    let mut usage: ScriptCpuUsage<H> = world.remove_resource().unwrap_or_default();
+    let throttle: Option<ScriptThrottle> = world.remove_resource();
+    let disabled: Option<DisabledScripts> = world.remove_resource();
 
     // we need a resource scope to be able to simultaneously access the contexts as well
     // as provide world access to scripts
     // afaik there is not really a better way to do this in bevy just now
-    let ctx_iter = ctxts
-        .context_entities
-        .iter_mut()
-        .filter_map(|(sid, (entity, o, name))| {
-            let ctx = match o {
-                Some(v) => v,
-                None => return None,
+    for (sid, (entity, o, name)) in ctxts.context_entities.iter_mut() {
+        let ctx = match o {
+            Some(v) => v,
+            None => continue,
+        };
+
+        // groups joined by previously handled scripts are visible
+        let groups = world
+            .get_resource::<ScriptGroups>()
+            .map(|g| g.groups_of(*sid).to_vec())
+            .unwrap_or_default();
+        let script_data = ScriptData {
+            sid: *sid,
+            entity: *entity,
+            name,
+            groups: &groups,
+        };
+
+        if disabled
+            .as_ref()
+            .map_or(false, |d| !d.is_script_enabled(&script_data))
+        {
+            continue;
+        }
+
+        if let Some(throttle) = &throttle {
+            let throttle_ctx = ThrottleContext {
+                frame: usage.frame(),
+                max_priority: max,
+                min_priority: min,
+                stats: usage.get(*sid),
+                last_frame_share: usage.last_frame_share(*sid),
             };
 
-            Some((
-                ScriptData {
-                    sid: *sid,
-                    entity: *entity,
-                    name,
-                },
-                ctx,
-            ))
-        });
+            if !throttle.0.should_deliver(&script_data, &throttle_ctx) {
+                continue;
+            }
+        }
+
+        let start = Instant::now();
+        let executing = ExecutingScript::enter(script_data.name);
+        if let Some(mut quotas) = world.get_resource_mut::<ScriptQuotas>() {
+            quotas.enter(*sid);
+        }
+        // safety: we have unique access to world, future accesses are protected
+        // by the lock in the pointer
+        host.handle_events(world, &events, once((script_data, ctx)), &mut providers);
+        if let Some(mut quotas) = world.get_resource_mut::<ScriptQuotas>() {
+            quotas.exit();
+        }
+        // changes queued by this script are visible to the next one
+        flush_script_commands(world);
+        drop(executing);
+        usage.record(*sid, start.elapsed());
+    }
 
-    // safety: we have unique access to world, future accesses are protected
-    // by the lock in the pointer
-    host.handle_events(world, &events, ctx_iter, &mut providers);
+    usage.retain(|sid| ctxts.context_entities.contains_key(&sid));
 
     world.insert_resource(ctxts);
     world.insert_resource(host);
     world.insert_resource(providers);
+    world.insert_resource(usage);
+    if let Some(throttle) = throttle {
+        world.insert_resource(throttle);
+    }
+    if let Some(disabled) = disabled {
+        world.insert_resource(disabled);
+    }
+}
+
+/// Determines what happens to script events of a state bound handler while outside of its state
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutOfStateEvents {
+    /// events are set aside in [`RetainedScriptEvents`] and sent again, with their original priorities,
+    /// once the state is entered again, so handlers of lower priority ranges do not discard them in the meantime
+    #[default]
+    Retain,
+    /// events are discarded without being handled
+    Drop,
+}
+
+/// Discards all script events in the given priority range without handling them
+pub fn script_event_dropper<H: ScriptHost, const MAX: u32, const MIN: u32>(
+    mut events: PriorityEventReader<H::ScriptEvent>,
+) {
+    events.iter_prio_range(MAX, MIN).for_each(drop);
+}
+
+/// The script events in the priority range [MAX,MIN] set aside by a state bound handler while outside of its state,
+/// in the order they were sent, along with their priorities
+#[derive(Resource)]
+pub struct RetainedScriptEvents<H: ScriptHost, const MAX: u32, const MIN: u32> {
+    events: Vec<(u32, H::ScriptEvent)>,
+}
+
+impl<H: ScriptHost, const MAX: u32, const MIN: u32> Default for RetainedScriptEvents<H, MAX, MIN> {
+    fn default() -> Self {
+        Self { events: Vec::new() }
+    }
+}
+
+impl<H: ScriptHost, const MAX: u32, const MIN: u32> RetainedScriptEvents<H, MAX, MIN> {
+    /// The number of events set aside
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+}
+
+/// Moves all script events in the given priority range into [`RetainedScriptEvents`]
+pub fn script_event_retainer<H: ScriptHost, const MAX: u32, const MIN: u32>(
+    mut events: PriorityEventReader<H::ScriptEvent>,
+    mut retained: ResMut<RetainedScriptEvents<H, MAX, MIN>>,
+) {
+    retained
+        .events
+        .extend(events.iter_prio_range_with_priority(MAX, MIN));
+}
+
+/// Sends the events in [`RetainedScriptEvents`] again with their original priorities
+pub fn script_event_resender<H: ScriptHost, const MAX: u32, const MIN: u32>(
+    mut events: PriorityEventWriter<H::ScriptEvent>,
+    mut retained: ResMut<RetainedScriptEvents<H, MAX, MIN>>,
+) {
+    for (prio, event) in retained.events.drain(..) {
+        events.send(event, prio);
+    }
 }
 
 #[derive(Resource)]
@@ -222,3 +470,115 @@ impl<H: ScriptHost> FromWorld for CachedScriptState<H> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::{IntoSystem, System};
+
+    use super::*;
+    use crate::{
+        accounting::ScriptThrottlePolicy,
+        hosts::{
+            test_host::{context, send_event, world_with_scripts, TestEvent, TestHost},
+            Recipients,
+        },
+    };
+
+    fn event(hook: &'static str, recipients: Recipients) -> TestEvent {
+        TestEvent { hook, recipients }
+    }
+
+    fn run_system<P>(world: &mut World, system: impl IntoSystem<(), (), P>) {
+        let mut system = IntoSystem::into_system(system);
+        system.initialize(world);
+        system.run((), world);
+    }
+
+    /// Refuses to deliver events to the given script
+    struct SkipScript(ScriptId);
+
+    impl ScriptThrottlePolicy for SkipScript {
+        fn should_deliver(&self, script: &ScriptData, _ctx: &ThrottleContext) -> bool {
+            script.sid != self.0
+        }
+    }
+
+    #[test]
+    fn throttled_scripts_miss_events() {
+        let entity = Entity::from_raw(0);
+        let mut world = world_with_scripts(&[(0, entity), (1, entity)]);
+        world.insert_resource(ScriptThrottle::new(SkipScript(1)));
+
+        send_event(&mut world, event("on_update", Recipients::All), 0);
+        handle_script_events::<TestHost>(&mut world, 0, 10);
+
+        assert_eq!(context(&world, 0).handled, vec!["on_update"]);
+        assert!(context(&world, 1).handled.is_empty());
+
+        let usage = world.resource::<ScriptCpuUsage<TestHost>>();
+        assert_eq!(usage.get(0).unwrap().calls, 1);
+        assert!(usage.get(1).is_none());
+        // the policy is kept for the next handler stage
+        assert!(world.contains_resource::<ScriptThrottle>());
+    }
+
+    #[test]
+    fn retained_events_are_resent_with_their_priorities() {
+        let mut world = world_with_scripts(&[(0, Entity::from_raw(0))]);
+        world.init_resource::<RetainedScriptEvents<TestHost, 0, 5>>();
+        send_event(&mut world, event("on_low", Recipients::All), 5);
+        send_event(&mut world, event("on_high", Recipients::All), 0);
+        send_event(&mut world, event("on_other", Recipients::All), 6);
+
+        run_system(&mut world, script_event_retainer::<TestHost, 0, 5>);
+        assert_eq!(
+            world
+                .resource::<RetainedScriptEvents<TestHost, 0, 5>>()
+                .len(),
+            2
+        );
+        // handlers of lower priority ranges do not discard the retained events
+        handle_script_events::<TestHost>(&mut world, 0, 10);
+        assert_eq!(context(&world, 0).handled, vec!["on_other"]);
+
+        run_system(&mut world, script_event_resender::<TestHost, 0, 5>);
+        assert!(world
+            .resource::<RetainedScriptEvents<TestHost, 0, 5>>()
+            .is_empty());
+        handle_script_events::<TestHost>(&mut world, 0, 0);
+        handle_script_events::<TestHost>(&mut world, 5, 5);
+        assert_eq!(
+            context(&world, 0).handled,
+            vec!["on_other", "on_high", "on_low"]
+        );
+    }
+
+    #[test]
+    fn dropped_events_are_not_handled() {
+        let mut world = world_with_scripts(&[(0, Entity::from_raw(0))]);
+        send_event(&mut world, event("on_dropped", Recipients::All), 3);
+        send_event(&mut world, event("on_kept", Recipients::All), 6);
+
+        run_system(&mut world, script_event_dropper::<TestHost, 0, 5>);
+        handle_script_events::<TestHost>(&mut world, 0, 10);
+        assert_eq!(context(&world, 0).handled, vec!["on_kept"]);
+    }
+
+    #[test]
+    fn events_for_many_recipients_are_handled_once_by_each_script() {
+        let (a, b) = (Entity::from_raw(0), Entity::from_raw(1));
+        let mut world = world_with_scripts(&[(0, a), (1, b), (2, b)]);
+
+        let recipients = Recipients::Many(vec![
+            Recipients::Entity(a),
+            Recipients::Id(0),
+            Recipients::Id(2),
+        ]);
+        send_event(&mut world, event("on_hit", recipients), 0);
+        handle_script_events::<TestHost>(&mut world, 0, 0);
+
+        assert_eq!(context(&world, 0).handled, vec!["on_hit"]);
+        assert!(context(&world, 1).handled.is_empty());
+        assert_eq!(context(&world, 2).handled, vec!["on_hit"]);
+    }
+}
@@ -0,0 +1,276 @@
+//! Running script files as tests, see [`crate::script_tests`]
+use std::{
+    fmt::Write,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use bevy::prelude::*;
+use parking_lot::Mutex;
+
+use crate::{event::ScriptErrorEvent, hosts::ScriptHost};
+
+/// Setting this environment variable makes the test harness overwrite golden output files instead of comparing against them
+pub const BLESS_SCRIPT_TESTS_VAR: &str = "BLESS_SCRIPT_TESTS";
+
+/// Lines printed by test scripts, API providers offering the test assertion API redirect the script's `print` here.
+///
+/// After running a test script `foo_test.lua` the printed lines are compared against the golden output file `foo_test.out`
+/// next to it, if one exists.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct ScriptTestOutput(Arc<Mutex<Vec<String>>>);
+
+impl ScriptTestOutput {
+    pub fn push(&self, line: String) {
+        self.0.lock().push(line);
+    }
+
+    /// The lines printed so far
+    pub fn lines(&self) -> Vec<String> {
+        self.0.lock().clone()
+    }
+
+    /// Removes and returns the lines printed so far
+    pub fn take(&self) -> Vec<String> {
+        std::mem::take(&mut *self.0.lock())
+    }
+}
+
+/// Finds all files under the given directory, including its subdirectories, whose name ends in the given suffix (e.g. `_test.lua`)
+pub fn discover_script_tests(dir: &Path, suffix: &str) -> std::io::Result<Vec<PathBuf>> {
+    let mut found = Vec::default();
+    let mut dirs = vec![dir.to_owned()];
+
+    while let Some(dir) = dirs.pop() {
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                dirs.push(path);
+            } else if path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .map_or(false, |n| n.ends_with(suffix))
+            {
+                found.push(path);
+            }
+        }
+    }
+
+    found.sort();
+    Ok(found)
+}
+
+/// Runs a single test script as a one shot script on a fresh entity, see [`ScriptHost::run_one_shot`].
+///
+/// The test fails if the script fails to load, if handling the event produces any [`ScriptErrorEvent`]s,
+/// such as failed assertions, or if the script's output does not match its golden output file.
+pub fn run_script_test<H: ScriptHost>(
+    app: &mut App,
+    path: &Path,
+    event: H::ScriptEvent,
+) -> Result<(), String> {
+    let script =
+        std::fs::read(path).map_err(|e| format!("Could not read `{}`: {e}", path.display()))?;
+    let name = path.to_string_lossy();
+
+    if let Some(output) = app.world.get_resource::<ScriptTestOutput>() {
+        output.take();
+    }
+
+    let mut errors = app
+        .world
+        .get_resource::<Events<ScriptErrorEvent>>()
+        .ok_or("The scripting plugin was not added to the test app")?
+        .get_reader_current();
+
+    let mut host = app
+        .world
+        .remove_resource::<H>()
+        .ok_or("The script host was not added to the test app")?;
+    let entity = app.world.spawn_empty().id();
+    let result = host.run_one_shot(&script, &name, entity, &mut app.world, event);
+    app.world.insert_resource(host);
+    app.world.despawn(entity);
+
+    let failures: Vec<String> = result
+        .err()
+        .map(|e| e.to_string())
+        .into_iter()
+        .chain(
+            errors
+                .iter(app.world.resource::<Events<ScriptErrorEvent>>())
+                .map(|e| e.error.to_string()),
+        )
+        .collect();
+    if !failures.is_empty() {
+        return Err(failures.join("\n"));
+    }
+
+    let output = app
+        .world
+        .get_resource::<ScriptTestOutput>()
+        .map(ScriptTestOutput::take)
+        .unwrap_or_default();
+    check_golden_output(&path.with_extension("out"), &output)
+}
+
+fn check_golden_output(golden: &Path, output: &[String]) -> Result<(), String> {
+    if std::env::var_os(BLESS_SCRIPT_TESTS_VAR).is_some() {
+        if output.is_empty() {
+            return Ok(());
+        }
+        let mut contents = output.join("\n");
+        contents.push('\n');
+        return std::fs::write(golden, contents)
+            .map_err(|e| format!("Could not write `{}`: {e}", golden.display()));
+    }
+
+    let expected = match std::fs::read_to_string(golden) {
+        Ok(expected) => expected,
+        Err(_) => return Ok(()),
+    };
+
+    if expected.lines().eq(output.iter().map(String::as_str)) {
+        Ok(())
+    } else {
+        Err(format!(
+            "The output does not match `{}`, set `{BLESS_SCRIPT_TESTS_VAR}` to update it\n--- expected\n{}\n--- printed\n{}",
+            golden.display(),
+            expected.trim_end(),
+            output.join("\n")
+        ))
+    }
+}
+
+/// The outcome of running all test scripts in a directory
+#[derive(Debug, Default)]
+pub struct ScriptTestReport {
+    pub dir: PathBuf,
+    pub passed: Vec<PathBuf>,
+    /// failed test scripts along with the reasons they failed
+    pub failed: Vec<(PathBuf, String)>,
+}
+
+impl ScriptTestReport {
+    /// Returns true if at least one test script was found and none of them failed
+    pub fn is_success(&self) -> bool {
+        self.failed.is_empty() && !self.passed.is_empty()
+    }
+
+    /// Panics listing every failed test script unless [`Self::is_success`]
+    pub fn assert_success(&self) {
+        if self.is_success() {
+            return;
+        }
+
+        if self.failed.is_empty() {
+            panic!("No test scripts found under `{}`", self.dir.display());
+        }
+
+        let mut message = String::default();
+        for (path, reason) in &self.failed {
+            let _ = writeln!(message, "---- {} ----\n{reason}\n", path.display());
+        }
+        let _ = write!(
+            message,
+            "{} of {} test scripts failed",
+            self.failed.len(),
+            self.failed.len() + self.passed.len()
+        );
+        panic!("{message}");
+    }
+}
+
+/// Runs every test script discovered under the directory with [`run_script_test`], each in a fresh app built by `setup`,
+/// printing a line per script in the style of `cargo test`.
+///
+/// The app is updated once before the test script runs, so that startup systems have run.
+pub fn run_script_tests<H, S, E>(
+    dir: impl AsRef<Path>,
+    suffix: &str,
+    setup: S,
+    event: E,
+) -> ScriptTestReport
+where
+    H: ScriptHost,
+    S: Fn() -> App,
+    E: Fn() -> H::ScriptEvent,
+{
+    let dir = dir.as_ref();
+    let mut report = ScriptTestReport {
+        dir: dir.to_owned(),
+        ..Default::default()
+    };
+
+    let paths = match discover_script_tests(dir, suffix) {
+        Ok(paths) => paths,
+        Err(e) => {
+            report.failed.push((
+                dir.to_owned(),
+                format!("Could not search for test scripts: {e}"),
+            ));
+            return report;
+        }
+    };
+
+    for path in paths {
+        let mut app = setup();
+        app.update();
+
+        let display = path
+            .strip_prefix(dir)
+            .unwrap_or(&path)
+            .display()
+            .to_string();
+        match run_script_test::<H>(&mut app, &path, event()) {
+            Ok(()) => {
+                println!("test {display} ... ok");
+                report.passed.push(path);
+            }
+            Err(reason) => {
+                println!("test {display} ... FAILED");
+                report.failed.push((path, reason));
+            }
+        }
+    }
+
+    report
+}
+
+/// Generates a `#[test]` function running every test script under a directory relative to the crate root,
+/// failing if any of them fail, see [`run_script_tests`].
+///
+/// Test scripts are written in the hook called by the given event, using the assertion API of the host's
+/// test API provider (e.g. `LuaTestAPIProvider`), and may have a golden output file next to them:
+/// ```rust,ignore
+/// fn setup() -> App {
+///     let mut app = App::new();
+///     app.add_plugins(MinimalPlugins)
+///         .add_plugin(AssetPlugin::default())
+///         .add_plugin(ScriptingPlugin)
+///         .add_script_host::<LuaScriptHost<()>, _>(CoreStage::PostUpdate)
+///         .add_api_provider::<LuaScriptHost<()>>(Box::new(LuaTestAPIProvider::default()));
+///     app
+/// }
+///
+/// script_tests!(lua_scripts, LuaScriptHost<()>, "tests/scripts", "_test.lua", setup, || LuaEvent {
+///     hook_name: "on_test".into(),
+///     args: (),
+///     recipients: Recipients::All,
+/// });
+/// ```
+#[macro_export]
+macro_rules! script_tests {
+    ($name:ident, $host:ty, $dir:expr, $suffix:expr, $setup:expr, $event:expr) => {
+        #[test]
+        fn $name() {
+            $crate::testing::run_script_tests::<$host, _, _>(
+                ::std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join($dir),
+                $suffix,
+                $setup,
+                $event,
+            )
+            .assert_success();
+        }
+    };
+}
@@ -1,3 +1,5 @@
+#[cfg(feature = "checked_world_access")]
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
 use bevy::prelude::World;
@@ -9,8 +11,16 @@ use parking_lot::{
 /// # Safety
 /// This pointer does not prevent dangling pointers, i.e. you must ensure the world is not dropped while any world pointers still exist,
 /// the world must also not change, from the moment a world pointer is created it must always point to the same world.
+///
+/// With the `checked_world_access` feature every access is validated instead: pointers used after
+/// [`WorldPointer::invalidate`] was called on them or any of their clones, e.g. by callbacks which escaped the script handler
+/// that created them, panic instead of dereferencing a possibly dangling pointer.
 #[derive(Debug, Clone)]
-pub struct WorldPointer(Arc<RwLock<*mut World>>);
+pub struct WorldPointer {
+    ptr: Arc<RwLock<*mut World>>,
+    #[cfg(feature = "checked_world_access")]
+    valid: Arc<AtomicBool>,
+}
 
 unsafe impl Send for WorldPointer {}
 unsafe impl Sync for WorldPointer {}
@@ -19,23 +29,140 @@ impl WorldPointer {
     /// Creates a new world pointer.
     /// # Safety
     /// satisfies world constancy, since it's impossible to change the underlying pointer
-    /// However you must ensure that the world does not go out of scope while this pointer is live    
+    /// However you must ensure that the world does not go out of scope while this pointer is live
     pub unsafe fn new(world: &mut World) -> Self {
-        WorldPointer(Arc::new(RwLock::new(world)))
+        WorldPointer {
+            ptr: Arc::new(RwLock::new(world)),
+            #[cfg(feature = "checked_world_access")]
+            valid: Arc::new(AtomicBool::new(true)),
+        }
+    }
+
+    /// Marks this pointer and all of its clones as no longer pointing to a live world,
+    /// called by script hosts once the world access they were created for ends.
+    ///
+    /// Only checked with the `checked_world_access` feature, without it this does nothing.
+    pub fn invalidate(&self) {
+        #[cfg(feature = "checked_world_access")]
+        self.valid.store(false, Ordering::SeqCst);
+    }
+
+    #[cfg(feature = "checked_world_access")]
+    fn check_valid(&self) {
+        assert!(
+            self.valid.load(Ordering::SeqCst),
+            "World pointer used after the world access it was created for ended, a script callback likely escaped its handler"
+        );
     }
 
     /// Returns a read guard which can be used for immutable world access.
     pub fn read(&self) -> MappedRwLockReadGuard<World> {
-        RwLockReadGuard::map(self.0.try_read().expect(""), |ptr: &*mut World| unsafe {
-            &**ptr
-        })
+        #[cfg(feature = "checked_world_access")]
+        self.check_valid();
+
+        let guard = self.ptr.try_read().unwrap_or_else(|| {
+            panic!("Could not read the world, it is already being written to elsewhere")
+        });
+        RwLockReadGuard::map(guard, |ptr: &*mut World| unsafe { &**ptr })
     }
 
     /// Returns a write guard which can be used for mutable world access.
     pub fn write(&self) -> MappedRwLockWriteGuard<World> {
-        RwLockWriteGuard::map(
-            self.0.try_write().expect(""),
-            |ptr: &mut *mut World| unsafe { &mut **ptr },
-        )
+        #[cfg(feature = "checked_world_access")]
+        self.check_valid();
+
+        let guard = self.ptr.try_write().unwrap_or_else(|| {
+            panic!("Could not write to the world, it is already being accessed elsewhere")
+        });
+        RwLockWriteGuard::map(guard, |ptr: &mut *mut World| unsafe { &mut **ptr })
+    }
+}
+
+/// Aliasing tests for the world access layer, run under Miri with:
+/// `cargo +nightly miri test -p bevy_mod_scripting_core --features checked_world_access world::`
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+
+    use super::*;
+
+    #[derive(Component, Debug, PartialEq)]
+    struct Health(u32);
+
+    fn world_with_entity() -> (World, Entity) {
+        let mut world = World::new();
+        let entity = world.spawn(Health(10)).id();
+        (world, entity)
+    }
+
+    #[test]
+    fn providers_mutate_same_component_in_turn() {
+        let (mut world, entity) = world_with_entity();
+        let ptr = unsafe { WorldPointer::new(&mut world) };
+        let providers = [ptr.clone(), ptr.clone()];
+
+        for (i, provider) in providers.iter().enumerate() {
+            let mut w = provider.write();
+            w.get_mut::<Health>(entity).unwrap().0 += i as u32 + 1;
+        }
+        ptr.invalidate();
+
+        assert_eq!(world.get::<Health>(entity), Some(&Health(13)));
+    }
+
+    #[test]
+    #[should_panic]
+    fn providers_mutate_same_component_at_once() {
+        let (mut world, entity) = world_with_entity();
+        let first = unsafe { WorldPointer::new(&mut world) };
+        let second = first.clone();
+
+        let mut a = first.write();
+        let mut b = second.write();
+        a.get_mut::<Health>(entity).unwrap().0 += 1;
+        b.get_mut::<Health>(entity).unwrap().0 += 1;
+    }
+
+    #[test]
+    #[should_panic]
+    fn read_while_writing() {
+        let (mut world, _) = world_with_entity();
+        let ptr = unsafe { WorldPointer::new(&mut world) };
+
+        let other = ptr.clone();
+        let _w = ptr.write();
+        let _r = other.read();
+    }
+
+    #[test]
+    fn concurrent_reads() {
+        let (mut world, entity) = world_with_entity();
+        let ptr = unsafe { WorldPointer::new(&mut world) };
+
+        let other = ptr.clone();
+        let a = ptr.read();
+        let b = other.read();
+        assert_eq!(a.get::<Health>(entity), b.get::<Health>(entity));
+    }
+
+    #[cfg(feature = "checked_world_access")]
+    #[test]
+    #[should_panic]
+    fn callback_escaping_handler() {
+        let (mut world, entity) = world_with_entity();
+
+        let escaped = {
+            let ptr = unsafe { WorldPointer::new(&mut world) };
+            let callback = {
+                let ptr = ptr.clone();
+                move || ptr.write().get_mut::<Health>(entity).unwrap().0 += 1
+            };
+            callback();
+            ptr.invalidate();
+            callback
+        };
+
+        world.get_mut::<Health>(entity).unwrap().0 += 1;
+        escaped();
     }
 }
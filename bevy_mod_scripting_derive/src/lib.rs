@@ -127,6 +127,89 @@ pub fn impl_script_newtype(input: TokenStream) -> TokenStream {
     output.into()
 }
 
+/// Registers the API provider it is placed on with the given host, when the host is added to the app.
+///
+/// Requires the `auto_register` feature of `bevy_mod_scripting_core`, the provider is added through
+/// `bevy_mod_scripting_core::auto_register::ScriptApiProviderRegistration`.
+/// Unit structs are constructed as is, other providers must implement `Default`.
+///
+/// # Example
+/// ```rust,ignore
+/// use bevy_mod_scripting_derive::script_api_provider;
+///
+/// #[script_api_provider(host = LuaScriptHost<MyArgs>)]
+/// pub struct InventoryAPIProvider;
+///
+/// impl APIProvider for InventoryAPIProvider {
+///     type APITarget = Mutex<Lua>;
+///     type ScriptContext = Mutex<Lua>;
+///     type DocTarget = LuaDocFragment;
+///     // ..
+/// }
+///
+/// // no need for `app.add_api_provider::<LuaScriptHost<MyArgs>>(Box::new(InventoryAPIProvider))`
+/// app.add_script_host::<LuaScriptHost<MyArgs>, _>(CoreStage::PostUpdate);
+/// ```
+#[proc_macro_attribute]
+pub fn script_api_provider(args: TokenStream, input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(args as ProviderArgs);
+    let item = parse_macro_input!(input as syn::ItemStruct);
+
+    if !item.generics.params.is_empty() {
+        return syn::Error::new_spanned(
+            &item.generics,
+            "Generic API providers cannot be registered automatically, add them with `add_api_provider` instead",
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    let ident = &item.ident;
+    let host = &args.host;
+    let constructor = match item.fields {
+        syn::Fields::Unit => quote::quote!(#ident),
+        _ => quote::quote!(<#ident as ::std::default::Default>::default()),
+    };
+
+    quote_spanned! {item.span()=>
+        #item
+
+        bevy_mod_scripting_core::auto_register::inventory::submit! {
+            bevy_mod_scripting_core::auto_register::ScriptApiProviderRegistration::new(
+                || ::std::any::TypeId::of::<#host>(),
+                |app| {
+                    bevy_mod_scripting_core::AddScriptApiProvider::add_api_provider::<#host>(
+                        app,
+                        ::std::boxed::Box::new(#constructor),
+                    );
+                },
+            )
+        }
+    }
+    .into()
+}
+
+/// The arguments of [`script_api_provider`], i.e. `host = LuaScriptHost<MyArgs>`
+pub(crate) struct ProviderArgs {
+    pub host: syn::Type,
+}
+
+impl Parse for ProviderArgs {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let key: syn::Ident = input.parse()?;
+        if key != "host" {
+            return Err(syn::Error::new_spanned(
+                key,
+                "Expected `host = ..` naming the script host of the provider",
+            ));
+        }
+        input.parse::<syn::Token![=]>()?;
+        Ok(Self {
+            host: input.parse()?,
+        })
+    }
+}
+
 pub(crate) struct MacroInvocation {
     pub languages: Attribute,
     pub inner: proc_macro2::TokenStream,
@@ -0,0 +1,214 @@
+use std::any::{type_name, TypeId};
+
+use bevy::{
+    ecs::event::{Event, Events, ManualEventReader},
+    prelude::{App, AppTypeRegistry, Mut, ReflectDefault, Resource},
+    reflect::{FromReflect, GetTypeRegistration, Reflect},
+    utils::{get_short_name, HashMap},
+};
+use bevy_mod_scripting_core::prelude::{ScriptError, ScriptId};
+
+use super::ScriptWorld;
+use crate::common::shared::ScriptValue;
+
+/// How scripts send and read the events of one type
+#[derive(Clone, Copy)]
+struct ScriptableEvent {
+    send: fn(&ScriptWorld, &ScriptValue) -> Result<(), ScriptError>,
+    read: fn(&ScriptWorld, ScriptId) -> Result<Vec<ScriptValue>, ScriptError>,
+}
+
+/// The Bevy event types scripts can send and read, by short and fully qualified type name,
+/// see [`RegisterScriptEvent`]
+#[derive(Resource, Default)]
+pub struct ScriptableEvents {
+    events: HashMap<String, ScriptableEvent>,
+}
+
+impl ScriptableEvents {
+    pub fn contains(&self, event: &str) -> bool {
+        self.events.contains_key(event)
+    }
+
+    fn get(&self, event: &str) -> Result<ScriptableEvent, ScriptError> {
+        self.events.get(event).copied().ok_or_else(|| {
+            ScriptError::Other(format!(
+                "No event named `{event}` can be used by scripts, see `register_script_event`"
+            ))
+        })
+    }
+}
+
+/// The position of each script in the events of type `E`, so every script reads each event once
+#[derive(Resource)]
+struct ScriptEventCursors<E: Event> {
+    readers: HashMap<ScriptId, ManualEventReader<E>>,
+}
+
+impl<E: Event> Default for ScriptEventCursors<E> {
+    fn default() -> Self {
+        Self {
+            readers: Default::default(),
+        }
+    }
+}
+
+pub trait RegisterScriptEvent {
+    /// Lets scripts send and read events of type `E`, converted from and to script values through reflection,
+    /// e.g. `world:send_event("Jump", { height = 2 })` in Lua. Sending `nil` sends the default value of types registered with `ReflectDefault`.
+    ///
+    /// Adds the event if it was not added yet.
+    fn register_script_event<E: Event + Reflect + FromReflect + GetTypeRegistration>(
+        &mut self,
+    ) -> &mut Self;
+
+    /// Lets scripts send and read events of type `E` which is not reflected, such as `AppExit`.
+    ///
+    /// Any value sent by scripts resolves to the default value of the event, and scripts read each event as an empty table or map.
+    fn register_default_script_event<E: Event + Default>(&mut self) -> &mut Self;
+}
+
+impl RegisterScriptEvent for App {
+    fn register_script_event<E: Event + Reflect + FromReflect + GetTypeRegistration>(
+        &mut self,
+    ) -> &mut Self {
+        self.register_type::<E>();
+        register_scriptable_event::<E>(
+            self,
+            ScriptableEvent {
+                send: send_reflected::<E>,
+                read: read_reflected::<E>,
+            },
+        );
+        self
+    }
+
+    fn register_default_script_event<E: Event + Default>(&mut self) -> &mut Self {
+        register_scriptable_event::<E>(
+            self,
+            ScriptableEvent {
+                send: send_default::<E>,
+                read: read_opaque::<E>,
+            },
+        );
+        self
+    }
+}
+
+fn register_scriptable_event<E: Event>(app: &mut App, event: ScriptableEvent) {
+    if !app.world.contains_resource::<Events<E>>() {
+        app.add_event::<E>();
+    }
+    let mut events = app
+        .world
+        .get_resource_or_insert_with(ScriptableEvents::default);
+    events
+        .events
+        .insert(get_short_name(type_name::<E>()), event);
+    events.events.insert(type_name::<E>().to_owned(), event);
+}
+
+fn send_event<E: Event>(world: &ScriptWorld, event: E) {
+    world.write().resource_mut::<Events<E>>().send(event);
+}
+
+fn send_reflected<E: Event + FromReflect>(
+    world: &ScriptWorld,
+    value: &ScriptValue,
+) -> Result<(), ScriptError> {
+    let default = match value {
+        ScriptValue::Nil => {
+            let w = world.read();
+            let registry = w.resource::<AppTypeRegistry>().read();
+            registry
+                .get_type_data::<ReflectDefault>(TypeId::of::<E>())
+                .map(|d| d.default())
+        }
+        _ => None,
+    };
+    let reflected = match default {
+        Some(reflected) => reflected,
+        None => world.build_value(type_name::<E>(), value)?,
+    };
+    let event = E::from_reflect(&*reflected).ok_or_else(|| {
+        ScriptError::Other(format!(
+            "Could not build a `{}` event from the given value",
+            get_short_name(type_name::<E>())
+        ))
+    })?;
+    send_event(world, event);
+    Ok(())
+}
+
+fn send_default<E: Event + Default>(
+    world: &ScriptWorld,
+    _value: &ScriptValue,
+) -> Result<(), ScriptError> {
+    send_event(world, E::default());
+    Ok(())
+}
+
+/// Reads the events of type `E` the script has not read yet
+fn read_events<E: Event, O>(
+    world: &ScriptWorld,
+    sid: ScriptId,
+    f: impl FnMut(&E) -> Result<O, ScriptError>,
+) -> Result<Vec<O>, ScriptError> {
+    let mut w = world.write();
+    w.init_resource::<ScriptEventCursors<E>>();
+    w.resource_scope(|w, mut cursors: Mut<ScriptEventCursors<E>>| {
+        cursors
+            .readers
+            .entry(sid)
+            .or_default()
+            .iter(w.resource::<Events<E>>())
+            .map(f)
+            .collect()
+    })
+}
+
+fn read_reflected<E: Event + Reflect>(
+    world: &ScriptWorld,
+    sid: ScriptId,
+) -> Result<Vec<ScriptValue>, ScriptError> {
+    read_events::<E, _>(world, sid, |event| {
+        ScriptValue::from_reflect(event).ok_or_else(|| {
+            ScriptError::Other(format!(
+                "`{}` events contain values which cannot be passed to scripts",
+                get_short_name(type_name::<E>())
+            ))
+        })
+    })
+}
+
+fn read_opaque<E: Event>(
+    world: &ScriptWorld,
+    sid: ScriptId,
+) -> Result<Vec<ScriptValue>, ScriptError> {
+    read_events::<E, _>(world, sid, |_| Ok(ScriptValue::Map(Default::default())))
+}
+
+impl ScriptWorld {
+    fn scriptable_event(&self, event: &str) -> Result<ScriptableEvent, ScriptError> {
+        let w = self.read();
+        w.get_resource::<ScriptableEvents>()
+            .ok_or_else(|| {
+                ScriptError::Other(
+                    "No events can be used by scripts, see `register_script_event`".to_owned(),
+                )
+            })?
+            .get(event)
+    }
+
+    /// Sends a Bevy event of the type with the given short or fully qualified name, built from the given value,
+    /// the type must have been registered with [`RegisterScriptEvent`]
+    pub fn send_event(&self, event: &str, value: &ScriptValue) -> Result<(), ScriptError> {
+        (self.scriptable_event(event)?.send)(self, value)
+    }
+
+    /// Reads the Bevy events of the type with the given name which the given script has not read yet,
+    /// which includes those sent in the previous frame the first time the script reads them
+    pub fn read_events(&self, sid: ScriptId, event: &str) -> Result<Vec<ScriptValue>, ScriptError> {
+        (self.scriptable_event(event)?.read)(self, sid)
+    }
+}
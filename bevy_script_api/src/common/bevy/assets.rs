@@ -0,0 +1,431 @@
+use std::{any::type_name, sync::Arc};
+
+use bevy::{
+    asset::{Asset, HandleId, LoadState},
+    prelude::{
+        AlphaMode, App, AppTypeRegistry, AssetServer, Assets, ColorMaterial, EventWriter, Handle,
+        HandleUntyped, Res, ResMut, Resource, StandardMaterial,
+    },
+    reflect::{GetTypeRegistration, Reflect},
+    utils::{get_short_name, HashMap},
+};
+use bevy_mod_scripting_core::prelude::{Recipients, ScriptError};
+
+use super::{color::color_from_value, ScriptWorld};
+use crate::common::shared::ScriptValue;
+
+/// Sent once an asset requested by a script through [`ScriptWorld::load_asset`] finished loading or failed to load
+#[derive(Debug, Clone)]
+pub struct ScriptAssetLoaded {
+    /// the scripts which requested the asset
+    pub recipients: Recipients,
+    /// the path the asset was loaded from, scripts identify their assets by it
+    pub path: String,
+    /// a strong handle to the asset
+    pub handle: HandleUntyped,
+    /// false if the asset failed to load
+    pub success: bool,
+}
+
+/// Assets requested by scripts which have not finished loading yet.
+///
+/// Requested assets are kept alive by [`ScriptAssetLoaded::handle`] once loaded, the app is responsible for keeping
+/// them around for longer if needed.
+#[derive(Debug, Default, Resource)]
+pub struct ScriptAssetLoads {
+    pending: Vec<(Recipients, String, HandleUntyped)>,
+}
+
+impl ScriptAssetLoads {
+    /// The number of assets still being loaded
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+}
+
+/// Sends [`ScriptAssetLoaded`] events for all pending script assets which finished loading
+pub fn track_script_asset_loads(
+    mut loads: ResMut<ScriptAssetLoads>,
+    server: Res<AssetServer>,
+    mut events: EventWriter<ScriptAssetLoaded>,
+) {
+    loads.pending.retain(|(recipients, path, handle)| {
+        let success = match server.get_load_state(handle) {
+            LoadState::Loaded => true,
+            LoadState::Failed => false,
+            _ => return true,
+        };
+
+        events.send(ScriptAssetLoaded {
+            recipients: recipients.clone(),
+            path: path.clone(),
+            handle: handle.clone(),
+            success,
+        });
+        false
+    });
+}
+
+/// Adds the asset tracking system and the [`ScriptAssetLoaded`] event, unless already added.
+///
+/// Called by the API providers forwarding asset events to scripts.
+pub fn register_script_asset_loads(app: &mut App) {
+    if app.world.contains_resource::<ScriptAssetLoads>() {
+        return;
+    }
+
+    app.init_resource::<ScriptAssetLoads>()
+        .add_event::<ScriptAssetLoaded>()
+        .add_system(track_script_asset_loads);
+}
+
+impl ScriptWorld {
+    /// Starts loading the asset at the given path, once it's loaded a [`ScriptAssetLoaded`] event is sent to the given recipients
+    pub fn load_asset(&self, path: &str, recipients: Recipients) -> Result<(), ScriptError> {
+        let mut w = self.write();
+        let handle = w
+            .get_resource::<AssetServer>()
+            .ok_or_else(|| ScriptError::Other("No `AssetServer` present".to_owned()))?
+            .load_untyped(path);
+
+        w.get_resource_mut::<ScriptAssetLoads>()
+            .ok_or_else(|| {
+                ScriptError::Other(
+                    "Asset events are not enabled, add an asset events API provider".to_owned(),
+                )
+            })?
+            .pending
+            .push((recipients, path.to_owned(), handle));
+        Ok(())
+    }
+}
+
+/// A handle to an asset passed to scripts, strong if the asset was loaded by the script so it stays loaded while the script holds on to it.
+///
+/// Scripts can assign it to `Handle<T>` fields of components, as long as the handle type is registered with the language,
+/// e.g. with `app.register_foreign_lua_type::<Handle<MyAsset>>()`. The asset API providers register the handles of bevy's own assets.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ScriptHandle(pub HandleUntyped);
+
+impl ScriptHandle {
+    pub fn id(&self) -> HandleId {
+        self.0.id
+    }
+
+    /// A handle of the given asset type pointing to the same asset, strong if this handle is strong.
+    ///
+    /// Handles loaded by path are not checked against the asset type, using one for the wrong type makes the typed handle point to nothing.
+    pub fn typed<T: Asset>(&self) -> Handle<T> {
+        self.0.clone().typed()
+    }
+}
+
+impl<T: Asset> From<&Handle<T>> for ScriptHandle {
+    fn from(handle: &Handle<T>) -> Self {
+        Self(handle.clone_untyped())
+    }
+}
+
+/// Access to the `AssetServer` for scripts, for loading assets and checking on their progress
+#[derive(Clone, Debug)]
+pub struct ScriptAssets(pub ScriptWorld);
+
+impl ScriptAssets {
+    fn with_server<O>(&self, f: impl FnOnce(&AssetServer) -> O) -> Result<O, ScriptError> {
+        let w = self.0.read();
+        w.get_resource::<AssetServer>()
+            .map(f)
+            .ok_or_else(|| ScriptError::Other("No `AssetServer` present".to_owned()))
+    }
+
+    /// Starts loading the asset at the given path relative to the assets folder, unless it is already loaded,
+    /// and returns a strong handle to it
+    pub fn load(&self, path: &str) -> Result<ScriptHandle, ScriptError> {
+        self.with_server(|server| ScriptHandle(server.load_untyped(path)))
+    }
+
+    /// The load state of the asset, one of `"NotLoaded"`, `"Loading"`, `"Loaded"`, `"Failed"` or `"Unloaded"`
+    pub fn load_state(&self, handle: &ScriptHandle) -> Result<&'static str, ScriptError> {
+        self.with_server(|server| match server.get_load_state(&handle.0) {
+            LoadState::NotLoaded => "NotLoaded",
+            LoadState::Loading => "Loading",
+            LoadState::Loaded => "Loaded",
+            LoadState::Failed => "Failed",
+            LoadState::Unloaded => "Unloaded",
+        })
+    }
+
+    /// The path the asset was loaded from, including its label if it is a labeled sub asset e.g. `"models/tree.gltf#Mesh0"`,
+    /// None for assets which were not loaded from a path
+    pub fn path(&self, handle: &ScriptHandle) -> Result<Option<String>, ScriptError> {
+        self.with_server(|server| {
+            server
+                .get_handle_path(&handle.0)
+                .map(|path| match path.label() {
+                    Some(label) => format!("{}#{label}", path.path().display()),
+                    None => path.path().display().to_string(),
+                })
+        })
+    }
+}
+
+/// Creates an asset from the value given by a script and adds it
+type ScriptAssetFn =
+    dyn Fn(&ScriptWorld, &ScriptValue) -> Result<ScriptHandle, ScriptError> + Send + Sync;
+
+/// The asset types scripts can create, by short and fully qualified type name, see [`RegisterScriptAsset`]
+#[derive(Resource, Default)]
+pub struct ScriptableAssets {
+    assets: HashMap<String, Arc<ScriptAssetFn>>,
+}
+
+impl ScriptableAssets {
+    pub fn contains(&self, asset: &str) -> bool {
+        self.assets.contains_key(asset)
+    }
+}
+
+pub trait RegisterScriptAsset {
+    /// Lets scripts create assets of type `A`, starting from the default value and setting the fields given by the script
+    /// through reflection, e.g. `world:add_asset("Palette", { primary = "#FF8800" })` in Lua. See [`ScriptValue::apply_to`].
+    ///
+    /// The asset type itself has to be added to the app with `add_asset`.
+    fn register_script_asset<A: Asset + Reflect + Default + GetTypeRegistration>(
+        &mut self,
+    ) -> &mut Self;
+
+    /// Lets scripts create assets of type `A` which is not reflected, built from the value given by the script with `build`
+    fn register_script_asset_with<A: Asset>(
+        &mut self,
+        build: fn(&ScriptValue) -> Result<A, ScriptError>,
+    ) -> &mut Self;
+}
+
+impl RegisterScriptAsset for App {
+    fn register_script_asset<A: Asset + Reflect + Default + GetTypeRegistration>(
+        &mut self,
+    ) -> &mut Self {
+        self.register_type::<A>();
+        register_scriptable_asset::<A>(self, Arc::new(add_reflected::<A>))
+    }
+
+    fn register_script_asset_with<A: Asset>(
+        &mut self,
+        build: fn(&ScriptValue) -> Result<A, ScriptError>,
+    ) -> &mut Self {
+        register_scriptable_asset::<A>(
+            self,
+            Arc::new(move |world: &ScriptWorld, value: &ScriptValue| {
+                add_script_asset(world, build(value)?)
+            }),
+        )
+    }
+}
+
+fn register_scriptable_asset<A: Asset>(app: &mut App, add: Arc<ScriptAssetFn>) -> &mut App {
+    let mut assets = app
+        .world
+        .get_resource_or_insert_with(ScriptableAssets::default);
+    assets
+        .assets
+        .insert(get_short_name(type_name::<A>()), add.clone());
+    assets.assets.insert(type_name::<A>().to_owned(), add);
+    app
+}
+
+fn add_script_asset<A: Asset>(world: &ScriptWorld, asset: A) -> Result<ScriptHandle, ScriptError> {
+    let mut w = world.write();
+    let handle = w
+        .get_resource_mut::<Assets<A>>()
+        .ok_or_else(|| {
+            ScriptError::Other(format!(
+                "No `Assets<{}>` present",
+                get_short_name(type_name::<A>())
+            ))
+        })?
+        .add(asset);
+    Ok(ScriptHandle::from(&handle))
+}
+
+fn add_reflected<A: Asset + Reflect + Default>(
+    world: &ScriptWorld,
+    value: &ScriptValue,
+) -> Result<ScriptHandle, ScriptError> {
+    let mut asset = A::default();
+    {
+        let w = world.read();
+        let registry = w.resource::<AppTypeRegistry>().read();
+        value
+            .apply_to(&mut asset, &registry)
+            .map_err(ScriptError::Other)?;
+    }
+    add_script_asset(world, asset)
+}
+
+/// The fields given by a script for a non reflected asset, erroring on fields the asset does not know
+fn asset_fields<'a>(
+    asset: &str,
+    value: &'a ScriptValue,
+    known: &[&str],
+) -> Result<Vec<(&'a str, &'a ScriptValue)>, ScriptError> {
+    let fields = match value {
+        ScriptValue::Nil => return Ok(Vec::default()),
+        ScriptValue::Map(fields) => fields,
+        _ => {
+            return Err(ScriptError::Other(format!(
+                "`{asset}` expects a map of the fields {{ {} }}, got a {}",
+                known.join(", "),
+                value.kind()
+            )))
+        }
+    };
+    fields
+        .iter()
+        .map(|(name, value)| match known.contains(&name.as_str()) {
+            true => Ok((name.as_str(), value)),
+            false => Err(ScriptError::Other(format!(
+                "`{asset}` has no field `{name}`, expected some of {{ {} }}",
+                known.join(", ")
+            ))),
+        })
+        .collect()
+}
+
+fn number_field(asset: &str, name: &str, value: &ScriptValue) -> Result<f32, ScriptError> {
+    match value {
+        ScriptValue::Integer(i) => Ok(*i as f32),
+        ScriptValue::Number(n) => Ok(*n as f32),
+        _ => Err(ScriptError::Other(format!(
+            "`{asset}.{name}` expects a number, got a {}",
+            value.kind()
+        ))),
+    }
+}
+
+fn bool_field(asset: &str, name: &str, value: &ScriptValue) -> Result<bool, ScriptError> {
+    match value {
+        ScriptValue::Bool(b) => Ok(*b),
+        _ => Err(ScriptError::Other(format!(
+            "`{asset}.{name}` expects a boolean, got a {}",
+            value.kind()
+        ))),
+    }
+}
+
+/// Builds a `StandardMaterial` from the colors (hex strings or lists of components) `base_color` and `emissive`,
+/// the numbers `perceptual_roughness`, `metallic` and `reflectance`, the booleans `double_sided` and `unlit`,
+/// and `alpha_mode`, one of `"Opaque"`, `"Blend"` or `"Mask"` with the threshold `alpha_cutoff` (0.5 by default).
+/// Fields which are not given keep their default value.
+pub fn standard_material_from_value(value: &ScriptValue) -> Result<StandardMaterial, ScriptError> {
+    const ASSET: &str = "StandardMaterial";
+    let mut material = StandardMaterial::default();
+    let mut alpha_cutoff = 0.5;
+    let mut alpha_mode = None;
+
+    for (name, value) in asset_fields(
+        ASSET,
+        value,
+        &[
+            "base_color",
+            "emissive",
+            "perceptual_roughness",
+            "metallic",
+            "reflectance",
+            "alpha_mode",
+            "alpha_cutoff",
+            "double_sided",
+            "unlit",
+        ],
+    )? {
+        match name {
+            "base_color" => material.base_color = color_from_value(value)?,
+            "emissive" => material.emissive = color_from_value(value)?,
+            "perceptual_roughness" => {
+                material.perceptual_roughness = number_field(ASSET, name, value)?
+            }
+            "metallic" => material.metallic = number_field(ASSET, name, value)?,
+            "reflectance" => material.reflectance = number_field(ASSET, name, value)?,
+            "alpha_cutoff" => alpha_cutoff = number_field(ASSET, name, value)?,
+            "double_sided" => material.double_sided = bool_field(ASSET, name, value)?,
+            "unlit" => material.unlit = bool_field(ASSET, name, value)?,
+            _ => alpha_mode = Some(value),
+        }
+    }
+
+    material.alpha_mode = match alpha_mode {
+        None => material.alpha_mode,
+        Some(ScriptValue::String(mode)) if mode == "Opaque" => AlphaMode::Opaque,
+        Some(ScriptValue::String(mode)) if mode == "Blend" => AlphaMode::Blend,
+        Some(ScriptValue::String(mode)) if mode == "Mask" => AlphaMode::Mask(alpha_cutoff),
+        Some(_) => {
+            return Err(ScriptError::Other(format!(
+                "`{ASSET}.alpha_mode` expects one of: \"Opaque\", \"Blend\", \"Mask\""
+            )))
+        }
+    };
+    Ok(material)
+}
+
+/// Builds a `ColorMaterial` from its `color`, a hex string or a list of components
+pub fn color_material_from_value(value: &ScriptValue) -> Result<ColorMaterial, ScriptError> {
+    let mut material = ColorMaterial::default();
+    for (_, value) in asset_fields("ColorMaterial", value, &["color"])? {
+        material.color = color_from_value(value)?;
+    }
+    Ok(material)
+}
+
+impl ScriptWorld {
+    /// Creates an asset of the type with the given short or fully qualified name from the value given by a script
+    /// and returns a strong handle to it, the type must have been registered with [`RegisterScriptAsset`]
+    pub fn add_asset(&self, asset: &str, value: &ScriptValue) -> Result<ScriptHandle, ScriptError> {
+        let add = {
+            let w = self.read();
+            w.get_resource::<ScriptableAssets>()
+                .and_then(|assets| assets.assets.get(asset).cloned())
+                .ok_or_else(|| {
+                    ScriptError::Other(format!(
+                        "No asset named `{asset}` can be created by scripts, see `register_script_asset`"
+                    ))
+                })?
+        };
+        add(self, value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::{AssetPlugin, MinimalPlugins};
+    use bevy_mod_scripting_core::world::WorldPointer;
+
+    use super::*;
+
+    #[test]
+    fn script_asset_loads_are_tracked_once_enabled() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugin(AssetPlugin::default());
+        // safety: the world outlives the pointer, which is only used in this test
+        let script_world = ScriptWorld::new(unsafe { WorldPointer::new(&mut app.world) });
+
+        let disabled = script_world
+            .load_asset("textures/icon.png", Recipients::All)
+            .unwrap_err()
+            .to_string();
+        assert!(disabled.contains("not enabled"), "{disabled}");
+
+        register_script_asset_loads(&mut app);
+        register_script_asset_loads(&mut app);
+        script_world
+            .load_asset("textures/icon.png", Recipients::All)
+            .unwrap();
+
+        let loads = app.world.resource::<ScriptAssetLoads>();
+        assert_eq!(loads.len(), 1);
+        assert_eq!(loads.pending[0].1, "textures/icon.png");
+    }
+}
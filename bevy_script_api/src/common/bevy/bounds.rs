@@ -0,0 +1,155 @@
+use bevy::{
+    prelude::{
+        Assets, Entity, GlobalTransform, Handle, Image, Rect, Sprite, TextureAtlas,
+        TextureAtlasSprite, Vec2, Vec3, Vec3A, World,
+    },
+    render::primitives::Aabb,
+};
+use bevy_mod_scripting_core::prelude::ScriptError;
+
+use super::ScriptWorld;
+
+/// Returns true if the two boxes overlap, touching boxes count as overlapping
+pub fn aabb_intersects(a: &Aabb, b: &Aabb) -> bool {
+    (a.min().cmple(b.max()) & a.max().cmpge(b.min())).all()
+}
+
+/// Returns true if the point lies inside the box or on its surface
+pub fn aabb_contains(aabb: &Aabb, point: Vec3) -> bool {
+    let point = Vec3A::from(point);
+    (aabb.min().cmple(point) & aabb.max().cmpge(point)).all()
+}
+
+/// Returns true if the two rectangles overlap, touching rectangles count as overlapping
+pub fn rect_overlaps(a: &Rect, b: &Rect) -> bool {
+    (a.min.cmple(b.max) & a.max.cmpge(b.min)).all()
+}
+
+/// Transforms a local space box into an axis aligned box in world space which encloses it
+fn transform_aabb(aabb: &Aabb, transform: &GlobalTransform) -> Aabb {
+    let (min, max) = (aabb.min(), aabb.max());
+    let (world_min, world_max) = (0..8)
+        .map(|i| {
+            let corner = Vec3A::new(
+                if i & 1 == 0 { min.x } else { max.x },
+                if i & 2 == 0 { min.y } else { max.y },
+                if i & 4 == 0 { min.z } else { max.z },
+            );
+            transform.affine().transform_point3a(corner)
+        })
+        .fold(
+            (Vec3A::splat(f32::MAX), Vec3A::splat(f32::MIN)),
+            |(lo, hi), p| (lo.min(p), hi.max(p)),
+        );
+
+    Aabb::from_min_max(world_min.into(), world_max.into())
+}
+
+/// The local space size of a sprite, if it's known
+fn sprite_size(world: &World, entity: Entity) -> Option<Vec2> {
+    let entity = world.get_entity(entity)?;
+
+    if let Some(sprite) = entity.get::<Sprite>() {
+        return sprite.custom_size.or_else(|| {
+            let image = entity.get::<Handle<Image>>()?;
+            world
+                .get_resource::<Assets<Image>>()?
+                .get(image)
+                .map(|image| image.size())
+        });
+    }
+
+    let sprite = entity.get::<TextureAtlasSprite>()?;
+    sprite.custom_size.or_else(|| {
+        let atlas = entity.get::<Handle<TextureAtlas>>()?;
+        world
+            .get_resource::<Assets<TextureAtlas>>()?
+            .get(atlas)?
+            .textures
+            .get(sprite.index)
+            .map(|rect| rect.size())
+    })
+}
+
+impl ScriptWorld {
+    /// Computes the world space bounding box of the entity from its `Aabb` (present on meshes) or sprite size, and its `GlobalTransform`.
+    ///
+    /// Returns None if the entity has no transform or its bounds are unknown.
+    pub fn world_aabb(&self, entity: Entity) -> Result<Option<Aabb>, ScriptError> {
+        self.validate_entity(entity)?;
+        let w = self.read();
+
+        let transform = match w.get::<GlobalTransform>(entity) {
+            Some(t) => t,
+            None => return Ok(None),
+        };
+
+        let local = w.get::<Aabb>(entity).cloned().or_else(|| {
+            sprite_size(&w, entity).map(|size| Aabb {
+                center: Vec3A::ZERO,
+                half_extents: (size / 2.0).extend(0.0).into(),
+            })
+        });
+
+        Ok(local.map(|aabb| transform_aabb(&aabb, transform)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::{Quat, Transform};
+    use bevy_mod_scripting_core::world::WorldPointer;
+
+    use super::*;
+
+    #[test]
+    fn touching_boxes_and_rectangles_overlap() {
+        let a = Aabb::from_min_max(Vec3::ZERO, Vec3::ONE);
+        let touching = Aabb::from_min_max(Vec3::ONE, Vec3::splat(2.0));
+        let apart = Aabb::from_min_max(Vec3::splat(1.5), Vec3::splat(2.0));
+        assert!(aabb_intersects(&a, &touching));
+        assert!(!aabb_intersects(&a, &apart));
+
+        assert!(aabb_contains(&a, Vec3::splat(0.5)));
+        assert!(aabb_contains(&a, Vec3::ONE));
+        assert!(!aabb_contains(&a, Vec3::new(0.5, 0.5, 1.5)));
+
+        let r = Rect::new(0.0, 0.0, 1.0, 1.0);
+        assert!(rect_overlaps(&r, &Rect::new(1.0, 1.0, 2.0, 2.0)));
+        assert!(!rect_overlaps(&r, &Rect::new(1.5, 0.0, 2.0, 1.0)));
+    }
+
+    #[test]
+    fn sprite_bounds_follow_the_global_transform() {
+        let mut world = World::new();
+        let transform = Transform::from_xyz(10.0, 0.0, 0.0)
+            .with_rotation(Quat::from_rotation_z(std::f32::consts::FRAC_PI_2));
+        let sprite = world
+            .spawn((
+                Sprite {
+                    custom_size: Some(Vec2::new(4.0, 2.0)),
+                    ..Default::default()
+                },
+                GlobalTransform::from(transform),
+            ))
+            .id();
+        let unknown = world.spawn(GlobalTransform::default()).id();
+        // safety: the world outlives the pointer, which is only used in this test
+        let script_world = ScriptWorld::new(unsafe { WorldPointer::new(&mut world) });
+
+        let aabb = script_world.world_aabb(sprite).unwrap().unwrap();
+        assert!(
+            (aabb.min() - Vec3A::new(9.0, -2.0, 0.0))
+                .abs()
+                .max_element()
+                < 1e-5
+        );
+        assert!(
+            (aabb.max() - Vec3A::new(11.0, 2.0, 0.0))
+                .abs()
+                .max_element()
+                < 1e-5
+        );
+        assert!(script_world.world_aabb(unknown).unwrap().is_none());
+    }
+}
@@ -0,0 +1,87 @@
+//! Color helpers shared by the `Color` proxies of all script hosts
+use bevy::prelude::Color;
+use bevy_mod_scripting_core::prelude::ScriptError;
+
+use crate::common::shared::ScriptValue;
+
+/// Parses a hex color such as `"#FF8800"`, `"F80"` or `"FF8800CC"`
+pub fn color_from_hex(hex: &str) -> Result<Color, ScriptError> {
+    Color::hex(hex.trim_start_matches('#'))
+        .map_err(|e| ScriptError::Other(format!("Invalid hex color `{hex}`: {e}")))
+}
+
+/// Formats the color as an sRGB hex string in the form `"#RRGGBBAA"`
+pub fn color_to_hex(color: Color) -> String {
+    let [r, g, b, a] = color
+        .as_rgba_f32()
+        .map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8);
+    format!("#{r:02X}{g:02X}{b:02X}{a:02X}")
+}
+
+/// Linearly interpolates between two colors in the sRGB colorspace, the result is an `Rgba` color
+pub fn lerp_color(a: Color, b: Color, t: f32) -> Color {
+    let [ar, ag, ab, aa] = a.as_rgba_f32();
+    let [br, bg, bb, ba] = b.as_rgba_f32();
+    Color::rgba(
+        ar + (br - ar) * t,
+        ag + (bg - ag) * t,
+        ab + (bb - ab) * t,
+        aa + (ba - aa) * t,
+    )
+}
+
+/// Reads a color given by a script as a hex string, or as a list of 3 or 4 sRGB components in the range [0,1]
+pub fn color_from_value(value: &ScriptValue) -> Result<Color, ScriptError> {
+    let components = match value {
+        ScriptValue::String(hex) => return color_from_hex(hex),
+        ScriptValue::List(components) if matches!(components.len(), 3 | 4) => components
+            .iter()
+            .map(|c| match c {
+                ScriptValue::Integer(i) => Ok(*i as f32),
+                ScriptValue::Number(n) => Ok(*n as f32),
+                c => Err(ScriptError::Other(format!(
+                    "Color components are numbers, got a {}",
+                    c.kind()
+                ))),
+            })
+            .collect::<Result<Vec<_>, _>>()?,
+        _ => {
+            return Err(ScriptError::Other(format!(
+                "Expected a hex color or a list of 3 or 4 color components, got a {}",
+                value.kind()
+            )))
+        }
+    };
+    Ok(Color::rgba(
+        components[0],
+        components[1],
+        components[2],
+        components.get(3).copied().unwrap_or(1.0),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hex_colors_round_trip() {
+        let color = color_from_hex("#FF8800CC").unwrap();
+        assert_eq!(color, Color::rgba_u8(0xFF, 0x88, 0x00, 0xCC));
+        assert_eq!(color_to_hex(color), "#FF8800CC");
+        assert_eq!(color_to_hex(color_from_hex("FF8800").unwrap()), "#FF8800FF");
+
+        let invalid = color_from_hex("#GG0000").unwrap_err().to_string();
+        assert!(invalid.contains("`#GG0000`"), "{invalid}");
+    }
+
+    #[test]
+    fn lerps_between_colors() {
+        assert_eq!(lerp_color(Color::BLACK, Color::WHITE, 0.0), Color::BLACK);
+        assert_eq!(
+            lerp_color(Color::BLACK, Color::WHITE, 0.5),
+            Color::rgba(0.5, 0.5, 0.5, 1.0)
+        );
+        assert_eq!(lerp_color(Color::BLACK, Color::WHITE, 1.0), Color::WHITE);
+    }
+}
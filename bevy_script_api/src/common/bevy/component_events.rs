@@ -0,0 +1,108 @@
+use std::any::TypeId;
+
+use bevy::{
+    prelude::{
+        Added, App, Component, CoreStage, Entity, EventWriter, Query, RemovedComponents, Resource,
+    },
+    utils::{get_short_name, HashSet},
+};
+
+/// Sent whenever a component of a type registered with [`register_script_component_events`] is added to or removed from an entity
+#[derive(Debug, Clone)]
+pub struct ScriptComponentEvent {
+    /// the short type name of the component, e.g. `Health`
+    pub component: String,
+    pub entity: Entity,
+    /// true if the component was added, false if it was removed
+    pub added: bool,
+}
+
+/// The component types for which [`ScriptComponentEvent`]s are sent
+#[derive(Debug, Default, Resource)]
+pub struct ScriptComponentEventTypes {
+    types: HashSet<TypeId>,
+}
+
+impl ScriptComponentEventTypes {
+    pub fn contains<T: Component>(&self) -> bool {
+        self.types.contains(&TypeId::of::<T>())
+    }
+}
+
+/// Sends [`ScriptComponentEvent`]s for every component of type `T` added or removed this frame
+pub fn send_script_component_events<T: Component>(
+    added: Query<Entity, Added<T>>,
+    removed: RemovedComponents<T>,
+    mut events: EventWriter<ScriptComponentEvent>,
+) {
+    let component = get_short_name(std::any::type_name::<T>());
+
+    let added = added.iter().map(|entity| (entity, true));
+    let removed = removed.iter().map(|entity| (entity, false));
+    for (entity, added) in added.chain(removed) {
+        events.send(ScriptComponentEvent {
+            component: component.clone(),
+            entity,
+            added,
+        });
+    }
+}
+
+/// Adds the [`ScriptComponentEvent`] event and the system sending it for components of type `T`, unless already added.
+///
+/// Removals are only visible until the end of the frame, so the system runs in [`CoreStage::Last`] to catch removals from all
+/// other stages. Called by the API providers forwarding component events to scripts.
+pub fn register_script_component_events<T: Component>(app: &mut App) {
+    if !app.world.contains_resource::<ScriptComponentEventTypes>() {
+        app.init_resource::<ScriptComponentEventTypes>()
+            .add_event::<ScriptComponentEvent>();
+    }
+
+    let newly_registered = app
+        .world
+        .resource_mut::<ScriptComponentEventTypes>()
+        .types
+        .insert(TypeId::of::<T>());
+
+    if newly_registered {
+        app.add_system_to_stage(CoreStage::Last, send_script_component_events::<T>);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::Events;
+
+    use super::*;
+
+    #[derive(Component)]
+    struct Health;
+
+    #[test]
+    fn sends_an_event_per_addition_and_removal() {
+        let mut app = App::new();
+        register_script_component_events::<Health>(&mut app);
+        register_script_component_events::<Health>(&mut app);
+        assert!(app
+            .world
+            .resource::<ScriptComponentEventTypes>()
+            .contains::<Health>());
+
+        let entity = app.world.spawn(Health).id();
+        app.update();
+        app.world.entity_mut(entity).remove::<Health>();
+        app.update();
+
+        let events = app.world.resource::<Events<ScriptComponentEvent>>();
+        let sent = events
+            .get_reader()
+            .iter(events)
+            .map(|e| (e.component.as_str(), e.entity, e.added))
+            .collect::<Vec<_>>();
+        // registering the type twice does not duplicate events
+        assert_eq!(
+            sent,
+            vec![("Health", entity, true), ("Health", entity, false)]
+        );
+    }
+}
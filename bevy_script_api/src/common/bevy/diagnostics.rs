@@ -0,0 +1,53 @@
+use bevy::diagnostic::{Diagnostic, Diagnostics};
+
+use super::ScriptWorld;
+
+/// Read-only access to the diagnostics of the app, for performance overlays and adaptive behaviour in scripts.
+///
+/// Diagnostics are looked up by name, so the ones added by bevy's diagnostic plugins are available as
+/// `"fps"`, `"frame_time"` and `"frame_count"` (`FrameTimeDiagnosticsPlugin`), or `"entity_count"` (`EntityCountDiagnosticsPlugin`),
+/// along with any the game registers itself. A diagnostic which is not registered or has no measurements yet reads as none.
+#[derive(Clone, Debug)]
+pub struct ScriptDiagnostics(pub ScriptWorld);
+
+impl ScriptDiagnostics {
+    fn with_diagnostic<O, F: FnOnce(&Diagnostic) -> Option<O>>(
+        &self,
+        name: &str,
+        f: F,
+    ) -> Option<O> {
+        let w = self.0.read();
+        w.get_resource::<Diagnostics>()?
+            .iter()
+            .find(|d| d.name == name)
+            .and_then(f)
+    }
+
+    /// The smoothed value of the diagnostic, which is what overlays usually display
+    pub fn get(&self, name: &str) -> Option<f64> {
+        self.with_diagnostic(name, |d| d.smoothed())
+    }
+
+    /// The latest measurement of the diagnostic
+    pub fn value(&self, name: &str) -> Option<f64> {
+        self.with_diagnostic(name, |d| d.value())
+    }
+
+    /// The average of the measurements in the history of the diagnostic
+    pub fn average(&self, name: &str) -> Option<f64> {
+        self.with_diagnostic(name, |d| d.average())
+    }
+
+    /// The names of all registered diagnostics
+    pub fn names(&self) -> Vec<String> {
+        let w = self.0.read();
+        w.get_resource::<Diagnostics>()
+            .map(|diagnostics| diagnostics.iter().map(|d| d.name.to_string()).collect())
+            .unwrap_or_default()
+    }
+
+    /// The number of entities in the world, which unlike the `"entity_count"` diagnostic is always available and up to date
+    pub fn entity_count(&self) -> usize {
+        self.0.read().entities().len() as usize
+    }
+}
@@ -0,0 +1,191 @@
+use bevy::prelude::{BuildWorldChildren, Children, Entity, Name, Parent};
+
+use super::ScriptWorld;
+
+/// Lazily walks the entities below an entity depth first, parents before their children.
+///
+/// Each step reads the world anew and only keeps the path to the current entity,
+/// so walking large hierarchies does not collect them first, and changes made while walking are seen by later steps.
+#[derive(Clone)]
+pub struct Descendants {
+    world: ScriptWorld,
+    /// the entities on the path to the current entity alongside the index of their next child
+    stack: Vec<(Entity, usize)>,
+}
+
+impl Iterator for Descendants {
+    type Item = Entity;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let w = self.world.read();
+        while let Some((parent, next_child)) = self.stack.last_mut() {
+            match w.get::<Children>(*parent).and_then(|c| c.get(*next_child)) {
+                Some(child) => {
+                    let child = *child;
+                    *next_child += 1;
+                    self.stack.push((child, 0));
+                    return Some(child);
+                }
+                None => {
+                    self.stack.pop();
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Lazily walks the parents of an entity up to the root of its hierarchy
+#[derive(Clone)]
+pub struct Ancestors {
+    world: ScriptWorld,
+    current: Entity,
+}
+
+impl Iterator for Ancestors {
+    type Item = Entity;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let parent = self.world.get_parent(self.current)?;
+        self.current = parent;
+        Some(parent)
+    }
+}
+
+impl ScriptWorld {
+    /// Returns a lazy iterator over the children of the entity, their children and so on, depth first
+    pub fn descendants(&self, entity: Entity) -> Descendants {
+        Descendants {
+            world: self.clone(),
+            stack: vec![(entity, 0)],
+        }
+    }
+
+    /// Returns a lazy iterator over the parent of the entity, its parent and so on
+    pub fn ancestors(&self, entity: Entity) -> Ancestors {
+        Ancestors {
+            world: self.clone(),
+            current: entity,
+        }
+    }
+
+    /// Makes `parent` the parent of the entity, removing it from the children of its previous parent.
+    ///
+    /// Returns false without changing anything if either entity does not exist,
+    /// or if `parent` is the entity itself or one of its descendants, which would create a cycle.
+    pub fn set_parent(&self, entity: Entity, parent: Entity) -> bool {
+        if entity == parent || self.ancestors(parent).any(|ancestor| ancestor == entity) {
+            return false;
+        }
+        let mut w = self.write();
+        if w.get_entity(entity).is_none() {
+            return false;
+        }
+        match w.get_entity_mut(parent) {
+            Some(mut parent) => {
+                parent.push_children(&[entity]);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Removes the entity from the children of its parent, making it the root of its own hierarchy.
+    /// Returns false if the entity had no parent
+    pub fn detach(&self, entity: Entity) -> bool {
+        match self.get_parent(entity) {
+            Some(parent) => {
+                self.remove_children(parent, &[entity]);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Finds the first descendant of the entity, depth first, whose `Name` component is the given name
+    pub fn find_child_by_name(&self, entity: Entity, name: &str) -> Option<Entity> {
+        self.descendants(entity).find(|child| {
+            self.read()
+                .get::<Name>(*child)
+                .map_or(false, |n| n.as_str() == name)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::{BuildWorldChildren, World};
+    use bevy_mod_scripting_core::world::WorldPointer;
+
+    use super::*;
+
+    #[test]
+    fn walks_the_hierarchy_depth_first() {
+        let mut world = World::new();
+        let root = world.spawn(()).id();
+        let a = world.spawn(()).id();
+        let a1 = world.spawn(Name::new("hand")).id();
+        let b = world.spawn(Name::new("hand")).id();
+        world.entity_mut(a).push_children(&[a1]);
+        world.entity_mut(root).push_children(&[a, b]);
+
+        // safety: the world outlives the pointer, which is only used in this test
+        let script_world = ScriptWorld::new(unsafe { WorldPointer::new(&mut world) });
+
+        assert_eq!(
+            script_world.descendants(root).collect::<Vec<_>>(),
+            vec![a, a1, b]
+        );
+        assert_eq!(
+            script_world.ancestors(a1).collect::<Vec<_>>(),
+            vec![a, root]
+        );
+        assert_eq!(script_world.find_child_by_name(root, "hand"), Some(a1));
+        assert_eq!(script_world.find_child_by_name(a1, "hand"), None);
+    }
+
+    #[test]
+    fn reparents_without_cycles() {
+        let mut world = World::new();
+        let root = world.spawn(()).id();
+        let a = world.spawn(()).id();
+        let b = world.spawn(()).id();
+        world.entity_mut(root).push_children(&[a]);
+
+        // safety: the world outlives the pointer, which is only used in this test
+        let script_world = ScriptWorld::new(unsafe { WorldPointer::new(&mut world) });
+
+        assert!(script_world.set_parent(b, a));
+        assert_eq!(script_world.get_parent(b), Some(a));
+        assert!(!script_world.set_parent(root, b));
+        assert!(!script_world.set_parent(a, a));
+
+        assert!(script_world.set_parent(b, root));
+        assert_eq!(script_world.get_children(root), vec![a, b]);
+        assert!(script_world.get_children(a).is_empty());
+
+        assert!(script_world.detach(a));
+        assert!(!script_world.detach(a));
+        assert_eq!(script_world.get_parent(a), None);
+        assert_eq!(script_world.get_children(root), vec![b]);
+    }
+
+    #[test]
+    fn despawns_recursively_once_flushed() {
+        let mut world = World::new();
+        let root = world.spawn(()).id();
+        let child = world.spawn(()).id();
+        world.entity_mut(root).push_children(&[child]);
+
+        // safety: the world outlives the pointer, which is only used in this test
+        let script_world = ScriptWorld::new(unsafe { WorldPointer::new(&mut world) });
+
+        script_world.despawn_recursive_deferred(root);
+        assert!(script_world.is_entity_valid(root));
+        assert!(script_world.is_entity_valid(child));
+
+        script_world.flush();
+        assert!(!script_world.is_entity_valid(root));
+        assert!(!script_world.is_entity_valid(child));
+    }
+}
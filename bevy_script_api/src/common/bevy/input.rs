@@ -0,0 +1,139 @@
+use std::{fmt::Debug, hash::Hash};
+
+use bevy::{
+    input::{
+        gamepad::{Gamepad, GamepadAxis, GamepadAxisType, GamepadButton, Gamepads},
+        mouse::MouseMotion,
+    },
+    prelude::{
+        App, Axis, CoreStage, EventReader, Input, KeyCode, MouseButton, ResMut, Resource, Vec2,
+    },
+    window::Windows,
+};
+use bevy_mod_scripting_core::prelude::ScriptError;
+
+use super::ScriptWorld;
+
+/// The distance the mouse moved this frame, summed from the `MouseMotion` events of the frame
+#[derive(Debug, Default, Resource)]
+pub struct ScriptMouseMotion(pub Vec2);
+
+/// Sums up the mouse motion of the current frame into [`ScriptMouseMotion`]
+pub fn track_script_mouse_motion(
+    mut motion: ResMut<ScriptMouseMotion>,
+    mut events: EventReader<MouseMotion>,
+) {
+    motion.0 = events.iter().map(|e| e.delta).sum();
+}
+
+/// Adds the [`ScriptMouseMotion`] resource and the system tracking it, unless already added.
+/// Called by the input API providers
+pub fn register_script_input(app: &mut App) {
+    if !app.world.contains_resource::<ScriptMouseMotion>() {
+        app.init_resource::<ScriptMouseMotion>()
+            .add_system_to_stage(CoreStage::PreUpdate, track_script_mouse_motion);
+    }
+}
+
+/// Read-only access to the keyboard, mouse and gamepads, backed by bevy's `Input` and `Axis` resources.
+///
+/// Keys and buttons are named after their bevy variants, e.g. `"Space"`, `"A"`, `"Key1"` or `"LShift"` for [`KeyCode`],
+/// `"Left"`, `"Right"` or `"Middle"` for [`MouseButton`], and `"South"` or `"LeftTrigger"` for gamepad buttons.
+/// Keys which do not exist read as not pressed.
+#[derive(Clone, Debug)]
+pub struct ScriptInput(pub ScriptWorld);
+
+/// Which state of an input to check
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InputState {
+    Pressed,
+    JustPressed,
+    JustReleased,
+}
+
+impl ScriptInput {
+    /// Checks whether any input of the given type matching `matches` is in the given state
+    fn check<T: Copy + Eq + Hash + Send + Sync + 'static>(
+        &self,
+        state: InputState,
+        matches: impl Fn(&T) -> bool,
+    ) -> bool {
+        let w = self.0.read();
+        let input = match w.get_resource::<Input<T>>() {
+            Some(input) => input,
+            None => return false,
+        };
+        let mut inputs: Box<dyn Iterator<Item = &T>> = match state {
+            InputState::Pressed => Box::new(input.get_pressed()),
+            InputState::JustPressed => Box::new(input.get_just_pressed()),
+            InputState::JustReleased => Box::new(input.get_just_released()),
+        };
+        inputs.any(matches)
+    }
+
+    /// Checks the key with the given name, e.g. `"Space"`
+    pub fn key(&self, state: InputState, key: &str) -> bool {
+        self.check::<KeyCode>(state, |k| debug_name(k) == key)
+    }
+
+    /// Checks the mouse button with the given name, e.g. `"Left"`
+    pub fn mouse_button(&self, state: InputState, button: &str) -> bool {
+        self.check::<MouseButton>(state, |b| debug_name(b) == button)
+    }
+
+    /// Checks the button with the given name of the gamepad with the given ID, e.g. `"South"`
+    pub fn gamepad_button(&self, state: InputState, gamepad: usize, button: &str) -> bool {
+        self.check::<GamepadButton>(state, |b| {
+            b.gamepad.id == gamepad && debug_name(&b.button_type) == button
+        })
+    }
+
+    /// The logical position of the cursor within the primary window, None if the cursor is outside of the window
+    pub fn mouse_position(&self) -> Option<Vec2> {
+        let w = self.0.read();
+        w.get_resource::<Windows>()?
+            .get_primary()?
+            .cursor_position()
+    }
+
+    /// The distance the mouse moved this frame, regardless of the cursor being in a window or grabbed
+    pub fn mouse_delta(&self) -> Vec2 {
+        let w = self.0.read();
+        w.get_resource::<ScriptMouseMotion>()
+            .map(|m| m.0)
+            .unwrap_or_default()
+    }
+
+    /// The IDs of the connected gamepads
+    pub fn gamepads(&self) -> Vec<usize> {
+        let w = self.0.read();
+        w.get_resource::<Gamepads>()
+            .map(|gamepads| gamepads.iter().map(|g| g.id).collect())
+            .unwrap_or_default()
+    }
+
+    /// The position of the axis with the given name of the gamepad with the given ID, between -1 and 1, e.g. `"LeftStickX"`
+    pub fn gamepad_axis(&self, gamepad: usize, axis: &str) -> Result<f32, ScriptError> {
+        let axis_type = match axis {
+            "LeftStickX" => GamepadAxisType::LeftStickX,
+            "LeftStickY" => GamepadAxisType::LeftStickY,
+            "LeftZ" => GamepadAxisType::LeftZ,
+            "RightStickX" => GamepadAxisType::RightStickX,
+            "RightStickY" => GamepadAxisType::RightStickY,
+            "RightZ" => GamepadAxisType::RightZ,
+            _ => {
+                return Err(ScriptError::Other(format!(
+                    "Unknown gamepad axis `{axis}`, expected one of LeftStickX, LeftStickY, LeftZ, RightStickX, RightStickY or RightZ"
+                )))
+            }
+        };
+        let w = self.0.read();
+        Ok(w.get_resource::<Axis<GamepadAxis>>()
+            .and_then(|axes| axes.get(GamepadAxis::new(Gamepad::new(gamepad), axis_type)))
+            .unwrap_or_default())
+    }
+}
+
+fn debug_name(value: &impl Debug) -> String {
+    format!("{value:?}")
+}
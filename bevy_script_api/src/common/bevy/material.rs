@@ -0,0 +1,141 @@
+use bevy::prelude::{Assets, Color, Entity, Handle, Mut, StandardMaterial, World};
+use bevy_mod_scripting_core::prelude::ScriptError;
+
+use super::ScriptWorld;
+
+/// PBR material tweaking, changes apply to the `StandardMaterial` of the given entity only.
+///
+/// Materials shared with other entities are cloned into a new asset on first modification,
+/// so that changing the look of one entity does not affect all others using the same material.
+impl ScriptWorld {
+    fn materials(w: &mut World) -> Result<Mut<Assets<StandardMaterial>>, ScriptError> {
+        w.get_resource_mut::<Assets<StandardMaterial>>()
+            .ok_or_else(|| ScriptError::Other("No `Assets<StandardMaterial>` present".to_owned()))
+    }
+
+    fn with_unique_material<F: FnOnce(&mut StandardMaterial)>(
+        &self,
+        entity: Entity,
+        f: F,
+    ) -> Result<(), ScriptError> {
+        self.validate_entity(entity)?;
+        let mut w = self.write();
+
+        let handle = w
+            .get::<Handle<StandardMaterial>>(entity)
+            .cloned()
+            .ok_or_else(|| {
+                ScriptError::Other(format!(
+                    "Entity {entity:?} has no `Handle<StandardMaterial>` component"
+                ))
+            })?;
+
+        let mut handles = w.query::<&Handle<StandardMaterial>>();
+        let shared = handles
+            .iter(&w)
+            .filter(|other| other.id() == handle.id())
+            .nth(1)
+            .is_some();
+
+        let handle = if shared {
+            let unique = {
+                let mut materials = Self::materials(&mut w)?;
+                let material = materials
+                    .get(&handle)
+                    .cloned()
+                    .ok_or_else(|| ScriptError::Other("Material is not loaded yet".to_owned()))?;
+                materials.add(material)
+            };
+            w.entity_mut(entity).insert(unique.clone());
+            unique
+        } else {
+            handle
+        };
+
+        let mut materials = Self::materials(&mut w)?;
+        let material = materials
+            .get_mut(&handle)
+            .ok_or_else(|| ScriptError::Other("Material is not loaded yet".to_owned()))?;
+        f(material);
+        Ok(())
+    }
+
+    /// Sets the base color of the entity's material
+    pub fn set_material_base_color(&self, entity: Entity, color: Color) -> Result<(), ScriptError> {
+        self.with_unique_material(entity, |m| m.base_color = color)
+    }
+
+    /// Sets the emissive color of the entity's material
+    pub fn set_material_emissive(&self, entity: Entity, color: Color) -> Result<(), ScriptError> {
+        self.with_unique_material(entity, |m| m.emissive = color)
+    }
+
+    /// Sets the metallic and perceptual roughness factors of the entity's material, both in the range [0,1]
+    pub fn set_material_metallic_roughness(
+        &self,
+        entity: Entity,
+        metallic: f32,
+        roughness: f32,
+    ) -> Result<(), ScriptError> {
+        self.with_unique_material(entity, |m| {
+            m.metallic = metallic.clamp(0.0, 1.0);
+            m.perceptual_roughness = roughness.clamp(0.0, 1.0);
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::{AddAsset, App, AssetPlugin, MinimalPlugins};
+    use bevy_mod_scripting_core::world::WorldPointer;
+
+    use super::*;
+
+    #[test]
+    fn shared_materials_are_cloned_before_changes() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugin(AssetPlugin::default())
+            .add_asset::<StandardMaterial>();
+        let world = &mut app.world;
+
+        let shared = world
+            .resource_mut::<Assets<StandardMaterial>>()
+            .add(Color::WHITE.into());
+        let tinted = world.spawn(shared.clone()).id();
+        let untouched = world.spawn(shared.clone()).id();
+        // safety: the world outlives the pointer, which is only used in this test
+        let script_world = ScriptWorld::new(unsafe { WorldPointer::new(world) });
+
+        script_world
+            .set_material_base_color(tinted, Color::RED)
+            .unwrap();
+        let unique = world
+            .get::<Handle<StandardMaterial>>(tinted)
+            .unwrap()
+            .clone();
+        assert_ne!(unique, shared);
+        assert_eq!(
+            world.get::<Handle<StandardMaterial>>(untouched),
+            Some(&shared)
+        );
+
+        // the material is no longer shared, so it is modified in place
+        script_world
+            .set_material_metallic_roughness(tinted, 2.0, -1.0)
+            .unwrap();
+        assert_eq!(world.get::<Handle<StandardMaterial>>(tinted), Some(&unique));
+
+        let materials = world.resource::<Assets<StandardMaterial>>();
+        let material = materials.get(&unique).unwrap();
+        assert_eq!(
+            (
+                material.base_color,
+                material.metallic,
+                material.perceptual_roughness
+            ),
+            (Color::RED, 1.0, 0.0)
+        );
+        assert_eq!(materials.get(&shared).unwrap().base_color, Color::WHITE);
+    }
+}
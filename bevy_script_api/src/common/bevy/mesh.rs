@@ -0,0 +1,64 @@
+use bevy::prelude::{shape, Assets, Mesh, Vec2};
+use bevy_mod_scripting_core::prelude::ScriptError;
+
+use super::{ScriptHandle, ScriptWorld};
+
+/// The most subdivisions of an icosphere, more generate too many vertices for the mesh indices
+const MAX_SPHERE_SUBDIVISIONS: usize = 80;
+
+/// Creates meshes from primitive shapes for scripts, the returned handles can be assigned to `Handle<Mesh>` fields
+#[derive(Clone, Debug)]
+pub struct ScriptMeshes(pub ScriptWorld);
+
+impl ScriptMeshes {
+    fn add(&self, mesh: Mesh) -> Result<ScriptHandle, ScriptError> {
+        let mut w = self.0.write();
+        let handle = w
+            .get_resource_mut::<Assets<Mesh>>()
+            .ok_or_else(|| ScriptError::Other("No `Assets<Mesh>` present".to_owned()))?
+            .add(mesh);
+        Ok(ScriptHandle::from(&handle))
+    }
+
+    /// A cube centered on the origin with sides of the given length
+    pub fn cube(&self, size: f32) -> Result<ScriptHandle, ScriptError> {
+        self.add(shape::Cube::new(positive("size", size)?).into())
+    }
+
+    /// A sphere centered on the origin, built from an icosahedron whose faces are subdivided the given number of times
+    pub fn sphere(&self, radius: f32, subdivisions: usize) -> Result<ScriptHandle, ScriptError> {
+        if subdivisions > MAX_SPHERE_SUBDIVISIONS {
+            return Err(ScriptError::Other(format!(
+                "Spheres have at most {MAX_SPHERE_SUBDIVISIONS} subdivisions, got {subdivisions}"
+            )));
+        }
+        self.add(
+            shape::Icosphere {
+                radius: positive("radius", radius)?,
+                subdivisions,
+            }
+            .into(),
+        )
+    }
+
+    /// A rectangle centered on the origin in the XY plane facing +Z, with the given width and height
+    pub fn quad(&self, width: f32, height: f32) -> Result<ScriptHandle, ScriptError> {
+        self.add(
+            shape::Quad::new(Vec2::new(
+                positive("width", width)?,
+                positive("height", height)?,
+            ))
+            .into(),
+        )
+    }
+}
+
+fn positive(name: &str, value: f32) -> Result<f32, ScriptError> {
+    if value.is_finite() && value > 0.0 {
+        Ok(value)
+    } else {
+        Err(ScriptError::Other(format!(
+            "The {name} of a mesh has to be a positive number, got {value}"
+        )))
+    }
+}
@@ -3,20 +3,108 @@ use std::{
     sync::Arc,
 };
 
-use crate::ScriptRef;
+use crate::{
+    common::{
+        shared::{ScriptValue, SharedScriptValues},
+        uniforms::ScriptUniforms,
+    },
+    ScriptRef,
+};
 /// Common functionality for all script hosts
 use bevy::{
+    asset::Asset,
     ecs::system::Command,
     prelude::{
         AppTypeRegistry, BuildWorldChildren, Children, DespawnChildrenRecursive, DespawnRecursive,
-        Entity, Parent, ReflectComponent, ReflectDefault, ReflectResource,
+        Entity, Parent, ReflectComponent, ReflectDefault, ReflectResource, Vec4,
     },
     reflect::{
         DynamicArray, DynamicEnum, DynamicList, DynamicMap, DynamicStruct, DynamicTuple,
-        DynamicTupleStruct, TypeRegistration,
+        DynamicTupleStruct, Reflect, TypeRegistration,
+    },
+};
+use bevy_mod_scripting_core::{
+    prelude::{
+        flush_script_commands, CopyScripts, MoveScripts, ScriptCommands, ScriptError, ScriptGroups,
+        ScriptId, ScriptQuotas,
     },
+    world::WorldPointer,
+};
+
+mod app_events;
+#[cfg(feature = "api_assets")]
+mod assets;
+pub mod bounds;
+pub mod color;
+mod component_events;
+mod diagnostics;
+mod hierarchy;
+#[cfg(feature = "api_input")]
+mod input;
+mod material;
+#[cfg(feature = "api_assets")]
+mod mesh;
+mod query;
+mod scene;
+mod screenshot;
+mod sent_events;
+mod sprite;
+mod states;
+#[cfg(feature = "api_time")]
+mod time;
+#[cfg(feature = "api_time")]
+mod timer;
+mod transform;
+mod triggers;
+mod tween;
+mod vfx;
+mod watch;
+mod window;
+
+pub use app_events::{RegisterScriptEvent, ScriptableEvents};
+#[cfg(feature = "api_assets")]
+pub use assets::{
+    color_material_from_value, register_script_asset_loads, standard_material_from_value,
+    track_script_asset_loads, RegisterScriptAsset, ScriptAssetLoaded, ScriptAssetLoads,
+    ScriptAssets, ScriptHandle, ScriptableAssets,
+};
+pub use component_events::{
+    register_script_component_events, send_script_component_events, ScriptComponentEvent,
+    ScriptComponentEventTypes,
+};
+pub use diagnostics::ScriptDiagnostics;
+pub use hierarchy::{Ancestors, Descendants};
+#[cfg(feature = "api_input")]
+pub use input::{
+    register_script_input, track_script_mouse_motion, InputState, ScriptInput, ScriptMouseMotion,
+};
+#[cfg(feature = "api_assets")]
+pub use mesh::ScriptMeshes;
+pub use query::ScriptQuery;
+pub use scene::{
+    register_script_scene_spawns, track_script_scene_spawns, ScriptSceneSpawned, ScriptSceneSpawns,
+};
+pub use screenshot::ScriptScreenshotRequests;
+pub use sent_events::{register_script_sent_events, ScriptSentEvent};
+pub use sprite::{advance_transform_tweens, register_transform_tweens, TransformTween};
+pub use states::{
+    send_script_state_events, RegisterScriptState, ScriptStateEvent, ScriptableStates,
+};
+#[cfg(feature = "api_time")]
+pub use time::ScriptTime;
+#[cfg(feature = "api_time")]
+pub use timer::{
+    register_script_timers, tick_script_timers, ScriptStopwatch, ScriptTimer, ScriptTimers,
 };
-use bevy_mod_scripting_core::{prelude::ScriptError, world::WorldPointer};
+pub use triggers::{
+    register_script_triggers, update_script_triggers, ScriptTrigger, ScriptTriggerEvent,
+    ScriptTriggers, TriggerShape,
+};
+pub use tween::{
+    advance_script_tweens, register_script_tweens, ScriptTweenFinished, ScriptTweens, TweenValue,
+};
+pub use vfx::{AddScriptVfxBackend, ScriptVfx, ScriptVfxBackend};
+pub use watch::{check_script_watches, register_script_watches, ScriptFieldChanged, ScriptWatches};
 
 /// Helper trait for retrieving a world pointer from a script context.
 pub trait GetWorld {
@@ -104,6 +192,48 @@ impl ScriptWorld {
     pub fn new(ptr: WorldPointer) -> Self {
         Self(ptr)
     }
+
+    /// Returns true if the given entity is still alive in this world
+    pub fn is_entity_valid(&self, entity: Entity) -> bool {
+        let w = self.read();
+        w.get_entity(entity).is_some()
+    }
+
+    /// Returns an error describing why the given entity cannot be used if it was despawned
+    pub fn validate_entity(&self, entity: Entity) -> Result<(), ScriptError> {
+        if self.is_entity_valid(entity) {
+            Ok(())
+        } else {
+            Err(ScriptError::Other(format!(
+                "Entity {entity:?} does not exist, it was most likely despawned. Use `valid()` to check entity references before using them"
+            )))
+        }
+    }
+
+    /// Counts a call to the given expensive API function against the quota of the script currently handling events,
+    /// see [`ScriptQuotas`]. Always succeeds if no quotas are configured
+    pub fn charge_quota(&self, api: &'static str) -> Result<(), ScriptError> {
+        let mut w = self.write();
+        match w.get_resource_mut::<ScriptQuotas>() {
+            Some(mut quotas) => quotas.charge(api),
+            None => Ok(()),
+        }
+    }
+
+    /// Spawns a new empty entity
+    pub fn spawn(&self) -> Result<Entity, ScriptError> {
+        self.charge_quota(ScriptQuotas::SPAWN)?;
+        let mut w = self.write();
+        Ok(w.spawn(()).id())
+    }
+
+    /// Despawns the given entity if it exists, its children are left in place without a parent.
+    /// Returns true if the entity existed
+    pub fn despawn(&self, entity: Entity) -> bool {
+        let mut w = self.write();
+        w.despawn(entity)
+    }
+
     pub fn get_children(&self, parent: Entity) -> Vec<Entity> {
         let w = self.read();
         w.get::<Children>(parent)
@@ -144,11 +274,55 @@ impl ScriptWorld {
         DespawnChildrenRecursive { entity }.write(&mut w);
     }
 
+    /// Queues a command in [`ScriptCommands`], applied once the current script finished handling its events
+    /// or when [`ScriptWorld::flush`] is called
+    pub fn defer<C: Command>(&self, command: C) {
+        let mut w = self.write();
+        w.get_resource_or_insert_with(ScriptCommands::default)
+            .push(command);
+    }
+
+    /// Applies all deferred commands now, making their changes visible to the calling script
+    pub fn flush(&self) {
+        let mut w = self.write();
+        flush_script_commands(&mut w);
+    }
+
+    /// Despawns the given entity and its children recursively once deferred commands are applied
+    pub fn despawn_recursive_deferred(&self, entity: Entity) {
+        self.defer(DespawnRecursive { entity });
+    }
+
     pub fn despawn_recursive(&self, entity: Entity) {
         let mut w = self.write();
         DespawnRecursive { entity }.write(&mut w);
     }
 
+    /// Moves all scripts of the host with script asset `T` from one entity to another, keeping their state.
+    /// The scripts handle events on behalf of their new entity once the host synchronized its scripts, see [`MoveScripts`]
+    pub fn move_scripts<T: Asset>(&self, from: Entity, to: Entity) -> Result<(), ScriptError> {
+        self.validate_entity(from)?;
+        self.validate_entity(to)?;
+        let mut w = self.write();
+        MoveScripts::<T>::new(from, to).write(&mut w);
+        Ok(())
+    }
+
+    /// Attaches new instances of all scripts of the host with script asset `T` on one entity to another,
+    /// optionally starting out with the state of the originals, see [`CopyScripts`]
+    pub fn copy_scripts<T: Asset>(
+        &self,
+        from: Entity,
+        to: Entity,
+        with_state: bool,
+    ) -> Result<(), ScriptError> {
+        self.validate_entity(from)?;
+        self.validate_entity(to)?;
+        let mut w = self.write();
+        CopyScripts::<T>::new(from, to, with_state).write(&mut w);
+        Ok(())
+    }
+
     pub fn get_type_by_name(&self, type_name: &str) -> Option<ScriptTypeRegistration> {
         let w = self.read();
 
@@ -162,11 +336,33 @@ impl ScriptWorld {
             .map(|registration| ScriptTypeRegistration::new(Arc::new(registration.clone())))
     }
 
+    /// Builds a value of the registered type with the given short or fully qualified name from a value given by a script,
+    /// e.g. an event a script emits. See [`ScriptValue::to_reflect`] for how values are matched against the type
+    pub fn build_value(
+        &self,
+        type_name: &str,
+        value: &ScriptValue,
+    ) -> Result<Box<dyn Reflect>, ScriptError> {
+        let w = self.read();
+        let registry = w.resource::<AppTypeRegistry>().read();
+
+        let registration = registry
+            .get_with_short_name(type_name)
+            .or_else(|| registry.get_with_name(type_name))
+            .ok_or_else(|| {
+                ScriptError::Other(format!("No type named `{type_name}` is registered"))
+            })?;
+        value
+            .to_reflect(registration.type_info(), &registry)
+            .map_err(ScriptError::Other)
+    }
+
     pub fn add_default_component(
         &self,
         entity: Entity,
         comp_type: ScriptTypeRegistration,
     ) -> Result<ScriptRef, ScriptError> {
+        self.validate_entity(entity)?;
         let mut w = self.write();
 
         let component_data = comp_type.data::<ReflectComponent>().ok_or_else(|| {
@@ -204,6 +400,7 @@ impl ScriptWorld {
         entity: Entity,
         comp_type: ScriptTypeRegistration,
     ) -> Result<Option<ScriptRef>, ScriptError> {
+        self.validate_entity(entity)?;
         let w = self.read();
 
         let component_data = comp_type.data::<ReflectComponent>().ok_or_else(|| {
@@ -220,6 +417,7 @@ impl ScriptWorld {
         entity: Entity,
         comp_type: ScriptTypeRegistration,
     ) -> Result<bool, ScriptError> {
+        self.validate_entity(entity)?;
         let w = self.read();
         let component_data = comp_type.data::<ReflectComponent>().ok_or_else(|| {
             ScriptError::Other(format!("Not a component {}", comp_type.short_name()))
@@ -228,17 +426,22 @@ impl ScriptWorld {
         Ok(component_data.reflect(&w, entity).is_some())
     }
 
+    /// Removes the component of the given type from the entity, returns true if the entity had one
     pub fn remove_component(
         &mut self,
         entity: Entity,
         comp_type: ScriptTypeRegistration,
-    ) -> Result<(), ScriptError> {
+    ) -> Result<bool, ScriptError> {
+        self.validate_entity(entity)?;
         let mut w = self.write();
         let component_data = comp_type.data::<ReflectComponent>().ok_or_else(|| {
             ScriptError::Other(format!("Not a component {}", comp_type.short_name()))
         })?;
+        if component_data.reflect(&w, entity).is_none() {
+            return Ok(false);
+        }
         component_data.remove(&mut w, entity);
-        Ok(())
+        Ok(true)
     }
 
     pub fn get_resource(
@@ -265,13 +468,110 @@ impl ScriptWorld {
 
         Ok(resource_data.reflect(&w).is_some())
     }
-    pub fn remove_resource(&mut self, res_type: ScriptTypeRegistration) -> Result<(), ScriptError> {
+    /// Retrieves a copy of the value shared between all script hosts under the given key
+    pub fn get_shared(&self, key: &str) -> ScriptValue {
+        let w = self.read();
+        w.get_resource::<SharedScriptValues>()
+            .and_then(|shared| shared.get(key).cloned())
+            .unwrap_or_default()
+    }
+
+    /// Stores a value visible to the scripts of all hosts under the given key
+    pub fn set_shared(&self, key: String, value: ScriptValue) {
+        let mut w = self.write();
+        w.get_resource_or_insert_with(SharedScriptValues::default)
+            .set(key, value);
+    }
+
+    /// Adds the script to the given group, returns false if it already was a member
+    pub fn join_group(&self, sid: ScriptId, group: &str) -> bool {
+        let mut w = self.write();
+        w.get_resource_or_insert_with(ScriptGroups::default)
+            .join(sid, group)
+    }
+
+    /// Removes the script from the given group, returns false if it was not a member
+    pub fn leave_group(&self, sid: ScriptId, group: &str) -> bool {
+        let mut w = self.write();
+        w.get_resource_mut::<ScriptGroups>()
+            .map_or(false, |mut groups| groups.leave(sid, group))
+    }
+
+    pub fn in_group(&self, sid: ScriptId, group: &str) -> bool {
+        let w = self.read();
+        w.get_resource::<ScriptGroups>()
+            .map_or(false, |groups| groups.is_member(sid, group))
+    }
+
+    /// The groups the script is a member of
+    pub fn script_groups(&self, sid: ScriptId) -> Vec<String> {
+        let w = self.read();
+        w.get_resource::<ScriptGroups>()
+            .map(|groups| groups.groups_of(sid).to_vec())
+            .unwrap_or_default()
+    }
+
+    /// Retrieves the value of the script uniform slot with the given name
+    pub fn get_uniform(&self, name: &str) -> Result<Vec4, ScriptError> {
+        let w = self.read();
+        w.get_resource::<ScriptUniforms>()
+            .and_then(|uniforms| uniforms.get(name))
+            .ok_or_else(|| {
+                ScriptError::Other(format!(
+                    "No script uniform slot named `{name}` was declared"
+                ))
+            })
+    }
+
+    /// Sets the value of the script uniform slot with the given name, the slot must have been declared by the app
+    pub fn set_uniform(&self, name: &str, value: Vec4) -> Result<(), ScriptError> {
+        let mut w = self.write();
+        w.get_resource_mut::<ScriptUniforms>()
+            .ok_or_else(|| ScriptError::Other("No `ScriptUniforms` present".to_owned()))?
+            .set(name, value)
+    }
+
+    /// Removes the resource of the given type from the world, returns true if it existed
+    pub fn remove_resource(
+        &mut self,
+        res_type: ScriptTypeRegistration,
+    ) -> Result<bool, ScriptError> {
         let mut w = self.write();
 
         let resource_data = res_type.data::<ReflectResource>().ok_or_else(|| {
             ScriptError::Other(format!("Not a resource {}", res_type.short_name()))
         })?;
+        if resource_data.reflect(&w).is_none() {
+            return Ok(false);
+        }
         resource_data.remove(&mut w);
-        Ok(())
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::World;
+
+    use super::*;
+
+    #[test]
+    fn despawned_entities_are_invalid() {
+        let mut world = World::new();
+        let alive = world.spawn(()).id();
+        let despawned = world.spawn(()).id();
+        world.despawn(despawned);
+
+        // safety: the world outlives the pointer, which is only used in this test
+        let script_world = ScriptWorld::new(unsafe { WorldPointer::new(&mut world) });
+
+        assert!(script_world.is_entity_valid(alive));
+        assert!(script_world.validate_entity(alive).is_ok());
+        assert!(!script_world.is_entity_valid(despawned));
+        assert!(script_world
+            .validate_entity(despawned)
+            .unwrap_err()
+            .to_string()
+            .contains("despawned"));
     }
 }
@@ -0,0 +1,269 @@
+use std::sync::{Arc, Mutex};
+
+use bevy::{
+    ecs::{
+        archetype::{ArchetypeGeneration, ArchetypeId},
+        component::ComponentId,
+    },
+    prelude::{Entity, ReflectComponent, World},
+    world::WorldId,
+};
+use bevy_mod_scripting_core::prelude::{ScriptError, ScriptQuotas};
+
+use crate::ScriptRef;
+
+use super::{ScriptTypeRegistration, ScriptWorld};
+
+/// A query for all entities with a set of components, whose results are cached between calls.
+///
+/// Like bevy's own `QueryState`, matching archetypes are tracked incrementally using the archetype generation.
+/// The matching entities are only collected again once the world's change tick advanced or the number of entities
+/// in the matching archetypes changed, so iterating the same query many times per frame costs next to nothing.
+///
+/// The query does not hold on to the world, which is given on every access instead, so it can be kept around
+/// (e.g. in a script global) for as long as the script lives. Using it with another world starts the cache over.
+#[derive(Clone)]
+pub struct ScriptQuery {
+    components: Arc<[(ScriptTypeRegistration, ReflectComponent)]>,
+    cache: Arc<Mutex<QueryCache>>,
+}
+
+impl std::fmt::Debug for ScriptQuery {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("ScriptQuery")
+            .field(&self.components.iter().map(|(t, _)| t).collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+struct QueryCache {
+    /// the world the cache was filled from
+    world_id: Option<WorldId>,
+    /// None until every component was registered with the world
+    component_ids: Option<Vec<ComponentId>>,
+    archetype_generation: ArchetypeGeneration,
+    archetypes: Vec<ArchetypeId>,
+    entities: Vec<Entity>,
+    /// the world change tick and the number of matching entities at the last refresh
+    refreshed: Option<(u32, usize)>,
+}
+
+impl Default for QueryCache {
+    fn default() -> Self {
+        Self {
+            world_id: None,
+            component_ids: None,
+            archetype_generation: ArchetypeGeneration::initial(),
+            archetypes: Vec::default(),
+            entities: Vec::default(),
+            refreshed: None,
+        }
+    }
+}
+
+impl ScriptQuery {
+    /// The component types every matching entity has, in the order given on creation
+    pub fn components(&self) -> impl Iterator<Item = &ScriptTypeRegistration> {
+        self.components.iter().map(|(t, _)| t)
+    }
+
+    /// Returns the matching entities, refreshing the cache first if the world changed.
+    ///
+    /// Counts against the [`ScriptQuotas::QUERY`] quota of the current script.
+    pub fn entities(&self, world: &ScriptWorld) -> Result<Vec<Entity>, ScriptError> {
+        world.charge_quota(ScriptQuotas::QUERY)?;
+        let w = world.read();
+        let mut cache = self.cache.lock().unwrap_or_else(|e| e.into_inner());
+        Self::refresh(&w, &self.components, &mut cache);
+        Ok(cache.entities.clone())
+    }
+
+    /// The number of matching entities, refreshing the cache first if the world changed
+    pub fn len(&self, world: &ScriptWorld) -> usize {
+        let w = world.read();
+        let mut cache = self.cache.lock().unwrap_or_else(|e| e.into_inner());
+        Self::refresh(&w, &self.components, &mut cache);
+        cache.entities.len()
+    }
+
+    pub fn is_empty(&self, world: &ScriptWorld) -> bool {
+        self.len(world) == 0
+    }
+
+    /// Forces the matching entities to be collected again on the next access,
+    /// necessary only if entities changed archetypes without the entity count changing since the last access this frame
+    pub fn invalidate(&self) {
+        self.cache
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .refreshed = None;
+    }
+
+    /// Returns a reference to the component at the given index of the query on the given entity in the given world,
+    /// the entity is not checked to be a match
+    pub fn component_ref(
+        &self,
+        world: &ScriptWorld,
+        entity: Entity,
+        index: usize,
+    ) -> Option<ScriptRef> {
+        self.components.get(index).map(|(_, component_data)| {
+            ScriptRef::new_component_ref(component_data.clone(), entity, world.clone().into())
+        })
+    }
+
+    fn refresh(
+        world: &World,
+        components: &[(ScriptTypeRegistration, ReflectComponent)],
+        cache: &mut QueryCache,
+    ) {
+        if cache.world_id != Some(world.id()) {
+            *cache = QueryCache {
+                world_id: Some(world.id()),
+                ..QueryCache::default()
+            };
+        }
+        if cache.component_ids.is_none() {
+            // components which were never inserted have no ID yet, and so no entity can match
+            cache.component_ids = components
+                .iter()
+                .map(|(t, _)| world.components().get_id(t.type_id()))
+                .collect();
+            if cache.component_ids.is_none() {
+                return;
+            }
+        }
+        let component_ids = cache.component_ids.as_deref().unwrap_or_default();
+
+        let archetypes = world.archetypes();
+        let generation = archetypes.generation();
+        if generation != cache.archetype_generation {
+            for archetype in &archetypes[cache.archetype_generation..] {
+                if component_ids.iter().all(|id| archetype.contains(*id)) {
+                    cache.archetypes.push(archetype.id());
+                }
+            }
+            cache.archetype_generation = generation;
+        }
+
+        let matching = cache
+            .archetypes
+            .iter()
+            .map(|id| archetypes[*id].len())
+            .sum();
+        let key = (world.read_change_tick(), matching);
+        if cache.refreshed == Some(key) {
+            return;
+        }
+
+        cache.entities.clear();
+        for id in &cache.archetypes {
+            cache
+                .entities
+                .extend(archetypes[*id].entities().iter().map(|e| e.entity()));
+        }
+        cache.refreshed = Some(key);
+    }
+}
+
+impl ScriptWorld {
+    /// Creates a cached query for all entities with the given components, see [`ScriptQuery`]
+    pub fn cached_query(
+        &self,
+        components: Vec<ScriptTypeRegistration>,
+    ) -> Result<ScriptQuery, ScriptError> {
+        if components.is_empty() {
+            return Err(ScriptError::Other(
+                "A query needs at least one component type".to_owned(),
+            ));
+        }
+
+        let components = components
+            .into_iter()
+            .map(|comp_type| {
+                let component_data =
+                    comp_type
+                        .data::<ReflectComponent>()
+                        .cloned()
+                        .ok_or_else(|| {
+                            ScriptError::Other(format!(
+                                "Not a component {}",
+                                comp_type.short_name()
+                            ))
+                        })?;
+                Ok((comp_type, component_data))
+            })
+            .collect::<Result<Arc<[_]>, ScriptError>>()?;
+
+        Ok(ScriptQuery {
+            components,
+            cache: Arc::new(Mutex::new(QueryCache::default())),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{
+        prelude::{Component, Reflect},
+        reflect::GetTypeRegistration,
+    };
+    use bevy_mod_scripting_core::world::WorldPointer;
+
+    use super::*;
+
+    #[derive(Component, Reflect, Default)]
+    #[reflect(Component)]
+    struct Health(u32);
+
+    #[derive(Component, Reflect, Default)]
+    #[reflect(Component)]
+    struct Armor(u32);
+
+    fn registration<T: GetTypeRegistration>() -> ScriptTypeRegistration {
+        ScriptTypeRegistration::new(Arc::new(T::get_type_registration()))
+    }
+
+    #[test]
+    fn cached_queries_follow_the_world() {
+        let mut world = World::new();
+        // safety: the world outlives the pointer, which is only used in this test
+        let script_world = ScriptWorld::new(unsafe { WorldPointer::new(&mut world) });
+        let healthy = script_world
+            .cached_query(vec![registration::<Health>()])
+            .unwrap();
+        let armored = script_world
+            .cached_query(vec![registration::<Health>(), registration::<Armor>()])
+            .unwrap();
+
+        // no entity can match components which were never inserted
+        assert!(healthy.is_empty(&script_world));
+
+        let a = world.spawn(Health(1)).id();
+        let b = world.spawn((Health(2), Armor(1))).id();
+        assert_eq!(healthy.entities(&script_world).unwrap(), vec![a, b]);
+        assert_eq!(armored.entities(&script_world).unwrap(), vec![b]);
+
+        world.spawn(Armor(2));
+        let c = world.spawn((Health(3), Armor(3))).id();
+        assert_eq!(healthy.len(&script_world), 3);
+        assert_eq!(armored.entities(&script_world).unwrap(), vec![b, c]);
+
+        world.despawn(b);
+        assert_eq!(armored.entities(&script_world).unwrap(), vec![c]);
+    }
+
+    #[test]
+    fn queries_need_component_types() {
+        let mut world = World::new();
+        // safety: the world outlives the pointer, which is only used in this test
+        let script_world = ScriptWorld::new(unsafe { WorldPointer::new(&mut world) });
+
+        assert!(script_world.cached_query(Vec::default()).is_err());
+        let err = script_world
+            .cached_query(vec![registration::<u32>()])
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Not a component"), "{err}");
+    }
+}
@@ -0,0 +1,179 @@
+use bevy::{
+    asset::LoadState,
+    prelude::{
+        App, AssetServer, Entity, EventWriter, Handle, Query, Res, ResMut, Resource, Scene,
+        SceneBundle, Transform,
+    },
+    scene::{SceneInstance, SceneSpawner},
+};
+use bevy_mod_scripting_core::prelude::{Recipients, ScriptError, ScriptQuotas};
+
+use super::ScriptWorld;
+
+/// Sent once a scene spawned by a script through [`ScriptWorld::spawn_scene`] is fully spawned, or failed to load
+#[derive(Debug, Clone)]
+pub struct ScriptSceneSpawned {
+    /// the scripts which spawned the scene
+    pub recipients: Recipients,
+    /// the path the scene was loaded from, e.g. `models/tree.glb#Scene0`
+    pub path: String,
+    /// the root entity of the scene, the entities of the scene are spawned as its children
+    pub root: Entity,
+    /// false if the scene failed to load, the root entity is left in place
+    pub success: bool,
+}
+
+/// Scenes spawned by scripts which are not ready yet
+#[derive(Debug, Default, Resource)]
+pub struct ScriptSceneSpawns {
+    pending: Vec<(Recipients, String, Entity, Handle<Scene>)>,
+}
+
+impl ScriptSceneSpawns {
+    /// The number of scenes still being spawned
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+}
+
+/// Sends [`ScriptSceneSpawned`] events for all pending script scenes which are ready or failed to load
+pub fn track_script_scene_spawns(
+    mut spawns: ResMut<ScriptSceneSpawns>,
+    server: Res<AssetServer>,
+    spawner: Res<SceneSpawner>,
+    instances: Query<Option<&SceneInstance>>,
+    mut events: EventWriter<ScriptSceneSpawned>,
+) {
+    spawns.pending.retain(|(recipients, path, root, handle)| {
+        let success = if server.get_load_state(handle) == LoadState::Failed {
+            false
+        } else {
+            match instances.get(*root) {
+                Ok(Some(instance)) if spawner.instance_is_ready(**instance) => true,
+                Ok(_) => return true,
+                // the root was despawned before the scene was ready
+                Err(_) => false,
+            }
+        };
+
+        events.send(ScriptSceneSpawned {
+            recipients: recipients.clone(),
+            path: path.clone(),
+            root: *root,
+            success,
+        });
+        false
+    });
+}
+
+/// Adds the scene tracking system and the [`ScriptSceneSpawned`] event, unless already added.
+///
+/// Called by the API providers forwarding scene events to scripts.
+pub fn register_script_scene_spawns(app: &mut App) {
+    if app.world.contains_resource::<ScriptSceneSpawns>() {
+        return;
+    }
+
+    app.init_resource::<ScriptSceneSpawns>()
+        .add_event::<ScriptSceneSpawned>()
+        .add_system(track_script_scene_spawns);
+}
+
+impl ScriptWorld {
+    /// Spawns the scene at the given path (e.g. `models/tree.glb#Scene0`) under a new root entity with the given transform,
+    /// and returns the root. Once the scene is spawned a [`ScriptSceneSpawned`] event is sent to the given recipients.
+    pub fn spawn_scene(
+        &self,
+        path: &str,
+        transform: Transform,
+        recipients: Recipients,
+    ) -> Result<Entity, ScriptError> {
+        self.charge_quota(ScriptQuotas::SPAWN)?;
+        let mut w = self.write();
+        if !w.contains_resource::<ScriptSceneSpawns>() {
+            return Err(ScriptError::Other(
+                "Scene events are not enabled, add a scene events API provider".to_owned(),
+            ));
+        }
+
+        let scene: Handle<Scene> = w
+            .get_resource::<AssetServer>()
+            .ok_or_else(|| ScriptError::Other("No `AssetServer` present".to_owned()))?
+            .load(path);
+
+        let root = w
+            .spawn(SceneBundle {
+                scene: scene.clone(),
+                transform,
+                ..Default::default()
+            })
+            .id();
+
+        w.resource_mut::<ScriptSceneSpawns>().pending.push((
+            recipients,
+            path.to_owned(),
+            root,
+            scene,
+        ));
+        Ok(root)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{
+        ecs::system::{IntoSystem, System},
+        prelude::{AssetPlugin, Events, MinimalPlugins, Vec3},
+    };
+    use bevy_mod_scripting_core::world::WorldPointer;
+
+    use super::*;
+
+    #[test]
+    fn scenes_whose_root_was_despawned_are_reported_as_failed() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugin(AssetPlugin::default())
+            .init_resource::<SceneSpawner>();
+        // safety: the world outlives the pointer, which is only used in this test
+        let script_world = ScriptWorld::new(unsafe { WorldPointer::new(&mut app.world) });
+        let transform = Transform::from_translation(Vec3::X);
+
+        assert!(script_world
+            .spawn_scene("models/tree.glb#Scene0", transform, Recipients::All)
+            .is_err());
+
+        register_script_scene_spawns(&mut app);
+        let root = script_world
+            .spawn_scene("models/tree.glb#Scene0", transform, Recipients::All)
+            .unwrap();
+        assert_eq!(app.world.get::<Transform>(root), Some(&transform));
+        assert_eq!(app.world.resource::<ScriptSceneSpawns>().len(), 1);
+
+        app.world.despawn(root);
+        let mut system = IntoSystem::into_system(track_script_scene_spawns);
+        system.initialize(&mut app.world);
+        system.run((), &mut app.world);
+
+        assert!(app.world.resource::<ScriptSceneSpawns>().is_empty());
+        let events = app.world.resource::<Events<ScriptSceneSpawned>>();
+        let spawned = events
+            .get_reader()
+            .iter(events)
+            .cloned()
+            .collect::<Vec<_>>();
+        assert_eq!(spawned.len(), 1);
+        assert_eq!(
+            (
+                spawned[0].path.as_str(),
+                spawned[0].root,
+                spawned[0].success
+            ),
+            ("models/tree.glb#Scene0", root, false)
+        );
+    }
+}
@@ -0,0 +1,97 @@
+use std::path::{Component, Path, PathBuf};
+
+use bevy::prelude::Resource;
+use bevy_mod_scripting_core::prelude::ScriptError;
+
+use crate::common::capabilities::ScriptCapabilities;
+
+use super::ScriptWorld;
+
+/// Screenshots requested by scripts, in the order they were requested.
+///
+/// Bevy does not provide frame capture in this version, so applications which want to support scripted
+/// photo modes or visual tests need to drain this resource and capture the frame themselves.
+/// Paths are guaranteed to be relative and to never leave the directory they are resolved against.
+#[derive(Debug, Default, Resource)]
+pub struct ScriptScreenshotRequests {
+    pub requests: Vec<PathBuf>,
+}
+
+impl ScriptScreenshotRequests {
+    /// Removes and returns all pending requests
+    pub fn drain(&mut self) -> impl Iterator<Item = PathBuf> + '_ {
+        self.requests.drain(..)
+    }
+}
+
+impl ScriptWorld {
+    /// Requests a screenshot of the next frame to be saved at the given relative path,
+    /// requires the `screenshot` capability
+    pub fn request_screenshot(&self, path: &str) -> Result<(), ScriptError> {
+        let mut w = self.write();
+        ScriptCapabilities::require(
+            w.get_resource::<ScriptCapabilities>()
+                .map(|c| c.screenshot)
+                .unwrap_or(false),
+            "screenshot",
+        )?;
+
+        let path = Path::new(path);
+        if !path
+            .components()
+            .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+        {
+            return Err(ScriptError::Other(format!(
+                "Invalid screenshot path `{}`, paths must be relative and cannot contain `..`",
+                path.display()
+            )));
+        }
+
+        w.get_resource_or_insert_with(ScriptScreenshotRequests::default)
+            .requests
+            .push(path.to_owned());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::World;
+    use bevy_mod_scripting_core::world::WorldPointer;
+
+    use super::*;
+
+    #[test]
+    fn screenshots_require_the_capability_and_relative_paths() {
+        let mut world = World::new();
+        // safety: the world outlives the pointer, which is only used in this test
+        let script_world = ScriptWorld::new(unsafe { WorldPointer::new(&mut world) });
+
+        let denied = script_world
+            .request_screenshot("shot.png")
+            .unwrap_err()
+            .to_string();
+        assert!(denied.contains("`screenshot` capability"), "{denied}");
+
+        script_world
+            .write()
+            .insert_resource(ScriptCapabilities::all());
+        script_world.request_screenshot("./shots/1.png").unwrap();
+        for escaping in ["../shot.png", "shots/../../shot.png", "/tmp/shot.png"] {
+            assert!(
+                script_world.request_screenshot(escaping).is_err(),
+                "`{escaping}` was accepted"
+            );
+        }
+
+        let requested: Vec<_> = world
+            .resource_mut::<ScriptScreenshotRequests>()
+            .drain()
+            .collect();
+        assert_eq!(requested, vec![PathBuf::from("./shots/1.png")]);
+        assert!(world
+            .resource::<ScriptScreenshotRequests>()
+            .requests
+            .is_empty());
+    }
+}
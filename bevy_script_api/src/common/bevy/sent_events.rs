@@ -0,0 +1,72 @@
+use bevy::prelude::{App, Events};
+use bevy_mod_scripting_core::{
+    config::validate_handler_priority,
+    hook::HookName,
+    prelude::{Recipients, ScriptError, ScriptHost},
+};
+
+use crate::common::shared::ScriptValue;
+
+use super::ScriptWorld;
+
+/// An event sent by a script through [`ScriptWorld::send_script_event`], forwarded to the scripts of the same host
+/// by the API providers which registered the send API
+#[derive(Debug, Clone)]
+pub struct ScriptSentEvent {
+    /// the type name of the script host the event is sent to
+    pub host: &'static str,
+    pub hook: HookName,
+    pub recipients: Recipients,
+    pub priority: u32,
+    pub args: ScriptValue,
+}
+
+impl ScriptSentEvent {
+    /// Returns true if the event is meant for the host `H`
+    pub fn is_for<H: ScriptHost>(&self) -> bool {
+        self.host == std::any::type_name::<H>()
+    }
+}
+
+/// Adds the [`ScriptSentEvent`] event, unless already added.
+///
+/// Called by the API providers forwarding events sent by scripts.
+pub fn register_script_sent_events(app: &mut App) {
+    if !app.world.contains_resource::<Events<ScriptSentEvent>>() {
+        app.add_event::<ScriptSentEvent>();
+    }
+}
+
+impl ScriptWorld {
+    /// Sends an event for the given hook to the recipients of the host `H`, handled with the given priority.
+    ///
+    /// Fails if none of the handler stages of `H` handle the priority, so that events are not silently dropped,
+    /// or if the hook name cannot be interned.
+    pub fn send_script_event<H: ScriptHost>(
+        &self,
+        hook: &str,
+        recipients: Recipients,
+        priority: u32,
+        args: ScriptValue,
+    ) -> Result<(), ScriptError> {
+        let hook = HookName::new_checked(hook)?;
+        let mut w = self.write();
+        validate_handler_priority::<H>(&w, priority)?;
+
+        let mut events = w
+            .get_resource_mut::<Events<ScriptSentEvent>>()
+            .ok_or_else(|| {
+                ScriptError::Other(
+                    "Sending events is not enabled, add a send events API provider".to_owned(),
+                )
+            })?;
+        events.send(ScriptSentEvent {
+            host: std::any::type_name::<H>(),
+            hook,
+            recipients,
+            priority,
+            args,
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,217 @@
+use bevy::{
+    prelude::{
+        App, AssetServer, Color, Commands, Component, Entity, Query, Res, Resource, Sprite,
+        SpriteBundle, TextureAtlasSprite, Transform, Vec3,
+    },
+    time::Time,
+};
+use bevy_mod_scripting_core::prelude::{ScriptError, ScriptQuotas};
+
+use super::ScriptWorld;
+
+/// Linearly interpolates the transform of an entity from `start` to `target` over `duration` seconds,
+/// the component is removed once the target is reached.
+#[derive(Component, Debug, Clone)]
+pub struct TransformTween {
+    pub start: Transform,
+    pub target: Transform,
+    pub duration: f32,
+    pub elapsed: f32,
+}
+
+/// Advances all transform tweens
+pub fn advance_transform_tweens(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut tweens: Query<(Entity, &mut Transform, &mut TransformTween)>,
+) {
+    for (entity, mut transform, mut tween) in tweens.iter_mut() {
+        tween.elapsed += time.delta_seconds();
+        let s = if tween.duration > 0.0 {
+            (tween.elapsed / tween.duration).min(1.0)
+        } else {
+            1.0
+        };
+
+        transform.translation = tween.start.translation.lerp(tween.target.translation, s);
+        transform.rotation = tween.start.rotation.slerp(tween.target.rotation, s);
+        transform.scale = tween.start.scale.lerp(tween.target.scale, s);
+
+        if s >= 1.0 {
+            commands.entity(entity).remove::<TransformTween>();
+        }
+    }
+}
+
+#[derive(Resource)]
+struct TransformTweensRegistered;
+
+/// Adds the transform tween system to the app, does nothing if it was already added
+pub fn register_transform_tweens(app: &mut App) {
+    if !app.world.contains_resource::<TransformTweensRegistered>() {
+        app.insert_resource(TransformTweensRegistered)
+            .add_system(advance_transform_tweens);
+    }
+}
+
+/// 2D sprite helpers, these work with both [`Sprite`] and [`TextureAtlasSprite`] components where it makes sense
+impl ScriptWorld {
+    fn with_sprite<F: FnOnce(&mut Color, &mut bool, &mut bool)>(
+        &self,
+        entity: Entity,
+        f: F,
+    ) -> Result<(), ScriptError> {
+        self.validate_entity(entity)?;
+        let mut w = self.write();
+        let mut entity_mut = w.entity_mut(entity);
+
+        if let Some(mut sprite) = entity_mut.get_mut::<Sprite>() {
+            let sprite = &mut *sprite;
+            f(&mut sprite.color, &mut sprite.flip_x, &mut sprite.flip_y);
+        } else if let Some(mut sprite) = entity_mut.get_mut::<TextureAtlasSprite>() {
+            let sprite = &mut *sprite;
+            f(&mut sprite.color, &mut sprite.flip_x, &mut sprite.flip_y);
+        } else {
+            return Err(ScriptError::Other(format!(
+                "Entity {entity:?} has no `Sprite` or `TextureAtlasSprite` component"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Spawns a sprite bundle using the texture at the given asset path
+    pub fn spawn_sprite(
+        &self,
+        texture_path: &str,
+        translation: Vec3,
+    ) -> Result<Entity, ScriptError> {
+        self.charge_quota(ScriptQuotas::SPAWN)?;
+        let mut w = self.write();
+        let texture = w
+            .get_resource::<AssetServer>()
+            .ok_or_else(|| ScriptError::Other("No `AssetServer` present".to_owned()))?
+            .load(texture_path);
+
+        Ok(w.spawn(SpriteBundle {
+            texture,
+            transform: Transform::from_translation(translation),
+            ..Default::default()
+        })
+        .id())
+    }
+
+    /// Sets the color tint of the sprite on the given entity
+    pub fn set_sprite_color(&self, entity: Entity, color: Color) -> Result<(), ScriptError> {
+        self.with_sprite(entity, |c, _, _| *c = color)
+    }
+
+    /// Flips the sprite on the given entity along the x and y axes
+    pub fn set_sprite_flip(
+        &self,
+        entity: Entity,
+        flip_x: bool,
+        flip_y: bool,
+    ) -> Result<(), ScriptError> {
+        self.with_sprite(entity, |_, x, y| {
+            *x = flip_x;
+            *y = flip_y;
+        })
+    }
+
+    /// Sets the texture atlas index of the [`TextureAtlasSprite`] on the given entity
+    pub fn set_sprite_index(&self, entity: Entity, index: usize) -> Result<(), ScriptError> {
+        self.validate_entity(entity)?;
+        let mut w = self.write();
+        let mut sprite = w.get_mut::<TextureAtlasSprite>(entity).ok_or_else(|| {
+            ScriptError::Other(format!(
+                "Entity {entity:?} has no `TextureAtlasSprite` component"
+            ))
+        })?;
+        sprite.index = index;
+        Ok(())
+    }
+
+    /// Starts interpolating the transform of the given entity towards a target transform derived from the current one,
+    /// replacing any tween already in progress
+    pub fn tween_transform<F: FnOnce(&mut Transform)>(
+        &self,
+        entity: Entity,
+        duration: f32,
+        target: F,
+    ) -> Result<(), ScriptError> {
+        self.validate_entity(entity)?;
+        let mut w = self.write();
+        let start = *w.get::<Transform>(entity).ok_or_else(|| {
+            ScriptError::Other(format!("Entity {entity:?} has no `Transform` component"))
+        })?;
+
+        let mut end = start;
+        target(&mut end);
+
+        w.entity_mut(entity).insert(TransformTween {
+            start,
+            target: end,
+            duration,
+            elapsed: 0.0,
+        });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{
+        ecs::schedule::{Stage, SystemStage},
+        prelude::World,
+    };
+    use bevy_mod_scripting_core::world::WorldPointer;
+
+    use super::*;
+
+    #[test]
+    fn tints_and_flips_both_kinds_of_sprites() {
+        let mut world = World::new();
+        let sprite = world.spawn(Sprite::default()).id();
+        let atlas_sprite = world.spawn(TextureAtlasSprite::default()).id();
+        let not_a_sprite = world.spawn(()).id();
+        // safety: the world outlives the pointer, which is only used in this test
+        let script_world = ScriptWorld::new(unsafe { WorldPointer::new(&mut world) });
+
+        for entity in [sprite, atlas_sprite] {
+            script_world.set_sprite_color(entity, Color::RED).unwrap();
+            script_world.set_sprite_flip(entity, true, false).unwrap();
+        }
+        assert!(script_world
+            .set_sprite_color(not_a_sprite, Color::RED)
+            .is_err());
+        assert!(script_world.set_sprite_index(sprite, 1).is_err());
+        script_world.set_sprite_index(atlas_sprite, 3).unwrap();
+
+        let s = world.get::<Sprite>(sprite).unwrap();
+        assert_eq!((s.color, s.flip_x, s.flip_y), (Color::RED, true, false));
+        let s = world.get::<TextureAtlasSprite>(atlas_sprite).unwrap();
+        assert_eq!(
+            (s.color, s.flip_x, s.flip_y, s.index),
+            (Color::RED, true, false, 3)
+        );
+    }
+
+    #[test]
+    fn finished_tweens_reach_their_target_and_are_removed() {
+        let mut world = World::new();
+        world.insert_resource(Time::default());
+        let entity = world.spawn(Transform::default()).id();
+        // safety: the world outlives the pointer, which is only used in this test
+        let script_world = ScriptWorld::new(unsafe { WorldPointer::new(&mut world) });
+
+        script_world
+            .tween_transform(entity, 0.0, |t| t.translation = Vec3::X)
+            .unwrap();
+
+        let mut stage = SystemStage::single_threaded().with_system(advance_transform_tweens);
+        stage.run(&mut world);
+
+        assert_eq!(world.get::<Transform>(entity).unwrap().translation, Vec3::X);
+        assert!(world.get::<TransformTween>(entity).is_none());
+    }
+}
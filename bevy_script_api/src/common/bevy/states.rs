@@ -0,0 +1,272 @@
+use std::any::type_name;
+
+use bevy::{
+    ecs::{
+        event::Events,
+        schedule::{StateData, StateError},
+    },
+    prelude::{App, CoreStage, EventWriter, Local, Res, Resource, State, World},
+    reflect::{DynamicEnum, DynamicVariant, FromReflect, Reflect, ReflectRef},
+    utils::get_short_name,
+};
+use bevy_mod_scripting_core::prelude::ScriptError;
+
+use super::ScriptWorld;
+
+/// Sent whenever the current state of a type registered with [`RegisterScriptState`] changes
+#[derive(Debug, Clone)]
+pub struct ScriptStateEvent {
+    /// the short type name of the states, e.g. `GameState`
+    pub state_type: String,
+    /// the name of the state which was entered or exited, e.g. `InGame`
+    pub state: String,
+    /// true if the state was entered, false if it was exited
+    pub entered: bool,
+}
+
+/// How scripts read and set the states of one type
+#[derive(Clone, Copy)]
+struct ScriptableState {
+    current: fn(&World) -> Option<String>,
+    /// returns `None` if the name is not a state of this type
+    set: fn(&mut World, &str) -> Option<Result<(), ScriptError>>,
+}
+
+/// The state types scripts can read and set, see [`RegisterScriptState`]
+#[derive(Resource, Default)]
+pub struct ScriptableStates {
+    /// by short and fully qualified type name, in registration order
+    states: Vec<(String, String, ScriptableState)>,
+}
+
+impl ScriptableStates {
+    /// The short type names of the registered states, in registration order
+    pub fn state_types(&self) -> impl Iterator<Item = &str> {
+        self.states.iter().map(|(short, _, _)| short.as_str())
+    }
+
+    fn get(&self, state_type: &str) -> Result<ScriptableState, ScriptError> {
+        self.states
+            .iter()
+            .find(|(short, full, _)| short == state_type || full == state_type)
+            .map(|(_, _, state)| *state)
+            .ok_or_else(|| {
+                ScriptError::Other(format!(
+                    "No state type named `{state_type}` can be used by scripts, see `register_script_state`"
+                ))
+            })
+    }
+}
+
+/// The name scripts use for the given state, the variant name for enums
+fn state_name<S: StateData + Reflect>(state: &S) -> String {
+    match state.reflect_ref() {
+        ReflectRef::Enum(e) => e.variant_name().to_owned(),
+        _ => format!("{state:?}"),
+    }
+}
+
+fn current_state<S: StateData + Reflect>(world: &World) -> Option<String> {
+    world
+        .get_resource::<State<S>>()
+        .map(|state| state_name(state.current()))
+}
+
+fn set_state<S: StateData + FromReflect>(
+    world: &mut World,
+    name: &str,
+) -> Option<Result<(), ScriptError>> {
+    let state = S::from_reflect(&DynamicEnum::new(
+        type_name::<S>(),
+        name,
+        DynamicVariant::Unit,
+    ))?;
+    let short_name = get_short_name(type_name::<S>());
+
+    let result = match world.get_resource_mut::<State<S>>() {
+        Some(mut current) => match current.set(state) {
+            // setting the current state again does nothing
+            Ok(()) | Err(StateError::AlreadyInState) => Ok(()),
+            Err(e) => Err(ScriptError::Other(format!(
+                "Cannot set the `{short_name}` state to `{name}`: {e}"
+            ))),
+        },
+        None => Err(ScriptError::Other(format!(
+            "There is no `{short_name}` state, it has to be added with `add_state`"
+        ))),
+    };
+    Some(result)
+}
+
+/// Sends [`ScriptStateEvent`]s whenever the current state of type `S` changed since the last time the system ran,
+/// when the app starts out in a state that state is entered
+pub fn send_script_state_events<S: StateData + Reflect>(
+    state: Option<Res<State<S>>>,
+    mut previous: Local<Option<S>>,
+    mut events: EventWriter<ScriptStateEvent>,
+) {
+    let current = match state {
+        Some(state) => state.current().clone(),
+        None => return,
+    };
+    if previous.as_ref() == Some(&current) {
+        return;
+    }
+
+    let state_type = get_short_name(type_name::<S>());
+    if let Some(exited) = previous.replace(current.clone()) {
+        events.send(ScriptStateEvent {
+            state_type: state_type.clone(),
+            state: state_name(&exited),
+            entered: false,
+        });
+    }
+    events.send(ScriptStateEvent {
+        state_type,
+        state: state_name(&current),
+        entered: true,
+    });
+}
+
+pub trait RegisterScriptState {
+    /// Lets scripts read and set the current state of type `S` by the name of its variants,
+    /// e.g. `world:set_state("InGame")` in Lua, and sends [`ScriptStateEvent`]s on its transitions.
+    ///
+    /// States are set with [`State::set`], transitions happen once the state is next updated.
+    /// The state itself still has to be added with `add_state`.
+    fn register_script_state<S: StateData + Reflect + FromReflect>(&mut self) -> &mut Self;
+}
+
+impl RegisterScriptState for App {
+    fn register_script_state<S: StateData + Reflect + FromReflect>(&mut self) -> &mut Self {
+        if !self.world.contains_resource::<Events<ScriptStateEvent>>() {
+            self.add_event::<ScriptStateEvent>();
+        }
+
+        let full_name = type_name::<S>();
+        let mut states = self
+            .world
+            .get_resource_or_insert_with(ScriptableStates::default);
+        if states.states.iter().any(|(_, full, _)| full == full_name) {
+            return self;
+        }
+        states.states.push((
+            get_short_name(full_name),
+            full_name.to_owned(),
+            ScriptableState {
+                current: current_state::<S>,
+                set: set_state::<S>,
+            },
+        ));
+
+        // transitions happen in the stage the state was added to, usually `CoreStage::Update`
+        self.add_system_to_stage(CoreStage::Last, send_script_state_events::<S>)
+    }
+}
+
+impl ScriptWorld {
+    /// The state types with the given name, or all state types in registration order without a name
+    fn scriptable_states(
+        &self,
+        state_type: Option<&str>,
+    ) -> Result<Vec<(String, ScriptableState)>, ScriptError> {
+        let w = self.read();
+        let states = w
+            .get_resource::<ScriptableStates>()
+            .filter(|states| !states.states.is_empty())
+            .ok_or_else(|| {
+                ScriptError::Other(
+                    "No states can be used by scripts, see `register_script_state`".to_owned(),
+                )
+            })?;
+        match state_type {
+            Some(state_type) => Ok(vec![(state_type.to_owned(), states.get(state_type)?)]),
+            None => Ok(states
+                .states
+                .iter()
+                .map(|(short, _, state)| (short.clone(), *state))
+                .collect()),
+        }
+    }
+
+    /// The name of the current state of the given type, or of the state type registered first
+    pub fn current_state(&self, state_type: Option<&str>) -> Result<String, ScriptError> {
+        let (name, state) = self.scriptable_states(state_type)?.remove(0);
+        let w = self.read();
+        (state.current)(&w).ok_or_else(|| {
+            ScriptError::Other(format!(
+                "There is no `{name}` state, it has to be added with `add_state`"
+            ))
+        })
+    }
+
+    /// Sets the state of the given type to the state with the given name,
+    /// without a type the first registered state type with a state of that name is set
+    pub fn set_state(&self, state: &str, state_type: Option<&str>) -> Result<(), ScriptError> {
+        let candidates = self.scriptable_states(state_type)?;
+        let mut w = self.write();
+        for (_, candidate) in &candidates {
+            if let Some(result) = (candidate.set)(&mut w, state) {
+                return result;
+            }
+        }
+        let names = candidates
+            .iter()
+            .map(|(name, _)| format!("`{name}`"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        Err(ScriptError::Other(format!(
+            "`{state}` is not a state of {names}"
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::event::ManualEventReader;
+    use bevy_mod_scripting_core::world::WorldPointer;
+
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq, Eq, Hash, Reflect, FromReflect)]
+    enum GameState {
+        Menu,
+        InGame,
+    }
+
+    #[test]
+    fn reads_sets_and_reports_states() {
+        let mut app = App::new();
+        app.add_state(GameState::Menu)
+            .register_script_state::<GameState>();
+        app.update();
+
+        let mut reader = ManualEventReader::<ScriptStateEvent>::default();
+        let mut transitions = |world: &World| {
+            reader
+                .iter(world.resource::<Events<ScriptStateEvent>>())
+                .map(|e| (e.state.clone(), e.entered))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(transitions(&app.world), vec![("Menu".to_owned(), true)]);
+
+        {
+            // safety: the world outlives the pointer, which is only used in this block
+            let script_world = ScriptWorld::new(unsafe { WorldPointer::new(&mut app.world) });
+            assert_eq!(script_world.current_state(None).unwrap(), "Menu");
+            assert!(script_world.set_state("Paused", None).is_err());
+            assert!(script_world.current_state(Some("Level")).is_err());
+            script_world.set_state("InGame", Some("GameState")).unwrap();
+        }
+        app.update();
+
+        assert_eq!(
+            *app.world.resource::<State<GameState>>().current(),
+            GameState::InGame
+        );
+        assert_eq!(
+            transitions(&app.world),
+            vec![("Menu".to_owned(), false), ("InGame".to_owned(), true)]
+        );
+    }
+}
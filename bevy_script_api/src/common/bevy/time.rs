@@ -0,0 +1,47 @@
+use bevy::time::{FixedTimesteps, Time};
+
+use super::ScriptWorld;
+
+/// Read-only access to bevy's `Time` resource and the state of fixed timesteps, for frame-rate independent movement in scripts.
+///
+/// Fixed timesteps are looked up by the label given to `FixedTimestep::with_label`, unlabeled ones cannot be read.
+/// A world without the `Time` resource reads as no time having passed.
+#[derive(Clone, Debug)]
+pub struct ScriptTime(pub ScriptWorld);
+
+impl ScriptTime {
+    fn with_time<O: Default>(&self, f: impl FnOnce(&Time) -> O) -> O {
+        self.0
+            .read()
+            .get_resource::<Time>()
+            .map(f)
+            .unwrap_or_default()
+    }
+
+    /// The seconds elapsed since the previous frame
+    pub fn delta_seconds(&self) -> f32 {
+        self.with_time(|t| t.delta_seconds())
+    }
+
+    /// The seconds elapsed since the app started
+    pub fn elapsed(&self) -> f64 {
+        self.with_time(|t| t.elapsed_seconds_f64())
+    }
+
+    /// The seconds between two steps of the fixed timestep with the given label
+    pub fn fixed_step(&self, label: &str) -> Option<f64> {
+        let w = self.0.read();
+        w.get_resource::<FixedTimesteps>()?
+            .get(label)
+            .map(|state| state.step())
+    }
+
+    /// How far the time accumulated by the fixed timestep with the given label is into its next step, between 0 and 1,
+    /// for interpolating between the last two steps when rendering
+    pub fn fixed_overstep(&self, label: &str) -> Option<f64> {
+        let w = self.0.read();
+        w.get_resource::<FixedTimesteps>()?
+            .get(label)
+            .map(|state| state.overstep_percentage())
+    }
+}
@@ -0,0 +1,164 @@
+use std::sync::{Arc, Weak};
+
+use bevy::{
+    prelude::{App, Res, ResMut, Resource, Time, Timer, TimerMode},
+    time::Stopwatch,
+    utils::Duration,
+};
+use bevy_mod_scripting_core::prelude::ScriptError;
+use parking_lot::Mutex;
+
+use crate::common::instant::ScriptDuration;
+
+use super::ScriptWorld;
+
+/// A timer created by a script, ticked automatically every frame for as long as the script holds on to it
+#[derive(Debug, Clone)]
+pub struct ScriptTimer(Arc<Mutex<Timer>>);
+
+impl ScriptTimer {
+    /// Runs the closure on the underlying timer
+    pub fn with<O, F: FnOnce(&mut Timer) -> O>(&self, f: F) -> O {
+        f(&mut self.0.lock())
+    }
+}
+
+/// A stopwatch created by a script, ticked automatically every frame for as long as the script holds on to it
+#[derive(Debug, Clone)]
+pub struct ScriptStopwatch(Arc<Mutex<Stopwatch>>);
+
+impl ScriptStopwatch {
+    /// Runs the closure on the underlying stopwatch
+    pub fn with<O, F: FnOnce(&mut Stopwatch) -> O>(&self, f: F) -> O {
+        f(&mut self.0.lock())
+    }
+}
+
+/// Keeps track of all timers and stopwatches created by scripts.
+///
+/// Only weak references are kept, once a script drops all references to a timer it stops being ticked.
+#[derive(Debug, Default, Resource)]
+pub struct ScriptTimers {
+    timers: Vec<Weak<Mutex<Timer>>>,
+    stopwatches: Vec<Weak<Mutex<Stopwatch>>>,
+}
+
+impl ScriptTimers {
+    /// Ticks all live timers and stopwatches by the given duration, forgetting those no longer referenced
+    pub fn tick(&mut self, delta: Duration) {
+        self.timers.retain(|timer| match timer.upgrade() {
+            Some(timer) => {
+                timer.lock().tick(delta);
+                true
+            }
+            None => false,
+        });
+
+        self.stopwatches
+            .retain(|stopwatch| match stopwatch.upgrade() {
+                Some(stopwatch) => {
+                    stopwatch.lock().tick(delta);
+                    true
+                }
+                None => false,
+            });
+    }
+}
+
+/// Ticks all script timers and stopwatches with the frame delta time
+pub fn tick_script_timers(time: Res<Time>, mut timers: ResMut<ScriptTimers>) {
+    timers.tick(time.delta());
+}
+
+/// Adds the timer ticking system, unless already added.
+///
+/// Called by the timer API providers.
+pub fn register_script_timers(app: &mut App) {
+    if !app.world.contains_resource::<ScriptTimers>() {
+        app.init_resource::<ScriptTimers>()
+            .add_system(tick_script_timers);
+    }
+}
+
+impl ScriptWorld {
+    fn script_timers<O, F: FnOnce(&mut ScriptTimers) -> O>(&self, f: F) -> Result<O, ScriptError> {
+        let mut w = self.write();
+        w.get_resource_mut::<ScriptTimers>()
+            .map(|mut timers| f(&mut timers))
+            .ok_or_else(|| {
+                ScriptError::Other("Timers are not enabled, add a timer API provider".to_owned())
+            })
+    }
+
+    /// Creates a new timer which finishes after the given number of seconds, and starts over if `repeating` is set.
+    ///
+    /// Negative durations finish immediately, NaN or infinite durations are an error
+    pub fn create_timer(&self, seconds: f32, repeating: bool) -> Result<ScriptTimer, ScriptError> {
+        if !seconds.is_finite() {
+            return Err(ScriptError::Other(format!(
+                "A timer cannot last {seconds} seconds, expected a finite number of seconds"
+            )));
+        }
+        let mode = if repeating {
+            TimerMode::Repeating
+        } else {
+            TimerMode::Once
+        };
+        let duration = ScriptDuration::from_secs(seconds.into()).0;
+        let timer = Arc::new(Mutex::new(Timer::new(duration, mode)));
+
+        self.script_timers(|timers| timers.timers.push(Arc::downgrade(&timer)))?;
+        Ok(ScriptTimer(timer))
+    }
+
+    /// Creates a new running stopwatch
+    pub fn create_stopwatch(&self) -> Result<ScriptStopwatch, ScriptError> {
+        let stopwatch = Arc::new(Mutex::new(Stopwatch::new()));
+
+        self.script_timers(|timers| timers.stopwatches.push(Arc::downgrade(&stopwatch)))?;
+        Ok(ScriptStopwatch(stopwatch))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::World;
+    use bevy_mod_scripting_core::world::WorldPointer;
+
+    use super::*;
+
+    #[test]
+    fn ticks_timers_until_scripts_drop_them() {
+        let mut world = World::new();
+        world.init_resource::<ScriptTimers>();
+        // safety: the world outlives the pointer, which is only used in this test
+        let script_world = ScriptWorld::new(unsafe { WorldPointer::new(&mut world) });
+
+        let timer = script_world.create_timer(1.0, false).unwrap();
+        let stopwatch = script_world.create_stopwatch().unwrap();
+        let dropped = script_world.create_timer(1.0, true).unwrap();
+        drop(dropped);
+
+        let mut timers = world.resource_mut::<ScriptTimers>();
+        timers.tick(Duration::from_millis(1500));
+        assert!(timer.with(|t| t.finished()));
+        assert_eq!(stopwatch.with(|s| s.elapsed()), Duration::from_millis(1500));
+        assert_eq!((timers.timers.len(), timers.stopwatches.len()), (1, 1));
+    }
+
+    #[test]
+    fn non_finite_timer_durations_are_errors() {
+        let mut world = World::new();
+        world.init_resource::<ScriptTimers>();
+        // safety: the world outlives the pointer, which is only used in this test
+        let script_world = ScriptWorld::new(unsafe { WorldPointer::new(&mut world) });
+
+        for seconds in [f32::NAN, f32::INFINITY, f32::NEG_INFINITY] {
+            assert!(script_world.create_timer(seconds, false).is_err());
+        }
+        assert!(script_world
+            .create_timer(-1.0, false)
+            .unwrap()
+            .with(|t| t.duration().is_zero()));
+    }
+}
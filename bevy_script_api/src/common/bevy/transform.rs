@@ -0,0 +1,118 @@
+use bevy::prelude::{Entity, GlobalTransform, Quat, Transform, Vec3};
+use bevy_mod_scripting_core::prelude::ScriptError;
+
+use super::ScriptWorld;
+
+/// Transform helpers, moving entities without reflecting on their `Transform` component.
+///
+/// Changes apply to the local `Transform`, which equals the world space transform for entities without a parent.
+/// Reads come from the `GlobalTransform` when present, so they account for parents but lag behind changes
+/// made this frame until transforms are propagated.
+impl ScriptWorld {
+    fn with_transform<O>(
+        &self,
+        entity: Entity,
+        f: impl FnOnce(&mut Transform) -> O,
+    ) -> Result<O, ScriptError> {
+        self.validate_entity(entity)?;
+        let mut w = self.write();
+        w.get_mut::<Transform>(entity)
+            .map(|mut transform| f(&mut transform))
+            .ok_or_else(|| {
+                ScriptError::Other(format!("Entity {entity:?} has no `Transform` component"))
+            })
+    }
+
+    /// The world space transform of the entity, its local `Transform` if it has no `GlobalTransform`
+    pub fn world_transform(&self, entity: Entity) -> Result<GlobalTransform, ScriptError> {
+        self.validate_entity(entity)?;
+        let w = self.read();
+        w.get::<GlobalTransform>(entity)
+            .copied()
+            .or_else(|| {
+                w.get::<Transform>(entity)
+                    .map(|t| GlobalTransform::from(*t))
+            })
+            .ok_or_else(|| {
+                ScriptError::Other(format!("Entity {entity:?} has no `Transform` component"))
+            })
+    }
+
+    /// Moves the entity by the given offset
+    pub fn translate(&self, entity: Entity, offset: Vec3) -> Result<(), ScriptError> {
+        self.with_transform(entity, |t| t.translation += offset)
+    }
+
+    /// Rotates the entity so that its forward direction (-Z) points at the target and its up direction points towards `up`
+    pub fn look_at(&self, entity: Entity, target: Vec3, up: Vec3) -> Result<(), ScriptError> {
+        self.with_transform(entity, |t| t.look_at(target, up))
+    }
+
+    /// Rotates the entity around the point by the angle in radians around the axis, changing both its position and orientation
+    pub fn rotate_around(
+        &self,
+        entity: Entity,
+        point: Vec3,
+        axis: Vec3,
+        angle: f32,
+    ) -> Result<(), ScriptError> {
+        let axis = axis
+            .try_normalize()
+            .ok_or_else(|| ScriptError::Other(format!("Cannot rotate around the axis {axis}")))?;
+        self.with_transform(entity, |t| {
+            t.rotate_around(point, Quat::from_axis_angle(axis, angle))
+        })
+    }
+
+    /// The direction the entity faces (-Z) in world space
+    pub fn forward(&self, entity: Entity) -> Result<Vec3, ScriptError> {
+        self.world_transform(entity).map(|t| t.forward())
+    }
+
+    /// The position of the entity in world space
+    pub fn world_position(&self, entity: Entity) -> Result<Vec3, ScriptError> {
+        self.world_transform(entity).map(|t| t.translation())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_2;
+
+    use bevy::prelude::World;
+    use bevy_mod_scripting_core::world::WorldPointer;
+
+    use super::*;
+
+    #[test]
+    fn moves_and_rotates_entities() {
+        let mut world = World::new();
+        let entity = world.spawn(Transform::from_xyz(1.0, 0.0, 0.0)).id();
+        let without_transform = world.spawn(()).id();
+
+        // safety: the world outlives the pointer, which is only used in this test
+        let script_world = ScriptWorld::new(unsafe { WorldPointer::new(&mut world) });
+
+        script_world
+            .rotate_around(entity, Vec3::ZERO, Vec3::Y, FRAC_PI_2)
+            .unwrap();
+        assert!(script_world
+            .world_position(entity)
+            .unwrap()
+            .abs_diff_eq(Vec3::new(0.0, 0.0, -1.0), 1e-5));
+
+        script_world.translate(entity, Vec3::Z).unwrap();
+        script_world
+            .look_at(entity, Vec3::new(5.0, 0.0, 0.0), Vec3::Y)
+            .unwrap();
+        assert!(script_world
+            .forward(entity)
+            .unwrap()
+            .abs_diff_eq(Vec3::X, 1e-5));
+
+        assert!(script_world.translate(without_transform, Vec3::X).is_err());
+        assert!(script_world
+            .rotate_around(entity, Vec3::ZERO, Vec3::ZERO, 1.0)
+            .is_err());
+    }
+}
@@ -0,0 +1,271 @@
+use bevy::{
+    prelude::{
+        App, CoreStage, Entity, EventWriter, GlobalTransform, IntoSystemDescriptor, Query, ResMut,
+        Resource, Vec3,
+    },
+    transform::TransformSystem,
+    utils::{HashMap, HashSet},
+};
+use bevy_mod_scripting_core::prelude::ScriptError;
+
+use super::ScriptWorld;
+
+/// The volume of a trigger, centered on its owner's position plus the trigger's offset
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TriggerShape {
+    Sphere {
+        radius: f32,
+    },
+    /// An axis aligned box, rotations of the owner are ignored
+    Box {
+        half_extents: Vec3,
+    },
+}
+
+impl TriggerShape {
+    /// Half the size of the axis aligned bounding box of the shape
+    fn half_extents(&self) -> Vec3 {
+        match *self {
+            TriggerShape::Sphere { radius } => Vec3::splat(radius),
+            TriggerShape::Box { half_extents } => half_extents,
+        }
+    }
+
+    /// True if the point relative to the center of the shape is inside it
+    fn contains(&self, point: Vec3) -> bool {
+        match *self {
+            TriggerShape::Sphere { radius } => point.length_squared() <= radius * radius,
+            TriggerShape::Box { half_extents } => point.abs().cmple(half_extents).all(),
+        }
+    }
+}
+
+/// A named trigger volume attached to an entity by a script
+#[derive(Debug, Clone)]
+pub struct ScriptTrigger {
+    pub shape: TriggerShape,
+    /// the offset of the center of the volume from the owner's position
+    pub offset: Vec3,
+    /// the entities inside the volume as of the last update
+    inside: HashSet<Entity>,
+}
+
+impl ScriptTrigger {
+    /// The entities inside the volume as of the last update
+    pub fn inside(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.inside.iter().copied()
+    }
+}
+
+/// Sent whenever an entity enters or leaves a trigger volume defined by a script
+#[derive(Debug, Clone)]
+pub struct ScriptTriggerEvent {
+    /// the name the trigger was given by the script
+    pub trigger: String,
+    /// the entity the trigger is attached to
+    pub owner: Entity,
+    /// the entity which entered or left the volume
+    pub other: Entity,
+    /// true if the entity entered the volume, false if it left
+    pub entered: bool,
+}
+
+/// Trigger volumes defined by scripts, checked against the positions of all entities with a [`GlobalTransform`] every frame.
+///
+/// Entities are bucketed into a uniform grid of cells of size `cell_size` first, so each trigger only tests the entities
+/// in the cells it overlaps. Ideally the cell size is close to the size of a typical trigger.
+#[derive(Debug, Resource)]
+pub struct ScriptTriggers {
+    pub cell_size: f32,
+    triggers: HashMap<(Entity, String), ScriptTrigger>,
+}
+
+impl Default for ScriptTriggers {
+    fn default() -> Self {
+        Self {
+            cell_size: 4.0,
+            triggers: HashMap::default(),
+        }
+    }
+}
+
+impl ScriptTriggers {
+    /// Adds the trigger to the entity, replacing any trigger of the same name on it
+    pub fn insert(&mut self, owner: Entity, name: String, shape: TriggerShape, offset: Vec3) {
+        let inside = self
+            .triggers
+            .remove(&(owner, name.clone()))
+            .map(|t| t.inside)
+            .unwrap_or_default();
+        self.triggers.insert(
+            (owner, name),
+            ScriptTrigger {
+                shape,
+                offset,
+                inside,
+            },
+        );
+    }
+
+    /// Removes the trigger from the entity, entities inside it do not receive exit events
+    pub fn remove(&mut self, owner: Entity, name: &str) -> Option<ScriptTrigger> {
+        self.triggers.remove(&(owner, name.to_owned()))
+    }
+
+    pub fn get(&self, owner: Entity, name: &str) -> Option<&ScriptTrigger> {
+        self.triggers.get(&(owner, name.to_owned()))
+    }
+
+    /// All triggers along with the entity they are attached to and their name, e.g. for drawing them while debugging
+    pub fn iter(&self) -> impl Iterator<Item = (Entity, &str, &ScriptTrigger)> {
+        self.triggers
+            .iter()
+            .map(|((owner, name), trigger)| (*owner, name.as_str(), trigger))
+    }
+
+    fn cell(&self, position: Vec3) -> (i32, i32, i32) {
+        let cell = (position / self.cell_size).floor();
+        (cell.x as i32, cell.y as i32, cell.z as i32)
+    }
+}
+
+/// Finds the entities which entered or left each trigger since the last frame and sends [`ScriptTriggerEvent`]s for them.
+///
+/// Triggers of despawned entities are removed.
+pub fn update_script_triggers(
+    mut triggers: ResMut<ScriptTriggers>,
+    transforms: Query<(Entity, &GlobalTransform)>,
+    mut events: EventWriter<ScriptTriggerEvent>,
+) {
+    if triggers.triggers.is_empty() {
+        return;
+    }
+
+    // broadphase
+    let mut grid: HashMap<(i32, i32, i32), Vec<(Entity, Vec3)>> = HashMap::default();
+    for (entity, transform) in transforms.iter() {
+        let position = transform.translation();
+        grid.entry(triggers.cell(position))
+            .or_default()
+            .push((entity, position));
+    }
+
+    let triggers = &mut *triggers;
+    triggers
+        .triggers
+        .retain(|(owner, _), _| transforms.contains(*owner));
+
+    for ((owner, name), trigger) in triggers.triggers.iter_mut() {
+        let center = transforms.get(*owner).unwrap().1.translation() + trigger.offset;
+        let half_extents = trigger.shape.half_extents();
+        let (min, max) = (
+            (center - half_extents) / triggers.cell_size,
+            (center + half_extents) / triggers.cell_size,
+        );
+        let (min, max) = (min.floor(), max.floor());
+
+        let mut inside = HashSet::default();
+        for x in min.x as i32..=max.x as i32 {
+            for y in min.y as i32..=max.y as i32 {
+                for z in min.z as i32..=max.z as i32 {
+                    let candidates = grid.get(&(x, y, z)).into_iter().flatten();
+                    inside.extend(candidates.filter_map(|(entity, position)| {
+                        (entity != owner && trigger.shape.contains(*position - center))
+                            .then_some(*entity)
+                    }));
+                }
+            }
+        }
+
+        let entered = inside.difference(&trigger.inside).map(|e| (*e, true));
+        let left = trigger.inside.difference(&inside).map(|e| (*e, false));
+        for (other, entered) in entered.chain(left) {
+            events.send(ScriptTriggerEvent {
+                trigger: name.clone(),
+                owner: *owner,
+                other,
+                entered,
+            });
+        }
+
+        trigger.inside = inside;
+    }
+}
+
+/// Adds the [`ScriptTriggerEvent`] event and the system updating triggers, unless already added.
+///
+/// Triggers are updated after transforms are propagated, called by the trigger API providers.
+pub fn register_script_triggers(app: &mut App) {
+    if !app.world.contains_resource::<ScriptTriggers>() {
+        app.init_resource::<ScriptTriggers>()
+            .add_event::<ScriptTriggerEvent>()
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                update_script_triggers.after(TransformSystem::TransformPropagate),
+            );
+    }
+}
+
+impl ScriptWorld {
+    fn script_triggers<O, F: FnOnce(&mut ScriptTriggers) -> O>(
+        &self,
+        f: F,
+    ) -> Result<O, ScriptError> {
+        let mut w = self.write();
+        w.get_resource_mut::<ScriptTriggers>()
+            .map(|mut triggers| f(&mut triggers))
+            .ok_or_else(|| {
+                ScriptError::Other(
+                    "Triggers are not enabled, add a trigger API provider".to_owned(),
+                )
+            })
+    }
+
+    /// Attaches a named trigger volume to the entity, replacing any trigger of the same name on it
+    pub fn add_trigger(
+        &self,
+        owner: Entity,
+        name: String,
+        shape: TriggerShape,
+        offset: Vec3,
+    ) -> Result<(), ScriptError> {
+        self.script_triggers(|triggers| triggers.insert(owner, name, shape, offset))
+    }
+
+    /// Removes the named trigger from the entity, returns false if it had no such trigger
+    pub fn remove_trigger(&self, owner: Entity, name: &str) -> Result<bool, ScriptError> {
+        self.script_triggers(|triggers| triggers.remove(owner, name).is_some())
+    }
+
+    /// The entities inside the named trigger of the entity as of the last update
+    pub fn entities_in_trigger(
+        &self,
+        owner: Entity,
+        name: &str,
+    ) -> Result<Vec<Entity>, ScriptError> {
+        self.script_triggers(|triggers| {
+            triggers
+                .get(owner, name)
+                .map(|t| t.inside().collect())
+                .unwrap_or_default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shapes_contain_points_relative_to_their_center() {
+        let sphere = TriggerShape::Sphere { radius: 2.0 };
+        assert!(sphere.contains(Vec3::new(1.0, 1.0, 1.0)));
+        assert!(!sphere.contains(Vec3::new(1.5, 1.5, 0.0)));
+
+        let cube = TriggerShape::Box {
+            half_extents: Vec3::new(1.0, 2.0, 3.0),
+        };
+        assert!(cube.contains(Vec3::new(-1.0, 1.5, 2.9)));
+        assert!(!cube.contains(Vec3::new(0.0, -2.5, 0.0)));
+    }
+}
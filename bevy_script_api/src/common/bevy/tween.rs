@@ -0,0 +1,332 @@
+use bevy::{
+    prelude::{
+        App, Color, CoreStage, Entity, Events, IntoSystemDescriptor, Mut, Quat, ReflectComponent,
+        Resource, Time, Vec2, Vec3, Vec4, World,
+    },
+    reflect::{GetPath, Reflect},
+    transform::TransformSystem,
+};
+use bevy_mod_scripting_core::prelude::{Recipients, ScriptError};
+
+use crate::common::mathx::Easing;
+
+use super::ScriptWorld;
+
+/// A value which can be tweened, fields of these types can be the target of a tween
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TweenValue {
+    F32(f32),
+    F64(f64),
+    Vec2(Vec2),
+    Vec3(Vec3),
+    Vec4(Vec4),
+    Quat(Quat),
+    Color(Color),
+}
+
+impl TweenValue {
+    /// Reads the value of a field of one of the tweenable types
+    pub fn from_reflect(field: &dyn Reflect) -> Option<Self> {
+        let any = field.as_any();
+        Some(if let Some(v) = any.downcast_ref::<f32>() {
+            TweenValue::F32(*v)
+        } else if let Some(v) = any.downcast_ref::<f64>() {
+            TweenValue::F64(*v)
+        } else if let Some(v) = any.downcast_ref::<Vec2>() {
+            TweenValue::Vec2(*v)
+        } else if let Some(v) = any.downcast_ref::<Vec3>() {
+            TweenValue::Vec3(*v)
+        } else if let Some(v) = any.downcast_ref::<Vec4>() {
+            TweenValue::Vec4(*v)
+        } else if let Some(v) = any.downcast_ref::<Quat>() {
+            TweenValue::Quat(*v)
+        } else if let Some(v) = any.downcast_ref::<Color>() {
+            TweenValue::Color(*v)
+        } else {
+            return None;
+        })
+    }
+
+    /// Writes the value to a field of the same type, returns false if the types differ
+    fn apply(self, field: &mut dyn Reflect) -> bool {
+        let any = field.as_any_mut();
+        match self {
+            TweenValue::F32(v) => any.downcast_mut().map(|f| *f = v),
+            TweenValue::F64(v) => any.downcast_mut().map(|f| *f = v),
+            TweenValue::Vec2(v) => any.downcast_mut().map(|f| *f = v),
+            TweenValue::Vec3(v) => any.downcast_mut().map(|f| *f = v),
+            TweenValue::Vec4(v) => any.downcast_mut().map(|f| *f = v),
+            TweenValue::Quat(v) => any.downcast_mut().map(|f| *f = v),
+            TweenValue::Color(v) => any.downcast_mut().map(|f| *f = v),
+        }
+        .is_some()
+    }
+
+    /// Converts the target given by a script to the type of the field, scripts have a single number type
+    /// and pass quaternions and colors as `Vec4`s in some languages
+    fn coerce_like(self, start: TweenValue) -> Option<Self> {
+        use TweenValue::*;
+        Some(match (self, start) {
+            (F32(v), F64(_)) => F64(v as f64),
+            (F64(v), F32(_)) => F32(v as f32),
+            (Vec4(v), Quat(_)) => Quat(bevy::prelude::Quat::from_vec4(v).normalize()),
+            (Vec4(v), Color(_)) => Color(bevy::prelude::Color::rgba(v.x, v.y, v.z, v.w)),
+            (target, start)
+                if std::mem::discriminant(&target) == std::mem::discriminant(&start) =>
+            {
+                target
+            }
+            _ => return None,
+        })
+    }
+
+    /// Interpolates between two values of the same type, rotations are interpolated spherically
+    fn lerp(self, target: TweenValue, s: f32) -> Self {
+        use TweenValue::*;
+        match (self, target) {
+            (F32(a), F32(b)) => F32(a + (b - a) * s),
+            (F64(a), F64(b)) => F64(a + (b - a) * s as f64),
+            (Vec2(a), Vec2(b)) => Vec2(a.lerp(b, s)),
+            (Vec3(a), Vec3(b)) => Vec3(a.lerp(b, s)),
+            (Vec4(a), Vec4(b)) => Vec4(a.lerp(b, s)),
+            (Quat(a), Quat(b)) => Quat(a.slerp(b, s)),
+            (Color(a), Color(b)) => {
+                let (a, b) = (Vec4::from(a.as_rgba_f32()), Vec4::from(b.as_rgba_f32()));
+                let c = a.lerp(b, s);
+                Color(bevy::prelude::Color::rgba(c.x, c.y, c.z, c.w))
+            }
+            // the target is coerced to the type of the start value when the tween is created
+            _ => target,
+        }
+    }
+}
+
+/// Sent once a tween started through [`ScriptWorld::tween`] reaches its target
+#[derive(Debug, Clone)]
+pub struct ScriptTweenFinished {
+    /// the scripts which started the tween
+    pub recipients: Recipients,
+    /// the ID returned by [`ScriptWorld::tween`]
+    pub tween: u32,
+    pub entity: Entity,
+    /// the tweened path including the component, e.g. `Transform.translation`
+    pub path: String,
+}
+
+struct Tween {
+    id: u32,
+    recipients: Recipients,
+    entity: Entity,
+    component_data: ReflectComponent,
+    path: String,
+    /// the path within the component
+    field: String,
+    start: TweenValue,
+    target: TweenValue,
+    duration: f32,
+    elapsed: f32,
+    easing: Easing,
+}
+
+/// Tweens started by scripts, advanced by the frame time every frame by [`advance_script_tweens`].
+///
+/// Tweens are removed once their entity is despawned or the tweened component removed.
+#[derive(Default, Resource)]
+pub struct ScriptTweens {
+    tweens: Vec<Tween>,
+    next_id: u32,
+}
+
+impl ScriptTweens {
+    /// The number of tweens in progress
+    pub fn len(&self) -> usize {
+        self.tweens.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tweens.is_empty()
+    }
+
+    /// Stops the tween with the given ID where it is, returns false if no such tween is in progress
+    pub fn cancel(&mut self, tween: u32) -> bool {
+        let before = self.tweens.len();
+        self.tweens.retain(|t| t.id != tween);
+        before != self.tweens.len()
+    }
+}
+
+/// Advances all tweens by the frame time, sending [`ScriptTweenFinished`] events for those which reached their target
+pub fn advance_script_tweens(world: &mut World) {
+    let delta = world.resource::<Time>().delta_seconds();
+
+    world.resource_scope(|world, mut tweens: Mut<ScriptTweens>| {
+        let mut finished = Vec::default();
+
+        tweens.tweens.retain_mut(|tween| {
+            let mut component = match tween.component_data.reflect_mut(world, tween.entity) {
+                Some(c) => c,
+                None => return false,
+            };
+            let field = match component.reflect_path_mut(&tween.field) {
+                Ok(field) => field,
+                Err(_) => return false,
+            };
+
+            tween.elapsed += delta;
+            let t = if tween.duration > 0.0 {
+                tween.elapsed / tween.duration
+            } else {
+                1.0
+            };
+            tween
+                .start
+                .lerp(tween.target, tween.easing.ease(t))
+                .apply(field);
+
+            if t < 1.0 {
+                return true;
+            }
+            finished.push(ScriptTweenFinished {
+                recipients: tween.recipients.clone(),
+                tween: tween.id,
+                entity: tween.entity,
+                path: tween.path.clone(),
+            });
+            false
+        });
+
+        let mut events = world.resource_mut::<Events<ScriptTweenFinished>>();
+        for tween in finished {
+            events.send(tween);
+        }
+    });
+}
+
+/// Adds the tween system and the [`ScriptTweenFinished`] event, unless already added.
+///
+/// Tweens are advanced before transforms are propagated, so tweened transforms are rendered in the same frame.
+/// Called by the tween API providers.
+pub fn register_script_tweens(app: &mut App) {
+    if app.world.contains_resource::<ScriptTweens>() {
+        return;
+    }
+
+    app.init_resource::<ScriptTweens>()
+        .add_event::<ScriptTweenFinished>()
+        .add_system_to_stage(
+            CoreStage::PostUpdate,
+            advance_script_tweens.before(TransformSystem::TransformPropagate),
+        );
+}
+
+impl ScriptWorld {
+    /// Interpolates the value at the given path, made up of the component's type name and a field path
+    /// (e.g. `Transform.translation` or `Sprite.color`), on the given entity from its current value to the target
+    /// over `duration` seconds, replacing any tween of the same path in progress.
+    /// Once the target is reached a [`ScriptTweenFinished`] event is sent to the recipients.
+    ///
+    /// Returns the ID of the tween which can be passed to [`ScriptWorld::cancel_tween`].
+    pub fn tween(
+        &self,
+        entity: Entity,
+        path: &str,
+        target: TweenValue,
+        duration: f32,
+        easing: Easing,
+        recipients: Recipients,
+    ) -> Result<u32, ScriptError> {
+        self.validate_entity(entity)?;
+        let (type_name, field) = path.split_once('.').ok_or_else(|| {
+            ScriptError::Other(format!(
+                "Cannot tween `{path}`, expected the path of a field such as `Transform.translation`"
+            ))
+        })?;
+
+        let comp_type = self.get_type_by_name(type_name).ok_or_else(|| {
+            ScriptError::Other(format!("No type named `{type_name}` is registered"))
+        })?;
+        let component_data = comp_type
+            .data::<ReflectComponent>()
+            .cloned()
+            .ok_or_else(|| {
+                ScriptError::Other(format!("Not a component {}", comp_type.short_name()))
+            })?;
+
+        let mut w = self.write();
+        if !w.contains_resource::<ScriptTweens>() {
+            return Err(ScriptError::Other(
+                "Tweens are not enabled, add a tween API provider".to_owned(),
+            ));
+        }
+
+        let start = component_data
+            .reflect(&w, entity)
+            .ok_or_else(|| {
+                ScriptError::Other(format!("Entity {entity:?} has no `{type_name}` component"))
+            })?
+            .reflect_path(field)
+            .ok()
+            .and_then(TweenValue::from_reflect)
+            .ok_or_else(|| {
+                ScriptError::Other(format!(
+                    "Cannot tween `{path}`, the field does not exist or is not a number, vector, quaternion or color"
+                ))
+            })?;
+        let target = target.coerce_like(start).ok_or_else(|| {
+            ScriptError::Other(format!(
+                "Cannot tween `{path}` from {start:?} to the target {target:?}"
+            ))
+        })?;
+
+        let mut tweens = w.resource_mut::<ScriptTweens>();
+        tweens
+            .tweens
+            .retain(|t| t.entity != entity || t.path != path);
+        let id = tweens.next_id;
+        tweens.next_id = id.wrapping_add(1);
+        tweens.tweens.push(Tween {
+            id,
+            recipients,
+            entity,
+            component_data,
+            path: path.to_owned(),
+            field: field.to_owned(),
+            start,
+            target,
+            duration,
+            elapsed: 0.0,
+            easing,
+        });
+        Ok(id)
+    }
+
+    /// Stops the tween with the given ID where it is, returns false if no such tween is in progress
+    pub fn cancel_tween(&self, tween: u32) -> bool {
+        let mut w = self.write();
+        w.get_resource_mut::<ScriptTweens>()
+            .map_or(false, |mut tweens| tweens.cancel(tween))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn targets_are_coerced_to_the_type_of_the_field() {
+        let start = TweenValue::F64(0.0);
+        let target = TweenValue::F32(2.0).coerce_like(start).unwrap();
+        assert_eq!(start.lerp(target, 0.25), TweenValue::F64(0.5));
+
+        let start = TweenValue::Quat(Quat::IDENTITY);
+        assert!(matches!(
+            TweenValue::Vec4(Vec4::new(0.0, 0.0, 0.0, 2.0)).coerce_like(start),
+            Some(TweenValue::Quat(q)) if q == Quat::IDENTITY
+        ));
+
+        assert_eq!(
+            TweenValue::Vec2(Vec2::ONE).coerce_like(TweenValue::Vec3(Vec3::ZERO)),
+            None
+        );
+    }
+}
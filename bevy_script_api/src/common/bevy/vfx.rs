@@ -0,0 +1,85 @@
+use bevy::{
+    ecs::system::Command,
+    prelude::{App, DespawnRecursive, Entity, Mut, Resource, Vec3, World},
+};
+use bevy_mod_scripting_core::prelude::{ScriptError, ScriptQuotas};
+
+use super::ScriptWorld;
+
+/// Spawns visual effects on behalf of scripts, implemented by the game for the particle crate it uses.
+///
+/// Scripts only ever refer to effects by name, so mods keep working when the effects behind the names
+/// or the particle crate itself change. With bevy_hanabi a backend could look like:
+/// ```rust,ignore
+/// struct HanabiVfx(HashMap<String, Handle<EffectAsset>>);
+///
+/// impl ScriptVfxBackend for HanabiVfx {
+///     fn spawn(&mut self, world: &mut World, effect: &str, position: Vec3) -> Result<Entity, ScriptError> {
+///         let handle = self.0.get(effect).cloned()
+///             .ok_or_else(|| ScriptError::Other(format!("Unknown effect `{effect}`")))?;
+///         Ok(world
+///             .spawn(ParticleEffectBundle::new(handle).with_spawner(Spawner::once(32.0.into(), true)))
+///             .insert(Transform::from_translation(position))
+///             .id())
+///     }
+/// }
+///
+/// app.add_script_vfx_backend(HanabiVfx(effects));
+/// ```
+pub trait ScriptVfxBackend: Send + Sync + 'static {
+    /// Spawns the effect with the given name at the given position, returning the entity of the effect
+    fn spawn(
+        &mut self,
+        world: &mut World,
+        effect: &str,
+        position: Vec3,
+    ) -> Result<Entity, ScriptError>;
+
+    /// Stops an effect returned by `spawn`, by default the entity of the effect is despawned along with its children
+    fn stop(&mut self, world: &mut World, effect: Entity) -> Result<(), ScriptError> {
+        DespawnRecursive { entity: effect }.write(world);
+        Ok(())
+    }
+}
+
+/// The visual effects backend used by scripts, see [`ScriptVfxBackend`]
+#[derive(Resource)]
+pub struct ScriptVfx(Box<dyn ScriptVfxBackend>);
+
+pub trait AddScriptVfxBackend {
+    /// Sets the backend spawning visual effects requested by scripts, replacing any previous backend
+    fn add_script_vfx_backend(&mut self, backend: impl ScriptVfxBackend) -> &mut Self;
+}
+
+impl AddScriptVfxBackend for App {
+    fn add_script_vfx_backend(&mut self, backend: impl ScriptVfxBackend) -> &mut Self {
+        self.insert_resource(ScriptVfx(Box::new(backend)))
+    }
+}
+
+impl ScriptWorld {
+    fn with_vfx<O, F: FnOnce(&mut World, &mut dyn ScriptVfxBackend) -> Result<O, ScriptError>>(
+        &self,
+        f: F,
+    ) -> Result<O, ScriptError> {
+        let mut w = self.write();
+        if !w.contains_resource::<ScriptVfx>() {
+            return Err(ScriptError::Other(
+                "No visual effects backend was added, see `add_script_vfx_backend`".to_owned(),
+            ));
+        }
+        w.resource_scope(|w, mut vfx: Mut<ScriptVfx>| f(w, vfx.0.as_mut()))
+    }
+
+    /// Spawns the named visual effect at the given position through the game's [`ScriptVfxBackend`]
+    pub fn spawn_vfx(&self, effect: &str, position: Vec3) -> Result<Entity, ScriptError> {
+        self.charge_quota(ScriptQuotas::SPAWN)?;
+        self.with_vfx(|w, vfx| vfx.spawn(w, effect, position))
+    }
+
+    /// Stops a visual effect spawned with [`ScriptWorld::spawn_vfx`]
+    pub fn stop_vfx(&self, effect: Entity) -> Result<(), ScriptError> {
+        self.validate_entity(effect)?;
+        self.with_vfx(|w, vfx| vfx.stop(w, effect))
+    }
+}
@@ -0,0 +1,259 @@
+use bevy::{
+    ecs::change_detection::DetectChanges,
+    prelude::{App, CoreStage, Entity, Events, Mut, ReflectComponent, Resource, World},
+    reflect::{GetPath, Reflect},
+};
+use bevy_mod_scripting_core::{
+    hook::HookName,
+    prelude::{Recipients, ScriptError},
+};
+
+use crate::common::shared::ScriptValue;
+
+use super::ScriptWorld;
+
+/// Sent when a field watched through [`ScriptWorld::watch`] changed its value
+#[derive(Debug, Clone)]
+pub struct ScriptFieldChanged {
+    /// the scripts which registered the watch
+    pub recipients: Recipients,
+    /// the ID returned by [`ScriptWorld::watch`]
+    pub watch: u32,
+    pub entity: Entity,
+    /// the watched path including the component, e.g. `Health.hp`
+    pub path: String,
+    /// the hook the scripts asked to be called, e.g. `on_hp_changed`
+    pub hook: HookName,
+    pub old: ScriptValue,
+    pub new: ScriptValue,
+}
+
+struct FieldWatch {
+    id: u32,
+    recipients: Recipients,
+    entity: Entity,
+    component_data: ReflectComponent,
+    path: String,
+    /// the path within the component, empty if the whole component is watched
+    field: String,
+    hook: HookName,
+    last: ScriptValue,
+}
+
+/// Fields watched by scripts, checked once per frame by [`check_script_watches`].
+///
+/// Watches are removed once their entity is despawned.
+#[derive(Default, Resource)]
+pub struct ScriptWatches {
+    watches: Vec<FieldWatch>,
+    next_id: u32,
+}
+
+impl ScriptWatches {
+    /// The number of active watches
+    pub fn len(&self) -> usize {
+        self.watches.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.watches.is_empty()
+    }
+
+    /// Stops the watch with the given ID, returns false if no such watch exists
+    pub fn unwatch(&mut self, watch: u32) -> bool {
+        let before = self.watches.len();
+        self.watches.retain(|w| w.id != watch);
+        before != self.watches.len()
+    }
+}
+
+fn read_field(component: &dyn Reflect, field: &str) -> Option<ScriptValue> {
+    if field.is_empty() {
+        ScriptValue::from_reflect(component)
+    } else {
+        ScriptValue::from_reflect(component.path(field).ok()?)
+    }
+}
+
+/// Sends [`ScriptFieldChanged`] events for every watched field whose value changed since the last check.
+///
+/// Only components whose change ticks show they were mutably accessed since the last check are inspected,
+/// and of those only fields whose value actually differs from the last one seen trigger an event.
+pub fn check_script_watches(world: &mut World) {
+    world.resource_scope(|world, mut watches: Mut<ScriptWatches>| {
+        let mut changes = Vec::default();
+
+        watches.watches.retain_mut(|watch| {
+            let component = match watch.component_data.reflect_mut(world, watch.entity) {
+                Some(c) => c,
+                // the component may be inserted again later, unless the entity is gone
+                None => return world.get_entity(watch.entity).is_some(),
+            };
+
+            if !component.is_changed() {
+                return true;
+            }
+
+            let new = read_field(&*component, &watch.field).unwrap_or_default();
+            if new != watch.last {
+                changes.push(ScriptFieldChanged {
+                    recipients: watch.recipients.clone(),
+                    watch: watch.id,
+                    entity: watch.entity,
+                    path: watch.path.clone(),
+                    hook: watch.hook,
+                    old: std::mem::replace(&mut watch.last, new.clone()),
+                    new,
+                });
+            }
+            true
+        });
+
+        let mut events = world.resource_mut::<Events<ScriptFieldChanged>>();
+        for change in changes {
+            events.send(change);
+        }
+    });
+}
+
+/// Adds the watch checking system and the [`ScriptFieldChanged`] event, unless already added.
+///
+/// The system runs in [`CoreStage::Last`] so that changes made in every other stage are seen.
+/// Called by the API providers forwarding field changes to scripts.
+pub fn register_script_watches(app: &mut App) {
+    if app.world.contains_resource::<ScriptWatches>() {
+        return;
+    }
+
+    app.init_resource::<ScriptWatches>()
+        .add_event::<ScriptFieldChanged>()
+        .add_system_to_stage(CoreStage::Last, check_script_watches);
+}
+
+impl ScriptWorld {
+    /// Watches the value at the given path, made up of the component's type name and a field path
+    /// (e.g. `Health.hp` or `Transform.translation.x`), on the given entity.
+    /// Whenever the value changes a [`ScriptFieldChanged`] event for the given hook is sent to the recipients.
+    ///
+    /// Returns the ID of the watch which can be passed to [`ScriptWorld::unwatch`].
+    /// Fails if the hook name cannot be interned, so that the watch does not fail once it fires.
+    pub fn watch(
+        &self,
+        entity: Entity,
+        path: &str,
+        hook: &str,
+        recipients: Recipients,
+    ) -> Result<u32, ScriptError> {
+        self.validate_entity(entity)?;
+        let hook = HookName::new_checked(hook)?;
+        let (type_name, field) = path.split_once('.').unwrap_or((path, ""));
+
+        let comp_type = self.get_type_by_name(type_name).ok_or_else(|| {
+            ScriptError::Other(format!("No type named `{type_name}` is registered"))
+        })?;
+        let component_data = comp_type
+            .data::<ReflectComponent>()
+            .cloned()
+            .ok_or_else(|| {
+                ScriptError::Other(format!("Not a component {}", comp_type.short_name()))
+            })?;
+
+        let mut w = self.write();
+        if !w.contains_resource::<ScriptWatches>() {
+            return Err(ScriptError::Other(
+                "Watches are not enabled, add a watch events API provider".to_owned(),
+            ));
+        }
+
+        let last = match component_data.reflect(&w, entity) {
+            Some(component) => read_field(component, field).ok_or_else(|| {
+                ScriptError::Other(format!(
+                    "Cannot watch `{path}`, the field does not exist or is not a boolean, number, string or a collection of these"
+                ))
+            })?,
+            None => ScriptValue::Nil,
+        };
+
+        let mut watches = w.resource_mut::<ScriptWatches>();
+        let id = watches.next_id;
+        watches.next_id = id.wrapping_add(1);
+        watches.watches.push(FieldWatch {
+            id,
+            recipients,
+            entity,
+            component_data,
+            path: path.to_owned(),
+            field: field.to_owned(),
+            hook,
+            last,
+        });
+        Ok(id)
+    }
+
+    /// Stops the watch with the given ID, returns false if no such watch exists
+    pub fn unwatch(&self, watch: u32) -> bool {
+        let mut w = self.write();
+        w.get_resource_mut::<ScriptWatches>()
+            .map_or(false, |mut watches| watches.unwatch(watch))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::Component;
+    use bevy_mod_scripting_core::world::WorldPointer;
+
+    use super::*;
+
+    #[derive(Component, Reflect, Default)]
+    #[reflect(Component)]
+    struct Health {
+        hp: u32,
+    }
+
+    fn changes(app: &App) -> Vec<(u32, ScriptValue, ScriptValue)> {
+        let events = app.world.resource::<Events<ScriptFieldChanged>>();
+        events
+            .get_reader()
+            .iter(events)
+            .map(|e| (e.watch, e.old.clone(), e.new.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn watches_fire_only_when_the_value_changes() {
+        let mut app = App::new();
+        app.register_type::<Health>();
+        let entity = app.world.spawn(Health { hp: 10 }).id();
+        // safety: the world outlives the pointer, which is only used in this test
+        let script_world = ScriptWorld::new(unsafe { WorldPointer::new(&mut app.world) });
+
+        let disabled = script_world.watch(entity, "Health.hp", "on_hp_changed", Recipients::All);
+        assert!(disabled.unwrap_err().to_string().contains("not enabled"));
+
+        register_script_watches(&mut app);
+        assert!(script_world
+            .watch(entity, "Health.mana", "on_mana_changed", Recipients::All)
+            .is_err());
+        let watch = script_world
+            .watch(entity, "Health.hp", "on_hp_changed", Recipients::All)
+            .unwrap();
+
+        app.update();
+        assert!(changes(&app).is_empty());
+
+        app.world.get_mut::<Health>(entity).unwrap().hp = 5;
+        app.update();
+        // writing the same value again is not a change
+        app.world.get_mut::<Health>(entity).unwrap().hp = 5;
+        app.update();
+        assert_eq!(
+            changes(&app),
+            vec![(watch, ScriptValue::Integer(10), ScriptValue::Integer(5))]
+        );
+
+        app.world.despawn(entity);
+        app.update();
+        assert!(app.world.resource::<ScriptWatches>().is_empty());
+    }
+}
@@ -0,0 +1,88 @@
+use bevy::{
+    prelude::{Vec2, World},
+    window::{CursorGrabMode, Window, Windows},
+};
+use bevy_mod_scripting_core::prelude::ScriptError;
+
+use crate::common::capabilities::ScriptCapabilities;
+
+use super::ScriptWorld;
+
+/// Window and cursor access, all of which requires the `window` capability
+impl ScriptWorld {
+    fn with_primary_window<O, F: FnOnce(&mut Window) -> O>(&self, f: F) -> Result<O, ScriptError> {
+        let mut w = self.write();
+        Self::require_window_capability(&w)?;
+
+        w.get_resource_mut::<Windows>()
+            .and_then(|mut windows| windows.get_primary_mut().map(f))
+            .ok_or_else(|| ScriptError::Other("There is no primary window".to_owned()))
+    }
+
+    fn require_window_capability(w: &World) -> Result<(), ScriptError> {
+        ScriptCapabilities::require(
+            w.get_resource::<ScriptCapabilities>()
+                .map(|c| c.window)
+                .unwrap_or(false),
+            "window",
+        )
+    }
+
+    /// The logical size of the primary window
+    pub fn window_size(&self) -> Result<Vec2, ScriptError> {
+        self.with_primary_window(|window| Vec2::new(window.width(), window.height()))
+    }
+
+    /// The logical position of the cursor within the primary window, None if the cursor is outside of the window
+    pub fn cursor_position(&self) -> Result<Option<Vec2>, ScriptError> {
+        self.with_primary_window(|window| window.cursor_position())
+    }
+
+    /// Locks the cursor inside the primary window, or releases it
+    pub fn set_cursor_grab(&self, grab: bool) -> Result<(), ScriptError> {
+        self.with_primary_window(|window| {
+            window.set_cursor_grab_mode(if grab {
+                CursorGrabMode::Confined
+            } else {
+                CursorGrabMode::None
+            })
+        })
+    }
+
+    /// Shows or hides the cursor while it is over the primary window
+    pub fn set_cursor_visible(&self, visible: bool) -> Result<(), ScriptError> {
+        self.with_primary_window(|window| window.set_cursor_visibility(visible))
+    }
+
+    /// Sets the title of the primary window
+    pub fn set_window_title(&self, title: String) -> Result<(), ScriptError> {
+        self.with_primary_window(|window| window.set_title(title))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_mod_scripting_core::world::WorldPointer;
+
+    use super::*;
+
+    #[test]
+    fn window_access_requires_the_window_capability() {
+        let mut world = World::new();
+        // safety: the world outlives the pointer, which is only used in this test
+        let script_world = ScriptWorld::new(unsafe { WorldPointer::new(&mut world) });
+
+        let denied = script_world.window_size().unwrap_err().to_string();
+        assert!(denied.contains("`window` capability"), "{denied}");
+
+        script_world.write().insert_resource(ScriptCapabilities {
+            window: true,
+            ..Default::default()
+        });
+        let missing = script_world
+            .set_cursor_visible(false)
+            .unwrap_err()
+            .to_string();
+        assert!(missing.contains("no primary window"), "{missing}");
+    }
+}
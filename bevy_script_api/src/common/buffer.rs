@@ -0,0 +1,339 @@
+//! Binary buffers shared by the `buffer` modules of all script hosts, for save formats, procedural generation and networking
+use std::{str::FromStr, sync::Arc};
+
+use bevy_mod_scripting_core::prelude::ScriptError;
+use parking_lot::RwLock;
+
+/// The types which can be read from and written to a [`ScriptBuffer`], all stored in little endian order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BufferType {
+    U8,
+    I8,
+    U16,
+    I16,
+    U32,
+    I32,
+    I64,
+    F32,
+    F64,
+}
+
+impl BufferType {
+    /// The number of bytes values of this type take up
+    pub fn size(self) -> usize {
+        match self {
+            BufferType::U8 | BufferType::I8 => 1,
+            BufferType::U16 | BufferType::I16 => 2,
+            BufferType::U32 | BufferType::I32 | BufferType::F32 => 4,
+            BufferType::I64 | BufferType::F64 => 8,
+        }
+    }
+}
+
+impl FromStr for BufferType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "u8" => BufferType::U8,
+            "i8" => BufferType::I8,
+            "u16" => BufferType::U16,
+            "i16" => BufferType::I16,
+            "u32" => BufferType::U32,
+            "i32" => BufferType::I32,
+            "i64" => BufferType::I64,
+            "f32" => BufferType::F32,
+            "f64" => BufferType::F64,
+            _ => {
+                return Err(format!(
+                    "Unknown buffer type `{s}`, expected one of u8, i8, u16, i16, u32, i32, i64, f32 or f64"
+                ))
+            }
+        })
+    }
+}
+
+/// A value read from or written to a [`ScriptBuffer`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BufferValue {
+    Integer(i64),
+    Float(f64),
+}
+
+impl BufferValue {
+    fn as_i64(self) -> i64 {
+        match self {
+            BufferValue::Integer(i) => i,
+            BufferValue::Float(f) => f as i64,
+        }
+    }
+
+    fn as_f64(self) -> f64 {
+        match self {
+            BufferValue::Integer(i) => i as f64,
+            BufferValue::Float(f) => f,
+        }
+    }
+}
+
+/// A resizable block of bytes which scripts read and write typed values in at byte offsets, starting at 0.
+///
+/// Buffers are shared, copies of a buffer passed between functions or stored in several places all refer to the same bytes.
+/// Use [`ScriptBuffer::slice`] to copy bytes into a new buffer.
+#[derive(Debug, Clone, Default)]
+pub struct ScriptBuffer(Arc<RwLock<Vec<u8>>>);
+
+impl ScriptBuffer {
+    /// A buffer of the given length filled with zeros
+    pub fn new(len: usize) -> Self {
+        Self::from_bytes(vec![0; len])
+    }
+
+    pub fn from_bytes(bytes: Vec<u8>) -> Self {
+        Self(Arc::new(RwLock::new(bytes)))
+    }
+
+    /// A copy of the bytes in the buffer
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.0.read().clone()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.read().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Changes the length of the buffer, new bytes are zero
+    pub fn resize(&self, len: usize) {
+        self.0.write().resize(len, 0)
+    }
+
+    fn range(&self, offset: usize, len: usize) -> Result<std::ops::Range<usize>, ScriptError> {
+        let buffer_len = self.len();
+        match offset.checked_add(len) {
+            Some(end) if end <= buffer_len => Ok(offset..end),
+            _ => Err(ScriptError::Other(format!(
+                "Cannot access {len} byte(s) at offset {offset} of a buffer of length {buffer_len}"
+            ))),
+        }
+    }
+
+    /// Reads the value of the given type starting at the byte offset
+    pub fn read(&self, ty: BufferType, offset: usize) -> Result<BufferValue, ScriptError> {
+        let range = self.range(offset, ty.size())?;
+        let bytes = self.0.read();
+        let mut b = [0; 8];
+        b[..ty.size()].copy_from_slice(&bytes[range]);
+
+        Ok(match ty {
+            BufferType::U8 => BufferValue::Integer(b[0].into()),
+            BufferType::I8 => BufferValue::Integer((b[0] as i8).into()),
+            BufferType::U16 => BufferValue::Integer(u16::from_le_bytes([b[0], b[1]]).into()),
+            BufferType::I16 => BufferValue::Integer(i16::from_le_bytes([b[0], b[1]]).into()),
+            BufferType::U32 => {
+                BufferValue::Integer(u32::from_le_bytes([b[0], b[1], b[2], b[3]]).into())
+            }
+            BufferType::I32 => {
+                BufferValue::Integer(i32::from_le_bytes([b[0], b[1], b[2], b[3]]).into())
+            }
+            BufferType::I64 => BufferValue::Integer(i64::from_le_bytes(b)),
+            BufferType::F32 => {
+                BufferValue::Float(f32::from_le_bytes([b[0], b[1], b[2], b[3]]).into())
+            }
+            BufferType::F64 => BufferValue::Float(f64::from_le_bytes(b)),
+        })
+    }
+
+    /// Writes the value as the given type starting at the byte offset, integers are truncated to the size of the type
+    pub fn write(
+        &self,
+        ty: BufferType,
+        offset: usize,
+        value: BufferValue,
+    ) -> Result<(), ScriptError> {
+        let range = self.range(offset, ty.size())?;
+        let b = match ty {
+            BufferType::F32 => (value.as_f64() as f32).to_le_bytes().to_vec(),
+            BufferType::F64 => value.as_f64().to_le_bytes().to_vec(),
+            _ => value.as_i64().to_le_bytes()[..ty.size()].to_vec(),
+        };
+        self.0.write()[range].copy_from_slice(&b);
+        Ok(())
+    }
+
+    /// Reads `len` bytes starting at the byte offset as UTF-8 text
+    pub fn read_string(&self, offset: usize, len: usize) -> Result<String, ScriptError> {
+        let range = self.range(offset, len)?;
+        String::from_utf8(self.0.read()[range].to_vec())
+            .map_err(|e| ScriptError::Other(format!("The bytes are not valid UTF-8: {e}")))
+    }
+
+    /// Writes the text as UTF-8 starting at the byte offset, returns the number of bytes written
+    pub fn write_string(&self, offset: usize, text: &str) -> Result<usize, ScriptError> {
+        let range = self.range(offset, text.len())?;
+        self.0.write()[range].copy_from_slice(text.as_bytes());
+        Ok(text.len())
+    }
+
+    /// A new buffer containing a copy of the bytes in the range [start,end)
+    pub fn slice(&self, start: usize, end: usize) -> Result<ScriptBuffer, ScriptError> {
+        let range = self.range(start, end.saturating_sub(start))?;
+        Ok(Self::from_bytes(self.0.read()[range].to_vec()))
+    }
+
+    /// Encodes the bytes as standard base64 with padding
+    pub fn to_base64(&self) -> String {
+        let bytes = self.0.read();
+        let mut out = String::with_capacity((bytes.len() + 2) / 3 * 4);
+        for chunk in bytes.chunks(3) {
+            let n = chunk
+                .iter()
+                .enumerate()
+                .fold(0u32, |n, (i, b)| n | (u32::from(*b) << (16 - 8 * i)));
+            for i in 0..4 {
+                if i <= chunk.len() {
+                    out.push(BASE64_ALPHABET[((n >> (18 - 6 * i)) & 0x3f) as usize] as char);
+                } else {
+                    out.push('=');
+                }
+            }
+        }
+        out
+    }
+
+    /// Decodes standard base64, padding is optional
+    pub fn from_base64(text: &str) -> Result<ScriptBuffer, ScriptError> {
+        let invalid = || ScriptError::Other("Invalid base64 text".to_owned());
+        let text = text.trim_end_matches('=');
+        let mut bytes = Vec::with_capacity(text.len() * 3 / 4);
+
+        for chunk in text.as_bytes().chunks(4) {
+            if chunk.len() == 1 {
+                return Err(invalid());
+            }
+            let mut n = 0u32;
+            for (i, c) in chunk.iter().enumerate() {
+                let value = BASE64_ALPHABET
+                    .iter()
+                    .position(|a| a == c)
+                    .ok_or_else(invalid)?;
+                n |= (value as u32) << (18 - 6 * i);
+            }
+            bytes.extend_from_slice(&n.to_be_bytes()[1..chunk.len()]);
+        }
+        Ok(Self::from_bytes(bytes))
+    }
+}
+
+/// A read-only view of shared numeric data, such as the heights of a terrain edit, for event payloads too large
+/// to convert element by element.
+///
+/// Scripts receive a handle to the same `Arc` the event was sent with, so passing one to any number of scripts copies no elements,
+/// elements are only converted as scripts read them.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SharedF32Buffer(pub Arc<Vec<f32>>);
+
+impl SharedF32Buffer {
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// The element at the given index starting at 0, if in bounds
+    pub fn get(&self, index: usize) -> Option<f32> {
+        self.0.get(index).copied()
+    }
+}
+
+impl From<Arc<Vec<f32>>> for SharedF32Buffer {
+    fn from(values: Arc<Vec<f32>>) -> Self {
+        Self(values)
+    }
+}
+
+impl From<Vec<f32>> for SharedF32Buffer {
+    fn from(values: Vec<f32>) -> Self {
+        Self(Arc::new(values))
+    }
+}
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn typed_values_round_trip() {
+        let buffer = ScriptBuffer::new(16);
+        buffer
+            .write(BufferType::I16, 0, BufferValue::Integer(-2))
+            .unwrap();
+        buffer
+            .write(BufferType::F32, 2, BufferValue::Float(1.5))
+            .unwrap();
+        buffer
+            .write(BufferType::U8, 6, BufferValue::Integer(300))
+            .unwrap();
+        buffer
+            .write(BufferType::I64, 8, BufferValue::Integer(i64::MIN))
+            .unwrap();
+
+        assert_eq!(
+            buffer.read(BufferType::I16, 0).unwrap(),
+            BufferValue::Integer(-2)
+        );
+        assert_eq!(
+            buffer.read(BufferType::U16, 0).unwrap(),
+            BufferValue::Integer(0xfffe)
+        );
+        assert_eq!(
+            buffer.read(BufferType::F32, 2).unwrap(),
+            BufferValue::Float(1.5)
+        );
+        assert_eq!(
+            buffer.read(BufferType::U8, 6).unwrap(),
+            BufferValue::Integer(44)
+        );
+        assert_eq!(
+            buffer.read(BufferType::I64, 8).unwrap(),
+            BufferValue::Integer(i64::MIN)
+        );
+        assert!(buffer.read(BufferType::I64, 9).is_err());
+        assert!(buffer.read(BufferType::U8, usize::MAX).is_err());
+    }
+
+    #[test]
+    fn slices_are_copies() {
+        let buffer = ScriptBuffer::from_bytes(b"hello world".to_vec());
+        let slice = buffer.slice(6, 11).unwrap();
+        slice.write_string(0, "there").unwrap();
+        assert_eq!(buffer.read_string(6, 5).unwrap(), "world");
+        assert_eq!(slice.read_string(0, 5).unwrap(), "there");
+        assert!(buffer.slice(6, 12).is_err());
+    }
+
+    #[test]
+    fn base64_round_trip() {
+        for (bytes, text) in [
+            (&b""[..], ""),
+            (b"f", "Zg=="),
+            (b"fo", "Zm8="),
+            (b"foo", "Zm9v"),
+            (b"foobar", "Zm9vYmFy"),
+        ] {
+            let buffer = ScriptBuffer::from_bytes(bytes.to_vec());
+            assert_eq!(buffer.to_base64(), text);
+            assert_eq!(ScriptBuffer::from_base64(text).unwrap().to_bytes(), bytes);
+        }
+        assert!(ScriptBuffer::from_base64("Z").is_err());
+        assert!(ScriptBuffer::from_base64("Zm9v!").is_err());
+    }
+}
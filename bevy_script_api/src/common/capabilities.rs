@@ -0,0 +1,33 @@
+use bevy::prelude::Resource;
+use bevy_mod_scripting_core::prelude::ScriptError;
+
+/// Capabilities granted to scripts, API functions which reach outside of the game world check these
+/// every time they are called. Nothing is granted if this resource is missing.
+#[derive(Debug, Clone, Default, Resource)]
+pub struct ScriptCapabilities {
+    /// allows scripts to read and modify the primary window and the cursor
+    pub window: bool,
+    /// allows scripts to request screenshots, see [`crate::common::bevy::ScriptScreenshotRequests`]
+    pub screenshot: bool,
+}
+
+impl ScriptCapabilities {
+    /// Grants every capability
+    pub fn all() -> Self {
+        Self {
+            window: true,
+            screenshot: true,
+        }
+    }
+
+    /// Returns an error naming the capability if it's not granted
+    pub(crate) fn require(granted: bool, capability: &str) -> Result<(), ScriptError> {
+        if granted {
+            Ok(())
+        } else {
+            Err(ScriptError::Other(format!(
+                "Scripts were not granted the `{capability}` capability, enable it in the `ScriptCapabilities` resource"
+            )))
+        }
+    }
+}
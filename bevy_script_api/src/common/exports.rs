@@ -0,0 +1,233 @@
+//! Named APIs exported by scripts for other scripts to import, letting mods act as libraries for other mods
+use bevy::{
+    prelude::{App, CoreStage, EventReader, EventWriter, ResMut, Resource},
+    utils::{HashMap, HashSet},
+};
+use bevy_mod_scripting_core::{
+    event::ScriptLoaded,
+    prelude::{ScriptError, ScriptId},
+};
+
+use super::{bevy::ScriptWorld, shared::ScriptValue};
+
+/// An API exported by a script
+#[derive(Debug, Clone)]
+pub struct ScriptExport {
+    /// the script which registered the export
+    pub owner: ScriptId,
+    pub owner_name: String,
+    /// incremented every time the export is registered again, e.g. after the owner was reloaded
+    pub version: u64,
+    pub value: ScriptValue,
+    /// the frame the export was registered in
+    frame: u64,
+}
+
+/// Sent whenever an export becomes available or stops being available to the scripts importing it,
+/// so they can (re)initialize themselves once their dependencies are registered.
+///
+/// Forward these to the importing scripts as a hook, for example:
+/// ```rust,ignore
+/// fn forward_export_changes(mut changes: EventReader<ScriptExportChanged>, mut w: PriorityEventWriter<LuaEvent<String>>) {
+///     for change in changes.iter() {
+///         for importer in &change.dependents {
+///             w.send(LuaEvent { hook_name: "on_export_changed".into(), args: change.name.clone(), recipients: Recipients::Id(*importer) }, 0);
+///         }
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ScriptExportChanged {
+    pub name: String,
+    /// true if the export was registered, false if it was removed because its owner was reloaded or unloaded
+    pub available: bool,
+    /// the scripts which tried to import the export so far
+    pub dependents: Vec<ScriptId>,
+}
+
+/// Every API exported by scripts, along with the scripts depending on each.
+///
+/// Exports are copied in and out as [`ScriptValue`]s, so they work between scripts of any host, but may only contain data:
+/// constants, configuration or item definitions for instance. Functions cannot be exported since every script runs in its own context.
+///
+/// Exports are removed when their owner is reloaded, making the reloaded script register them again.
+#[derive(Debug, Default, Resource)]
+pub struct ScriptExports {
+    exports: HashMap<String, ScriptExport>,
+    /// the importers of every export requested so far, including exports which were never registered
+    dependents: HashMap<String, HashSet<ScriptId>>,
+    versions: HashMap<String, u64>,
+    changes: Vec<ScriptExportChanged>,
+    frame: u64,
+}
+
+impl ScriptExports {
+    /// Registers or replaces the export with the given name. Fails if the name is exported by another script
+    pub fn register(
+        &mut self,
+        name: &str,
+        owner: ScriptId,
+        owner_name: &str,
+        value: ScriptValue,
+    ) -> Result<(), ScriptError> {
+        if let Some(existing) = self.exports.get(name) {
+            if existing.owner != owner {
+                return Err(ScriptError::Other(format!(
+                    "`{name}` is already exported by `{}`",
+                    existing.owner_name
+                )));
+            }
+        }
+
+        let version = self.versions.entry(name.to_owned()).or_default();
+        *version += 1;
+        self.exports.insert(
+            name.to_owned(),
+            ScriptExport {
+                owner,
+                owner_name: owner_name.to_owned(),
+                version: *version,
+                value,
+                frame: self.frame,
+            },
+        );
+        self.changed(name, true);
+        Ok(())
+    }
+
+    /// Retrieves the export with the given name on behalf of the importer, recording it as a dependent of the export
+    /// even if the export was not registered yet
+    pub fn import(&mut self, name: &str, importer: ScriptId) -> Option<&ScriptExport> {
+        self.dependents
+            .entry(name.to_owned())
+            .or_default()
+            .insert(importer);
+        self.exports.get(name)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&ScriptExport> {
+        self.exports.get(name)
+    }
+
+    /// The scripts which imported, or tried to import, the export with the given name
+    pub fn dependents(&self, name: &str) -> impl Iterator<Item = ScriptId> + '_ {
+        self.dependents.get(name).into_iter().flatten().copied()
+    }
+
+    /// The names of the exports which were imported but are not registered, i.e. missing dependencies
+    pub fn missing(&self) -> impl Iterator<Item = &str> {
+        self.dependents
+            .keys()
+            .filter(|name| !self.exports.contains_key(*name))
+            .map(String::as_str)
+    }
+
+    /// Removes every export of the given script and forgets its imports, e.g. once the script is unloaded
+    pub fn remove_owner(&mut self, owner: ScriptId) {
+        let removed = self
+            .exports
+            .iter()
+            .filter(|(_, e)| e.owner == owner)
+            .map(|(name, _)| name.clone())
+            .collect::<Vec<_>>();
+        for name in removed {
+            self.exports.remove(&name);
+            self.changed(&name, false);
+        }
+        for dependents in self.dependents.values_mut() {
+            dependents.remove(&owner);
+        }
+    }
+
+    fn changed(&mut self, name: &str, available: bool) {
+        self.changes.push(ScriptExportChanged {
+            name: name.to_owned(),
+            available,
+            dependents: self.dependents(name).collect(),
+        })
+    }
+}
+
+/// Removes the exports of reloaded scripts, which register them again once loaded, and sends [`ScriptExportChanged`] events.
+///
+/// Runs at the start of every frame.
+pub fn update_script_exports(
+    mut exports: ResMut<ScriptExports>,
+    mut loaded: EventReader<ScriptLoaded>,
+    mut changes: EventWriter<ScriptExportChanged>,
+) {
+    // scripts were loaded during the previous frame, exports they registered after loading are kept
+    let load_frame = exports.frame;
+    for loaded in loaded.iter() {
+        let stale = exports
+            .exports
+            .iter()
+            .filter(|(_, e)| e.owner == loaded.sid && e.frame < load_frame)
+            .map(|(name, _)| name.clone())
+            .collect::<Vec<_>>();
+        for name in stale {
+            exports.exports.remove(&name);
+            exports.changed(&name, false);
+        }
+    }
+    exports.frame += 1;
+    changes.send_batch(exports.changes.drain(..));
+}
+
+/// Adds the exports resource and its update system, unless already added.
+///
+/// Called by the export API providers.
+pub fn register_script_exports(app: &mut App) {
+    if !app.world.contains_resource::<ScriptExports>() {
+        app.init_resource::<ScriptExports>()
+            .add_event::<ScriptExportChanged>()
+            .add_event::<ScriptLoaded>()
+            .add_system_to_stage(CoreStage::First, update_script_exports);
+    }
+}
+
+impl ScriptWorld {
+    fn script_exports<O, F: FnOnce(&mut ScriptExports) -> O>(
+        &self,
+        f: F,
+    ) -> Result<O, ScriptError> {
+        let mut w = self.write();
+        w.get_resource_mut::<ScriptExports>()
+            .map(|mut exports| f(&mut exports))
+            .ok_or_else(|| {
+                ScriptError::Other(
+                    "Exports are not enabled, add an exports API provider".to_owned(),
+                )
+            })
+    }
+
+    /// Registers an export owned by the given script
+    pub fn register_export(
+        &self,
+        name: &str,
+        owner: ScriptId,
+        owner_name: &str,
+        value: ScriptValue,
+    ) -> Result<(), ScriptError> {
+        self.script_exports(|exports| exports.register(name, owner, owner_name, value))?
+    }
+
+    /// Imports a copy of the export with the given name, `ScriptValue::Nil` if it is not registered (yet)
+    pub fn import_export(
+        &self,
+        name: &str,
+        importer: ScriptId,
+    ) -> Result<ScriptValue, ScriptError> {
+        self.script_exports(|exports| {
+            exports
+                .import(name, importer)
+                .map(|e| e.value.clone())
+                .unwrap_or_default()
+        })
+    }
+
+    /// The version of the export with the given name, changing whenever it's registered again, 0 if it is not registered
+    pub fn export_version(&self, name: &str) -> Result<u64, ScriptError> {
+        self.script_exports(|exports| exports.get(name).map(|e| e.version).unwrap_or_default())
+    }
+}
@@ -0,0 +1,425 @@
+//! Fixed-point math shared by the `fixed` modules of all script hosts, for simulations which must be deterministic across platforms
+use std::{
+    fmt,
+    ops::{Add, Mul, Neg, Sub},
+    str::FromStr,
+};
+
+/// A signed fixed-point number with 32 integer and 32 fractional bits.
+///
+/// All operations are done on integers, so give bit identical results on every platform,
+/// unlike floats whose results may differ between platforms and compilers. Arithmetic wraps around on overflow.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Fixed(i64);
+
+impl Fixed {
+    pub const FRAC_BITS: u32 = 32;
+    pub const ZERO: Fixed = Fixed(0);
+    pub const ONE: Fixed = Fixed(1 << Self::FRAC_BITS);
+
+    pub const fn from_bits(bits: i64) -> Self {
+        Self(bits)
+    }
+
+    pub const fn to_bits(self) -> i64 {
+        self.0
+    }
+
+    /// Converts the integer, wrapping around if it does not fit into 32 bits
+    pub const fn from_int(value: i64) -> Self {
+        Self(value.wrapping_shl(Self::FRAC_BITS))
+    }
+
+    /// The fixed-point number closest to `num / den`, `None` if `den` is 0
+    pub fn from_ratio(num: i64, den: i64) -> Option<Self> {
+        Self::from_int(num).checked_div(Self::from_int(den))
+    }
+
+    /// Converts the float, rounding towards zero. The result only depends on the value of the float,
+    /// but floats computed by scripts may differ between platforms, prefer [`Fixed::from_ratio`] or parsing
+    pub fn from_f64(value: f64) -> Self {
+        Self((value * Self::ONE.0 as f64) as i64)
+    }
+
+    /// Converts to a float, for display or interfacing with float based APIs
+    pub fn to_f64(self) -> f64 {
+        self.0 as f64 / Self::ONE.0 as f64
+    }
+
+    /// Divides, rounding towards zero, `None` if `rhs` is 0
+    pub fn checked_div(self, rhs: Self) -> Option<Self> {
+        if rhs.0 == 0 {
+            return None;
+        }
+        Some(Self(
+            (((self.0 as i128) << Self::FRAC_BITS) / rhs.0 as i128) as i64,
+        ))
+    }
+
+    /// The largest integer less than or equal to the number
+    pub fn floor(self) -> Self {
+        Self(self.0 & !(Self::ONE.0 - 1))
+    }
+
+    /// The integer part of the number, rounding towards negative infinity
+    pub fn to_int(self) -> i64 {
+        self.0 >> Self::FRAC_BITS
+    }
+
+    pub fn abs(self) -> Self {
+        Self(self.0.wrapping_abs())
+    }
+
+    /// The square root rounded down, `None` for negative numbers
+    pub fn sqrt(self) -> Option<Self> {
+        if self.0 < 0 {
+            return None;
+        }
+        // sqrt(bits * 2^32) = sqrt(bits / 2^32) * 2^32
+        let value = (self.0 as u128) << Self::FRAC_BITS;
+        let mut root = 0u128;
+        let mut bit = 1u128 << 126;
+        while bit > value {
+            bit >>= 2;
+        }
+        let mut rest = value;
+        while bit != 0 {
+            if rest >= root + bit {
+                rest -= root + bit;
+                root = (root >> 1) + bit;
+            } else {
+                root >>= 1;
+            }
+            bit >>= 2;
+        }
+        Some(Self(root as i64))
+    }
+}
+
+impl Add for Fixed {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self(self.0.wrapping_add(rhs.0))
+    }
+}
+
+impl Sub for Fixed {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self(self.0.wrapping_sub(rhs.0))
+    }
+}
+
+impl Mul for Fixed {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        Self(((self.0 as i128 * rhs.0 as i128) >> Self::FRAC_BITS) as i64)
+    }
+}
+
+impl Neg for Fixed {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self(self.0.wrapping_neg())
+    }
+}
+
+impl From<i32> for Fixed {
+    fn from(value: i32) -> Self {
+        Self::from_int(value.into())
+    }
+}
+
+/// Parses decimal numbers such as `-12.375` exactly, up to the precision of the fraction
+impl FromStr for Fixed {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("`{s}` is not a valid fixed-point number");
+        let (negative, digits) = match s.trim().strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, s.trim()),
+        };
+        let (integer, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+        if integer.is_empty() && fraction.is_empty()
+            || !integer
+                .bytes()
+                .chain(fraction.bytes())
+                .all(|b| b.is_ascii_digit())
+        {
+            return Err(invalid());
+        }
+
+        let integer = if integer.is_empty() {
+            0
+        } else {
+            integer.parse::<i64>().map_err(|_| invalid())?
+        };
+        // digits past the 18th are below the precision of the fraction
+        let fraction = &fraction[..fraction.len().min(18)];
+        let fraction_bits = if fraction.is_empty() {
+            0
+        } else {
+            let scale = 10u128.pow(fraction.len() as u32);
+            let value = fraction.parse::<u128>().map_err(|_| invalid())?;
+            (((value << Self::FRAC_BITS) + scale / 2) / scale) as i64
+        };
+
+        let value = Self(
+            integer
+                .wrapping_shl(Self::FRAC_BITS)
+                .wrapping_add(fraction_bits),
+        );
+        Ok(if negative { -value } else { value })
+    }
+}
+
+/// Formats the number exactly as a decimal, with up to 9 fractional digits
+impl fmt::Display for Fixed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut bits = self.0 as i128;
+        if bits < 0 {
+            f.write_str("-")?;
+            bits = -bits;
+        }
+        let one = 1i128 << Self::FRAC_BITS;
+        let mut integer = bits >> Self::FRAC_BITS;
+        let mut fraction = ((bits & (one - 1)) * 1_000_000_000 + one / 2) >> Self::FRAC_BITS;
+        if fraction == 1_000_000_000 {
+            integer += 1;
+            fraction = 0;
+        }
+
+        write!(f, "{integer}")?;
+        if fraction != 0 {
+            let fraction = format!("{fraction:09}");
+            write!(f, ".{}", fraction.trim_end_matches('0'))?;
+        }
+        Ok(())
+    }
+}
+
+/// A 2D vector of [`Fixed`] numbers
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct FixedVec2 {
+    pub x: Fixed,
+    pub y: Fixed,
+}
+
+impl FixedVec2 {
+    pub const ZERO: FixedVec2 = FixedVec2::new(Fixed::ZERO, Fixed::ZERO);
+
+    pub const fn new(x: Fixed, y: Fixed) -> Self {
+        Self { x, y }
+    }
+
+    pub fn dot(self, rhs: Self) -> Fixed {
+        self.x * rhs.x + self.y * rhs.y
+    }
+
+    pub fn length_squared(self) -> Fixed {
+        self.dot(self)
+    }
+
+    pub fn length(self) -> Fixed {
+        self.length_squared().sqrt().unwrap_or_default()
+    }
+
+    /// The vector scaled to a length of 1, the zero vector if its length is 0
+    pub fn normalize_or_zero(self) -> Self {
+        let length = self.length();
+        match (self.x.checked_div(length), self.y.checked_div(length)) {
+            (Some(x), Some(y)) => Self::new(x, y),
+            _ => Self::ZERO,
+        }
+    }
+}
+
+impl Add for FixedVec2 {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl Sub for FixedVec2 {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl Mul<Fixed> for FixedVec2 {
+    type Output = Self;
+
+    fn mul(self, rhs: Fixed) -> Self {
+        Self::new(self.x * rhs, self.y * rhs)
+    }
+}
+
+impl Neg for FixedVec2 {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::new(-self.x, -self.y)
+    }
+}
+
+impl fmt::Display for FixedVec2 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+/// A 3D vector of [`Fixed`] numbers
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct FixedVec3 {
+    pub x: Fixed,
+    pub y: Fixed,
+    pub z: Fixed,
+}
+
+impl FixedVec3 {
+    pub const ZERO: FixedVec3 = FixedVec3::new(Fixed::ZERO, Fixed::ZERO, Fixed::ZERO);
+
+    pub const fn new(x: Fixed, y: Fixed, z: Fixed) -> Self {
+        Self { x, y, z }
+    }
+
+    pub fn dot(self, rhs: Self) -> Fixed {
+        self.x * rhs.x + self.y * rhs.y + self.z * rhs.z
+    }
+
+    pub fn cross(self, rhs: Self) -> Self {
+        Self::new(
+            self.y * rhs.z - self.z * rhs.y,
+            self.z * rhs.x - self.x * rhs.z,
+            self.x * rhs.y - self.y * rhs.x,
+        )
+    }
+
+    pub fn length_squared(self) -> Fixed {
+        self.dot(self)
+    }
+
+    pub fn length(self) -> Fixed {
+        self.length_squared().sqrt().unwrap_or_default()
+    }
+
+    /// The vector scaled to a length of 1, the zero vector if its length is 0
+    pub fn normalize_or_zero(self) -> Self {
+        let length = self.length();
+        match (
+            self.x.checked_div(length),
+            self.y.checked_div(length),
+            self.z.checked_div(length),
+        ) {
+            (Some(x), Some(y), Some(z)) => Self::new(x, y, z),
+            _ => Self::ZERO,
+        }
+    }
+}
+
+impl Add for FixedVec3 {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self::new(self.x + rhs.x, self.y + rhs.y, self.z + rhs.z)
+    }
+}
+
+impl Sub for FixedVec3 {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self::new(self.x - rhs.x, self.y - rhs.y, self.z - rhs.z)
+    }
+}
+
+impl Mul<Fixed> for FixedVec3 {
+    type Output = Self;
+
+    fn mul(self, rhs: Fixed) -> Self {
+        Self::new(self.x * rhs, self.y * rhs, self.z * rhs)
+    }
+}
+
+impl Neg for FixedVec3 {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::new(-self.x, -self.y, -self.z)
+    }
+}
+
+impl fmt::Display for FixedVec3 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {}, {})", self.x, self.y, self.z)
+    }
+}
+
+/// The message logged the first time a script calls a float math function while float math warnings are enabled
+pub fn float_math_warning(script: &str, function: &str) -> String {
+    format!(
+        "Script `{script}` called the float math function `{function}`, which may give different results on other platforms, use fixed-point math to keep the simulation deterministic"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixed(s: &str) -> Fixed {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn arithmetic_is_exact() {
+        assert_eq!(fixed("1.5") + fixed("2.25"), fixed("3.75"));
+        assert_eq!(fixed("1.5") - fixed("2.25"), fixed("-0.75"));
+        assert_eq!(fixed("-1.5") * fixed("2.5"), fixed("-3.75"));
+        assert_eq!(fixed("7").checked_div(fixed("2")), Some(fixed("3.5")));
+        assert_eq!(fixed("7").checked_div(Fixed::ZERO), None);
+        assert_eq!(Fixed::from_ratio(1, 4), Some(fixed(".25")));
+        assert_eq!(fixed("-1.25").floor(), fixed("-2"));
+        assert_eq!(fixed("-1.25").to_int(), -2);
+        assert_eq!(fixed("6.25").sqrt(), Some(fixed("2.5")));
+        assert_eq!(fixed("-1").sqrt(), None);
+    }
+
+    #[test]
+    fn parsing_and_display_round_trip() {
+        for s in ["0", "12", "-12.375", "0.1", "-0.000000001", "2147483647.5"] {
+            assert_eq!(fixed(s).to_string(), s.to_owned());
+        }
+        assert!("".parse::<Fixed>().is_err());
+        assert!("1.2.3".parse::<Fixed>().is_err());
+        assert!("1e5".parse::<Fixed>().is_err());
+    }
+
+    #[test]
+    fn vectors() {
+        let v = FixedVec3::new(fixed("3"), fixed("4"), Fixed::ZERO);
+        assert_eq!(v.length(), fixed("5"));
+        assert_eq!(Some(v.normalize_or_zero().x), Fixed::from_ratio(3, 5));
+        assert_eq!(FixedVec3::ZERO.normalize_or_zero(), FixedVec3::ZERO);
+        assert_eq!(
+            FixedVec3::new(Fixed::ONE, Fixed::ZERO, Fixed::ZERO).cross(FixedVec3::new(
+                Fixed::ZERO,
+                Fixed::ONE,
+                Fixed::ZERO
+            )),
+            FixedVec3::new(Fixed::ZERO, Fixed::ZERO, Fixed::ONE)
+        );
+        assert_eq!(
+            (FixedVec2::new(fixed("1"), fixed("2")) * fixed("0.5")).to_string(),
+            "(0.5, 1)"
+        );
+    }
+}
@@ -0,0 +1,228 @@
+//! Formatting of user facing text, implemented once so that every script host formats numbers, durations and templates alike
+use std::fmt::Write;
+
+use bevy::{prelude::Resource, utils::HashMap};
+use bevy_mod_scripting_core::prelude::ScriptError;
+
+/// How numbers are formatted for a script
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScriptLocale {
+    /// separates the integer part from the fraction, `.` by default
+    pub decimal_separator: char,
+    /// separates groups of three integer digits, `,` by default, no grouping if `None`
+    pub group_separator: Option<char>,
+}
+
+impl Default for ScriptLocale {
+    fn default() -> Self {
+        Self {
+            decimal_separator: '.',
+            group_separator: Some(','),
+        }
+    }
+}
+
+/// The locales scripts format text with, scripts without their own locale use the default one.
+///
+/// Scripts are identified by name, i.e. the path they were loaded from:
+/// ```rust,ignore
+/// let mut locales = ScriptLocales::default();
+/// locales.set_script_locale("scripts/hud_de.lua", ScriptLocale {
+///     decimal_separator: ',',
+///     group_separator: Some('.'),
+/// });
+/// app.insert_resource(locales);
+/// ```
+#[derive(Resource, Debug, Clone, Default)]
+pub struct ScriptLocales {
+    pub default: ScriptLocale,
+    scripts: HashMap<String, ScriptLocale>,
+}
+
+impl ScriptLocales {
+    pub fn set_script_locale(&mut self, script: impl Into<String>, locale: ScriptLocale) {
+        self.scripts.insert(script.into(), locale);
+    }
+
+    /// Makes the script use the default locale again
+    pub fn clear_script_locale(&mut self, script: &str) {
+        self.scripts.remove(script);
+    }
+
+    /// The locale used by the given script
+    pub fn for_script(&self, script: &str) -> &ScriptLocale {
+        self.scripts.get(script).unwrap_or(&self.default)
+    }
+}
+
+/// Formats the number with the given number of decimals, e.g. `1234.5` with 2 decimals becomes `1,234.50` in the default locale
+pub fn format_number(value: f64, decimals: usize, locale: &ScriptLocale) -> String {
+    if !value.is_finite() {
+        return value.to_string();
+    }
+
+    let digits = format!("{:.*}", decimals, value.abs());
+    let (integer, fraction) = digits.split_once('.').unwrap_or((&digits, ""));
+
+    let mut out = String::with_capacity(digits.len() + integer.len() / 3 + 1);
+    // negative numbers rounding to zero are shown without their sign
+    if value < 0.0 && digits.bytes().any(|b| matches!(b, b'1'..=b'9')) {
+        out.push('-');
+    }
+    for (i, digit) in integer.chars().enumerate() {
+        let remaining = integer.len() - i;
+        if i > 0 && remaining % 3 == 0 {
+            if let Some(separator) = locale.group_separator {
+                out.push(separator);
+            }
+        }
+        out.push(digit);
+    }
+    if !fraction.is_empty() {
+        out.push(locale.decimal_separator);
+        out.push_str(fraction);
+    }
+    out
+}
+
+/// Formats a number of seconds as a clock, `m:ss` under an hour and `h:mm:ss` otherwise, negative durations are shown as zero
+pub fn format_duration(seconds: f64) -> String {
+    let total = if seconds.is_finite() {
+        seconds.max(0.0).floor() as u64
+    } else {
+        0
+    };
+    let (hours, minutes, seconds) = (total / 3600, (total / 60) % 60, total % 60);
+
+    if hours > 0 {
+        format!("{hours}:{minutes:02}:{seconds:02}")
+    } else {
+        format!("{minutes}:{seconds:02}")
+    }
+}
+
+/// A value interpolated into a template by [`interpolate`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum FmtValue {
+    String(String),
+    Integer(i64),
+    Float(f64),
+    Bool(bool),
+}
+
+impl FmtValue {
+    fn write(&self, out: &mut String, decimals: Option<usize>, locale: &ScriptLocale) {
+        match (self, decimals) {
+            (FmtValue::Integer(i), Some(decimals)) => {
+                out.push_str(&format_number(*i as f64, decimals, locale))
+            }
+            (FmtValue::Float(f), Some(decimals)) => {
+                out.push_str(&format_number(*f, decimals, locale))
+            }
+            (FmtValue::String(s), _) => out.push_str(s),
+            (FmtValue::Integer(i), None) => write!(out, "{i}").unwrap(),
+            (FmtValue::Float(f), None) => write!(out, "{f}").unwrap(),
+            (FmtValue::Bool(b), _) => write!(out, "{b}").unwrap(),
+        }
+    }
+}
+
+/// Replaces every `{name}` in the template with the value of that name.
+///
+/// Numbers can be formatted with the locale by giving the number of decimals, as in `{score:0}` or `{speed:2}`,
+/// `{{` and `}}` produce literal braces. Names without a value are an error.
+pub fn interpolate<F: FnMut(&str) -> Option<FmtValue>>(
+    template: &str,
+    locale: &ScriptLocale,
+    mut value_of: F,
+) -> Result<String, ScriptError> {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(i) = rest.find(|c: char| c == '{' || c == '}') {
+        out.push_str(&rest[..i]);
+        let (brace, after) = (&rest[i..i + 1], &rest[i + 1..]);
+
+        if after.starts_with(brace) {
+            out.push_str(brace);
+            rest = &after[1..];
+            continue;
+        }
+        if brace == "}" {
+            return Err(ScriptError::Other(format!(
+                "Unmatched `}}` in format template `{template}`, use `}}}}` for a literal brace"
+            )));
+        }
+
+        let end = after.find('}').ok_or_else(|| {
+            ScriptError::Other(format!("Unclosed `{{` in format template `{template}`"))
+        })?;
+        let (name, decimals) = match after[..end].split_once(':') {
+            Some((name, decimals)) => (
+                name,
+                Some(decimals.parse::<usize>().map_err(|_| {
+                    ScriptError::Other(format!(
+                        "Invalid number of decimals `{decimals}` for `{name}` in format template `{template}`"
+                    ))
+                })?),
+            ),
+            None => (&after[..end], None),
+        };
+
+        let value = value_of(name).ok_or_else(|| {
+            ScriptError::Other(format!(
+                "No value given for `{name}` in format template `{template}`"
+            ))
+        })?;
+        value.write(&mut out, decimals, locale);
+        rest = &after[end + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numbers_are_grouped_and_rounded() {
+        let locale = ScriptLocale::default();
+        assert_eq!(format_number(1234.5, 2, &locale), "1,234.50");
+        assert_eq!(format_number(-1234567.0, 0, &locale), "-1,234,567");
+        assert_eq!(format_number(999.0, 0, &locale), "999");
+        assert_eq!(format_number(-0.001, 2, &locale), "0.00");
+
+        let german = ScriptLocale {
+            decimal_separator: ',',
+            group_separator: Some('.'),
+        };
+        assert_eq!(format_number(1234.5, 1, &german), "1.234,5");
+    }
+
+    #[test]
+    fn durations_are_clocks() {
+        assert_eq!(format_duration(5.9), "0:05");
+        assert_eq!(format_duration(125.0), "2:05");
+        assert_eq!(format_duration(3723.0), "1:02:03");
+        assert_eq!(format_duration(-3.0), "0:00");
+    }
+
+    #[test]
+    fn templates_are_interpolated() {
+        let locale = ScriptLocale::default();
+        let values = |name: &str| match name {
+            "name" => Some(FmtValue::String("Ferris".to_owned())),
+            "score" => Some(FmtValue::Float(12345.678)),
+            _ => None,
+        };
+
+        assert_eq!(
+            interpolate("Hello {name}, {{score}}: {score:1}", &locale, values).unwrap(),
+            "Hello Ferris, {score}: 12,345.7"
+        );
+        assert!(interpolate("Hello {missing}", &locale, values).is_err());
+        assert!(interpolate("Hello {name", &locale, values).is_err());
+        assert!(interpolate("Hello }", &locale, values).is_err());
+    }
+}
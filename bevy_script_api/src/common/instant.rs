@@ -0,0 +1,122 @@
+//! Monotonic timestamps and durations shared by the `Instant` and `Duration` modules of all script hosts
+use std::{
+    fmt,
+    ops::{Add, Sub},
+};
+
+use bevy::utils::{Duration, Instant};
+
+/// A point in time measured by a monotonic clock, for profiling scripts and timing gameplay.
+///
+/// Unlike `os.time` and other wall clocks, instants never go backwards and behave the same on every platform,
+/// including the web. Only the difference between two instants is meaningful.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ScriptInstant(pub Instant);
+
+impl ScriptInstant {
+    pub fn now() -> Self {
+        Self(Instant::now())
+    }
+
+    /// The time passed since this instant
+    pub fn elapsed(&self) -> ScriptDuration {
+        ScriptDuration(self.0.elapsed())
+    }
+
+    /// The time passed between the earlier instant and this one, zero if the earlier instant is later
+    pub fn duration_since(&self, earlier: ScriptInstant) -> ScriptDuration {
+        ScriptDuration(self.0.saturating_duration_since(earlier.0))
+    }
+}
+
+/// Gives the instant itself if the result is too far in the future to be represented
+impl Add<ScriptDuration> for ScriptInstant {
+    type Output = Self;
+
+    fn add(self, rhs: ScriptDuration) -> Self {
+        Self(self.0.checked_add(rhs.0).unwrap_or(self.0))
+    }
+}
+
+impl Sub for ScriptInstant {
+    type Output = ScriptDuration;
+
+    fn sub(self, rhs: Self) -> ScriptDuration {
+        self.duration_since(rhs)
+    }
+}
+
+/// A span of time, which is never negative: subtracting a longer duration gives zero
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ScriptDuration(pub Duration);
+
+impl ScriptDuration {
+    /// The duration of the given number of seconds, negative or invalid numbers give zero
+    pub fn from_secs(secs: f64) -> Self {
+        if secs.is_nan() || secs <= 0.0 {
+            Self(Duration::ZERO)
+        } else if secs >= Duration::MAX.as_secs_f64() {
+            Self(Duration::MAX)
+        } else {
+            Self(Duration::from_secs_f64(secs))
+        }
+    }
+
+    pub fn from_millis(millis: f64) -> Self {
+        Self::from_secs(millis / 1_000.0)
+    }
+
+    pub fn from_micros(micros: f64) -> Self {
+        Self::from_secs(micros / 1_000_000.0)
+    }
+
+    pub fn as_secs(&self) -> f64 {
+        self.0.as_secs_f64()
+    }
+
+    pub fn as_millis(&self) -> f64 {
+        self.0.as_secs_f64() * 1_000.0
+    }
+
+    pub fn as_micros(&self) -> f64 {
+        self.0.as_secs_f64() * 1_000_000.0
+    }
+
+    /// The duration multiplied by the factor, negative factors give zero
+    pub fn mul_f64(self, factor: f64) -> Self {
+        Self::from_secs(self.as_secs() * factor)
+    }
+
+    /// The duration divided by the divisor, `None` if the divisor is 0
+    pub fn div_f64(self, divisor: f64) -> Option<Self> {
+        (divisor != 0.0).then(|| Self::from_secs(self.as_secs() / divisor))
+    }
+}
+
+impl Add for ScriptDuration {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self(self.0.saturating_add(rhs.0))
+    }
+}
+
+impl Sub for ScriptDuration {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self(self.0.saturating_sub(rhs.0))
+    }
+}
+
+impl fmt::Display for ScriptDuration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self.0)
+    }
+}
+
+impl fmt::Display for ScriptInstant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Instant({:?} ago)", self.0.elapsed())
+    }
+}
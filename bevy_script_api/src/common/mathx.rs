@@ -0,0 +1,293 @@
+//! Math utilities shared by the `mathx` modules of all script hosts
+use std::{f32::consts::PI, str::FromStr};
+
+use bevy::prelude::Quat;
+
+/// Linearly interpolates between `a` and `b`, `t` is not clamped
+pub fn lerp(a: f32, b: f32, t: f32) -> f32 {
+    a + (b - a) * t
+}
+
+/// The inverse of [`lerp`], returns the `t` at which `value` lies between `a` and `b`, 0 if `a == b`
+pub fn inverse_lerp(a: f32, b: f32, value: f32) -> f32 {
+    if a == b {
+        0.0
+    } else {
+        (value - a) / (b - a)
+    }
+}
+
+/// Maps `value` from the range [`from_min`,`from_max`] onto the range [`to_min`,`to_max`], the result is not clamped
+pub fn remap(value: f32, from_min: f32, from_max: f32, to_min: f32, to_max: f32) -> f32 {
+    lerp(to_min, to_max, inverse_lerp(from_min, from_max, value))
+}
+
+/// Clamps `value` to the range [`min`,`max`], unlike [`f32::clamp`] this does not panic if `min > max`
+pub fn clamp(value: f32, min: f32, max: f32) -> f32 {
+    value.max(min).min(max)
+}
+
+/// Spherically interpolates between two rotations
+pub fn slerp(a: Quat, b: Quat, t: f32) -> Quat {
+    a.slerp(b, t)
+}
+
+/// Easing functions, usable by name from scripts i.e. `"ease_in_out_cubic"`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Easing {
+    #[default]
+    Linear,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+    EaseInCubic,
+    EaseOutCubic,
+    EaseInOutCubic,
+    EaseInSine,
+    EaseOutSine,
+    EaseInOutSine,
+    EaseInExpo,
+    EaseOutExpo,
+    EaseInOutExpo,
+    EaseInBack,
+    EaseOutBack,
+    EaseOutBounce,
+}
+
+impl Easing {
+    /// Applies the easing function to `t`, which is clamped to [0,1] first
+    pub fn ease(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t,
+            Easing::EaseOut => 1.0 - (1.0 - t) * (1.0 - t),
+            Easing::EaseInOut => {
+                if t < 0.5 {
+                    2.0 * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(2) / 2.0
+                }
+            }
+            Easing::EaseInCubic => t * t * t,
+            Easing::EaseOutCubic => 1.0 - (1.0 - t).powi(3),
+            Easing::EaseInOutCubic => {
+                if t < 0.5 {
+                    4.0 * t * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+                }
+            }
+            Easing::EaseInSine => 1.0 - (t * PI / 2.0).cos(),
+            Easing::EaseOutSine => (t * PI / 2.0).sin(),
+            Easing::EaseInOutSine => -((t * PI).cos() - 1.0) / 2.0,
+            Easing::EaseInExpo => {
+                if t == 0.0 {
+                    0.0
+                } else {
+                    2f32.powf(10.0 * t - 10.0)
+                }
+            }
+            Easing::EaseOutExpo => {
+                if t == 1.0 {
+                    1.0
+                } else {
+                    1.0 - 2f32.powf(-10.0 * t)
+                }
+            }
+            Easing::EaseInOutExpo => {
+                if t == 0.0 || t == 1.0 {
+                    t
+                } else if t < 0.5 {
+                    2f32.powf(20.0 * t - 10.0) / 2.0
+                } else {
+                    (2.0 - 2f32.powf(-20.0 * t + 10.0)) / 2.0
+                }
+            }
+            Easing::EaseInBack => {
+                const C1: f32 = 1.70158;
+                (C1 + 1.0) * t * t * t - C1 * t * t
+            }
+            Easing::EaseOutBack => {
+                const C1: f32 = 1.70158;
+                1.0 + (C1 + 1.0) * (t - 1.0).powi(3) + C1 * (t - 1.0).powi(2)
+            }
+            Easing::EaseOutBounce => {
+                const N1: f32 = 7.5625;
+                const D1: f32 = 2.75;
+                if t < 1.0 / D1 {
+                    N1 * t * t
+                } else if t < 2.0 / D1 {
+                    let t = t - 1.5 / D1;
+                    N1 * t * t + 0.75
+                } else if t < 2.5 / D1 {
+                    let t = t - 2.25 / D1;
+                    N1 * t * t + 0.9375
+                } else {
+                    let t = t - 2.625 / D1;
+                    N1 * t * t + 0.984375
+                }
+            }
+        }
+    }
+}
+
+impl FromStr for Easing {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "linear" => Easing::Linear,
+            "ease_in" => Easing::EaseIn,
+            "ease_out" => Easing::EaseOut,
+            "ease_in_out" => Easing::EaseInOut,
+            "ease_in_cubic" => Easing::EaseInCubic,
+            "ease_out_cubic" => Easing::EaseOutCubic,
+            "ease_in_out_cubic" => Easing::EaseInOutCubic,
+            "ease_in_sine" => Easing::EaseInSine,
+            "ease_out_sine" => Easing::EaseOutSine,
+            "ease_in_out_sine" => Easing::EaseInOutSine,
+            "ease_in_expo" => Easing::EaseInExpo,
+            "ease_out_expo" => Easing::EaseOutExpo,
+            "ease_in_out_expo" => Easing::EaseInOutExpo,
+            "ease_in_back" => Easing::EaseInBack,
+            "ease_out_back" => Easing::EaseOutBack,
+            "ease_out_bounce" => Easing::EaseOutBounce,
+            _ => return Err(format!("Unknown easing function `{s}`")),
+        })
+    }
+}
+
+/// Hashes integer lattice coordinates together with the seed
+fn hash(seed: u32, coords: &[i32]) -> u32 {
+    let mut h = seed.wrapping_mul(0x9E37_79B9) ^ 0x85EB_CA6B;
+    for c in coords {
+        h ^= (*c as u32).wrapping_mul(0xC2B2_AE35);
+        h = h.rotate_left(13).wrapping_mul(0x27D4_EB2F);
+    }
+    h ^= h >> 16;
+    h = h.wrapping_mul(0x85EB_CA6B);
+    h ^ (h >> 13)
+}
+
+fn fade(t: f32) -> f32 {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+fn gradient2(seed: u32, x: i32, y: i32, dx: f32, dy: f32) -> f32 {
+    let angle = (hash(seed, &[x, y]) as f32 / u32::MAX as f32) * 2.0 * PI;
+    angle.cos() * dx + angle.sin() * dy
+}
+
+fn gradient3(seed: u32, x: i32, y: i32, z: i32, dx: f32, dy: f32, dz: f32) -> f32 {
+    // one of the 12 edge directions of a cube, as in improved perlin noise
+    match hash(seed, &[x, y, z]) % 12 {
+        0 => dx + dy,
+        1 => -dx + dy,
+        2 => dx - dy,
+        3 => -dx - dy,
+        4 => dx + dz,
+        5 => -dx + dz,
+        6 => dx - dz,
+        7 => -dx - dz,
+        8 => dy + dz,
+        9 => -dy + dz,
+        10 => dy - dz,
+        _ => -dy - dz,
+    }
+}
+
+/// The lattice cell containing the coordinate and the offset of the coordinate within it,
+/// cells wrap around instead of saturating at the bounds of `i32` for huge coordinates
+fn lattice(x: f32) -> (i32, f32) {
+    let floor = x.floor();
+    (floor as i64 as i32, x - floor)
+}
+
+/// Seeded 2D gradient noise, the result lies roughly in the range [-1,1] and is 0 at integer coordinates
+pub fn noise2(seed: u32, x: f32, y: f32) -> f32 {
+    let ((x0, dx), (y0, dy)) = (lattice(x), lattice(y));
+    let (x1, y1) = (x0.wrapping_add(1), y0.wrapping_add(1));
+    let (u, v) = (fade(dx), fade(dy));
+
+    let n00 = gradient2(seed, x0, y0, dx, dy);
+    let n10 = gradient2(seed, x1, y0, dx - 1.0, dy);
+    let n01 = gradient2(seed, x0, y1, dx, dy - 1.0);
+    let n11 = gradient2(seed, x1, y1, dx - 1.0, dy - 1.0);
+
+    // the largest possible value is sqrt(1/2)
+    lerp(lerp(n00, n10, u), lerp(n01, n11, u), v) * std::f32::consts::SQRT_2
+}
+
+/// Seeded 3D gradient noise, the result lies roughly in the range [-1,1] and is 0 at integer coordinates
+pub fn noise3(seed: u32, x: f32, y: f32, z: f32) -> f32 {
+    let ((x0, dx), (y0, dy), (z0, dz)) = (lattice(x), lattice(y), lattice(z));
+    let (u, v, w) = (fade(dx), fade(dy), fade(dz));
+
+    let corner = |ox: i32, oy: i32, oz: i32| {
+        gradient3(
+            seed,
+            x0.wrapping_add(ox),
+            y0.wrapping_add(oy),
+            z0.wrapping_add(oz),
+            dx - ox as f32,
+            dy - oy as f32,
+            dz - oz as f32,
+        )
+    };
+
+    let x00 = lerp(corner(0, 0, 0), corner(1, 0, 0), u);
+    let x10 = lerp(corner(0, 1, 0), corner(1, 1, 0), u);
+    let x01 = lerp(corner(0, 0, 1), corner(1, 0, 1), u);
+    let x11 = lerp(corner(0, 1, 1), corner(1, 1, 1), u);
+
+    lerp(lerp(x00, x10, v), lerp(x01, x11, v), w).clamp(-1.0, 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 2^31, the first coordinate past `i32::MAX`, which wraps around to the cell of `i32::MIN`
+    const WRAP: f32 = 2_147_483_648.0;
+
+    #[test]
+    fn noise_wraps_around_huge_coordinates() {
+        assert_eq!(lattice(WRAP), (i32::MIN, 0.0));
+        assert_eq!(lattice(-WRAP), (i32::MIN, 0.0));
+        assert_eq!(lattice(-2.25), (-3, 0.75));
+
+        let coords = [
+            i32::MAX as f32,
+            i32::MIN as f32,
+            WRAP + 256.0,
+            -WRAP - 256.0,
+            2.0 * WRAP,
+            -1e20,
+            f32::MAX,
+            f32::MIN,
+        ];
+        for x in coords {
+            for y in coords {
+                let n2 = noise2(7, x, y + 0.5);
+                assert!(n2.is_finite() && n2.abs() <= 1.5, "noise2({x}, {y}) = {n2}");
+                let n3 = noise3(7, x, y, x);
+                assert!((-1.0..=1.0).contains(&n3), "noise3({x}, {y}) = {n3}");
+            }
+        }
+
+        // the lattice repeats every 2^32 cells, so both ends of the range sample the same cells
+        assert_eq!(noise2(7, WRAP, 0.5), noise2(7, -WRAP, 0.5));
+        assert_eq!(noise3(7, WRAP, 0.25, -WRAP), noise3(7, -WRAP, 0.25, WRAP));
+    }
+
+    #[test]
+    fn noise_is_continuous_across_negative_cells() {
+        for x in [-1.0, -3.0, -1000.0] {
+            let (before, after) = (noise2(3, x - 1e-3, 0.3), noise2(3, x + 1e-3, 0.3));
+            assert!((before - after).abs() < 1e-2, "noise2 jumps at {x}");
+            let (before, after) = (noise3(3, 0.7, x - 1e-3, 0.3), noise3(3, 0.7, x + 1e-3, 0.3));
+            assert!((before - after).abs() < 1e-2, "noise3 jumps at {x}");
+        }
+    }
+}
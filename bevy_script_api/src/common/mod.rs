@@ -1,2 +1,15 @@
 pub mod bevy;
+pub mod buffer;
+pub mod capabilities;
+pub mod exports;
+#[cfg(feature = "api_math")]
+pub mod fixed;
+pub mod fmt;
+#[cfg(feature = "api_time")]
+pub mod instant;
+pub mod mathx;
+pub mod run_once;
+pub mod shared;
 pub mod std;
+pub mod uniforms;
+pub mod vectors;
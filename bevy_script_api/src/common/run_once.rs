@@ -0,0 +1,156 @@
+//! Running a single hook of a script from Rust and reading back its return value
+use std::borrow::Cow;
+
+use bevy::prelude::{error, Commands, World};
+use bevy_mod_scripting_core::{
+    hosts::next_script_id,
+    prelude::{APIProviders, ScriptData, ScriptError, ScriptHost},
+};
+
+use super::shared::ScriptValue;
+
+/// The name given to scripts ran by [`RunScriptOnce::run_script_once`]
+pub const RUN_ONCE_SCRIPT_NAME: &str = "run_once";
+
+/// Script hosts which can call a hook of a script once and convert its return value
+pub trait RunOnceHost: ScriptHost {
+    /// Calls the hook in the given freshly loaded context, API providers have not yet been set up for this run.
+    ///
+    /// Lists of arguments are passed as separate arguments, `ScriptValue::Nil` as no arguments and
+    /// any other value as a single argument. Returns `None` if the script does not define the hook.
+    fn call_hook_once(
+        &mut self,
+        world: &mut World,
+        hook: &str,
+        args: ScriptValue,
+        script_data: &ScriptData,
+        ctx: &mut Self::ScriptContext,
+        providers: &mut APIProviders<Self>,
+    ) -> Result<Option<ScriptValue>, ScriptError>;
+}
+
+/// Runs scripts once, without storing any script data in the world
+pub trait RunScriptOnce {
+    /// Loads the script, calls its hook with the arguments and returns the value the hook returned,
+    /// or `None` if the script does not define the hook.
+    ///
+    /// Unlike [`ScriptHost::run_one_shot`] the host does not need to be removed from the world beforehand.
+    /// The script runs on its own empty entity, despawned afterwards, and with a fresh script ID.
+    /// ```rust,ignore
+    /// fn spawn_wave(world: &mut World) {
+    ///     let size = world.run_script_once::<LuaScriptHost<()>>(
+    ///         include_bytes!("wave.lua"),
+    ///         "wave_size",
+    ///         ScriptValue::Integer(3),
+    ///     );
+    /// }
+    /// ```
+    fn run_script_once<H: RunOnceHost>(
+        &mut self,
+        source: &[u8],
+        hook: &str,
+        args: ScriptValue,
+    ) -> Result<Option<ScriptValue>, ScriptError>;
+}
+
+impl RunScriptOnce for World {
+    fn run_script_once<H: RunOnceHost>(
+        &mut self,
+        source: &[u8],
+        hook: &str,
+        args: ScriptValue,
+    ) -> Result<Option<ScriptValue>, ScriptError> {
+        let mut host = self.remove_resource::<H>().ok_or_else(|| {
+            ScriptError::Other(format!(
+                "Script host `{}` was not added to the app",
+                std::any::type_name::<H>()
+            ))
+        })?;
+        let mut providers = match self.remove_resource::<APIProviders<H>>() {
+            Some(providers) => providers,
+            None => {
+                self.insert_resource(host);
+                return Err(ScriptError::Other(format!(
+                    "API providers of script host `{}` were not added to the app",
+                    std::any::type_name::<H>()
+                )));
+            }
+        };
+
+        let entity = self.spawn(()).id();
+        let script_data = ScriptData {
+            sid: next_script_id(),
+            entity,
+            name: RUN_ONCE_SCRIPT_NAME,
+            groups: &[],
+        };
+
+        let result = host
+            .load_script(source, &script_data, &mut providers)
+            .and_then(|mut ctx| {
+                host.setup_script(&script_data, &mut ctx, &mut providers)
+                    .map(|_| ctx)
+            })
+            .and_then(|mut ctx| {
+                host.call_hook_once(self, hook, args, &script_data, &mut ctx, &mut providers)
+            });
+
+        // the script may have despawned its entity already
+        self.despawn(entity);
+        self.insert_resource(host);
+        self.insert_resource(providers);
+
+        result
+    }
+}
+
+/// Queues scripts to be ran once from systems without exclusive world access
+pub trait QueueScriptOnce {
+    /// Queues [`RunScriptOnce::run_script_once`] to run when the commands are applied, errors are logged
+    fn run_script_once<H: RunOnceHost>(
+        &mut self,
+        source: impl Into<Cow<'static, [u8]>>,
+        hook: impl Into<String>,
+        args: ScriptValue,
+    );
+
+    /// Queues [`RunScriptOnce::run_script_once`] to run when the commands are applied,
+    /// the callback receives the world and the result of running the script
+    fn run_script_once_then<H: RunOnceHost>(
+        &mut self,
+        source: impl Into<Cow<'static, [u8]>>,
+        hook: impl Into<String>,
+        args: ScriptValue,
+        then: impl FnOnce(&mut World, Result<Option<ScriptValue>, ScriptError>) + Send + Sync + 'static,
+    );
+}
+
+impl QueueScriptOnce for Commands<'_, '_> {
+    fn run_script_once<H: RunOnceHost>(
+        &mut self,
+        source: impl Into<Cow<'static, [u8]>>,
+        hook: impl Into<String>,
+        args: ScriptValue,
+    ) {
+        self.run_script_once_then::<H>(source, hook, args, |_, result| {
+            if let Err(e) = result {
+                error!("{}", e);
+            }
+        })
+    }
+
+    fn run_script_once_then<H: RunOnceHost>(
+        &mut self,
+        source: impl Into<Cow<'static, [u8]>>,
+        hook: impl Into<String>,
+        args: ScriptValue,
+        then: impl FnOnce(&mut World, Result<Option<ScriptValue>, ScriptError>) + Send + Sync + 'static,
+    ) {
+        let source = source.into();
+        let hook = hook.into();
+        self.add(move |world: &mut World| {
+            let result = world.run_script_once::<H>(&source, &hook, args);
+            then(world, result)
+        });
+    }
+}
@@ -0,0 +1,539 @@
+use std::any::TypeId;
+
+use bevy::{
+    prelude::Resource,
+    reflect::{
+        DynamicArray, DynamicEnum, DynamicList, DynamicMap, DynamicStruct, DynamicTuple,
+        DynamicTupleStruct, DynamicVariant, Reflect, ReflectMut, ReflectRef, StructInfo, TypeInfo,
+        TypeRegistry, ValueInfo, VariantInfo, VariantType,
+    },
+    utils::{get_short_name, HashMap},
+};
+
+/// A language agnostic value, used to exchange data between scripts of different hosts
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum ScriptValue {
+    #[default]
+    Nil,
+    Bool(bool),
+    Integer(i64),
+    Number(f64),
+    String(String),
+    List(Vec<ScriptValue>),
+    Map(HashMap<String, ScriptValue>),
+}
+
+impl ScriptValue {
+    /// Copies a reflected value, None if it contains anything other than booleans, numbers, strings, unit enum variants
+    /// and structs, tuples, lists or string keyed maps of these. Unit variants are copied as their name
+    pub fn from_reflect(value: &dyn Reflect) -> Option<Self> {
+        match value.reflect_ref() {
+            ReflectRef::Struct(s) => s
+                .iter_fields()
+                .enumerate()
+                .map(|(i, f)| Some((s.name_at(i)?.to_owned(), Self::from_reflect(f)?)))
+                .collect::<Option<_>>()
+                .map(Self::Map),
+            ReflectRef::TupleStruct(s) => Self::from_reflect_list(s.iter_fields()),
+            ReflectRef::Tuple(t) => Self::from_reflect_list(t.iter_fields()),
+            ReflectRef::List(l) => Self::from_reflect_list(l.iter()),
+            ReflectRef::Array(a) => Self::from_reflect_list(a.iter()),
+            ReflectRef::Map(m) => m
+                .iter()
+                .map(|(k, v)| Some((k.downcast_ref::<String>()?.clone(), Self::from_reflect(v)?)))
+                .collect::<Option<_>>()
+                .map(Self::Map),
+            ReflectRef::Enum(e) => (e.variant_type() == VariantType::Unit)
+                .then(|| Self::String(e.variant_name().to_owned())),
+            ReflectRef::Value(v) => Self::from_reflect_value(v),
+        }
+    }
+
+    fn from_reflect_list<'a>(values: impl Iterator<Item = &'a dyn Reflect>) -> Option<Self> {
+        values
+            .map(Self::from_reflect)
+            .collect::<Option<_>>()
+            .map(Self::List)
+    }
+
+    fn from_reflect_value(v: &dyn Reflect) -> Option<Self> {
+        macro_rules! integers {
+            ($($t:ty),*) => {
+                $(if let Some(i) = v.downcast_ref::<$t>() {
+                    return i64::try_from(*i).ok().map(Self::Integer);
+                })*
+            };
+        }
+
+        if let Some(b) = v.downcast_ref::<bool>() {
+            return Some(Self::Bool(*b));
+        }
+        if let Some(i) = v.downcast_ref::<i64>() {
+            return Some(Self::Integer(*i));
+        }
+        integers!(i8, i16, i32, isize, u8, u16, u32, u64, usize);
+        if let Some(f) = v.downcast_ref::<f32>() {
+            return Some(Self::Number(*f as f64));
+        }
+        if let Some(f) = v.downcast_ref::<f64>() {
+            return Some(Self::Number(*f));
+        }
+        v.downcast_ref::<String>().cloned().map(Self::String)
+    }
+}
+
+impl ScriptValue {
+    /// The name of the kind of value, as used in error messages
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ScriptValue::Nil => "nil",
+            ScriptValue::Bool(_) => "boolean",
+            ScriptValue::Integer(_) => "integer",
+            ScriptValue::Number(_) => "number",
+            ScriptValue::String(_) => "string",
+            ScriptValue::List(_) => "list",
+            ScriptValue::Map(_) => "map",
+        }
+    }
+
+    /// Builds a dynamic value of the given type, e.g. an event or component constructed by a script.
+    ///
+    /// Structs are built from maps, tuple structs, tuples, lists and arrays from lists and enums from the names of their unit variants.
+    /// Every field has to be given, unknown and missing fields as well as values of the wrong kind are reported
+    /// alongside the fields the type expects rather than being defaulted.
+    pub fn to_reflect(
+        &self,
+        type_info: &TypeInfo,
+        registry: &TypeRegistry,
+    ) -> Result<Box<dyn Reflect>, String> {
+        self.to_reflect_at(type_info, registry, &get_short_name(type_info.type_name()))
+    }
+
+    /// Applies the value onto an existing value of any type, e.g. the default value of an asset created by a script.
+    ///
+    /// Unlike [`ScriptValue::to_reflect`], maps only need to name the fields they change and nested structs are patched the same way,
+    /// any other field is replaced by the value built for its type. `Nil` leaves the target unchanged
+    pub fn apply_to(
+        &self,
+        target: &mut dyn Reflect,
+        registry: &TypeRegistry,
+    ) -> Result<(), String> {
+        let path = get_short_name(target.type_name());
+        self.apply_at(target, registry, &path)
+    }
+
+    fn apply_at(
+        &self,
+        target: &mut dyn Reflect,
+        registry: &TypeRegistry,
+        path: &str,
+    ) -> Result<(), String> {
+        let type_info = target.get_type_info();
+        if *self == ScriptValue::Nil {
+            return Ok(());
+        }
+
+        if let (ScriptValue::Map(map), ReflectMut::Struct(s), TypeInfo::Struct(info)) =
+            (self, target.reflect_mut(), type_info)
+        {
+            for (name, value) in map {
+                let field = s.field_mut(name).ok_or_else(|| {
+                    format!(
+                        "`{path}` has no field `{name}`, `{}` has the fields {{ {} }}",
+                        get_short_name(info.type_name()),
+                        describe_fields(info)
+                    )
+                })?;
+                value.apply_at(field, registry, &format!("{path}.{name}"))?;
+            }
+            return Ok(());
+        }
+
+        let value = self.to_reflect_at(type_info, registry, path)?;
+        target.apply(&*value);
+        Ok(())
+    }
+
+    /// Like [`ScriptValue::to_reflect`], naming the value by its path from the outermost value in errors
+    fn to_reflect_at(
+        &self,
+        type_info: &TypeInfo,
+        registry: &TypeRegistry,
+        path: &str,
+    ) -> Result<Box<dyn Reflect>, String> {
+        let type_name = get_short_name(type_info.type_name());
+        let registered = |type_id: TypeId, type_name: &str, path: &str| {
+            registry.get_type_info(type_id).ok_or_else(|| {
+                format!(
+                    "`{path}` is a `{}` which is not registered",
+                    get_short_name(type_name)
+                )
+            })
+        };
+        let expected = |what: String| format!("`{path}` expects {what}, got a {}", self.kind());
+
+        Ok(match type_info {
+            TypeInfo::Struct(info) => {
+                let map = match self {
+                    ScriptValue::Map(map) => map,
+                    _ => {
+                        return Err(expected(format!(
+                            "a map of the fields {{ {} }}",
+                            describe_fields(info)
+                        )))
+                    }
+                };
+                if let Some(unknown) = map.keys().find(|k| info.field(k).is_none()) {
+                    return Err(format!(
+                        "`{path}` has no field `{unknown}`, `{type_name}` has the fields {{ {} }}",
+                        describe_fields(info)
+                    ));
+                }
+
+                let mut dynamic = DynamicStruct::default();
+                dynamic.set_name(info.type_name().to_owned());
+                for field in info.iter() {
+                    let field_path = format!("{path}.{}", field.name());
+                    let value = map.get(field.name()).ok_or_else(|| {
+                        format!(
+                            "`{path}` is missing the field `{}: {}`, `{type_name}` has the fields {{ {} }}",
+                            field.name(),
+                            get_short_name(field.type_name()),
+                            describe_fields(info)
+                        )
+                    })?;
+                    let field_info = registered(field.type_id(), field.type_name(), &field_path)?;
+                    dynamic.insert_boxed(
+                        field.name(),
+                        value.to_reflect_at(field_info, registry, &field_path)?,
+                    );
+                }
+                Box::new(dynamic)
+            }
+            TypeInfo::TupleStruct(info) => {
+                let values = self.list_of(info.field_len(), &expected)?;
+                let mut dynamic = DynamicTupleStruct::default();
+                dynamic.set_name(info.type_name().to_owned());
+                for (i, (field, value)) in info.iter().zip(values).enumerate() {
+                    let field_path = format!("{path}[{i}]");
+                    let field_info = registered(field.type_id(), field.type_name(), &field_path)?;
+                    dynamic.insert_boxed(value.to_reflect_at(field_info, registry, &field_path)?);
+                }
+                Box::new(dynamic)
+            }
+            TypeInfo::Tuple(info) => {
+                let values = self.list_of(info.field_len(), &expected)?;
+                let mut dynamic = DynamicTuple::default();
+                dynamic.set_name(info.type_name().to_owned());
+                for (i, (field, value)) in info.iter().zip(values).enumerate() {
+                    let field_path = format!("{path}[{i}]");
+                    let field_info = registered(field.type_id(), field.type_name(), &field_path)?;
+                    dynamic.insert_boxed(value.to_reflect_at(field_info, registry, &field_path)?);
+                }
+                Box::new(dynamic)
+            }
+            TypeInfo::List(info) => {
+                let values = match self {
+                    ScriptValue::List(values) => values,
+                    _ => return Err(expected("a list".to_owned())),
+                };
+                let mut dynamic = DynamicList::default();
+                dynamic.set_name(info.type_name().to_owned());
+                for (i, value) in values.iter().enumerate() {
+                    let item_path = format!("{path}[{i}]");
+                    let item_info =
+                        registered(info.item_type_id(), info.item_type_name(), &item_path)?;
+                    dynamic.push_box(value.to_reflect_at(item_info, registry, &item_path)?);
+                }
+                Box::new(dynamic)
+            }
+            TypeInfo::Array(info) => {
+                let values = self.list_of(info.capacity(), &expected)?;
+                let items = values
+                    .iter()
+                    .enumerate()
+                    .map(|(i, value)| {
+                        let item_path = format!("{path}[{i}]");
+                        let item_info =
+                            registered(info.item_type_id(), info.item_type_name(), &item_path)?;
+                        value.to_reflect_at(item_info, registry, &item_path)
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                let mut dynamic = DynamicArray::new(items.into_boxed_slice());
+                dynamic.set_name(info.type_name().to_owned());
+                Box::new(dynamic)
+            }
+            TypeInfo::Map(info) => {
+                let map = match self {
+                    ScriptValue::Map(map) => map,
+                    _ => return Err(expected("a map".to_owned())),
+                };
+                if info.key_type_id() != TypeId::of::<String>() {
+                    return Err(format!(
+                        "`{path}` is a `{type_name}` whose keys are not strings, which cannot be built by scripts"
+                    ));
+                }
+                let mut dynamic = DynamicMap::default();
+                dynamic.set_name(info.type_name().to_owned());
+                for (key, value) in map {
+                    let value_path = format!("{path}.{key}");
+                    let value_info =
+                        registered(info.value_type_id(), info.value_type_name(), &value_path)?;
+                    dynamic.insert_boxed(
+                        Box::new(key.clone()),
+                        value.to_reflect_at(value_info, registry, &value_path)?,
+                    );
+                }
+                Box::new(dynamic)
+            }
+            TypeInfo::Enum(info) => {
+                let variants = || {
+                    info.iter()
+                        .filter(|v| matches!(v, VariantInfo::Unit(_)))
+                        .map(|v| format!("\"{}\"", v.name()))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                };
+                match self {
+                    ScriptValue::String(variant)
+                        if matches!(info.variant(variant), Some(VariantInfo::Unit(_))) =>
+                    {
+                        Box::new(DynamicEnum::new(
+                            info.type_name(),
+                            variant.as_str(),
+                            DynamicVariant::Unit,
+                        ))
+                    }
+                    ScriptValue::String(variant) => {
+                        return Err(format!(
+                        "`{path}` has no variant `{variant}` without fields, expected one of: {}",
+                        variants()
+                    ))
+                    }
+                    _ => return Err(expected(format!("one of: {}", variants()))),
+                }
+            }
+            TypeInfo::Value(info) => self.to_reflect_value(info, path)?,
+            TypeInfo::Dynamic(_) => {
+                return Err(format!(
+                    "`{path}` is a dynamic `{type_name}` which cannot be built by scripts"
+                ))
+            }
+        })
+    }
+
+    fn list_of(
+        &self,
+        len: usize,
+        expected: &dyn Fn(String) -> String,
+    ) -> Result<&[ScriptValue], String> {
+        match self {
+            ScriptValue::List(values) if values.len() == len => Ok(values),
+            _ => Err(expected(format!("a list of {len} values"))),
+        }
+    }
+
+    fn to_reflect_value(&self, info: &ValueInfo, path: &str) -> Result<Box<dyn Reflect>, String> {
+        let type_id = info.type_id();
+        let type_name = get_short_name(info.type_name());
+        let expected = || format!("`{path}` expects a `{type_name}`, got a {}", self.kind());
+
+        macro_rules! integers {
+            ($($t:ty),*) => {
+                $(if type_id == TypeId::of::<$t>() {
+                    return match self {
+                        ScriptValue::Integer(i) => <$t>::try_from(*i)
+                            .map(|v| Box::new(v) as Box<dyn Reflect>)
+                            .map_err(|_| format!("`{path}` is a `{type_name}` which cannot hold {i}")),
+                        _ => Err(expected()),
+                    };
+                })*
+            };
+        }
+        integers!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+        Ok(match self {
+            ScriptValue::Bool(b) if type_id == TypeId::of::<bool>() => Box::new(*b),
+            ScriptValue::Integer(i) if type_id == TypeId::of::<f32>() => Box::new(*i as f32),
+            ScriptValue::Integer(i) if type_id == TypeId::of::<f64>() => Box::new(*i as f64),
+            ScriptValue::Number(n) if type_id == TypeId::of::<f32>() => Box::new(*n as f32),
+            ScriptValue::Number(n) if type_id == TypeId::of::<f64>() => Box::new(*n),
+            ScriptValue::String(s) if type_id == TypeId::of::<String>() => Box::new(s.clone()),
+            _ if [
+                TypeId::of::<bool>(),
+                TypeId::of::<f32>(),
+                TypeId::of::<f64>(),
+                TypeId::of::<String>(),
+            ]
+            .contains(&type_id) =>
+            {
+                return Err(expected())
+            }
+            _ => {
+                return Err(format!(
+                    "`{path}` is a `{type_name}` which cannot be built by scripts"
+                ))
+            }
+        })
+    }
+}
+
+/// Lists the fields of a struct with their types, e.g. `x: f32, y: f32`
+fn describe_fields(info: &StructInfo) -> String {
+    info.iter()
+        .map(|f| format!("{}: {}", f.name(), get_short_name(f.type_name())))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// A resource storing values which are visible to the scripts of every host, for example letting
+/// Lua and Rhai mods read each other's data. Values are always copied in and out of the store,
+/// so scripts can never hold references into each other's contexts.
+#[derive(Debug, Default, Resource)]
+pub struct SharedScriptValues {
+    values: HashMap<String, ScriptValue>,
+}
+
+impl SharedScriptValues {
+    /// Retrieves the value stored under the given key
+    pub fn get(&self, key: &str) -> Option<&ScriptValue> {
+        self.values.get(key)
+    }
+
+    /// Stores the value under the given key, setting a key to `ScriptValue::Nil` removes it
+    pub fn set(&mut self, key: String, value: ScriptValue) {
+        if value == ScriptValue::Nil {
+            self.values.remove(&key);
+        } else {
+            self.values.insert(key, value);
+        }
+    }
+
+    /// Iterates over all stored values and their keys
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &ScriptValue)> {
+        self.values.iter().map(|(k, v)| (k.as_str(), v))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::reflect::{FromReflect, GetTypeRegistration, Typed};
+
+    use super::*;
+
+    #[derive(Reflect, FromReflect, Debug, PartialEq)]
+    struct Hit {
+        damage: u32,
+        kind: HitKind,
+        source: String,
+    }
+
+    #[derive(Reflect, FromReflect, Debug, PartialEq)]
+    enum HitKind {
+        Melee,
+        Ranged,
+    }
+
+    fn hit(fields: &[(&str, ScriptValue)]) -> ScriptValue {
+        ScriptValue::Map(
+            fields
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.clone()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn storing_nil_removes_shared_values() {
+        let mut shared = SharedScriptValues::default();
+        shared.set("score".to_owned(), ScriptValue::Integer(3));
+        assert_eq!(shared.get("score"), Some(&ScriptValue::Integer(3)));
+
+        shared.set("score".to_owned(), ScriptValue::Nil);
+        assert_eq!(shared.get("score"), None);
+        assert_eq!(shared.iter().count(), 0);
+    }
+
+    #[test]
+    fn builds_values_and_reports_the_expected_fields() {
+        let mut registry = TypeRegistry::default();
+        registry.add_registration(Hit::get_type_registration());
+        registry.add_registration(HitKind::get_type_registration());
+
+        let built = hit(&[
+            ("damage", ScriptValue::Integer(3)),
+            ("kind", ScriptValue::String("Ranged".to_owned())),
+            ("source", ScriptValue::String("trap".to_owned())),
+        ])
+        .to_reflect(Hit::type_info(), &registry)
+        .unwrap();
+        assert_eq!(
+            Hit::from_reflect(&*built),
+            Some(Hit {
+                damage: 3,
+                kind: HitKind::Ranged,
+                source: "trap".to_owned()
+            })
+        );
+
+        let missing = hit(&[("damage", ScriptValue::Integer(3))])
+            .to_reflect(Hit::type_info(), &registry)
+            .unwrap_err();
+        assert!(
+            missing.contains("missing the field `kind: HitKind`")
+                && missing.contains("{ damage: u32, kind: HitKind, source: String }"),
+            "{missing}"
+        );
+
+        let negative = hit(&[
+            ("damage", ScriptValue::Integer(-1)),
+            ("kind", ScriptValue::String("Melee".to_owned())),
+            ("source", ScriptValue::String("trap".to_owned())),
+        ])
+        .to_reflect(Hit::type_info(), &registry)
+        .unwrap_err();
+        assert!(negative.contains("`Hit.damage`"), "{negative}");
+
+        let unknown = hit(&[("dmg", ScriptValue::Integer(3))])
+            .to_reflect(Hit::type_info(), &registry)
+            .unwrap_err();
+        assert!(unknown.contains("no field `dmg`"), "{unknown}");
+    }
+
+    #[test]
+    fn applies_only_the_given_fields() {
+        let registry = TypeRegistry::default();
+        let mut target = Hit {
+            damage: 3,
+            kind: HitKind::Melee,
+            source: "trap".to_owned(),
+        };
+
+        hit(&[
+            ("damage", ScriptValue::Integer(5)),
+            ("kind", ScriptValue::String("Ranged".to_owned())),
+        ])
+        .apply_to(&mut target, &registry)
+        .unwrap();
+        assert_eq!(
+            target,
+            Hit {
+                damage: 5,
+                kind: HitKind::Ranged,
+                source: "trap".to_owned()
+            }
+        );
+
+        ScriptValue::Nil.apply_to(&mut target, &registry).unwrap();
+        assert_eq!(target.damage, 5);
+
+        let unknown = hit(&[("dmg", ScriptValue::Integer(3))])
+            .apply_to(&mut target, &registry)
+            .unwrap_err();
+        assert!(
+            unknown.contains("`Hit` has no field `dmg`")
+                && unknown.contains("{ damage: u32, kind: HitKind, source: String }"),
+            "{unknown}"
+        );
+    }
+}
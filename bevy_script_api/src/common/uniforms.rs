@@ -0,0 +1,103 @@
+use bevy::prelude::{Resource, Vec4};
+use bevy_mod_scripting_core::prelude::ScriptError;
+
+/// The number of slots in a [`ScriptUniforms`] buffer
+pub const MAX_SCRIPT_UNIFORMS: usize = 16;
+
+/// A fixed size buffer of named `vec4` slots which scripts can write to, used to drive shader parameters from mods.
+///
+/// Slots are declared by the app, scripts can only set values of declared slots which keeps the layout
+/// of the buffer stable. Material plugins copy [`ScriptUniforms::as_array`] into their uniform binding,
+/// where slot `i` is found at index `i` of an `array<vec4<f32>, 16>`. Scalar values occupy the `x` component of their slot.
+#[derive(Debug, Default, Clone, Resource)]
+pub struct ScriptUniforms {
+    names: Vec<String>,
+    values: [Vec4; MAX_SCRIPT_UNIFORMS],
+}
+
+impl ScriptUniforms {
+    /// Declares a new slot under the given name and returns its index, declaring an existing name returns its current index.
+    ///
+    /// Panics if all slots are taken.
+    pub fn declare(&mut self, name: impl Into<String>) -> usize {
+        let name = name.into();
+        if let Some(idx) = self.slot(&name) {
+            return idx;
+        }
+
+        assert!(
+            self.names.len() < MAX_SCRIPT_UNIFORMS,
+            "Cannot declare more than {MAX_SCRIPT_UNIFORMS} script uniforms"
+        );
+        self.names.push(name);
+        self.names.len() - 1
+    }
+
+    /// Declares a slot, for chaining calls when creating the resource
+    pub fn with_slot(mut self, name: impl Into<String>) -> Self {
+        self.declare(name);
+        self
+    }
+
+    /// The index of the slot with the given name
+    pub fn slot(&self, name: &str) -> Option<usize> {
+        self.names.iter().position(|n| n == name)
+    }
+
+    /// The names of all declared slots, in slot order
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.names.iter().map(String::as_str)
+    }
+
+    /// Retrieves the value of the slot with the given name
+    pub fn get(&self, name: &str) -> Option<Vec4> {
+        self.slot(name).map(|idx| self.values[idx])
+    }
+
+    /// Sets the value of the slot with the given name, fails if no such slot was declared
+    pub fn set(&mut self, name: &str, value: Vec4) -> Result<(), ScriptError> {
+        let idx = self.slot(name).ok_or_else(|| {
+            ScriptError::Other(format!(
+                "No script uniform slot named `{name}` was declared"
+            ))
+        })?;
+        self.values[idx] = value;
+        Ok(())
+    }
+
+    /// The raw values of all slots, ready to be copied into a uniform buffer
+    pub fn as_array(&self) -> [Vec4; MAX_SCRIPT_UNIFORMS] {
+        self.values
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_declared_slots_can_be_set() {
+        let mut uniforms = ScriptUniforms::default()
+            .with_slot("time")
+            .with_slot("tint");
+        assert_eq!(uniforms.declare("tint"), 1);
+        assert_eq!(uniforms.names().collect::<Vec<_>>(), vec!["time", "tint"]);
+
+        uniforms.set("tint", Vec4::ONE).unwrap();
+        assert!(uniforms.set("glow", Vec4::ONE).is_err());
+
+        assert_eq!(uniforms.get("tint"), Some(Vec4::ONE));
+        assert_eq!(uniforms.get("glow"), None);
+        assert_eq!(uniforms.as_array()[0], Vec4::ZERO);
+        assert_eq!(uniforms.as_array()[1], Vec4::ONE);
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot declare more than")]
+    fn declaring_too_many_slots_panics() {
+        let mut uniforms = ScriptUniforms::default();
+        for i in 0..=MAX_SCRIPT_UNIFORMS {
+            uniforms.declare(format!("slot{i}"));
+        }
+    }
+}
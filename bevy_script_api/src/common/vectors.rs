@@ -0,0 +1,237 @@
+//! Component access shared by the vector proxies of every language, so that indexing (`v[0]`) and swizzles (`v.zyx`)
+//! behave the same in all of them
+use bevy::math::{
+    DVec2, DVec3, DVec4, IVec2, IVec3, IVec4, UVec2, UVec3, UVec4, Vec2, Vec3, Vec3A, Vec4,
+};
+
+use crate::error::ReflectionError;
+
+/// The value read by a swizzle, a single component or a vector with as many components as were named
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Swizzled {
+    Float(f32),
+    Vec2(Vec2),
+    Vec3(Vec3),
+    Vec4(Vec4),
+}
+
+/// A vector of `f32` components indexed from 0, named `x`, `y`, `z` and `w` in order
+pub trait VectorComponents: Copy {
+    /// The names of the components, in order
+    const NAMES: &'static str;
+
+    fn to_components(self) -> Vec<f32>;
+
+    fn component(self, index: usize) -> Result<f32, ReflectionError> {
+        self.to_components()
+            .get(index)
+            .copied()
+            .ok_or_else(|| out_of_range(index, Self::NAMES.len()))
+    }
+
+    fn set_component(&mut self, index: usize, value: f32) -> Result<(), ReflectionError>;
+
+    /// Reads the named components, e.g. `"xy"`, `"zyx"` or `"xxyy"`, as a vector of the same length,
+    /// single components are read as a float
+    fn swizzle(self, names: &str) -> Result<Swizzled, ReflectionError> {
+        let components = self.to_components();
+        let swizzled = names
+            .chars()
+            .map(|name| {
+                Self::NAMES
+                    .find(name)
+                    .map(|index| components[index])
+                    .ok_or_else(|| {
+                        ReflectionError::Other(format!(
+                            "`{name}` is not a component of a vector with components `{}`",
+                            Self::NAMES
+                        ))
+                    })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        match swizzled.as_slice() {
+            [x] => Ok(Swizzled::Float(*x)),
+            [x, y] => Ok(Swizzled::Vec2(Vec2::new(*x, *y))),
+            [x, y, z] => Ok(Swizzled::Vec3(Vec3::new(*x, *y, *z))),
+            [x, y, z, w] => Ok(Swizzled::Vec4(Vec4::new(*x, *y, *z, *w))),
+            _ => Err(ReflectionError::Other(format!(
+                "Swizzles name between 1 and 4 components, `{names}` names {}",
+                swizzled.len()
+            ))),
+        }
+    }
+}
+
+fn out_of_range(index: usize, len: usize) -> ReflectionError {
+    ReflectionError::Other(format!(
+        "Index {index} is out of range for a vector with {len} components"
+    ))
+}
+
+macro_rules! impl_vector_components {
+    ($type:ty, $names:literal) => {
+        impl VectorComponents for $type {
+            const NAMES: &'static str = $names;
+
+            fn to_components(self) -> Vec<f32> {
+                self.to_array().to_vec()
+            }
+
+            fn set_component(&mut self, index: usize, value: f32) -> Result<(), ReflectionError> {
+                if index >= Self::NAMES.len() {
+                    return Err(out_of_range(index, Self::NAMES.len()));
+                }
+                self[index] = value;
+                Ok(())
+            }
+        }
+    };
+}
+
+impl_vector_components!(Vec2, "xy");
+impl_vector_components!(Vec3, "xyz");
+impl_vector_components!(Vec3A, "xyz");
+impl_vector_components!(Vec4, "xyzw");
+
+/// Bounds checked indexing of the integer and `f64` vectors, which are indexed but not swizzled
+pub trait IndexedComponents: Copy {
+    type Scalar: Copy;
+    /// The number of components
+    const LEN: usize;
+
+    fn component(self, index: usize) -> Result<Self::Scalar, ReflectionError>;
+
+    fn set_component(&mut self, index: usize, value: Self::Scalar) -> Result<(), ReflectionError>;
+}
+
+macro_rules! impl_indexed_components {
+    ($type:ty, $scalar:ty, $len:literal) => {
+        impl IndexedComponents for $type {
+            type Scalar = $scalar;
+            const LEN: usize = $len;
+
+            fn component(self, index: usize) -> Result<$scalar, ReflectionError> {
+                if index >= Self::LEN {
+                    return Err(out_of_range(index, Self::LEN));
+                }
+                Ok(self[index])
+            }
+
+            fn set_component(
+                &mut self,
+                index: usize,
+                value: $scalar,
+            ) -> Result<(), ReflectionError> {
+                if index >= Self::LEN {
+                    return Err(out_of_range(index, Self::LEN));
+                }
+                self[index] = value;
+                Ok(())
+            }
+        }
+    };
+}
+
+impl_indexed_components!(DVec2, f64, 2);
+impl_indexed_components!(DVec3, f64, 3);
+impl_indexed_components!(DVec4, f64, 4);
+impl_indexed_components!(IVec2, i32, 2);
+impl_indexed_components!(IVec3, i32, 3);
+impl_indexed_components!(IVec4, i32, 4);
+impl_indexed_components!(UVec2, u32, 2);
+impl_indexed_components!(UVec3, u32, 3);
+impl_indexed_components!(UVec4, u32, 4);
+
+/// The expected result of a conformance case
+#[cfg(test)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Expected {
+    Bool(bool),
+    Value(Swizzled),
+}
+
+/// Expressions which every language evaluates to the same result, given the globals `a = Vec3(1, 2, 3)` and `b = Vec3(4, 5, 6)`.
+///
+/// Each language runs these against its own vector proxies, the expressions are written in the syntax Lua and Rhai share.
+#[cfg(test)]
+pub(crate) const CONFORMANCE_CASES: &[(&str, Expected)] = &[
+    (
+        "-a",
+        Expected::Value(Swizzled::Vec3(Vec3::new(-1.0, -2.0, -3.0))),
+    ),
+    (
+        "a + b",
+        Expected::Value(Swizzled::Vec3(Vec3::new(5.0, 7.0, 9.0))),
+    ),
+    (
+        "b - a",
+        Expected::Value(Swizzled::Vec3(Vec3::new(3.0, 3.0, 3.0))),
+    ),
+    (
+        "a * b",
+        Expected::Value(Swizzled::Vec3(Vec3::new(4.0, 10.0, 18.0))),
+    ),
+    (
+        "a * 2.0",
+        Expected::Value(Swizzled::Vec3(Vec3::new(2.0, 4.0, 6.0))),
+    ),
+    (
+        "b / 2.0",
+        Expected::Value(Swizzled::Vec3(Vec3::new(2.0, 2.5, 3.0))),
+    ),
+    ("(a + b).y", Expected::Value(Swizzled::Float(7.0))),
+    ("a == a", Expected::Bool(true)),
+    ("a == b", Expected::Bool(false)),
+    ("a[0]", Expected::Value(Swizzled::Float(1.0))),
+    ("a[2]", Expected::Value(Swizzled::Float(3.0))),
+    ("a.xy", Expected::Value(Swizzled::Vec2(Vec2::new(1.0, 2.0)))),
+    (
+        "a.zyx",
+        Expected::Value(Swizzled::Vec3(Vec3::new(3.0, 2.0, 1.0))),
+    ),
+    (
+        "a.xxyz",
+        Expected::Value(Swizzled::Vec4(Vec4::new(1.0, 1.0, 2.0, 3.0))),
+    ),
+    ("(a.xy + b.zz).y", Expected::Value(Swizzled::Float(8.0))),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn indexes_and_swizzles() {
+        let v = Vec3::new(1.0, 2.0, 3.0);
+        assert_eq!(v.component(2).unwrap(), 3.0);
+        assert!(v.component(3).is_err());
+        assert_eq!(v.swizzle("y").unwrap(), Swizzled::Float(2.0));
+        assert_eq!(
+            v.swizzle("zx").unwrap(),
+            Swizzled::Vec2(Vec2::new(3.0, 1.0))
+        );
+        assert!(v.swizzle("xw").is_err());
+        assert!(v.swizzle("xyzxy").is_err());
+        assert!(v.swizzle("").is_err());
+
+        let mut v = Vec2::ZERO;
+        v.set_component(1, 5.0).unwrap();
+        assert_eq!(v, Vec2::new(0.0, 5.0));
+        assert!(v.set_component(2, 5.0).is_err());
+    }
+
+    #[test]
+    fn indexes_integer_vectors_within_bounds() {
+        let mut v = IVec3::new(1, 2, 3);
+        assert_eq!(IndexedComponents::component(v, 2).unwrap(), 3);
+        assert!(IndexedComponents::component(v, 5).is_err());
+
+        IndexedComponents::set_component(&mut v, 0, 7).unwrap();
+        assert_eq!(v, IVec3::new(7, 2, 3));
+        assert!(IndexedComponents::set_component(&mut v, 3, 7).is_err());
+
+        assert!(IndexedComponents::component(UVec2::ONE, 2).is_err());
+        assert!(IndexedComponents::component(DVec4::ONE, 4).is_err());
+    }
+}
@@ -1458,6 +1458,101 @@ impl_script_newtype! {
     )
     lua impl
     {
+
+    /// Returns true if this entity still exists in the world, entities may be despawned while scripts still hold references to them
+    "valid" => |ctx,s,()| {
+        let world = ctx.get_world()?;
+        let world = world.read();
+        Ok(world.get_entity(s.inner()?).is_some())
+    };
+
+    /// Despawns this entity if it exists, its children are left in place without a parent. Returns true if the entity existed
+    "despawn" => |ctx,s,()| {
+        let world = crate::common::bevy::ScriptWorld::new(ctx.get_world()?);
+        Ok(world.despawn(s.inner()?))
+    };
+
+    /// Despawns this entity and its children recursively
+    "despawn_recursive" => |ctx,s,()| {
+        let world = crate::common::bevy::ScriptWorld::new(ctx.get_world()?);
+        world.despawn_recursive(s.inner()?);
+        Ok(())
+    };
+
+    /// Makes the given entity the parent of this entity, removing it from the children of its previous parent.
+    /// Returns false without changing anything if either entity does not exist, or if the parent is this entity or one of its descendants
+    "set_parent" => |ctx,s,parent: LuaEntity| {
+        let world = crate::common::bevy::ScriptWorld::new(ctx.get_world()?);
+        Ok(world.set_parent(s.inner()?, parent.inner()?))
+    };
+
+    /// Removes this entity from the children of its parent, making it the root of its own hierarchy. Returns false if it had no parent
+    "detach" => |ctx,s,()| {
+        let world = crate::common::bevy::ScriptWorld::new(ctx.get_world()?);
+        Ok(world.detach(s.inner()?))
+    };
+
+    /// Returns an iterator over the children of this entity, their children and so on, depth first: `for child in entity:descendants() do .. end`
+    "descendants" => |ctx,s,()| {
+        let world = crate::common::bevy::ScriptWorld::new(ctx.get_world()?);
+        let mut descendants = world.descendants(s.inner()?);
+        bevy_mod_scripting_lua::tealr::mlu::TypedFunction::from_rust_mut(
+            move |_, ()| Ok(descendants.next().map(LuaEntity::new)),
+            ctx,
+        )
+    };
+
+    /// Computes the world space bounding box of this entity from its mesh `Aabb` or sprite size and its `GlobalTransform`, returns nil if its bounds are unknown
+    "world_aabb" => |ctx,s,()| {
+        let world = crate::common::bevy::ScriptWorld::new(ctx.get_world()?);
+        world
+            .world_aabb(s.inner()?)
+            .map(|aabb| aabb.map(LuaAabb::new))
+            .map_err(|e| bevy_mod_scripting_lua::tealr::mlu::mlua::Error::RuntimeError(e.to_string()))
+    };
+
+    /// Moves this entity by the given offset, e.g. `entity:translate(Vec3.new(0, 1, 0))`
+    "translate" => |ctx,s,offset: LuaVec3| {
+        let world = crate::common::bevy::ScriptWorld::new(ctx.get_world()?);
+        world
+            .translate(s.inner()?, offset.inner()?)
+            .map_err(|e| bevy_mod_scripting_lua::tealr::mlu::mlua::Error::RuntimeError(e.to_string()))
+    };
+
+    /// Rotates this entity so that it faces the given position, with its up direction towards `up` or the Y axis if not given
+    "look_at" => |ctx,s,(target, up): (LuaVec3, Option<LuaVec3>)| {
+        let world = crate::common::bevy::ScriptWorld::new(ctx.get_world()?);
+        let up = up.map(|up| up.inner()).transpose()?.unwrap_or(bevy::prelude::Vec3::Y);
+        world
+            .look_at(s.inner()?, target.inner()?, up)
+            .map_err(|e| bevy_mod_scripting_lua::tealr::mlu::mlua::Error::RuntimeError(e.to_string()))
+    };
+
+    /// Rotates this entity around the given point by the angle in radians around the axis, changing both its position and orientation
+    "rotate_around" => |ctx,s,(point, axis, angle): (LuaVec3, LuaVec3, f32)| {
+        let world = crate::common::bevy::ScriptWorld::new(ctx.get_world()?);
+        world
+            .rotate_around(s.inner()?, point.inner()?, axis.inner()?, angle)
+            .map_err(|e| bevy_mod_scripting_lua::tealr::mlu::mlua::Error::RuntimeError(e.to_string()))
+    };
+
+    /// Returns the direction this entity faces (-Z) in world space, read from its `GlobalTransform` or its `Transform` if it has none
+    "forward" => |ctx,s,()| {
+        let world = crate::common::bevy::ScriptWorld::new(ctx.get_world()?);
+        world
+            .forward(s.inner()?)
+            .map(LuaVec3::new)
+            .map_err(|e| bevy_mod_scripting_lua::tealr::mlu::mlua::Error::RuntimeError(e.to_string()))
+    };
+
+    /// Returns the position of this entity in world space, read from its `GlobalTransform` or its `Transform` if it has none
+    "world_position" => |ctx,s,()| {
+        let world = crate::common::bevy::ScriptWorld::new(ctx.get_world()?);
+        world
+            .world_position(s.inner()?)
+            .map(LuaVec3::new)
+            .map_err(|e| bevy_mod_scripting_lua::tealr::mlu::mlua::Error::RuntimeError(e.to_string()))
+    };
     }
 }
 impl_script_newtype! {
@@ -2727,6 +2822,40 @@ impl_script_newtype! {
     )
     lua impl
     {
+
+    /// Creates a color from an sRGB hex string such as `"#FF8800"`, `"F80"` or `"FF8800CC"`
+    static "hex" => |_,hex: String| {
+        crate::common::bevy::color::color_from_hex(&hex)
+            .map(LuaColor::new)
+            .map_err(|e| bevy_mod_scripting_lua::tealr::mlu::mlua::Error::RuntimeError(e.to_string()))
+    };
+
+    /// Creates a color from sRGB channels in the range [0,255], alpha defaults to 255
+    static "rgb_u8" => |_,(r,g,b,a): (u8,u8,u8,Option<u8>)| {
+        Ok(LuaColor::new(Color::rgba_u8(r,g,b,a.unwrap_or(u8::MAX))))
+    };
+
+    /// Returns the color as an sRGB hex string in the form `"#RRGGBBAA"`
+    "to_hex" => |_,s,()| {
+        Ok(crate::common::bevy::color::color_to_hex(s.inner()?))
+    };
+
+    /// Returns the red, green, blue and alpha channels of the color in the sRGB colorspace
+    "to_rgba" => |_,s,()| {
+        let [r,g,b,a] = s.inner()?.as_rgba_f32();
+        Ok((r,g,b,a))
+    };
+
+    /// Returns the hue, saturation, lightness and alpha of the color
+    "to_hsla" => |_,s,()| {
+        let [h,sat,l,a] = s.inner()?.as_hsla_f32();
+        Ok((h,sat,l,a))
+    };
+
+    /// Linearly interpolates between this and the other color in the sRGB colorspace
+    "lerp" => |_,s,(other,t): (LuaColor,f32)| {
+        Ok(LuaColor::new(crate::common::bevy::color::lerp_color(s.inner()?, other.inner()?, t)))
+    };
     }
 }
 impl_script_newtype! {
@@ -2757,6 +2886,16 @@ impl_script_newtype! {
     )
     lua impl
     {
+
+    /// Returns true if this box overlaps the other box
+    "intersects" => |_,s,other: LuaAabb| {
+        Ok(crate::common::bevy::bounds::aabb_intersects(&s.inner()?, &other.inner()?))
+    };
+
+    /// Returns true if the point lies inside this box
+    "contains" => |_,s,point: LuaVec3| {
+        Ok(crate::common::bevy::bounds::aabb_contains(&s.inner()?, point.inner()?))
+    };
     }
 }
 impl_script_newtype! {
@@ -3524,8 +3663,9 @@ impl_script_newtype! {
     )
     lua impl
     {
-        (MetaMethod::Index) => |_,s,idx: usize| {Ok(s.inner()?[idx])};
-        mut (MetaMethod::NewIndex) => |_,s,(idx,val): (usize,f32)| {s.val_mut(|s| Ok(s[idx] = val))?};
+        (MetaMethod::Index) => |ctx,s,key: bevy_mod_scripting_lua::tealr::mlu::mlua::Value| {crate::lua::bevy::vectors::index_vector(ctx, s.inner()?, key)};
+        mut (MetaMethod::NewIndex) => |_,s,(idx,val): (usize,f32)| {Ok(s.val_mut(|s| crate::common::vectors::VectorComponents::set_component(s, idx, val))??)};
+        (MetaMethod::Eq) => |_,s,other: LuaVec2| {Ok(s.inner()? == other.inner()?)};
     }
 }
 impl_script_newtype! {
@@ -3879,8 +4019,9 @@ impl_script_newtype! {
     )
     lua impl
     {
-        (MetaMethod::Index) => |_,s,idx: usize| {Ok(s.inner()?[idx])};
-        mut (MetaMethod::NewIndex) => |_,s,(idx,val): (usize,f32)| {s.val_mut(|s| Ok(s[idx] = val))?};
+        (MetaMethod::Index) => |ctx,s,key: bevy_mod_scripting_lua::tealr::mlu::mlua::Value| {crate::lua::bevy::vectors::index_vector(ctx, s.inner()?, key)};
+        mut (MetaMethod::NewIndex) => |_,s,(idx,val): (usize,f32)| {Ok(s.val_mut(|s| crate::common::vectors::VectorComponents::set_component(s, idx, val))??)};
+        (MetaMethod::Eq) => |_,s,other: LuaVec3| {Ok(s.inner()? == other.inner()?)};
     }
 }
 impl_script_newtype! {
@@ -4236,8 +4377,9 @@ impl_script_newtype! {
     )
     lua impl
     {
-        (MetaMethod::Index) => |_,s,idx: usize| {Ok(s.inner()?[idx])};
-        mut (MetaMethod::NewIndex) => |_,s,(idx,val): (usize,f32)| {s.val_mut(|s| Ok(s[idx] = val))?};
+        (MetaMethod::Index) => |ctx,s,key: bevy_mod_scripting_lua::tealr::mlu::mlua::Value| {crate::lua::bevy::vectors::index_vector(ctx, s.inner()?, key)};
+        mut (MetaMethod::NewIndex) => |_,s,(idx,val): (usize,f32)| {Ok(s.val_mut(|s| crate::common::vectors::VectorComponents::set_component(s, idx, val))??)};
+        (MetaMethod::Eq) => |_,s,other: LuaVec3A| {Ok(s.inner()? == other.inner()?)};
     }
 }
 impl_script_newtype! {
@@ -4565,8 +4707,9 @@ impl_script_newtype! {
     )
     lua impl
     {
-        (MetaMethod::Index) => |_,s,idx: usize| {Ok(s.inner()?[idx])};
-        mut (MetaMethod::NewIndex) => |_,s,(idx,val): (usize,f32)| {s.val_mut(|s| Ok(s[idx] = val))?};
+        (MetaMethod::Index) => |ctx,s,key: bevy_mod_scripting_lua::tealr::mlu::mlua::Value| {crate::lua::bevy::vectors::index_vector(ctx, s.inner()?, key)};
+        mut (MetaMethod::NewIndex) => |_,s,(idx,val): (usize,f32)| {Ok(s.val_mut(|s| crate::common::vectors::VectorComponents::set_component(s, idx, val))??)};
+        (MetaMethod::Eq) => |_,s,other: LuaVec4| {Ok(s.inner()? == other.inner()?)};
     }
 }
 impl_script_newtype! {
@@ -4609,6 +4752,7 @@ impl_script_newtype! {
     )
     lua impl
     {
+        (MetaMethod::Eq) => |_,s,other: LuaBVec2| {Ok(s.inner()? == other.inner()?)};
     }
 }
 impl_script_newtype! {
@@ -4652,6 +4796,7 @@ impl_script_newtype! {
     )
     lua impl
     {
+        (MetaMethod::Eq) => |_,s,other: LuaBVec3| {Ok(s.inner()? == other.inner()?)};
     }
 }
 impl_script_newtype! {
@@ -4696,6 +4841,7 @@ impl_script_newtype! {
     )
     lua impl
     {
+        (MetaMethod::Eq) => |_,s,other: LuaBVec4| {Ok(s.inner()? == other.inner()?)};
     }
 }
 impl_script_newtype! {
@@ -4739,6 +4885,7 @@ impl_script_newtype! {
     )
     lua impl
     {
+        (MetaMethod::Eq) => |_,s,other: LuaBVec3A| {Ok(s.inner()? == other.inner()?)};
     }
 }
 impl_script_newtype! {
@@ -4782,6 +4929,7 @@ impl_script_newtype! {
     )
     lua impl
     {
+        (MetaMethod::Eq) => |_,s,other: LuaBVec4A| {Ok(s.inner()? == other.inner()?)};
     }
 }
 impl_script_newtype! {
@@ -5127,8 +5275,9 @@ impl_script_newtype! {
     )
     lua impl
     {
-        (MetaMethod::Index) => |_,s,idx: usize| {Ok(s.inner()?[idx])};
-        mut (MetaMethod::NewIndex) => |_,s,(idx,val): (usize,f64)| {s.val_mut(|s| Ok(s[idx] = val))?};
+        (MetaMethod::Index) => |_,s,idx: usize| {Ok(crate::common::vectors::IndexedComponents::component(s.inner()?, idx)?)};
+        mut (MetaMethod::NewIndex) => |_,s,(idx,val): (usize,f64)| {Ok(s.val_mut(|s| crate::common::vectors::IndexedComponents::set_component(s, idx, val))??)};
+        (MetaMethod::Eq) => |_,s,other: LuaDVec2| {Ok(s.inner()? == other.inner()?)};
     }
 }
 impl_script_newtype! {
@@ -5485,8 +5634,9 @@ impl_script_newtype! {
     )
     lua impl
     {
-        (MetaMethod::Index) => |_,s,idx: usize| {Ok(s.inner()?[idx])};
-        mut (MetaMethod::NewIndex) => |_,s,(idx,val): (usize,f64)| {s.val_mut(|s| Ok(s[idx] = val))?};
+        (MetaMethod::Index) => |_,s,idx: usize| {Ok(crate::common::vectors::IndexedComponents::component(s.inner()?, idx)?)};
+        mut (MetaMethod::NewIndex) => |_,s,(idx,val): (usize,f64)| {Ok(s.val_mut(|s| crate::common::vectors::IndexedComponents::set_component(s, idx, val))??)};
+        (MetaMethod::Eq) => |_,s,other: LuaDVec3| {Ok(s.inner()? == other.inner()?)};
     }
 }
 impl_script_newtype! {
@@ -5814,8 +5964,9 @@ impl_script_newtype! {
     )
     lua impl
     {
-        (MetaMethod::Index) => |_,s,idx: usize| {Ok(s.inner()?[idx])};
-        mut (MetaMethod::NewIndex) => |_,s,(idx,val): (usize,f64)| {s.val_mut(|s| Ok(s[idx] = val))?};
+        (MetaMethod::Index) => |_,s,idx: usize| {Ok(crate::common::vectors::IndexedComponents::component(s.inner()?, idx)?)};
+        mut (MetaMethod::NewIndex) => |_,s,(idx,val): (usize,f64)| {Ok(s.val_mut(|s| crate::common::vectors::IndexedComponents::set_component(s, idx, val))??)};
+        (MetaMethod::Eq) => |_,s,other: LuaDVec4| {Ok(s.inner()? == other.inner()?)};
     }
 }
 impl_script_newtype! {
@@ -5986,8 +6137,9 @@ impl_script_newtype! {
     )
     lua impl
     {
-        (MetaMethod::Index) => |_,s,idx: usize| {Ok(s.inner()?[idx])};
-        mut (MetaMethod::NewIndex) => |_,s,(idx,val): (usize,i32)| {s.val_mut(|s| Ok(s[idx] = val))?};
+        (MetaMethod::Index) => |_,s,idx: usize| {Ok(crate::common::vectors::IndexedComponents::component(s.inner()?, idx)?)};
+        mut (MetaMethod::NewIndex) => |_,s,(idx,val): (usize,i32)| {Ok(s.val_mut(|s| crate::common::vectors::IndexedComponents::set_component(s, idx, val))??)};
+        (MetaMethod::Eq) => |_,s,other: LuaIVec2| {Ok(s.inner()? == other.inner()?)};
     }
 }
 impl_script_newtype! {
@@ -6158,8 +6310,9 @@ impl_script_newtype! {
     )
     lua impl
     {
-        (MetaMethod::Index) => |_,s,idx: usize| {Ok(s.inner()?[idx])};
-        mut (MetaMethod::NewIndex) => |_,s,(idx,val): (usize,i32)| {s.val_mut(|s| Ok(s[idx] = val))?};
+        (MetaMethod::Index) => |_,s,idx: usize| {Ok(crate::common::vectors::IndexedComponents::component(s.inner()?, idx)?)};
+        mut (MetaMethod::NewIndex) => |_,s,(idx,val): (usize,i32)| {Ok(s.val_mut(|s| crate::common::vectors::IndexedComponents::set_component(s, idx, val))??)};
+        (MetaMethod::Eq) => |_,s,other: LuaIVec3| {Ok(s.inner()? == other.inner()?)};
     }
 }
 impl_script_newtype! {
@@ -6322,8 +6475,9 @@ impl_script_newtype! {
     )
     lua impl
     {
-        (MetaMethod::Index) => |_,s,idx: usize| {Ok(s.inner()?[idx])};
-        mut (MetaMethod::NewIndex) => |_,s,(idx,val): (usize,i32)| {s.val_mut(|s| Ok(s[idx] = val))?};
+        (MetaMethod::Index) => |_,s,idx: usize| {Ok(crate::common::vectors::IndexedComponents::component(s.inner()?, idx)?)};
+        mut (MetaMethod::NewIndex) => |_,s,(idx,val): (usize,i32)| {Ok(s.val_mut(|s| crate::common::vectors::IndexedComponents::set_component(s, idx, val))??)};
+        (MetaMethod::Eq) => |_,s,other: LuaIVec4| {Ok(s.inner()? == other.inner()?)};
     }
 }
 impl_script_newtype! {
@@ -6465,8 +6619,9 @@ impl_script_newtype! {
     )
     lua impl
     {
-        (MetaMethod::Index) => |_,s,idx: usize| {Ok(s.inner()?[idx])};
-        mut (MetaMethod::NewIndex) => |_,s,(idx,val): (usize,u32)| {s.val_mut(|s| Ok(s[idx] = val))?};
+        (MetaMethod::Index) => |_,s,idx: usize| {Ok(crate::common::vectors::IndexedComponents::component(s.inner()?, idx)?)};
+        mut (MetaMethod::NewIndex) => |_,s,(idx,val): (usize,u32)| {Ok(s.val_mut(|s| crate::common::vectors::IndexedComponents::set_component(s, idx, val))??)};
+        (MetaMethod::Eq) => |_,s,other: LuaUVec2| {Ok(s.inner()? == other.inner()?)};
     }
 }
 impl_script_newtype! {
@@ -6620,8 +6775,9 @@ impl_script_newtype! {
     )
     lua impl
     {
-        (MetaMethod::Index) => |_,s,idx: usize| {Ok(s.inner()?[idx])};
-        mut (MetaMethod::NewIndex) => |_,s,(idx,val): (usize,u32)| {s.val_mut(|s| Ok(s[idx] = val))?};
+        (MetaMethod::Index) => |_,s,idx: usize| {Ok(crate::common::vectors::IndexedComponents::component(s.inner()?, idx)?)};
+        mut (MetaMethod::NewIndex) => |_,s,(idx,val): (usize,u32)| {Ok(s.val_mut(|s| crate::common::vectors::IndexedComponents::set_component(s, idx, val))??)};
+        (MetaMethod::Eq) => |_,s,other: LuaUVec3| {Ok(s.inner()? == other.inner()?)};
     }
 }
 impl_script_newtype! {
@@ -6767,8 +6923,9 @@ impl_script_newtype! {
     )
     lua impl
     {
-        (MetaMethod::Index) => |_,s,idx: usize| {Ok(s.inner()?[idx])};
-        mut (MetaMethod::NewIndex) => |_,s,(idx,val): (usize,u32)| {s.val_mut(|s| Ok(s[idx] = val))?};
+        (MetaMethod::Index) => |_,s,idx: usize| {Ok(crate::common::vectors::IndexedComponents::component(s.inner()?, idx)?)};
+        mut (MetaMethod::NewIndex) => |_,s,(idx,val): (usize,u32)| {Ok(s.val_mut(|s| crate::common::vectors::IndexedComponents::set_component(s, idx, val))??)};
+        (MetaMethod::Eq) => |_,s,other: LuaUVec4| {Ok(s.inner()? == other.inner()?)};
     }
 }
 impl_script_newtype! {
@@ -7012,6 +7169,7 @@ impl_script_newtype! {
         )
     }
 ;
+        (MetaMethod::Eq) => |_,s,other: LuaMat3| {Ok(s.inner()? == other.inner()?)};
     }
 }
 impl_script_newtype! {
@@ -7148,6 +7306,7 @@ impl_script_newtype! {
         )
     }
 ;
+        (MetaMethod::Eq) => |_,s,other: LuaMat2| {Ok(s.inner()? == other.inner()?)};
     }
 }
 impl_script_newtype! {
@@ -7391,6 +7550,7 @@ impl_script_newtype! {
         )
     }
 ;
+        (MetaMethod::Eq) => |_,s,other: LuaMat3A| {Ok(s.inner()? == other.inner()?)};
     }
 }
 impl_script_newtype! {
@@ -7776,6 +7936,7 @@ impl_script_newtype! {
         )
     }
 ;
+        (MetaMethod::Eq) => |_,s,other: LuaMat4| {Ok(s.inner()? == other.inner()?)};
     }
 }
 impl_script_newtype! {
@@ -7911,6 +8072,7 @@ impl_script_newtype! {
         )
     }
 ;
+        (MetaMethod::Eq) => |_,s,other: LuaDMat2| {Ok(s.inner()? == other.inner()?)};
     }
 }
 impl_script_newtype! {
@@ -8150,6 +8312,7 @@ impl_script_newtype! {
         )
     }
 ;
+        (MetaMethod::Eq) => |_,s,other: LuaDMat3| {Ok(s.inner()? == other.inner()?)};
     }
 }
 impl_script_newtype! {
@@ -8518,6 +8681,7 @@ impl_script_newtype! {
         )
     }
 ;
+        (MetaMethod::Eq) => |_,s,other: LuaDMat4| {Ok(s.inner()? == other.inner()?)};
     }
 }
 impl_script_newtype! {
@@ -8621,6 +8785,7 @@ impl_script_newtype! {
     )
     lua impl
     {
+        (MetaMethod::Eq) => |_,s,other: LuaAffine2| {Ok(s.inner()? == other.inner()?)};
     }
 }
 impl_script_newtype! {
@@ -8775,6 +8940,7 @@ impl_script_newtype! {
     )
     lua impl
     {
+        (MetaMethod::Eq) => |_,s,other: LuaAffine3A| {Ok(s.inner()? == other.inner()?)};
     }
 }
 impl_script_newtype! {
@@ -8874,6 +9040,7 @@ impl_script_newtype! {
     )
     lua impl
     {
+        (MetaMethod::Eq) => |_,s,other: LuaDAffine2| {Ok(s.inner()? == other.inner()?)};
     }
 }
 impl_script_newtype! {
@@ -9019,6 +9186,7 @@ impl_script_newtype! {
     )
     lua impl
     {
+        (MetaMethod::Eq) => |_,s,other: LuaDAffine3| {Ok(s.inner()? == other.inner()?)};
     }
 }
 impl_script_newtype! {
@@ -9284,6 +9452,7 @@ impl_script_newtype! {
     )
     lua impl
     {
+        (MetaMethod::Eq) => |_,s,other: LuaQuat| {Ok(s.inner()? == other.inner()?)};
     }
 }
 impl_script_newtype! {
@@ -9544,6 +9713,7 @@ impl_script_newtype! {
     )
     lua impl
     {
+        (MetaMethod::Eq) => |_,s,other: LuaDQuat| {Ok(s.inner()? == other.inner()?)};
     }
 }
 impl_script_newtype! {
@@ -9571,6 +9741,7 @@ impl_script_newtype! {
     )
     lua impl
     {
+        (MetaMethod::Eq) => |_,s,other: LuaEulerRot| {Ok(s.inner()? == other.inner()?)};
     }
 }
 impl_script_newtype! {
@@ -9812,6 +9983,11 @@ impl_script_newtype! {
     )
     lua impl
     {
+
+    /// Returns true if this rectangle overlaps the other rectangle
+    "overlaps" => |_,s,other: LuaRect| {
+        Ok(crate::common::bevy::bounds::rect_overlaps(&s.inner()?, &other.inner()?))
+    };
     }
 }
 #[cfg(feature = "lua")]
@@ -10208,6 +10384,7 @@ impl APIProvider for LuaBevyAPIProvider {
 			.process_type::<crate::lua::bevy::LuaScriptData>()
 			.process_type::<bevy_mod_scripting_lua::tealr::mlu::UserDataProxy<crate::lua::bevy::LuaScriptData>>()
 			.process_type::<crate::lua::bevy::LuaTypeRegistration>()
+			.process_type::<crate::lua::bevy::LuaQuery>()
 			.process_type::<crate::lua::std::LuaVec<T>>()
         }))
     }
@@ -10374,5 +10551,9 @@ impl APIProvider for LuaBevyAPIProvider {
         app.register_foreign_lua_type::<usize>();
         app.register_foreign_lua_type::<i128>();
         app.register_foreign_lua_type::<i8>();
+        // lets scripts quit the app with `world:send_event("AppExit", {})`
+        crate::common::bevy::RegisterScriptEvent::register_default_script_event::<bevy::app::AppExit>(
+            app,
+        );
     }
 }
@@ -19,19 +19,110 @@ pub mod prelude {
     pub use crate::{
         impl_lua_newtype,
         lua::{
-            bevy::LuaBevyAPIProvider, std::LuaVec, FromLuaProxy, LuaProxyable, ReflectLuaProxyable,
-            ToLuaProxy,
+            bevy::{
+                LuaBevyAPIProvider, LuaComponentEventsProvider, LuaDiagnosticsAPIProvider,
+                LuaEntitySweepProvider, LuaExportsAPIProvider, LuaSceneEventsProvider,
+                LuaSendEventsProvider, LuaSprite2dAPIProvider, LuaStateEventsProvider,
+                LuaTriggersAPIProvider, LuaTweenAPIProvider, LuaVfxAPIProvider,
+                LuaWatchEventsProvider,
+            },
+            buffer::{LuaBufferAPIProvider, LuaScriptBuffer, LuaSharedF32Buffer},
+            core_api::{lua_core_api, LuaCoreAPIProvider},
+            fmt::LuaFmtAPIProvider,
+            std::LuaVec,
+            testing::LuaTestAPIProvider,
+            FromLuaProxy, LuaProxyable, ReflectLuaProxyable, ToLuaProxy,
         },
     };
 
+    #[cfg(all(feature = "lua", feature = "api_assets"))]
+    pub use crate::lua::bevy::{LuaAssetEventsProvider, LuaAssetsAPIProvider};
+
+    #[cfg(all(feature = "lua", feature = "api_input"))]
+    pub use crate::lua::bevy::LuaInputAPIProvider;
+
+    #[cfg(all(feature = "lua", feature = "api_math"))]
+    pub use crate::lua::{
+        fixed::{LuaFixed, LuaFixedAPIProvider, LuaFixedVec2, LuaFixedVec3},
+        mathx::LuaMathxAPIProvider,
+    };
+
+    #[cfg(all(feature = "lua", feature = "api_time"))]
+    pub use crate::lua::{
+        bevy::{LuaTimeAPIProvider, LuaTimerAPIProvider},
+        instant::{LuaDuration, LuaInstant, LuaInstantAPIProvider},
+    };
+
     #[cfg(feature = "rhai")]
     pub use crate::rhai::{
-        bevy::RhaiBevyAPIProvider,
+        bevy::{
+            RhaiBevyAPIProvider, RhaiComponentEventsProvider, RhaiDiagnosticsAPIProvider,
+            RhaiEntitySweepProvider, RhaiExportsAPIProvider, RhaiSceneEventsProvider,
+            RhaiSendEventsProvider, RhaiSprite2dAPIProvider, RhaiStateEventsProvider,
+            RhaiTriggersAPIProvider, RhaiTweenAPIProvider, RhaiVfxAPIProvider,
+            RhaiWatchEventsProvider,
+        },
+        buffer::RhaiBufferAPIProvider,
+        core_api::{rhai_core_api, RhaiCoreAPIProvider},
+        fmt::RhaiFmtAPIProvider,
         std::{RhaiCopy, RhaiVec},
+        testing::RhaiTestAPIProvider,
         FromRhaiProxy, ReflectRhaiProxyable, RhaiProxyable, ToRhaiProxy,
     };
 
-    pub use crate::{common::bevy::GetWorld, impl_script_newtype, ValueIndex};
+    #[cfg(all(feature = "rhai", feature = "api_assets"))]
+    pub use crate::rhai::bevy::{RhaiAssetEventsProvider, RhaiAssetsAPIProvider};
+
+    #[cfg(all(feature = "rhai", feature = "api_input"))]
+    pub use crate::rhai::bevy::RhaiInputAPIProvider;
+
+    #[cfg(all(feature = "rhai", feature = "api_math"))]
+    pub use crate::rhai::{fixed::RhaiFixedAPIProvider, mathx::RhaiMathxAPIProvider};
+
+    #[cfg(all(feature = "rhai", feature = "api_time"))]
+    pub use crate::rhai::{
+        bevy::{RhaiTimeAPIProvider, RhaiTimerAPIProvider},
+        instant::RhaiInstantAPIProvider,
+    };
+
+    pub use crate::{
+        common::{
+            bevy::{
+                AddScriptVfxBackend, GetWorld, RegisterScriptEvent, RegisterScriptState,
+                ScriptComponentEvent, ScriptDiagnostics, ScriptFieldChanged, ScriptSceneSpawned,
+                ScriptScreenshotRequests, ScriptSentEvent, ScriptStateEvent, ScriptTriggerEvent,
+                ScriptTriggers, ScriptTweenFinished, ScriptTweens, ScriptVfx, ScriptVfxBackend,
+                ScriptableEvents, ScriptableStates, TriggerShape, TweenValue,
+            },
+            buffer::{BufferType, BufferValue, ScriptBuffer, SharedF32Buffer},
+            capabilities::ScriptCapabilities,
+            exports::{ScriptExport, ScriptExportChanged, ScriptExports},
+            fmt::{ScriptLocale, ScriptLocales},
+            mathx::Easing,
+            run_once::{QueueScriptOnce, RunOnceHost, RunScriptOnce},
+            shared::{ScriptValue, SharedScriptValues},
+            uniforms::{ScriptUniforms, MAX_SCRIPT_UNIFORMS},
+        },
+        impl_script_newtype, ValueIndex,
+    };
+
+    #[cfg(feature = "api_assets")]
+    pub use crate::common::bevy::{
+        RegisterScriptAsset, ScriptAssetLoaded, ScriptAssets, ScriptHandle, ScriptMeshes,
+        ScriptableAssets,
+    };
+
+    #[cfg(feature = "api_input")]
+    pub use crate::common::bevy::{InputState, ScriptInput};
+
+    #[cfg(feature = "api_math")]
+    pub use crate::common::fixed::{Fixed, FixedVec2, FixedVec3};
+
+    #[cfg(feature = "api_time")]
+    pub use crate::common::{
+        bevy::ScriptTime,
+        instant::{ScriptDuration, ScriptInstant},
+    };
 }
 
 // re-export derive macros from other langs
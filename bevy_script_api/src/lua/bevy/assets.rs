@@ -0,0 +1,245 @@
+use std::{marker::PhantomData, sync::Mutex};
+
+use bevy::{
+    asset::Asset,
+    prelude::{
+        App, ColorMaterial, EventReader, Font, Handle, Image, Mesh, Scene, StandardMaterial,
+        TextureAtlas,
+    },
+};
+use bevy_mod_scripting_core::prelude::*;
+use bevy_mod_scripting_lua::{
+    prelude::{LuaDocFragment, LuaEvent},
+    tealr, LuaArg,
+};
+
+use tealr::mlu::{
+    mlua::{self, Lua, MetaMethod, ToLua, Value},
+    TealData, TealDataMethods,
+};
+
+use crate::{
+    common::bevy::{
+        color_material_from_value, register_script_asset_loads, standard_material_from_value,
+        GetWorld, RegisterScriptAsset, ScriptAssetLoaded, ScriptAssets, ScriptHandle, ScriptMeshes,
+        ScriptWorld,
+    },
+    impl_tealr_type,
+    lua::{LuaProxyable, RegisterForeignLuaType},
+    script_ref::ScriptRef,
+};
+
+/// Forwards asset lifecycle events of assets loaded with `world:load_asset(path)` to the scripts which loaded them.
+///
+/// The `on_asset_loaded` hook is called once the asset is loaded and `on_asset_failed` if loading failed,
+/// with the arguments created from the [`ScriptAssetLoaded`] event. Hooks are sent with the priority given in `priority`.
+pub struct LuaAssetEventsProvider<A: LuaArg + From<ScriptAssetLoaded>> {
+    pub priority: u32,
+    _ph: PhantomData<fn() -> A>,
+}
+
+impl<A: LuaArg + From<ScriptAssetLoaded>> Default for LuaAssetEventsProvider<A> {
+    fn default() -> Self {
+        Self {
+            priority: 0,
+            _ph: Default::default(),
+        }
+    }
+}
+
+impl<A: LuaArg + From<ScriptAssetLoaded>> LuaAssetEventsProvider<A> {
+    pub fn new(priority: u32) -> Self {
+        Self {
+            priority,
+            _ph: Default::default(),
+        }
+    }
+}
+
+fn forward_script_asset_loads<A: LuaArg + From<ScriptAssetLoaded>>(
+    mut events: EventReader<ScriptAssetLoaded>,
+    mut writer: PriorityEventWriter<LuaEvent<A>>,
+    priority: u32,
+) {
+    for event in events.iter() {
+        writer.send(
+            LuaEvent {
+                hook_name: if event.success {
+                    "on_asset_loaded"
+                } else {
+                    "on_asset_failed"
+                }
+                .into(),
+                recipients: event.recipients.clone(),
+                args: event.clone().into(),
+            },
+            priority,
+        )
+    }
+}
+
+impl<A: LuaArg + From<ScriptAssetLoaded>> APIProvider for LuaAssetEventsProvider<A> {
+    type APITarget = Mutex<Lua>;
+    type ScriptContext = Mutex<Lua>;
+    type DocTarget = LuaDocFragment;
+
+    fn attach_api(&mut self, _: &mut Self::APITarget) -> Result<(), ScriptError> {
+        Ok(())
+    }
+
+    fn register_with_app(&self, app: &mut App) {
+        register_script_asset_loads(app);
+        let priority = self.priority;
+        app.add_system(
+            move |events: EventReader<ScriptAssetLoaded>,
+                  writer: PriorityEventWriter<LuaEvent<A>>| {
+                forward_script_asset_loads(events, writer, priority)
+            },
+        );
+    }
+}
+
+pub type LuaHandle = ScriptHandle;
+impl_tealr_type!(LuaHandle);
+
+impl TealData for LuaHandle {
+    fn add_methods<'lua, T: TealDataMethods<'lua, Self>>(methods: &mut T) {
+        methods.document_type("A handle to an asset, created with `assets.load(path)`.");
+        methods.document_type("It can be assigned to the `Handle` fields of components.");
+
+        methods.add_meta_method(MetaMethod::Eq, |_, s, rhs: LuaHandle| Ok(*s == rhs));
+        methods.add_meta_method(MetaMethod::ToString, |_, s, ()| Ok(format!("{:?}", s.id())));
+    }
+}
+
+impl<A: Asset> LuaProxyable for Handle<A> {
+    fn ref_to_lua(self_: ScriptRef, lua: &Lua) -> mlua::Result<Value> {
+        self_.get_typed(|s: &Self| LuaHandle::from(s).to_lua(lua))?
+    }
+
+    fn apply_lua<'lua>(
+        self_: &mut ScriptRef,
+        _: &'lua Lua,
+        new_val: Value<'lua>,
+    ) -> mlua::Result<()> {
+        if let Value::UserData(v) = new_val {
+            let handle = v.borrow::<LuaHandle>()?.typed::<A>();
+            self_.get_mut_typed(|s| *s = handle)?;
+            Ok(())
+        } else {
+            Err(mlua::Error::RuntimeError(
+                "Only asset handles can be assigned to `Handle` fields".to_owned(),
+            ))
+        }
+    }
+}
+
+/// Provides the `assets` global table, loading assets through the `AssetServer`:
+///
+/// - `assets.load(path)` starts loading the asset at the path relative to the assets folder and returns a handle to it, e.g. `assets.load("models/tree.gltf#Scene0")`
+/// - `assets.get_load_state(handle)` returns one of `"NotLoaded"`, `"Loading"`, `"Loaded"`, `"Failed"` or `"Unloaded"`
+/// - `assets.path(handle)` returns the path the asset was loaded from, or `nil` if it was not loaded from a path
+///
+/// The `mesh` global table creates meshes from primitive shapes and returns handles to them:
+///
+/// - `mesh.cube(size)` a cube with sides of the given length
+/// - `mesh.sphere(radius, subdivisions)` an icosphere, subdivided at most 80 times
+/// - `mesh.quad(width, height)` a rectangle in the XY plane facing +Z
+///
+/// It also registers bevy's `StandardMaterial` and `ColorMaterial` for `world:add_asset(type_name, table)`.
+///
+/// Handles can be assigned to the `Handle<T>` fields of components for bevy's images, meshes, materials, texture atlases, scenes and fonts,
+/// other asset types need to be registered with `app.register_foreign_lua_type::<Handle<MyAsset>>()`.
+pub struct LuaAssetsAPIProvider;
+
+fn assets(ctx: &Lua) -> mlua::Result<ScriptAssets> {
+    ctx.get_world()
+        .map(|world| ScriptAssets(ScriptWorld::new(world)))
+}
+
+fn meshes(ctx: &Lua) -> mlua::Result<ScriptMeshes> {
+    ctx.get_world()
+        .map(|world| ScriptMeshes(ScriptWorld::new(world)))
+}
+
+fn to_lua_error(e: ScriptError) -> mlua::Error {
+    mlua::Error::RuntimeError(e.to_string())
+}
+
+impl LuaAssetsAPIProvider {
+    fn assets_table(ctx: &Lua) -> mlua::Result<mlua::Table> {
+        let table = ctx.create_table()?;
+        table.set(
+            "load",
+            ctx.create_function(|ctx, path: String| {
+                assets(ctx)?.load(&path).map_err(to_lua_error)
+            })?,
+        )?;
+        table.set(
+            "get_load_state",
+            ctx.create_function(|ctx, handle: LuaHandle| {
+                assets(ctx)?.load_state(&handle).map_err(to_lua_error)
+            })?,
+        )?;
+        table.set(
+            "path",
+            ctx.create_function(|ctx, handle: LuaHandle| {
+                assets(ctx)?.path(&handle).map_err(to_lua_error)
+            })?,
+        )?;
+        Ok(table)
+    }
+
+    fn mesh_table(ctx: &Lua) -> mlua::Result<mlua::Table> {
+        let table = ctx.create_table()?;
+        table.set(
+            "cube",
+            ctx.create_function(|ctx, size: f32| meshes(ctx)?.cube(size).map_err(to_lua_error))?,
+        )?;
+        table.set(
+            "sphere",
+            ctx.create_function(|ctx, (radius, subdivisions): (f32, usize)| {
+                meshes(ctx)?
+                    .sphere(radius, subdivisions)
+                    .map_err(to_lua_error)
+            })?,
+        )?;
+        table.set(
+            "quad",
+            ctx.create_function(|ctx, (width, height): (f32, f32)| {
+                meshes(ctx)?.quad(width, height).map_err(to_lua_error)
+            })?,
+        )?;
+        Ok(table)
+    }
+}
+
+impl APIProvider for LuaAssetsAPIProvider {
+    type APITarget = Mutex<Lua>;
+    type ScriptContext = Mutex<Lua>;
+    type DocTarget = LuaDocFragment;
+
+    fn attach_api(&mut self, ctx: &mut Self::APITarget) -> Result<(), ScriptError> {
+        let ctx = ctx
+            .get_mut()
+            .expect("Unable to acquire lock on Lua context");
+
+        Self::assets_table(ctx)
+            .and_then(|assets| ctx.globals().set("assets", assets))
+            .and_then(|_| Self::mesh_table(ctx))
+            .and_then(|mesh| ctx.globals().set("mesh", mesh))
+            .map_err(ScriptError::new_other)
+    }
+
+    fn register_with_app(&self, app: &mut App) {
+        app.register_foreign_lua_type::<Handle<Image>>()
+            .register_foreign_lua_type::<Handle<Mesh>>()
+            .register_foreign_lua_type::<Handle<StandardMaterial>>()
+            .register_foreign_lua_type::<Handle<ColorMaterial>>()
+            .register_foreign_lua_type::<Handle<TextureAtlas>>()
+            .register_foreign_lua_type::<Handle<Scene>>()
+            .register_foreign_lua_type::<Handle<Font>>()
+            .register_script_asset_with(standard_material_from_value)
+            .register_script_asset_with(color_material_from_value);
+    }
+}
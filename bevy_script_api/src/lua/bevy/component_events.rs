@@ -0,0 +1,91 @@
+use std::{marker::PhantomData, sync::Mutex};
+
+use bevy::prelude::{App, Component, EventReader};
+use bevy_mod_scripting_core::prelude::*;
+use bevy_mod_scripting_lua::{
+    prelude::{LuaDocFragment, LuaEvent},
+    tealr, LuaArg,
+};
+
+use tealr::mlu::mlua::Lua;
+
+use crate::common::bevy::{register_script_component_events, ScriptComponentEvent};
+
+/// Forwards additions and removals of components of the types registered with [`Self::with_component`] to all scripts.
+///
+/// The `on_component_added` and `on_component_removed` hooks are called with the arguments created from the
+/// [`ScriptComponentEvent`] event, usually the short type name of the component and the entity.
+/// Hooks are sent with the priority given in `priority`.
+pub struct LuaComponentEventsProvider<A: LuaArg + From<ScriptComponentEvent>> {
+    pub priority: u32,
+    registrations: Vec<fn(&mut App)>,
+    _ph: PhantomData<fn() -> A>,
+}
+
+impl<A: LuaArg + From<ScriptComponentEvent>> Default for LuaComponentEventsProvider<A> {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl<A: LuaArg + From<ScriptComponentEvent>> LuaComponentEventsProvider<A> {
+    pub fn new(priority: u32) -> Self {
+        Self {
+            priority,
+            registrations: Vec::default(),
+            _ph: Default::default(),
+        }
+    }
+
+    /// Forwards additions and removals of components of type `T`
+    pub fn with_component<T: Component>(mut self) -> Self {
+        self.registrations
+            .push(register_script_component_events::<T>);
+        self
+    }
+}
+
+fn forward_script_component_events<A: LuaArg + From<ScriptComponentEvent>>(
+    mut events: EventReader<ScriptComponentEvent>,
+    mut writer: PriorityEventWriter<LuaEvent<A>>,
+    priority: u32,
+) {
+    for event in events.iter() {
+        writer.send(
+            LuaEvent {
+                hook_name: if event.added {
+                    "on_component_added"
+                } else {
+                    "on_component_removed"
+                }
+                .into(),
+                recipients: Recipients::All,
+                args: event.clone().into(),
+            },
+            priority,
+        )
+    }
+}
+
+impl<A: LuaArg + From<ScriptComponentEvent>> APIProvider for LuaComponentEventsProvider<A> {
+    type APITarget = Mutex<Lua>;
+    type ScriptContext = Mutex<Lua>;
+    type DocTarget = LuaDocFragment;
+
+    fn attach_api(&mut self, _: &mut Self::APITarget) -> Result<(), ScriptError> {
+        Ok(())
+    }
+
+    fn register_with_app(&self, app: &mut App) {
+        for register in &self.registrations {
+            register(app);
+        }
+        let priority = self.priority;
+        app.add_system(
+            move |events: EventReader<ScriptComponentEvent>,
+                  writer: PriorityEventWriter<LuaEvent<A>>| {
+                forward_script_component_events(events, writer, priority)
+            },
+        );
+    }
+}
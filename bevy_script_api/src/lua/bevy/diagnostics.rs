@@ -0,0 +1,68 @@
+use std::sync::Mutex;
+
+use bevy_mod_scripting_core::prelude::*;
+use bevy_mod_scripting_lua::{prelude::LuaDocFragment, tealr};
+
+use tealr::mlu::mlua::{self, Lua};
+
+use crate::common::bevy::{GetWorld, ScriptDiagnostics, ScriptWorld};
+
+/// Provides the `diagnostics` global table, reading the diagnostics of the app such as the frame rate:
+///
+/// - `diagnostics.get(name)` returns the smoothed value of the named diagnostic, e.g. `"fps"` or `"frame_time"`, or `nil` if there is none
+/// - `diagnostics.value(name)` and `diagnostics.average(name)` return its latest measurement or average instead
+/// - `diagnostics.names()` returns the names of all registered diagnostics
+/// - `diagnostics.entity_count()` returns the number of entities in the world
+///
+/// See [`ScriptDiagnostics`] for which bevy plugins provide which diagnostics.
+pub struct LuaDiagnosticsAPIProvider;
+
+fn diagnostics(ctx: &Lua) -> mlua::Result<ScriptDiagnostics> {
+    ctx.get_world()
+        .map(|world| ScriptDiagnostics(ScriptWorld::new(world)))
+}
+
+impl LuaDiagnosticsAPIProvider {
+    fn diagnostics_table(ctx: &Lua) -> mlua::Result<mlua::Table> {
+        let table = ctx.create_table()?;
+
+        table.set(
+            "get",
+            ctx.create_function(|ctx, name: String| Ok(diagnostics(ctx)?.get(&name)))?,
+        )?;
+        table.set(
+            "value",
+            ctx.create_function(|ctx, name: String| Ok(diagnostics(ctx)?.value(&name)))?,
+        )?;
+        table.set(
+            "average",
+            ctx.create_function(|ctx, name: String| Ok(diagnostics(ctx)?.average(&name)))?,
+        )?;
+        table.set(
+            "names",
+            ctx.create_function(|ctx, ()| Ok(diagnostics(ctx)?.names()))?,
+        )?;
+        table.set(
+            "entity_count",
+            ctx.create_function(|ctx, ()| Ok(diagnostics(ctx)?.entity_count()))?,
+        )?;
+
+        Ok(table)
+    }
+}
+
+impl APIProvider for LuaDiagnosticsAPIProvider {
+    type APITarget = Mutex<Lua>;
+    type ScriptContext = Mutex<Lua>;
+    type DocTarget = LuaDocFragment;
+
+    fn attach_api(&mut self, ctx: &mut Self::APITarget) -> Result<(), ScriptError> {
+        let ctx = ctx
+            .get_mut()
+            .expect("Unable to acquire lock on Lua context");
+
+        Self::diagnostics_table(ctx)
+            .and_then(|diagnostics| ctx.globals().set("diagnostics", diagnostics))
+            .map_err(ScriptError::new_other)
+    }
+}
@@ -0,0 +1,109 @@
+use ::std::sync::Mutex;
+
+use bevy::prelude::App;
+use bevy_mod_scripting_core::prelude::*;
+use bevy_mod_scripting_lua::{prelude::LuaDocFragment, tealr};
+
+use tealr::mlu::mlua::{self, Lua, Table};
+
+use crate::common::{
+    bevy::{GetWorld, ScriptWorld},
+    exports::register_script_exports,
+    shared::ScriptValue,
+};
+
+/// Provides the `exports` global table, sharing named APIs between scripts, see [`crate::common::exports::ScriptExports`]:
+///
+/// - `exports.register(name, value)` exports a table of data under the given name, owned by this script
+/// - `exports.get(name)` returns a copy of the export, or `nil` if it was not registered yet
+/// - `exports.version(name)` returns a number which changes whenever the export is registered again, 0 if it's not registered
+pub struct LuaExportsAPIProvider;
+
+/// The script a Lua context belongs to
+struct ExportingScript {
+    sid: ScriptId,
+    name: String,
+}
+
+fn world(ctx: &Lua) -> mlua::Result<ScriptWorld> {
+    ctx.get_world().map(ScriptWorld::new)
+}
+
+fn to_lua_error(e: ScriptError) -> mlua::Error {
+    mlua::Error::RuntimeError(e.to_string())
+}
+
+fn with_script<O>(ctx: &Lua, f: impl FnOnce(&ExportingScript) -> O) -> mlua::Result<O> {
+    ctx.app_data_ref::<ExportingScript>()
+        .map(|script| f(&script))
+        .ok_or_else(|| {
+            mlua::Error::RuntimeError("Exports are not set up for this script".to_owned())
+        })
+}
+
+impl LuaExportsAPIProvider {
+    fn exports_table(ctx: &Lua) -> mlua::Result<Table> {
+        let table = ctx.create_table()?;
+
+        table.set(
+            "register",
+            ctx.create_function(|ctx, (name, value): (String, ScriptValue)| {
+                let world = world(ctx)?;
+                with_script(ctx, |script| {
+                    world.register_export(&name, script.sid, &script.name, value)
+                })?
+                .map_err(to_lua_error)
+            })?,
+        )?;
+        table.set(
+            "get",
+            ctx.create_function(|ctx, name: String| {
+                let world = world(ctx)?;
+                with_script(ctx, |script| world.import_export(&name, script.sid))?
+                    .map_err(to_lua_error)
+            })?,
+        )?;
+        table.set(
+            "version",
+            ctx.create_function(|ctx, name: String| {
+                world(ctx)?.export_version(&name).map_err(to_lua_error)
+            })?,
+        )?;
+
+        Ok(table)
+    }
+}
+
+impl APIProvider for LuaExportsAPIProvider {
+    type APITarget = Mutex<Lua>;
+    type ScriptContext = Mutex<Lua>;
+    type DocTarget = LuaDocFragment;
+
+    fn attach_api(&mut self, ctx: &mut Self::APITarget) -> Result<(), ScriptError> {
+        let ctx = ctx
+            .get_mut()
+            .expect("Unable to acquire lock on Lua context");
+
+        Self::exports_table(ctx)
+            .and_then(|exports| ctx.globals().set("exports", exports))
+            .map_err(ScriptError::new_other)
+    }
+
+    fn setup_script(
+        &mut self,
+        script_data: &ScriptData,
+        ctx: &mut Self::ScriptContext,
+    ) -> Result<(), ScriptError> {
+        ctx.get_mut()
+            .expect("Unable to acquire lock on Lua context")
+            .set_app_data(ExportingScript {
+                sid: script_data.sid,
+                name: script_data.name.to_owned(),
+            });
+        Ok(())
+    }
+
+    fn register_with_app(&self, app: &mut App) {
+        register_script_exports(app);
+    }
+}
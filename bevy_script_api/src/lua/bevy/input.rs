@@ -0,0 +1,101 @@
+use std::sync::Mutex;
+
+use bevy::prelude::App;
+use bevy_mod_scripting_core::prelude::*;
+use bevy_mod_scripting_lua::{prelude::LuaDocFragment, tealr};
+
+use tealr::mlu::mlua::{self, Lua};
+
+use crate::common::bevy::{register_script_input, GetWorld, InputState, ScriptInput, ScriptWorld};
+
+use super::LuaVec2;
+
+/// Provides the `input` global table, reading the keyboard, mouse and gamepads:
+///
+/// - `input.pressed(key)`, `input.just_pressed(key)` and `input.just_released(key)` check keys, e.g. `input.pressed("Space")`
+/// - `input.mouse_pressed(button)`, `input.mouse_just_pressed(button)` and `input.mouse_just_released(button)` check mouse buttons, e.g. `"Left"`
+/// - `input.mouse_position()` returns the cursor position in the primary window as a `Vec2`, or `nil` if the cursor is outside of it
+/// - `input.mouse_delta()` returns the distance the mouse moved this frame as a `Vec2`
+/// - `input.gamepads()` returns the IDs of the connected gamepads
+/// - `input.gamepad_axis(id, axis)` returns the position of a gamepad axis between -1 and 1, e.g. `"LeftStickX"`
+/// - `input.gamepad_pressed(id, button)`, `input.gamepad_just_pressed(id, button)` and `input.gamepad_just_released(id, button)` check gamepad buttons, e.g. `"South"`
+///
+/// See [`ScriptInput`] for how keys and buttons are named.
+pub struct LuaInputAPIProvider;
+
+fn input(ctx: &Lua) -> mlua::Result<ScriptInput> {
+    ctx.get_world()
+        .map(|world| ScriptInput(ScriptWorld::new(world)))
+}
+
+impl LuaInputAPIProvider {
+    fn input_table(ctx: &Lua) -> mlua::Result<mlua::Table> {
+        let table = ctx.create_table()?;
+
+        for (suffix, state) in [
+            ("pressed", InputState::Pressed),
+            ("just_pressed", InputState::JustPressed),
+            ("just_released", InputState::JustReleased),
+        ] {
+            table.set(
+                suffix,
+                ctx.create_function(move |ctx, key: String| Ok(input(ctx)?.key(state, &key)))?,
+            )?;
+            table.set(
+                format!("mouse_{suffix}"),
+                ctx.create_function(move |ctx, button: String| {
+                    Ok(input(ctx)?.mouse_button(state, &button))
+                })?,
+            )?;
+            table.set(
+                format!("gamepad_{suffix}"),
+                ctx.create_function(move |ctx, (gamepad, button): (usize, String)| {
+                    Ok(input(ctx)?.gamepad_button(state, gamepad, &button))
+                })?,
+            )?;
+        }
+
+        table.set(
+            "mouse_position",
+            ctx.create_function(|ctx, ()| Ok(input(ctx)?.mouse_position().map(LuaVec2::new)))?,
+        )?;
+        table.set(
+            "mouse_delta",
+            ctx.create_function(|ctx, ()| Ok(LuaVec2::new(input(ctx)?.mouse_delta())))?,
+        )?;
+        table.set(
+            "gamepads",
+            ctx.create_function(|ctx, ()| Ok(input(ctx)?.gamepads()))?,
+        )?;
+        table.set(
+            "gamepad_axis",
+            ctx.create_function(|ctx, (gamepad, axis): (usize, String)| {
+                input(ctx)?
+                    .gamepad_axis(gamepad, &axis)
+                    .map_err(|e| mlua::Error::RuntimeError(e.to_string()))
+            })?,
+        )?;
+
+        Ok(table)
+    }
+}
+
+impl APIProvider for LuaInputAPIProvider {
+    type APITarget = Mutex<Lua>;
+    type ScriptContext = Mutex<Lua>;
+    type DocTarget = LuaDocFragment;
+
+    fn attach_api(&mut self, ctx: &mut Self::APITarget) -> Result<(), ScriptError> {
+        let ctx = ctx
+            .get_mut()
+            .expect("Unable to acquire lock on Lua context");
+
+        Self::input_table(ctx)
+            .and_then(|input| ctx.globals().set("input", input))
+            .map_err(ScriptError::new_other)
+    }
+
+    fn register_with_app(&self, app: &mut App) {
+        register_script_input(app);
+    }
+}
@@ -1,23 +1,72 @@
-use crate::common::bevy::{ScriptTypeRegistration, ScriptWorld};
+use crate::common::{
+    bevy::{GetWorld, ScriptQuery, ScriptTypeRegistration, ScriptWorld},
+    shared::ScriptValue,
+};
 use crate::impl_tealr_type;
 
-use std::sync::Arc;
+use std::collections::HashSet;
+use std::sync::Mutex;
 
-use bevy::ecs::system::Command;
+#[cfg(feature = "api_hierarchy")]
 use bevy::hierarchy::BuildWorldChildren;
-use bevy::prelude::AppTypeRegistry;
+use bevy::prelude::Vec4;
 
-use bevy::{hierarchy::DespawnRecursive, prelude::ReflectResource};
-use bevy_mod_scripting_core::prelude::*;
-use bevy_mod_scripting_lua::tealr;
+use bevy_mod_scripting_core::{prelude::*, world::WorldPointer};
+use bevy_mod_scripting_lua::{
+    prelude::{LuaDocFragment, LuaFile},
+    tealr,
+};
 
 use tealr::mlu::{
-    mlua::{self},
-    TealData, TealDataMethods,
+    mlua::{self, Lua, MetaMethod, Table, ToLua, Value},
+    TealData, TealDataMethods, TypedFunction,
 };
 
+#[cfg(feature = "api_reflection")]
+use tealr::mlu::mlua::Variadic;
+
 pub use crate::generated::*;
 
+#[cfg(feature = "api_assets")]
+mod assets;
+mod component_events;
+mod diagnostics;
+mod exports;
+#[cfg(feature = "api_input")]
+mod input;
+mod scene;
+mod sent_events;
+mod sprite;
+mod states;
+#[cfg(feature = "api_time")]
+mod time;
+#[cfg(feature = "api_time")]
+mod timer;
+mod triggers;
+mod tween;
+pub(crate) mod vectors;
+mod vfx;
+mod watch;
+#[cfg(feature = "api_assets")]
+pub use assets::{LuaAssetEventsProvider, LuaAssetsAPIProvider, LuaHandle};
+pub use component_events::LuaComponentEventsProvider;
+pub use diagnostics::LuaDiagnosticsAPIProvider;
+pub use exports::LuaExportsAPIProvider;
+#[cfg(feature = "api_input")]
+pub use input::LuaInputAPIProvider;
+pub use scene::LuaSceneEventsProvider;
+pub use sent_events::LuaSendEventsProvider;
+pub use sprite::LuaSprite2dAPIProvider;
+pub use states::LuaStateEventsProvider;
+#[cfg(feature = "api_time")]
+pub use time::LuaTimeAPIProvider;
+#[cfg(feature = "api_time")]
+pub use timer::{LuaStopwatch, LuaTimer, LuaTimerAPIProvider};
+pub use triggers::LuaTriggersAPIProvider;
+pub use tween::LuaTweenAPIProvider;
+pub use vfx::LuaVfxAPIProvider;
+pub use watch::LuaWatchEventsProvider;
+
 pub type LuaTypeRegistration = ScriptTypeRegistration;
 impl_tealr_type!(LuaTypeRegistration);
 
@@ -36,9 +85,83 @@ impl TealData for LuaTypeRegistration {
     }
 }
 
+pub type LuaQuery = ScriptQuery;
+impl_tealr_type!(LuaQuery);
+
+impl TealData for LuaQuery {
+    fn add_methods<'lua, T: TealDataMethods<'lua, Self>>(methods: &mut T) {
+        methods.document_type("A cached query for all entities with a set of components.");
+        methods.document_type("Can be obtained via [`LuaWorld::cached_query`], results are only recomputed once the world changed.");
+
+        methods.document("Returns an iterator over the matching entities and a table of their queried components keyed by short type name.");
+        methods.document("```lua");
+        methods.document("for entity, c in q:iter() do c.Transform.translation.x = 0 end");
+        methods.document("```");
+        methods.document("The query runs against the world of the script iterating it, so it can be kept around between callbacks.");
+        methods.add_method("iter", |ctx, query, ()| {
+            let entities = query
+                .entities(&ScriptWorld::new(ctx.get_world()?))
+                .map_err(|e| mlua::Error::RuntimeError(e.to_string()))?;
+            let names = query
+                .components()
+                .map(|t| t.short_name().to_owned())
+                .collect::<Vec<_>>();
+            let query = query.clone();
+            let mut curr_idx = 0;
+            TypedFunction::from_rust_mut(
+                move |ctx, ()| {
+                    let o = match entities.get(curr_idx) {
+                        Some(entity) => {
+                            let world = ScriptWorld::new(ctx.get_world()?);
+                            let components = ctx.create_table()?;
+                            for (idx, name) in names.iter().enumerate() {
+                                components.set(
+                                    name.as_str(),
+                                    query.component_ref(&world, *entity, idx),
+                                )?;
+                            }
+                            (
+                                LuaEntity::new(*entity).to_lua(ctx)?,
+                                Value::Table(components),
+                            )
+                        }
+                        None => (Value::Nil, Value::Nil),
+                    };
+                    curr_idx += 1;
+                    Ok(o)
+                },
+                ctx,
+            )
+        });
+
+        methods.document("Returns the matching entities.");
+        methods.add_method("entities", |ctx, query, ()| {
+            query
+                .entities(&ScriptWorld::new(ctx.get_world()?))
+                .map(|entities| entities.into_iter().map(LuaEntity::new).collect::<Vec<_>>())
+                .map_err(|e| mlua::Error::RuntimeError(e.to_string()))
+        });
+
+        methods.document("Forces the matching entities to be collected again on the next access.");
+        methods.document("Only necessary if entities changed their components this frame without the number of matching entities changing.");
+        methods.add_method("invalidate", |_, query, ()| {
+            query.invalidate();
+            Ok(())
+        });
+
+        methods.add_meta_method(MetaMethod::Len, |ctx, query, ()| {
+            Ok(query.len(&ScriptWorld::new(ctx.get_world()?)))
+        });
+
+        methods.add_meta_method(MetaMethod::ToString, |_, query, ()| {
+            Ok(format!("{:?}", query))
+        });
+    }
+}
+
 #[derive(Debug)]
 pub struct LuaScriptData {
-    sid: u32,
+    sid: ScriptId,
 }
 
 impl From<&ScriptData<'_>> for LuaScriptData {
@@ -59,6 +182,29 @@ impl TealData for LuaScriptData {
         methods.add_meta_method(tealr::mlu::mlua::MetaMethod::ToString, |_, s, ()| {
             Ok(format!("{:?}", s))
         });
+
+        methods.document("Joins the given script group, events sent to the group are handled by this script from now on.");
+        methods.document("Returns false if this script already was a member.");
+        methods.add_method("join_group", |ctx, s, group: String| {
+            Ok(ScriptWorld::new(ctx.get_world()?).join_group(s.sid, &group))
+        });
+
+        methods.document(
+            "Leaves the given script group, returns false if this script was not a member.",
+        );
+        methods.add_method("leave_group", |ctx, s, group: String| {
+            Ok(ScriptWorld::new(ctx.get_world()?).leave_group(s.sid, &group))
+        });
+
+        methods.document("Returns true if this script is a member of the given script group.");
+        methods.add_method("in_group", |ctx, s, group: String| {
+            Ok(ScriptWorld::new(ctx.get_world()?).in_group(s.sid, &group))
+        });
+
+        methods.document("Returns the names of all script groups this script is a member of.");
+        methods.add_method("groups", |ctx, s, ()| {
+            Ok(ScriptWorld::new(ctx.get_world()?).script_groups(s.sid))
+        });
     }
 }
 
@@ -66,6 +212,22 @@ pub type LuaWorld = ScriptWorld;
 
 impl_tealr_type!(LuaWorld);
 
+/// Looks up the component types of a query by short or fully qualified type name
+#[cfg(feature = "api_reflection")]
+fn component_types(
+    world: &ScriptWorld,
+    type_names: &[String],
+) -> mlua::Result<Vec<ScriptTypeRegistration>> {
+    type_names
+        .iter()
+        .map(|name| {
+            world.get_type_by_name(name).ok_or_else(|| {
+                mlua::Error::RuntimeError(format!("No type named `{name}` is registered"))
+            })
+        })
+        .collect()
+}
+
 impl TealData for LuaWorld {
     fn add_methods<'lua, T: TealDataMethods<'lua, Self>>(methods: &mut T) {
         methods.document_type("Represents the bevy world all scripts live in.");
@@ -75,230 +237,706 @@ impl TealData for LuaWorld {
             Ok(format!("{s:?}"))
         });
 
-        methods.document("Retrieves type information given either a short (`MyType`) or fully qualified rust type name (`MyModule::MyType`).");
-        methods.document(
-            "Returns `nil` if no such type exists or if one wasn't registered on the rust side.",
+        #[cfg(feature = "api_reflection")]
+        {
+            methods.document("Retrieves type information given either a short (`MyType`) or fully qualified rust type name (`MyModule::MyType`).");
+            methods.document(
+                "Returns `nil` if no such type exists or if one wasn't registered on the rust side.",
+            );
+            methods.document("\n");
+            methods.document("This is used extensively in [`LuaWorld`]");
+            methods.add_method("get_type_by_name", |_, world, type_name: String| {
+                Ok(world.get_type_by_name(&type_name))
+            });
+
+            methods.document("Inserts a component of the given type to the given entity by instantiating a default version of it.");
+            methods.document("The component can then be modified using field access.");
+            methods.add_method(
+                "add_default_component",
+                |_, world, (entity, comp_type): (LuaEntity, LuaTypeRegistration)| {
+                    world
+                        .add_default_component(entity.inner()?, comp_type)
+                        .map_err(|e| mlua::Error::RuntimeError(e.to_string()))
+                },
+            );
+
+            methods.document("Retrieves a component of the given type from the given entity.");
+            methods.document("If such a component does not exist returns `nil`.");
+            methods.add_method(
+                "get_component",
+                |_, world, (entity, comp_type): (LuaEntity, LuaTypeRegistration)| {
+                    world
+                        .get_component(entity.inner()?, comp_type)
+                        .map_err(|e| mlua::Error::RuntimeError(e.to_string()))
+                },
+            );
+
+            methods.document("Creates a cached query for all entities with components of the given types, given by short or fully qualified type name.");
+            methods.document("Keep the query around (e.g. in a global) and iterate it with `q:iter()`, the matching entities are cached between calls.");
+            methods.add_method("cached_query", |_, world, type_names: Vec<String>| {
+                world
+                    .cached_query(component_types(world, &type_names)?)
+                    .map_err(|e| mlua::Error::RuntimeError(e.to_string()))
+            });
+
+            methods.document("Returns an iterator over all entities with components of the given types, given by short or fully qualified type name.");
+            methods.document("Each step returns the entity followed by a reference to each of its queried components, in the order the types were given.");
+            methods.document("```lua");
+            methods.document("for entity, transform, health in world:query(\"Transform\", \"Health\") do health.hp = health.hp - 1 end");
+            methods.document("```");
+            methods.document("Entities are collected once the loop starts, those despawned during the loop are skipped.");
+            methods.document("Prefer `world:cached_query` for queries run every frame.");
+            methods.add_method("query", |ctx, world, type_names: Variadic<String>| {
+                let query = world
+                    .cached_query(component_types(world, &type_names)?)
+                    .map_err(|e| mlua::Error::RuntimeError(e.to_string()))?;
+                let mut entities = query
+                    .entities(world)
+                    .map_err(|e| mlua::Error::RuntimeError(e.to_string()))?
+                    .into_iter();
+                let component_count = type_names.len();
+                TypedFunction::from_rust_mut(
+                    move |ctx, ()| {
+                        // the iterator may outlive the callback it was created in
+                        let world = ScriptWorld::new(ctx.get_world()?);
+                        let mut values = Variadic::new();
+                        if let Some(entity) = entities.find(|e| world.is_entity_valid(*e)) {
+                            values.push(LuaEntity::new(entity).to_lua(ctx)?);
+                            for idx in 0..component_count {
+                                values.push(query.component_ref(&world, entity, idx).to_lua(ctx)?);
+                            }
+                        }
+                        Ok(values)
+                    },
+                    ctx,
+                )
+            });
+        }
+
+        methods.document("Watches the value at the given path on the given entity, made up of a component type name and a field path (e.g. `Health.hp`).");
+        methods.document("Whenever the value changes the given hook of this script is called, returns the ID of the watch.");
+        methods.document("Requires the `LuaWatchEventsProvider` API provider.");
+        methods.add_method(
+            "watch",
+            |ctx, world, (entity, path, hook): (LuaEntity, String, String)| {
+                let sid = ctx
+                    .globals()
+                    .get::<_, mlua::AnyUserData>("script")?
+                    .borrow::<LuaScriptData>()?
+                    .sid;
+                world
+                    .watch(entity.inner()?, &path, &hook, Recipients::Id(sid))
+                    .map_err(|e| mlua::Error::RuntimeError(e.to_string()))
+            },
         );
-        methods.document("\n");
-        methods.document("This is used extensively in [`LuaWorld`]");
-        methods.add_method("get_type_by_name", |_, world, type_name: String| {
-            let w = world.read();
 
-            let registry: &AppTypeRegistry = w.get_resource().unwrap();
+        methods.document(
+            "Stops the watch with the given ID, returns `false` if no such watch exists.",
+        );
+        methods.add_method("unwatch", |_, world, watch: u32| Ok(world.unwatch(watch)));
+
+        #[cfg(feature = "api_reflection")]
+        {
+            methods.document(
+                "Returns `true` if the given entity contains a component of the given type.",
+            );
+            methods.add_method(
+                "has_component",
+                |_, world, (entity, comp_type): (LuaEntity, LuaTypeRegistration)| {
+                    world
+                        .has_component(entity.inner()?, comp_type)
+                        .map_err(|e| mlua::Error::RuntimeError(e.to_string()))
+                },
+            );
+
+            methods.document("Removes the given component from the given entity, returns true if the entity had such a component.");
+            methods.add_method_mut(
+                "remove_component",
+                |_, world, (entity, comp_type): (LuaEntity, LuaTypeRegistration)| {
+                    world
+                        .remove_component(entity.inner()?, comp_type)
+                        .map_err(|e| mlua::Error::RuntimeError(e.to_string()))
+                },
+            );
+
+            methods.document("Retrieves a resource of the given type from the world.");
+            methods.document("If such a resource does not exist returns `nil`.");
+            methods.add_method("get_resource", |_, world, res_type: LuaTypeRegistration| {
+                world
+                    .get_resource(res_type)
+                    .map_err(|e| mlua::Error::RuntimeError(e.to_string()))
+            });
+
+            methods.document(
+                "Removes the given resource from the world, returns true if such a resource existed.",
+            );
+            methods.add_method_mut(
+                "remove_resource",
+                |_, world, res_type: LuaTypeRegistration| {
+                    world
+                        .remove_resource(res_type)
+                        .map_err(|e| mlua::Error::RuntimeError(e.to_string()))
+                },
+            );
+
+            methods.document("Returns `true` if the world contains a resource of the given type.");
+            methods.add_method("has_resource", |_, world, res_type: LuaTypeRegistration| {
+                world
+                    .has_resource(res_type)
+                    .map_err(|e| mlua::Error::RuntimeError(e.to_string()))
+            });
+        }
 
-            let registry = registry.read();
+        methods.document("Returns `true` if the given entity still exists in the world.");
+        methods.add_method("is_entity_valid", |_, world, entity: LuaEntity| {
+            Ok(world.is_entity_valid(entity.inner()?))
+        });
 
-            Ok(registry
-                .get_with_short_name(&type_name)
-                .or_else(|| registry.get_with_name(&type_name))
-                .map(|registration| LuaTypeRegistration::new(Arc::new(registration.clone()))))
+        methods.document("Retrieves a copy of the value stored under the given key, which is visible to the scripts of all languages.");
+        methods.document("Returns `nil` if no such value exists.");
+        methods.add_method("get_shared", |_, world, key: String| {
+            Ok(world.get_shared(&key))
         });
 
-        methods.document("Inserts a component of the given type to the given entity by instantiating a default version of it.");
-        methods.document("The component can then be modified using field access.");
+        methods.document("Stores a copy of the given value under the given key, making it visible to the scripts of all languages.");
+        methods.document("Only `nil`, booleans, numbers, strings and tables of these can be shared, setting a key to `nil` removes it.");
         methods.add_method(
-            "add_default_component",
-            |_, world, (entity, comp_type): (LuaEntity, LuaTypeRegistration)| {
-                world
-                    .add_default_component(entity.inner()?, comp_type)
-                    .map_err(|e| mlua::Error::RuntimeError(e.to_string()))
+            "set_shared",
+            |_, world, (key, value): (String, ScriptValue)| {
+                world.set_shared(key, value);
+                Ok(())
             },
         );
 
-        methods.document("Retrieves a component of the given type from the given entity.");
-        methods.document("If such a component does not exist returns `nil`.");
+        methods.document("Sends a Bevy event of the type with the given name, built from the given table, e.g. `world:send_event(\"AppExit\", {})`.");
+        methods.document("The event type must have been registered by the app with `register_script_event` or `register_default_script_event`.");
         methods.add_method(
-            "get_component",
-            |_, world, (entity, comp_type): (LuaEntity, LuaTypeRegistration)| {
+            "send_event",
+            |_, world, (event, value): (String, ScriptValue)| {
                 world
-                    .get_component(entity.inner()?, comp_type)
+                    .send_event(&event, &value)
                     .map_err(|e| mlua::Error::RuntimeError(e.to_string()))
             },
         );
 
-        methods
-            .document("Returns `true` if the given entity contains a component of the given type.");
+        methods.document("Returns the name of the current state, e.g. `\"InGame\"`, of the state type with the given name or of the state type registered first.");
+        methods.document(
+            "The state type must have been registered by the app with `register_script_state`.",
+        );
+        methods.add_method("current_state", |_, world, state_type: Option<String>| {
+            world
+                .current_state(state_type.as_deref())
+                .map_err(|e| mlua::Error::RuntimeError(e.to_string()))
+        });
+
+        methods.document("Sets the state with the given name, e.g. `world:set_state(\"InGame\")`, taking effect once the state is next updated.");
+        methods.document("Without a state type the first registered state type with a state of that name is set.");
         methods.add_method(
-            "has_component",
-            |_, world, (entity, comp_type): (LuaEntity, LuaTypeRegistration)| {
+            "set_state",
+            |_, world, (state, state_type): (String, Option<String>)| {
                 world
-                    .has_component(entity.inner()?, comp_type)
+                    .set_state(&state, state_type.as_deref())
                     .map_err(|e| mlua::Error::RuntimeError(e.to_string()))
             },
         );
 
-        methods.document("Removes the given component from the given entity, does nothing if it doesn't exist on the entity.");
-        methods.add_method_mut(
-            "remove_component",
-            |_, world, (entity, comp_type): (LuaEntity, LuaTypeRegistration)| {
+        methods.document("Returns the Bevy events of the type with the given name which this script has not read yet, as a list of tables.");
+        methods.document("The first time a script reads an event type, events sent in the previous frame are included.");
+        methods.add_method("read_events", |ctx, world, event: String| {
+            let sid = ctx
+                .globals()
+                .get::<_, mlua::AnyUserData>("script")?
+                .borrow::<LuaScriptData>()?
+                .sid;
+            world
+                .read_events(sid, &event)
+                .map_err(|e| mlua::Error::RuntimeError(e.to_string()))
+        });
+
+        #[cfg(feature = "api_time")]
+        {
+            methods.document("Creates a timer which finishes after the given number of seconds, and starts over if `repeating` is true.");
+            methods.document("Requires the `LuaTimerAPIProvider` API provider.");
+            methods.add_method(
+                "create_timer",
+                |_, world, (seconds, repeating): (f32, Option<bool>)| {
+                    world
+                        .create_timer(seconds, repeating.unwrap_or(false))
+                        .map_err(|e| mlua::Error::RuntimeError(e.to_string()))
+                },
+            );
+
+            methods.document("Creates a running stopwatch.");
+            methods.document("Requires the `LuaTimerAPIProvider` API provider.");
+            methods.add_method("create_stopwatch", |_, world, ()| {
+                world
+                    .create_stopwatch()
+                    .map_err(|e| mlua::Error::RuntimeError(e.to_string()))
+            });
+        }
+
+        #[cfg(feature = "api_assets")]
+        {
+            methods.document("Starts loading the asset at the given path.");
+            methods.document("Once loaded the `on_asset_loaded` hook of this script is called, or `on_asset_failed` if the asset could not be loaded.");
+            methods.document("Requires the `LuaAssetEventsProvider` API provider.");
+            methods.add_method("load_asset", |ctx, world, path: String| {
+                let sid = ctx
+                    .globals()
+                    .get::<_, mlua::AnyUserData>("script")?
+                    .borrow::<LuaScriptData>()?
+                    .sid;
+                world
+                    .load_asset(&path, Recipients::Id(sid))
+                    .map_err(|e| mlua::Error::RuntimeError(e.to_string()))
+            });
+
+            methods.document("Creates an asset of the type with the given name from the given table and returns a handle to it, e.g. `world:add_asset(\"StandardMaterial\", { base_color = \"#FF8800\" })`.");
+            methods.document("Fields which are not given keep their default value. The asset type must have been registered by the app with `register_script_asset`, bevy's `StandardMaterial` and `ColorMaterial` are registered by `LuaAssetsAPIProvider`.");
+            methods.add_method(
+                "add_asset",
+                |_, world, (asset, value): (String, ScriptValue)| {
+                    world
+                        .add_asset(&asset, &value)
+                        .map_err(|e| mlua::Error::RuntimeError(e.to_string()))
+                },
+            );
+        }
+
+        methods.document("Spawns the scene at the given path (e.g. `models/tree.glb#Scene0`) under a new root entity and returns the root.");
+        methods.document("The entities of the scene are spawned as children of the root once the scene is loaded, after which the `on_scene_spawned` hook of this script is called, or `on_scene_failed` if the scene could not be loaded.");
+        methods.document("Requires the `LuaSceneEventsProvider` API provider.");
+        methods.add_method(
+            "spawn_scene",
+            |ctx, world, (path, transform): (String, Option<LuaTransform>)| {
+                let sid = ctx
+                    .globals()
+                    .get::<_, mlua::AnyUserData>("script")?
+                    .borrow::<LuaScriptData>()?
+                    .sid;
+                let transform = match transform {
+                    Some(t) => t.inner()?,
+                    None => Default::default(),
+                };
                 world
-                    .remove_component(entity.inner()?, comp_type)
+                    .spawn_scene(&path, transform, Recipients::Id(sid))
+                    .map(LuaEntity::new)
                     .map_err(|e| mlua::Error::RuntimeError(e.to_string()))
             },
         );
 
-        methods.document("Retrieves a resource of the given type from the world.");
-        methods.document("If such a resource does not exist returns `nil`.");
-        methods.add_method("get_resource", |_, world, res_type: LuaTypeRegistration| {
+        methods.document("Retrieves the value of the script uniform slot with the given name.");
+        methods.add_method("get_uniform", |_, world, name: String| {
             world
-                .get_resource(res_type)
+                .get_uniform(&name)
+                .map(LuaVec4::new)
                 .map_err(|e| mlua::Error::RuntimeError(e.to_string()))
         });
 
+        methods.document("Sets the script uniform slot with the given name to a number, stored in the `x` component of the slot.");
         methods.document(
-            "Removes the given resource from the world, if one doesn't exist it does nothing.",
+            "The slot must have been declared by the app in the `ScriptUniforms` resource.",
+        );
+        methods.add_method("set_uniform", |_, world, (name, value): (String, f32)| {
+            world
+                .set_uniform(&name, Vec4::new(value, 0.0, 0.0, 0.0))
+                .map_err(|e| mlua::Error::RuntimeError(e.to_string()))
+        });
+
+        methods.document("Sets the script uniform slot with the given name to a vector.");
+        methods.document(
+            "The slot must have been declared by the app in the `ScriptUniforms` resource.",
         );
         methods.add_method(
-            "remove_resource",
-            |_, world, res_type: LuaTypeRegistration| {
-                let mut w = world.write();
-
-                let resource_data = res_type.data::<ReflectResource>().ok_or_else(|| {
-                    mlua::Error::RuntimeError(format!("Not a resource {}", res_type.short_name()))
-                })?;
-                resource_data.remove(&mut w);
-                Ok(())
+            "set_uniform_vec4",
+            |_, world, (name, value): (String, LuaVec4)| {
+                world
+                    .set_uniform(&name, value.inner()?)
+                    .map_err(|e| mlua::Error::RuntimeError(e.to_string()))
             },
         );
 
-        methods.document("Returns `true` if the world contains a resource of the given type.");
-        methods.add_method("has_resource", |_, world, res_type: LuaTypeRegistration| {
-            let w = world.read();
-
-            let resource_data = res_type.data::<ReflectResource>().ok_or_else(|| {
-                mlua::Error::RuntimeError(format!("Not a resource {}", res_type.short_name()))
-            })?;
+        methods.document("Returns the logical size of the primary window.");
+        methods.document("Requires the `window` script capability.");
+        methods.add_method("window_size", |_, world, ()| {
+            world
+                .window_size()
+                .map(LuaVec2::new)
+                .map_err(|e| mlua::Error::RuntimeError(e.to_string()))
+        });
 
-            Ok(resource_data.reflect(&w).is_some())
+        methods.document("Returns the logical position of the cursor in the primary window, or `nil` if the cursor is outside of it.");
+        methods.document("Requires the `window` script capability.");
+        methods.add_method("cursor_position", |_, world, ()| {
+            world
+                .cursor_position()
+                .map(|pos| pos.map(LuaVec2::new))
+                .map_err(|e| mlua::Error::RuntimeError(e.to_string()))
         });
 
-        methods.document("Retrieves children entities of the parent entity if it has any.");
-        methods.add_method("get_children", |_, world, parent: LuaEntity| {
-            Ok(world
-                .get_children(parent.inner()?)
-                .into_iter()
-                .map(LuaEntity::new)
-                .collect::<Vec<LuaEntity>>())
+        methods.document(
+            "Confines the cursor to the primary window if `true`, releases it otherwise.",
+        );
+        methods.document("Requires the `window` script capability.");
+        methods.add_method("set_cursor_grab", |_, world, grab: bool| {
+            world
+                .set_cursor_grab(grab)
+                .map_err(|e| mlua::Error::RuntimeError(e.to_string()))
         });
 
-        methods.document("Retrieves the parent entity of the given entity if it has any.");
-        methods.add_method("get_parent", |_, world, parent: LuaEntity| {
-            Ok(world.get_parent(parent.inner()?).map(LuaEntity::new))
+        methods.document("Shows or hides the cursor while it's over the primary window.");
+        methods.document("Requires the `window` script capability.");
+        methods.add_method("set_cursor_visible", |_, world, visible: bool| {
+            world
+                .set_cursor_visible(visible)
+                .map_err(|e| mlua::Error::RuntimeError(e.to_string()))
         });
 
-        methods.document("Attaches children entities to the given parent entity.");
-        methods.add_method(
-            "push_children",
-            |_, world, (parent, children): (LuaEntity, Vec<LuaEntity>)| {
-                let mut w = world.write();
-                let children = children
-                    .iter()
-                    .map(|e| e.inner())
-                    .collect::<Result<Vec<_>, _>>()?;
-
-                if let Some(mut entity) = w.get_entity_mut(parent.inner()?) {
-                    entity.push_children(&children);
-                }
+        methods.document("Sets the title of the primary window.");
+        methods.document("Requires the `window` script capability.");
+        methods.add_method("set_window_title", |_, world, title: String| {
+            world
+                .set_window_title(title)
+                .map_err(|e| mlua::Error::RuntimeError(e.to_string()))
+        });
 
-                Ok(())
-            },
+        methods.document(
+            "Requests a screenshot of the next frame to be saved at the given relative path.",
         );
+        methods.document("Requires the `screenshot` script capability.");
+        methods.add_method("screenshot", |_, world, path: String| {
+            world
+                .request_screenshot(&path)
+                .map_err(|e| mlua::Error::RuntimeError(e.to_string()))
+        });
 
-        methods.document("Attaches child entity to the given parent entity.");
-        methods.add_method_mut(
-            "push_child",
-            |_, world, (parent, child): (LuaEntity, LuaEntity)| {
-                world.push_child(parent.inner()?, child.inner()?);
-                Ok(())
+        methods.document("Sets the base color of the entity's `StandardMaterial`.");
+        methods.document("Materials shared with other entities are cloned first, so only this entity is affected.");
+        methods.add_method(
+            "set_material_base_color",
+            |_, world, (entity, color): (LuaEntity, LuaColor)| {
+                world
+                    .set_material_base_color(entity.inner()?, color.inner()?)
+                    .map_err(|e| mlua::Error::RuntimeError(e.to_string()))
             },
         );
 
-        methods.document("Removes children entities from the given parent entity.");
+        methods.document("Sets the emissive color of the entity's `StandardMaterial`.");
+        methods.document("Materials shared with other entities are cloned first, so only this entity is affected.");
         methods.add_method(
-            "remove_children",
-            |_, world, (parent, children): (LuaEntity, Vec<LuaEntity>)| {
-                let children = children
-                    .iter()
-                    .map(|e| e.inner())
-                    .collect::<Result<Vec<_>, _>>()?;
-
-                world.remove_children(parent.inner()?, &children);
-                Ok(())
+            "set_material_emissive",
+            |_, world, (entity, color): (LuaEntity, LuaColor)| {
+                world
+                    .set_material_emissive(entity.inner()?, color.inner()?)
+                    .map_err(|e| mlua::Error::RuntimeError(e.to_string()))
             },
         );
 
-        methods.document("Removes child entity from the given parent entity.");
+        methods.document("Sets the metallic and roughness factors of the entity's `StandardMaterial`, both in the range [0,1].");
+        methods.document("Materials shared with other entities are cloned first, so only this entity is affected.");
         methods.add_method(
-            "remove_child",
-            |_, world, (parent, child): (LuaEntity, LuaEntity)| {
-                world.remove_children(parent.inner()?, &[child.inner()?]);
-                Ok(())
+            "set_material_metallic_roughness",
+            |_, world, (entity, metallic, roughness): (LuaEntity, f32, f32)| {
+                world
+                    .set_material_metallic_roughness(entity.inner()?, metallic, roughness)
+                    .map_err(|e| mlua::Error::RuntimeError(e.to_string()))
             },
         );
 
-        methods
-            .document("Inserts children entities to the given parent entity at the given index.");
-        methods.add_method(
-            "insert_children",
-            |_, world, (parent, index, children): (LuaEntity, usize, Vec<LuaEntity>)| {
-                let children = children
-                    .iter()
-                    .map(|e| e.inner())
-                    .collect::<Result<Vec<_>, _>>()?;
-
-                world.insert_children(parent.inner()?, index, &children);
+        #[cfg(feature = "api_hierarchy")]
+        {
+            methods.document("Retrieves children entities of the parent entity if it has any.");
+            methods.add_method("get_children", |_, world, parent: LuaEntity| {
+                Ok(world
+                    .get_children(parent.inner()?)
+                    .into_iter()
+                    .map(LuaEntity::new)
+                    .collect::<Vec<LuaEntity>>())
+            });
+
+            methods.document("Retrieves the parent entity of the given entity if it has any.");
+            methods.add_method("get_parent", |_, world, parent: LuaEntity| {
+                Ok(world.get_parent(parent.inner()?).map(LuaEntity::new))
+            });
+
+            methods.document("Returns an iterator over the children of the entity, their children and so on, depth first.");
+            methods.document(
+                "The hierarchy is walked lazily, one entity per step, without collecting it first.",
+            );
+            methods.document("```lua");
+            methods.document("for child in world:descendants(entity) do print(child) end");
+            methods.document("```");
+            methods.add_method("descendants", |ctx, world, entity: LuaEntity| {
+                let mut descendants = world.descendants(entity.inner()?);
+                TypedFunction::from_rust_mut(
+                    move |_, ()| Ok(descendants.next().map(LuaEntity::new)),
+                    ctx,
+                )
+            });
+
+            methods.document("Returns an iterator over the parent of the entity, its parent and so on up to the root.");
+            methods.add_method("ancestors", |ctx, world, entity: LuaEntity| {
+                let mut ancestors = world.ancestors(entity.inner()?);
+                TypedFunction::from_rust_mut(
+                    move |_, ()| Ok(ancestors.next().map(LuaEntity::new)),
+                    ctx,
+                )
+            });
+
+            methods.document("Finds the first descendant of the entity, depth first, with the given `Name` component.");
+            methods.add_method(
+                "find_child_by_name",
+                |_, world, (entity, name): (LuaEntity, String)| {
+                    Ok(world
+                        .find_child_by_name(entity.inner()?, &name)
+                        .map(LuaEntity::new))
+                },
+            );
+
+            methods.document("Makes the second entity the parent of the first, removing it from the children of its previous parent.");
+            methods.document("Returns false without changing anything if either entity does not exist, or if the parent is the entity itself or one of its descendants.");
+            methods.add_method(
+                "set_parent",
+                |_, world, (entity, parent): (LuaEntity, LuaEntity)| {
+                    Ok(world.set_parent(entity.inner()?, parent.inner()?))
+                },
+            );
+
+            methods.document("Removes the entity from the children of its parent, making it the root of its own hierarchy.");
+            methods.document("Returns false if the entity had no parent.");
+            methods.add_method("detach", |_, world, entity: LuaEntity| {
+                Ok(world.detach(entity.inner()?))
+            });
+
+            methods.document("Attaches children entities to the given parent entity.");
+            methods.add_method(
+                "push_children",
+                |_, world, (parent, children): (LuaEntity, Vec<LuaEntity>)| {
+                    let mut w = world.write();
+                    let children = children
+                        .iter()
+                        .map(|e| e.inner())
+                        .collect::<Result<Vec<_>, _>>()?;
+
+                    if let Some(mut entity) = w.get_entity_mut(parent.inner()?) {
+                        entity.push_children(&children);
+                    }
+
+                    Ok(())
+                },
+            );
+
+            methods.document("Attaches child entity to the given parent entity.");
+            methods.add_method_mut(
+                "push_child",
+                |_, world, (parent, child): (LuaEntity, LuaEntity)| {
+                    world.push_child(parent.inner()?, child.inner()?);
+                    Ok(())
+                },
+            );
+
+            methods.document("Removes children entities from the given parent entity.");
+            methods.add_method(
+                "remove_children",
+                |_, world, (parent, children): (LuaEntity, Vec<LuaEntity>)| {
+                    let children = children
+                        .iter()
+                        .map(|e| e.inner())
+                        .collect::<Result<Vec<_>, _>>()?;
+
+                    world.remove_children(parent.inner()?, &children);
+                    Ok(())
+                },
+            );
+
+            methods.document("Removes child entity from the given parent entity.");
+            methods.add_method(
+                "remove_child",
+                |_, world, (parent, child): (LuaEntity, LuaEntity)| {
+                    world.remove_children(parent.inner()?, &[child.inner()?]);
+                    Ok(())
+                },
+            );
+
+            methods.document(
+                "Inserts children entities to the given parent entity at the given index.",
+            );
+            methods.add_method(
+                "insert_children",
+                |_, world, (parent, index, children): (LuaEntity, usize, Vec<LuaEntity>)| {
+                    let children = children
+                        .iter()
+                        .map(|e| e.inner())
+                        .collect::<Result<Vec<_>, _>>()?;
+
+                    world.insert_children(parent.inner()?, index, &children);
+                    Ok(())
+                },
+            );
+
+            methods.document("Inserts child entity to the given parent entity at the given index.");
+            methods.add_method(
+                "insert_child",
+                |_, world, (parent, index, child): (LuaEntity, usize, LuaEntity)| {
+                    world.insert_children(parent.inner()?, index, &[child.inner()?]);
+                    Ok(())
+                },
+            );
+
+            methods.document("Despawns the given entity's children recursively");
+            methods.add_method(
+                "despawn_children_recursive",
+                |_, world, entity: LuaEntity| {
+                    world.despawn_children_recursive(entity.inner()?);
+                    Ok(())
+                },
+            );
+
+            methods.document("Despawns the given entity and the entity's children recursively");
+            methods.add_method("despawn_recursive", |_, world, entity: LuaEntity| {
+                world.despawn_recursive(entity.inner()?);
                 Ok(())
-            },
-        );
+            });
+
+            methods.document("Despawns the given entity and the entity's children recursively once deferred commands are applied,");
+            methods.document(
+                "which happens after this script finished handling its events or on `world:flush()`.",
+            );
+            methods.add_method(
+                "despawn_recursive_deferred",
+                |_, world, entity: LuaEntity| {
+                    world.despawn_recursive_deferred(entity.inner()?);
+                    Ok(())
+                },
+            );
+        }
+
+        methods.document("Applies all deferred commands queued by this script or the API now.");
+        methods.document("Changes made directly through the world, such as spawning entities or inserting components, are always visible immediately.");
+        methods.add_method("flush", |_, world, ()| {
+            world.flush();
+            Ok(())
+        });
 
-        methods.document("Inserts child entity to the given parent entity at the given index.");
+        methods.document(
+            "Moves all scripts from the first entity to the second, keeping their state.",
+        );
+        methods.document("The scripts handle events on behalf of their new entity from the next time scripts are synchronized.");
         methods.add_method(
-            "insert_child",
-            |_, world, (parent, index, child): (LuaEntity, usize, LuaEntity)| {
-                world.insert_children(parent.inner()?, index, &[child.inner()?]);
-                Ok(())
+            "move_scripts",
+            |_, world, (from, to): (LuaEntity, LuaEntity)| {
+                world
+                    .move_scripts::<LuaFile>(from.inner()?, to.inner()?)
+                    .map_err(|e| mlua::Error::RuntimeError(e.to_string()))
             },
         );
 
-        methods.document("Despawns the given entity's children recursively");
+        methods
+            .document("Attaches new instances of all scripts of the first entity to the second,");
+        methods.document("with `with_state` the copies start out with the state of the originals instead of being loaded anew.");
         methods.add_method(
-            "despawn_children_recursive",
-            |_, world, entity: LuaEntity| {
-                world.despawn_children_recursive(entity.inner()?);
-                Ok(())
+            "copy_scripts",
+            |_, world, (from, to, with_state): (LuaEntity, LuaEntity, Option<bool>)| {
+                world
+                    .copy_scripts::<LuaFile>(
+                        from.inner()?,
+                        to.inner()?,
+                        with_state.unwrap_or(false),
+                    )
+                    .map_err(|e| mlua::Error::RuntimeError(e.to_string()))
             },
         );
 
-        methods.document("Despawns the given entity and the entity's children recursively");
-        methods.add_method("despawn_recursive", |_, world, entity: LuaEntity| {
-            world.despawn_recursive(entity.inner()?);
-            Ok(())
-        });
-
-        methods.document("Despawns the given entity and the entity's children recursively");
-        methods.add_method("insert_children", |_, world, entity: LuaEntity| {
-            let mut w = world.write();
-            DespawnRecursive {
-                entity: entity.inner()?,
-            }
-            .write(&mut w);
-            Ok(())
-        });
-
         methods.document("Spawns a new entity and returns its Entity ID");
         methods.add_method("spawn", |_, world, ()| {
-            let mut w = world.write();
-
-            Ok(LuaEntity::new(w.spawn(()).id()))
+            world
+                .spawn()
+                .map(LuaEntity::new)
+                .map_err(|e| mlua::Error::RuntimeError(e.to_string()))
         });
 
         methods.document(
             "Despawns the given entity if it exists, returns true if deletion was successfull",
         );
         methods.add_method("despawn", |_, world, entity: LuaEntity| {
-            let mut w = world.write();
-
-            Ok(w.despawn(entity.inner()?))
+            Ok(world.despawn(entity.inner()?))
         });
     }
 }
+
+/// An opt-in API provider which replaces references to despawned entities held in script globals with `nil`.
+///
+/// The sweep happens every time a script is about to handle events, and walks nested tables up to `max_depth` levels deep,
+/// so prefer checking `entity:valid()` in scripts which hold on to many entity references.
+pub struct LuaEntitySweepProvider {
+    /// how many levels of nested tables are searched for entity references, 0 means only globals are swept
+    pub max_depth: usize,
+}
+
+impl Default for LuaEntitySweepProvider {
+    fn default() -> Self {
+        Self { max_depth: 2 }
+    }
+}
+
+impl LuaEntitySweepProvider {
+    fn sweep_table<'lua>(
+        world: &WorldPointer,
+        table: Table<'lua>,
+        depth: usize,
+        visited: &mut HashSet<usize>,
+    ) -> mlua::Result<()> {
+        if !visited.insert(table.to_pointer() as usize) {
+            return Ok(());
+        }
+
+        let mut despawned = Vec::default();
+        for pair in table.clone().pairs::<Value, Value>() {
+            let (key, value) = pair?;
+            match value {
+                Value::UserData(ud) => {
+                    if let Ok(entity) = ud.borrow::<LuaEntity>() {
+                        let entity = entity.inner()?;
+                        if world.read().get_entity(entity).is_none() {
+                            despawned.push(key);
+                        }
+                    }
+                }
+                Value::Table(t) if depth > 0 => Self::sweep_table(world, t, depth - 1, visited)?,
+                _ => {}
+            }
+        }
+
+        for key in despawned {
+            table.raw_set(key, Value::Nil)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl APIProvider for LuaEntitySweepProvider {
+    type APITarget = Mutex<Lua>;
+    type ScriptContext = Mutex<Lua>;
+    type DocTarget = LuaDocFragment;
+
+    fn attach_api(&mut self, _: &mut Self::APITarget) -> Result<(), ScriptError> {
+        Ok(())
+    }
+
+    fn setup_script_runtime(
+        &mut self,
+        world_ptr: WorldPointer,
+        _script_data: &ScriptData,
+        ctx: &mut Self::ScriptContext,
+    ) -> Result<(), ScriptError> {
+        let ctx = ctx.get_mut().expect("Could not get context");
+        Self::sweep_table(
+            &world_ptr,
+            ctx.globals(),
+            self.max_depth,
+            &mut HashSet::default(),
+        )
+        .map_err(ScriptError::new_other)
+    }
+}
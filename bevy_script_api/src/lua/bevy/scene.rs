@@ -0,0 +1,82 @@
+use std::{marker::PhantomData, sync::Mutex};
+
+use bevy::prelude::{App, EventReader};
+use bevy_mod_scripting_core::prelude::*;
+use bevy_mod_scripting_lua::{
+    prelude::{LuaDocFragment, LuaEvent},
+    tealr, LuaArg,
+};
+
+use tealr::mlu::mlua::Lua;
+
+use crate::common::bevy::{register_script_scene_spawns, ScriptSceneSpawned};
+
+/// Forwards completion events of scenes spawned with `world:spawn_scene(path, [transform])` to the scripts which spawned them.
+///
+/// The `on_scene_spawned` hook is called once all entities of the scene are spawned and `on_scene_failed` if the scene
+/// could not be loaded, with the arguments created from the [`ScriptSceneSpawned`] event. Hooks are sent with the priority given in `priority`.
+pub struct LuaSceneEventsProvider<A: LuaArg + From<ScriptSceneSpawned>> {
+    pub priority: u32,
+    _ph: PhantomData<fn() -> A>,
+}
+
+impl<A: LuaArg + From<ScriptSceneSpawned>> Default for LuaSceneEventsProvider<A> {
+    fn default() -> Self {
+        Self {
+            priority: 0,
+            _ph: Default::default(),
+        }
+    }
+}
+
+impl<A: LuaArg + From<ScriptSceneSpawned>> LuaSceneEventsProvider<A> {
+    pub fn new(priority: u32) -> Self {
+        Self {
+            priority,
+            _ph: Default::default(),
+        }
+    }
+}
+
+fn forward_script_scene_spawns<A: LuaArg + From<ScriptSceneSpawned>>(
+    mut events: EventReader<ScriptSceneSpawned>,
+    mut writer: PriorityEventWriter<LuaEvent<A>>,
+    priority: u32,
+) {
+    for event in events.iter() {
+        writer.send(
+            LuaEvent {
+                hook_name: if event.success {
+                    "on_scene_spawned"
+                } else {
+                    "on_scene_failed"
+                }
+                .into(),
+                recipients: event.recipients.clone(),
+                args: event.clone().into(),
+            },
+            priority,
+        )
+    }
+}
+
+impl<A: LuaArg + From<ScriptSceneSpawned>> APIProvider for LuaSceneEventsProvider<A> {
+    type APITarget = Mutex<Lua>;
+    type ScriptContext = Mutex<Lua>;
+    type DocTarget = LuaDocFragment;
+
+    fn attach_api(&mut self, _: &mut Self::APITarget) -> Result<(), ScriptError> {
+        Ok(())
+    }
+
+    fn register_with_app(&self, app: &mut App) {
+        register_script_scene_spawns(app);
+        let priority = self.priority;
+        app.add_system(
+            move |events: EventReader<ScriptSceneSpawned>,
+                  writer: PriorityEventWriter<LuaEvent<A>>| {
+                forward_script_scene_spawns(events, writer, priority)
+            },
+        );
+    }
+}
@@ -0,0 +1,129 @@
+use std::{marker::PhantomData, sync::Mutex};
+
+use bevy::prelude::{App, EventReader};
+use bevy_mod_scripting_core::prelude::*;
+use bevy_mod_scripting_lua::{
+    prelude::{LuaDocFragment, LuaEvent, LuaScriptHost},
+    tealr, LuaArg,
+};
+
+use tealr::mlu::mlua::{self, FromLua, Lua, Table, Value};
+
+use crate::common::{
+    bevy::{register_script_sent_events, GetWorld, ScriptSentEvent, ScriptWorld},
+    shared::ScriptValue,
+};
+
+use super::LuaEntity;
+
+/// Provides the `events` global table, letting scripts send events to other Lua scripts:
+///
+/// - `events.send(hook, recipients, priority, args)` sends an event for the given hook, handled with the given priority
+///
+/// The recipients are either `nil` for all scripts, an entity, the name of a script group or a script ID.
+/// The priority defaults to `priority` if `nil`, and is validated against the handler stages of the host,
+/// sending with a priority no handler stage handles is an error naming the valid ranges.
+/// The hook is called with the arguments created from the [`ScriptSentEvent`] event, usually its `args`.
+pub struct LuaSendEventsProvider<A: LuaArg + From<ScriptSentEvent>> {
+    pub priority: u32,
+    _ph: PhantomData<fn() -> A>,
+}
+
+impl<A: LuaArg + From<ScriptSentEvent>> Default for LuaSendEventsProvider<A> {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl<A: LuaArg + From<ScriptSentEvent>> LuaSendEventsProvider<A> {
+    pub fn new(priority: u32) -> Self {
+        Self {
+            priority,
+            _ph: Default::default(),
+        }
+    }
+
+    fn events_table(ctx: &Lua, default_priority: u32) -> mlua::Result<Table> {
+        let table = ctx.create_table()?;
+
+        table.set(
+            "send",
+            ctx.create_function(
+                move |ctx,
+                      (hook, recipients, priority, args): (
+                    String,
+                    Value,
+                    Option<u32>,
+                    ScriptValue,
+                )| {
+                    let recipients = to_recipients(ctx, recipients)?;
+                    ScriptWorld::new(ctx.get_world()?)
+                        .send_script_event::<LuaScriptHost<A>>(
+                            &hook,
+                            recipients,
+                            priority.unwrap_or(default_priority),
+                            args,
+                        )
+                        .map_err(|e| mlua::Error::RuntimeError(e.to_string()))
+                },
+            )?,
+        )?;
+
+        Ok(table)
+    }
+}
+
+fn to_recipients<'lua>(ctx: &'lua Lua, recipients: Value<'lua>) -> mlua::Result<Recipients> {
+    Ok(match recipients {
+        Value::Nil => Recipients::All,
+        Value::String(group) => Recipients::Group(group.to_str()?.to_owned()),
+        Value::Integer(sid) => Recipients::Id(sid as ScriptId),
+        v @ Value::UserData(_) => Recipients::Entity(LuaEntity::from_lua(v, ctx)?.inner()?),
+        v => {
+            return Err(mlua::Error::FromLuaConversionError {
+                from: v.type_name(),
+                to: "Recipients",
+                message: Some(
+                    "expected nil, an entity, a script group name or a script ID".to_owned(),
+                ),
+            })
+        }
+    })
+}
+
+fn forward_script_sent_events<A: LuaArg + From<ScriptSentEvent>>(
+    mut events: EventReader<ScriptSentEvent>,
+    mut writer: PriorityEventWriter<LuaEvent<A>>,
+) {
+    for event in events.iter().filter(|e| e.is_for::<LuaScriptHost<A>>()) {
+        writer.send(
+            LuaEvent {
+                hook_name: event.hook,
+                recipients: event.recipients.clone(),
+                args: event.clone().into(),
+            },
+            event.priority,
+        )
+    }
+}
+
+impl<A: LuaArg + From<ScriptSentEvent>> APIProvider for LuaSendEventsProvider<A> {
+    type APITarget = Mutex<Lua>;
+    type ScriptContext = Mutex<Lua>;
+    type DocTarget = LuaDocFragment;
+
+    fn attach_api(&mut self, ctx: &mut Self::APITarget) -> Result<(), ScriptError> {
+        let ctx = ctx
+            .get_mut()
+            .expect("Unable to acquire lock on Lua context");
+
+        Self::events_table(ctx, self.priority)
+            .and_then(|events| ctx.globals().set("events", events))
+            .map_err(ScriptError::new_other)
+    }
+
+    fn register_with_app(&self, app: &mut App) {
+        register_script_sent_events(app);
+        app.add_system(forward_script_sent_events::<A>);
+    }
+}
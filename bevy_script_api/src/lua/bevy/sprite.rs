@@ -0,0 +1,135 @@
+use std::sync::Mutex;
+
+use bevy::prelude::{App, Color, Quat, Vec3};
+use bevy_mod_scripting_core::prelude::*;
+use bevy_mod_scripting_lua::{prelude::LuaDocFragment, tealr};
+
+use tealr::mlu::mlua::{self, Lua};
+
+use crate::common::bevy::{register_transform_tweens, GetWorld, ScriptWorld};
+
+use super::LuaEntity;
+
+/// Provides the `sprite` global table, containing helpers for 2D games:
+///
+/// - `sprite.spawn(texture_path, x, y, [z])` spawns a sprite bundle and returns its entity
+/// - `sprite.set_color(entity, r, g, b, [a])` tints the sprite
+/// - `sprite.set_flip(entity, flip_x, flip_y)` flips the sprite
+/// - `sprite.set_index(entity, index)` sets the texture atlas index of the sprite
+/// - `sprite.move_to(entity, x, y, duration)`, `sprite.rotate_to(entity, angle, duration)` and `sprite.scale_to(entity, x, y, duration)`
+///   tween the transform of the entity over the given number of seconds
+pub struct LuaSprite2dAPIProvider;
+
+fn world(ctx: &Lua) -> mlua::Result<ScriptWorld> {
+    ctx.get_world().map(ScriptWorld::new)
+}
+
+fn to_lua_error(e: ScriptError) -> mlua::Error {
+    mlua::Error::RuntimeError(e.to_string())
+}
+
+impl LuaSprite2dAPIProvider {
+    fn sprite_table(ctx: &Lua) -> mlua::Result<mlua::Table> {
+        let sprite = ctx.create_table()?;
+
+        sprite.set(
+            "spawn",
+            ctx.create_function(|ctx, (path, x, y, z): (String, f32, f32, Option<f32>)| {
+                world(ctx)?
+                    .spawn_sprite(&path, Vec3::new(x, y, z.unwrap_or_default()))
+                    .map(LuaEntity::new)
+                    .map_err(to_lua_error)
+            })?,
+        )?;
+
+        sprite.set(
+            "set_color",
+            ctx.create_function(
+                |ctx, (entity, r, g, b, a): (LuaEntity, f32, f32, f32, Option<f32>)| {
+                    world(ctx)?
+                        .set_sprite_color(entity.inner()?, Color::rgba(r, g, b, a.unwrap_or(1.0)))
+                        .map_err(to_lua_error)
+                },
+            )?,
+        )?;
+
+        sprite.set(
+            "set_flip",
+            ctx.create_function(|ctx, (entity, flip_x, flip_y): (LuaEntity, bool, bool)| {
+                world(ctx)?
+                    .set_sprite_flip(entity.inner()?, flip_x, flip_y)
+                    .map_err(to_lua_error)
+            })?,
+        )?;
+
+        sprite.set(
+            "set_index",
+            ctx.create_function(|ctx, (entity, index): (LuaEntity, usize)| {
+                world(ctx)?
+                    .set_sprite_index(entity.inner()?, index)
+                    .map_err(to_lua_error)
+            })?,
+        )?;
+
+        sprite.set(
+            "move_to",
+            ctx.create_function(
+                |ctx, (entity, x, y, duration): (LuaEntity, f32, f32, f32)| {
+                    world(ctx)?
+                        .tween_transform(entity.inner()?, duration, |t| {
+                            t.translation.x = x;
+                            t.translation.y = y;
+                        })
+                        .map_err(to_lua_error)
+                },
+            )?,
+        )?;
+
+        sprite.set(
+            "rotate_to",
+            ctx.create_function(|ctx, (entity, angle, duration): (LuaEntity, f32, f32)| {
+                world(ctx)?
+                    .tween_transform(entity.inner()?, duration, |t| {
+                        t.rotation = Quat::from_rotation_z(angle)
+                    })
+                    .map_err(to_lua_error)
+            })?,
+        )?;
+
+        sprite.set(
+            "scale_to",
+            ctx.create_function(
+                |ctx, (entity, x, y, duration): (LuaEntity, f32, f32, f32)| {
+                    world(ctx)?
+                        .tween_transform(entity.inner()?, duration, |t| {
+                            t.scale.x = x;
+                            t.scale.y = y;
+                        })
+                        .map_err(to_lua_error)
+                },
+            )?,
+        )?;
+
+        Ok(sprite)
+    }
+}
+
+impl APIProvider for LuaSprite2dAPIProvider {
+    type APITarget = Mutex<Lua>;
+    type ScriptContext = Mutex<Lua>;
+    type DocTarget = LuaDocFragment;
+
+    fn attach_api(&mut self, ctx: &mut Self::APITarget) -> Result<(), ScriptError> {
+        let ctx = ctx
+            .get_mut()
+            .expect("Unable to acquire lock on Lua context");
+
+        Self::sprite_table(ctx)
+            .and_then(|sprite| ctx.globals().set("sprite", sprite))
+            .map_err(ScriptError::new_other)
+    }
+
+    fn register_with_app(&self, app: &mut App) {
+        register_transform_tweens(app);
+    }
+}
@@ -0,0 +1,85 @@
+use std::{marker::PhantomData, sync::Mutex};
+
+use bevy::{
+    ecs::event::Events,
+    prelude::{App, EventReader},
+};
+use bevy_mod_scripting_core::prelude::*;
+use bevy_mod_scripting_lua::{
+    prelude::{LuaDocFragment, LuaEvent},
+    tealr, LuaArg,
+};
+
+use tealr::mlu::mlua::Lua;
+
+use crate::common::bevy::ScriptStateEvent;
+
+/// Forwards transitions of the states registered with [`RegisterScriptState`](crate::common::bevy::RegisterScriptState) to all scripts.
+///
+/// The `on_state_enter` and `on_state_exit` hooks are called with the arguments created from the
+/// [`ScriptStateEvent`] event, usually the name of the state and the short type name of its type.
+/// Hooks are sent with the priority given in `priority`.
+pub struct LuaStateEventsProvider<A: LuaArg + From<ScriptStateEvent>> {
+    pub priority: u32,
+    _ph: PhantomData<fn() -> A>,
+}
+
+impl<A: LuaArg + From<ScriptStateEvent>> Default for LuaStateEventsProvider<A> {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl<A: LuaArg + From<ScriptStateEvent>> LuaStateEventsProvider<A> {
+    pub fn new(priority: u32) -> Self {
+        Self {
+            priority,
+            _ph: Default::default(),
+        }
+    }
+}
+
+fn forward_script_state_events<A: LuaArg + From<ScriptStateEvent>>(
+    mut events: EventReader<ScriptStateEvent>,
+    mut writer: PriorityEventWriter<LuaEvent<A>>,
+    priority: u32,
+) {
+    for event in events.iter() {
+        writer.send(
+            LuaEvent {
+                hook_name: if event.entered {
+                    "on_state_enter"
+                } else {
+                    "on_state_exit"
+                }
+                .into(),
+                recipients: Recipients::All,
+                args: event.clone().into(),
+            },
+            priority,
+        )
+    }
+}
+
+impl<A: LuaArg + From<ScriptStateEvent>> APIProvider for LuaStateEventsProvider<A> {
+    type APITarget = Mutex<Lua>;
+    type ScriptContext = Mutex<Lua>;
+    type DocTarget = LuaDocFragment;
+
+    fn attach_api(&mut self, _: &mut Self::APITarget) -> Result<(), ScriptError> {
+        Ok(())
+    }
+
+    fn register_with_app(&self, app: &mut App) {
+        if !app.world.contains_resource::<Events<ScriptStateEvent>>() {
+            app.add_event::<ScriptStateEvent>();
+        }
+        let priority = self.priority;
+        app.add_system(
+            move |events: EventReader<ScriptStateEvent>,
+                  writer: PriorityEventWriter<LuaEvent<A>>| {
+                forward_script_state_events(events, writer, priority)
+            },
+        );
+    }
+}
@@ -0,0 +1,62 @@
+use std::sync::Mutex;
+
+use bevy_mod_scripting_core::prelude::*;
+use bevy_mod_scripting_lua::{prelude::LuaDocFragment, tealr};
+
+use tealr::mlu::mlua::{self, Lua};
+
+use crate::common::bevy::{GetWorld, ScriptTime, ScriptWorld};
+
+/// Provides the `time` global table, reading the frame time and fixed timesteps:
+///
+/// - `time.delta_seconds()` returns the seconds elapsed since the previous frame
+/// - `time.elapsed()` returns the seconds elapsed since the app started
+/// - `time.fixed_step(label)` returns the seconds between two steps of the labeled fixed timestep, or `nil` if there is none
+/// - `time.fixed_overstep(label)` returns how far the labeled fixed timestep is into its next step, between 0 and 1, or `nil` if there is none
+///
+/// See [`ScriptTime`] for how fixed timesteps are labeled.
+pub struct LuaTimeAPIProvider;
+
+fn time(ctx: &Lua) -> mlua::Result<ScriptTime> {
+    ctx.get_world()
+        .map(|world| ScriptTime(ScriptWorld::new(world)))
+}
+
+impl LuaTimeAPIProvider {
+    fn time_table(ctx: &Lua) -> mlua::Result<mlua::Table> {
+        let table = ctx.create_table()?;
+        table.set(
+            "delta_seconds",
+            ctx.create_function(|ctx, ()| Ok(time(ctx)?.delta_seconds()))?,
+        )?;
+        table.set(
+            "elapsed",
+            ctx.create_function(|ctx, ()| Ok(time(ctx)?.elapsed()))?,
+        )?;
+        table.set(
+            "fixed_step",
+            ctx.create_function(|ctx, label: String| Ok(time(ctx)?.fixed_step(&label)))?,
+        )?;
+        table.set(
+            "fixed_overstep",
+            ctx.create_function(|ctx, label: String| Ok(time(ctx)?.fixed_overstep(&label)))?,
+        )?;
+        Ok(table)
+    }
+}
+
+impl APIProvider for LuaTimeAPIProvider {
+    type APITarget = Mutex<Lua>;
+    type ScriptContext = Mutex<Lua>;
+    type DocTarget = LuaDocFragment;
+
+    fn attach_api(&mut self, ctx: &mut Self::APITarget) -> Result<(), ScriptError> {
+        let ctx = ctx
+            .get_mut()
+            .expect("Unable to acquire lock on Lua context");
+
+        Self::time_table(ctx)
+            .and_then(|time| ctx.globals().set("time", time))
+            .map_err(ScriptError::new_other)
+    }
+}
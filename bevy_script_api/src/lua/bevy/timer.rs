@@ -0,0 +1,110 @@
+use std::sync::Mutex;
+
+use bevy::prelude::App;
+use bevy_mod_scripting_core::prelude::*;
+use bevy_mod_scripting_lua::{prelude::LuaDocFragment, tealr};
+
+use tealr::mlu::{mlua::Lua, TealData, TealDataMethods};
+
+use crate::{
+    common::bevy::{register_script_timers, ScriptStopwatch, ScriptTimer},
+    impl_tealr_type,
+};
+
+pub type LuaTimer = ScriptTimer;
+impl_tealr_type!(LuaTimer);
+
+impl TealData for LuaTimer {
+    fn add_methods<'lua, T: TealDataMethods<'lua, Self>>(methods: &mut T) {
+        methods.document_type(
+            "A timer created with `world:create_timer`, ticked automatically every frame.",
+        );
+
+        methods.document("Returns true if the timer finished, repeating timers are only finished on the frame they wrap around.");
+        methods.add_method("finished", |_, s, ()| Ok(s.with(|t| t.finished())));
+
+        methods.document("Returns true if the timer finished during the last frame.");
+        methods.add_method(
+            "just_finished",
+            |_, s, ()| Ok(s.with(|t| t.just_finished())),
+        );
+
+        methods.document("Returns the number of times the timer finished during the last frame.");
+        methods.add_method("times_finished", |_, s, ()| {
+            Ok(s.with(|t| t.times_finished_this_tick()))
+        });
+
+        methods.document("Returns the time elapsed on the timer in seconds.");
+        methods.add_method("elapsed", |_, s, ()| Ok(s.with(|t| t.elapsed_secs())));
+
+        methods.document("Returns the duration of the timer in seconds.");
+        methods.add_method("duration", |_, s, ()| {
+            Ok(s.with(|t| t.duration().as_secs_f32()))
+        });
+
+        methods.document("Returns the fraction of the timer elapsed, in the range [0,1].");
+        methods.add_method("percent", |_, s, ()| Ok(s.with(|t| t.percent())));
+
+        methods.document("Returns true if the timer is paused.");
+        methods.add_method("paused", |_, s, ()| Ok(s.with(|t| t.paused())));
+
+        methods.document("Pauses the timer.");
+        methods.add_method("pause", |_, s, ()| Ok(s.with(|t| t.pause())));
+
+        methods.document("Resumes the timer.");
+        methods.add_method("unpause", |_, s, ()| Ok(s.with(|t| t.unpause())));
+
+        methods.document("Resets the timer to zero.");
+        methods.add_method("reset", |_, s, ()| Ok(s.with(|t| t.reset())));
+    }
+}
+
+pub type LuaStopwatch = ScriptStopwatch;
+impl_tealr_type!(LuaStopwatch);
+
+impl TealData for LuaStopwatch {
+    fn add_methods<'lua, T: TealDataMethods<'lua, Self>>(methods: &mut T) {
+        methods.document_type(
+            "A stopwatch created with `world:create_stopwatch`, ticked automatically every frame.",
+        );
+
+        methods.document("Returns the time elapsed on the stopwatch in seconds.");
+        methods.add_method("elapsed", |_, s, ()| Ok(s.with(|t| t.elapsed_secs())));
+
+        methods.document("Returns true if the stopwatch is paused.");
+        methods.add_method("paused", |_, s, ()| Ok(s.with(|t| t.paused())));
+
+        methods.document("Pauses the stopwatch.");
+        methods.add_method("pause", |_, s, ()| Ok(s.with(|t| t.pause())));
+
+        methods.document("Resumes the stopwatch.");
+        methods.add_method("unpause", |_, s, ()| Ok(s.with(|t| t.unpause())));
+
+        methods.document("Resets the stopwatch to zero.");
+        methods.add_method("reset", |_, s, ()| Ok(s.with(|t| t.reset())));
+    }
+}
+
+/// Enables `world:create_timer(seconds, repeating)` and `world:create_stopwatch()`,
+/// the created objects are ticked every frame until the script stops referencing them.
+pub struct LuaTimerAPIProvider;
+
+impl APIProvider for LuaTimerAPIProvider {
+    type APITarget = Mutex<Lua>;
+    type ScriptContext = Mutex<Lua>;
+    type DocTarget = LuaDocFragment;
+
+    fn attach_api(&mut self, _: &mut Self::APITarget) -> Result<(), ScriptError> {
+        Ok(())
+    }
+
+    fn get_doc_fragment(&self) -> Option<Self::DocTarget> {
+        Some(LuaDocFragment::new("TimerAPI", |tw| {
+            tw.process_type::<LuaTimer>().process_type::<LuaStopwatch>()
+        }))
+    }
+
+    fn register_with_app(&self, app: &mut App) {
+        register_script_timers(app);
+    }
+}
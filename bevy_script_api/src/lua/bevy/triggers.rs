@@ -0,0 +1,165 @@
+use std::{marker::PhantomData, sync::Mutex};
+
+use bevy::prelude::{App, EventReader, Vec3};
+use bevy_mod_scripting_core::prelude::*;
+use bevy_mod_scripting_lua::{
+    prelude::{LuaDocFragment, LuaEvent},
+    tealr, LuaArg,
+};
+
+use tealr::mlu::mlua::{self, Lua};
+
+use crate::common::bevy::{
+    register_script_triggers, GetWorld, ScriptTriggerEvent, ScriptWorld, TriggerShape,
+};
+
+use super::{LuaEntity, LuaVec3};
+
+/// Provides the `triggers` global table, attaching named trigger volumes to entities:
+///
+/// - `triggers.sphere(entity, name, radius, [offset])` attaches a sphere, optionally offset from the entity by a `Vec3`
+/// - `triggers.box(entity, name, half_extents, [offset])` attaches an axis aligned box with the given `Vec3` half extents
+/// - `triggers.remove(entity, name)` removes the trigger, returns false if there was none
+/// - `triggers.inside(entity, name)` returns the entities inside the trigger as of the last frame
+///
+/// The `on_trigger_enter` and `on_trigger_exit` hooks of the scripts on the entity owning the trigger are called
+/// with the arguments created from the [`ScriptTriggerEvent`] event, usually the trigger's name and the other entity.
+/// Hooks are sent with the priority given in `priority`.
+pub struct LuaTriggersAPIProvider<A: LuaArg + From<ScriptTriggerEvent>> {
+    pub priority: u32,
+    _ph: PhantomData<fn() -> A>,
+}
+
+impl<A: LuaArg + From<ScriptTriggerEvent>> Default for LuaTriggersAPIProvider<A> {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl<A: LuaArg + From<ScriptTriggerEvent>> LuaTriggersAPIProvider<A> {
+    pub fn new(priority: u32) -> Self {
+        Self {
+            priority,
+            _ph: Default::default(),
+        }
+    }
+}
+
+fn world(ctx: &Lua) -> mlua::Result<ScriptWorld> {
+    ctx.get_world().map(ScriptWorld::new)
+}
+
+fn to_lua_error(e: ScriptError) -> mlua::Error {
+    mlua::Error::RuntimeError(e.to_string())
+}
+
+fn offset(offset: Option<LuaVec3>) -> mlua::Result<Vec3> {
+    match offset {
+        Some(offset) => Ok(offset.inner()?),
+        None => Ok(Vec3::ZERO),
+    }
+}
+
+fn triggers_table(ctx: &Lua) -> mlua::Result<mlua::Table> {
+    let triggers = ctx.create_table()?;
+
+    triggers.set(
+        "sphere",
+        ctx.create_function(
+            |ctx, (entity, name, radius, o): (LuaEntity, String, f32, Option<LuaVec3>)| {
+                world(ctx)?
+                    .add_trigger(
+                        entity.inner()?,
+                        name,
+                        TriggerShape::Sphere { radius },
+                        offset(o)?,
+                    )
+                    .map_err(to_lua_error)
+            },
+        )?,
+    )?;
+
+    triggers.set(
+        "box",
+        ctx.create_function(
+            |ctx, (entity, name, half_extents, o): (LuaEntity, String, LuaVec3, Option<LuaVec3>)| {
+                let shape = TriggerShape::Box {
+                    half_extents: half_extents.inner()?,
+                };
+                world(ctx)?
+                    .add_trigger(entity.inner()?, name, shape, offset(o)?)
+                    .map_err(to_lua_error)
+            },
+        )?,
+    )?;
+
+    triggers.set(
+        "remove",
+        ctx.create_function(|ctx, (entity, name): (LuaEntity, String)| {
+            world(ctx)?
+                .remove_trigger(entity.inner()?, &name)
+                .map_err(to_lua_error)
+        })?,
+    )?;
+
+    triggers.set(
+        "inside",
+        ctx.create_function(|ctx, (entity, name): (LuaEntity, String)| {
+            world(ctx)?
+                .entities_in_trigger(entity.inner()?, &name)
+                .map(|entities| entities.into_iter().map(LuaEntity::new).collect::<Vec<_>>())
+                .map_err(to_lua_error)
+        })?,
+    )?;
+
+    Ok(triggers)
+}
+
+fn forward_script_trigger_events<A: LuaArg + From<ScriptTriggerEvent>>(
+    mut events: EventReader<ScriptTriggerEvent>,
+    mut writer: PriorityEventWriter<LuaEvent<A>>,
+    priority: u32,
+) {
+    for event in events.iter() {
+        writer.send(
+            LuaEvent {
+                hook_name: if event.entered {
+                    "on_trigger_enter"
+                } else {
+                    "on_trigger_exit"
+                }
+                .into(),
+                recipients: Recipients::Entity(event.owner),
+                args: event.clone().into(),
+            },
+            priority,
+        )
+    }
+}
+
+impl<A: LuaArg + From<ScriptTriggerEvent>> APIProvider for LuaTriggersAPIProvider<A> {
+    type APITarget = Mutex<Lua>;
+    type ScriptContext = Mutex<Lua>;
+    type DocTarget = LuaDocFragment;
+
+    fn attach_api(&mut self, ctx: &mut Self::APITarget) -> Result<(), ScriptError> {
+        let ctx = ctx
+            .get_mut()
+            .expect("Unable to acquire lock on Lua context");
+
+        triggers_table(ctx)
+            .and_then(|triggers| ctx.globals().set("triggers", triggers))
+            .map_err(ScriptError::new_other)
+    }
+
+    fn register_with_app(&self, app: &mut App) {
+        register_script_triggers(app);
+        let priority = self.priority;
+        app.add_system(
+            move |events: EventReader<ScriptTriggerEvent>,
+                  writer: PriorityEventWriter<LuaEvent<A>>| {
+                forward_script_trigger_events(events, writer, priority)
+            },
+        );
+    }
+}
@@ -0,0 +1,172 @@
+use std::{marker::PhantomData, sync::Mutex};
+
+use bevy::prelude::{App, EventReader};
+use bevy_mod_scripting_core::prelude::*;
+use bevy_mod_scripting_lua::{
+    prelude::{LuaDocFragment, LuaEvent},
+    tealr, LuaArg,
+};
+
+use tealr::mlu::mlua::{self, Lua, Value};
+
+use crate::common::{
+    bevy::{register_script_tweens, GetWorld, ScriptTweenFinished, ScriptWorld, TweenValue},
+    mathx::Easing,
+};
+
+use super::{LuaColor, LuaEntity, LuaQuat, LuaScriptData, LuaVec2, LuaVec3, LuaVec4};
+
+/// Provides the `tween` global table, interpolating fields of components over time on the Rust side:
+///
+/// - `tween.to(entity, path, target, duration, [easing])` tweens the field at the path, e.g. `"Transform.translation"`,
+///   from its current value to the target number, vector, quaternion or color over `duration` seconds,
+///   with the named easing function (`"linear"` by default). Returns the ID of the tween
+/// - `tween.cancel(id)` stops the tween where it is, returns false if it already finished
+///
+/// Once a tween finishes the `on_tween_finished` hook of the script which started it is called with the arguments created
+/// from the [`ScriptTweenFinished`] event, usually the ID of the tween and the entity.
+/// Hooks are sent with the priority given in `priority`.
+pub struct LuaTweenAPIProvider<A: LuaArg + From<ScriptTweenFinished>> {
+    pub priority: u32,
+    _ph: PhantomData<fn() -> A>,
+}
+
+impl<A: LuaArg + From<ScriptTweenFinished>> Default for LuaTweenAPIProvider<A> {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl<A: LuaArg + From<ScriptTweenFinished>> LuaTweenAPIProvider<A> {
+    pub fn new(priority: u32) -> Self {
+        Self {
+            priority,
+            _ph: Default::default(),
+        }
+    }
+}
+
+fn world(ctx: &Lua) -> mlua::Result<ScriptWorld> {
+    ctx.get_world().map(ScriptWorld::new)
+}
+
+fn to_lua_error(e: ScriptError) -> mlua::Error {
+    mlua::Error::RuntimeError(e.to_string())
+}
+
+fn tween_value(value: Value) -> mlua::Result<TweenValue> {
+    Ok(match value {
+        Value::Integer(v) => TweenValue::F64(v as f64),
+        Value::Number(v) => TweenValue::F64(v),
+        Value::UserData(ud) if ud.is::<LuaVec2>() => {
+            TweenValue::Vec2(ud.borrow::<LuaVec2>()?.inner()?)
+        }
+        Value::UserData(ud) if ud.is::<LuaVec3>() => {
+            TweenValue::Vec3(ud.borrow::<LuaVec3>()?.inner()?)
+        }
+        Value::UserData(ud) if ud.is::<LuaVec4>() => {
+            TweenValue::Vec4(ud.borrow::<LuaVec4>()?.inner()?)
+        }
+        Value::UserData(ud) if ud.is::<LuaQuat>() => {
+            TweenValue::Quat(ud.borrow::<LuaQuat>()?.inner()?)
+        }
+        Value::UserData(ud) if ud.is::<LuaColor>() => {
+            TweenValue::Color(ud.borrow::<LuaColor>()?.inner()?)
+        }
+        v => {
+            return Err(mlua::Error::RuntimeError(format!(
+                "Cannot tween towards a {}, expected a number, vector, quaternion or color",
+                v.type_name()
+            )))
+        }
+    })
+}
+
+fn tween_table(ctx: &Lua) -> mlua::Result<mlua::Table> {
+    let tween = ctx.create_table()?;
+
+    tween.set(
+        "to",
+        ctx.create_function(
+            |ctx,
+             (entity, path, target, duration, easing): (
+                LuaEntity,
+                String,
+                Value,
+                f32,
+                Option<String>,
+            )| {
+                let sid = ctx
+                    .globals()
+                    .get::<_, mlua::AnyUserData>("script")?
+                    .borrow::<LuaScriptData>()?
+                    .sid;
+                let easing = match easing {
+                    Some(easing) => easing.parse().map_err(mlua::Error::RuntimeError)?,
+                    None => Easing::Linear,
+                };
+                world(ctx)?
+                    .tween(
+                        entity.inner()?,
+                        &path,
+                        tween_value(target)?,
+                        duration,
+                        easing,
+                        Recipients::Id(sid),
+                    )
+                    .map_err(to_lua_error)
+            },
+        )?,
+    )?;
+
+    tween.set(
+        "cancel",
+        ctx.create_function(|ctx, id: u32| Ok(world(ctx)?.cancel_tween(id)))?,
+    )?;
+
+    Ok(tween)
+}
+
+fn forward_script_tweens_finished<A: LuaArg + From<ScriptTweenFinished>>(
+    mut events: EventReader<ScriptTweenFinished>,
+    mut writer: PriorityEventWriter<LuaEvent<A>>,
+    priority: u32,
+) {
+    for event in events.iter() {
+        writer.send(
+            LuaEvent {
+                hook_name: "on_tween_finished".into(),
+                recipients: event.recipients.clone(),
+                args: event.clone().into(),
+            },
+            priority,
+        )
+    }
+}
+
+impl<A: LuaArg + From<ScriptTweenFinished>> APIProvider for LuaTweenAPIProvider<A> {
+    type APITarget = Mutex<Lua>;
+    type ScriptContext = Mutex<Lua>;
+    type DocTarget = LuaDocFragment;
+
+    fn attach_api(&mut self, ctx: &mut Self::APITarget) -> Result<(), ScriptError> {
+        let ctx = ctx
+            .get_mut()
+            .expect("Unable to acquire lock on Lua context");
+
+        tween_table(ctx)
+            .and_then(|tween| ctx.globals().set("tween", tween))
+            .map_err(ScriptError::new_other)
+    }
+
+    fn register_with_app(&self, app: &mut App) {
+        register_script_tweens(app);
+        let priority = self.priority;
+        app.add_system(
+            move |events: EventReader<ScriptTweenFinished>,
+                  writer: PriorityEventWriter<LuaEvent<A>>| {
+                forward_script_tweens_finished(events, writer, priority)
+            },
+        );
+    }
+}
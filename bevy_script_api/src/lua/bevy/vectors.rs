@@ -0,0 +1,88 @@
+use bevy_mod_scripting_lua::tealr;
+
+use tealr::mlu::mlua::{self, Lua, ToLua, Value};
+
+use crate::common::vectors::{Swizzled, VectorComponents};
+
+use super::{LuaVec2, LuaVec3, LuaVec4};
+
+/// The `__index` metamethod of the vector proxies, reading a component by index (`v[0]`) or several by name (`v.zyx`).
+/// Named fields and methods take precedence, so this is only called for names which are neither.
+pub(crate) fn index_vector<'lua, V: VectorComponents>(
+    ctx: &'lua Lua,
+    v: V,
+    key: Value<'lua>,
+) -> mlua::Result<Value<'lua>> {
+    match key {
+        Value::Integer(index) => usize::try_from(index)
+            .map_err(|_| mlua::Error::RuntimeError(format!("Index {index} is negative")))
+            .and_then(|index| Ok(v.component(index)?))?
+            .to_lua(ctx),
+        Value::String(names) => match v.swizzle(names.to_str()?)? {
+            Swizzled::Float(v) => v.to_lua(ctx),
+            Swizzled::Vec2(v) => LuaVec2::new(v).to_lua(ctx),
+            Swizzled::Vec3(v) => LuaVec3::new(v).to_lua(ctx),
+            Swizzled::Vec4(v) => LuaVec4::new(v).to_lua(ctx),
+        },
+        _ => Err(mlua::Error::RuntimeError(format!(
+            "Vectors are indexed by integers or component names, not by `{}`",
+            key.type_name()
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::Vec3;
+    use tealr::mlu::mlua::FromLua;
+
+    use crate::common::vectors::{Expected, CONFORMANCE_CASES};
+
+    use super::*;
+
+    fn swizzled<'lua>(ctx: &'lua Lua, value: Value<'lua>) -> mlua::Result<Swizzled> {
+        Ok(match value {
+            Value::Integer(v) => Swizzled::Float(v as f32),
+            Value::Number(v) => Swizzled::Float(v as f32),
+            v @ Value::UserData(_) => {
+                if let Ok(v) = LuaVec2::from_lua(v.clone(), ctx) {
+                    Swizzled::Vec2(v.inner()?)
+                } else if let Ok(v) = LuaVec3::from_lua(v.clone(), ctx) {
+                    Swizzled::Vec3(v.inner()?)
+                } else {
+                    Swizzled::Vec4(LuaVec4::from_lua(v, ctx)?.inner()?)
+                }
+            }
+            v => {
+                return Err(mlua::Error::RuntimeError(format!(
+                    "Expected a number or vector, got `{}`",
+                    v.type_name()
+                )))
+            }
+        })
+    }
+
+    #[test]
+    fn conforms() {
+        let ctx = Lua::new();
+        let globals = ctx.globals();
+        globals
+            .set("a", LuaVec3::new(Vec3::new(1.0, 2.0, 3.0)))
+            .unwrap();
+        globals
+            .set("b", LuaVec3::new(Vec3::new(4.0, 5.0, 6.0)))
+            .unwrap();
+
+        for (expr, expected) in CONFORMANCE_CASES {
+            let value: Value = ctx
+                .load(&format!("return {expr}"))
+                .eval()
+                .unwrap_or_else(|e| panic!("`{expr}` failed: {e}"));
+            let actual = match value {
+                Value::Boolean(b) => Expected::Bool(b),
+                v => Expected::Value(swizzled(&ctx, v).unwrap()),
+            };
+            assert_eq!(actual, *expected, "`{expr}`");
+        }
+    }
+}
@@ -0,0 +1,65 @@
+use std::sync::Mutex;
+
+use bevy_mod_scripting_core::prelude::*;
+use bevy_mod_scripting_lua::{prelude::LuaDocFragment, tealr};
+
+use tealr::mlu::mlua::{self, Lua};
+
+use crate::common::bevy::{GetWorld, ScriptWorld};
+
+use super::{LuaEntity, LuaVec3};
+
+/// Provides the `vfx` global table, triggering visual effects through the game's [`crate::common::bevy::ScriptVfxBackend`]:
+///
+/// - `vfx.spawn(name, position)` spawns the named effect at the given `Vec3` and returns its entity
+/// - `vfx.stop(entity)` stops an effect spawned with `vfx.spawn`
+pub struct LuaVfxAPIProvider;
+
+fn world(ctx: &Lua) -> mlua::Result<ScriptWorld> {
+    ctx.get_world().map(ScriptWorld::new)
+}
+
+fn to_lua_error(e: ScriptError) -> mlua::Error {
+    mlua::Error::RuntimeError(e.to_string())
+}
+
+impl LuaVfxAPIProvider {
+    fn vfx_table(ctx: &Lua) -> mlua::Result<mlua::Table> {
+        let vfx = ctx.create_table()?;
+
+        vfx.set(
+            "spawn",
+            ctx.create_function(|ctx, (effect, position): (String, LuaVec3)| {
+                world(ctx)?
+                    .spawn_vfx(&effect, position.inner()?)
+                    .map(LuaEntity::new)
+                    .map_err(to_lua_error)
+            })?,
+        )?;
+
+        vfx.set(
+            "stop",
+            ctx.create_function(|ctx, entity: LuaEntity| {
+                world(ctx)?.stop_vfx(entity.inner()?).map_err(to_lua_error)
+            })?,
+        )?;
+
+        Ok(vfx)
+    }
+}
+
+impl APIProvider for LuaVfxAPIProvider {
+    type APITarget = Mutex<Lua>;
+    type ScriptContext = Mutex<Lua>;
+    type DocTarget = LuaDocFragment;
+
+    fn attach_api(&mut self, ctx: &mut Self::APITarget) -> Result<(), ScriptError> {
+        let ctx = ctx
+            .get_mut()
+            .expect("Unable to acquire lock on Lua context");
+
+        Self::vfx_table(ctx)
+            .and_then(|vfx| ctx.globals().set("vfx", vfx))
+            .map_err(ScriptError::new_other)
+    }
+}
@@ -0,0 +1,78 @@
+use std::{marker::PhantomData, sync::Mutex};
+
+use bevy::prelude::{App, EventReader};
+use bevy_mod_scripting_core::prelude::*;
+use bevy_mod_scripting_lua::{
+    prelude::{LuaDocFragment, LuaEvent},
+    tealr, LuaArg,
+};
+
+use tealr::mlu::mlua::Lua;
+
+use crate::common::bevy::{register_script_watches, ScriptFieldChanged};
+
+/// Forwards changes of fields watched with `world:watch(entity, path, hook)` to the scripts which registered the watches.
+///
+/// The hook given to `watch` is called with the arguments created from the [`ScriptFieldChanged`] event,
+/// usually the entity and the old and new values. Watches can be stopped with `world:unwatch(id)`.
+/// Hooks are sent with the priority given in `priority`.
+pub struct LuaWatchEventsProvider<A: LuaArg + From<ScriptFieldChanged>> {
+    pub priority: u32,
+    _ph: PhantomData<fn() -> A>,
+}
+
+impl<A: LuaArg + From<ScriptFieldChanged>> Default for LuaWatchEventsProvider<A> {
+    fn default() -> Self {
+        Self {
+            priority: 0,
+            _ph: Default::default(),
+        }
+    }
+}
+
+impl<A: LuaArg + From<ScriptFieldChanged>> LuaWatchEventsProvider<A> {
+    pub fn new(priority: u32) -> Self {
+        Self {
+            priority,
+            _ph: Default::default(),
+        }
+    }
+}
+
+fn forward_script_field_changes<A: LuaArg + From<ScriptFieldChanged>>(
+    mut events: EventReader<ScriptFieldChanged>,
+    mut writer: PriorityEventWriter<LuaEvent<A>>,
+    priority: u32,
+) {
+    for event in events.iter() {
+        writer.send(
+            LuaEvent {
+                hook_name: event.hook,
+                recipients: event.recipients.clone(),
+                args: event.clone().into(),
+            },
+            priority,
+        )
+    }
+}
+
+impl<A: LuaArg + From<ScriptFieldChanged>> APIProvider for LuaWatchEventsProvider<A> {
+    type APITarget = Mutex<Lua>;
+    type ScriptContext = Mutex<Lua>;
+    type DocTarget = LuaDocFragment;
+
+    fn attach_api(&mut self, _: &mut Self::APITarget) -> Result<(), ScriptError> {
+        Ok(())
+    }
+
+    fn register_with_app(&self, app: &mut App) {
+        register_script_watches(app);
+        let priority = self.priority;
+        app.add_system(
+            move |events: EventReader<ScriptFieldChanged>,
+                  writer: PriorityEventWriter<LuaEvent<A>>| {
+                forward_script_field_changes(events, writer, priority)
+            },
+        );
+    }
+}
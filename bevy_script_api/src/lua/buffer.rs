@@ -0,0 +1,187 @@
+use ::std::sync::Mutex;
+
+use bevy_mod_scripting_core::prelude::*;
+use bevy_mod_scripting_lua::{prelude::LuaDocFragment, tealr};
+
+use tealr::mlu::{
+    mlua::{self, Lua, MetaMethod, Table, Value},
+    TealData, TealDataMethods,
+};
+
+use crate::{
+    common::buffer::{BufferType, BufferValue, ScriptBuffer, SharedF32Buffer},
+    impl_tealr_type,
+};
+
+/// Provides the `buffer` global table, creating binary buffers implemented in Rust:
+///
+/// - `buffer.new(len)` creates a buffer of `len` zero bytes
+/// - `buffer.from_string(text)` and `buffer.from_base64(text)` create a buffer from the bytes of the text or the decoded base64
+///
+/// Buffers read and write values with `buf:read(type, offset)` and `buf:write(type, offset, value)`,
+/// where the type is one of `"u8"`, `"i8"`, `"u16"`, `"i16"`, `"u32"`, `"i32"`, `"i64"`, `"f32"` or `"f64"`.
+/// Offsets are in bytes and start at 0, unlike Lua tables.
+///
+/// [`SharedF32Buffer`] event arguments arrive as read-only arrays indexed from 1 like Lua tables.
+pub struct LuaBufferAPIProvider;
+
+pub type LuaScriptBuffer = ScriptBuffer;
+impl_tealr_type!(LuaScriptBuffer);
+
+pub type LuaSharedF32Buffer = SharedF32Buffer;
+impl_tealr_type!(LuaSharedF32Buffer);
+
+fn to_lua_error(e: ScriptError) -> mlua::Error {
+    mlua::Error::RuntimeError(e.to_string())
+}
+
+fn buffer_type(ty: &str) -> mlua::Result<BufferType> {
+    ty.parse().map_err(mlua::Error::RuntimeError)
+}
+
+impl TealData for LuaScriptBuffer {
+    fn add_methods<'lua, T: TealDataMethods<'lua, Self>>(methods: &mut T) {
+        methods.document_type(
+            "A block of bytes created with `buffer.new`, copies of a buffer share its bytes.",
+        );
+
+        methods.add_meta_method(MetaMethod::Len, |_, s, ()| Ok(s.len()));
+        methods.add_meta_method(MetaMethod::ToString, |_, s, ()| {
+            Ok(format!("Buffer({} bytes)", s.len()))
+        });
+
+        methods.document("Returns the length of the buffer in bytes.");
+        methods.add_method("len", |_, s, ()| Ok(s.len()));
+
+        methods.document("Changes the length of the buffer, new bytes are zero.");
+        methods.add_method("resize", |_, s, len: usize| {
+            s.resize(len);
+            Ok(())
+        });
+
+        methods.document("Reads the value of the given type, such as `\"u16\"` or `\"f32\"`, at the byte offset.");
+        methods.add_method("read", |_, s, (ty, offset): (String, usize)| {
+            match s.read(buffer_type(&ty)?, offset).map_err(to_lua_error)? {
+                BufferValue::Integer(i) => Ok(Value::Integer(i)),
+                BufferValue::Float(f) => Ok(Value::Number(f)),
+            }
+        });
+
+        methods.document("Writes the value as the given type, such as `\"u16\"` or `\"f32\"`, at the byte offset.");
+        methods.add_method(
+            "write",
+            |_, s, (ty, offset, value): (String, usize, Value)| {
+                let value = match value {
+                    Value::Integer(i) => BufferValue::Integer(i),
+                    Value::Number(f) => BufferValue::Float(f),
+                    v => {
+                        return Err(mlua::Error::FromLuaConversionError {
+                            from: v.type_name(),
+                            to: "number",
+                            message: None,
+                        })
+                    }
+                };
+                s.write(buffer_type(&ty)?, offset, value)
+                    .map_err(to_lua_error)
+            },
+        );
+
+        methods.document("Reads `len` bytes at the byte offset as UTF-8 text.");
+        methods.add_method("read_string", |_, s, (offset, len): (usize, usize)| {
+            s.read_string(offset, len).map_err(to_lua_error)
+        });
+
+        methods.document(
+            "Writes the text as UTF-8 at the byte offset, returns the number of bytes written.",
+        );
+        methods.add_method("write_string", |_, s, (offset, text): (usize, String)| {
+            s.write_string(offset, &text).map_err(to_lua_error)
+        });
+
+        methods.document(
+            "Returns a new buffer with a copy of the bytes from `start` up to but excluding `end`.",
+        );
+        methods.add_method("slice", |_, s, (start, end): (usize, usize)| {
+            s.slice(start, end).map_err(to_lua_error)
+        });
+
+        methods.document("Returns the bytes encoded as base64.");
+        methods.add_method("to_base64", |_, s, ()| Ok(s.to_base64()));
+
+        methods.document("Returns the bytes as a Lua string.");
+        methods.add_method("to_string", |ctx, s, ()| ctx.create_string(&s.to_bytes()));
+    }
+}
+
+impl TealData for LuaSharedF32Buffer {
+    fn add_methods<'lua, T: TealDataMethods<'lua, Self>>(methods: &mut T) {
+        methods.document_type(
+            "A read-only array of numbers shared with Rust, elements are indexed from 1 and copied only when read.",
+        );
+
+        methods.add_meta_method(MetaMethod::Len, |_, s, ()| Ok(s.len()));
+        methods.add_meta_method(MetaMethod::Index, |_, s, index: usize| {
+            Ok(index.checked_sub(1).and_then(|i| s.get(i)))
+        });
+        methods.add_meta_method(MetaMethod::ToString, |_, s, ()| {
+            Ok(format!("F32Buffer({} values)", s.len()))
+        });
+
+        methods.document("Returns the number of values.");
+        methods.add_method("len", |_, s, ()| Ok(s.len()));
+
+        methods.document("Returns a copy of the values as a Lua table.");
+        methods.add_method("to_table", |ctx, s, ()| {
+            ctx.create_sequence_from(s.0.iter().copied())
+        });
+    }
+}
+
+impl LuaBufferAPIProvider {
+    fn buffer_table(ctx: &Lua) -> mlua::Result<Table> {
+        let table = ctx.create_table()?;
+
+        table.set(
+            "new",
+            ctx.create_function(|_, len: usize| Ok(ScriptBuffer::new(len)))?,
+        )?;
+        table.set(
+            "from_string",
+            ctx.create_function(|_, text: mlua::String| {
+                Ok(ScriptBuffer::from_bytes(text.as_bytes().to_vec()))
+            })?,
+        )?;
+        table.set(
+            "from_base64",
+            ctx.create_function(|_, text: String| {
+                ScriptBuffer::from_base64(&text).map_err(to_lua_error)
+            })?,
+        )?;
+
+        Ok(table)
+    }
+}
+
+impl APIProvider for LuaBufferAPIProvider {
+    type APITarget = Mutex<Lua>;
+    type ScriptContext = Mutex<Lua>;
+    type DocTarget = LuaDocFragment;
+
+    fn attach_api(&mut self, ctx: &mut Self::APITarget) -> Result<(), ScriptError> {
+        let ctx = ctx
+            .get_mut()
+            .expect("Unable to acquire lock on Lua context");
+
+        Self::buffer_table(ctx)
+            .and_then(|buffer| ctx.globals().set("buffer", buffer))
+            .map_err(ScriptError::new_other)
+    }
+
+    fn get_doc_fragment(&self) -> Option<Self::DocTarget> {
+        Some(LuaDocFragment::new("BufferAPI", |tw| {
+            tw.process_type::<LuaScriptBuffer>()
+                .process_type::<LuaSharedF32Buffer>()
+        }))
+    }
+}
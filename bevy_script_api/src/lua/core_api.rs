@@ -0,0 +1,43 @@
+use std::sync::Mutex;
+
+use bevy_mod_scripting_core::prelude::*;
+use bevy_mod_scripting_lua::{prelude::LuaDocFragment, tealr::mlu::mlua::Lua};
+
+use super::bevy::LuaBevyAPIProvider;
+
+/// The bevy API modules enabled by the `api_*` cargo features, grouped into one provider
+pub type LuaCoreAPIProvider = CompositeAPIProvider<Mutex<Lua>, Mutex<Lua>, LuaDocFragment>;
+
+/// Groups the [`LuaBevyAPIProvider`] with the providers of every enabled bevy API module:
+/// ```rust,ignore
+/// app.add_script_host::<LuaScriptHost<MyArgs>, _>(CoreStage::PostUpdate)
+///     .add_api_provider::<LuaScriptHost<MyArgs>>(Box::new(lua_core_api()));
+/// ```
+/// The `core_api` feature enables all modules. The reflection and hierarchy modules
+/// only change the methods of `world`, so they add no providers of their own.
+pub fn lua_core_api() -> LuaCoreAPIProvider {
+    #[allow(unused_mut)]
+    let mut api = LuaCoreAPIProvider::new("core_api").with(LuaBevyAPIProvider);
+    #[cfg(feature = "api_math")]
+    {
+        api = api
+            .with(super::mathx::LuaMathxAPIProvider)
+            .with(super::fixed::LuaFixedAPIProvider::default());
+    }
+    #[cfg(feature = "api_time")]
+    {
+        api = api
+            .with(super::bevy::LuaTimeAPIProvider)
+            .with(super::bevy::LuaTimerAPIProvider)
+            .with(super::instant::LuaInstantAPIProvider);
+    }
+    #[cfg(feature = "api_input")]
+    {
+        api = api.with(super::bevy::LuaInputAPIProvider);
+    }
+    #[cfg(feature = "api_assets")]
+    {
+        api = api.with(super::bevy::LuaAssetsAPIProvider);
+    }
+    api
+}
@@ -0,0 +1,278 @@
+use ::std::sync::Mutex;
+
+use bevy::log::warn;
+use bevy_mod_scripting_core::prelude::*;
+use bevy_mod_scripting_lua::{prelude::LuaDocFragment, tealr};
+
+use tealr::mlu::{
+    mlua::{self, Lua, MetaMethod, Table, Value},
+    TealData, TealDataMethods,
+};
+
+use crate::{
+    common::fixed::{float_math_warning, Fixed, FixedVec2, FixedVec3},
+    impl_tealr_type,
+};
+
+/// Provides the `fixed` global table, containing deterministic fixed-point math implemented in Rust:
+///
+/// - `fixed.new(value)` creates a number from an integer or a decimal string such as `"1.25"`
+/// - `fixed.ratio(num, den)` creates the number `num / den`
+/// - `fixed.vec2(x, y)` and `fixed.vec3(x, y, z)` create vectors, the components are given like in `fixed.new`
+///
+/// Numbers and vectors support the arithmetic and comparison operators, mixed with integers where sensible.
+/// Floats are never converted implicitly, since their values may differ between platforms.
+///
+/// If `warn_float_math` is set, a warning is logged the first time each script calls any of the float functions
+/// of the `math` and `mathx` tables.
+#[derive(Default)]
+pub struct LuaFixedAPIProvider {
+    pub warn_float_math: bool,
+}
+
+/// Functions of the `math` table which are deterministic for integer arguments
+const DETERMINISTIC_MATH_FUNCTIONS: &[&str] = &["abs", "max", "min", "tointeger", "type", "ult"];
+
+pub type LuaFixed = Fixed;
+impl_tealr_type!(LuaFixed);
+
+pub type LuaFixedVec2 = FixedVec2;
+impl_tealr_type!(LuaFixedVec2);
+
+pub type LuaFixedVec3 = FixedVec3;
+impl_tealr_type!(LuaFixedVec3);
+
+/// Converts fixed-point numbers, integers and decimal strings to fixed-point numbers
+fn to_fixed(value: Value) -> mlua::Result<Fixed> {
+    match value {
+        Value::Integer(i) => Ok(Fixed::from_int(i)),
+        Value::String(s) => s.to_str()?.parse().map_err(mlua::Error::RuntimeError),
+        Value::UserData(u) if u.is::<LuaFixed>() => Ok(*u.borrow::<LuaFixed>()?),
+        v => Err(mlua::Error::FromLuaConversionError {
+            from: v.type_name(),
+            to: "Fixed",
+            message: Some(
+                "expected a fixed-point number, an integer or a decimal string, floats are not converted since they are not deterministic".to_owned(),
+            ),
+        }),
+    }
+}
+
+fn division_by_zero() -> mlua::Error {
+    mlua::Error::RuntimeError("Fixed-point division by zero".to_owned())
+}
+
+impl TealData for LuaFixed {
+    fn add_methods<'lua, T: TealDataMethods<'lua, Self>>(methods: &mut T) {
+        methods.document_type("A deterministic fixed-point number, created with `fixed.new`.");
+
+        methods.add_meta_function(MetaMethod::Add, |_, (a, b): (Value, Value)| {
+            Ok(to_fixed(a)? + to_fixed(b)?)
+        });
+        methods.add_meta_function(MetaMethod::Sub, |_, (a, b): (Value, Value)| {
+            Ok(to_fixed(a)? - to_fixed(b)?)
+        });
+        methods.add_meta_function(MetaMethod::Mul, |_, (a, b): (Value, Value)| {
+            Ok(to_fixed(a)? * to_fixed(b)?)
+        });
+        methods.add_meta_function(MetaMethod::Div, |_, (a, b): (Value, Value)| {
+            to_fixed(a)?
+                .checked_div(to_fixed(b)?)
+                .ok_or_else(division_by_zero)
+        });
+        methods.add_meta_method(MetaMethod::Unm, |_, s, ()| Ok(-*s));
+        methods.add_meta_function(MetaMethod::Eq, |_, (a, b): (Value, Value)| {
+            Ok(to_fixed(a)? == to_fixed(b)?)
+        });
+        methods.add_meta_function(MetaMethod::Lt, |_, (a, b): (Value, Value)| {
+            Ok(to_fixed(a)? < to_fixed(b)?)
+        });
+        methods.add_meta_function(MetaMethod::Le, |_, (a, b): (Value, Value)| {
+            Ok(to_fixed(a)? <= to_fixed(b)?)
+        });
+        methods.add_meta_method(MetaMethod::ToString, |_, s, ()| Ok(s.to_string()));
+
+        methods.document(
+            "Returns the largest integer less than or equal to the number, as an integer.",
+        );
+        methods.add_method("floor", |_, s, ()| Ok(s.to_int()));
+
+        methods.document("Returns the absolute value of the number.");
+        methods.add_method("abs", |_, s, ()| Ok(s.abs()));
+
+        methods.document("Returns the square root of the number, errors for negative numbers.");
+        methods.add_method("sqrt", |_, s, ()| {
+            s.sqrt().ok_or_else(|| {
+                mlua::Error::RuntimeError(format!("Cannot take the square root of {s}"))
+            })
+        });
+
+        methods.document("Returns the number as a float, for display or float based APIs only.");
+        methods.add_method("to_number", |_, s, ()| Ok(s.to_f64()));
+    }
+}
+
+impl TealData for LuaFixedVec2 {
+    fn add_methods<'lua, T: TealDataMethods<'lua, Self>>(methods: &mut T) {
+        methods.document_type("A 2D vector of fixed-point numbers, created with `fixed.vec2`.");
+
+        methods.add_meta_method(MetaMethod::Add, |_, s, rhs: LuaFixedVec2| Ok(*s + rhs));
+        methods.add_meta_method(MetaMethod::Sub, |_, s, rhs: LuaFixedVec2| Ok(*s - rhs));
+        methods.add_meta_method(MetaMethod::Mul, |_, s, rhs: Value| Ok(*s * to_fixed(rhs)?));
+        methods.add_meta_method(MetaMethod::Unm, |_, s, ()| Ok(-*s));
+        methods.add_meta_method(MetaMethod::Eq, |_, s, rhs: LuaFixedVec2| Ok(*s == rhs));
+        methods.add_meta_method(MetaMethod::ToString, |_, s, ()| Ok(s.to_string()));
+
+        methods.document("Returns the x component.");
+        methods.add_method("x", |_, s, ()| Ok(s.x));
+        methods.document("Returns the y component.");
+        methods.add_method("y", |_, s, ()| Ok(s.y));
+
+        methods.document("Returns the dot product with the other vector.");
+        methods.add_method("dot", |_, s, rhs: LuaFixedVec2| Ok(s.dot(rhs)));
+        methods.document("Returns the length of the vector.");
+        methods.add_method("length", |_, s, ()| Ok(s.length()));
+        methods.document(
+            "Returns the vector scaled to a length of 1, or the zero vector if its length is 0.",
+        );
+        methods.add_method("normalize", |_, s, ()| Ok(s.normalize_or_zero()));
+    }
+}
+
+impl TealData for LuaFixedVec3 {
+    fn add_methods<'lua, T: TealDataMethods<'lua, Self>>(methods: &mut T) {
+        methods.document_type("A 3D vector of fixed-point numbers, created with `fixed.vec3`.");
+
+        methods.add_meta_method(MetaMethod::Add, |_, s, rhs: LuaFixedVec3| Ok(*s + rhs));
+        methods.add_meta_method(MetaMethod::Sub, |_, s, rhs: LuaFixedVec3| Ok(*s - rhs));
+        methods.add_meta_method(MetaMethod::Mul, |_, s, rhs: Value| Ok(*s * to_fixed(rhs)?));
+        methods.add_meta_method(MetaMethod::Unm, |_, s, ()| Ok(-*s));
+        methods.add_meta_method(MetaMethod::Eq, |_, s, rhs: LuaFixedVec3| Ok(*s == rhs));
+        methods.add_meta_method(MetaMethod::ToString, |_, s, ()| Ok(s.to_string()));
+
+        methods.document("Returns the x component.");
+        methods.add_method("x", |_, s, ()| Ok(s.x));
+        methods.document("Returns the y component.");
+        methods.add_method("y", |_, s, ()| Ok(s.y));
+        methods.document("Returns the z component.");
+        methods.add_method("z", |_, s, ()| Ok(s.z));
+
+        methods.document("Returns the dot product with the other vector.");
+        methods.add_method("dot", |_, s, rhs: LuaFixedVec3| Ok(s.dot(rhs)));
+        methods.document("Returns the cross product with the other vector.");
+        methods.add_method("cross", |_, s, rhs: LuaFixedVec3| Ok(s.cross(rhs)));
+        methods.document("Returns the length of the vector.");
+        methods.add_method("length", |_, s, ()| Ok(s.length()));
+        methods.document(
+            "Returns the vector scaled to a length of 1, or the zero vector if its length is 0.",
+        );
+        methods.add_method("normalize", |_, s, ()| Ok(s.normalize_or_zero()));
+    }
+}
+
+impl LuaFixedAPIProvider {
+    fn fixed_table(ctx: &Lua) -> mlua::Result<Table> {
+        let table = ctx.create_table()?;
+
+        table.set(
+            "new",
+            ctx.create_function(|_, value: Value| to_fixed(value))?,
+        )?;
+        table.set(
+            "ratio",
+            ctx.create_function(|_, (num, den): (i64, i64)| {
+                Fixed::from_ratio(num, den).ok_or_else(division_by_zero)
+            })?,
+        )?;
+        table.set(
+            "vec2",
+            ctx.create_function(|_, (x, y): (Value, Value)| {
+                Ok(FixedVec2::new(to_fixed(x)?, to_fixed(y)?))
+            })?,
+        )?;
+        table.set(
+            "vec3",
+            ctx.create_function(|_, (x, y, z): (Value, Value, Value)| {
+                Ok(FixedVec3::new(to_fixed(x)?, to_fixed(y)?, to_fixed(z)?))
+            })?,
+        )?;
+
+        Ok(table)
+    }
+
+    /// Replaces the float functions of the `math` and `mathx` tables with ones warning on their first call
+    fn warn_on_float_math(ctx: &Lua, script: &str) -> mlua::Result<()> {
+        let script = script.to_owned();
+        let warn = ctx.create_function(move |_, function: String| {
+            warn!("{}", float_math_warning(&script, &function));
+            Ok(())
+        })?;
+
+        ctx.load(
+            r#"
+            local warn, deterministic = ...
+            for _, name in ipairs({ "math", "mathx" }) do
+                local lib = _G[name]
+                if type(lib) == "table" then
+                    for key, f in pairs(lib) do
+                        if type(f) == "function" and not (name == "math" and deterministic[key]) then
+                            local warned = false
+                            lib[key] = function(...)
+                                if not warned then
+                                    warned = true
+                                    warn(name .. "." .. key)
+                                end
+                                return f(...)
+                            end
+                        end
+                    end
+                end
+            end
+            "#,
+        )
+        .set_name("warn_on_float_math")?
+        .call((
+            warn,
+            ctx.create_table_from(DETERMINISTIC_MATH_FUNCTIONS.iter().map(|f| (*f, true)))?,
+        ))
+    }
+}
+
+impl APIProvider for LuaFixedAPIProvider {
+    type APITarget = Mutex<Lua>;
+    type ScriptContext = Mutex<Lua>;
+    type DocTarget = LuaDocFragment;
+
+    fn attach_api(&mut self, ctx: &mut Self::APITarget) -> Result<(), ScriptError> {
+        let ctx = ctx
+            .get_mut()
+            .expect("Unable to acquire lock on Lua context");
+
+        Self::fixed_table(ctx)
+            .and_then(|fixed| ctx.globals().set("fixed", fixed))
+            .map_err(ScriptError::new_other)
+    }
+
+    fn setup_script(
+        &mut self,
+        script_data: &ScriptData,
+        ctx: &mut Self::ScriptContext,
+    ) -> Result<(), ScriptError> {
+        if !self.warn_float_math {
+            return Ok(());
+        }
+
+        let ctx = ctx
+            .get_mut()
+            .expect("Unable to acquire lock on Lua context");
+        Self::warn_on_float_math(ctx, script_data.name).map_err(ScriptError::new_other)
+    }
+
+    fn get_doc_fragment(&self) -> Option<Self::DocTarget> {
+        Some(LuaDocFragment::new("FixedAPI", |tw| {
+            tw.process_type::<LuaFixed>()
+                .process_type::<LuaFixedVec2>()
+                .process_type::<LuaFixedVec3>()
+        }))
+    }
+}
@@ -0,0 +1,125 @@
+use ::std::sync::Mutex;
+
+use bevy_mod_scripting_core::{prelude::*, world::WorldPointer};
+use bevy_mod_scripting_lua::{prelude::LuaDocFragment, tealr};
+
+use tealr::mlu::mlua::{self, Lua, Table, Value};
+
+use crate::common::fmt::{self, FmtValue, ScriptLocale, ScriptLocales};
+
+/// Provides the `fmt` global, formatting user facing text with the locale of the script, see [`ScriptLocales`]:
+///
+/// - `fmt(template, values)` or `fmt.format(template, values)` replaces `{name}` in the template with `values.name`,
+///   `{name:2}` formats a number with 2 decimals
+/// - `fmt.number(value, [decimals])` formats a number with digit grouping, `fmt.number(1234.5, 2)` gives `1,234.50`
+/// - `fmt.duration(seconds)` formats seconds as a clock, `fmt.duration(125)` gives `2:05`
+pub struct LuaFmtAPIProvider;
+
+fn to_lua_error(e: ScriptError) -> mlua::Error {
+    mlua::Error::RuntimeError(e.to_string())
+}
+
+fn locale(ctx: &Lua) -> ScriptLocale {
+    ctx.app_data_ref::<ScriptLocale>()
+        .map(|l| l.clone())
+        .unwrap_or_default()
+}
+
+fn format(ctx: &Lua, template: String, values: Option<Table>) -> mlua::Result<String> {
+    let locale = locale(ctx);
+    let mut lookup_error = None;
+    let out = fmt::interpolate(&template, &locale, |name| {
+        let value = match &values {
+            Some(values) => values.get::<_, Value>(name),
+            None => Ok(Value::Nil),
+        };
+        match value {
+            Ok(Value::String(s)) => s.to_str().ok().map(|s| FmtValue::String(s.to_owned())),
+            Ok(Value::Integer(i)) => Some(FmtValue::Integer(i)),
+            Ok(Value::Number(n)) => Some(FmtValue::Float(n)),
+            Ok(Value::Boolean(b)) => Some(FmtValue::Bool(b)),
+            Ok(_) => None,
+            Err(e) => {
+                lookup_error = Some(e);
+                None
+            }
+        }
+    });
+    match lookup_error {
+        Some(e) => Err(e),
+        None => out.map_err(to_lua_error),
+    }
+}
+
+impl LuaFmtAPIProvider {
+    fn fmt_table(ctx: &Lua) -> mlua::Result<Table> {
+        let table = ctx.create_table()?;
+
+        table.set(
+            "format",
+            ctx.create_function(|ctx, (template, values): (String, Option<Table>)| {
+                format(ctx, template, values)
+            })?,
+        )?;
+        table.set(
+            "number",
+            ctx.create_function(|ctx, (value, decimals): (f64, Option<usize>)| {
+                Ok(fmt::format_number(
+                    value,
+                    decimals.unwrap_or_default(),
+                    &locale(ctx),
+                ))
+            })?,
+        )?;
+        table.set(
+            "duration",
+            ctx.create_function(|_, seconds: f64| Ok(fmt::format_duration(seconds)))?,
+        )?;
+
+        let meta = ctx.create_table()?;
+        meta.set(
+            "__call",
+            ctx.create_function(
+                |ctx, (_, template, values): (Table, String, Option<Table>)| {
+                    format(ctx, template, values)
+                },
+            )?,
+        )?;
+        table.set_metatable(Some(meta));
+
+        Ok(table)
+    }
+}
+
+impl APIProvider for LuaFmtAPIProvider {
+    type APITarget = Mutex<Lua>;
+    type ScriptContext = Mutex<Lua>;
+    type DocTarget = LuaDocFragment;
+
+    fn attach_api(&mut self, ctx: &mut Self::APITarget) -> Result<(), ScriptError> {
+        let ctx = ctx
+            .get_mut()
+            .expect("Unable to acquire lock on Lua context");
+
+        Self::fmt_table(ctx)
+            .and_then(|fmt| ctx.globals().set("fmt", fmt))
+            .map_err(ScriptError::new_other)
+    }
+
+    fn setup_script_runtime(
+        &mut self,
+        world_ptr: WorldPointer,
+        script_data: &ScriptData,
+        ctx: &mut Self::ScriptContext,
+    ) -> Result<(), ScriptError> {
+        let locale = world_ptr
+            .read()
+            .get_resource::<ScriptLocales>()
+            .map(|locales| locales.for_script(script_data.name).clone())
+            .unwrap_or_default();
+        ctx.get_mut()
+            .expect("Unable to acquire lock on Lua context")
+            .set_app_data(locale);
+        Ok(())
+    }
+}
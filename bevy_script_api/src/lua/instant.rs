@@ -0,0 +1,151 @@
+use ::std::sync::Mutex;
+
+use bevy_mod_scripting_core::prelude::*;
+use bevy_mod_scripting_lua::{prelude::LuaDocFragment, tealr};
+
+use tealr::mlu::{
+    mlua::{self, Lua, MetaMethod, Table, Value},
+    TealData, TealDataMethods,
+};
+
+use crate::{
+    common::instant::{ScriptDuration, ScriptInstant},
+    impl_tealr_type,
+};
+
+/// Provides the `Instant` and `Duration` global tables, monotonic timestamps and spans of time:
+///
+/// - `Instant.now()` returns the current instant, `instant:elapsed()` the duration passed since
+/// - `Duration.from_secs(s)`, `Duration.from_millis(ms)` and `Duration.from_micros(us)` create durations
+///
+/// Subtracting two instants gives the duration between them, and instants can be offset by adding durations.
+/// Durations can be added, subtracted, multiplied and divided by numbers, and compared.
+pub struct LuaInstantAPIProvider;
+
+pub type LuaInstant = ScriptInstant;
+impl_tealr_type!(LuaInstant);
+
+pub type LuaDuration = ScriptDuration;
+impl_tealr_type!(LuaDuration);
+
+impl TealData for LuaInstant {
+    fn add_methods<'lua, T: TealDataMethods<'lua, Self>>(methods: &mut T) {
+        methods.document_type(
+            "A point in time measured by a monotonic clock, created with `Instant.now()`.",
+        );
+
+        methods.add_meta_method(MetaMethod::Add, |_, s, rhs: LuaDuration| Ok(*s + rhs));
+        methods.add_meta_method(MetaMethod::Sub, |_, s, rhs: LuaInstant| Ok(*s - rhs));
+        methods.add_meta_method(MetaMethod::Eq, |_, s, rhs: LuaInstant| Ok(*s == rhs));
+        methods.add_meta_method(MetaMethod::Lt, |_, s, rhs: LuaInstant| Ok(*s < rhs));
+        methods.add_meta_method(MetaMethod::Le, |_, s, rhs: LuaInstant| Ok(*s <= rhs));
+        methods.add_meta_method(MetaMethod::ToString, |_, s, ()| Ok(s.to_string()));
+
+        methods.document("Returns the duration passed since this instant.");
+        methods.add_method("elapsed", |_, s, ()| Ok(s.elapsed()));
+
+        methods.document("Returns the duration passed between the earlier instant and this one, zero if it is not earlier.");
+        methods.add_method("duration_since", |_, s, earlier: LuaInstant| {
+            Ok(s.duration_since(earlier))
+        });
+    }
+}
+
+impl TealData for LuaDuration {
+    fn add_methods<'lua, T: TealDataMethods<'lua, Self>>(methods: &mut T) {
+        methods.document_type("A span of time which is never negative, created with `Duration.from_secs` or by subtracting instants.");
+
+        methods.add_meta_method(MetaMethod::Add, |_, s, rhs: LuaDuration| Ok(*s + rhs));
+        methods.add_meta_method(MetaMethod::Sub, |_, s, rhs: LuaDuration| Ok(*s - rhs));
+        methods.add_meta_function(MetaMethod::Mul, |_, (a, b): (Value, Value)| match (a, b) {
+            (Value::UserData(d), factor) | (factor, Value::UserData(d)) => {
+                let factor = match factor {
+                    Value::Integer(i) => i as f64,
+                    Value::Number(n) => n,
+                    v => {
+                        return Err(mlua::Error::FromLuaConversionError {
+                            from: v.type_name(),
+                            to: "number",
+                            message: None,
+                        })
+                    }
+                };
+                Ok(d.borrow::<LuaDuration>()?.mul_f64(factor))
+            }
+            _ => Err(mlua::Error::RuntimeError(
+                "Durations can only be multiplied by numbers".to_owned(),
+            )),
+        });
+        methods.add_meta_method(MetaMethod::Div, |_, s, divisor: f64| {
+            s.div_f64(divisor).ok_or_else(|| {
+                mlua::Error::RuntimeError("Cannot divide a duration by zero".to_owned())
+            })
+        });
+        methods.add_meta_method(MetaMethod::Eq, |_, s, rhs: LuaDuration| Ok(*s == rhs));
+        methods.add_meta_method(MetaMethod::Lt, |_, s, rhs: LuaDuration| Ok(*s < rhs));
+        methods.add_meta_method(MetaMethod::Le, |_, s, rhs: LuaDuration| Ok(*s <= rhs));
+        methods.add_meta_method(MetaMethod::ToString, |_, s, ()| Ok(s.to_string()));
+
+        methods.document("Returns the duration in seconds.");
+        methods.add_method("as_secs", |_, s, ()| Ok(s.as_secs()));
+
+        methods.document("Returns the duration in milliseconds.");
+        methods.add_method("as_millis", |_, s, ()| Ok(s.as_millis()));
+
+        methods.document("Returns the duration in microseconds.");
+        methods.add_method("as_micros", |_, s, ()| Ok(s.as_micros()));
+    }
+}
+
+impl LuaInstantAPIProvider {
+    fn instant_table(ctx: &Lua) -> mlua::Result<Table> {
+        let table = ctx.create_table()?;
+        table.set(
+            "now",
+            ctx.create_function(|_, ()| Ok(ScriptInstant::now()))?,
+        )?;
+        Ok(table)
+    }
+
+    fn duration_table(ctx: &Lua) -> mlua::Result<Table> {
+        let table = ctx.create_table()?;
+        table.set(
+            "from_secs",
+            ctx.create_function(|_, secs: f64| Ok(ScriptDuration::from_secs(secs)))?,
+        )?;
+        table.set(
+            "from_millis",
+            ctx.create_function(|_, millis: f64| Ok(ScriptDuration::from_millis(millis)))?,
+        )?;
+        table.set(
+            "from_micros",
+            ctx.create_function(|_, micros: f64| Ok(ScriptDuration::from_micros(micros)))?,
+        )?;
+        Ok(table)
+    }
+}
+
+impl APIProvider for LuaInstantAPIProvider {
+    type APITarget = Mutex<Lua>;
+    type ScriptContext = Mutex<Lua>;
+    type DocTarget = LuaDocFragment;
+
+    fn attach_api(&mut self, ctx: &mut Self::APITarget) -> Result<(), ScriptError> {
+        let ctx = ctx
+            .get_mut()
+            .expect("Unable to acquire lock on Lua context");
+
+        Self::instant_table(ctx)
+            .and_then(|instant| ctx.globals().set("Instant", instant))
+            .and_then(|_| Self::duration_table(ctx))
+            .and_then(|duration| ctx.globals().set("Duration", duration))
+            .map_err(ScriptError::new_other)
+    }
+
+    fn get_doc_fragment(&self) -> Option<Self::DocTarget> {
+        Some(LuaDocFragment::new("InstantAPI", |tw| {
+            tw.process_type::<LuaInstant>()
+                .process_type::<LuaDuration>()
+        }))
+    }
+}
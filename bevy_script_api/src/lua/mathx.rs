@@ -0,0 +1,93 @@
+use ::std::sync::Mutex;
+
+use bevy_mod_scripting_core::prelude::*;
+use bevy_mod_scripting_lua::{prelude::LuaDocFragment, tealr};
+
+use tealr::mlu::mlua::{self, Lua, Table};
+
+use crate::{
+    common::mathx::{self, Easing},
+    lua::bevy::LuaQuat,
+};
+
+/// Provides the `mathx` global table, containing math utilities implemented in Rust:
+///
+/// - `mathx.lerp(a, b, t)`, `mathx.inverse_lerp(a, b, value)` and `mathx.remap(value, from_min, from_max, to_min, to_max)`
+/// - `mathx.clamp(value, min, max)`
+/// - `mathx.slerp(a, b, t)` for `Quat` rotations
+/// - `mathx.ease(name, t)` applying an easing function such as `"ease_in_out_cubic"` to `t`
+/// - `mathx.noise2(seed, x, y)` and `mathx.noise3(seed, x, y, z)`, seeded gradient noise in the range [-1,1]
+pub struct LuaMathxAPIProvider;
+
+impl LuaMathxAPIProvider {
+    fn mathx_table(ctx: &Lua) -> mlua::Result<Table> {
+        let table = ctx.create_table()?;
+
+        table.set(
+            "lerp",
+            ctx.create_function(|_, (a, b, t): (f32, f32, f32)| Ok(mathx::lerp(a, b, t)))?,
+        )?;
+        table.set(
+            "inverse_lerp",
+            ctx.create_function(|_, (a, b, value): (f32, f32, f32)| {
+                Ok(mathx::inverse_lerp(a, b, value))
+            })?,
+        )?;
+        table.set(
+            "remap",
+            ctx.create_function(
+                |_, (value, from_min, from_max, to_min, to_max): (f32, f32, f32, f32, f32)| {
+                    Ok(mathx::remap(value, from_min, from_max, to_min, to_max))
+                },
+            )?,
+        )?;
+        table.set(
+            "clamp",
+            ctx.create_function(|_, (value, min, max): (f32, f32, f32)| {
+                Ok(mathx::clamp(value, min, max))
+            })?,
+        )?;
+        table.set(
+            "slerp",
+            ctx.create_function(|_, (a, b, t): (LuaQuat, LuaQuat, f32)| {
+                Ok(LuaQuat::new(mathx::slerp(a.inner()?, b.inner()?, t)))
+            })?,
+        )?;
+        table.set(
+            "ease",
+            ctx.create_function(|_, (name, t): (String, f32)| {
+                name.parse::<Easing>()
+                    .map(|easing| easing.ease(t))
+                    .map_err(mlua::Error::RuntimeError)
+            })?,
+        )?;
+        table.set(
+            "noise2",
+            ctx.create_function(|_, (seed, x, y): (u32, f32, f32)| Ok(mathx::noise2(seed, x, y)))?,
+        )?;
+        table.set(
+            "noise3",
+            ctx.create_function(|_, (seed, x, y, z): (u32, f32, f32, f32)| {
+                Ok(mathx::noise3(seed, x, y, z))
+            })?,
+        )?;
+
+        Ok(table)
+    }
+}
+
+impl APIProvider for LuaMathxAPIProvider {
+    type APITarget = Mutex<Lua>;
+    type ScriptContext = Mutex<Lua>;
+    type DocTarget = LuaDocFragment;
+
+    fn attach_api(&mut self, ctx: &mut Self::APITarget) -> Result<(), ScriptError> {
+        let ctx = ctx
+            .get_mut()
+            .expect("Unable to acquire lock on Lua context");
+
+        Self::mathx_table(ctx)
+            .and_then(|mathx| ctx.globals().set("mathx", mathx))
+            .map_err(ScriptError::new_other)
+    }
+}
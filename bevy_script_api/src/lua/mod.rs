@@ -1,7 +1,9 @@
 use ::std::any::TypeId;
 use ::std::borrow::Cow;
+use ::std::collections::HashSet;
+use ::std::ffi::c_void;
 
-use crate::common::bevy::GetWorld;
+use crate::common::{bevy::GetWorld, shared::ScriptValue};
 use crate::impl_tealr_type;
 use ::bevy::prelude::{App, AppTypeRegistry};
 
@@ -22,7 +24,18 @@ use crate::script_ref::{ReflectedValue, ScriptRef, ValueIndex};
 use self::bevy::LuaWorld;
 
 pub mod bevy;
+pub mod buffer;
+pub mod core_api;
+#[cfg(feature = "api_math")]
+pub mod fixed;
+pub mod fmt;
+#[cfg(feature = "api_time")]
+pub mod instant;
+#[cfg(feature = "api_math")]
+pub mod mathx;
+pub mod run_once;
 pub mod std;
+pub mod testing;
 pub mod util;
 
 /// A trait allowing to register the [`LuaProxyable`] trait with the type registry for foreign types
@@ -305,3 +318,146 @@ impl GetWorld for Lua {
         self.globals().get::<_, LuaWorld>("world").map(Into::into)
     }
 }
+
+impl<'lua> ToLua<'lua> for ScriptValue {
+    fn to_lua(self, ctx: &'lua Lua) -> mlua::Result<Value<'lua>> {
+        Ok(match self {
+            ScriptValue::Nil => Value::Nil,
+            ScriptValue::Bool(b) => Value::Boolean(b),
+            ScriptValue::Integer(i) => Value::Integer(i as mlua::Integer),
+            ScriptValue::Number(n) => Value::Number(n),
+            ScriptValue::String(s) => Value::String(ctx.create_string(&s)?),
+            ScriptValue::List(l) => Value::Table(ctx.create_sequence_from(l)?),
+            ScriptValue::Map(m) => Value::Table(ctx.create_table_from(m)?),
+        })
+    }
+}
+
+/// Tables nested deeper than this cannot be converted to [`ScriptValue`]s
+const MAX_SCRIPT_VALUE_DEPTH: usize = 64;
+
+fn script_value_conversion_error(from: &'static str, message: &str) -> mlua::Error {
+    mlua::Error::FromLuaConversionError {
+        from,
+        to: "ScriptValue",
+        message: Some(message.to_owned()),
+    }
+}
+
+// the width of lua integers depends on the lua version
+#[allow(clippy::unnecessary_cast)]
+/// Converts the value, `ancestors` holds the tables currently being converted so that cycles are an error
+fn script_value_from_lua(
+    v: Value,
+    ancestors: &mut HashSet<*const c_void>,
+) -> mlua::Result<ScriptValue> {
+    Ok(match v {
+        Value::Nil => ScriptValue::Nil,
+        Value::Boolean(b) => ScriptValue::Bool(b),
+        Value::Integer(i) => ScriptValue::Integer(i as i64),
+        Value::Number(n) => ScriptValue::Number(n),
+        Value::String(s) => ScriptValue::String(s.to_str()?.to_owned()),
+        Value::Table(t) => {
+            if ancestors.len() >= MAX_SCRIPT_VALUE_DEPTH {
+                return Err(script_value_conversion_error(
+                    "table",
+                    &format!("tables nested deeper than {MAX_SCRIPT_VALUE_DEPTH} levels cannot be shared between scripts"),
+                ));
+            }
+            let ptr = t.to_pointer();
+            if !ancestors.insert(ptr) {
+                return Err(script_value_conversion_error(
+                    "table",
+                    "tables containing themselves cannot be shared between scripts",
+                ));
+            }
+            let value = if t.raw_len() > 0 {
+                t.sequence_values::<Value>()
+                    .map(|v| script_value_from_lua(v?, ancestors))
+                    .collect::<mlua::Result<_>>()
+                    .map(ScriptValue::List)
+            } else {
+                t.pairs::<String, Value>()
+                    .map(|pair| {
+                        let (k, v) = pair?;
+                        Ok((k, script_value_from_lua(v, ancestors)?))
+                    })
+                    .collect::<mlua::Result<_>>()
+                    .map(ScriptValue::Map)
+            };
+            ancestors.remove(&ptr);
+            value?
+        }
+        v => {
+            return Err(script_value_conversion_error(
+                v.type_name(),
+                "only nil, booleans, numbers, strings and tables of these can be shared between scripts",
+            ))
+        }
+    })
+}
+
+impl<'lua> FromLua<'lua> for ScriptValue {
+    /// Copies the given lua value, tables with a non-empty sequence part are converted to lists, other tables to maps with string keys.
+    ///
+    /// Tables containing themselves or nested too deeply are a conversion error
+    fn from_lua(v: Value<'lua>, _: &'lua Lua) -> mlua::Result<Self> {
+        script_value_from_lua(v, &mut HashSet::default())
+    }
+}
+
+impl TypeName for ScriptValue {
+    fn get_type_parts() -> Cow<'static, [tealr::NamePart]> {
+        Cow::Borrowed(&[tealr::NamePart::Type(tealr::TealType {
+            name: Cow::Borrowed("any"),
+            generics: None,
+            type_kind: tealr::KindOfType::Builtin,
+        })])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn script_values_round_trip_through_lua() {
+        let lua = Lua::new();
+        let value = ScriptValue::Map(
+            [
+                (
+                    "hits".to_owned(),
+                    ScriptValue::List(vec![ScriptValue::Integer(1), ScriptValue::Number(2.5)]),
+                ),
+                ("name".to_owned(), ScriptValue::String("bob".to_owned())),
+                ("alive".to_owned(), ScriptValue::Bool(true)),
+            ]
+            .into_iter()
+            .collect(),
+        );
+
+        let converted = value.clone().to_lua(&lua).unwrap();
+        assert_eq!(ScriptValue::from_lua(converted, &lua).unwrap(), value);
+    }
+
+    #[test]
+    fn cyclic_and_deeply_nested_tables_are_rejected() {
+        let lua = Lua::new();
+        let convert = |code: &str| {
+            let v: Value = lua.load(code).eval().unwrap();
+            ScriptValue::from_lua(v, &lua)
+        };
+
+        let cyclic = convert("local t = {} t.me = t return t").unwrap_err();
+        assert!(
+            cyclic.to_string().contains("containing themselves"),
+            "{cyclic}"
+        );
+
+        let deep = convert("local t = {} for i = 1, 100 do t = { t } end return t").unwrap_err();
+        assert!(deep.to_string().contains("nested deeper"), "{deep}");
+
+        // the same table may appear more than once as long as it does not contain itself
+        assert!(convert("local s = { 1 } return { a = s, b = s }").is_ok());
+    }
+}
@@ -0,0 +1,57 @@
+use bevy::prelude::World;
+use bevy_mod_scripting_core::{prelude::*, world::WorldPointer};
+use bevy_mod_scripting_lua::{prelude::LuaScriptHost, script_runtime_error, tealr, LuaArg};
+
+use tealr::mlu::mlua::{MultiValue, ToLua, Value};
+
+use crate::common::{run_once::RunOnceHost, shared::ScriptValue};
+
+impl<A: LuaArg> RunOnceHost for LuaScriptHost<A> {
+    fn call_hook_once(
+        &mut self,
+        world: &mut World,
+        hook: &str,
+        args: ScriptValue,
+        script_data: &ScriptData,
+        ctx: &mut Self::ScriptContext,
+        providers: &mut APIProviders<Self>,
+    ) -> Result<Option<ScriptValue>, ScriptError> {
+        // safety:
+        // - we have &mut World access
+        // - we do not use world_ptr after using the world reference which it's derived from
+        let world_ptr = unsafe { WorldPointer::new(world) };
+        providers.setup_runtime_all(world_ptr.clone(), script_data, ctx)?;
+
+        let lua = ctx.get_mut().expect("Poison error in context");
+        let runtime_error = |e: tealr::mlu::mlua::Error| script_runtime_error(script_data.name, &e);
+
+        let result = lua
+            .globals()
+            .get::<_, Value>(hook)
+            .map_err(runtime_error)
+            .and_then(|f| match f {
+                Value::Nil => Ok(None),
+                Value::Function(f) => {
+                    let args = match args {
+                        ScriptValue::Nil => Ok(MultiValue::new()),
+                        ScriptValue::List(l) => l
+                            .into_iter()
+                            .map(|v| v.to_lua(lua))
+                            .collect::<Result<MultiValue, _>>(),
+                        v => v.to_lua(lua).map(|v| MultiValue::from_vec(vec![v])),
+                    };
+                    args.and_then(|args| f.call::<_, ScriptValue>(args))
+                        .map(Some)
+                        .map_err(runtime_error)
+                }
+                v => Err(ScriptError::InvalidCallback {
+                    script: script_data.name.to_owned(),
+                    callback: hook.to_owned(),
+                    msg: format!("expected a function, found a {}", v.type_name()),
+                }),
+            });
+
+        world_ptr.invalidate();
+        result
+    }
+}
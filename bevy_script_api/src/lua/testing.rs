@@ -0,0 +1,133 @@
+use ::std::sync::Mutex;
+
+use bevy::prelude::App;
+use bevy_mod_scripting_core::{prelude::*, testing::ScriptTestOutput};
+use bevy_mod_scripting_lua::{prelude::LuaDocFragment, tealr};
+
+use tealr::mlu::mlua::{self, Function, Lua, MultiValue, Value};
+
+/// Provides the assertion API used by test scripts run with `script_tests!`:
+///
+/// - `assert_eq(actual, expected[, message])` failing unless both values are equal, tables are compared by their contents
+/// - `expect_error(f[, contains])` calling `f` and failing unless it raises an error (containing the given text), returns the error message
+///
+/// The script's `print` is also redirected to the [`ScriptTestOutput`] compared against golden output files.
+#[derive(Default)]
+pub struct LuaTestAPIProvider {
+    output: ScriptTestOutput,
+}
+
+fn display(ctx: &Lua, value: Value) -> mlua::Result<String> {
+    ctx.globals()
+        .get::<_, Function>("tostring")?
+        .call::<_, String>(value)
+}
+
+fn values_equal(ctx: &Lua, a: &Value, b: &Value) -> mlua::Result<bool> {
+    Ok(match (a, b) {
+        (Value::Integer(a), Value::Number(b)) | (Value::Number(b), Value::Integer(a)) => {
+            *a as f64 == *b
+        }
+        (Value::String(a), Value::String(b)) => a.as_bytes() == b.as_bytes(),
+        (Value::Table(a), Value::Table(b)) => {
+            if a == b {
+                return Ok(true);
+            }
+            if a.clone().pairs::<Value, Value>().count()
+                != b.clone().pairs::<Value, Value>().count()
+            {
+                return Ok(false);
+            }
+            for pair in a.clone().pairs::<Value, Value>() {
+                let (k, v) = pair?;
+                if !values_equal(ctx, &v, &b.raw_get(k)?)? {
+                    return Ok(false);
+                }
+            }
+            true
+        }
+        // proxies of the same value display the same way
+        (Value::UserData(_), Value::UserData(_)) => {
+            a == b || display(ctx, a.clone())? == display(ctx, b.clone())?
+        }
+        _ => a == b,
+    })
+}
+
+impl LuaTestAPIProvider {
+    fn attach(&self, ctx: &Lua) -> mlua::Result<()> {
+        let globals = ctx.globals();
+
+        globals.set(
+            "assert_eq",
+            ctx.create_function(
+                |ctx, (actual, expected, message): (Value, Value, Option<String>)| {
+                    if values_equal(ctx, &actual, &expected)? {
+                        return Ok(());
+                    }
+                    let mut error = format!(
+                        "assertion failed: expected `{}`, got `{}`",
+                        display(ctx, expected)?,
+                        display(ctx, actual)?
+                    );
+                    if let Some(message) = message {
+                        error = format!("{error}: {message}");
+                    }
+                    Err(mlua::Error::RuntimeError(error))
+                },
+            )?,
+        )?;
+
+        globals.set(
+            "expect_error",
+            ctx.create_function(|_, (f, contains): (Function, Option<String>)| {
+                match f.call::<_, MultiValue>(()) {
+                    Ok(_) => Err(mlua::Error::RuntimeError(
+                        "expected an error, but the function succeeded".to_owned(),
+                    )),
+                    Err(e) => {
+                        let error = e.to_string();
+                        match contains {
+                            Some(text) if !error.contains(&text) => Err(mlua::Error::RuntimeError(
+                                format!("expected an error containing `{text}`, got `{error}`"),
+                            )),
+                            _ => Ok(error),
+                        }
+                    }
+                }
+            })?,
+        )?;
+
+        let output = self.output.clone();
+        globals.set(
+            "print",
+            ctx.create_function(move |ctx, args: MultiValue| {
+                let line = args
+                    .into_iter()
+                    .map(|v| display(ctx, v))
+                    .collect::<mlua::Result<Vec<_>>>()?
+                    .join("\t");
+                output.push(line);
+                Ok(())
+            })?,
+        )
+    }
+}
+
+impl APIProvider for LuaTestAPIProvider {
+    type APITarget = Mutex<Lua>;
+    type ScriptContext = Mutex<Lua>;
+    type DocTarget = LuaDocFragment;
+
+    fn attach_api(&mut self, ctx: &mut Self::APITarget) -> Result<(), ScriptError> {
+        let ctx = ctx
+            .get_mut()
+            .expect("Unable to acquire lock on Lua context");
+
+        self.attach(ctx).map_err(ScriptError::new_other)
+    }
+
+    fn register_with_app(&self, app: &mut App) {
+        app.insert_resource(self.output.clone());
+    }
+}
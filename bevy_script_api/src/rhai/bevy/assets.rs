@@ -0,0 +1,233 @@
+use std::marker::PhantomData;
+
+use bevy::{
+    asset::Asset,
+    prelude::{
+        App, ColorMaterial, Entity, EventReader, Font, Handle, Image, Mesh, Scene,
+        StandardMaterial, TextureAtlas,
+    },
+};
+use bevy_mod_scripting_core::{prelude::*, world::WorldPointer};
+use bevy_mod_scripting_rhai::{
+    prelude::*,
+    rhai::{Dynamic, EvalAltResult, Position, FLOAT, INT},
+};
+
+use crate::{
+    common::{
+        bevy::{
+            color_material_from_value, register_script_asset_loads, standard_material_from_value,
+            RegisterScriptAsset, ScriptAssetLoaded, ScriptAssets, ScriptHandle, ScriptMeshes,
+            ScriptWorld,
+        },
+        shared::ScriptValue,
+    },
+    rhai::{FromRhaiProxy, RegisterForeignRhaiType, RhaiProxyable},
+    script_ref::ScriptRef,
+};
+
+use super::to_eval_error;
+
+/// Forwards asset lifecycle events of assets loaded with `world.load_asset(entity, path)` to the scripts
+/// attached to the given entity.
+///
+/// The `on_asset_loaded` hook is called once the asset is loaded and `on_asset_failed` if loading failed,
+/// with the arguments created from the [`ScriptAssetLoaded`] event. Hooks are sent with the priority given in `priority`.
+pub struct RhaiAssetEventsProvider<A: FuncArgs + Clone + Send + Sync + 'static> {
+    pub priority: u32,
+    _ph: PhantomData<fn() -> A>,
+}
+
+impl<A: FuncArgs + Clone + Send + Sync + From<ScriptAssetLoaded> + 'static> Default
+    for RhaiAssetEventsProvider<A>
+{
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl<A: FuncArgs + Clone + Send + Sync + From<ScriptAssetLoaded> + 'static>
+    RhaiAssetEventsProvider<A>
+{
+    pub fn new(priority: u32) -> Self {
+        Self {
+            priority,
+            _ph: Default::default(),
+        }
+    }
+}
+
+fn forward_script_asset_loads<A: FuncArgs + Clone + Send + Sync + From<ScriptAssetLoaded>>(
+    mut events: EventReader<ScriptAssetLoaded>,
+    mut writer: PriorityEventWriter<RhaiEvent<A>>,
+    priority: u32,
+) {
+    for event in events.iter() {
+        writer.send(
+            RhaiEvent {
+                hook_name: if event.success {
+                    "on_asset_loaded"
+                } else {
+                    "on_asset_failed"
+                }
+                .into(),
+                recipients: event.recipients.clone(),
+                args: event.clone().into(),
+            },
+            priority,
+        )
+    }
+}
+
+impl<A: FuncArgs + Clone + Send + Sync + From<ScriptAssetLoaded> + 'static> APIProvider
+    for RhaiAssetEventsProvider<A>
+{
+    type APITarget = Engine;
+    type ScriptContext = RhaiContext;
+    type DocTarget = RhaiDocFragment;
+
+    fn attach_api(&mut self, engine: &mut Self::APITarget) -> Result<(), ScriptError> {
+        engine.register_fn(
+            "load_asset",
+            |world: &mut ScriptWorld, entity: Entity, path: &str| {
+                world
+                    .load_asset(path, Recipients::Entity(entity))
+                    .map_err(to_eval_error)
+            },
+        );
+        Ok(())
+    }
+
+    fn register_with_app(&self, app: &mut App) {
+        register_script_asset_loads(app);
+        let priority = self.priority;
+        app.add_system(
+            move |events: EventReader<ScriptAssetLoaded>,
+                  writer: PriorityEventWriter<RhaiEvent<A>>| {
+                forward_script_asset_loads(events, writer, priority)
+            },
+        );
+    }
+}
+
+impl<A: Asset> RhaiProxyable for Handle<A> {
+    fn ref_to_rhai(self_: ScriptRef) -> Result<Dynamic, Box<EvalAltResult>> {
+        self_.get_typed(|s: &Self| Ok(Dynamic::from(ScriptHandle::from(s))))?
+    }
+
+    fn apply_rhai(self_: &mut ScriptRef, new_val: Dynamic) -> Result<(), Box<EvalAltResult>> {
+        let handle = match new_val.try_cast::<ScriptHandle>() {
+            Some(handle) => handle.typed::<A>(),
+            None => {
+                return Err(Box::new(EvalAltResult::ErrorRuntime(
+                    "Only asset handles can be assigned to `Handle` fields".into(),
+                    Position::NONE,
+                )))
+            }
+        };
+        self_.set_val(handle)?;
+        Ok(())
+    }
+}
+
+/// Provides the `assets` variable, loading assets through the `AssetServer`:
+///
+/// - `assets.load(path)` starts loading the asset at the path relative to the assets folder and returns a handle to it, e.g. `assets.load("models/tree.gltf#Scene0")`
+/// - `assets.get_load_state(handle)` returns one of `"NotLoaded"`, `"Loading"`, `"Loaded"`, `"Failed"` or `"Unloaded"`
+/// - `assets.path(handle)` returns the path the asset was loaded from, or `()` if it was not loaded from a path
+/// - `world.add_asset(type_name, map)` creates an asset of a type registered with `register_script_asset` and returns a handle to it,
+///   e.g. `world.add_asset("StandardMaterial", #{ base_color: "#FF8800" })`, bevy's `StandardMaterial` and `ColorMaterial` are registered by this provider
+///
+/// and the `mesh` variable, creating meshes from primitive shapes and returning handles to them:
+///
+/// - `mesh.cube(size)` a cube with sides of the given length
+/// - `mesh.sphere(radius, subdivisions)` an icosphere, subdivided at most 80 times
+/// - `mesh.quad(width, height)` a rectangle in the XY plane facing +Z
+///
+/// Handles can be assigned to the `Handle<T>` fields of components for bevy's images, meshes, materials, texture atlases, scenes and fonts,
+/// other asset types need to be registered with `app.register_foreign_rhai_type::<Handle<MyAsset>>()`.
+pub struct RhaiAssetsAPIProvider;
+
+impl APIProvider for RhaiAssetsAPIProvider {
+    type APITarget = Engine;
+    type ScriptContext = RhaiContext;
+    type DocTarget = RhaiDocFragment;
+
+    fn attach_api(&mut self, engine: &mut Self::APITarget) -> Result<(), ScriptError> {
+        engine
+            .register_type_with_name::<ScriptHandle>("Handle")
+            .register_fn("==", |a: ScriptHandle, b: ScriptHandle| a == b)
+            .register_fn("!=", |a: ScriptHandle, b: ScriptHandle| a != b)
+            .register_fn("to_string", |h: &mut ScriptHandle| format!("{:?}", h.id()))
+            .register_fn("to_debug", |h: &mut ScriptHandle| format!("{:?}", h.id()))
+            .register_type_with_name::<ScriptAssets>("Assets")
+            .register_fn("load", |a: &mut ScriptAssets, path: &str| {
+                a.load(path).map_err(to_eval_error)
+            })
+            .register_fn(
+                "get_load_state",
+                |a: &mut ScriptAssets, handle: ScriptHandle| {
+                    a.load_state(&handle).map_err(to_eval_error)
+                },
+            )
+            .register_fn("path", |a: &mut ScriptAssets, handle: ScriptHandle| {
+                a.path(&handle)
+                    .map(|path| path.map(Dynamic::from).unwrap_or(Dynamic::UNIT))
+                    .map_err(to_eval_error)
+            })
+            .register_fn(
+                "add_asset",
+                |world: &mut ScriptWorld, asset: &str, value: Dynamic| {
+                    let value = ScriptValue::from_rhai_proxy(value)?;
+                    world.add_asset(asset, &value).map_err(to_eval_error)
+                },
+            )
+            .register_type_with_name::<ScriptMeshes>("Meshes")
+            .register_fn("cube", |m: &mut ScriptMeshes, size: FLOAT| {
+                m.cube(size as f32).map_err(to_eval_error)
+            })
+            .register_fn(
+                "sphere",
+                |m: &mut ScriptMeshes, radius: FLOAT, subdivisions: INT| {
+                    let subdivisions = usize::try_from(subdivisions)
+                        .map_err(|_| format!("Spheres cannot have {subdivisions} subdivisions"))?;
+                    m.sphere(radius as f32, subdivisions).map_err(to_eval_error)
+                },
+            )
+            .register_fn(
+                "quad",
+                |m: &mut ScriptMeshes, width: FLOAT, height: FLOAT| {
+                    m.quad(width as f32, height as f32).map_err(to_eval_error)
+                },
+            );
+        Ok(())
+    }
+
+    fn script_variables(&self) -> Vec<&'static str> {
+        vec!["assets", "mesh"]
+    }
+
+    fn setup_script_runtime(
+        &mut self,
+        world_ptr: WorldPointer,
+        _script_data: &ScriptData,
+        ctx: &mut Self::ScriptContext,
+    ) -> Result<(), ScriptError> {
+        ctx.scope
+            .set_value("assets", ScriptAssets(ScriptWorld::new(world_ptr.clone())))
+            .set_value("mesh", ScriptMeshes(ScriptWorld::new(world_ptr)));
+        Ok(())
+    }
+
+    fn register_with_app(&self, app: &mut App) {
+        app.register_foreign_rhai_type::<Handle<Image>>()
+            .register_foreign_rhai_type::<Handle<Mesh>>()
+            .register_foreign_rhai_type::<Handle<StandardMaterial>>()
+            .register_foreign_rhai_type::<Handle<ColorMaterial>>()
+            .register_foreign_rhai_type::<Handle<TextureAtlas>>()
+            .register_foreign_rhai_type::<Handle<Scene>>()
+            .register_foreign_rhai_type::<Handle<Font>>()
+            .register_script_asset_with(standard_material_from_value)
+            .register_script_asset_with(color_material_from_value);
+    }
+}
@@ -0,0 +1,61 @@
+use bevy::{
+    prelude::{Entity, Vec3, Vec3A},
+    render::primitives::Aabb,
+};
+use bevy_mod_scripting_rhai::rhai::{Dynamic, Engine, FLOAT};
+
+use crate::{
+    common::bevy::{
+        bounds::{aabb_contains, aabb_intersects},
+        ScriptWorld,
+    },
+    rhai::std::RhaiCopy,
+};
+
+use super::to_eval_error;
+
+impl RhaiCopy for Aabb {}
+
+fn to_floats(v: Vec3A) -> Vec<Dynamic> {
+    v.to_array()
+        .into_iter()
+        .map(|c| Dynamic::from_float(c.into()))
+        .collect()
+}
+
+/// Registers the `Aabb` type, its constructor `aabb(min_x, min_y, min_z, max_x, max_y, max_z)` and `world.world_aabb(entity)`
+pub(crate) fn register_bounds(engine: &mut Engine) {
+    engine
+        .register_type_with_name::<Aabb>("Aabb")
+        .register_fn(
+            "aabb",
+            |min_x: FLOAT, min_y: FLOAT, min_z: FLOAT, max_x: FLOAT, max_y: FLOAT, max_z: FLOAT| {
+                Aabb::from_min_max(
+                    Vec3::new(min_x as f32, min_y as f32, min_z as f32),
+                    Vec3::new(max_x as f32, max_y as f32, max_z as f32),
+                )
+            },
+        )
+        .register_get("min", |aabb: &mut Aabb| to_floats(aabb.min()))
+        .register_get("max", |aabb: &mut Aabb| to_floats(aabb.max()))
+        .register_get("center", |aabb: &mut Aabb| to_floats(aabb.center))
+        .register_get("half_extents", |aabb: &mut Aabb| {
+            to_floats(aabb.half_extents)
+        })
+        .register_fn("intersects", |aabb: &mut Aabb, other: Aabb| {
+            aabb_intersects(aabb, &other)
+        })
+        .register_fn(
+            "contains",
+            |aabb: &mut Aabb, x: FLOAT, y: FLOAT, z: FLOAT| {
+                aabb_contains(aabb, Vec3::new(x as f32, y as f32, z as f32))
+            },
+        )
+        .register_fn("to_debug", |aabb: &mut Aabb| format!("{aabb:?}"))
+        .register_fn("world_aabb", |world: &mut ScriptWorld, entity: Entity| {
+            world
+                .world_aabb(entity)
+                .map(|aabb| aabb.map(Dynamic::from).unwrap_or(Dynamic::UNIT))
+                .map_err(to_eval_error)
+        });
+}
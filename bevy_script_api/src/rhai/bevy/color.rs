@@ -0,0 +1,56 @@
+use bevy::prelude::Color;
+use bevy_mod_scripting_rhai::rhai::{Dynamic, Engine, FLOAT, INT};
+
+use crate::{
+    common::bevy::color::{color_from_hex, color_to_hex, lerp_color},
+    rhai::std::RhaiCopy,
+};
+
+use super::to_eval_error;
+
+impl RhaiCopy for Color {}
+
+fn to_floats(channels: [f32; 4]) -> Vec<Dynamic> {
+    channels
+        .into_iter()
+        .map(|c| Dynamic::from_float(c.into()))
+        .collect()
+}
+
+/// Registers the `Color` type along with its constructors:
+/// `color_rgb(r, g, b)`, `color_rgba(r, g, b, a)`, `color_hsl(h, s, l)`, `color_hsla(h, s, l, a)`, `color_rgb_u8(r, g, b)` and `color_hex(hex)`
+pub(crate) fn register_color(engine: &mut Engine) {
+    engine
+        .register_type_with_name::<Color>("Color")
+        .register_fn("color_rgb", |r: FLOAT, g: FLOAT, b: FLOAT| {
+            Color::rgb(r as f32, g as f32, b as f32)
+        })
+        .register_fn("color_rgba", |r: FLOAT, g: FLOAT, b: FLOAT, a: FLOAT| {
+            Color::rgba(r as f32, g as f32, b as f32, a as f32)
+        })
+        .register_fn("color_hsl", |h: FLOAT, s: FLOAT, l: FLOAT| {
+            Color::hsl(h as f32, s as f32, l as f32)
+        })
+        .register_fn("color_hsla", |h: FLOAT, s: FLOAT, l: FLOAT, a: FLOAT| {
+            Color::hsla(h as f32, s as f32, l as f32, a as f32)
+        })
+        .register_fn("color_rgb_u8", |r: INT, g: INT, b: INT| {
+            let channel = |c: INT| c.clamp(0, u8::MAX.into()) as u8;
+            Color::rgb_u8(channel(r), channel(g), channel(b))
+        })
+        .register_fn("color_hex", |hex: &str| {
+            color_from_hex(hex).map_err(to_eval_error)
+        })
+        .register_get("r", |c: &mut Color| FLOAT::from(c.r()))
+        .register_get("g", |c: &mut Color| FLOAT::from(c.g()))
+        .register_get("b", |c: &mut Color| FLOAT::from(c.b()))
+        .register_get("a", |c: &mut Color| FLOAT::from(c.a()))
+        .register_fn("to_hex", |c: &mut Color| color_to_hex(*c))
+        .register_fn("to_rgba", |c: &mut Color| to_floats(c.as_rgba_f32()))
+        .register_fn("to_hsla", |c: &mut Color| to_floats(c.as_hsla_f32()))
+        .register_fn("lerp", |c: &mut Color, other: Color, t: FLOAT| {
+            lerp_color(*c, other, t as f32)
+        })
+        .register_fn("to_string", |c: &mut Color| color_to_hex(*c))
+        .register_fn("to_debug", |c: &mut Color| format!("{c:?}"));
+}
@@ -0,0 +1,94 @@
+use std::marker::PhantomData;
+
+use bevy::prelude::{App, Component, EventReader};
+use bevy_mod_scripting_core::prelude::*;
+use bevy_mod_scripting_rhai::prelude::*;
+
+use crate::common::bevy::{register_script_component_events, ScriptComponentEvent};
+
+/// Forwards additions and removals of components of the types registered with [`Self::with_component`] to all scripts.
+///
+/// The `on_component_added` and `on_component_removed` hooks are called with the arguments created from the
+/// [`ScriptComponentEvent`] event, usually the short type name of the component and the entity.
+/// Hooks are sent with the priority given in `priority`.
+pub struct RhaiComponentEventsProvider<A: FuncArgs + Clone + Send + Sync + 'static> {
+    pub priority: u32,
+    registrations: Vec<fn(&mut App)>,
+    _ph: PhantomData<fn() -> A>,
+}
+
+impl<A: FuncArgs + Clone + Send + Sync + From<ScriptComponentEvent> + 'static> Default
+    for RhaiComponentEventsProvider<A>
+{
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl<A: FuncArgs + Clone + Send + Sync + From<ScriptComponentEvent> + 'static>
+    RhaiComponentEventsProvider<A>
+{
+    pub fn new(priority: u32) -> Self {
+        Self {
+            priority,
+            registrations: Vec::default(),
+            _ph: Default::default(),
+        }
+    }
+
+    /// Forwards additions and removals of components of type `T`
+    pub fn with_component<T: Component>(mut self) -> Self {
+        self.registrations
+            .push(register_script_component_events::<T>);
+        self
+    }
+}
+
+fn forward_script_component_events<
+    A: FuncArgs + Clone + Send + Sync + From<ScriptComponentEvent>,
+>(
+    mut events: EventReader<ScriptComponentEvent>,
+    mut writer: PriorityEventWriter<RhaiEvent<A>>,
+    priority: u32,
+) {
+    for event in events.iter() {
+        writer.send(
+            RhaiEvent {
+                hook_name: if event.added {
+                    "on_component_added"
+                } else {
+                    "on_component_removed"
+                }
+                .into(),
+                recipients: Recipients::All,
+                args: event.clone().into(),
+            },
+            priority,
+        )
+    }
+}
+
+impl<A: FuncArgs + Clone + Send + Sync + From<ScriptComponentEvent> + 'static> APIProvider
+    for RhaiComponentEventsProvider<A>
+{
+    type APITarget = Engine;
+    type ScriptContext = RhaiContext;
+    type DocTarget = RhaiDocFragment;
+
+    fn attach_api(&mut self, _: &mut Self::APITarget) -> Result<(), ScriptError> {
+        Ok(())
+    }
+
+    fn register_with_app(&self, app: &mut App) {
+        for register in &self.registrations {
+            register(app);
+        }
+        let priority = self.priority;
+        app.add_system(
+            move |events: EventReader<ScriptComponentEvent>,
+                  writer: PriorityEventWriter<RhaiEvent<A>>| {
+                forward_script_component_events(events, writer, priority)
+            },
+        );
+    }
+}
@@ -0,0 +1,67 @@
+use bevy_mod_scripting_core::{prelude::*, world::WorldPointer};
+use bevy_mod_scripting_rhai::{
+    prelude::*,
+    rhai::{Array, Dynamic, FLOAT, INT},
+};
+
+use crate::common::bevy::{ScriptDiagnostics, ScriptWorld};
+
+fn to_dynamic(value: Option<f64>) -> Dynamic {
+    value
+        .map(|v| Dynamic::from_float(v as FLOAT))
+        .unwrap_or(Dynamic::UNIT)
+}
+
+/// Provides the `diagnostics` variable, reading the diagnostics of the app such as the frame rate:
+///
+/// - `diagnostics.get(name)` returns the smoothed value of the named diagnostic, e.g. `"fps"` or `"frame_time"`, or `()` if there is none
+/// - `diagnostics.value(name)` and `diagnostics.average(name)` return its latest measurement or average instead
+/// - `diagnostics.names()` returns the names of all registered diagnostics
+/// - `diagnostics.entity_count()` returns the number of entities in the world
+///
+/// See [`ScriptDiagnostics`] for which bevy plugins provide which diagnostics.
+pub struct RhaiDiagnosticsAPIProvider;
+
+impl APIProvider for RhaiDiagnosticsAPIProvider {
+    type APITarget = Engine;
+    type ScriptContext = RhaiContext;
+    type DocTarget = RhaiDocFragment;
+
+    fn attach_api(&mut self, engine: &mut Self::APITarget) -> Result<(), ScriptError> {
+        engine
+            .register_type_with_name::<ScriptDiagnostics>("Diagnostics")
+            .register_fn("get", |d: &mut ScriptDiagnostics, name: &str| {
+                to_dynamic(d.get(name))
+            })
+            .register_fn("value", |d: &mut ScriptDiagnostics, name: &str| {
+                to_dynamic(d.value(name))
+            })
+            .register_fn("average", |d: &mut ScriptDiagnostics, name: &str| {
+                to_dynamic(d.average(name))
+            })
+            .register_fn("names", |d: &mut ScriptDiagnostics| -> Array {
+                d.names().into_iter().map(Dynamic::from).collect()
+            })
+            .register_fn("entity_count", |d: &mut ScriptDiagnostics| {
+                d.entity_count() as INT
+            });
+        Ok(())
+    }
+
+    fn script_variables(&self) -> Vec<&'static str> {
+        vec!["diagnostics"]
+    }
+
+    fn setup_script_runtime(
+        &mut self,
+        world_ptr: WorldPointer,
+        _script_data: &ScriptData,
+        ctx: &mut Self::ScriptContext,
+    ) -> Result<(), ScriptError> {
+        ctx.scope.set_value(
+            "diagnostics",
+            ScriptDiagnostics(ScriptWorld::new(world_ptr)),
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,95 @@
+use ::std::sync::Arc;
+
+use bevy::prelude::App;
+use bevy_mod_scripting_core::{prelude::*, world::WorldPointer};
+use bevy_mod_scripting_rhai::{
+    prelude::*,
+    rhai::{EvalAltResult, INT},
+};
+use parking_lot::Mutex;
+
+use crate::{
+    common::{bevy::ScriptWorld, exports::register_script_exports, shared::ScriptValue},
+    rhai::{FromRhaiProxy, ToDynamic},
+};
+
+use super::to_eval_error;
+
+/// Registers methods on the `world` object sharing named APIs between scripts, see [`crate::common::exports::ScriptExports`]:
+///
+/// - `world.register_export(name, value)` exports a map of data under the given name, owned by this script
+/// - `world.get_export(name)` returns a copy of the export, or `()` if it was not registered yet
+/// - `world.export_version(name)` returns a number which changes whenever the export is registered again, 0 if it's not registered
+#[derive(Default)]
+pub struct RhaiExportsAPIProvider {
+    /// the script currently handling events, the engine is shared by all scripts
+    script: Arc<Mutex<Option<(ScriptId, String)>>>,
+}
+
+fn not_set_up() -> Box<EvalAltResult> {
+    to_eval_error(ScriptError::Other(
+        "Exports are not set up for this script".to_owned(),
+    ))
+}
+
+impl APIProvider for RhaiExportsAPIProvider {
+    type APITarget = Engine;
+    type ScriptContext = RhaiContext;
+    type DocTarget = RhaiDocFragment;
+
+    fn attach_api(&mut self, engine: &mut Self::APITarget) -> Result<(), ScriptError> {
+        let script = self.script.clone();
+        engine.register_fn(
+            "register_export",
+            move |world: &mut ScriptWorld,
+                  name: &str,
+                  value: Dynamic|
+                  -> Result<(), Box<EvalAltResult>> {
+                let value = ScriptValue::from_rhai_proxy(value)?;
+                let script = script.lock();
+                let (sid, owner) = script.as_ref().ok_or_else(not_set_up)?;
+                world
+                    .register_export(name, *sid, owner, value)
+                    .map_err(to_eval_error)
+            },
+        );
+
+        let script = self.script.clone();
+        engine.register_fn(
+            "get_export",
+            move |world: &mut ScriptWorld, name: &str| -> Result<Dynamic, Box<EvalAltResult>> {
+                let sid = script
+                    .lock()
+                    .as_ref()
+                    .map(|(sid, _)| *sid)
+                    .ok_or_else(not_set_up)?;
+                world
+                    .import_export(name, sid)
+                    .map_err(to_eval_error)?
+                    .to_dynamic()
+            },
+        );
+
+        engine.register_fn("export_version", |world: &mut ScriptWorld, name: &str| {
+            world
+                .export_version(name)
+                .map(|version| version as INT)
+                .map_err(to_eval_error)
+        });
+        Ok(())
+    }
+
+    fn setup_script_runtime(
+        &mut self,
+        _world_ptr: WorldPointer,
+        script_data: &ScriptData,
+        _ctx: &mut Self::ScriptContext,
+    ) -> Result<(), ScriptError> {
+        *self.script.lock() = Some((script_data.sid, script_data.name.to_owned()));
+        Ok(())
+    }
+
+    fn register_with_app(&self, app: &mut App) {
+        register_script_exports(app);
+    }
+}
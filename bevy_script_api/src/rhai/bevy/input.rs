@@ -0,0 +1,103 @@
+use bevy::prelude::{App, Vec2};
+use bevy_mod_scripting_core::{prelude::*, world::WorldPointer};
+use bevy_mod_scripting_rhai::{
+    prelude::*,
+    rhai::{Array, Dynamic, FLOAT, INT},
+};
+
+use crate::common::bevy::{register_script_input, InputState, ScriptInput, ScriptWorld};
+
+use super::to_eval_error;
+
+fn to_array(v: Vec2) -> Array {
+    vec![
+        Dynamic::from_float(v.x as FLOAT),
+        Dynamic::from_float(v.y as FLOAT),
+    ]
+}
+
+/// Provides the `input` variable, reading the keyboard, mouse and gamepads:
+///
+/// - `input.pressed(key)`, `input.just_pressed(key)` and `input.just_released(key)` check keys, e.g. `input.pressed("Space")`
+/// - `input.mouse_pressed(button)`, `input.mouse_just_pressed(button)` and `input.mouse_just_released(button)` check mouse buttons, e.g. `"Left"`
+/// - `input.mouse_position()` returns the cursor position in the primary window as an `[x, y]` array, or `()` if the cursor is outside of it
+/// - `input.mouse_delta()` returns the distance the mouse moved this frame as an `[x, y]` array
+/// - `input.gamepads()` returns the IDs of the connected gamepads
+/// - `input.gamepad_axis(id, axis)` returns the position of a gamepad axis between -1 and 1, e.g. `"LeftStickX"`
+/// - `input.gamepad_pressed(id, button)`, `input.gamepad_just_pressed(id, button)` and `input.gamepad_just_released(id, button)` check gamepad buttons, e.g. `"South"`
+///
+/// See [`ScriptInput`] for how keys and buttons are named.
+pub struct RhaiInputAPIProvider;
+
+impl APIProvider for RhaiInputAPIProvider {
+    type APITarget = Engine;
+    type ScriptContext = RhaiContext;
+    type DocTarget = RhaiDocFragment;
+
+    fn attach_api(&mut self, engine: &mut Self::APITarget) -> Result<(), ScriptError> {
+        engine.register_type_with_name::<ScriptInput>("Input");
+        for (suffix, state) in [
+            ("pressed", InputState::Pressed),
+            ("just_pressed", InputState::JustPressed),
+            ("just_released", InputState::JustReleased),
+        ] {
+            engine
+                .register_fn(suffix, move |i: &mut ScriptInput, key: &str| {
+                    i.key(state, key)
+                })
+                .register_fn(
+                    format!("mouse_{suffix}"),
+                    move |i: &mut ScriptInput, button: &str| i.mouse_button(state, button),
+                )
+                .register_fn(
+                    format!("gamepad_{suffix}"),
+                    move |i: &mut ScriptInput, gamepad: INT, button: &str| {
+                        i.gamepad_button(state, gamepad as usize, button)
+                    },
+                );
+        }
+        engine
+            .register_fn("mouse_position", |i: &mut ScriptInput| {
+                i.mouse_position()
+                    .map(|pos| Dynamic::from_array(to_array(pos)))
+                    .unwrap_or(Dynamic::UNIT)
+            })
+            .register_fn("mouse_delta", |i: &mut ScriptInput| {
+                to_array(i.mouse_delta())
+            })
+            .register_fn("gamepads", |i: &mut ScriptInput| -> Array {
+                i.gamepads()
+                    .into_iter()
+                    .map(|id| Dynamic::from_int(id as INT))
+                    .collect()
+            })
+            .register_fn(
+                "gamepad_axis",
+                |i: &mut ScriptInput, gamepad: INT, axis: &str| {
+                    i.gamepad_axis(gamepad as usize, axis)
+                        .map(|v| v as FLOAT)
+                        .map_err(to_eval_error)
+                },
+            );
+        Ok(())
+    }
+
+    fn script_variables(&self) -> Vec<&'static str> {
+        vec!["input"]
+    }
+
+    fn setup_script_runtime(
+        &mut self,
+        world_ptr: WorldPointer,
+        _script_data: &ScriptData,
+        ctx: &mut Self::ScriptContext,
+    ) -> Result<(), ScriptError> {
+        ctx.scope
+            .set_value("input", ScriptInput(ScriptWorld::new(world_ptr)));
+        Ok(())
+    }
+
+    fn register_with_app(&self, app: &mut App) {
+        register_script_input(app);
+    }
+}
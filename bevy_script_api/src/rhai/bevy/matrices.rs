@@ -0,0 +1,189 @@
+use bevy::prelude::{EulerRot, Mat2, Mat3, Mat4, Quat, Vec2, Vec3, Vec3A, Vec4};
+use bevy_mod_scripting_rhai::rhai::{Dynamic, Engine, EvalAltResult, FLOAT, INT};
+
+use crate::rhai::std::RhaiCopy;
+
+impl RhaiCopy for Quat {}
+impl RhaiCopy for Mat2 {}
+impl RhaiCopy for Mat3 {}
+impl RhaiCopy for Mat4 {}
+
+type MatrixResult<T> = Result<T, Box<EvalAltResult>>;
+
+fn to_index(index: INT, len: usize) -> MatrixResult<usize> {
+    usize::try_from(index)
+        .ok()
+        .filter(|i| *i < len)
+        .ok_or_else(|| format!("Index {index} is out of range for {len} columns").into())
+}
+
+macro_rules! register_matrix {
+    ($engine:ident, $type:ty, $name:literal, $ctor:literal, $col:ty, $len:literal, [$($column:ident),*]) => {
+        $engine
+            .register_type_with_name::<$type>($name)
+            .register_fn($ctor, |$($column: $col),*| <$type>::from_cols($($column),*))
+            .register_fn(concat!($ctor, "_identity"), || <$type>::IDENTITY)
+            // columns are indexed from 0, e.g. `m[3]` is the translation of an affine `Mat4`
+            .register_indexer_get(|m: &mut $type, index: INT| -> MatrixResult<$col> {
+                Ok(m.col(to_index(index, $len)?))
+            })
+            .register_indexer_set(|m: &mut $type, index: INT, column: $col| -> MatrixResult<()> {
+                *m.col_mut(to_index(index, $len)?) = column;
+                Ok(())
+            })
+            .register_fn("+", |a: $type, b: $type| a + b)
+            .register_fn("-", |a: $type, b: $type| a - b)
+            .register_fn("*", |a: $type, b: $type| a * b)
+            .register_fn("*", |a: $type, b: $col| a * b)
+            .register_fn("*", |a: $type, b: FLOAT| a * b as f32)
+            .register_fn("*", |a: FLOAT, b: $type| b * a as f32)
+            .register_fn("-", |a: $type| -a)
+            .register_fn("==", |a: $type, b: $type| a == b)
+            .register_fn("!=", |a: $type, b: $type| a != b)
+            .register_fn("transpose", |m: &mut $type| m.transpose())
+            .register_fn("inverse", |m: &mut $type| m.inverse())
+            .register_fn("determinant", |m: &mut $type| FLOAT::from(m.determinant()))
+            .register_fn("to_string", |m: &mut $type| m.to_string())
+            .register_fn("to_debug", |m: &mut $type| format!("{m:?}"));
+    };
+}
+
+fn register_quat(engine: &mut Engine) {
+    engine
+        .register_type_with_name::<Quat>("Quat")
+        .register_fn("quat", |x: FLOAT, y: FLOAT, z: FLOAT, w: FLOAT| {
+            Quat::from_xyzw(x as f32, y as f32, z as f32, w as f32)
+        })
+        .register_fn("quat_identity", || Quat::IDENTITY)
+        .register_fn("quat_from_axis_angle", |axis: Vec3, angle: FLOAT| {
+            Quat::from_axis_angle(axis.normalize_or_zero(), angle as f32)
+        })
+        .register_fn("quat_from_euler", |x: FLOAT, y: FLOAT, z: FLOAT| {
+            Quat::from_euler(EulerRot::XYZ, x as f32, y as f32, z as f32)
+        })
+        .register_fn("quat_from_rotation_x", |angle: FLOAT| {
+            Quat::from_rotation_x(angle as f32)
+        })
+        .register_fn("quat_from_rotation_y", |angle: FLOAT| {
+            Quat::from_rotation_y(angle as f32)
+        })
+        .register_fn("quat_from_rotation_z", |angle: FLOAT| {
+            Quat::from_rotation_z(angle as f32)
+        })
+        .register_get("x", |q: &mut Quat| FLOAT::from(q.x))
+        .register_get("y", |q: &mut Quat| FLOAT::from(q.y))
+        .register_get("z", |q: &mut Quat| FLOAT::from(q.z))
+        .register_get("w", |q: &mut Quat| FLOAT::from(q.w))
+        .register_indexer_get(|q: &mut Quat, index: INT| -> MatrixResult<FLOAT> {
+            Ok(q.to_array()[to_index(index, 4)?].into())
+        })
+        .register_fn("+", |a: Quat, b: Quat| a + b)
+        .register_fn("-", |a: Quat, b: Quat| a - b)
+        .register_fn("*", |a: Quat, b: Quat| a * b)
+        .register_fn("*", |a: Quat, b: Vec3| a * b)
+        .register_fn("*", |a: Quat, b: Vec3A| a * b)
+        .register_fn("*", |a: Quat, b: FLOAT| a * b as f32)
+        .register_fn("/", |a: Quat, b: FLOAT| a / b as f32)
+        .register_fn("-", |a: Quat| -a)
+        .register_fn("==", |a: Quat, b: Quat| a == b)
+        .register_fn("!=", |a: Quat, b: Quat| a != b)
+        .register_fn("inverse", |q: &mut Quat| q.inverse())
+        .register_fn("normalize", |q: &mut Quat| q.normalize())
+        .register_fn("length", |q: &mut Quat| FLOAT::from(q.length()))
+        .register_fn("dot", |a: &mut Quat, b: Quat| FLOAT::from(a.dot(b)))
+        .register_fn("slerp", |a: &mut Quat, b: Quat, t: FLOAT| {
+            a.slerp(b, t as f32)
+        })
+        .register_fn("to_euler", |q: &mut Quat| {
+            let (x, y, z) = q.to_euler(EulerRot::XYZ);
+            vec![
+                Dynamic::from_float(x.into()),
+                Dynamic::from_float(y.into()),
+                Dynamic::from_float(z.into()),
+            ]
+        })
+        .register_fn("to_string", |q: &mut Quat| q.to_string())
+        .register_fn("to_debug", |q: &mut Quat| format!("{q:?}"));
+}
+
+/// Registers the `Quat`, `Mat2`, `Mat3` and `Mat4` types with the same operators as the Lua proxies.
+///
+/// - quaternions are built with `quat(x, y, z, w)`, `quat_identity()`, `quat_from_axis_angle(axis, angle)`, `quat_from_euler(x, y, z)`
+///   and `quat_from_rotation_x/y/z(angle)`, and rotate vectors with `q * v`
+/// - matrices are built from their columns with `mat2(c0, c1)`, `mat3(c0, c1, c2)` and `mat4(c0, c1, c2, c3)` or as `mat2_identity()` and so on,
+///   `m[i]` reads and writes column `i` counting from 0, `m * v` transforms a vector
+pub(crate) fn register_matrices(engine: &mut Engine) {
+    register_quat(engine);
+    register_matrix!(engine, Mat2, "Mat2", "mat2", Vec2, 2, [x_axis, y_axis]);
+    register_matrix!(
+        engine,
+        Mat3,
+        "Mat3",
+        "mat3",
+        Vec3,
+        3,
+        [x_axis, y_axis, z_axis]
+    );
+    register_matrix!(
+        engine,
+        Mat4,
+        "Mat4",
+        "mat4",
+        Vec4,
+        4,
+        [x_axis, y_axis, z_axis, w_axis]
+    );
+    engine
+        .register_fn(
+            "mat4_from_scale_rotation_translation",
+            |scale: Vec3, rotation: Quat, translation: Vec3| {
+                Mat4::from_scale_rotation_translation(scale, rotation, translation)
+            },
+        )
+        .register_fn("transform_point3", |m: &mut Mat4, p: Vec3| {
+            m.transform_point3(p)
+        })
+        .register_fn("transform_vector3", |m: &mut Mat4, v: Vec3| {
+            m.transform_vector3(v)
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::vectors::register_vectors;
+    use super::*;
+
+    #[test]
+    fn operators_match_glam() {
+        let mut engine = Engine::new();
+        register_vectors(&mut engine);
+        register_matrices(&mut engine);
+        let eval = |expr: &str| {
+            engine
+                .eval::<Dynamic>(expr)
+                .unwrap_or_else(|e| panic!("`{expr}` failed: {e}"))
+        };
+
+        let rotated = eval("quat_from_rotation_z(PI() / 2.0) * vec3(1.0, 0.0, 0.0)").cast::<Vec3>();
+        assert!(rotated.abs_diff_eq(Vec3::Y, 1e-6));
+        assert!(eval("quat_identity() * quat_identity() == quat_identity()").cast::<bool>());
+
+        let m =
+            eval("let m = mat2(vec2(1.0, 2.0), vec2(3.0, 4.0)); m[1] = vec2(5.0, 6.0); m * 2.0")
+                .cast::<Mat2>();
+        assert_eq!(
+            m,
+            Mat2::from_cols(Vec2::new(2.0, 4.0), Vec2::new(10.0, 12.0))
+        );
+        assert_eq!(
+            eval("mat3_identity() * vec3(1.0, 2.0, 3.0)").cast::<Vec3>(),
+            Vec3::new(1.0, 2.0, 3.0)
+        );
+        assert_eq!(
+            eval("mat4_from_scale_rotation_translation(vec3(1.0, 1.0, 1.0), quat_identity(), vec3(1.0, 0.0, 0.0))[3]")
+                .cast::<Vec4>(),
+            Vec4::new(1.0, 0.0, 0.0, 1.0)
+        );
+        assert!(engine.eval::<Dynamic>("mat2_identity()[2]").is_err());
+    }
+}
@@ -1,19 +1,66 @@
-use bevy::prelude::Entity;
+use bevy::prelude::{Color, Entity, Vec4};
 use bevy_mod_scripting_core::{prelude::*, world::WorldPointer};
 
 #[allow(deprecated)]
 use bevy_mod_scripting_rhai::{
     prelude::*,
-    rhai::{self, CustomType, INT},
+    rhai::{self, Array, CustomType, Map, FLOAT, INT},
 };
 use rhai::plugin::*;
 
 use crate::{
-    common::bevy::{ScriptTypeRegistration, ScriptWorld},
+    common::{
+        bevy::{Ancestors, Descendants, RegisterScriptEvent, ScriptTypeRegistration, ScriptWorld},
+        shared::ScriptValue,
+    },
     ReflectedValue,
 };
 
-use super::{RegisterForeignRhaiType, ToDynamic};
+use super::{FromRhaiProxy, RegisterForeignRhaiType, ToDynamic};
+
+#[cfg(feature = "api_assets")]
+mod assets;
+mod bounds;
+mod color;
+mod component_events;
+mod diagnostics;
+mod exports;
+#[cfg(feature = "api_input")]
+mod input;
+mod matrices;
+mod scene;
+mod sent_events;
+mod sprite;
+mod states;
+#[cfg(feature = "api_time")]
+mod time;
+#[cfg(feature = "api_time")]
+mod timer;
+mod transform;
+mod triggers;
+mod tween;
+mod vectors;
+mod vfx;
+mod watch;
+#[cfg(feature = "api_assets")]
+pub use assets::{RhaiAssetEventsProvider, RhaiAssetsAPIProvider};
+pub use component_events::RhaiComponentEventsProvider;
+pub use diagnostics::RhaiDiagnosticsAPIProvider;
+pub use exports::RhaiExportsAPIProvider;
+#[cfg(feature = "api_input")]
+pub use input::RhaiInputAPIProvider;
+pub use scene::RhaiSceneEventsProvider;
+pub use sent_events::RhaiSendEventsProvider;
+pub use sprite::RhaiSprite2dAPIProvider;
+pub use states::RhaiStateEventsProvider;
+#[cfg(feature = "api_time")]
+pub use time::RhaiTimeAPIProvider;
+#[cfg(feature = "api_time")]
+pub use timer::RhaiTimerAPIProvider;
+pub use triggers::RhaiTriggersAPIProvider;
+pub use tween::RhaiTweenAPIProvider;
+pub use vfx::RhaiVfxAPIProvider;
+pub use watch::RhaiWatchEventsProvider;
 
 #[allow(deprecated)]
 impl CustomType for ScriptTypeRegistration {
@@ -29,24 +76,30 @@ impl CustomType for ScriptTypeRegistration {
     }
 }
 
+fn to_eval_error(e: ScriptError) -> Box<EvalAltResult> {
+    Box::new(EvalAltResult::ErrorRuntime(
+        e.to_string().into(),
+        Position::NONE,
+    ))
+}
+
 #[allow(deprecated)]
 impl CustomType for ScriptWorld {
     fn build(mut builder: rhai::TypeBuilder<Self>) {
         builder
             .with_name("World")
+            .with_fn("is_entity_valid", |self_: ScriptWorld, entity: Entity| {
+                self_.is_entity_valid(entity)
+            });
+
+        #[cfg(feature = "api_reflection")]
+        builder
             .with_fn("get_type_by_name", |self_: ScriptWorld, type_name: &str| {
                 self_
                     .get_type_by_name(type_name)
                     .map(Dynamic::from)
                     .unwrap_or_default()
             })
-            .with_fn("get_children", |self_: ScriptWorld, parent: Entity| {
-                self_
-                    .get_children(parent)
-                    .into_iter()
-                    .map(Dynamic::from)
-                    .collect::<Vec<_>>()
-            })
             .with_fn(
                 "add_default_component",
                 |self_: ScriptWorld, entity: Entity, type_registration: ScriptTypeRegistration| {
@@ -78,7 +131,7 @@ impl CustomType for ScriptWorld {
                 },
             )
             .with_fn(
-                "has_compoennt",
+                "has_component",
                 |self_: ScriptWorld, entity: Entity, comp_type: ScriptTypeRegistration| {
                     self_.has_component(entity, comp_type).map_err(|e| {
                         Box::new(EvalAltResult::ErrorRuntime(
@@ -137,7 +190,169 @@ impl CustomType for ScriptWorld {
                         ))
                     })
                 },
+            );
+
+        builder
+            .with_fn("get_shared", |self_: &mut ScriptWorld, key: &str| {
+                self_.get_shared(key).to_dynamic()
+            })
+            .with_fn(
+                "set_shared",
+                |self_: &mut ScriptWorld, key: &str, value: Dynamic| {
+                    self_.set_shared(key.to_owned(), ScriptValue::from_rhai_proxy(value)?);
+                    Ok::<_, Box<EvalAltResult>>(())
+                },
+            )
+            .with_fn(
+                "send_event",
+                |self_: &mut ScriptWorld, event: &str, value: Dynamic| {
+                    let value = ScriptValue::from_rhai_proxy(value)?;
+                    self_.send_event(event, &value).map_err(to_eval_error)
+                },
+            )
+            .with_fn("send_event", |self_: &mut ScriptWorld, event: &str| {
+                self_
+                    .send_event(event, &ScriptValue::Nil)
+                    .map_err(to_eval_error)
+            })
+            .with_fn("current_state", |self_: &mut ScriptWorld| {
+                self_.current_state(None).map_err(to_eval_error)
+            })
+            .with_fn(
+                "current_state",
+                |self_: &mut ScriptWorld, state_type: &str| {
+                    self_.current_state(Some(state_type)).map_err(to_eval_error)
+                },
+            )
+            .with_fn("set_state", |self_: &mut ScriptWorld, state: &str| {
+                self_.set_state(state, None).map_err(to_eval_error)
+            })
+            .with_fn(
+                "set_state",
+                |self_: &mut ScriptWorld, state: &str, state_type: &str| {
+                    self_
+                        .set_state(state, Some(state_type))
+                        .map_err(to_eval_error)
+                },
+            )
+            .with_fn("get_uniform", |self_: &mut ScriptWorld, name: &str| {
+                let value = self_.get_uniform(name).map_err(to_eval_error)?;
+                Ok::<_, Box<EvalAltResult>>(
+                    value
+                        .to_array()
+                        .into_iter()
+                        .map(|v| Dynamic::from_float(v.into()))
+                        .collect::<Vec<Dynamic>>(),
+                )
+            })
+            .with_fn(
+                "set_uniform",
+                |self_: &mut ScriptWorld, name: &str, value: FLOAT| {
+                    self_
+                        .set_uniform(name, Vec4::new(value as f32, 0.0, 0.0, 0.0))
+                        .map_err(to_eval_error)
+                },
+            )
+            .with_fn(
+                "set_uniform",
+                |self_: &mut ScriptWorld, name: &str, value: Array| {
+                    let mut components = [0.0; 4];
+                    for (c, v) in components.iter_mut().zip(value) {
+                        *c = v.as_float().map_err(|t| {
+                            Box::new(EvalAltResult::ErrorMismatchDataType(
+                                "float".to_owned(),
+                                t.to_owned(),
+                                Position::NONE,
+                            ))
+                        })? as f32;
+                    }
+                    self_
+                        .set_uniform(name, Vec4::from_array(components))
+                        .map_err(to_eval_error)
+                },
+            )
+            .with_fn("window_size", |self_: &mut ScriptWorld| {
+                let size = self_.window_size().map_err(to_eval_error)?;
+                Ok::<_, Box<EvalAltResult>>(vec![
+                    Dynamic::from_float(size.x.into()),
+                    Dynamic::from_float(size.y.into()),
+                ])
+            })
+            .with_fn("cursor_position", |self_: &mut ScriptWorld| {
+                let pos = self_.cursor_position().map_err(to_eval_error)?;
+                Ok::<_, Box<EvalAltResult>>(
+                    pos.map(|pos| {
+                        Dynamic::from_array(vec![
+                            Dynamic::from_float(pos.x.into()),
+                            Dynamic::from_float(pos.y.into()),
+                        ])
+                    })
+                    .unwrap_or_default(),
+                )
+            })
+            .with_fn("set_cursor_grab", |self_: &mut ScriptWorld, grab: bool| {
+                self_.set_cursor_grab(grab).map_err(to_eval_error)
+            })
+            .with_fn(
+                "set_cursor_visible",
+                |self_: &mut ScriptWorld, visible: bool| {
+                    self_.set_cursor_visible(visible).map_err(to_eval_error)
+                },
+            )
+            .with_fn(
+                "set_window_title",
+                |self_: &mut ScriptWorld, title: &str| {
+                    self_
+                        .set_window_title(title.to_owned())
+                        .map_err(to_eval_error)
+                },
             )
+            .with_fn("screenshot", |self_: &mut ScriptWorld, path: &str| {
+                self_.request_screenshot(path).map_err(to_eval_error)
+            })
+            .with_fn(
+                "set_material_base_color",
+                |self_: &mut ScriptWorld,
+                 entity: Entity,
+                 r: FLOAT,
+                 g: FLOAT,
+                 b: FLOAT,
+                 a: FLOAT| {
+                    self_
+                        .set_material_base_color(
+                            entity,
+                            Color::rgba(r as f32, g as f32, b as f32, a as f32),
+                        )
+                        .map_err(to_eval_error)
+                },
+            )
+            .with_fn(
+                "set_material_emissive",
+                |self_: &mut ScriptWorld,
+                 entity: Entity,
+                 r: FLOAT,
+                 g: FLOAT,
+                 b: FLOAT,
+                 a: FLOAT| {
+                    self_
+                        .set_material_emissive(
+                            entity,
+                            Color::rgba(r as f32, g as f32, b as f32, a as f32),
+                        )
+                        .map_err(to_eval_error)
+                },
+            )
+            .with_fn(
+                "set_material_metallic_roughness",
+                |self_: &mut ScriptWorld, entity: Entity, metallic: FLOAT, roughness: FLOAT| {
+                    self_
+                        .set_material_metallic_roughness(entity, metallic as f32, roughness as f32)
+                        .map_err(to_eval_error)
+                },
+            );
+
+        #[cfg(feature = "api_hierarchy")]
+        builder
             .with_fn("get_children", |self_: &ScriptWorld, parent: Entity| {
                 self_
                     .get_children(parent)
@@ -148,6 +363,30 @@ impl CustomType for ScriptWorld {
             .with_fn("get_parent", |self_: &ScriptWorld, entity: Entity| {
                 self_.get_parent(entity)
             })
+            .with_fn("descendants", |self_: &ScriptWorld, entity: Entity| {
+                self_.descendants(entity)
+            })
+            .with_fn("ancestors", |self_: &ScriptWorld, entity: Entity| {
+                self_.ancestors(entity)
+            })
+            .with_fn(
+                "find_child_by_name",
+                |self_: &ScriptWorld, entity: Entity, name: &str| {
+                    self_
+                        .find_child_by_name(entity, name)
+                        .map(Dynamic::from)
+                        .unwrap_or_default()
+                },
+            )
+            .with_fn(
+                "set_parent",
+                |self_: &mut ScriptWorld, entity: Entity, parent: Entity| {
+                    self_.set_parent(entity, parent)
+                },
+            )
+            .with_fn("detach", |self_: &mut ScriptWorld, entity: Entity| {
+                self_.detach(entity)
+            })
             .with_fn(
                 "push_child",
                 |self_: &mut ScriptWorld, parent: Entity, child: Entity| {
@@ -203,20 +442,91 @@ impl CustomType for ScriptWorld {
                 "despawn_recursive",
                 |self_: &mut ScriptWorld, entity: Entity| self_.despawn_recursive(entity),
             )
+            .with_fn(
+                "despawn_recursive_deferred",
+                |self_: &mut ScriptWorld, entity: Entity| self_.despawn_recursive_deferred(entity),
+            );
+
+        builder
+            .with_fn("flush", |self_: &mut ScriptWorld| self_.flush())
             .with_fn("spawn", |self_: &mut ScriptWorld| {
-                let mut w = self_.write();
-                w.spawn(()).id()
+                self_.spawn().map_err(to_eval_error)
             })
             .with_fn("despawn", |self_: &mut ScriptWorld, entity: Entity| {
-                let mut w = self_.write();
-
-                w.despawn(entity)
+                self_.despawn(entity)
             })
+            .with_fn(
+                "move_scripts",
+                |self_: &mut ScriptWorld, from: Entity, to: Entity| {
+                    self_
+                        .move_scripts::<RhaiFile>(from, to)
+                        .map_err(to_eval_error)
+                },
+            )
+            .with_fn(
+                "copy_scripts",
+                |self_: &mut ScriptWorld, from: Entity, to: Entity| {
+                    self_
+                        .copy_scripts::<RhaiFile>(from, to, false)
+                        .map_err(to_eval_error)
+                },
+            )
+            .with_fn(
+                "copy_scripts",
+                |self_: &mut ScriptWorld, from: Entity, to: Entity, with_state: bool| {
+                    self_
+                        .copy_scripts::<RhaiFile>(from, to, with_state)
+                        .map_err(to_eval_error)
+                },
+            )
             .with_fn("to_string", |self_: &mut ScriptWorld| self_.to_string())
             .with_fn("to_debug", |self_: &mut ScriptWorld| format!("{:?}", self_));
     }
 }
 
+/// The `script` value of every script, giving access to the script groups it is a member of
+#[derive(Clone)]
+pub struct RhaiScript {
+    sid: ScriptId,
+    world: ScriptWorld,
+}
+
+#[allow(deprecated)]
+impl CustomType for RhaiScript {
+    fn build(mut builder: rhai::TypeBuilder<Self>) {
+        builder
+            .with_name("Script")
+            .with_get("sid", |self_: &mut Self| self_.sid as INT)
+            .with_fn("join_group", |self_: &mut Self, group: &str| {
+                self_.world.join_group(self_.sid, group)
+            })
+            .with_fn("leave_group", |self_: &mut Self, group: &str| {
+                self_.world.leave_group(self_.sid, group)
+            })
+            .with_fn("in_group", |self_: &mut Self, group: &str| {
+                self_.world.in_group(self_.sid, group)
+            })
+            // events are read by scripts rather than the world, which does not know which script is reading
+            .with_fn("read_events", |self_: &mut Self, event: &str| {
+                self_
+                    .world
+                    .read_events(self_.sid, event)
+                    .map_err(to_eval_error)?
+                    .into_iter()
+                    .map(ToDynamic::to_dynamic)
+                    .collect::<Result<Array, _>>()
+            })
+            .with_fn("groups", |self_: &mut Self| {
+                self_
+                    .world
+                    .script_groups(self_.sid)
+                    .into_iter()
+                    .map(Dynamic::from)
+                    .collect::<Array>()
+            });
+    }
+}
+
 pub struct RhaiBevyAPIProvider;
 
 impl APIProvider for RhaiBevyAPIProvider {
@@ -228,15 +538,38 @@ impl APIProvider for RhaiBevyAPIProvider {
         engine.build_type::<ReflectedValue>();
         engine.build_type::<ScriptTypeRegistration>();
         engine.build_type::<ScriptWorld>();
+        engine.build_type::<RhaiScript>();
+        // hierarchy walks are iterated lazily by `for` loops
+        engine
+            .register_type_with_name::<Descendants>("Descendants")
+            .register_iterator::<Descendants>()
+            .register_type_with_name::<Ancestors>("Ancestors")
+            .register_iterator::<Ancestors>();
+        bounds::register_bounds(engine);
+        color::register_color(engine);
+        transform::register_transform_helpers(engine);
+        vectors::register_vectors(engine);
+        matrices::register_matrices(engine);
         Ok(())
     }
 
+    fn script_variables(&self) -> Vec<&'static str> {
+        vec!["script", "world", "entity"]
+    }
+
     fn setup_script_runtime(
         &mut self,
         world_ptr: WorldPointer,
-        _script_data: &ScriptData,
+        script_data: &ScriptData,
         ctx: &mut Self::ScriptContext,
     ) -> Result<(), ScriptError> {
+        ctx.scope.set_value(
+            "script",
+            RhaiScript {
+                sid: script_data.sid,
+                world: ScriptWorld::new(world_ptr.clone()),
+            },
+        );
         ctx.scope.set_value("world", ScriptWorld::new(world_ptr));
         Ok(())
     }
@@ -271,5 +604,115 @@ impl APIProvider for RhaiBevyAPIProvider {
         app.register_foreign_rhai_type::<u128>();
         app.register_foreign_rhai_type::<usize>();
         app.register_foreign_rhai_type::<String>();
+        app.register_foreign_rhai_type::<Color>();
+        app.register_foreign_rhai_type::<bevy::prelude::Vec2>();
+        app.register_foreign_rhai_type::<bevy::prelude::Vec3>();
+        app.register_foreign_rhai_type::<bevy::prelude::Vec3A>();
+        app.register_foreign_rhai_type::<bevy::prelude::Vec4>();
+        app.register_foreign_rhai_type::<bevy::prelude::Quat>();
+        app.register_foreign_rhai_type::<bevy::prelude::Mat2>();
+        app.register_foreign_rhai_type::<bevy::prelude::Mat3>();
+        app.register_foreign_rhai_type::<bevy::prelude::Mat4>();
+        app.register_foreign_rhai_type::<bevy::render::primitives::Aabb>();
+        // lets scripts quit the app with `world.send_event("AppExit")`
+        app.register_default_script_event::<bevy::app::AppExit>();
+    }
+}
+
+/// An opt-in API provider which replaces references to despawned entities held in the `state` map of each script with `()`.
+///
+/// The sweep happens every time a script is about to handle events, and walks nested maps and arrays up to `max_depth` levels deep,
+/// so prefer checking `world.is_entity_valid(entity)` in scripts which hold on to many entity references.
+pub struct RhaiEntitySweepProvider {
+    /// how many levels of nested maps and arrays are searched for entity references, 0 means only the `state` map is swept
+    pub max_depth: usize,
+}
+
+impl Default for RhaiEntitySweepProvider {
+    fn default() -> Self {
+        Self { max_depth: 2 }
+    }
+}
+
+impl RhaiEntitySweepProvider {
+    fn sweep_value(world: &ScriptWorld, value: &mut Dynamic, depth: usize) {
+        if value.is::<Entity>() {
+            if !world.is_entity_valid(value.clone_cast::<Entity>()) {
+                *value = Dynamic::UNIT;
+            }
+        } else if depth > 0 && value.is::<Map>() {
+            if let Some(mut map) = value.write_lock::<Map>() {
+                map.values_mut()
+                    .for_each(|v| Self::sweep_value(world, v, depth - 1));
+            }
+        } else if depth > 0 && value.is::<Array>() {
+            if let Some(mut array) = value.write_lock::<Array>() {
+                array
+                    .iter_mut()
+                    .for_each(|v| Self::sweep_value(world, v, depth - 1));
+            }
+        }
+    }
+}
+
+impl APIProvider for RhaiEntitySweepProvider {
+    type APITarget = Engine;
+    type ScriptContext = RhaiContext;
+    type DocTarget = RhaiDocFragment;
+
+    fn attach_api(&mut self, _: &mut Self::APITarget) -> Result<(), ScriptError> {
+        Ok(())
+    }
+
+    fn setup_script_runtime(
+        &mut self,
+        world_ptr: WorldPointer,
+        _script_data: &ScriptData,
+        ctx: &mut Self::ScriptContext,
+    ) -> Result<(), ScriptError> {
+        let world = ScriptWorld::new(world_ptr);
+        if let Some(state) = ctx.scope.get_mut("state") {
+            // the state map itself is always swept
+            Self::sweep_value(&world, state, self.max_depth + 1);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::World;
+
+    use super::*;
+
+    #[test]
+    fn sweeps_despawned_entities_up_to_the_given_depth() {
+        let mut world = World::new();
+        let alive = world.spawn(()).id();
+        let despawned = world.spawn(()).id();
+        world.despawn(despawned);
+
+        // safety: the world outlives the pointer, which is only used in this test
+        let script_world = ScriptWorld::new(unsafe { WorldPointer::new(&mut world) });
+
+        let mut state = Map::new();
+        state.insert("alive".into(), Dynamic::from(alive));
+        state.insert("target".into(), Dynamic::from(despawned));
+        state.insert(
+            "nested".into(),
+            Dynamic::from_array(vec![Dynamic::from(despawned)]),
+        );
+        let mut state = Dynamic::from_map(state);
+
+        // sweeps the entries of the state map, but not the arrays within it
+        RhaiEntitySweepProvider::sweep_value(&script_world, &mut state, 1);
+        let map = state.clone_cast::<Map>();
+        assert!(map["alive"].is::<Entity>());
+        assert!(map["target"].is::<()>());
+        assert!(map["nested"].clone_cast::<Array>()[0].is::<Entity>());
+
+        RhaiEntitySweepProvider::sweep_value(&script_world, &mut state, 2);
+        let map = state.clone_cast::<Map>();
+        assert!(map["nested"].clone_cast::<Array>()[0].is::<()>());
     }
 }
@@ -0,0 +1,109 @@
+use std::marker::PhantomData;
+
+use bevy::prelude::{App, Entity, EventReader, Transform, Vec3};
+use bevy_mod_scripting_core::prelude::*;
+use bevy_mod_scripting_rhai::{prelude::*, rhai::FLOAT};
+
+use crate::common::bevy::{register_script_scene_spawns, ScriptSceneSpawned, ScriptWorld};
+
+use super::to_eval_error;
+
+/// Forwards completion events of scenes spawned with `world.spawn_scene(entity, path, [x, y, z])` to the scripts
+/// attached to the given entity.
+///
+/// The `on_scene_spawned` hook is called once all entities of the scene are spawned and `on_scene_failed` if the scene
+/// could not be loaded, with the arguments created from the [`ScriptSceneSpawned`] event. Hooks are sent with the priority given in `priority`.
+pub struct RhaiSceneEventsProvider<A: FuncArgs + Clone + Send + Sync + 'static> {
+    pub priority: u32,
+    _ph: PhantomData<fn() -> A>,
+}
+
+impl<A: FuncArgs + Clone + Send + Sync + From<ScriptSceneSpawned> + 'static> Default
+    for RhaiSceneEventsProvider<A>
+{
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl<A: FuncArgs + Clone + Send + Sync + From<ScriptSceneSpawned> + 'static>
+    RhaiSceneEventsProvider<A>
+{
+    pub fn new(priority: u32) -> Self {
+        Self {
+            priority,
+            _ph: Default::default(),
+        }
+    }
+}
+
+fn forward_script_scene_spawns<A: FuncArgs + Clone + Send + Sync + From<ScriptSceneSpawned>>(
+    mut events: EventReader<ScriptSceneSpawned>,
+    mut writer: PriorityEventWriter<RhaiEvent<A>>,
+    priority: u32,
+) {
+    for event in events.iter() {
+        writer.send(
+            RhaiEvent {
+                hook_name: if event.success {
+                    "on_scene_spawned"
+                } else {
+                    "on_scene_failed"
+                }
+                .into(),
+                recipients: event.recipients.clone(),
+                args: event.clone().into(),
+            },
+            priority,
+        )
+    }
+}
+
+impl<A: FuncArgs + Clone + Send + Sync + From<ScriptSceneSpawned> + 'static> APIProvider
+    for RhaiSceneEventsProvider<A>
+{
+    type APITarget = Engine;
+    type ScriptContext = RhaiContext;
+    type DocTarget = RhaiDocFragment;
+
+    fn attach_api(&mut self, engine: &mut Self::APITarget) -> Result<(), ScriptError> {
+        engine
+            .register_fn(
+                "spawn_scene",
+                |world: &mut ScriptWorld, entity: Entity, path: &str| {
+                    world
+                        .spawn_scene(path, Transform::default(), Recipients::Entity(entity))
+                        .map_err(to_eval_error)
+                },
+            )
+            .register_fn(
+                "spawn_scene",
+                |world: &mut ScriptWorld,
+                 entity: Entity,
+                 path: &str,
+                 x: FLOAT,
+                 y: FLOAT,
+                 z: FLOAT| {
+                    world
+                        .spawn_scene(
+                            path,
+                            Transform::from_translation(Vec3::new(x as f32, y as f32, z as f32)),
+                            Recipients::Entity(entity),
+                        )
+                        .map_err(to_eval_error)
+                },
+            );
+        Ok(())
+    }
+
+    fn register_with_app(&self, app: &mut App) {
+        register_script_scene_spawns(app);
+        let priority = self.priority;
+        app.add_system(
+            move |events: EventReader<ScriptSceneSpawned>,
+                  writer: PriorityEventWriter<RhaiEvent<A>>| {
+                forward_script_scene_spawns(events, writer, priority)
+            },
+        );
+    }
+}
@@ -0,0 +1,142 @@
+use std::marker::PhantomData;
+
+use bevy::prelude::{App, Entity, EventReader};
+use bevy_mod_scripting_core::prelude::*;
+use bevy_mod_scripting_rhai::{
+    prelude::*,
+    rhai::{EvalAltResult, ImmutableString, INT},
+};
+
+use crate::{
+    common::{
+        bevy::{register_script_sent_events, ScriptSentEvent, ScriptWorld},
+        shared::ScriptValue,
+    },
+    rhai::FromRhaiProxy,
+};
+
+use super::to_eval_error;
+
+/// Registers `world.send_event(hook, recipients, priority, args)` letting scripts send events to other Rhai scripts,
+/// `args` may be left out, as may the priority in which case `priority` is used.
+///
+/// The recipients are either `()` for all scripts, an entity, the name of a script group or a script ID.
+/// The priority is validated against the handler stages of the host,
+/// sending with a priority no handler stage handles is an error naming the valid ranges.
+/// The hook is called with the arguments created from the [`ScriptSentEvent`] event, usually its `args`.
+pub struct RhaiSendEventsProvider<A: FuncArgs + Clone + Send + Sync + 'static> {
+    pub priority: u32,
+    _ph: PhantomData<fn() -> A>,
+}
+
+impl<A: FuncArgs + Clone + Send + Sync + From<ScriptSentEvent> + 'static> Default
+    for RhaiSendEventsProvider<A>
+{
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl<A: FuncArgs + Clone + Send + Sync + From<ScriptSentEvent> + 'static>
+    RhaiSendEventsProvider<A>
+{
+    pub fn new(priority: u32) -> Self {
+        Self {
+            priority,
+            _ph: Default::default(),
+        }
+    }
+}
+
+fn to_recipients(recipients: Dynamic) -> Result<Recipients, Box<EvalAltResult>> {
+    if recipients.is::<()>() {
+        Ok(Recipients::All)
+    } else if recipients.is::<Entity>() {
+        Ok(Recipients::Entity(recipients.cast::<Entity>()))
+    } else if recipients.is::<ImmutableString>() {
+        Ok(Recipients::Group(
+            recipients.cast::<ImmutableString>().into(),
+        ))
+    } else if let Some(sid) = recipients
+        .as_int()
+        .ok()
+        .and_then(|i| ScriptId::try_from(i).ok())
+    {
+        Ok(Recipients::Id(sid))
+    } else {
+        Err(to_eval_error(ScriptError::Other(format!(
+            "Invalid recipients of type `{}`, expected `()`, an entity, a script group name or a script ID",
+            recipients.type_name()
+        ))))
+    }
+}
+
+fn send<A: FuncArgs + Clone + Send + Sync + 'static>(
+    world: &mut ScriptWorld,
+    hook: &str,
+    recipients: Dynamic,
+    priority: INT,
+    args: Dynamic,
+) -> Result<(), Box<EvalAltResult>> {
+    let priority = u32::try_from(priority).map_err(|_| {
+        to_eval_error(ScriptError::Other(format!(
+            "Invalid priority {priority}, priorities are positive"
+        )))
+    })?;
+    world
+        .send_script_event::<RhaiScriptHost<A>>(
+            hook,
+            to_recipients(recipients)?,
+            priority,
+            ScriptValue::from_rhai_proxy(args)?,
+        )
+        .map_err(to_eval_error)
+}
+
+fn forward_script_sent_events<A: FuncArgs + Clone + Send + Sync + From<ScriptSentEvent>>(
+    mut events: EventReader<ScriptSentEvent>,
+    mut writer: PriorityEventWriter<RhaiEvent<A>>,
+) {
+    for event in events.iter().filter(|e| e.is_for::<RhaiScriptHost<A>>()) {
+        writer.send(
+            RhaiEvent {
+                hook_name: event.hook,
+                recipients: event.recipients.clone(),
+                args: event.clone().into(),
+            },
+            event.priority,
+        )
+    }
+}
+
+impl<A: FuncArgs + Clone + Send + Sync + From<ScriptSentEvent> + 'static> APIProvider
+    for RhaiSendEventsProvider<A>
+{
+    type APITarget = Engine;
+    type ScriptContext = RhaiContext;
+    type DocTarget = RhaiDocFragment;
+
+    fn attach_api(&mut self, engine: &mut Self::APITarget) -> Result<(), ScriptError> {
+        let priority = INT::from(self.priority);
+        engine
+            .register_fn("send_event", send::<A>)
+            .register_fn(
+                "send_event",
+                |world: &mut ScriptWorld, hook: &str, recipients: Dynamic, priority: INT| {
+                    send::<A>(world, hook, recipients, priority, Dynamic::UNIT)
+                },
+            )
+            .register_fn(
+                "send_event",
+                move |world: &mut ScriptWorld, hook: &str, recipients: Dynamic| {
+                    send::<A>(world, hook, recipients, priority, Dynamic::UNIT)
+                },
+            );
+        Ok(())
+    }
+
+    fn register_with_app(&self, app: &mut App) {
+        register_script_sent_events(app);
+        app.add_system(forward_script_sent_events::<A>);
+    }
+}
@@ -0,0 +1,110 @@
+use bevy::prelude::{App, Color, Entity, Quat, Vec3};
+use bevy_mod_scripting_core::prelude::*;
+use bevy_mod_scripting_rhai::{
+    prelude::*,
+    rhai::{FLOAT, INT},
+};
+
+use crate::common::bevy::{register_transform_tweens, ScriptWorld};
+
+use super::to_eval_error;
+
+/// Registers 2D sprite helper methods on the `world` object:
+///
+/// - `world.spawn_sprite(texture_path, x, y)` spawns a sprite bundle and returns its entity
+/// - `world.set_sprite_color(entity, r, g, b, a)` tints the sprite
+/// - `world.set_sprite_flip(entity, flip_x, flip_y)` flips the sprite
+/// - `world.set_sprite_index(entity, index)` sets the texture atlas index of the sprite
+/// - `world.move_to(entity, x, y, duration)`, `world.rotate_to(entity, angle, duration)` and `world.scale_to(entity, x, y, duration)`
+///   tween the transform of the entity over the given number of seconds
+pub struct RhaiSprite2dAPIProvider;
+
+impl APIProvider for RhaiSprite2dAPIProvider {
+    type APITarget = Engine;
+    type ScriptContext = RhaiContext;
+    type DocTarget = RhaiDocFragment;
+
+    fn attach_api(&mut self, engine: &mut Self::APITarget) -> Result<(), ScriptError> {
+        engine
+            .register_fn(
+                "spawn_sprite",
+                |world: &mut ScriptWorld, path: &str, x: FLOAT, y: FLOAT| {
+                    world
+                        .spawn_sprite(path, Vec3::new(x as f32, y as f32, 0.0))
+                        .map_err(to_eval_error)
+                },
+            )
+            .register_fn(
+                "set_sprite_color",
+                |world: &mut ScriptWorld,
+                 entity: Entity,
+                 r: FLOAT,
+                 g: FLOAT,
+                 b: FLOAT,
+                 a: FLOAT| {
+                    world
+                        .set_sprite_color(
+                            entity,
+                            Color::rgba(r as f32, g as f32, b as f32, a as f32),
+                        )
+                        .map_err(to_eval_error)
+                },
+            )
+            .register_fn(
+                "set_sprite_flip",
+                |world: &mut ScriptWorld, entity: Entity, flip_x: bool, flip_y: bool| {
+                    world
+                        .set_sprite_flip(entity, flip_x, flip_y)
+                        .map_err(to_eval_error)
+                },
+            )
+            .register_fn(
+                "set_sprite_index",
+                |world: &mut ScriptWorld, entity: Entity, index: INT| {
+                    let index = index.try_into().map_err(|_| {
+                        to_eval_error(ScriptError::Other(format!(
+                            "{index} is not a valid sprite index"
+                        )))
+                    })?;
+                    world.set_sprite_index(entity, index).map_err(to_eval_error)
+                },
+            )
+            .register_fn(
+                "move_to",
+                |world: &mut ScriptWorld, entity: Entity, x: FLOAT, y: FLOAT, duration: FLOAT| {
+                    world
+                        .tween_transform(entity, duration as f32, |t| {
+                            t.translation.x = x as f32;
+                            t.translation.y = y as f32;
+                        })
+                        .map_err(to_eval_error)
+                },
+            )
+            .register_fn(
+                "rotate_to",
+                |world: &mut ScriptWorld, entity: Entity, angle: FLOAT, duration: FLOAT| {
+                    world
+                        .tween_transform(entity, duration as f32, |t| {
+                            t.rotation = Quat::from_rotation_z(angle as f32)
+                        })
+                        .map_err(to_eval_error)
+                },
+            )
+            .register_fn(
+                "scale_to",
+                |world: &mut ScriptWorld, entity: Entity, x: FLOAT, y: FLOAT, duration: FLOAT| {
+                    world
+                        .tween_transform(entity, duration as f32, |t| {
+                            t.scale.x = x as f32;
+                            t.scale.y = y as f32;
+                        })
+                        .map_err(to_eval_error)
+                },
+            );
+        Ok(())
+    }
+
+    fn register_with_app(&self, app: &mut App) {
+        register_transform_tweens(app);
+    }
+}
@@ -0,0 +1,86 @@
+use std::marker::PhantomData;
+
+use bevy::{
+    ecs::event::Events,
+    prelude::{App, EventReader},
+};
+use bevy_mod_scripting_core::prelude::*;
+use bevy_mod_scripting_rhai::prelude::*;
+
+use crate::common::bevy::ScriptStateEvent;
+
+/// Forwards transitions of the states registered with [`RegisterScriptState`](crate::common::bevy::RegisterScriptState) to all scripts.
+///
+/// The `on_state_enter` and `on_state_exit` hooks are called with the arguments created from the
+/// [`ScriptStateEvent`] event, usually the name of the state and the short type name of its type.
+/// Hooks are sent with the priority given in `priority`.
+pub struct RhaiStateEventsProvider<A: FuncArgs + Clone + Send + Sync + 'static> {
+    pub priority: u32,
+    _ph: PhantomData<fn() -> A>,
+}
+
+impl<A: FuncArgs + Clone + Send + Sync + From<ScriptStateEvent> + 'static> Default
+    for RhaiStateEventsProvider<A>
+{
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl<A: FuncArgs + Clone + Send + Sync + From<ScriptStateEvent> + 'static>
+    RhaiStateEventsProvider<A>
+{
+    pub fn new(priority: u32) -> Self {
+        Self {
+            priority,
+            _ph: Default::default(),
+        }
+    }
+}
+
+fn forward_script_state_events<A: FuncArgs + Clone + Send + Sync + From<ScriptStateEvent>>(
+    mut events: EventReader<ScriptStateEvent>,
+    mut writer: PriorityEventWriter<RhaiEvent<A>>,
+    priority: u32,
+) {
+    for event in events.iter() {
+        writer.send(
+            RhaiEvent {
+                hook_name: if event.entered {
+                    "on_state_enter"
+                } else {
+                    "on_state_exit"
+                }
+                .into(),
+                recipients: Recipients::All,
+                args: event.clone().into(),
+            },
+            priority,
+        )
+    }
+}
+
+impl<A: FuncArgs + Clone + Send + Sync + From<ScriptStateEvent> + 'static> APIProvider
+    for RhaiStateEventsProvider<A>
+{
+    type APITarget = Engine;
+    type ScriptContext = RhaiContext;
+    type DocTarget = RhaiDocFragment;
+
+    fn attach_api(&mut self, _: &mut Self::APITarget) -> Result<(), ScriptError> {
+        Ok(())
+    }
+
+    fn register_with_app(&self, app: &mut App) {
+        if !app.world.contains_resource::<Events<ScriptStateEvent>>() {
+            app.add_event::<ScriptStateEvent>();
+        }
+        let priority = self.priority;
+        app.add_system(
+            move |events: EventReader<ScriptStateEvent>,
+                  writer: PriorityEventWriter<RhaiEvent<A>>| {
+                forward_script_state_events(events, writer, priority)
+            },
+        );
+    }
+}
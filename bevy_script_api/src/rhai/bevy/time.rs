@@ -0,0 +1,60 @@
+use bevy_mod_scripting_core::{prelude::*, world::WorldPointer};
+use bevy_mod_scripting_rhai::{
+    prelude::*,
+    rhai::{Dynamic, FLOAT},
+};
+
+use crate::common::bevy::{ScriptTime, ScriptWorld};
+
+fn to_dynamic(value: Option<f64>) -> Dynamic {
+    value
+        .map(|v| Dynamic::from_float(v as FLOAT))
+        .unwrap_or(Dynamic::UNIT)
+}
+
+/// Provides the `time` variable, reading the frame time and fixed timesteps:
+///
+/// - `time.delta_seconds()` returns the seconds elapsed since the previous frame
+/// - `time.elapsed()` returns the seconds elapsed since the app started
+/// - `time.fixed_step(label)` returns the seconds between two steps of the labeled fixed timestep, or `()` if there is none
+/// - `time.fixed_overstep(label)` returns how far the labeled fixed timestep is into its next step, between 0 and 1, or `()` if there is none
+///
+/// See [`ScriptTime`] for how fixed timesteps are labeled.
+pub struct RhaiTimeAPIProvider;
+
+impl APIProvider for RhaiTimeAPIProvider {
+    type APITarget = Engine;
+    type ScriptContext = RhaiContext;
+    type DocTarget = RhaiDocFragment;
+
+    fn attach_api(&mut self, engine: &mut Self::APITarget) -> Result<(), ScriptError> {
+        engine
+            .register_type_with_name::<ScriptTime>("Time")
+            .register_fn("delta_seconds", |t: &mut ScriptTime| {
+                t.delta_seconds() as FLOAT
+            })
+            .register_fn("elapsed", |t: &mut ScriptTime| t.elapsed() as FLOAT)
+            .register_fn("fixed_step", |t: &mut ScriptTime, label: &str| {
+                to_dynamic(t.fixed_step(label))
+            })
+            .register_fn("fixed_overstep", |t: &mut ScriptTime, label: &str| {
+                to_dynamic(t.fixed_overstep(label))
+            });
+        Ok(())
+    }
+
+    fn script_variables(&self) -> Vec<&'static str> {
+        vec!["time"]
+    }
+
+    fn setup_script_runtime(
+        &mut self,
+        world_ptr: WorldPointer,
+        _script_data: &ScriptData,
+        ctx: &mut Self::ScriptContext,
+    ) -> Result<(), ScriptError> {
+        ctx.scope
+            .set_value("time", ScriptTime(ScriptWorld::new(world_ptr)));
+        Ok(())
+    }
+}
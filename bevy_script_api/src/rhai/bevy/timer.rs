@@ -0,0 +1,87 @@
+use bevy::prelude::App;
+use bevy_mod_scripting_core::prelude::*;
+
+#[allow(deprecated)]
+use bevy_mod_scripting_rhai::{
+    prelude::*,
+    rhai::{self, CustomType, FLOAT, INT},
+};
+
+use crate::common::bevy::{register_script_timers, ScriptStopwatch, ScriptTimer, ScriptWorld};
+
+use super::to_eval_error;
+
+#[allow(deprecated)]
+impl CustomType for ScriptTimer {
+    fn build(mut builder: rhai::TypeBuilder<Self>) {
+        builder
+            .with_name("Timer")
+            .with_fn("finished", |self_: &mut Self| self_.with(|t| t.finished()))
+            .with_fn("just_finished", |self_: &mut Self| {
+                self_.with(|t| t.just_finished())
+            })
+            .with_fn("times_finished", |self_: &mut Self| {
+                INT::from(self_.with(|t| t.times_finished_this_tick()))
+            })
+            .with_fn("elapsed", |self_: &mut Self| {
+                FLOAT::from(self_.with(|t| t.elapsed_secs()))
+            })
+            .with_fn("duration", |self_: &mut Self| {
+                FLOAT::from(self_.with(|t| t.duration().as_secs_f32()))
+            })
+            .with_fn("percent", |self_: &mut Self| {
+                FLOAT::from(self_.with(|t| t.percent()))
+            })
+            .with_fn("paused", |self_: &mut Self| self_.with(|t| t.paused()))
+            .with_fn("pause", |self_: &mut Self| self_.with(|t| t.pause()))
+            .with_fn("unpause", |self_: &mut Self| self_.with(|t| t.unpause()))
+            .with_fn("reset", |self_: &mut Self| self_.with(|t| t.reset()));
+    }
+}
+
+#[allow(deprecated)]
+impl CustomType for ScriptStopwatch {
+    fn build(mut builder: rhai::TypeBuilder<Self>) {
+        builder
+            .with_name("Stopwatch")
+            .with_fn("elapsed", |self_: &mut Self| {
+                FLOAT::from(self_.with(|t| t.elapsed_secs()))
+            })
+            .with_fn("paused", |self_: &mut Self| self_.with(|t| t.paused()))
+            .with_fn("pause", |self_: &mut Self| self_.with(|t| t.pause()))
+            .with_fn("unpause", |self_: &mut Self| self_.with(|t| t.unpause()))
+            .with_fn("reset", |self_: &mut Self| self_.with(|t| t.reset()));
+    }
+}
+
+/// Enables `world.create_timer(seconds, repeating)` and `world.create_stopwatch()`,
+/// the created objects are ticked every frame until the script stops referencing them.
+pub struct RhaiTimerAPIProvider;
+
+impl APIProvider for RhaiTimerAPIProvider {
+    type APITarget = Engine;
+    type ScriptContext = RhaiContext;
+    type DocTarget = RhaiDocFragment;
+
+    fn attach_api(&mut self, engine: &mut Self::APITarget) -> Result<(), ScriptError> {
+        engine
+            .build_type::<ScriptTimer>()
+            .build_type::<ScriptStopwatch>()
+            .register_fn(
+                "create_timer",
+                |world: &mut ScriptWorld, seconds: FLOAT, repeating: bool| {
+                    world
+                        .create_timer(seconds as f32, repeating)
+                        .map_err(to_eval_error)
+                },
+            )
+            .register_fn("create_stopwatch", |world: &mut ScriptWorld| {
+                world.create_stopwatch().map_err(to_eval_error)
+            });
+        Ok(())
+    }
+
+    fn register_with_app(&self, app: &mut App) {
+        register_script_timers(app);
+    }
+}
@@ -0,0 +1,53 @@
+use bevy::prelude::{Entity, Vec3};
+use bevy_mod_scripting_rhai::rhai::{Engine, FLOAT};
+
+use crate::common::bevy::ScriptWorld;
+
+use super::to_eval_error;
+
+/// Registers the transform helpers on the world, taking and returning `Vec3` values:
+///
+/// - `world.translate(entity, offset)` moves the entity by the offset
+/// - `world.look_at(entity, target)` and `world.look_at(entity, target, up)` rotate the entity to face the target, up defaults to the Y axis
+/// - `world.rotate_around(entity, point, axis, angle)` rotates the entity around the point by the angle in radians
+/// - `world.forward(entity)` and `world.world_position(entity)` read the facing direction and position in world space
+pub(crate) fn register_transform_helpers(engine: &mut Engine) {
+    engine
+        .register_fn(
+            "translate",
+            |world: &mut ScriptWorld, entity: Entity, offset: Vec3| {
+                world.translate(entity, offset).map_err(to_eval_error)
+            },
+        )
+        .register_fn(
+            "look_at",
+            |world: &mut ScriptWorld, entity: Entity, target: Vec3| {
+                world
+                    .look_at(entity, target, Vec3::Y)
+                    .map_err(to_eval_error)
+            },
+        )
+        .register_fn(
+            "look_at",
+            |world: &mut ScriptWorld, entity: Entity, target: Vec3, up: Vec3| {
+                world.look_at(entity, target, up).map_err(to_eval_error)
+            },
+        )
+        .register_fn(
+            "rotate_around",
+            |world: &mut ScriptWorld, entity: Entity, point: Vec3, axis: Vec3, angle: FLOAT| {
+                world
+                    .rotate_around(entity, point, axis, angle as f32)
+                    .map_err(to_eval_error)
+            },
+        )
+        .register_fn("forward", |world: &mut ScriptWorld, entity: Entity| {
+            world.forward(entity).map_err(to_eval_error)
+        })
+        .register_fn(
+            "world_position",
+            |world: &mut ScriptWorld, entity: Entity| {
+                world.world_position(entity).map_err(to_eval_error)
+            },
+        );
+}
@@ -0,0 +1,177 @@
+use std::marker::PhantomData;
+
+use bevy::prelude::{App, Entity, EventReader, Vec3};
+use bevy_mod_scripting_core::prelude::*;
+use bevy_mod_scripting_rhai::{
+    prelude::*,
+    rhai::{Array, Dynamic, FLOAT},
+};
+
+use crate::common::bevy::{
+    register_script_triggers, ScriptTriggerEvent, ScriptWorld, TriggerShape,
+};
+
+use super::to_eval_error;
+
+/// Registers methods on the `world` object attaching named trigger volumes to entities:
+///
+/// - `world.add_sphere_trigger(entity, name, radius)` attaches a sphere centered on the entity
+/// - `world.add_box_trigger(entity, name, hx, hy, hz)` attaches an axis aligned box with the given half extents
+/// - both take an optional offset from the entity as three more arguments
+/// - `world.remove_trigger(entity, name)` removes the trigger, returns false if there was none
+/// - `world.entities_in_trigger(entity, name)` returns the entities inside the trigger as of the last frame
+///
+/// The `on_trigger_enter` and `on_trigger_exit` hooks of the scripts on the entity owning the trigger are called
+/// with the arguments created from the [`ScriptTriggerEvent`] event, usually the trigger's name and the other entity.
+/// Hooks are sent with the priority given in `priority`.
+pub struct RhaiTriggersAPIProvider<A: FuncArgs + Clone + Send + Sync + 'static> {
+    pub priority: u32,
+    _ph: PhantomData<fn() -> A>,
+}
+
+impl<A: FuncArgs + Clone + Send + Sync + From<ScriptTriggerEvent> + 'static> Default
+    for RhaiTriggersAPIProvider<A>
+{
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl<A: FuncArgs + Clone + Send + Sync + From<ScriptTriggerEvent> + 'static>
+    RhaiTriggersAPIProvider<A>
+{
+    pub fn new(priority: u32) -> Self {
+        Self {
+            priority,
+            _ph: Default::default(),
+        }
+    }
+}
+
+fn vec3(x: FLOAT, y: FLOAT, z: FLOAT) -> Vec3 {
+    Vec3::new(x as f32, y as f32, z as f32)
+}
+
+fn forward_script_trigger_events<A: FuncArgs + Clone + Send + Sync + From<ScriptTriggerEvent>>(
+    mut events: EventReader<ScriptTriggerEvent>,
+    mut writer: PriorityEventWriter<RhaiEvent<A>>,
+    priority: u32,
+) {
+    for event in events.iter() {
+        writer.send(
+            RhaiEvent {
+                hook_name: if event.entered {
+                    "on_trigger_enter"
+                } else {
+                    "on_trigger_exit"
+                }
+                .into(),
+                recipients: Recipients::Entity(event.owner),
+                args: event.clone().into(),
+            },
+            priority,
+        )
+    }
+}
+
+impl<A: FuncArgs + Clone + Send + Sync + From<ScriptTriggerEvent> + 'static> APIProvider
+    for RhaiTriggersAPIProvider<A>
+{
+    type APITarget = Engine;
+    type ScriptContext = RhaiContext;
+    type DocTarget = RhaiDocFragment;
+
+    fn attach_api(&mut self, engine: &mut Self::APITarget) -> Result<(), ScriptError> {
+        engine
+            .register_fn(
+                "add_sphere_trigger",
+                |world: &mut ScriptWorld, entity: Entity, name: &str, radius: FLOAT| {
+                    let shape = TriggerShape::Sphere {
+                        radius: radius as f32,
+                    };
+                    world
+                        .add_trigger(entity, name.to_owned(), shape, Vec3::ZERO)
+                        .map_err(to_eval_error)
+                },
+            )
+            .register_fn(
+                "add_sphere_trigger",
+                |world: &mut ScriptWorld,
+                 entity: Entity,
+                 name: &str,
+                 radius: FLOAT,
+                 x: FLOAT,
+                 y: FLOAT,
+                 z: FLOAT| {
+                    let shape = TriggerShape::Sphere {
+                        radius: radius as f32,
+                    };
+                    world
+                        .add_trigger(entity, name.to_owned(), shape, vec3(x, y, z))
+                        .map_err(to_eval_error)
+                },
+            )
+            .register_fn(
+                "add_box_trigger",
+                |world: &mut ScriptWorld,
+                 entity: Entity,
+                 name: &str,
+                 hx: FLOAT,
+                 hy: FLOAT,
+                 hz: FLOAT| {
+                    let shape = TriggerShape::Box {
+                        half_extents: vec3(hx, hy, hz),
+                    };
+                    world
+                        .add_trigger(entity, name.to_owned(), shape, Vec3::ZERO)
+                        .map_err(to_eval_error)
+                },
+            )
+            .register_fn(
+                "add_box_trigger",
+                |world: &mut ScriptWorld,
+                 entity: Entity,
+                 name: &str,
+                 hx: FLOAT,
+                 hy: FLOAT,
+                 hz: FLOAT,
+                 x: FLOAT,
+                 y: FLOAT,
+                 z: FLOAT| {
+                    let shape = TriggerShape::Box {
+                        half_extents: vec3(hx, hy, hz),
+                    };
+                    world
+                        .add_trigger(entity, name.to_owned(), shape, vec3(x, y, z))
+                        .map_err(to_eval_error)
+                },
+            )
+            .register_fn(
+                "remove_trigger",
+                |world: &mut ScriptWorld, entity: Entity, name: &str| {
+                    world.remove_trigger(entity, name).map_err(to_eval_error)
+                },
+            )
+            .register_fn(
+                "entities_in_trigger",
+                |world: &mut ScriptWorld, entity: Entity, name: &str| {
+                    world
+                        .entities_in_trigger(entity, name)
+                        .map(|entities| entities.into_iter().map(Dynamic::from).collect::<Array>())
+                        .map_err(to_eval_error)
+                },
+            );
+        Ok(())
+    }
+
+    fn register_with_app(&self, app: &mut App) {
+        register_script_triggers(app);
+        let priority = self.priority;
+        app.add_system(
+            move |events: EventReader<ScriptTriggerEvent>,
+                  writer: PriorityEventWriter<RhaiEvent<A>>| {
+                forward_script_trigger_events(events, writer, priority)
+            },
+        );
+    }
+}
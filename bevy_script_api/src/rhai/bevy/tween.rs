@@ -0,0 +1,155 @@
+use std::marker::PhantomData;
+
+use bevy::prelude::{App, Entity, EventReader, Vec2, Vec3, Vec4};
+use bevy_mod_scripting_core::prelude::*;
+use bevy_mod_scripting_rhai::{
+    prelude::*,
+    rhai::{Dynamic, FLOAT, INT},
+};
+
+use crate::common::{
+    bevy::{register_script_tweens, ScriptTweenFinished, ScriptWorld, TweenValue},
+    mathx::Easing,
+};
+
+use super::to_eval_error;
+
+/// Registers methods on the `world` object interpolating fields of components over time on the Rust side:
+///
+/// - `world.tween(entity, path, target, duration)` tweens the field at the path, e.g. `"Transform.translation"`,
+///   from its current value to the target over `duration` seconds. Returns the ID of the tween
+/// - `world.tween(entity, path, target, duration, easing)` does the same with the named easing function, e.g. `"ease_out"`
+/// - `world.cancel_tween(id)` stops the tween where it is, returns false if it already finished
+///
+/// Targets are numbers, or arrays of 2 to 4 numbers for vectors, quaternions and colors.
+/// Once a tween finishes the `on_tween_finished` hook of the scripts attached to the entity is called with the arguments
+/// created from the [`ScriptTweenFinished`] event, usually the ID of the tween and the entity.
+/// Hooks are sent with the priority given in `priority`.
+pub struct RhaiTweenAPIProvider<A: FuncArgs + Clone + Send + Sync + 'static> {
+    pub priority: u32,
+    _ph: PhantomData<fn() -> A>,
+}
+
+impl<A: FuncArgs + Clone + Send + Sync + From<ScriptTweenFinished> + 'static> Default
+    for RhaiTweenAPIProvider<A>
+{
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl<A: FuncArgs + Clone + Send + Sync + From<ScriptTweenFinished> + 'static>
+    RhaiTweenAPIProvider<A>
+{
+    pub fn new(priority: u32) -> Self {
+        Self {
+            priority,
+            _ph: Default::default(),
+        }
+    }
+}
+
+fn tween_value(target: Dynamic) -> Result<TweenValue, Box<EvalAltResult>> {
+    if let Ok(v) = target.as_float() {
+        return Ok(TweenValue::F64(v as f64));
+    }
+    if let Ok(v) = target.as_int() {
+        return Ok(TweenValue::F64(v as f64));
+    }
+
+    let expected = "Cannot tween towards the target, expected a number or an array of numbers";
+    let c = target
+        .into_array()
+        .map_err(|_| expected)?
+        .into_iter()
+        .map(|v| match v.as_float() {
+            Ok(v) => Ok(v as f32),
+            Err(_) => v.as_int().map(|v| v as f32).map_err(|_| expected),
+        })
+        .collect::<Result<Vec<f32>, _>>()?;
+    Ok(match c[..] {
+        [x, y] => TweenValue::Vec2(Vec2::new(x, y)),
+        [x, y, z] => TweenValue::Vec3(Vec3::new(x, y, z)),
+        [x, y, z, w] => TweenValue::Vec4(Vec4::new(x, y, z, w)),
+        _ => return Err("Cannot tween towards an array which does not have 2 to 4 numbers".into()),
+    })
+}
+
+fn tween(
+    world: &mut ScriptWorld,
+    entity: Entity,
+    path: &str,
+    target: Dynamic,
+    duration: FLOAT,
+    easing: &str,
+) -> Result<INT, Box<EvalAltResult>> {
+    let easing: Easing = easing
+        .parse()
+        .map_err(|e: String| -> Box<EvalAltResult> { e.into() })?;
+    world
+        .tween(
+            entity,
+            path,
+            tween_value(target)?,
+            duration as f32,
+            easing,
+            Recipients::Entity(entity),
+        )
+        .map(INT::from)
+        .map_err(to_eval_error)
+}
+
+fn forward_script_tweens_finished<A: FuncArgs + Clone + Send + Sync + From<ScriptTweenFinished>>(
+    mut events: EventReader<ScriptTweenFinished>,
+    mut writer: PriorityEventWriter<RhaiEvent<A>>,
+    priority: u32,
+) {
+    for event in events.iter() {
+        writer.send(
+            RhaiEvent {
+                hook_name: "on_tween_finished".into(),
+                recipients: event.recipients.clone(),
+                args: event.clone().into(),
+            },
+            priority,
+        )
+    }
+}
+
+impl<A: FuncArgs + Clone + Send + Sync + From<ScriptTweenFinished> + 'static> APIProvider
+    for RhaiTweenAPIProvider<A>
+{
+    type APITarget = Engine;
+    type ScriptContext = RhaiContext;
+    type DocTarget = RhaiDocFragment;
+
+    fn attach_api(&mut self, engine: &mut Self::APITarget) -> Result<(), ScriptError> {
+        engine
+            .register_fn(
+                "tween",
+                |world: &mut ScriptWorld,
+                 entity: Entity,
+                 path: &str,
+                 target: Dynamic,
+                 duration: FLOAT| {
+                    tween(world, entity, path, target, duration, "linear")
+                },
+            )
+            .register_fn("tween", tween)
+            .register_fn("cancel_tween", |world: &mut ScriptWorld, tween: INT| {
+                u32::try_from(tween).map_or(false, |tween| world.cancel_tween(tween))
+            });
+        Ok(())
+    }
+
+    fn register_with_app(&self, app: &mut App) {
+        register_script_tweens(app);
+        let priority = self.priority;
+        app.add_system(
+            move |events: EventReader<ScriptTweenFinished>,
+                  writer: PriorityEventWriter<RhaiEvent<A>>| {
+                forward_script_tweens_finished(events, writer, priority)
+            },
+        );
+    }
+}
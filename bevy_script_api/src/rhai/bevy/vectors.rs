@@ -0,0 +1,117 @@
+use bevy::prelude::{Vec2, Vec3, Vec3A, Vec4};
+use bevy_mod_scripting_rhai::rhai::{Dynamic, Engine, EvalAltResult, ImmutableString, FLOAT, INT};
+
+use crate::{
+    common::vectors::{Swizzled, VectorComponents},
+    rhai::std::RhaiCopy,
+};
+
+impl RhaiCopy for Vec2 {}
+impl RhaiCopy for Vec3 {}
+impl RhaiCopy for Vec3A {}
+impl RhaiCopy for Vec4 {}
+
+type VectorResult<T> = Result<T, Box<EvalAltResult>>;
+
+fn to_index(index: INT) -> VectorResult<usize> {
+    usize::try_from(index).map_err(|_| format!("Index {index} is negative").into())
+}
+
+fn to_dynamic(swizzled: Swizzled) -> Dynamic {
+    match swizzled {
+        Swizzled::Float(v) => Dynamic::from_float(v.into()),
+        Swizzled::Vec2(v) => Dynamic::from(v),
+        Swizzled::Vec3(v) => Dynamic::from(v),
+        Swizzled::Vec4(v) => Dynamic::from(v),
+    }
+}
+
+macro_rules! register_vector {
+    ($engine:ident, $type:ty, $name:literal, $ctor:literal, [$($component:ident),*]) => {
+        $engine
+            .register_type_with_name::<$type>($name)
+            .register_fn($ctor, |$($component: FLOAT),*| <$type>::new($($component as f32),*))
+            $(
+                .register_get(stringify!($component), |v: &mut $type| FLOAT::from(v.$component))
+                .register_set(stringify!($component), |v: &mut $type, value: FLOAT| {
+                    v.$component = value as f32
+                })
+            )*
+            .register_indexer_get(|v: &mut $type, index: INT| -> VectorResult<FLOAT> {
+                Ok(v.component(to_index(index)?)?.into())
+            })
+            .register_indexer_set(|v: &mut $type, index: INT, value: FLOAT| -> VectorResult<()> {
+                Ok(v.set_component(to_index(index)?, value as f32)?)
+            })
+            // property access such as `v.zyx` falls back to string indexers when there is no getter
+            .register_indexer_get(|v: &mut $type, names: ImmutableString| -> VectorResult<Dynamic> {
+                Ok(to_dynamic(v.swizzle(&names)?))
+            })
+            .register_fn("+", |a: $type, b: $type| a + b)
+            .register_fn("-", |a: $type, b: $type| a - b)
+            .register_fn("*", |a: $type, b: $type| a * b)
+            .register_fn("/", |a: $type, b: $type| a / b)
+            .register_fn("*", |a: $type, b: FLOAT| a * b as f32)
+            .register_fn("*", |a: FLOAT, b: $type| a as f32 * b)
+            .register_fn("/", |a: $type, b: FLOAT| a / b as f32)
+            .register_fn("-", |a: $type| -a)
+            .register_fn("==", |a: $type, b: $type| a == b)
+            .register_fn("!=", |a: $type, b: $type| a != b)
+            .register_fn("dot", |a: &mut $type, b: $type| FLOAT::from(a.dot(b)))
+            .register_fn("length", |a: &mut $type| FLOAT::from(a.length()))
+            .register_fn("normalize", |a: &mut $type| a.normalize_or_zero())
+            .register_fn("lerp", |a: &mut $type, b: $type, t: FLOAT| a.lerp(b, t as f32))
+            .register_fn("to_string", |a: &mut $type| a.to_string())
+            .register_fn("to_debug", |a: &mut $type| format!("{a:?}"));
+    };
+}
+
+/// Registers the `Vec2`, `Vec3`, `Vec3A` and `Vec4` types along with their constructors `vec2(x, y)`, `vec3(x, y, z)`, `vec3a(x, y, z)` and `vec4(x, y, z, w)`.
+///
+/// Vectors support the arithmetic operators with each other and with floats, unary minus, `==` and `!=`,
+/// indexing components from 0 (`v[0]`) and swizzles (`v.zyx`), the same as the Lua proxies.
+pub(crate) fn register_vectors(engine: &mut Engine) {
+    register_vector!(engine, Vec2, "Vec2", "vec2", [x, y]);
+    register_vector!(engine, Vec3, "Vec3", "vec3", [x, y, z]);
+    register_vector!(engine, Vec3A, "Vec3A", "vec3a", [x, y, z]);
+    register_vector!(engine, Vec4, "Vec4", "vec4", [x, y, z, w]);
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_mod_scripting_rhai::rhai::Scope;
+
+    use crate::common::vectors::{Expected, CONFORMANCE_CASES};
+
+    use super::*;
+
+    fn expected(value: Dynamic) -> Expected {
+        if let Some(b) = value.clone().try_cast::<bool>() {
+            Expected::Bool(b)
+        } else if let Some(v) = value.clone().try_cast::<Vec2>() {
+            Expected::Value(Swizzled::Vec2(v))
+        } else if let Some(v) = value.clone().try_cast::<Vec3>() {
+            Expected::Value(Swizzled::Vec3(v))
+        } else if let Some(v) = value.clone().try_cast::<Vec4>() {
+            Expected::Value(Swizzled::Vec4(v))
+        } else {
+            Expected::Value(Swizzled::Float(value.as_float().unwrap() as f32))
+        }
+    }
+
+    #[test]
+    fn conforms() {
+        let mut engine = Engine::new();
+        register_vectors(&mut engine);
+        let mut scope = Scope::new();
+        scope.push("a", Vec3::new(1.0, 2.0, 3.0));
+        scope.push("b", Vec3::new(4.0, 5.0, 6.0));
+
+        for (expr, case) in CONFORMANCE_CASES {
+            let value = engine
+                .eval_with_scope::<Dynamic>(&mut scope, expr)
+                .unwrap_or_else(|e| panic!("`{expr}` failed: {e}"));
+            assert_eq!(expected(value), *case, "`{expr}`");
+        }
+    }
+}
@@ -0,0 +1,35 @@
+use bevy::prelude::{Entity, Vec3};
+use bevy_mod_scripting_core::prelude::*;
+use bevy_mod_scripting_rhai::{prelude::*, rhai::FLOAT};
+
+use crate::common::bevy::ScriptWorld;
+
+use super::to_eval_error;
+
+/// Registers methods on the `world` object triggering visual effects through the game's [`crate::common::bevy::ScriptVfxBackend`]:
+///
+/// - `world.spawn_vfx(name, x, y, z)` spawns the named effect at the given position and returns its entity
+/// - `world.stop_vfx(entity)` stops an effect spawned with `world.spawn_vfx`
+pub struct RhaiVfxAPIProvider;
+
+impl APIProvider for RhaiVfxAPIProvider {
+    type APITarget = Engine;
+    type ScriptContext = RhaiContext;
+    type DocTarget = RhaiDocFragment;
+
+    fn attach_api(&mut self, engine: &mut Self::APITarget) -> Result<(), ScriptError> {
+        engine
+            .register_fn(
+                "spawn_vfx",
+                |world: &mut ScriptWorld, effect: &str, x: FLOAT, y: FLOAT, z: FLOAT| {
+                    world
+                        .spawn_vfx(effect, Vec3::new(x as f32, y as f32, z as f32))
+                        .map_err(to_eval_error)
+                },
+            )
+            .register_fn("stop_vfx", |world: &mut ScriptWorld, entity: Entity| {
+                world.stop_vfx(entity).map_err(to_eval_error)
+            });
+        Ok(())
+    }
+}
@@ -0,0 +1,91 @@
+use std::marker::PhantomData;
+
+use bevy::prelude::{App, Entity, EventReader};
+use bevy_mod_scripting_core::prelude::*;
+use bevy_mod_scripting_rhai::{prelude::*, rhai::INT};
+
+use crate::common::bevy::{register_script_watches, ScriptFieldChanged, ScriptWorld};
+
+use super::to_eval_error;
+
+/// Forwards changes of fields watched with `world.watch(entity, path, hook)` to the scripts attached to the given entity.
+///
+/// The hook given to `watch` is called with the arguments created from the [`ScriptFieldChanged`] event,
+/// usually the entity and the old and new values. Watches can be stopped with `world.unwatch(id)`.
+/// Hooks are sent with the priority given in `priority`.
+pub struct RhaiWatchEventsProvider<A: FuncArgs + Clone + Send + Sync + 'static> {
+    pub priority: u32,
+    _ph: PhantomData<fn() -> A>,
+}
+
+impl<A: FuncArgs + Clone + Send + Sync + From<ScriptFieldChanged> + 'static> Default
+    for RhaiWatchEventsProvider<A>
+{
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl<A: FuncArgs + Clone + Send + Sync + From<ScriptFieldChanged> + 'static>
+    RhaiWatchEventsProvider<A>
+{
+    pub fn new(priority: u32) -> Self {
+        Self {
+            priority,
+            _ph: Default::default(),
+        }
+    }
+}
+
+fn forward_script_field_changes<A: FuncArgs + Clone + Send + Sync + From<ScriptFieldChanged>>(
+    mut events: EventReader<ScriptFieldChanged>,
+    mut writer: PriorityEventWriter<RhaiEvent<A>>,
+    priority: u32,
+) {
+    for event in events.iter() {
+        writer.send(
+            RhaiEvent {
+                hook_name: event.hook,
+                recipients: event.recipients.clone(),
+                args: event.clone().into(),
+            },
+            priority,
+        )
+    }
+}
+
+impl<A: FuncArgs + Clone + Send + Sync + From<ScriptFieldChanged> + 'static> APIProvider
+    for RhaiWatchEventsProvider<A>
+{
+    type APITarget = Engine;
+    type ScriptContext = RhaiContext;
+    type DocTarget = RhaiDocFragment;
+
+    fn attach_api(&mut self, engine: &mut Self::APITarget) -> Result<(), ScriptError> {
+        engine
+            .register_fn(
+                "watch",
+                |world: &mut ScriptWorld, entity: Entity, path: &str, hook: &str| {
+                    world
+                        .watch(entity, path, hook, Recipients::Entity(entity))
+                        .map(INT::from)
+                        .map_err(to_eval_error)
+                },
+            )
+            .register_fn("unwatch", |world: &mut ScriptWorld, watch: INT| {
+                u32::try_from(watch).map_or(false, |watch| world.unwatch(watch))
+            });
+        Ok(())
+    }
+
+    fn register_with_app(&self, app: &mut App) {
+        register_script_watches(app);
+        let priority = self.priority;
+        app.add_system(
+            move |events: EventReader<ScriptFieldChanged>,
+                  writer: PriorityEventWriter<RhaiEvent<A>>| {
+                forward_script_field_changes(events, writer, priority)
+            },
+        );
+    }
+}
@@ -0,0 +1,163 @@
+use bevy_mod_scripting_core::prelude::*;
+use bevy_mod_scripting_rhai::{
+    prelude::*,
+    rhai::{Array, EvalAltResult, Module, Position, FLOAT, INT},
+};
+
+use crate::common::buffer::{BufferType, BufferValue, ScriptBuffer, SharedF32Buffer};
+
+/// Registers the `buffer` static module and the `Buffer` type, binary buffers implemented in Rust:
+///
+/// - `buffer::new(len)` creates a buffer of `len` zero bytes
+/// - `buffer::from_string(text)` and `buffer::from_base64(text)` create a buffer from the bytes of the text or the decoded base64
+///
+/// Buffers read and write values with `buf.read(type, offset)` and `buf.write(type, offset, value)`,
+/// where the type is one of `"u8"`, `"i8"`, `"u16"`, `"i16"`, `"u32"`, `"i32"`, `"i64"`, `"f32"` or `"f64"`.
+/// Offsets are in bytes and start at 0, copies of a buffer share its bytes.
+///
+/// [`SharedF32Buffer`] event arguments arrive as read-only `F32Buffer` values indexed like arrays.
+pub struct RhaiBufferAPIProvider;
+
+type BufferResult<T> = Result<T, Box<EvalAltResult>>;
+
+fn to_eval_error(e: impl ToString) -> Box<EvalAltResult> {
+    Box::new(EvalAltResult::ErrorRuntime(
+        e.to_string().into(),
+        Position::NONE,
+    ))
+}
+
+fn to_usize(value: INT) -> BufferResult<usize> {
+    usize::try_from(value).map_err(|_| to_eval_error(format!("Invalid offset or length {value}")))
+}
+
+fn buffer_type(ty: &str) -> BufferResult<BufferType> {
+    ty.parse().map_err(to_eval_error)
+}
+
+fn write(buffer: &mut ScriptBuffer, ty: &str, offset: INT, value: BufferValue) -> BufferResult<()> {
+    buffer
+        .write(buffer_type(ty)?, to_usize(offset)?, value)
+        .map_err(to_eval_error)
+}
+
+fn register_buffer(engine: &mut Engine) {
+    engine
+        .register_type_with_name::<ScriptBuffer>("Buffer")
+        .register_get("len", |b: &mut ScriptBuffer| b.len() as INT)
+        .register_fn(
+            "resize",
+            |b: &mut ScriptBuffer, len: INT| -> BufferResult<()> {
+                b.resize(to_usize(len)?);
+                Ok(())
+            },
+        )
+        .register_fn(
+            "read",
+            |b: &mut ScriptBuffer, ty: &str, offset: INT| -> BufferResult<Dynamic> {
+                match b
+                    .read(buffer_type(ty)?, to_usize(offset)?)
+                    .map_err(to_eval_error)?
+                {
+                    BufferValue::Integer(i) => Ok(Dynamic::from_int(i)),
+                    BufferValue::Float(f) => Ok(Dynamic::from_float(f)),
+                }
+            },
+        )
+        .register_fn(
+            "write",
+            |b: &mut ScriptBuffer, ty: &str, offset: INT, value: INT| {
+                write(b, ty, offset, BufferValue::Integer(value))
+            },
+        )
+        .register_fn(
+            "write",
+            |b: &mut ScriptBuffer, ty: &str, offset: INT, value: FLOAT| {
+                write(b, ty, offset, BufferValue::Float(value))
+            },
+        )
+        .register_fn(
+            "read_string",
+            |b: &mut ScriptBuffer, offset: INT, len: INT| -> BufferResult<String> {
+                b.read_string(to_usize(offset)?, to_usize(len)?)
+                    .map_err(to_eval_error)
+            },
+        )
+        .register_fn(
+            "write_string",
+            |b: &mut ScriptBuffer, offset: INT, text: &str| -> BufferResult<INT> {
+                b.write_string(to_usize(offset)?, text)
+                    .map(|written| written as INT)
+                    .map_err(to_eval_error)
+            },
+        )
+        .register_fn(
+            "slice",
+            |b: &mut ScriptBuffer, start: INT, end: INT| -> BufferResult<ScriptBuffer> {
+                b.slice(to_usize(start)?, to_usize(end)?)
+                    .map_err(to_eval_error)
+            },
+        )
+        .register_fn("to_base64", |b: &mut ScriptBuffer| b.to_base64())
+        .register_fn("to_string", |b: &mut ScriptBuffer| {
+            format!("Buffer({} bytes)", b.len())
+        })
+        .register_fn("to_debug", |b: &mut ScriptBuffer| {
+            format!("Buffer({} bytes)", b.len())
+        });
+}
+
+fn register_f32_buffer(engine: &mut Engine) {
+    engine
+        .register_type_with_name::<SharedF32Buffer>("F32Buffer")
+        .register_get("len", |b: &mut SharedF32Buffer| b.len() as INT)
+        .register_indexer_get(
+            |b: &mut SharedF32Buffer, index: INT| -> BufferResult<FLOAT> {
+                to_usize(index)
+                    .ok()
+                    .and_then(|i| b.get(i))
+                    .map(FLOAT::from)
+                    .ok_or_else(|| {
+                        to_eval_error(format!(
+                            "Index {index} is out of bounds of a buffer of {} values",
+                            b.len()
+                        ))
+                    })
+            },
+        )
+        .register_fn("to_array", |b: &mut SharedF32Buffer| -> Array {
+            b.0.iter()
+                .map(|v| Dynamic::from_float((*v).into()))
+                .collect()
+        })
+        .register_fn("to_string", |b: &mut SharedF32Buffer| {
+            format!("F32Buffer({} values)", b.len())
+        })
+        .register_fn("to_debug", |b: &mut SharedF32Buffer| {
+            format!("F32Buffer({} values)", b.len())
+        });
+}
+
+impl APIProvider for RhaiBufferAPIProvider {
+    type APITarget = Engine;
+    type ScriptContext = RhaiContext;
+    type DocTarget = RhaiDocFragment;
+
+    fn attach_api(&mut self, engine: &mut Self::APITarget) -> Result<(), ScriptError> {
+        register_buffer(engine);
+        register_f32_buffer(engine);
+
+        let mut module = Module::new();
+        module.set_native_fn("new", |len: INT| -> BufferResult<ScriptBuffer> {
+            Ok(ScriptBuffer::new(to_usize(len)?))
+        });
+        module.set_native_fn("from_string", |text: &str| -> BufferResult<ScriptBuffer> {
+            Ok(ScriptBuffer::from_bytes(text.as_bytes().to_vec()))
+        });
+        module.set_native_fn("from_base64", |text: &str| {
+            ScriptBuffer::from_base64(text).map_err(to_eval_error)
+        });
+        engine.register_static_module("buffer", module.into());
+        Ok(())
+    }
+}
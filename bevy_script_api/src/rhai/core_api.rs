@@ -0,0 +1,41 @@
+use bevy_mod_scripting_core::prelude::*;
+use bevy_mod_scripting_rhai::prelude::*;
+
+use super::bevy::RhaiBevyAPIProvider;
+
+/// The bevy API modules enabled by the `api_*` cargo features, grouped into one provider
+pub type RhaiCoreAPIProvider = CompositeAPIProvider<Engine, RhaiContext, RhaiDocFragment>;
+
+/// Groups the [`RhaiBevyAPIProvider`] with the providers of every enabled bevy API module:
+/// ```rust,ignore
+/// app.add_script_host::<RhaiScriptHost<MyArgs>, _>(CoreStage::PostUpdate)
+///     .add_api_provider::<RhaiScriptHost<MyArgs>>(Box::new(rhai_core_api()));
+/// ```
+/// The `core_api` feature enables all modules. The reflection and hierarchy modules
+/// only change the methods of `world`, so they add no providers of their own.
+pub fn rhai_core_api() -> RhaiCoreAPIProvider {
+    #[allow(unused_mut)]
+    let mut api = RhaiCoreAPIProvider::new("core_api").with(RhaiBevyAPIProvider);
+    #[cfg(feature = "api_math")]
+    {
+        api = api
+            .with(super::mathx::RhaiMathxAPIProvider)
+            .with(super::fixed::RhaiFixedAPIProvider::default());
+    }
+    #[cfg(feature = "api_time")]
+    {
+        api = api
+            .with(super::bevy::RhaiTimeAPIProvider)
+            .with(super::bevy::RhaiTimerAPIProvider)
+            .with(super::instant::RhaiInstantAPIProvider);
+    }
+    #[cfg(feature = "api_input")]
+    {
+        api = api.with(super::bevy::RhaiInputAPIProvider);
+    }
+    #[cfg(feature = "api_assets")]
+    {
+        api = api.with(super::bevy::RhaiAssetsAPIProvider);
+    }
+    api
+}
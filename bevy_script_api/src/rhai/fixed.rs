@@ -0,0 +1,204 @@
+use ::std::sync::Arc;
+
+use bevy::{log::warn, utils::HashSet};
+use bevy_mod_scripting_core::{prelude::*, world::WorldPointer};
+use bevy_mod_scripting_rhai::{
+    prelude::*,
+    rhai::{EvalAltResult, Module, Position, FLOAT, INT},
+};
+use parking_lot::Mutex;
+
+use crate::common::fixed::{float_math_warning, Fixed, FixedVec2, FixedVec3};
+
+/// Registers the `fixed` static module and the `Fixed`, `FixedVec2` and `FixedVec3` types, deterministic fixed-point math implemented in Rust:
+///
+/// - `fixed::new(value)` creates a number from an integer or a decimal string such as `"1.25"`
+/// - `fixed::ratio(num, den)` creates the number `num / den`
+/// - `fixed::vec2(x, y)` and `fixed::vec3(x, y, z)` create vectors from fixed-point numbers or integers
+///
+/// Numbers and vectors support the arithmetic and comparison operators, mixed with integers where sensible.
+/// Floats are never converted implicitly, since their values may differ between platforms.
+///
+/// If `warn_float_math` is set, a warning is logged the first time each script calls one of the built-in float functions,
+/// such as `sin` or `sqrt`.
+#[derive(Default)]
+pub struct RhaiFixedAPIProvider {
+    pub warn_float_math: bool,
+    /// the script currently handling events, the engine is shared by all scripts
+    script: Arc<Mutex<String>>,
+}
+
+type FixedResult<T> = Result<T, Box<EvalAltResult>>;
+
+/// The built-in float functions overridden to warn when `warn_float_math` is set
+const FLOAT_MATH_FUNCTIONS: &[(&str, fn(FLOAT) -> FLOAT)] = &[
+    ("sin", FLOAT::sin),
+    ("cos", FLOAT::cos),
+    ("tan", FLOAT::tan),
+    ("asin", FLOAT::asin),
+    ("acos", FLOAT::acos),
+    ("atan", FLOAT::atan),
+    ("sinh", FLOAT::sinh),
+    ("cosh", FLOAT::cosh),
+    ("tanh", FLOAT::tanh),
+    ("exp", FLOAT::exp),
+    ("ln", FLOAT::ln),
+    ("log", FLOAT::log10),
+    ("sqrt", FLOAT::sqrt),
+];
+
+fn error(message: String) -> Box<EvalAltResult> {
+    Box::new(EvalAltResult::ErrorRuntime(message.into(), Position::NONE))
+}
+
+fn division_by_zero() -> Box<EvalAltResult> {
+    error("Fixed-point division by zero".to_owned())
+}
+
+fn div(a: Fixed, b: Fixed) -> FixedResult<Fixed> {
+    a.checked_div(b).ok_or_else(division_by_zero)
+}
+
+/// Registers the arithmetic and comparison operators of [`Fixed`]
+fn register_fixed(engine: &mut Engine) {
+    engine
+        .register_type_with_name::<Fixed>("Fixed")
+        .register_fn("+", |a: Fixed, b: Fixed| a + b)
+        .register_fn("+", |a: Fixed, b: INT| a + Fixed::from_int(b))
+        .register_fn("+", |a: INT, b: Fixed| Fixed::from_int(a) + b)
+        .register_fn("-", |a: Fixed, b: Fixed| a - b)
+        .register_fn("-", |a: Fixed, b: INT| a - Fixed::from_int(b))
+        .register_fn("-", |a: INT, b: Fixed| Fixed::from_int(a) - b)
+        .register_fn("-", |a: Fixed| -a)
+        .register_fn("*", |a: Fixed, b: Fixed| a * b)
+        .register_fn("*", |a: Fixed, b: INT| a * Fixed::from_int(b))
+        .register_fn("*", |a: INT, b: Fixed| Fixed::from_int(a) * b)
+        .register_fn("/", div)
+        .register_fn("/", |a: Fixed, b: INT| div(a, Fixed::from_int(b)))
+        .register_fn("/", |a: INT, b: Fixed| div(Fixed::from_int(a), b))
+        .register_fn("==", |a: Fixed, b: Fixed| a == b)
+        .register_fn("!=", |a: Fixed, b: Fixed| a != b)
+        .register_fn("<", |a: Fixed, b: Fixed| a < b)
+        .register_fn("<=", |a: Fixed, b: Fixed| a <= b)
+        .register_fn(">", |a: Fixed, b: Fixed| a > b)
+        .register_fn(">=", |a: Fixed, b: Fixed| a >= b)
+        .register_fn("floor", |a: &mut Fixed| a.to_int())
+        .register_fn("abs", |a: &mut Fixed| a.abs())
+        .register_fn("sqrt", |a: &mut Fixed| -> FixedResult<Fixed> {
+            a.sqrt()
+                .ok_or_else(|| error(format!("Cannot take the square root of {a}")))
+        })
+        .register_fn("to_float", |a: &mut Fixed| a.to_f64())
+        .register_fn("to_string", |a: &mut Fixed| a.to_string())
+        .register_fn("to_debug", |a: &mut Fixed| a.to_string());
+}
+
+/// Registers the components and operators of [`FixedVec2`] and [`FixedVec3`]
+fn register_fixed_vecs(engine: &mut Engine) {
+    engine
+        .register_type_with_name::<FixedVec2>("FixedVec2")
+        .register_get("x", |v: &mut FixedVec2| v.x)
+        .register_get("y", |v: &mut FixedVec2| v.y)
+        .register_fn("+", |a: FixedVec2, b: FixedVec2| a + b)
+        .register_fn("-", |a: FixedVec2, b: FixedVec2| a - b)
+        .register_fn("-", |a: FixedVec2| -a)
+        .register_fn("*", |a: FixedVec2, b: Fixed| a * b)
+        .register_fn("*", |a: FixedVec2, b: INT| a * Fixed::from_int(b))
+        .register_fn("==", |a: FixedVec2, b: FixedVec2| a == b)
+        .register_fn("!=", |a: FixedVec2, b: FixedVec2| a != b)
+        .register_fn("dot", |a: &mut FixedVec2, b: FixedVec2| a.dot(b))
+        .register_fn("length", |a: &mut FixedVec2| a.length())
+        .register_fn("normalize", |a: &mut FixedVec2| a.normalize_or_zero())
+        .register_fn("to_string", |a: &mut FixedVec2| a.to_string())
+        .register_fn("to_debug", |a: &mut FixedVec2| a.to_string());
+
+    engine
+        .register_type_with_name::<FixedVec3>("FixedVec3")
+        .register_get("x", |v: &mut FixedVec3| v.x)
+        .register_get("y", |v: &mut FixedVec3| v.y)
+        .register_get("z", |v: &mut FixedVec3| v.z)
+        .register_fn("+", |a: FixedVec3, b: FixedVec3| a + b)
+        .register_fn("-", |a: FixedVec3, b: FixedVec3| a - b)
+        .register_fn("-", |a: FixedVec3| -a)
+        .register_fn("*", |a: FixedVec3, b: Fixed| a * b)
+        .register_fn("*", |a: FixedVec3, b: INT| a * Fixed::from_int(b))
+        .register_fn("==", |a: FixedVec3, b: FixedVec3| a == b)
+        .register_fn("!=", |a: FixedVec3, b: FixedVec3| a != b)
+        .register_fn("dot", |a: &mut FixedVec3, b: FixedVec3| a.dot(b))
+        .register_fn("cross", |a: &mut FixedVec3, b: FixedVec3| a.cross(b))
+        .register_fn("length", |a: &mut FixedVec3| a.length())
+        .register_fn("normalize", |a: &mut FixedVec3| a.normalize_or_zero())
+        .register_fn("to_string", |a: &mut FixedVec3| a.to_string())
+        .register_fn("to_debug", |a: &mut FixedVec3| a.to_string());
+}
+
+/// Converts fixed-point numbers, integers and decimal strings to fixed-point numbers
+fn to_fixed(value: Dynamic) -> FixedResult<Fixed> {
+    if value.is::<Fixed>() {
+        Ok(value.cast::<Fixed>())
+    } else if let Ok(i) = value.as_int() {
+        Ok(Fixed::from_int(i))
+    } else if value.is_string() {
+        value.to_string().parse().map_err(error)
+    } else {
+        Err(error(format!(
+            "Expected a fixed-point number, an integer or a decimal string but got `{}`, floats are not converted since they are not deterministic",
+            value.type_name()
+        )))
+    }
+}
+
+impl APIProvider for RhaiFixedAPIProvider {
+    type APITarget = Engine;
+    type ScriptContext = RhaiContext;
+    type DocTarget = RhaiDocFragment;
+
+    fn attach_api(&mut self, engine: &mut Self::APITarget) -> Result<(), ScriptError> {
+        register_fixed(engine);
+        register_fixed_vecs(engine);
+
+        let mut module = Module::new();
+        module.set_native_fn("new", to_fixed);
+        module.set_native_fn("ratio", |num: INT, den: INT| -> FixedResult<Fixed> {
+            Fixed::from_ratio(num, den).ok_or_else(division_by_zero)
+        });
+        module.set_native_fn("vec2", |x: Dynamic, y: Dynamic| -> FixedResult<FixedVec2> {
+            Ok(FixedVec2::new(to_fixed(x)?, to_fixed(y)?))
+        });
+        module.set_native_fn(
+            "vec3",
+            |x: Dynamic, y: Dynamic, z: Dynamic| -> FixedResult<FixedVec3> {
+                Ok(FixedVec3::new(to_fixed(x)?, to_fixed(y)?, to_fixed(z)?))
+            },
+        );
+        engine.register_static_module("fixed", module.into());
+
+        if self.warn_float_math {
+            let warned = Arc::new(Mutex::new(HashSet::<(String, &'static str)>::default()));
+            for (name, f) in FLOAT_MATH_FUNCTIONS.iter().copied() {
+                let script = self.script.clone();
+                let warned = warned.clone();
+                engine.register_fn(name, move |x: FLOAT| {
+                    let script = script.lock();
+                    if warned.lock().insert((script.clone(), name)) {
+                        warn!("{}", float_math_warning(&script, name));
+                    }
+                    f(x)
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn setup_script_runtime(
+        &mut self,
+        _world_ptr: WorldPointer,
+        script_data: &ScriptData,
+        _ctx: &mut Self::ScriptContext,
+    ) -> Result<(), ScriptError> {
+        if self.warn_float_math {
+            *self.script.lock() = script_data.name.to_owned();
+        }
+        Ok(())
+    }
+}
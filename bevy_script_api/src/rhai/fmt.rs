@@ -0,0 +1,109 @@
+use ::std::sync::Arc;
+
+use bevy_mod_scripting_core::{prelude::*, world::WorldPointer};
+use bevy_mod_scripting_rhai::{
+    prelude::*,
+    rhai::{EvalAltResult, Map, Module, Position, FLOAT, INT},
+};
+use parking_lot::Mutex;
+
+use crate::common::fmt::{self, FmtValue, ScriptLocale, ScriptLocales};
+
+/// Registers the `fmt` static module, formatting user facing text with the locale of the script, see [`ScriptLocales`]:
+///
+/// - `fmt::format(template, values)` replaces `{name}` in the template with `values.name`,
+///   `{name:2}` formats a number with 2 decimals
+/// - `fmt::number(value, decimals)` formats a number with digit grouping, `fmt::number(1234.5, 2)` gives `1,234.50`
+/// - `fmt::duration(seconds)` formats seconds as a clock, `fmt::duration(125)` gives `2:05`
+#[derive(Default)]
+pub struct RhaiFmtAPIProvider {
+    /// the locale of the script currently handling events, the engine is shared by all scripts
+    locale: Arc<Mutex<ScriptLocale>>,
+}
+
+type FmtResult<T> = Result<T, Box<EvalAltResult>>;
+
+fn to_fmt_value(value: &Dynamic) -> Option<FmtValue> {
+    if let Ok(i) = value.as_int() {
+        Some(FmtValue::Integer(i64::from(i)))
+    } else if let Ok(f) = value.as_float() {
+        Some(FmtValue::Float(f64::from(f)))
+    } else if let Ok(b) = value.as_bool() {
+        Some(FmtValue::Bool(b))
+    } else if value.is_string() {
+        Some(FmtValue::String(value.to_string()))
+    } else {
+        None
+    }
+}
+
+impl APIProvider for RhaiFmtAPIProvider {
+    type APITarget = Engine;
+    type ScriptContext = RhaiContext;
+    type DocTarget = RhaiDocFragment;
+
+    fn attach_api(&mut self, engine: &mut Self::APITarget) -> Result<(), ScriptError> {
+        let mut module = Module::new();
+
+        let locale = self.locale.clone();
+        module.set_native_fn(
+            "format",
+            move |template: &str, values: Map| -> FmtResult<String> {
+                fmt::interpolate(template, &locale.lock(), |name| {
+                    values.get(name).and_then(to_fmt_value)
+                })
+                .map_err(|e| {
+                    Box::new(EvalAltResult::ErrorRuntime(
+                        e.to_string().into(),
+                        Position::NONE,
+                    ))
+                })
+            },
+        );
+        let locale = self.locale.clone();
+        module.set_native_fn(
+            "number",
+            move |value: FLOAT, decimals: INT| -> FmtResult<String> {
+                Ok(fmt::format_number(
+                    f64::from(value),
+                    decimals.max(0) as usize,
+                    &locale.lock(),
+                ))
+            },
+        );
+        let locale = self.locale.clone();
+        module.set_native_fn(
+            "number",
+            move |value: INT, decimals: INT| -> FmtResult<String> {
+                Ok(fmt::format_number(
+                    value as f64,
+                    decimals.max(0) as usize,
+                    &locale.lock(),
+                ))
+            },
+        );
+        module.set_native_fn("duration", |seconds: FLOAT| -> FmtResult<String> {
+            Ok(fmt::format_duration(f64::from(seconds)))
+        });
+        module.set_native_fn("duration", |seconds: INT| -> FmtResult<String> {
+            Ok(fmt::format_duration(seconds as f64))
+        });
+
+        engine.register_static_module("fmt", module.into());
+        Ok(())
+    }
+
+    fn setup_script_runtime(
+        &mut self,
+        world_ptr: WorldPointer,
+        script_data: &ScriptData,
+        _ctx: &mut Self::ScriptContext,
+    ) -> Result<(), ScriptError> {
+        *self.locale.lock() = world_ptr
+            .read()
+            .get_resource::<ScriptLocales>()
+            .map(|locales| locales.for_script(script_data.name).clone())
+            .unwrap_or_default();
+        Ok(())
+    }
+}
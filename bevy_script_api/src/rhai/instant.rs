@@ -0,0 +1,124 @@
+use bevy_mod_scripting_core::prelude::*;
+use bevy_mod_scripting_rhai::{
+    prelude::*,
+    rhai::{EvalAltResult, Module, Position, FLOAT, INT},
+};
+
+use crate::common::instant::{ScriptDuration, ScriptInstant};
+
+/// Registers the `Instant` and `Duration` static modules and types, monotonic timestamps and spans of time:
+///
+/// - `Instant::now()` returns the current instant, `instant.elapsed()` the duration passed since
+/// - `Duration::from_secs(s)`, `Duration::from_millis(ms)` and `Duration::from_micros(us)` create durations
+///
+/// Subtracting two instants gives the duration between them, and instants can be offset by adding durations.
+/// Durations can be added, subtracted, multiplied and divided by numbers, and compared.
+pub struct RhaiInstantAPIProvider;
+
+type InstantResult<T> = Result<T, Box<EvalAltResult>>;
+
+fn div(d: ScriptDuration, divisor: FLOAT) -> InstantResult<ScriptDuration> {
+    d.div_f64(divisor).ok_or_else(|| {
+        Box::new(EvalAltResult::ErrorRuntime(
+            "Cannot divide a duration by zero".into(),
+            Position::NONE,
+        ))
+    })
+}
+
+fn register_instant(engine: &mut Engine) {
+    engine
+        .register_type_with_name::<ScriptInstant>("Instant")
+        .register_fn("+", |a: ScriptInstant, b: ScriptDuration| a + b)
+        .register_fn("-", |a: ScriptInstant, b: ScriptInstant| a - b)
+        .register_fn("==", |a: ScriptInstant, b: ScriptInstant| a == b)
+        .register_fn("!=", |a: ScriptInstant, b: ScriptInstant| a != b)
+        .register_fn("<", |a: ScriptInstant, b: ScriptInstant| a < b)
+        .register_fn("<=", |a: ScriptInstant, b: ScriptInstant| a <= b)
+        .register_fn(">", |a: ScriptInstant, b: ScriptInstant| a > b)
+        .register_fn(">=", |a: ScriptInstant, b: ScriptInstant| a >= b)
+        .register_fn("elapsed", |a: &mut ScriptInstant| a.elapsed())
+        .register_fn(
+            "duration_since",
+            |a: &mut ScriptInstant, earlier: ScriptInstant| a.duration_since(earlier),
+        )
+        .register_fn("to_string", |a: &mut ScriptInstant| a.to_string())
+        .register_fn("to_debug", |a: &mut ScriptInstant| a.to_string());
+}
+
+fn register_duration(engine: &mut Engine) {
+    engine
+        .register_type_with_name::<ScriptDuration>("Duration")
+        .register_fn("+", |a: ScriptDuration, b: ScriptDuration| a + b)
+        .register_fn("-", |a: ScriptDuration, b: ScriptDuration| a - b)
+        .register_fn("*", |a: ScriptDuration, b: FLOAT| a.mul_f64(b))
+        .register_fn("*", |a: ScriptDuration, b: INT| a.mul_f64(b as f64))
+        .register_fn("*", |a: FLOAT, b: ScriptDuration| b.mul_f64(a))
+        .register_fn("*", |a: INT, b: ScriptDuration| b.mul_f64(a as f64))
+        .register_fn("/", div)
+        .register_fn("/", |a: ScriptDuration, b: INT| div(a, b as f64))
+        .register_fn("==", |a: ScriptDuration, b: ScriptDuration| a == b)
+        .register_fn("!=", |a: ScriptDuration, b: ScriptDuration| a != b)
+        .register_fn("<", |a: ScriptDuration, b: ScriptDuration| a < b)
+        .register_fn("<=", |a: ScriptDuration, b: ScriptDuration| a <= b)
+        .register_fn(">", |a: ScriptDuration, b: ScriptDuration| a > b)
+        .register_fn(">=", |a: ScriptDuration, b: ScriptDuration| a >= b)
+        .register_fn("as_secs", |a: &mut ScriptDuration| a.as_secs())
+        .register_fn("as_millis", |a: &mut ScriptDuration| a.as_millis())
+        .register_fn("as_micros", |a: &mut ScriptDuration| a.as_micros())
+        .register_fn("to_string", |a: &mut ScriptDuration| a.to_string())
+        .register_fn("to_debug", |a: &mut ScriptDuration| a.to_string());
+}
+
+impl APIProvider for RhaiInstantAPIProvider {
+    type APITarget = Engine;
+    type ScriptContext = RhaiContext;
+    type DocTarget = RhaiDocFragment;
+
+    fn attach_api(&mut self, engine: &mut Self::APITarget) -> Result<(), ScriptError> {
+        register_instant(engine);
+        register_duration(engine);
+
+        let mut instant = Module::new();
+        instant.set_native_fn("now", || -> InstantResult<ScriptInstant> {
+            Ok(ScriptInstant::now())
+        });
+        engine.register_static_module("Instant", instant.into());
+
+        let mut duration = Module::new();
+        duration.set_native_fn(
+            "from_secs",
+            |secs: FLOAT| -> InstantResult<ScriptDuration> { Ok(ScriptDuration::from_secs(secs)) },
+        );
+        duration.set_native_fn(
+            "from_millis",
+            |millis: FLOAT| -> InstantResult<ScriptDuration> {
+                Ok(ScriptDuration::from_millis(millis))
+            },
+        );
+        duration.set_native_fn(
+            "from_micros",
+            |micros: FLOAT| -> InstantResult<ScriptDuration> {
+                Ok(ScriptDuration::from_micros(micros))
+            },
+        );
+        // integer literals are not converted to floats implicitly
+        duration.set_native_fn("from_secs", |secs: INT| -> InstantResult<ScriptDuration> {
+            Ok(ScriptDuration::from_secs(secs as f64))
+        });
+        duration.set_native_fn(
+            "from_millis",
+            |millis: INT| -> InstantResult<ScriptDuration> {
+                Ok(ScriptDuration::from_millis(millis as f64))
+            },
+        );
+        duration.set_native_fn(
+            "from_micros",
+            |micros: INT| -> InstantResult<ScriptDuration> {
+                Ok(ScriptDuration::from_micros(micros as f64))
+            },
+        );
+        engine.register_static_module("Duration", duration.into());
+        Ok(())
+    }
+}
@@ -0,0 +1,128 @@
+use bevy::prelude::Quat;
+use bevy_mod_scripting_core::prelude::*;
+use bevy_mod_scripting_rhai::{
+    prelude::*,
+    rhai::{EvalAltResult, Module, Position, FLOAT, INT},
+};
+
+use crate::common::mathx::{self, Easing};
+
+/// Registers the `mathx` static module, containing math utilities implemented in Rust:
+///
+/// - `mathx::lerp(a, b, t)`, `mathx::inverse_lerp(a, b, value)` and `mathx::remap(value, from_min, from_max, to_min, to_max)`
+/// - `mathx::clamp(value, min, max)`
+/// - `mathx::slerp(a, b, t)` for rotations given as `[x, y, z, w]` arrays of floats
+/// - `mathx::ease(name, t)` applying an easing function such as `"ease_in_out_cubic"` to `t`
+/// - `mathx::noise2(seed, x, y)` and `mathx::noise3(seed, x, y, z)`, seeded gradient noise in the range [-1,1]
+pub struct RhaiMathxAPIProvider;
+
+type MathxResult<T> = Result<T, Box<EvalAltResult>>;
+
+fn to_quat(value: Vec<Dynamic>) -> MathxResult<Quat> {
+    let mut components = [0.0; 4];
+    if value.len() != 4 {
+        return Err(Box::new(EvalAltResult::ErrorRuntime(
+            "Expected an array of 4 floats".into(),
+            Position::NONE,
+        )));
+    }
+    for (c, v) in components.iter_mut().zip(value) {
+        *c = v.as_float().map_err(|t| {
+            Box::new(EvalAltResult::ErrorMismatchDataType(
+                "float".to_owned(),
+                t.to_owned(),
+                Position::NONE,
+            ))
+        })? as f32;
+    }
+    Ok(Quat::from_array(components))
+}
+
+impl APIProvider for RhaiMathxAPIProvider {
+    type APITarget = Engine;
+    type ScriptContext = RhaiContext;
+    type DocTarget = RhaiDocFragment;
+
+    fn attach_api(&mut self, engine: &mut Self::APITarget) -> Result<(), ScriptError> {
+        let mut module = Module::new();
+
+        module.set_native_fn(
+            "lerp",
+            |a: FLOAT, b: FLOAT, t: FLOAT| -> MathxResult<FLOAT> {
+                Ok(FLOAT::from(mathx::lerp(a as f32, b as f32, t as f32)))
+            },
+        );
+        module.set_native_fn(
+            "inverse_lerp",
+            |a: FLOAT, b: FLOAT, value: FLOAT| -> MathxResult<FLOAT> {
+                Ok(FLOAT::from(mathx::inverse_lerp(
+                    a as f32,
+                    b as f32,
+                    value as f32,
+                )))
+            },
+        );
+        module.set_native_fn(
+            "remap",
+            |value: FLOAT,
+             from_min: FLOAT,
+             from_max: FLOAT,
+             to_min: FLOAT,
+             to_max: FLOAT|
+             -> MathxResult<FLOAT> {
+                Ok(FLOAT::from(mathx::remap(
+                    value as f32,
+                    from_min as f32,
+                    from_max as f32,
+                    to_min as f32,
+                    to_max as f32,
+                )))
+            },
+        );
+        module.set_native_fn(
+            "clamp",
+            |value: FLOAT, min: FLOAT, max: FLOAT| -> MathxResult<FLOAT> {
+                Ok(FLOAT::from(mathx::clamp(
+                    value as f32,
+                    min as f32,
+                    max as f32,
+                )))
+            },
+        );
+        module.set_native_fn(
+            "slerp",
+            |a: Vec<Dynamic>, b: Vec<Dynamic>, t: FLOAT| -> MathxResult<Vec<Dynamic>> {
+                let q = mathx::slerp(to_quat(a)?, to_quat(b)?, t as f32);
+                Ok(q.to_array()
+                    .into_iter()
+                    .map(|v| Dynamic::from_float(v.into()))
+                    .collect::<Vec<Dynamic>>())
+            },
+        );
+        module.set_native_fn("ease", |name: &str, t: FLOAT| {
+            name.parse::<Easing>()
+                .map(|easing| FLOAT::from(easing.ease(t as f32)))
+                .map_err(|e| Box::new(EvalAltResult::ErrorRuntime(e.into(), Position::NONE)))
+        });
+        module.set_native_fn(
+            "noise2",
+            |seed: INT, x: FLOAT, y: FLOAT| -> MathxResult<FLOAT> {
+                Ok(FLOAT::from(mathx::noise2(seed as u32, x as f32, y as f32)))
+            },
+        );
+        module.set_native_fn(
+            "noise3",
+            |seed: INT, x: FLOAT, y: FLOAT, z: FLOAT| -> MathxResult<FLOAT> {
+                Ok(FLOAT::from(mathx::noise3(
+                    seed as u32,
+                    x as f32,
+                    y as f32,
+                    z as f32,
+                )))
+            },
+        );
+
+        engine.register_static_module("mathx", module.into());
+        Ok(())
+    }
+}
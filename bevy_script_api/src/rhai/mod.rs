@@ -5,12 +5,25 @@ use ::bevy::{
     reflect::{FromType, GetTypeRegistration, Reflect},
 };
 #[allow(deprecated)]
-use bevy_mod_scripting_rhai::rhai::{CustomType, Dynamic, EvalAltResult, INT};
+use bevy_mod_scripting_rhai::rhai::{
+    Array, CustomType, Dynamic, EvalAltResult, ImmutableString, Map, Position, INT,
+};
 
-use crate::{ReflectedValue, ScriptRef, ValueIndex};
+use crate::{common::shared::ScriptValue, ReflectedValue, ScriptRef, ValueIndex};
 
 pub mod bevy;
+pub mod buffer;
+pub mod core_api;
+#[cfg(feature = "api_math")]
+pub mod fixed;
+pub mod fmt;
+#[cfg(feature = "api_time")]
+pub mod instant;
+#[cfg(feature = "api_math")]
+pub mod mathx;
+pub mod run_once;
 pub mod std;
+pub mod testing;
 
 /// A trait allowing the registration of the [`RhaiProxyable`] trait with the type registry for foreign types
 ///
@@ -185,3 +198,61 @@ impl CustomType for ReflectedValue {
             });
     }
 }
+
+impl ToDynamic for ScriptValue {
+    fn to_dynamic(self) -> Result<Dynamic, Box<EvalAltResult>> {
+        Ok(match self {
+            ScriptValue::Nil => Dynamic::UNIT,
+            ScriptValue::Bool(b) => b.into(),
+            ScriptValue::Integer(i) => Dynamic::from_int(i),
+            ScriptValue::Number(n) => Dynamic::from_float(n),
+            ScriptValue::String(s) => s.into(),
+            ScriptValue::List(l) => l
+                .into_iter()
+                .map(ToDynamic::to_dynamic)
+                .collect::<Result<Array, _>>()?
+                .into(),
+            ScriptValue::Map(m) => m
+                .into_iter()
+                .map(|(k, v)| Ok((k.into(), v.to_dynamic()?)))
+                .collect::<Result<Map, Box<EvalAltResult>>>()?
+                .into(),
+        })
+    }
+}
+
+impl FromRhaiProxy for ScriptValue {
+    fn from_rhai_proxy(self_: Dynamic) -> Result<Self, Box<EvalAltResult>> {
+        if self_.is::<()>() {
+            Ok(ScriptValue::Nil)
+        } else if let Ok(b) = self_.as_bool() {
+            Ok(ScriptValue::Bool(b))
+        } else if let Ok(i) = self_.as_int() {
+            Ok(ScriptValue::Integer(i))
+        } else if let Ok(n) = self_.as_float() {
+            Ok(ScriptValue::Number(n))
+        } else if self_.is::<ImmutableString>() {
+            Ok(ScriptValue::String(self_.cast::<ImmutableString>().into()))
+        } else if self_.is::<Array>() {
+            self_
+                .cast::<Array>()
+                .into_iter()
+                .map(ScriptValue::from_rhai_proxy)
+                .collect::<Result<_, _>>()
+                .map(ScriptValue::List)
+        } else if self_.is::<Map>() {
+            self_
+                .cast::<Map>()
+                .into_iter()
+                .map(|(k, v)| Ok((k.to_string(), ScriptValue::from_rhai_proxy(v)?)))
+                .collect::<Result<_, Box<EvalAltResult>>>()
+                .map(ScriptValue::Map)
+        } else {
+            Err(Box::new(EvalAltResult::ErrorMismatchDataType(
+                "(), bool, number, string, array or map".to_owned(),
+                self_.type_name().to_owned(),
+                Position::NONE,
+            )))
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use bevy::prelude::World;
+use bevy_mod_scripting_core::{prelude::*, world::WorldPointer};
+use bevy_mod_scripting_rhai::{prelude::*, rhai::EvalAltResult, script_runtime_error};
+
+use crate::common::{run_once::RunOnceHost, shared::ScriptValue};
+
+use super::{FromRhaiProxy, ToDynamic};
+
+impl<A: FuncArgs + Send + Clone + Sync + 'static> RunOnceHost for RhaiScriptHost<A> {
+    fn call_hook_once(
+        &mut self,
+        world: &mut World,
+        hook: &str,
+        args: ScriptValue,
+        script_data: &ScriptData,
+        ctx: &mut Self::ScriptContext,
+        providers: &mut APIProviders<Self>,
+    ) -> Result<Option<ScriptValue>, ScriptError> {
+        // safety:
+        // - we have &mut World access
+        // - we do not use world_ptr after we use the original reference again anywhere in this function
+        let world_ptr = unsafe { WorldPointer::new(world) };
+        providers.setup_runtime_all(world_ptr.clone(), script_data, ctx)?;
+
+        let result = if ctx.ast.iter_functions().any(|f| f.name == hook) {
+            let args = match args {
+                ScriptValue::Nil => Ok(Vec::default()),
+                ScriptValue::List(l) => l
+                    .into_iter()
+                    .map(ScriptValue::to_dynamic)
+                    .collect::<Result<Vec<_>, _>>(),
+                v => v.to_dynamic().map(|v| vec![v]),
+            };
+            args.and_then(|mut args| {
+                self.engine.call_fn_raw(
+                    &mut ctx.scope,
+                    &ctx.ast,
+                    true,
+                    false,
+                    hook,
+                    None,
+                    &mut args,
+                )
+            })
+            .and_then(ScriptValue::from_rhai_proxy)
+            .map(Some)
+        } else {
+            // global statements still run, as they would before the hook is called
+            self.engine
+                .run_ast_with_scope(&mut ctx.scope, &ctx.ast)
+                .map(|_| None)
+        };
+
+        world_ptr.invalidate();
+        result.map_err(|e: Box<EvalAltResult>| script_runtime_error(script_data.name, &e))
+    }
+}
@@ -0,0 +1,98 @@
+use bevy::prelude::App;
+use bevy_mod_scripting_core::{prelude::*, testing::ScriptTestOutput};
+use bevy_mod_scripting_rhai::{
+    prelude::*,
+    rhai::{Dynamic, EvalAltResult, FnPtr, NativeCallContext, Position},
+};
+
+/// Provides the assertion API used by test scripts run with `script_tests!`:
+///
+/// - `assert_eq(actual, expected[, message])` failing unless both values are equal
+/// - `expect_error(f[, contains])` calling the function pointer `f` and failing unless it raises an error (containing the given text),
+/// returns the error message
+///
+/// The output of `print` is also redirected to the [`ScriptTestOutput`] compared against golden output files,
+/// since the engine is shared this applies to every script.
+#[derive(Default)]
+pub struct RhaiTestAPIProvider {
+    output: ScriptTestOutput,
+}
+
+type TestResult<T> = Result<T, Box<EvalAltResult>>;
+
+fn fail<T>(msg: String) -> TestResult<T> {
+    Err(Box::new(EvalAltResult::ErrorRuntime(
+        msg.into(),
+        Position::NONE,
+    )))
+}
+
+fn values_equal(a: &Dynamic, b: &Dynamic) -> bool {
+    match (a.as_float(), b.as_float(), a.as_int(), b.as_int()) {
+        (Ok(a), Ok(b), _, _) => a == b,
+        (Ok(a), _, _, Ok(b)) | (_, Ok(a), Ok(b), _) => a == b as f64,
+        _ => a.type_name() == b.type_name() && a.to_string() == b.to_string(),
+    }
+}
+
+fn assert_eq(actual: Dynamic, expected: Dynamic, message: Option<&str>) -> TestResult<()> {
+    if values_equal(&actual, &expected) {
+        return Ok(());
+    }
+    let error = format!("assertion failed: expected `{expected}`, got `{actual}`");
+    fail(match message {
+        Some(message) => format!("{error}: {message}"),
+        None => error,
+    })
+}
+
+fn expect_error(ctx: NativeCallContext, f: FnPtr, contains: Option<&str>) -> TestResult<String> {
+    match f.call_within_context::<Dynamic>(&ctx, ()) {
+        Ok(_) => fail("expected an error, but the function succeeded".to_owned()),
+        Err(e) => {
+            let error = e.to_string();
+            match contains {
+                Some(text) if !error.contains(text) => fail(format!(
+                    "expected an error containing `{text}`, got `{error}`"
+                )),
+                _ => Ok(error),
+            }
+        }
+    }
+}
+
+impl APIProvider for RhaiTestAPIProvider {
+    type APITarget = Engine;
+    type ScriptContext = RhaiContext;
+    type DocTarget = RhaiDocFragment;
+
+    fn attach_api(&mut self, engine: &mut Self::APITarget) -> Result<(), ScriptError> {
+        engine
+            .register_fn("assert_eq", |actual: Dynamic, expected: Dynamic| {
+                assert_eq(actual, expected, None)
+            })
+            .register_fn(
+                "assert_eq",
+                |actual: Dynamic, expected: Dynamic, message: &str| {
+                    assert_eq(actual, expected, Some(message))
+                },
+            )
+            .register_fn("expect_error", |ctx: NativeCallContext, f: FnPtr| {
+                expect_error(ctx, f, None)
+            })
+            .register_fn(
+                "expect_error",
+                |ctx: NativeCallContext, f: FnPtr, contains: &str| {
+                    expect_error(ctx, f, Some(contains))
+                },
+            );
+
+        let output = self.output.clone();
+        engine.on_print(move |line| output.push(line.to_owned()));
+        Ok(())
+    }
+
+    fn register_with_app(&self, app: &mut App) {
+        app.insert_resource(self.output.clone());
+    }
+}
@@ -4,7 +4,7 @@ use std::fmt::{Debug, Display};
 use std::{borrow::Cow, sync::Weak};
 
 use bevy::{
-    prelude::{Entity, ReflectComponent, ReflectResource},
+    prelude::{Entity, ReflectComponent, ReflectResource, World},
     reflect::{Reflect, ReflectMut, ReflectRef},
 };
 
@@ -332,6 +332,20 @@ impl ReflectPath {
         }
     }
 
+    /// Produces an error explaining why the component base of this path could not be reached
+    fn missing_component_error(&self, world: &World, entity: Entity) -> ReflectionError {
+        let reason = if world.get_entity(entity).is_none() {
+            "The entity this component belonged to was despawned"
+        } else {
+            "Given component does not exist on this entity"
+        };
+
+        ReflectionError::InvalidBaseReference {
+            base: self.base.to_string(),
+            reason: reason.to_owned(),
+        }
+    }
+
     pub fn len(&self) -> u8 {
         self.accesses.len() as u8
     }
@@ -348,12 +362,10 @@ impl ReflectPath {
             ReflectBase::Component { comp, entity } => {
                 let g = world_ptr.read();
 
-                let ref_ = self.walk_path(comp.reflect(&g, *entity).ok_or_else(|| {
-                    ReflectionError::InvalidBaseReference {
-                        base: self.base.to_string(),
-                        reason: "Given component does not exist on this entity".to_owned(),
-                    }
-                })?)?;
+                let ref_ = self.walk_path(
+                    comp.reflect(&g, *entity)
+                        .ok_or_else(|| self.missing_component_error(&g, *entity))?,
+                )?;
                 // unsafe since pointer may be dangling
                 let o = f(ref_);
                 drop(g);
@@ -396,6 +408,10 @@ impl ReflectPath {
             ReflectBase::Component { comp, entity } => {
                 let mut g = world_ptr.write();
 
+                if g.get_entity(*entity).is_none() {
+                    return Err(self.missing_component_error(&g, *entity));
+                }
+
                 let ref_ = self.walk_path_mut(
                     comp.reflect_mut(&mut g, *entity)
                         .ok_or_else(|| ReflectionError::InvalidBaseReference {
@@ -220,7 +220,7 @@ fn main() -> std::io::Result<()> {
                         entity,
                         world,
                         LuaEvent {
-                            hook_name: "once".to_owned(),
+                            hook_name: "once".into(),
                             args: (),
                             recipients: Recipients::All,
                         },
@@ -63,7 +63,7 @@ fn fire_random_event(
             args.push(arg);
             (
                 LuaEvent {
-                    hook_name: v.0.to_string(),
+                    hook_name: v.0.into(),
                     args,
                     recipients: Recipients::All,
                 },
@@ -4,7 +4,7 @@ use bevy_mod_scripting::prelude::*;
 /// fire on_update
 fn do_update(mut w: PriorityEventWriter<LuaEvent<()>>, time: Res<Time>) {
     let event = LuaEvent {
-        hook_name: "on_update".to_owned(),
+        hook_name: "on_update".into(),
         args: (),
         recipients: Recipients::All,
     };
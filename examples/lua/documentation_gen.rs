@@ -101,6 +101,16 @@ fn main() -> std::io::Result<()> {
         .add_script_host::<LuaScriptHost<MyLuaArg>, _>(CoreStage::PostUpdate)
         .add_api_provider::<LuaScriptHost<MyLuaArg>>(Box::new(LuaAPIProvider))
         .add_api_provider::<LuaScriptHost<MyLuaArg>>(Box::new(LuaBevyAPIProvider))
+        // hooks emitted by the game are documented alongside the API, in `assets/scripts/doc/MyAPI_hooks.md`
+        .register_script_hook(HookDoc::new("on_update", "every frame"))
+        .register_script_hook(
+            HookDoc::new(
+                "on_collision",
+                "when the script's entity collides with another",
+            )
+            .with_arg("entity", "Entity", "the entity owning the script")
+            .with_arg("other", "Entity", "the entity it collided with"),
+        )
         // this needs to be placed after any `add_api_provider` and `add_script_host` calls
         // it will generate `doc` and `types` folders under `assets/scripts` containing the documentation and teal declaration files
         // respectively. See example asset folder to see how they look like. The `teal_file.tl` script in example assets shows the usage of one of those
@@ -68,7 +68,7 @@ fn fire_random_event(w: &mut PriorityEventWriter<LuaEvent<MyLuaArg>>, events: &[
     let event = events
         .choose(&mut rng)
         .map(|v| LuaEvent {
-            hook_name: v.0.to_string(),
+            hook_name: v.0.into(),
             args: arg,
             recipients: v.1.clone(),
         })
@@ -86,12 +86,16 @@ fn do_update(mut w: PriorityEventWriter<LuaEvent<MyLuaArg>>) {
 
     let all_events = [
         ScriptEventData("on_event", Recipients::All),
-        ScriptEventData("on_event", Recipients::ScriptID(0)),
-        ScriptEventData("on_event", Recipients::ScriptID(1)),
+        ScriptEventData("on_event", Recipients::Id(0)),
+        ScriptEventData("on_event", Recipients::Id(1)),
         ScriptEventData(
             "on_event",
             Recipients::ScriptName("scripts/event_recipients.lua".to_owned()),
         ),
+        ScriptEventData(
+            "on_event",
+            Recipients::Many(vec![Recipients::Id(0), Recipients::Id(1)]),
+        ),
     ];
 
     // fire random event, for any stages
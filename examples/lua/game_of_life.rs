@@ -191,7 +191,7 @@ pub fn update_rendered_state(
 pub fn send_on_update(mut events: PriorityEventWriter<LuaEvent<()>>) {
     events.send(
         LuaEvent {
-            hook_name: "on_update".to_owned(),
+            hook_name: "on_update".into(),
             args: (),
             recipients: Recipients::All,
         },
@@ -203,7 +203,7 @@ pub fn send_on_update(mut events: PriorityEventWriter<LuaEvent<()>>) {
 pub fn send_init(mut events: PriorityEventWriter<LuaEvent<()>>) {
     events.send(
         LuaEvent {
-            hook_name: "init".to_owned(),
+            hook_name: "init".into(),
             args: (),
             recipients: Recipients::All,
         },
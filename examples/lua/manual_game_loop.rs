@@ -0,0 +1,48 @@
+use bevy::{ecs::system::SystemState, prelude::*, utils::Duration};
+use bevy_mod_scripting::prelude::*;
+
+const FIXED_STEPS_PER_FRAME: u32 = 2;
+
+fn load_our_script(server: Res<AssetServer>, mut commands: Commands) {
+    let path = "scripts/manual_game_loop.lua";
+    let handle = server.load::<LuaFile, &str>(path);
+
+    commands.spawn(()).insert(ScriptCollection::<LuaFile> {
+        scripts: vec![Script::<LuaFile>::new(path.to_string(), handle)],
+    });
+}
+
+/// An external game loop driving the app, as a custom runner or an engine embedding bevy would.
+///
+/// No handler stage is added, instead the loop sends a script event for every fixed step
+/// and lets the scripts handle it right away with `handle_events_manual`.
+fn main() {
+    let mut app = App::new();
+
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(AssetPlugin::default())
+        .add_plugin(ScriptingPlugin)
+        .add_startup_system(load_our_script)
+        .add_script_host::<LuaScriptHost<u32>, _>(CoreStage::PostUpdate);
+
+    let mut events = SystemState::<PriorityEventWriter<LuaEvent<u32>>>::new(&mut app.world);
+
+    for frame in 0..300 {
+        // runs the regular bevy systems, which load the script and keep its context up to date
+        app.update();
+
+        for step in 0..FIXED_STEPS_PER_FRAME {
+            events.get_mut(&mut app.world).send(
+                LuaEvent {
+                    hook_name: "on_fixed_step".into(),
+                    args: frame * FIXED_STEPS_PER_FRAME + step,
+                    recipients: Recipients::All,
+                },
+                0,
+            );
+            LuaScriptHost::<u32>::handle_events_manual(&mut app.world, 0, 0);
+        }
+
+        std::thread::sleep(Duration::from_millis(16));
+    }
+}
@@ -149,7 +149,7 @@ fn main() -> std::io::Result<()> {
                     entity,
                     world,
                     RhaiEvent {
-                        hook_name: "once".to_owned(),
+                        hook_name: "once".into(),
                         args: (),
                         recipients: Recipients::All,
                     },
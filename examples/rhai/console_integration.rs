@@ -48,7 +48,7 @@ impl APIProvider for RhaiAPI {
 // in the designated stage
 pub fn trigger_on_update_rhai(mut w: PriorityEventWriter<RhaiEvent<()>>) {
     let event = RhaiEvent {
-        hook_name: "on_update".to_string(),
+        hook_name: "on_update".into(),
         args: (),
         recipients: Recipients::All,
     };
@@ -72,42 +72,54 @@ pub fn forward_script_err_to_console(
 // but since we aren't despawning/spawning entities this works in our case
 #[derive(ConsoleCommand)]
 #[console_command(name = "run_script")]
-///Runs a Lua script from the `assets/scripts` directory
+///Runs a script from the `assets/scripts` directory on the given host, e.g.: `run_script rhai /hello.rhai 5 '{"speed":2}'`
 pub struct RunScriptCmd {
-    /// the relative path to the script, e.g.: `/hello.lua` for a script located in `assets/scripts/hello.lua`
+    /// the name of the host to run the script with, e.g.: `rhai`
+    pub host: String,
+
+    /// the relative path to the script, e.g.: `/hello.rhai` for a script located in `assets/scripts/hello.rhai`
     pub path: String,
 
     /// the entity id to attach this script to
     pub entity: Option<u32>,
-}
 
-pub fn run_script_cmd(
-    mut log: ConsoleCommand<RunScriptCmd>,
-    server: Res<AssetServer>,
-    mut commands: Commands,
-    mut existing_scripts: Query<&mut ScriptCollection<RhaiFile>>,
-) {
-    if let Some(Ok(RunScriptCmd { path, entity })) = log.take() {
-        let handle = server.load::<RhaiFile, &str>(&format!("scripts/{}", &path));
-
-        match entity {
-            Some(e) => {
-                if let Ok(mut scripts) = existing_scripts.get_mut(Entity::from_raw(e)) {
-                    info!("Creating script: scripts/{} {:?}", &path, e);
+    /// raw parameters stored alongside the script in its entity's `ScriptParams`
+    pub params: Option<String>,
+}
 
-                    scripts.scripts.push(Script::<RhaiFile>::new(path, handle));
-                } else {
-                    log.reply_failed("Something went wrong".to_string());
-                };
-            }
-            None => {
-                info!("Creating script: scripts/{}", &path);
+pub fn run_script_cmd(mut log: ConsoleCommand<RunScriptCmd>, mut commands: Commands) {
+    if let Some(Ok(RunScriptCmd {
+        host,
+        path,
+        entity,
+        params,
+    })) = log.take()
+    {
+        let request = ScriptLaunchRequest {
+            host,
+            path,
+            entity: entity.map(Entity::from_raw),
+            params,
+        };
 
-                commands.spawn(()).insert(ScriptCollection::<RhaiFile> {
-                    scripts: vec![Script::<RhaiFile>::new(path, handle)],
+        // launching needs exclusive world access
+        commands.add(move |world: &mut World| {
+            let line =
+                world.resource_scope(|world, launchers: Mut<ScriptLaunchers>| {
+                    match launchers.launch(world, request) {
+                        Ok(launched) => format!(
+                            "Host `{}` is running script {} on entity {:?}",
+                            launched.host, launched.asset_path, launched.entity
+                        ),
+                        Err(e) => format!("ERROR:{e}"),
+                    }
                 });
-            }
-        };
+
+            info!("{line}");
+            world
+                .resource_mut::<Events<PrintConsoleLine>>()
+                .send(PrintConsoleLine { line });
+        });
     }
 }
 
@@ -153,6 +165,118 @@ pub struct DeleteScriptCmd {
     pub entity_id: u32,
 }
 
+#[derive(ConsoleCommand)]
+#[console_command(name = "list_scripts")]
+///Lists every attached script with its entity, host, load state and timings
+pub struct ListScriptsCmd;
+
+pub fn list_scripts_cmd(mut log: ConsoleCommand<ListScriptsCmd>, mut commands: Commands) {
+    if let Some(Ok(ListScriptsCmd)) = log.take() {
+        commands.add(|world: &mut World| {
+            let statuses = world.resource::<ScriptLaunchers>().list_scripts(world);
+            let mut lines = statuses
+                .into_iter()
+                .map(|s| {
+                    format!(
+                        "[{}] {} on {:?} ({}): {:?}{}, avg hook time: {}{}",
+                        s.sid,
+                        s.name,
+                        s.entity,
+                        s.host,
+                        s.load_state,
+                        if s.enabled { "" } else { ", disabled" },
+                        s.average_hook_time
+                            .map(|t| format!("{t:?}"))
+                            .unwrap_or_else(|| "-".to_owned()),
+                        s.last_error
+                            .map(|e| format!(", last error: {e}"))
+                            .unwrap_or_default()
+                    )
+                })
+                .collect::<Vec<_>>();
+
+            if lines.is_empty() {
+                lines.push("No scripts attached".to_owned());
+            }
+
+            let mut events = world.resource_mut::<Events<PrintConsoleLine>>();
+            for line in lines {
+                events.send(PrintConsoleLine { line });
+            }
+        });
+    }
+}
+
+#[derive(ConsoleCommand)]
+#[console_command(name = "rhai>")]
+///Evaluates Rhai code in an interactive context which persists between commands,
+///snippets spanning multiple lines are continued with further `rhai>` commands
+pub struct ReplCmd {
+    /// the line of code, e.g.: `rhai> "let x = 1 + 2;"`
+    pub code: String,
+}
+
+pub fn repl_cmd(
+    mut log: ConsoleCommand<ReplCmd>,
+    mut repl: ResMut<ScriptRepl<RhaiScriptHost<()>>>,
+) {
+    if let Some(Ok(ReplCmd { code })) = log.take() {
+        repl.submit(code);
+    }
+}
+
+#[derive(ConsoleCommand)]
+#[console_command(name = "rhai_history")]
+///Prints previously evaluated Rhai snippets, or cancels the snippet being typed in
+pub struct ReplHistoryCmd {
+    /// discard the unfinished multi-line snippet instead of printing the history
+    pub cancel: Option<bool>,
+}
+
+pub fn repl_history_cmd(
+    mut log: ConsoleCommand<ReplHistoryCmd>,
+    mut repl: ResMut<ScriptRepl<RhaiScriptHost<()>>>,
+) {
+    if let Some(Ok(ReplHistoryCmd { cancel })) = log.take() {
+        if cancel.unwrap_or(false) {
+            repl.cancel();
+            log.reply_ok("Cancelled snippet");
+            return;
+        }
+
+        for (i, snippet) in repl.history().enumerate() {
+            log.reply(format!("[{i}] {snippet}"));
+        }
+    }
+}
+
+/// prints the results of evaluated snippets, pretty-printed by the host
+pub fn forward_repl_output_to_console(
+    mut repl: ResMut<ScriptRepl<RhaiScriptHost<()>>>,
+    mut w: EventWriter<PrintConsoleLine>,
+) {
+    if !repl.is_changed() {
+        return;
+    }
+
+    let awaiting_more = repl.awaiting_more();
+    for output in repl.drain_output() {
+        w.send(PrintConsoleLine {
+            line: match output.result {
+                Ok(Some(value)) => value,
+                Ok(None) => continue,
+                Err(e) => format!("ERROR:{e}"),
+            },
+        });
+    }
+
+    if awaiting_more {
+        w.send(PrintConsoleLine {
+            line: "..".to_owned(),
+        });
+    }
+}
+
 fn main() -> std::io::Result<()> {
     let mut app = App::new();
     app.add_plugins(DefaultPlugins)
@@ -162,16 +286,26 @@ fn main() -> std::io::Result<()> {
         // register bevy_console commands
         .add_console_command::<RunScriptCmd, _>(run_script_cmd)
         .add_console_command::<DeleteScriptCmd, _>(delete_script_cmd)
+        .add_console_command::<ListScriptsCmd, _>(list_scripts_cmd)
+        .add_console_command::<ReplCmd, _>(repl_cmd)
+        .add_console_command::<ReplHistoryCmd, _>(repl_history_cmd)
         // choose and register the script hosts you want to use
         .add_script_host::<RhaiScriptHost<()>, _>(CoreStage::PostUpdate)
+        .insert_resource(
+            ScriptLaunchers::default().with_host::<RhaiScriptHost<()>>("rhai", &["rhai"]),
+        )
         .add_api_provider::<RhaiScriptHost<()>>(Box::new(RhaiAPI))
         .add_api_provider::<RhaiScriptHost<()>>(Box::new(RhaiBevyAPIProvider))
         .add_script_handler_stage::<RhaiScriptHost<()>, _, 0, 0>(CoreStage::PostUpdate)
         // add your systems
         .add_system(trigger_on_update_rhai)
-        .add_system(forward_script_err_to_console);
+        .add_system(forward_script_err_to_console)
+        // interactive Rhai snippets, evaluated with the same API providers as the scripts
+        .init_resource::<ScriptRepl<RhaiScriptHost<()>>>()
+        .add_system(script_repl_system::<RhaiScriptHost<()>>)
+        .add_system(forward_repl_output_to_console);
 
-    info!("press '~' to open the console. Type in `run_script \"console_integration.rhai\"` to run example script!");
+    info!("press '~' to open the console. Type in `run_script rhai \"console_integration.rhai\"` to run example script!");
 
     app.run();
 
@@ -176,7 +176,7 @@ pub fn update_rendered_state(
 pub fn send_on_update(mut events: PriorityEventWriter<RhaiEvent<()>>) {
     events.send(
         RhaiEvent {
-            hook_name: "on_update".to_owned(),
+            hook_name: "on_update".into(),
             args: (),
             recipients: Recipients::All,
         },
@@ -188,7 +188,7 @@ pub fn send_on_update(mut events: PriorityEventWriter<RhaiEvent<()>>) {
 pub fn send_init(mut events: PriorityEventWriter<RhaiEvent<()>>) {
     events.send(
         RhaiEvent {
-            hook_name: "init".to_owned(),
+            hook_name: "init".into(),
             args: (),
             recipients: Recipients::All,
         },
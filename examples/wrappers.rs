@@ -116,7 +116,7 @@ fn main() -> std::io::Result<()> {
                     Entity::from_raw(0),
                     world,
                     LuaEvent {
-                        hook_name: "once".to_owned(),
+                        hook_name: "once".into(),
                         args: LuaMyThing::new(MyThing {
                             usize: 42,
                             string: "Haha! Yes I can!!!!".to_owned(),
@@ -0,0 +1,77 @@
+use bevy::{
+    asset::{AssetLoader, Error, LoadedAsset},
+    reflect::TypeUuid,
+};
+use bevy_mod_scripting_core::asset::CodeAsset;
+
+use std::sync::Arc;
+
+use crate::graph::ScriptGraph;
+
+#[derive(Debug, TypeUuid)]
+#[uuid = "a3f06d2e-81c4-4b9a-b7e5-4d19c0e2f6a8"]
+/// A node graph, stored as RON regardless of the format it was authored in
+pub struct GraphFile {
+    pub bytes: Arc<[u8]>,
+    /// the error of graphs which could not be parsed or link to nodes which do not exist, reported instead of running the script
+    pub load_error: Option<String>,
+}
+
+impl CodeAsset for GraphFile {
+    fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    fn load_error(&self) -> Option<&str> {
+        self.load_error.as_deref()
+    }
+}
+
+/// Parses a graph authored in RON, or JSON if the path ends in `.json`
+fn parse_graph(bytes: &[u8], json: bool) -> Result<ScriptGraph, String> {
+    let graph: ScriptGraph = if json {
+        serde_json::from_slice(bytes).map_err(|e| e.to_string())?
+    } else {
+        ron::de::from_bytes(bytes).map_err(|e| e.to_string())?
+    };
+    graph.validate()?;
+    Ok(graph)
+}
+
+#[derive(Default)]
+/// Asset loader for node graphs, in `.graph.ron` or `.graph.json` files
+pub struct GraphLoader;
+
+impl AssetLoader for GraphLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut bevy::asset::LoadContext,
+    ) -> bevy::asset::BoxedFuture<'a, Result<(), Error>> {
+        let json = load_context
+            .path()
+            .extension()
+            .map_or(false, |e| e == "json");
+
+        let asset = match parse_graph(bytes, json)
+            .and_then(|graph| ron::to_string(&graph).map_err(|e| e.to_string()))
+        {
+            Ok(ron) => GraphFile {
+                bytes: ron.as_bytes().into(),
+                load_error: None,
+            },
+            // still loaded, so that the scripts using it report the error instead of never starting
+            Err(e) => GraphFile {
+                bytes: Arc::from(&[][..]),
+                load_error: Some(e),
+            },
+        };
+        load_context.set_default_asset(LoadedAsset::new(asset));
+
+        Box::pin(async move { Ok(()) })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["graph.ron", "graph.json"]
+    }
+}
@@ -0,0 +1,297 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// The most nodes a single hook may run, graphs can loop back onto themselves
+/// and would otherwise freeze the game when they never exit the loop
+pub const MAX_GRAPH_STEPS: usize = 10_000;
+
+/// The index of a node within the `nodes` of its graph
+pub type NodeId = usize;
+
+/// A value flowing through a node graph
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum GraphValue {
+    Null,
+    Bool(bool),
+    Num(f64),
+    Str(String),
+    List(Vec<GraphValue>),
+}
+
+impl GraphValue {
+    /// Everything but null and false continues a branch with `then`
+    pub fn is_truthy(&self) -> bool {
+        !matches!(self, GraphValue::Null | GraphValue::Bool(false))
+    }
+}
+
+macro_rules! impl_from_graph_value {
+    ($($ty:ty => $variant:ident),*) => {
+        $(impl From<$ty> for GraphValue {
+            fn from(value: $ty) -> Self {
+                GraphValue::$variant(value.into())
+            }
+        })*
+    };
+}
+
+impl_from_graph_value!(bool => Bool, f64 => Num, f32 => Num, i32 => Num, u32 => Num, String => Str, &str => Str, Vec<GraphValue> => List);
+
+/// Where a node takes one of its inputs from
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum GraphInput {
+    /// a constant
+    Value(GraphValue),
+    /// the argument of the hook being handled at the given index, null if there are fewer arguments
+    Arg(usize),
+    /// a variable of the script, null until set. Variables keep their values between hooks
+    Var(String),
+    /// the value returned by the latest call of the given `Call` node, null until it is called
+    Output(NodeId),
+}
+
+/// A node of a graph, nodes run one after another starting from the `Event` nodes of the handled hook
+/// and following their `next` node until there is none
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum GraphNode {
+    /// The entry point of a hook, a graph is not subscribed to hooks it has no event nodes for
+    Event {
+        hook: String,
+        #[serde(default)]
+        next: Option<NodeId>,
+    },
+    /// Calls the node function of the given name registered by an API provider
+    Call {
+        function: String,
+        #[serde(default)]
+        args: Vec<GraphInput>,
+        #[serde(default)]
+        next: Option<NodeId>,
+    },
+    /// Continues with `then` if the condition is neither null nor false, and with `otherwise` if it is
+    Branch {
+        condition: GraphInput,
+        #[serde(default)]
+        then: Option<NodeId>,
+        #[serde(default)]
+        otherwise: Option<NodeId>,
+    },
+    /// Sets a variable of the script
+    Set {
+        var: String,
+        value: GraphInput,
+        #[serde(default)]
+        next: Option<NodeId>,
+    },
+}
+
+/// The values a graph keeps between hooks
+#[derive(Debug, Clone, Default)]
+pub struct GraphState {
+    pub variables: HashMap<String, GraphValue>,
+    outputs: HashMap<NodeId, GraphValue>,
+}
+
+/// A graph of nodes making up a script, deserialized from RON or JSON, e.g.:
+///
+/// ```ron
+/// (
+///     nodes: [
+///         Event(hook: "on_update", next: Some(1)),
+///         Call(function: "print", args: [Value(Str("hello"))]),
+///     ],
+/// )
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ScriptGraph {
+    pub nodes: Vec<GraphNode>,
+}
+
+impl ScriptGraph {
+    /// Checks that every node only refers to nodes which exist
+    pub fn validate(&self) -> Result<(), String> {
+        for (id, node) in self.nodes.iter().enumerate() {
+            let (inputs, next): (Vec<&GraphInput>, Vec<Option<NodeId>>) = match node {
+                GraphNode::Event { next, .. } => (vec![], vec![*next]),
+                GraphNode::Call { args, next, .. } => (args.iter().collect(), vec![*next]),
+                GraphNode::Branch {
+                    condition,
+                    then,
+                    otherwise,
+                } => (vec![condition], vec![*then, *otherwise]),
+                GraphNode::Set { value, next, .. } => (vec![value], vec![*next]),
+            };
+
+            if let Some(missing) = next.into_iter().flatten().find(|n| *n >= self.nodes.len()) {
+                return Err(format!(
+                    "node {id} continues with node {missing} which does not exist"
+                ));
+            }
+            for input in inputs {
+                if let GraphInput::Output(output) = input {
+                    if !matches!(self.nodes.get(*output), Some(GraphNode::Call { .. })) {
+                        return Err(format!(
+                            "node {id} takes the output of node {output} which is not a call node"
+                        ));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The names of the node functions called by the graph
+    pub fn functions(&self) -> impl Iterator<Item = &str> {
+        self.nodes.iter().filter_map(|node| match node {
+            GraphNode::Call { function, .. } => Some(function.as_str()),
+            _ => None,
+        })
+    }
+
+    /// The nodes following the event nodes of the given hook
+    pub fn entries<'a>(&'a self, hook: &'a str) -> impl Iterator<Item = NodeId> + 'a {
+        self.nodes.iter().filter_map(move |node| match node {
+            GraphNode::Event { hook: h, next } if h == hook => *next,
+            _ => None,
+        })
+    }
+
+    /// Runs the nodes starting from the given one, calling node functions with `call`.
+    /// Stops at the first error returned by a node function, or once [`MAX_GRAPH_STEPS`] nodes ran
+    pub fn run(
+        &self,
+        entry: NodeId,
+        args: &[GraphValue],
+        state: &mut GraphState,
+        mut call: impl FnMut(&str, Vec<GraphValue>) -> Result<GraphValue, String>,
+    ) -> Result<(), String> {
+        let mut current = Some(entry);
+
+        for _ in 0..MAX_GRAPH_STEPS {
+            let id = match current {
+                Some(id) => id,
+                None => return Ok(()),
+            };
+            let node = self
+                .nodes
+                .get(id)
+                .ok_or_else(|| format!("node {id} does not exist"))?;
+
+            current = match node {
+                GraphNode::Event { next, .. } => *next,
+                GraphNode::Call {
+                    function,
+                    args: inputs,
+                    next,
+                } => {
+                    let values = inputs.iter().map(|i| state.input(i, args)).collect();
+                    let output = call(function, values)
+                        .map_err(|e| format!("node {id} `{function}`: {e}"))?;
+                    state.outputs.insert(id, output);
+                    *next
+                }
+                GraphNode::Branch {
+                    condition,
+                    then,
+                    otherwise,
+                } => {
+                    if state.input(condition, args).is_truthy() {
+                        *then
+                    } else {
+                        *otherwise
+                    }
+                }
+                GraphNode::Set { var, value, next } => {
+                    let value = state.input(value, args);
+                    state.variables.insert(var.clone(), value);
+                    *next
+                }
+            };
+        }
+
+        match current {
+            None => Ok(()),
+            Some(_) => Err(format!(
+                "ran more than {MAX_GRAPH_STEPS} nodes, the graph is likely stuck in a loop"
+            )),
+        }
+    }
+}
+
+impl GraphState {
+    fn input(&self, input: &GraphInput, args: &[GraphValue]) -> GraphValue {
+        match input {
+            GraphInput::Value(v) => Some(v),
+            GraphInput::Arg(i) => args.get(*i),
+            GraphInput::Var(name) => self.variables.get(name),
+            GraphInput::Output(id) => self.outputs.get(id),
+        }
+        .cloned()
+        .unwrap_or(GraphValue::Null)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn runs_nodes_from_the_event_of_the_hook() {
+        let graph: ScriptGraph = ron::from_str(
+            r#"(nodes: [
+                Event(hook: "on_hit", next: Some(1)),
+                Call(function: "damage", args: [Arg(0), Var("armor")], next: Some(2)),
+                Branch(condition: Output(1), then: Some(3)),
+                Set(var: "dead", value: Value(Bool(true))),
+            ])"#,
+        )
+        .unwrap();
+        graph.validate().unwrap();
+
+        let mut state = GraphState::default();
+        let mut calls = Vec::default();
+        for entry in graph.entries("on_hit") {
+            graph
+                .run(entry, &[GraphValue::Num(10.0)], &mut state, |name, args| {
+                    calls.push((name.to_owned(), args));
+                    Ok(true.into())
+                })
+                .unwrap();
+        }
+
+        assert_eq!(
+            calls,
+            vec![(
+                "damage".to_owned(),
+                vec![GraphValue::Num(10.0), GraphValue::Null]
+            )]
+        );
+        assert_eq!(state.variables.get("dead"), Some(&GraphValue::Bool(true)));
+        assert_eq!(graph.entries("on_update").count(), 0);
+    }
+
+    #[test]
+    fn rejects_links_to_missing_nodes_and_endless_loops() {
+        let graph = ScriptGraph {
+            nodes: vec![GraphNode::Event {
+                hook: "on_update".to_owned(),
+                next: Some(1),
+            }],
+        };
+        assert!(graph.validate().is_err());
+
+        let graph = ScriptGraph {
+            nodes: vec![GraphNode::Set {
+                var: "x".to_owned(),
+                value: GraphInput::Value(GraphValue::Null),
+                next: Some(0),
+            }],
+        };
+        graph.validate().unwrap();
+        assert!(graph
+            .run(0, &[], &mut GraphState::default(), |_, _| Ok(
+                GraphValue::Null
+            ))
+            .is_err());
+    }
+}
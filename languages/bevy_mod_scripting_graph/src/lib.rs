@@ -0,0 +1,264 @@
+use crate::{
+    assets::{GraphFile, GraphLoader},
+    docs::GraphDocFragment,
+    graph::{GraphState, GraphValue, ScriptGraph},
+};
+use bevy::prelude::*;
+use bevy_mod_scripting_core::{prelude::*, systems::*, world::WorldPointer};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, sync::Arc};
+
+pub mod assets;
+pub mod docs;
+pub mod graph;
+pub mod prelude {
+    pub use crate::{
+        assets::{GraphFile, GraphLoader},
+        docs::GraphDocFragment,
+        graph::{GraphInput, GraphNode, GraphState, GraphValue, NodeId, ScriptGraph},
+        GraphCall, GraphContext, GraphEvent, GraphFunction, GraphFunctions, GraphScriptHost,
+    };
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+/// A node graph Hook. The result of creating this event will be
+/// running the nodes following each `Event` node of the graph whose hook is the hook_name, with the given arguments
+///
+/// Serializable, e.g. for forwarding events over the network with a [`ScriptEventMirror`]
+pub struct GraphEvent {
+    pub hook_name: HookName,
+    pub args: Vec<GraphValue>,
+    pub recipients: Recipients,
+}
+
+impl ScriptEvent for GraphEvent {
+    fn recipients(&self) -> &crate::Recipients {
+        &self.recipients
+    }
+}
+
+/// What a node function is called with besides its arguments
+pub struct GraphCall<'a> {
+    /// the world the script is running in, lock it to access the world
+    pub world: &'a WorldPointer,
+    /// the script whose graph called the function
+    pub script: &'a ScriptData<'a>,
+}
+
+/// A function callable from `Call` nodes, returning the output of the node or an error message
+pub type GraphFunction =
+    Arc<dyn Fn(&GraphCall, Vec<GraphValue>) -> Result<GraphValue, String> + Send + Sync>;
+
+/// The node functions graphs can call, registered by API providers
+#[derive(Default, Clone)]
+pub struct GraphFunctions {
+    functions: HashMap<String, GraphFunction>,
+}
+
+impl GraphFunctions {
+    /// Registers a node function under the given name, replacing any function of the same name, e.g.:
+    ///
+    /// ```rust,ignore
+    /// functions.register("despawn", |call, _args| {
+    ///     call.world.write().despawn(call.script.entity);
+    ///     Ok(GraphValue::Null)
+    /// });
+    /// ```
+    pub fn register(
+        &mut self,
+        name: impl Into<String>,
+        function: impl Fn(&GraphCall, Vec<GraphValue>) -> Result<GraphValue, String>
+            + Send
+            + Sync
+            + 'static,
+    ) -> &mut Self {
+        self.functions.insert(name.into(), Arc::new(function));
+        self
+    }
+
+    pub fn get(&self, name: &str) -> Option<&GraphFunction> {
+        self.functions.get(name)
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.functions.contains_key(name)
+    }
+
+    /// The names of the registered node functions
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.functions.keys().map(String::as_str)
+    }
+}
+
+/// The graph of a script alongside the node functions it can call and the variables it set
+pub struct GraphContext {
+    graph: ScriptGraph,
+    functions: GraphFunctions,
+    state: GraphState,
+}
+
+impl GraphContext {
+    pub fn graph(&self) -> &ScriptGraph {
+        &self.graph
+    }
+
+    /// The variables set by the script so far
+    pub fn variables(&self) -> &HashMap<String, GraphValue> {
+        &self.state.variables
+    }
+}
+
+/// Script host running node graphs, authored as data instead of code, e.g. by editors for non-programmers.
+///
+/// API providers attach their API by registering node functions with [`GraphFunctions`],
+/// which `Call` nodes refer to by name. Hooks run the nodes following the `Event` nodes of the hook.
+#[derive(Resource, Default)]
+pub struct GraphScriptHost;
+
+impl ScriptHost for GraphScriptHost {
+    type ScriptContext = GraphContext;
+    type APITarget = GraphFunctions;
+    type ScriptEvent = GraphEvent;
+    type ScriptAsset = GraphFile;
+    type DocTarget = GraphDocFragment;
+
+    fn register_with_app(app: &mut App, stage: impl StageLabel) {
+        app.add_priority_event::<Self::ScriptEvent>()
+            .add_asset::<GraphFile>()
+            .init_asset_loader::<GraphLoader>()
+            .init_resource::<CachedScriptState<Self>>()
+            .init_resource::<ScriptContexts<Self::ScriptContext>>()
+            .init_resource::<APIProviders<Self>>()
+            .register_type::<ScriptCollection<Self::ScriptAsset>>()
+            .register_type::<Script<Self::ScriptAsset>>()
+            .register_type::<Handle<GraphFile>>()
+            .add_system_set_to_stage(
+                stage,
+                SystemSet::new()
+                    .with_system(
+                        script_add_synchronizer::<Self>.before(script_remove_synchronizer::<Self>),
+                    )
+                    .with_system(
+                        script_remove_synchronizer::<Self>
+                            .before(script_hot_reload_handler::<Self>),
+                    )
+                    .with_system(script_hot_reload_handler::<Self>),
+            );
+    }
+
+    fn load_script(
+        &mut self,
+        script: &[u8],
+        script_data: &ScriptData,
+        providers: &mut APIProviders<Self>,
+    ) -> Result<Self::ScriptContext, ScriptError> {
+        let graph: ScriptGraph =
+            ron::de::from_bytes(script).map_err(|e| ScriptError::SyntaxError {
+                script: script_data.name.to_owned(),
+                msg: e.to_string(),
+            })?;
+
+        let mut functions = GraphFunctions::default();
+        providers.attach_all(&mut functions)?;
+
+        // graphs calling functions which do not exist are rejected up front, like scripts which do not compile
+        if let Some(missing) = graph.functions().find(|f| !functions.contains(f)) {
+            return Err(ScriptError::SyntaxError {
+                script: script_data.name.to_owned(),
+                msg: format!("no node function named `{missing}` is registered"),
+            });
+        }
+
+        Ok(GraphContext {
+            graph,
+            functions,
+            state: GraphState::default(),
+        })
+    }
+
+    fn setup_script(
+        &mut self,
+        script_data: &ScriptData,
+        ctx: &mut Self::ScriptContext,
+        providers: &mut APIProviders<Self>,
+    ) -> Result<(), ScriptError> {
+        providers.setup_all(script_data, ctx)
+    }
+
+    fn handle_events<'a>(
+        &self,
+        world: &mut World,
+        events: &[Self::ScriptEvent],
+        ctxs: impl Iterator<Item = (ScriptData<'a>, &'a mut Self::ScriptContext)>,
+        providers: &mut APIProviders<Self>,
+    ) {
+        // copied since the world is handed to the scripts while they handle events
+        let routes = world
+            .get_resource::<ScriptHookRoutes>()
+            .filter(|r| !r.is_empty())
+            .cloned();
+
+        // safety:
+        // - we have &mut World access
+        // - we do not use world_ptr after using the world reference which it's derived from
+        let world_ptr = unsafe { WorldPointer::new(world) };
+
+        ctxs.for_each(|(script_data, ctx)| {
+            providers
+                .setup_runtime_all(world_ptr.clone(), &script_data, ctx)
+                .expect("Could not setup script runtime");
+
+            let GraphContext {
+                graph,
+                functions,
+                state: graph_state,
+            } = ctx;
+            let call = GraphCall {
+                world: &world_ptr,
+                script: &script_data,
+            };
+
+            for event in events {
+                // check if this script should handle this event
+                if !event.recipients().is_recipient(&script_data) {
+                    continue;
+                }
+
+                let hook = match &routes {
+                    Some(routes) => routes.function_for(script_data.name, event.hook_name),
+                    None => event.hook_name,
+                };
+
+                let result = graph.entries(hook.as_str()).try_for_each(|entry| {
+                    graph.run(entry, &event.args, graph_state, |name, args| {
+                        // checked to exist when the script was loaded
+                        let function = functions
+                            .get(name)
+                            .ok_or_else(|| format!("no node function named `{name}`"))?;
+                        function(&call, args)
+                    })
+                });
+
+                if let Err(msg) = result {
+                    let error = ScriptError::RuntimeError {
+                        script: script_data.name.to_owned(),
+                        msg,
+                    };
+                    let mut world = world_ptr.write();
+                    let mut state: CachedScriptState<Self> = world.remove_resource().unwrap();
+
+                    let (_, mut error_wrt, _) = state.event_state.get_mut(&mut world);
+
+                    error!("{}", error);
+                    error_wrt.send(ScriptErrorEvent {
+                        sid: script_data.sid,
+                        error,
+                    });
+                    world.insert_resource(state);
+                }
+            }
+        });
+
+        world_ptr.invalidate();
+    }
+}
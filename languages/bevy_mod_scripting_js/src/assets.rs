@@ -0,0 +1,124 @@
+use bevy::{
+    asset::{AssetLoader, Error, LoadedAsset},
+    reflect::TypeUuid,
+};
+use bevy_mod_scripting_core::asset::{script_sections, CodeAsset};
+
+use std::{ops::Range, sync::Arc};
+
+/// Lines of the form `//#script <label>` begin a new labeled script within a javascript file,
+/// which can be loaded on its own as a sub-asset, e.g.: `scripts/enemies.js#goblin`
+pub const JS_SECTION_MARKER: &str = "//#script";
+
+#[derive(Debug, TypeUuid)]
+#[uuid = "657cefd4-f47f-4890-9226-5fa904e5a5ba"]
+/// A javascript code file in bytes, typescript files are stored compiled to javascript
+pub struct JsFile {
+    pub bytes: Arc<[u8]>,
+    /// for labeled sub-assets, the byte range of the labeled script within the shared `bytes` of the whole file
+    pub section: Option<Range<usize>>,
+}
+
+impl CodeAsset for JsFile {
+    fn bytes(&self) -> &[u8] {
+        match &self.section {
+            Some(section) => &self.bytes[section.clone()],
+            None => &self.bytes,
+        }
+    }
+}
+
+/// Compiles the typescript file at the given asset path with `tsc`, returning the javascript code
+#[cfg(all(feature = "typescript", debug_assertions, not(target_arch = "wasm32")))]
+fn compile_typescript(path: &std::path::Path) -> Result<String, Error> {
+    use bevy::asset::FileAssetIo;
+    use std::fs;
+    use std::process::Command;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    // every load gets its own output file, since assets may be loaded in parallel
+    static COMPILED: AtomicUsize = AtomicUsize::new(0);
+    let temp_file_path = &std::env::temp_dir().join(format!(
+        "bevy_mod_scripting.{}.{}.temp.js",
+        std::process::id(),
+        COMPILED.fetch_add(1, Ordering::Relaxed)
+    ));
+
+    let full_path = &FileAssetIo::get_base_path().join("assets").join(path);
+
+    let status = Command::new("tsc")
+        .args(["--target", "ES2020", "--outFile"])
+        .arg(temp_file_path)
+        .arg(full_path)
+        .status()
+        .map_err(|e| {
+            Error::msg(format!(
+                "Could not run `tsc` to compile the typescript file `{}`, is typescript installed? {e}",
+                path.display()
+            ))
+        })?;
+    if !status.success() {
+        let _ = fs::remove_file(temp_file_path);
+        return Err(Error::msg(format!(
+            "Typescript file `{}` could not be compiled!",
+            path.display()
+        )));
+    }
+
+    let js_code = fs::read_to_string(temp_file_path).map_err(|e| {
+        Error::msg(format!(
+            "Could not read the compiled typescript file `{}`: {e}",
+            path.display()
+        ))
+    });
+    let _ = fs::remove_file(temp_file_path);
+    js_code
+}
+
+#[derive(Default)]
+/// Asset loader for javascript scripts, and typescript scripts with the `typescript` feature
+pub struct JsLoader;
+
+impl AssetLoader for JsLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut bevy::asset::LoadContext,
+    ) -> bevy::asset::BoxedFuture<'a, Result<(), Error>> {
+        let bytes: Arc<[u8]> = match load_context.path().extension().map(|s| s.to_str().unwrap()) {
+            #[cfg(all(feature = "typescript", debug_assertions, not(target_arch = "wasm32")))]
+            Some("ts") => match compile_typescript(load_context.path()) {
+                Ok(js_code) => js_code.as_bytes().into(),
+                Err(e) => return Box::pin(async move { Err(e) }),
+            },
+            _ => bytes.into(),
+        };
+
+        // labeled scripts share the bytes of the whole file
+        for (label, section) in script_sections(&bytes, JS_SECTION_MARKER) {
+            load_context.set_labeled_asset(
+                &label,
+                LoadedAsset::new(JsFile {
+                    bytes: bytes.clone(),
+                    section: Some(section),
+                }),
+            );
+        }
+
+        load_context.set_default_asset(LoadedAsset::new(JsFile {
+            bytes,
+            section: None,
+        }));
+
+        Box::pin(async move { Ok(()) })
+    }
+
+    #[cfg(feature = "typescript")]
+    fn extensions(&self) -> &[&str] {
+        &["js", "ts"]
+    }
+    #[cfg(not(feature = "typescript"))]
+    fn extensions(&self) -> &[&str] {
+        &["js"]
+    }
+}
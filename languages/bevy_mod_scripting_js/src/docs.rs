@@ -0,0 +1,18 @@
+use bevy_mod_scripting_core::prelude::*;
+
+/// JavaScript scripts are not documented yet, generating documentation does nothing
+pub struct JsDocFragment;
+
+impl DocFragment for JsDocFragment {
+    fn merge(self, _o: Self) -> Self {
+        self
+    }
+
+    fn gen_docs(self) -> Result<(), ScriptError> {
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "javascript"
+    }
+}
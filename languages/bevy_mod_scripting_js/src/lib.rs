@@ -0,0 +1,229 @@
+use crate::{
+    assets::{JsFile, JsLoader},
+    docs::JsDocFragment,
+};
+use bevy::{prelude::*, utils::Instant};
+use bevy_mod_scripting_core::{prelude::*, systems::*, world::WorldPointer};
+use rquickjs::{AsArguments, Context, Error as JsError, Function, Runtime};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::marker::PhantomData;
+use std::sync::Mutex;
+
+pub mod assets;
+pub mod docs;
+pub use rquickjs;
+pub mod prelude {
+    pub use crate::{
+        assets::{JsFile, JsLoader},
+        docs::JsDocFragment,
+        JsArg, JsEvent, JsScriptHost,
+    };
+    pub use rquickjs;
+}
+
+pub trait JsArg: for<'js> AsArguments<'js> + Clone + Sync + Send + 'static {}
+
+impl<T: for<'js> AsArguments<'js> + Clone + Sync + Send + 'static> JsArg for T {}
+
+#[derive(Clone, Serialize, Deserialize)]
+/// A JavaScript Hook. The result of creating this event will be
+/// a call to the javascript script with the hook_name and the given arguments
+///
+/// Serializable whenever the arguments are, e.g. for forwarding events over the network with a [`ScriptEventMirror`]
+pub struct JsEvent<A: JsArg> {
+    pub hook_name: HookName,
+    pub args: A,
+    pub recipients: Recipients,
+}
+
+impl<A: JsArg> fmt::Debug for JsEvent<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JsEvent")
+            .field("hook_name", &self.hook_name)
+            .field("recipients", &self.recipients)
+            .finish()
+    }
+}
+
+impl<A: JsArg> ScriptEvent for JsEvent<A> {
+    fn recipients(&self) -> &crate::Recipients {
+        &self.recipients
+    }
+}
+
+/// Converts an error raised by a script into a [`ScriptError`] of the matching category
+pub fn script_runtime_error(script: &str, error: &JsError) -> ScriptError {
+    let script = script.to_owned();
+    let msg = error.to_string();
+    match error {
+        JsError::FromJs { .. } | JsError::IntoJs { .. } => {
+            ScriptError::ConversionError { script, msg }
+        }
+        JsError::Allocation => ScriptError::MemoryLimit { script, msg },
+        // thrown once the interrupt handler set with `set_fuzz_limits` returns true
+        JsError::Exception { message, .. } if message == "interrupted" => {
+            ScriptError::Timeout { script, msg }
+        }
+        JsError::Exception { message, .. } if message == "out of memory" => {
+            ScriptError::MemoryLimit { script, msg }
+        }
+        _ => ScriptError::RuntimeError { script, msg },
+    }
+}
+
+/// Script host running javascript scripts in an embedded QuickJS engine
+#[derive(Resource)]
+pub struct JsScriptHost<A: JsArg> {
+    /// The runtime shared by all scripts, memory limits and the garbage collector can be configured here
+    /// before any scripts are loaded.
+    pub runtime: Runtime,
+    _ph: PhantomData<A>,
+}
+
+impl<A: JsArg> Default for JsScriptHost<A> {
+    fn default() -> Self {
+        Self {
+            runtime: Runtime::new().expect("Could not create the javascript runtime"),
+            _ph: Default::default(),
+        }
+    }
+}
+
+impl<A: JsArg> FuzzTarget for JsScriptHost<A> {
+    /// The limits apply to the runtime shared by all scripts, so to the memory of all scripts combined
+    fn set_fuzz_limits(&mut self, limits: FuzzLimits) {
+        let deadline = Instant::now() + limits.max_duration;
+        self.runtime.set_memory_limit(limits.max_memory);
+        self.runtime
+            .set_interrupt_handler(Some(Box::new(move || Instant::now() > deadline)));
+    }
+}
+
+impl<A: JsArg> ScriptHost for JsScriptHost<A> {
+    /// each script has its own context and with it its own global object
+    type ScriptContext = Mutex<Context>;
+    type APITarget = Mutex<Context>;
+    type ScriptEvent = JsEvent<A>;
+    type ScriptAsset = JsFile;
+    type DocTarget = JsDocFragment;
+
+    fn register_with_app(app: &mut App, stage: impl StageLabel) {
+        app.add_priority_event::<Self::ScriptEvent>()
+            .add_asset::<JsFile>()
+            .init_asset_loader::<JsLoader>()
+            .init_resource::<CachedScriptState<Self>>()
+            .init_resource::<ScriptContexts<Self::ScriptContext>>()
+            .init_resource::<APIProviders<Self>>()
+            .register_type::<ScriptCollection<Self::ScriptAsset>>()
+            .register_type::<Script<Self::ScriptAsset>>()
+            .register_type::<Handle<JsFile>>()
+            .add_system_set_to_stage(
+                stage,
+                SystemSet::new()
+                    .with_system(
+                        script_add_synchronizer::<Self>.before(script_remove_synchronizer::<Self>),
+                    )
+                    .with_system(
+                        script_remove_synchronizer::<Self>
+                            .before(script_hot_reload_handler::<Self>),
+                    )
+                    .with_system(script_hot_reload_handler::<Self>),
+            );
+    }
+
+    fn load_script(
+        &mut self,
+        script: &[u8],
+        script_data: &ScriptData,
+        providers: &mut APIProviders<Self>,
+    ) -> Result<Self::ScriptContext, ScriptError> {
+        let mut ctx = Context::full(&self.runtime)
+            .map(Mutex::new)
+            .map_err(ScriptError::new_other)?;
+
+        // APIs are attached before running the script so that its top level code can use them
+        providers.attach_all(&mut ctx)?;
+
+        ctx.get_mut()
+            .expect("Poison error in context")
+            .with(|ctx| ctx.eval::<(), _>(script.to_vec()))
+            .map_err(|e| script_runtime_error(script_data.name, &e))?;
+
+        Ok(ctx)
+    }
+
+    fn setup_script(
+        &mut self,
+        script_data: &ScriptData,
+        ctx: &mut Self::ScriptContext,
+        providers: &mut APIProviders<Self>,
+    ) -> Result<(), ScriptError> {
+        providers.setup_all(script_data, ctx)
+    }
+
+    fn handle_events<'a>(
+        &self,
+        world: &mut World,
+        events: &[Self::ScriptEvent],
+        ctxs: impl Iterator<Item = (ScriptData<'a>, &'a mut Self::ScriptContext)>,
+        providers: &mut APIProviders<Self>,
+    ) {
+        // copied since the world is handed to the scripts while they handle events
+        let routes = world
+            .get_resource::<ScriptHookRoutes>()
+            .filter(|r| !r.is_empty())
+            .cloned();
+
+        // safety:
+        // - we have &mut World access
+        // - we do not use world_ptr after using the world reference which it's derived from
+        let world_ptr = unsafe { WorldPointer::new(world) };
+
+        ctxs.for_each(|(script_data, ctx)| {
+            providers
+                .setup_runtime_all(world_ptr.clone(), &script_data, ctx)
+                .expect("Could not setup script runtime");
+
+            let ctx = ctx.get_mut().expect("Poison error in context");
+
+            ctx.with(|ctx| {
+                let globals = ctx.globals();
+
+                for event in events {
+                    // check if this script should handle this event
+                    if !event.recipients().is_recipient(&script_data) {
+                        continue;
+                    }
+
+                    let hook = match &routes {
+                        Some(routes) => routes.function_for(script_data.name, event.hook_name),
+                        None => event.hook_name,
+                    };
+                    let f: Function = match globals.get::<_, Option<Function>>(hook.as_str()) {
+                        Ok(Some(f)) => f,
+                        _ => continue, // not subscribed to this event
+                    };
+
+                    if let Err(error) = f.call::<_, ()>(event.args.clone()) {
+                        let mut world = world_ptr.write();
+                        let mut state: CachedScriptState<Self> = world.remove_resource().unwrap();
+
+                        let (_, mut error_wrt, _) = state.event_state.get_mut(&mut world);
+
+                        let error = script_runtime_error(script_data.name, &error);
+
+                        error!("{}", error);
+                        error_wrt.send(ScriptErrorEvent {
+                            sid: script_data.sid,
+                            error,
+                        });
+                        world.insert_resource(state);
+                    }
+                }
+            });
+        });
+
+        world_ptr.invalidate();
+    }
+}
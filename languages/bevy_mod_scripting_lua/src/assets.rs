@@ -2,25 +2,146 @@ use bevy::{
     asset::{AssetLoader, Error, LoadedAsset},
     reflect::TypeUuid,
 };
-use bevy_mod_scripting_core::asset::CodeAsset;
+use bevy_mod_scripting_core::asset::{script_sections, CodeAsset};
 
-use std::sync::Arc;
+use std::{ops::Range, sync::Arc};
+
+/// Lines of the form `--#script <label>` begin a new labeled script within a lua file,
+/// which can be loaded on its own as a sub-asset, e.g.: `scripts/enemies.lua#goblin`
+pub const LUA_SECTION_MARKER: &str = "--#script";
 
 #[derive(Debug, TypeUuid)]
 #[uuid = "39cadc56-aa9c-4543-8640-a018b74b5052"]
 /// A lua code file in bytes
 pub struct LuaFile {
     pub bytes: Arc<[u8]>,
+    /// for labeled sub-assets, the byte range of the labeled script within the shared `bytes` of the whole file
+    pub section: Option<Range<usize>>,
+    /// the errors of teal or fennel files which failed to compile, reported instead of running the script
+    pub load_error: Option<String>,
 }
 
 impl CodeAsset for LuaFile {
     fn bytes(&self) -> &[u8] {
-        &self.bytes
+        match &self.section {
+            Some(section) => &self.bytes[section.clone()],
+            None => &self.bytes,
+        }
+    }
+
+    fn load_error(&self) -> Option<&str> {
+        self.load_error.as_deref()
+    }
+}
+
+/// Type checks and compiles the teal file at the given asset path, returns the lua code or the errors reported by `tl`
+#[cfg(all(feature = "teal", debug_assertions, not(target_arch = "wasm32")))]
+fn compile_teal(path: &std::path::Path) -> Result<String, String> {
+    use bevy::asset::FileAssetIo;
+    use std::fs;
+    use std::path::PathBuf;
+    use std::process::Command;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    let scripts_dir = &FileAssetIo::get_base_path().join("assets").join("scripts");
+
+    // every load gets its own output file, since assets may be loaded in parallel
+    static COMPILED: AtomicUsize = AtomicUsize::new(0);
+    let temp_file_path = &std::env::temp_dir().join(format!(
+        "bevy_mod_scripting.{}.{}.temp.lua",
+        std::process::id(),
+        COMPILED.fetch_add(1, Ordering::Relaxed)
+    ));
+
+    // optionally put the output in the /build folder
+    let build_dir_path: Option<PathBuf> = if path.starts_with("scripts/build/") {
+        Some(path.strip_prefix("scripts/").unwrap().to_owned())
+    } else if path.starts_with("scripts/") {
+        Some(PathBuf::from("build/").join(path.strip_prefix("scripts/").unwrap()))
+    } else {
+        None
+    };
+
+    let full_path = &FileAssetIo::get_base_path().join("assets").join(path);
+
+    // `tl` prints the type errors it finds, which are kept to be reported as the script's error
+    let run_tl = |args: &[&str], what: &str| {
+        let output = Command::new("tl")
+            .args(args)
+            .current_dir(scripts_dir)
+            .output()
+            .map_err(|e| format!("Could not run `tl`, is teal installed? {e}"))?;
+        if output.status.success() {
+            Ok(())
+        } else {
+            Err(format!(
+                "Teal file `{}` {what}:\n{}{}",
+                path.display(),
+                String::from_utf8_lossy(&output.stdout),
+                String::from_utf8_lossy(&output.stderr)
+            ))
+        }
+    };
+
+    run_tl(&["check", full_path.to_str().unwrap()], "has errors")?;
+    run_tl(
+        &[
+            "gen",
+            full_path.to_str().unwrap(),
+            "-o",
+            temp_file_path.to_str().unwrap(),
+        ],
+        "could not be compiled",
+    )
+    .map_err(|e| {
+        let _ = fs::remove_file(temp_file_path);
+        e
+    })?;
+
+    if let Some(mut build_dir_path) = build_dir_path {
+        build_dir_path = scripts_dir.join(build_dir_path);
+        let _ = fs::create_dir_all(build_dir_path.parent().unwrap());
+        let _ = fs::copy(temp_file_path, build_dir_path.with_extension("lua"));
+    }
+
+    let lua_code = fs::read_to_string(temp_file_path)
+        .map_err(|e| format!("Could not read the output of `tl gen`: {e}"));
+    let _ = fs::remove_file(temp_file_path);
+
+    lua_code
+}
+
+/// Compiles the fennel file at the given asset path, returns the lua code or the errors reported by `fennel`.
+///
+/// The output is correlated with the source, i.e. lua code is kept on the line of the fennel code it was compiled from
+/// wherever possible, so that the line numbers of errors raised by the script point into the fennel file.
+#[cfg(all(feature = "fennel", not(target_arch = "wasm32")))]
+fn compile_fennel(path: &std::path::Path) -> Result<String, String> {
+    use bevy::asset::FileAssetIo;
+    use std::process::Command;
+
+    let full_path = &FileAssetIo::get_base_path().join("assets").join(path);
+
+    let output = Command::new("fennel")
+        .args(["--correlate", "--compile", full_path.to_str().unwrap()])
+        .output()
+        .map_err(|e| format!("Could not run `fennel`, is fennel installed? {e}"))?;
+
+    if output.status.success() {
+        String::from_utf8(output.stdout)
+            .map_err(|e| format!("`fennel` produced invalid lua code: {e}"))
+    } else {
+        // compile errors are reported with the line and column of the fennel source
+        Err(format!(
+            "Fennel file `{}` could not be compiled:\n{}",
+            path.display(),
+            String::from_utf8_lossy(&output.stderr)
+        ))
     }
 }
 
 #[derive(Default)]
-/// Asset loader for lua scripts
+/// Asset loader for lua scripts, and teal and fennel scripts with the `teal` and `fennel` features
 pub struct LuaLoader;
 
 impl AssetLoader for LuaLoader {
@@ -29,113 +150,80 @@ impl AssetLoader for LuaLoader {
         bytes: &'a [u8],
         load_context: &'a mut bevy::asset::LoadContext,
     ) -> bevy::asset::BoxedFuture<'a, Result<(), Error>> {
-        match load_context.path().extension().map(|s| s.to_str().unwrap()) {
-            #[cfg(all(feature = "teal", debug_assertions))]
-            Some("tl") => {
-                use bevy::asset::FileAssetIo;
-                use std::fs;
-                use std::path::PathBuf;
-                use std::process::Command;
-
-                let scripts_dir = &FileAssetIo::get_base_path().join("assets").join("scripts");
-
-                let temp_file_path = &std::env::temp_dir().join("bevy_mod_scripting.temp.lua");
-
-                // optionally put the output in the /build folder
-                let build_dir_path: Option<PathBuf> =
-                    if load_context.path().starts_with("scripts/build/") {
-                        Some(
-                            load_context
-                                .path()
-                                .strip_prefix("scripts/")
-                                .unwrap()
-                                .to_owned(),
-                        )
-                    } else if load_context.path().starts_with("scripts/") {
-                        Some(
-                            PathBuf::from("build/")
-                                .join(load_context.path().strip_prefix("scripts/").unwrap()),
-                        )
-                    } else {
-                        None
-                    };
-
-                let full_path = &FileAssetIo::get_base_path()
-                    .join("assets")
-                    .join(load_context.path());
-
-                if let Ok(e) = Command::new("tl")
-                    .args(["check", full_path.to_str().unwrap()])
-                    .current_dir(scripts_dir)
-                    .status()
-                {
-                    if !e.success() {
-                        return Box::pin(async move {
-                            Err(Error::msg(format!(
-                                "Teal file `{}` has errors",
-                                load_context.path().to_str().unwrap()
-                            )))
-                        });
-                    }
-                } else {
-                    fs::remove_file(temp_file_path)
-                        .expect("Something went wrong running `tl check`");
-                    panic!("Something went wrong running `tl check`");
-                }
-
-                if let Ok(e) = Command::new("tl")
-                    .args([
-                        "gen",
-                        full_path.to_str().unwrap(),
-                        "-o",
-                        temp_file_path.to_str().unwrap(),
-                    ])
-                    .current_dir(scripts_dir)
-                    .status()
-                {
-                    if !e.success() {
-                        return Box::pin(async move {
-                            Err(Error::msg(format!(
-                                "Teal file `{}` could not be compiled!",
-                                load_context.path().to_str().unwrap()
-                            )))
-                        });
-                    }
-                } else {
-                    fs::remove_file(temp_file_path).expect("Something went wrong running `tl gen`");
-                    panic!("Something went wrong running `tl gen`")
-                }
-
-                if let Some(mut build_dir_path) = build_dir_path {
-                    build_dir_path = scripts_dir.join(build_dir_path);
-                    let _ = fs::create_dir_all(build_dir_path.parent().unwrap());
-                    let _ = fs::copy(temp_file_path, build_dir_path.with_extension("lua"));
-                }
-
-                let lua_code =
-                    fs::read_to_string(temp_file_path).expect("Could not find output lua file");
-                fs::remove_file(temp_file_path).unwrap();
-
-                load_context.set_default_asset(LoadedAsset::new(LuaFile {
-                    bytes: lua_code.as_bytes().into(),
-                }));
-            }
-            _ => {
-                load_context.set_default_asset(LoadedAsset::new(LuaFile {
-                    bytes: bytes.into(),
-                }));
-            }
+        let (code, load_error): (Arc<[u8]>, Option<String>) =
+            match load_context.path().extension().map(|s| s.to_str().unwrap()) {
+                #[cfg(all(feature = "teal", debug_assertions, not(target_arch = "wasm32")))]
+                Some("tl") => match compile_teal(load_context.path()) {
+                    Ok(lua_code) => (lua_code.as_bytes().into(), None),
+                    // still loaded, so that the scripts using it report the errors instead of never starting
+                    Err(e) => (Arc::from(&[][..]), Some(e)),
+                },
+                // compiled anew whenever the file is hot-reloaded
+                #[cfg(all(feature = "fennel", not(target_arch = "wasm32")))]
+                Some("fnl") => match compile_fennel(load_context.path()) {
+                    Ok(lua_code) => (lua_code.as_bytes().into(), None),
+                    Err(e) => (Arc::from(&[][..]), Some(e)),
+                },
+                _ => (bytes.into(), None),
+            };
+
+        // the sections of files which failed to compile are found in the source, and share its errors
+        let sections = match load_error {
+            None => script_sections(&code, LUA_SECTION_MARKER),
+            Some(_) => script_sections(bytes, LUA_SECTION_MARKER)
+                .into_iter()
+                .map(|(label, _)| (label, 0..0))
+                .collect(),
+        };
+
+        // labeled scripts share the bytes of the whole file
+        for (label, section) in sections {
+            load_context.set_labeled_asset(
+                &label,
+                LoadedAsset::new(LuaFile {
+                    bytes: code.clone(),
+                    section: Some(section),
+                    load_error: load_error.clone(),
+                }),
+            );
         }
 
+        load_context.set_default_asset(LoadedAsset::new(LuaFile {
+            bytes: code,
+            section: None,
+            load_error,
+        }));
+
         Box::pin(async move { Ok(()) })
     }
 
-    #[cfg(feature = "teal")]
     fn extensions(&self) -> &[&str] {
-        &["lua", "tl"]
+        &[
+            "lua",
+            // neither compiler is available on the web, where these files could not be loaded
+            #[cfg(all(feature = "teal", not(target_arch = "wasm32")))]
+            "tl",
+            #[cfg(all(feature = "fennel", not(target_arch = "wasm32")))]
+            "fnl",
+        ]
     }
-    #[cfg(not(feature = "teal"))]
-    fn extensions(&self) -> &[&str] {
-        &["lua"]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn claims_extensions_of_the_enabled_compilers() {
+        let extensions = LuaLoader.extensions();
+        assert!(extensions.contains(&"lua"));
+        assert_eq!(
+            extensions.contains(&"tl"),
+            cfg!(all(feature = "teal", not(target_arch = "wasm32")))
+        );
+        assert_eq!(
+            extensions.contains(&"fnl"),
+            cfg!(all(feature = "fennel", not(target_arch = "wasm32")))
+        );
     }
 }
@@ -1,3 +1,4 @@
+#[cfg(not(target_arch = "wasm32"))]
 use std::{
     env,
     fs::{self, File},
@@ -5,6 +6,7 @@ use std::{
     process::Command,
 };
 
+#[cfg(not(target_arch = "wasm32"))]
 use bevy::asset::FileAssetIo;
 use tealr::TypeWalker;
 
@@ -12,6 +14,7 @@ use bevy_mod_scripting_core::prelude::*;
 
 pub type TypeWalkerBuilder = fn(TypeWalker) -> TypeWalker;
 
+#[cfg(not(target_arch = "wasm32"))]
 static DEFAULT_DOC_CONFIG: fn(&str) -> String = |s| {
     format!(
         r#"
@@ -35,7 +38,7 @@ static DEFAULT_DOC_CONFIG: fn(&str) -> String = |s| {
     )
 };
 
-#[cfg(feature = "teal")]
+#[cfg(all(feature = "teal", not(target_arch = "wasm32")))]
 static DEFAULT_TEAL_CONFIG: &str = r#"
 return {
     global_env_def="types/types",
@@ -43,6 +46,7 @@ return {
 }
 "#;
 
+#[cfg_attr(target_arch = "wasm32", allow(dead_code))]
 struct Fragment {
     builder: TypeWalkerBuilder,
 }
@@ -50,6 +54,7 @@ struct Fragment {
 pub struct LuaDocFragment {
     name: &'static str,
     walker: Vec<Fragment>,
+    hooks: HookRegistry,
 }
 
 /// A piece of lua documentation,
@@ -59,6 +64,7 @@ impl LuaDocFragment {
         Self {
             name,
             walker: vec![Fragment { builder: f }],
+            hooks: HookRegistry::default(),
         }
     }
 }
@@ -70,9 +76,26 @@ impl DocFragment for LuaDocFragment {
 
     fn merge(mut self, o: Self) -> Self {
         self.walker.extend(o.walker.into_iter());
+        for hook in o.hooks.iter() {
+            self.hooks.register(hook.clone());
+        }
+        self
+    }
+
+    fn with_hooks(mut self, hooks: &HookRegistry) -> Self {
+        for hook in hooks.iter() {
+            self.hooks.register(hook.clone());
+        }
         self
     }
 
+    #[cfg(target_arch = "wasm32")]
+    fn gen_docs(self) -> Result<(), ScriptError> {
+        // documentation is generated by native builds, there is no filesystem on the web
+        Ok(())
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
     fn gen_docs(self) -> Result<(), ScriptError> {
         let script_asset_path = &FileAssetIo::get_base_path().join("assets").join("scripts");
 
@@ -108,6 +131,14 @@ impl DocFragment for LuaDocFragment {
             })
             .map_err(|e| ScriptError::DocGenError(e.to_string()))?;
 
+        // document the callbacks scripts can implement next to the generated API docs
+        if !self.hooks.is_empty() {
+            let hooks_path = script_doc_dir.join(format!("{}_hooks.md", docs_name));
+            File::create(hooks_path)
+                .and_then(|mut file| file.write_all(self.hooks.to_markdown(&docs_name).as_bytes()))
+                .map_err(|e| ScriptError::DocGenError(e.to_string()))?;
+        }
+
         // generate doc config files if they don't exist
         if !script_doc_dir.join("tealr_doc_gen_config.json").exists() {
             let config_path = script_doc_dir.join("tealr_doc_gen_config.json");
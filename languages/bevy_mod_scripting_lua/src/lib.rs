@@ -2,9 +2,11 @@ use crate::{
     assets::{LuaFile, LuaLoader},
     docs::LuaDocFragment,
 };
-use bevy::prelude::*;
+use bevy::{prelude::*, utils::Instant};
 use bevy_mod_scripting_core::{prelude::*, systems::*, world::WorldPointer};
 
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use std::fmt;
 use std::marker::PhantomData;
 use std::sync::Mutex;
@@ -12,12 +14,16 @@ use tealr::mlu::mlua::{prelude::*, Function};
 
 pub mod assets;
 pub mod docs;
+pub mod repl;
+pub mod rollback;
 pub mod util;
 pub use tealr;
 pub mod prelude {
     pub use crate::{
         assets::{LuaFile, LuaLoader},
         docs::{LuaDocFragment, TypeWalkerBuilder},
+        lua_namespace,
+        rollback::LuaSnapshot,
         tealr::{
             self,
             mlu::{
@@ -25,7 +31,7 @@ pub mod prelude {
                 TealData,
             },
         },
-        LuaEvent, LuaScriptHost,
+        LuaBackend, LuaEvent, LuaScriptHost,
     };
 }
 
@@ -33,11 +39,50 @@ pub trait LuaArg: for<'lua> ToLuaMulti<'lua> + Clone + Sync + Send + 'static {}
 
 impl<T: for<'lua> ToLuaMulti<'lua> + Clone + Sync + Send + 'static> LuaArg for T {}
 
-#[derive(Clone)]
+/// The flavors of Lua scripts can be run with.
+///
+/// The flavor is chosen at compile time with one of the `lua51`, `lua52`, `lua53`, `lua54`, `luajit` or `luajit52` features,
+/// and cannot be selected per host: each flavor is a separate C library exporting the same symbols,
+/// so one binary can only link one of them. Use [`LuaBackend::COMPILED`] to check which one scripts run with,
+/// e.g. to load different variants of performance sensitive scripts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LuaBackend {
+    Lua51,
+    Lua52,
+    Lua53,
+    Lua54,
+    LuaJit,
+    LuaJit52,
+}
+
+impl LuaBackend {
+    /// The flavor this binary was compiled with
+    #[cfg(feature = "lua51")]
+    pub const COMPILED: LuaBackend = LuaBackend::Lua51;
+    #[cfg(feature = "lua52")]
+    pub const COMPILED: LuaBackend = LuaBackend::Lua52;
+    #[cfg(feature = "lua53")]
+    pub const COMPILED: LuaBackend = LuaBackend::Lua53;
+    #[cfg(feature = "lua54")]
+    pub const COMPILED: LuaBackend = LuaBackend::Lua54;
+    #[cfg(feature = "luajit")]
+    pub const COMPILED: LuaBackend = LuaBackend::LuaJit;
+    #[cfg(feature = "luajit52")]
+    pub const COMPILED: LuaBackend = LuaBackend::LuaJit52;
+
+    /// Returns true for the LuaJIT flavors
+    pub fn is_jit(self) -> bool {
+        matches!(self, LuaBackend::LuaJit | LuaBackend::LuaJit52)
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
 /// A Lua Hook. The result of creating this event will be
 /// a call to the lua script with the hook_name and the given arguments
+///
+/// Serializable whenever the arguments are, e.g. for forwarding events over the network with a [`ScriptEventMirror`]
 pub struct LuaEvent<A: LuaArg> {
-    pub hook_name: String,
+    pub hook_name: HookName,
     pub args: A,
     pub recipients: Recipients,
 }
@@ -57,20 +102,249 @@ impl<A: LuaArg> ScriptEvent for LuaEvent<A> {
     }
 }
 
+/// Moves every global created while attaching the given APIs into a table named after the namespace,
+/// for use with [`CompositeAPIProvider::namespaced`]
+pub fn lua_namespace(
+    lua: &mut Mutex<Lua>,
+    namespace: &str,
+    attach: &mut dyn FnMut(&mut Mutex<Lua>) -> Result<(), ScriptError>,
+) -> Result<(), ScriptError> {
+    let to_error = |e: LuaError| ScriptError::Other(e.to_string());
+    let before = global_names(lua.get_mut().unwrap_or_else(|e| e.into_inner()));
+    attach(lua)?;
+    let lua = lua.get_mut().unwrap_or_else(|e| e.into_inner());
+
+    let globals = lua.globals();
+    let table: LuaTable = match globals.raw_get(namespace).map_err(to_error)? {
+        LuaValue::Table(t) => t,
+        _ => lua.create_table().map_err(to_error)?,
+    };
+    for name in global_names(lua).difference(&before) {
+        let value: LuaValue = globals.raw_get(name.as_str()).map_err(to_error)?;
+        table.raw_set(name.as_str(), value).map_err(to_error)?;
+        globals
+            .raw_set(name.as_str(), LuaValue::Nil)
+            .map_err(to_error)?;
+    }
+    globals.raw_set(namespace, table).map_err(to_error)
+}
+
+/// The names of all globals of the Lua state
+fn global_names(lua: &Lua) -> HashSet<String> {
+    lua.globals()
+        .pairs::<LuaValue, LuaValue>()
+        .filter_map(|pair| match pair {
+            Ok((LuaValue::String(name), _)) => name.to_str().ok().map(str::to_owned),
+            _ => None,
+        })
+        .collect()
+}
+
+/// The name of the registry table holding the globals of API providers for scripts with [`LuaScriptHost::explicit_imports`]
+const IMPORTS_REGISTRY_KEY: &str = "bevy_mod_scripting_imports";
+
+/// Marks Lua states in which a script imported a name no provider attached, so loading it fails with a descriptive error
+struct UnknownImport;
+
+/// Moves every global created while attaching the APIs of the providers into the imports of the script,
+/// and defines the `import` function scripts retrieve them with
+fn attach_imports<H: ScriptHost<APITarget = Mutex<Lua>>>(
+    lua: &mut Mutex<Lua>,
+    providers: &mut APIProviders<H>,
+) -> Result<(), ScriptError> {
+    let before = global_names(lua.get_mut().unwrap_or_else(|e| e.into_inner()));
+    providers.attach_all(lua)?;
+    let lua = lua.get_mut().unwrap_or_else(|e| e.into_inner());
+
+    let hide_globals = || -> LuaResult<()> {
+        let globals = lua.globals();
+        let imports = lua.create_table()?;
+        for name in global_names(lua).difference(&before) {
+            let value: LuaValue = globals.raw_get(name.as_str())?;
+            imports.raw_set(name.as_str(), value)?;
+            globals.raw_set(name.as_str(), LuaValue::Nil)?;
+        }
+        lua.set_named_registry_value(IMPORTS_REGISTRY_KEY, imports)?;
+
+        let import = lua.create_function(|lua, name: String| {
+            let imports: LuaTable = lua.named_registry_value(IMPORTS_REGISTRY_KEY)?;
+            match imports.raw_get::<_, LuaValue>(name.as_str())? {
+                LuaValue::Nil => {
+                    let mut available = imports
+                        .pairs::<String, LuaValue>()
+                        .filter_map(|pair| pair.ok().map(|(name, _)| name))
+                        .collect::<Vec<_>>();
+                    available.sort_unstable();
+                    lua.set_app_data(UnknownImport);
+                    Err(LuaError::RuntimeError(format!(
+                        "Unknown import `{name}`, the available imports are: {}",
+                        available.join(", ")
+                    )))
+                }
+                value => Ok(value),
+            }
+        })?;
+        globals.raw_set("import", import)
+    };
+    hide_globals().map_err(ScriptError::new_other)
+}
+
+/// The message of the error raised in scripts running past the time limit set with [`FuzzTarget::set_fuzz_limits`]
+const TIME_LIMIT_MESSAGE: &str = "script exceeded its time limit";
+
+/// Converts an error raised by a running script into a [`ScriptError`] of the matching category,
+/// classified by the error which originally caused it
+pub fn script_runtime_error(script: &str, error: &LuaError) -> ScriptError {
+    let mut cause = error;
+    while let LuaError::CallbackError { cause: inner, .. } = cause {
+        cause = inner.as_ref();
+    }
+
+    let script = script.to_owned();
+    let msg = error.to_string();
+    match cause {
+        LuaError::SyntaxError { .. } => ScriptError::SyntaxError { script, msg },
+        LuaError::FromLuaConversionError { .. } | LuaError::ToLuaConversionError { .. } => {
+            ScriptError::ConversionError { script, msg }
+        }
+        LuaError::MemoryError(_) => ScriptError::MemoryLimit { script, msg },
+        LuaError::RuntimeError(m) if m == TIME_LIMIT_MESSAGE => {
+            ScriptError::Timeout { script, msg }
+        }
+        _ => ScriptError::RuntimeError { script, msg },
+    }
+}
+
+/// The hook names interned as Lua strings, stored in the context's app data so that reloading the script,
+/// which creates a new context, interns them again
+#[derive(Default)]
+struct HookCache {
+    /// indexed by [`HookName::index`]
+    names: Vec<Option<LuaRegistryKey>>,
+}
+
+/// Returns the global function implementing the given hook, if the script defines one.
+///
+/// Only the name of the hook is cached, the function itself is looked up with a raw get of the interned name on every call,
+/// so scripts may define, replace or remove (by setting it to `nil`) hook functions at any time.
+fn hook_function(lua: &Lua, hook: HookName) -> LuaResult<Option<Function>> {
+    if lua.app_data_ref::<HookCache>().is_none() {
+        lua.set_app_data(HookCache::default());
+    }
+    let mut cache = lua.app_data_mut::<HookCache>().unwrap();
+
+    let index = hook.index();
+    if cache.names.len() <= index {
+        cache.names.resize_with(index + 1, || None);
+    }
+
+    let name: LuaString = match &cache.names[index] {
+        Some(key) => lua.registry_value(key)?,
+        None => {
+            let name = lua.create_string(hook.as_str())?;
+            cache.names[index] = Some(lua.create_registry_value(name.clone())?);
+            name
+        }
+    };
+
+    match lua.globals().raw_get::<_, LuaValue>(name)? {
+        LuaValue::Function(f) => Ok(Some(f)),
+        _ => Ok(None),
+    }
+}
+
 #[derive(Resource)]
 /// Lua script host, enables Lua scripting.
 pub struct LuaScriptHost<A: LuaArg> {
+    /// if true, scripts only get access to a safe subset of the standard library, see [`LuaScriptHost::sandboxed_lua`].
+    /// This takes precedence over the `unsafe_lua_modules` feature.
+    pub sandbox: bool,
+    /// if true, the globals API providers attach are hidden from scripts, which retrieve them by name instead,
+    /// e.g. `local game = import("game")` for the namespace of a provider group namespaced with [`lua_namespace`].
+    /// Importing a name no provider attached raises an error, which fails loading the script when imported at the top of it.
+    ///
+    /// The `world`, `entity` and `script` globals set up for every script stay globals.
+    pub explicit_imports: bool,
+    /// the limits set with [`FuzzTarget::set_fuzz_limits`] along with the deadline they end at
+    fuzz_limits: Option<(Instant, FuzzLimits)>,
     _ph: PhantomData<A>,
 }
 
 impl<A: LuaArg> Default for LuaScriptHost<A> {
     fn default() -> Self {
         Self {
+            sandbox: false,
+            explicit_imports: false,
+            fuzz_limits: None,
             _ph: Default::default(),
         }
     }
 }
 
+impl<A: LuaArg> LuaScriptHost<A> {
+    /// Creates a Lua state containing only the `string`, `table` and `math` standard libraries.
+    ///
+    /// The `math.random` generator is seeded with a constant so scripts behave deterministically,
+    /// `load`, `loadstring`, `loadfile`, `dofile` and `require` are removed,
+    /// and `print` is redirected to bevy's logging instead of stdout.
+    pub fn sandboxed_lua(script_name: &str) -> LuaResult<Lua> {
+        let lua = Lua::new_with(
+            LuaStdLib::STRING | LuaStdLib::TABLE | LuaStdLib::MATH,
+            LuaOptions::default(),
+        )?;
+
+        {
+            let globals = lua.globals();
+            for unsafe_global in ["load", "loadstring", "loadfile", "dofile", "require"] {
+                globals.raw_set(unsafe_global, LuaValue::Nil)?;
+            }
+
+            lua.load("math.randomseed(0)").exec()?;
+
+            let script_name = script_name.to_owned();
+            let print = lua.create_function(move |lua, args: LuaMultiValue| {
+                let tostring: Function = lua.globals().get("tostring")?;
+                let msg = args
+                    .into_iter()
+                    .map(|v| tostring.call::<_, String>(v))
+                    .collect::<LuaResult<Vec<_>>>()?
+                    .join("\t");
+                info!("[{}] {}", script_name, msg);
+                Ok(())
+            })?;
+            globals.raw_set("print", print)?;
+        }
+
+        Ok(lua)
+    }
+}
+
+impl<A: LuaArg> LuaScriptHost<A> {
+    fn apply_fuzz_limits(lua: &Lua, deadline: Instant, limits: FuzzLimits) -> LuaResult<()> {
+        // not every Lua version supports memory limits
+        let _ = lua.set_memory_limit(limits.max_memory);
+        lua.set_hook(
+            LuaHookTriggers {
+                every_nth_instruction: Some(1000),
+                ..Default::default()
+            },
+            move |_, _| {
+                if Instant::now() > deadline {
+                    Err(LuaError::RuntimeError(TIME_LIMIT_MESSAGE.to_owned()))
+                } else {
+                    Ok(())
+                }
+            },
+        )
+    }
+}
+
+impl<A: LuaArg> FuzzTarget for LuaScriptHost<A> {
+    fn set_fuzz_limits(&mut self, limits: FuzzLimits) {
+        self.fuzz_limits = Some((Instant::now() + limits.max_duration, limits));
+    }
+}
+
 impl<A: LuaArg> ScriptHost for LuaScriptHost<A> {
     type ScriptContext = Mutex<Lua>;
     type APITarget = Mutex<Lua>;
@@ -110,21 +384,47 @@ impl<A: LuaArg> ScriptHost for LuaScriptHost<A> {
         script_data: &ScriptData,
         providers: &mut APIProviders<Self>,
     ) -> Result<Self::ScriptContext, ScriptError> {
-        #[cfg(feature = "unsafe_lua_modules")]
-        let lua = unsafe { Lua::unsafe_new() };
-        #[cfg(not(feature = "unsafe_lua_modules"))]
-        let lua = Lua::new();
+        let lua = if self.sandbox {
+            Self::sandboxed_lua(script_data.name).map_err(ScriptError::new_other)?
+        } else {
+            #[cfg(feature = "unsafe_lua_modules")]
+            let lua = unsafe { Lua::unsafe_new() };
+            #[cfg(not(feature = "unsafe_lua_modules"))]
+            let lua = Lua::new();
+            lua
+        };
+
+        if let Some((deadline, limits)) = self.fuzz_limits {
+            Self::apply_fuzz_limits(&lua, deadline, limits).map_err(ScriptError::new_other)?;
+        }
+
+        let mut lua = Mutex::new(lua);
+
+        // imports are resolved while the script runs, so they have to be attached beforehand
+        if self.explicit_imports {
+            attach_imports(&mut lua, providers)?;
+        }
 
-        lua.load(script)
+        let ctx = lua.get_mut().unwrap_or_else(|e| e.into_inner());
+        ctx.load(script)
             .set_name(script_data.name)
             .and_then(|c| c.exec())
-            .map_err(|_e| ScriptError::FailedToLoad {
-                script: script_data.name.to_owned(),
+            .map_err(|e| match ctx.remove_app_data::<UnknownImport>() {
+                Some(UnknownImport) => ScriptError::FailedToAttachAPI {
+                    script: script_data.name.to_owned(),
+                    msg: e.to_string(),
+                },
+                None => ScriptError::FailedToLoad {
+                    script: script_data.name.to_owned(),
+                    msg: e.to_string(),
+                },
             })?;
+        // a failed import the script recovered from, e.g. with `pcall`
+        ctx.remove_app_data::<UnknownImport>();
 
-        let mut lua = Mutex::new(lua);
-
-        providers.attach_all(&mut lua)?;
+        if !self.explicit_imports {
+            providers.attach_all(&mut lua)?;
+        }
         Ok(lua)
     }
 
@@ -145,6 +445,12 @@ impl<A: LuaArg> ScriptHost for LuaScriptHost<A> {
         ctxs: impl Iterator<Item = (ScriptData<'a>, &'a mut Self::ScriptContext)>,
         providers: &mut APIProviders<Self>,
     ) {
+        // copied since the world is handed to the scripts while they handle events
+        let routes = world
+            .get_resource::<ScriptHookRoutes>()
+            .filter(|r| !r.is_empty())
+            .cloned();
+
         // safety:
         // - we have &mut World access
         // - we do not use world_ptr after using the world reference which it's derived from
@@ -160,17 +466,19 @@ impl<A: LuaArg> ScriptHost for LuaScriptHost<A> {
             // event order is preserved, but scripts can't rely on any temporal
             // guarantees when it comes to other scripts callbacks,
             // at least for now.
-            let globals = ctx.globals();
-
             for event in events {
                 // check if this script should handle this event
                 if !event.recipients().is_recipient(&script_data) {
                     continue;
                 }
 
-                let f: Function = match globals.raw_get(event.hook_name.clone()) {
-                    Ok(f) => f,
-                    Err(_) => continue, // not subscribed to this event
+                let hook = match &routes {
+                    Some(routes) => routes.function_for(script_data.name, event.hook_name),
+                    None => event.hook_name,
+                };
+                let f: Function = match hook_function(ctx, hook) {
+                    Ok(Some(f)) => f,
+                    _ => continue, // not subscribed to this event
                 };
 
                 if let Err(error) = f.call::<_, ()>(event.args.clone()) {
@@ -179,16 +487,70 @@ impl<A: LuaArg> ScriptHost for LuaScriptHost<A> {
 
                     let (_, mut error_wrt, _) = state.event_state.get_mut(&mut world);
 
-                    let error = ScriptError::RuntimeError {
-                        script: script_data.name.to_owned(),
-                        msg: error.to_string(),
-                    };
+                    let error = script_runtime_error(script_data.name, &error);
 
                     error!("{}", error);
-                    error_wrt.send(ScriptErrorEvent { error });
+                    error_wrt.send(ScriptErrorEvent {
+                        sid: script_data.sid,
+                        error,
+                    });
                     world.insert_resource(state);
                 }
             }
         });
+
+        world_ptr.invalidate();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sandbox_only_exposes_safe_globals() {
+        let lua = LuaScriptHost::<()>::sandboxed_lua("sandbox.lua").unwrap();
+        let globals = lua.globals();
+
+        for removed in [
+            "os",
+            "io",
+            "package",
+            "load",
+            "loadstring",
+            "loadfile",
+            "dofile",
+            "require",
+        ] {
+            let value: LuaValue = globals.get(removed).unwrap();
+            assert!(
+                matches!(value, LuaValue::Nil),
+                "`{removed}` is reachable from the sandbox"
+            );
+        }
+
+        for kept in ["string", "table", "math", "print"] {
+            let value: LuaValue = globals.get(kept).unwrap();
+            assert!(
+                !matches!(value, LuaValue::Nil),
+                "`{kept}` is missing from the sandbox"
+            );
+        }
+
+        lua.load("print('hello', 1)").exec().unwrap();
+    }
+
+    #[test]
+    fn sandboxed_random_numbers_are_deterministic() {
+        let roll = |lua: &Lua| {
+            lua.load("return math.random(1, 1000000)")
+                .eval::<i64>()
+                .unwrap()
+        };
+
+        let first = LuaScriptHost::<()>::sandboxed_lua("first.lua").unwrap();
+        let second = LuaScriptHost::<()>::sandboxed_lua("second.lua").unwrap();
+
+        assert_eq!(roll(&first), roll(&second));
     }
 }
@@ -0,0 +1,125 @@
+use bevy::prelude::World;
+use bevy_mod_scripting_core::{
+    prelude::*,
+    repl::{ReplEval, ReplHost},
+    world::WorldPointer,
+};
+use tealr::mlu::mlua::{prelude::*, Function, Value};
+
+use crate::{script_runtime_error, LuaArg, LuaScriptHost};
+
+/// How many levels of nested tables are printed before eliding them
+const MAX_PRINT_DEPTH: usize = 3;
+
+/// Formats a Lua value for display in a REPL, strings are quoted and tables are printed with their contents
+pub fn pretty_print(lua: &Lua, value: &Value) -> LuaResult<String> {
+    pretty_print_depth(lua, value, MAX_PRINT_DEPTH)
+}
+
+fn pretty_print_depth(lua: &Lua, value: &Value, depth: usize) -> LuaResult<String> {
+    Ok(match value {
+        Value::String(s) => format!("{:?}", s.to_str()?),
+        Value::Table(t) if depth == 0 => format!(
+            "{{...}} ({} items)",
+            t.clone().pairs::<Value, Value>().count()
+        ),
+        Value::Table(t) => {
+            let mut items = Vec::default();
+            let len = t.raw_len();
+
+            // print the sequence part in order first, followed by all other keys
+            for i in 1..=len {
+                items.push(pretty_print_depth(
+                    lua,
+                    &t.raw_get::<_, Value>(i)?,
+                    depth - 1,
+                )?);
+            }
+
+            for pair in t.clone().pairs::<Value, Value>() {
+                let (k, v) = pair?;
+                if matches!(k, Value::Integer(i) if i >= 1 && i <= len) {
+                    continue;
+                }
+                let key = match &k {
+                    Value::String(s) => s.to_str()?.to_owned(),
+                    _ => format!("[{}]", pretty_print_depth(lua, &k, 0)?),
+                };
+                items.push(format!(
+                    "{key} = {}",
+                    pretty_print_depth(lua, &v, depth - 1)?
+                ));
+            }
+
+            format!("{{{}}}", items.join(", "))
+        }
+        _ => {
+            let tostring: Function = lua.globals().get("tostring")?;
+            tostring.call::<_, String>(value.clone())?
+        }
+    })
+}
+
+impl<A: LuaArg> ReplHost for LuaScriptHost<A> {
+    fn eval_snippet(
+        &mut self,
+        world: &mut World,
+        code: &str,
+        script_data: &ScriptData,
+        ctx: &mut Self::ScriptContext,
+        providers: &mut APIProviders<Self>,
+    ) -> ReplEval {
+        // safety:
+        // - we have &mut World access
+        // - we do not use world_ptr after using the world reference which it's derived from
+        let world_ptr = unsafe { WorldPointer::new(world) };
+        if let Err(e) = providers.setup_runtime_all(world_ptr.clone(), script_data, ctx) {
+            return ReplEval::Done(Err(e));
+        }
+
+        let lua = ctx.get_mut().expect("Poison error in context");
+
+        // expressions are evaluated as `return <expr>` so that their value can be printed
+        let chunk = match lua
+            .load(&format!("return {code}"))
+            .set_name(script_data.name)
+            .and_then(|c| c.into_function())
+        {
+            Ok(f) => f,
+            Err(_) => match lua
+                .load(code)
+                .set_name(script_data.name)
+                .and_then(|c| c.into_function())
+            {
+                Ok(f) => f,
+                Err(LuaError::SyntaxError {
+                    incomplete_input: true,
+                    ..
+                }) => return ReplEval::Incomplete,
+                Err(e) => {
+                    return ReplEval::Done(Err(ScriptError::SyntaxError {
+                        script: script_data.name.to_owned(),
+                        msg: e.to_string(),
+                    }))
+                }
+            },
+        };
+
+        let result = chunk
+            .call::<_, LuaMultiValue>(())
+            .and_then(|values| {
+                if values.is_empty() {
+                    return Ok(None);
+                }
+                values
+                    .iter()
+                    .map(|v| pretty_print(lua, v))
+                    .collect::<LuaResult<Vec<_>>>()
+                    .map(|v| Some(v.join("\t")))
+            })
+            .map_err(|e| script_runtime_error(script_data.name, &e));
+
+        world_ptr.invalidate();
+        ReplEval::Done(result)
+    }
+}
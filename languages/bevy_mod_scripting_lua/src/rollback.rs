@@ -0,0 +1,228 @@
+use std::{
+    collections::{HashMap, HashSet},
+    ffi::c_void,
+};
+
+use bevy_mod_scripting_core::prelude::*;
+use tealr::mlu::mlua::{prelude::*, Table, Value};
+
+use crate::{LuaArg, LuaScriptHost};
+
+/// Tables nested deeper than this are not snapshotted, which keeps deeply nested tables from overflowing the stack
+const MAX_SNAPSHOT_DEPTH: usize = 32;
+
+/// Globals belonging to the standard library, which are never snapshotted
+const STD_GLOBALS: [&str; 12] = [
+    "_G",
+    "_VERSION",
+    "string",
+    "table",
+    "math",
+    "io",
+    "os",
+    "coroutine",
+    "debug",
+    "package",
+    "utf8",
+    "bit32",
+];
+
+/// A copy of a Lua value made up of only plain data
+#[derive(Debug, Clone, PartialEq)]
+pub enum LuaSnapshotValue {
+    Bool(bool),
+    Integer(LuaInteger),
+    Number(LuaNumber),
+    String(Vec<u8>),
+    /// a table, by its index in the tables of the snapshot
+    Table(usize),
+}
+
+/// The data entries of a table, entries holding functions, userdata or threads are left out
+type SnapshotEntries = Vec<(LuaSnapshotValue, LuaSnapshotValue)>;
+
+/// The state of a Lua script, i.e. its global variables holding booleans, numbers, strings or tables of those.
+///
+/// State held in locals, upvalues or userdata is not part of the snapshot.
+/// Every table is captured once, so tables referenced from multiple places (or from themselves)
+/// are restored as a single table referenced from all of those places again.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LuaSnapshot {
+    /// the globals table first, followed by every table reachable from it
+    tables: Vec<SnapshotEntries>,
+}
+
+fn is_std_global(key: &Value) -> bool {
+    matches!(key, Value::String(s) if s.to_str().map_or(false, |s| STD_GLOBALS.contains(&s)))
+}
+
+/// Returns true for the values which are part of snapshots
+fn is_data(value: &Value) -> bool {
+    matches!(
+        value,
+        Value::Boolean(_)
+            | Value::Integer(_)
+            | Value::Number(_)
+            | Value::String(_)
+            | Value::Table(_)
+    )
+}
+
+#[derive(Default)]
+struct Capture {
+    tables: Vec<SnapshotEntries>,
+    /// the index of every table captured so far, by its address
+    visited: HashMap<*const c_void, usize>,
+}
+
+impl Capture {
+    fn value(&mut self, value: &Value, depth: usize) -> LuaResult<Option<LuaSnapshotValue>> {
+        Ok(Some(match value {
+            Value::Boolean(b) => LuaSnapshotValue::Bool(*b),
+            Value::Integer(i) => LuaSnapshotValue::Integer(*i),
+            Value::Number(n) => LuaSnapshotValue::Number(*n),
+            Value::String(s) => LuaSnapshotValue::String(s.as_bytes().to_vec()),
+            Value::Table(t) if depth < MAX_SNAPSHOT_DEPTH => {
+                LuaSnapshotValue::Table(self.table(t, depth + 1, false)?)
+            }
+            _ => return Ok(None),
+        }))
+    }
+
+    /// Captures the table unless it was captured already, returning its index
+    fn table(&mut self, table: &Table, depth: usize, skip_std: bool) -> LuaResult<usize> {
+        if let Some(index) = self.visited.get(&table.to_pointer()) {
+            return Ok(*index);
+        }
+        let index = self.tables.len();
+        self.tables.push(Vec::default());
+        self.visited.insert(table.to_pointer(), index);
+
+        let mut entries = Vec::default();
+        for pair in table.clone().pairs::<Value, Value>() {
+            let (k, v) = pair?;
+            if skip_std && is_std_global(&k) {
+                continue;
+            }
+            if let (Some(k), Some(v)) = (self.value(&k, depth)?, self.value(&v, depth)?) {
+                entries.push((k, v));
+            }
+        }
+        self.tables[index] = entries;
+        Ok(index)
+    }
+}
+
+struct Restore<'lua, 's> {
+    lua: &'lua Lua,
+    snapshot: &'s LuaSnapshot,
+    /// the table each snapshot table was restored to so far
+    restored: HashMap<usize, Table<'lua>>,
+    /// the addresses of the tables restored so far, which cannot be restored to another snapshot table too
+    used: HashSet<*const c_void>,
+}
+
+impl<'lua, 's> Restore<'lua, 's> {
+    fn to_lua(&mut self, value: &LuaSnapshotValue) -> LuaResult<Value<'lua>> {
+        Ok(match value {
+            LuaSnapshotValue::Bool(b) => Value::Boolean(*b),
+            LuaSnapshotValue::Integer(i) => Value::Integer(*i),
+            LuaSnapshotValue::Number(n) => Value::Number(*n),
+            LuaSnapshotValue::String(s) => Value::String(self.lua.create_string(s)?),
+            LuaSnapshotValue::Table(index) => match self.restored.get(index) {
+                Some(table) => Value::Table(table.clone()),
+                None => {
+                    let table = self.lua.create_table()?;
+                    self.table(&table, *index, false)?;
+                    Value::Table(table)
+                }
+            },
+        })
+    }
+
+    fn scalar_key(value: &Value) -> Option<LuaSnapshotValue> {
+        match value {
+            Value::Boolean(b) => Some(LuaSnapshotValue::Bool(*b)),
+            Value::Integer(i) => Some(LuaSnapshotValue::Integer(*i)),
+            Value::Number(n) => Some(LuaSnapshotValue::Number(*n)),
+            Value::String(s) => Some(LuaSnapshotValue::String(s.as_bytes().to_vec())),
+            _ => None,
+        }
+    }
+
+    /// Restores the data entries of the table in place to those of the snapshot table at the given index,
+    /// so that references to it elsewhere see the restored state
+    fn table(&mut self, table: &Table<'lua>, index: usize, skip_std: bool) -> LuaResult<()> {
+        self.restored.insert(index, table.clone());
+        self.used.insert(table.to_pointer());
+        let snapshot = self.snapshot;
+        let entries = &snapshot.tables[index];
+
+        // data entries created after the snapshot was taken are removed,
+        // entries keyed by tables are all removed and set again below, with the keys restored like any other table
+        for pair in table.clone().pairs::<Value, Value>() {
+            let (k, v) = pair?;
+            if (skip_std && is_std_global(&k)) || !is_data(&v) {
+                continue;
+            }
+            let keep = match Self::scalar_key(&k) {
+                Some(key) => entries.iter().any(|(saved, _)| saved == &key),
+                None => false,
+            };
+            if !keep {
+                table.raw_set(k, Value::Nil)?;
+            }
+        }
+
+        for (k, v) in entries {
+            let key = self.to_lua(k)?;
+            match (v, table.raw_get::<_, Value>(key.clone())?) {
+                (LuaSnapshotValue::Table(i), Value::Table(current))
+                    if !self.restored.contains_key(i)
+                        && !self.used.contains(&current.to_pointer()) =>
+                {
+                    self.table(&current, *i, false)?
+                }
+                (v, _) => {
+                    let value = self.to_lua(v)?;
+                    table.raw_set(key, value)?
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<A: LuaArg> RollbackHost for LuaScriptHost<A> {
+    type Snapshot = LuaSnapshot;
+
+    fn snapshot_context(
+        &self,
+        ctx: &mut Self::ScriptContext,
+    ) -> Result<Self::Snapshot, ScriptError> {
+        let lua = ctx.get_mut().expect("Poison error in context");
+        let mut capture = Capture::default();
+        capture
+            .table(&lua.globals(), 0, true)
+            .map(|_| LuaSnapshot {
+                tables: capture.tables,
+            })
+            .map_err(ScriptError::new_other)
+    }
+
+    fn restore_context(
+        &self,
+        ctx: &mut Self::ScriptContext,
+        snapshot: &Self::Snapshot,
+    ) -> Result<(), ScriptError> {
+        let lua = ctx.get_mut().expect("Poison error in context");
+        Restore {
+            lua,
+            snapshot,
+            restored: HashMap::default(),
+            used: HashSet::default(),
+        }
+        .table(&lua.globals(), 0, true)
+        .map_err(ScriptError::new_other)
+    }
+}
@@ -0,0 +1,64 @@
+use bevy::{
+    asset::Error,
+    asset::{AssetLoader, LoadedAsset},
+    reflect::TypeUuid,
+};
+use bevy_mod_scripting_core::{asset::script_sections, prelude::*};
+use std::{ops::Range, sync::Arc};
+
+/// Lines of the form `##script <label>` begin a new labeled script within a python file,
+/// which can be loaded on its own as a sub-asset, e.g.: `scripts/enemies.py#goblin`
+pub const PYTHON_SECTION_MARKER: &str = "##script";
+
+#[derive(Debug, TypeUuid)]
+#[uuid = "948faff4-5147-42fd-abf5-ba7610ac96ab"]
+/// A python code file in bytes
+pub struct PythonFile {
+    pub bytes: Arc<[u8]>,
+    /// for labeled sub-assets, the byte range of the labeled script within the shared `bytes` of the whole file
+    pub section: Option<Range<usize>>,
+}
+
+impl CodeAsset for PythonFile {
+    fn bytes(&self) -> &[u8] {
+        match &self.section {
+            Some(section) => &self.bytes[section.clone()],
+            None => &self.bytes,
+        }
+    }
+}
+
+#[derive(Default)]
+/// Asset loader for python scripts
+pub struct PythonLoader;
+
+impl AssetLoader for PythonLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut bevy::asset::LoadContext,
+    ) -> bevy::asset::BoxedFuture<'a, Result<(), Error>> {
+        let bytes: Arc<[u8]> = bytes.into();
+
+        // labeled scripts share the bytes of the whole file
+        for (label, section) in script_sections(&bytes, PYTHON_SECTION_MARKER) {
+            load_context.set_labeled_asset(
+                &label,
+                LoadedAsset::new(PythonFile {
+                    bytes: bytes.clone(),
+                    section: Some(section),
+                }),
+            );
+        }
+
+        load_context.set_default_asset(LoadedAsset::new(PythonFile {
+            bytes,
+            section: None,
+        }));
+        Box::pin(async move { Ok(()) })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["py"]
+    }
+}
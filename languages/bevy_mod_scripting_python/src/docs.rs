@@ -0,0 +1,18 @@
+use bevy_mod_scripting_core::prelude::*;
+
+/// Python scripts are not documented yet, generating documentation does nothing
+pub struct PythonDocFragment;
+
+impl DocFragment for PythonDocFragment {
+    fn merge(self, _o: Self) -> Self {
+        self
+    }
+
+    fn gen_docs(self) -> Result<(), ScriptError> {
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "python"
+    }
+}
@@ -0,0 +1,238 @@
+use crate::{
+    assets::{PythonFile, PythonLoader},
+    docs::PythonDocFragment,
+};
+use bevy::prelude::*;
+use bevy_mod_scripting_core::{prelude::*, systems::*, world::WorldPointer};
+use pyo3::{
+    exceptions::{PyMemoryError, PySyntaxError, PyTypeError},
+    prelude::*,
+    types::{PyDict, PyTuple},
+};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::marker::PhantomData;
+
+pub mod assets;
+pub mod docs;
+pub use pyo3;
+pub mod prelude {
+    pub use crate::{
+        assets::{PythonFile, PythonLoader},
+        docs::PythonDocFragment,
+        PyArg, PythonContext, PythonEvent, PythonScriptHost,
+    };
+    pub use pyo3;
+}
+
+pub trait PyArg: IntoPy<Py<PyTuple>> + Clone + Sync + Send + 'static {}
+
+impl<T: IntoPy<Py<PyTuple>> + Clone + Sync + Send + 'static> PyArg for T {}
+
+#[derive(Clone, Serialize, Deserialize)]
+/// A Python Hook. The result of creating this event will be
+/// a call to the python script with the hook_name and the given arguments
+///
+/// Serializable whenever the arguments are, e.g. for forwarding events over the network with a [`ScriptEventMirror`]
+pub struct PythonEvent<A: PyArg> {
+    pub hook_name: HookName,
+    pub args: A,
+    pub recipients: Recipients,
+}
+
+impl<A: PyArg> fmt::Debug for PythonEvent<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PythonEvent")
+            .field("hook_name", &self.hook_name)
+            .field("recipients", &self.recipients)
+            .finish()
+    }
+}
+
+impl<A: PyArg> ScriptEvent for PythonEvent<A> {
+    fn recipients(&self) -> &crate::Recipients {
+        &self.recipients
+    }
+}
+
+/// The module namespace of a python script, holding its global variables and hook functions.
+///
+/// API providers attach their API by setting globals in this namespace.
+pub struct PythonContext {
+    pub globals: Py<PyDict>,
+}
+
+/// Converts an exception raised by a script into a [`ScriptError`] of the matching category
+pub fn script_runtime_error(py: Python, script: &str, error: &PyErr) -> ScriptError {
+    let script = script.to_owned();
+    let msg = error.to_string();
+    if error.is_instance_of::<PySyntaxError>(py) {
+        ScriptError::SyntaxError { script, msg }
+    } else if error.is_instance_of::<PyTypeError>(py) {
+        ScriptError::ConversionError { script, msg }
+    } else if error.is_instance_of::<PyMemoryError>(py) {
+        ScriptError::MemoryLimit { script, msg }
+    } else {
+        ScriptError::RuntimeError { script, msg }
+    }
+}
+
+/// Script host running python scripts with the interpreter installed on the system
+#[derive(Resource)]
+pub struct PythonScriptHost<A: PyArg> {
+    _ph: PhantomData<A>,
+}
+
+impl<A: PyArg> Default for PythonScriptHost<A> {
+    fn default() -> Self {
+        Self {
+            _ph: Default::default(),
+        }
+    }
+}
+
+impl<A: PyArg> ScriptHost for PythonScriptHost<A> {
+    type ScriptContext = PythonContext;
+    type APITarget = PythonContext;
+    type ScriptEvent = PythonEvent<A>;
+    type ScriptAsset = PythonFile;
+    type DocTarget = PythonDocFragment;
+
+    fn register_with_app(app: &mut App, stage: impl StageLabel) {
+        app.add_priority_event::<Self::ScriptEvent>()
+            .add_asset::<PythonFile>()
+            .init_asset_loader::<PythonLoader>()
+            .init_resource::<CachedScriptState<Self>>()
+            .init_resource::<ScriptContexts<Self::ScriptContext>>()
+            .init_resource::<APIProviders<Self>>()
+            .register_type::<ScriptCollection<Self::ScriptAsset>>()
+            .register_type::<Script<Self::ScriptAsset>>()
+            .register_type::<Handle<PythonFile>>()
+            .add_system_set_to_stage(
+                stage,
+                SystemSet::new()
+                    .with_system(
+                        script_add_synchronizer::<Self>.before(script_remove_synchronizer::<Self>),
+                    )
+                    .with_system(
+                        script_remove_synchronizer::<Self>
+                            .before(script_hot_reload_handler::<Self>),
+                    )
+                    .with_system(script_hot_reload_handler::<Self>),
+            );
+    }
+
+    fn load_script(
+        &mut self,
+        script: &[u8],
+        script_data: &ScriptData,
+        providers: &mut APIProviders<Self>,
+    ) -> Result<Self::ScriptContext, ScriptError> {
+        let source = std::str::from_utf8(script).map_err(|e| ScriptError::FailedToLoad {
+            script: script_data.name.to_owned(),
+            msg: e.to_string(),
+        })?;
+
+        let mut ctx = Python::with_gil(|py| -> PyResult<_> {
+            let globals = PyDict::new(py);
+            globals.set_item("__name__", script_data.name)?;
+            Ok(PythonContext {
+                globals: globals.into(),
+            })
+        })
+        .map_err(ScriptError::new_other)?;
+
+        // APIs are attached before running the script so that its top level code can use them
+        providers.attach_all(&mut ctx)?;
+
+        Python::with_gil(|py| {
+            // compiled with the script name so that tracebacks point at the script
+            let run = || -> PyResult<()> {
+                let builtins = py.import("builtins")?;
+                let code =
+                    builtins
+                        .getattr("compile")?
+                        .call1((source, script_data.name, "exec"))?;
+                builtins
+                    .getattr("exec")?
+                    .call1((code, ctx.globals.as_ref(py)))?;
+                Ok(())
+            };
+            run().map_err(|e| script_runtime_error(py, script_data.name, &e))
+        })?;
+
+        Ok(ctx)
+    }
+
+    fn setup_script(
+        &mut self,
+        script_data: &ScriptData,
+        ctx: &mut Self::ScriptContext,
+        providers: &mut APIProviders<Self>,
+    ) -> Result<(), ScriptError> {
+        providers.setup_all(script_data, ctx)
+    }
+
+    fn handle_events<'a>(
+        &self,
+        world: &mut World,
+        events: &[Self::ScriptEvent],
+        ctxs: impl Iterator<Item = (ScriptData<'a>, &'a mut Self::ScriptContext)>,
+        providers: &mut APIProviders<Self>,
+    ) {
+        // copied since the world is handed to the scripts while they handle events
+        let routes = world
+            .get_resource::<ScriptHookRoutes>()
+            .filter(|r| !r.is_empty())
+            .cloned();
+
+        // safety:
+        // - we have &mut World access
+        // - we do not use world_ptr after using the world reference which it's derived from
+        let world_ptr = unsafe { WorldPointer::new(world) };
+
+        Python::with_gil(|py| {
+            ctxs.for_each(|(script_data, ctx)| {
+                providers
+                    .setup_runtime_all(world_ptr.clone(), &script_data, ctx)
+                    .expect("Could not setup script runtime");
+
+                let globals = ctx.globals.as_ref(py);
+
+                for event in events {
+                    // check if this script should handle this event
+                    if !event.recipients().is_recipient(&script_data) {
+                        continue;
+                    }
+
+                    let hook = match &routes {
+                        Some(routes) => routes.function_for(script_data.name, event.hook_name),
+                        None => event.hook_name,
+                    };
+                    let f = match globals.get_item(hook.as_str()) {
+                        Some(f) if f.is_callable() => f,
+                        _ => continue, // not subscribed to this event
+                    };
+
+                    if let Err(error) = f.call1(event.args.clone()) {
+                        let mut world = world_ptr.write();
+                        let mut state: CachedScriptState<Self> = world.remove_resource().unwrap();
+
+                        let (_, mut error_wrt, _) = state.event_state.get_mut(&mut world);
+
+                        let error = script_runtime_error(py, script_data.name, &error);
+
+                        error!("{}", error);
+                        error_wrt.send(ScriptErrorEvent {
+                            sid: script_data.sid,
+                            error,
+                        });
+                        world.insert_resource(state);
+                    }
+                }
+            });
+        });
+
+        world_ptr.invalidate();
+    }
+}
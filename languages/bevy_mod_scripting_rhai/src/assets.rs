@@ -3,19 +3,28 @@ use bevy::{
     asset::{AssetLoader, LoadedAsset},
     reflect::TypeUuid,
 };
-use bevy_mod_scripting_core::prelude::*;
-use std::sync::Arc;
+use bevy_mod_scripting_core::{asset::script_sections, prelude::*};
+use std::{ops::Range, sync::Arc};
+
+/// Lines of the form `//#script <label>` begin a new labeled script within a rhai file,
+/// which can be loaded on its own as a sub-asset, e.g.: `scripts/enemies.rhai#goblin`
+pub const RHAI_SECTION_MARKER: &str = "//#script";
 
 #[derive(Debug, TypeUuid)]
 #[uuid = "e4f7d00d-5acd-45fb-a29c-5a44c5447f5c"]
 /// A rhai code file in bytes
 pub struct RhaiFile {
     pub bytes: Arc<[u8]>,
+    /// for labeled sub-assets, the byte range of the labeled script within the shared `bytes` of the whole file
+    pub section: Option<Range<usize>>,
 }
 
 impl CodeAsset for RhaiFile {
     fn bytes(&self) -> &[u8] {
-        &self.bytes
+        match &self.section {
+            Some(section) => &self.bytes[section.clone()],
+            None => &self.bytes,
+        }
     }
 }
 
@@ -29,8 +38,22 @@ impl AssetLoader for RhaiLoader {
         bytes: &'a [u8],
         load_context: &'a mut bevy::asset::LoadContext,
     ) -> bevy::asset::BoxedFuture<'a, Result<(), Error>> {
+        let bytes: Arc<[u8]> = bytes.into();
+
+        // labeled scripts share the bytes of the whole file
+        for (label, section) in script_sections(&bytes, RHAI_SECTION_MARKER) {
+            load_context.set_labeled_asset(
+                &label,
+                LoadedAsset::new(RhaiFile {
+                    bytes: bytes.clone(),
+                    section: Some(section),
+                }),
+            );
+        }
+
         load_context.set_default_asset(LoadedAsset::new(RhaiFile {
-            bytes: bytes.into(),
+            bytes,
+            section: None,
         }));
         Box::pin(async move { Ok(()) })
     }
@@ -39,3 +62,26 @@ impl AssetLoader for RhaiLoader {
         &["rhai"]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sections_are_views_into_the_whole_file() {
+        let bytes: Arc<[u8]> = b"let shared = 1;\n//#script goblin\nfn on_update() {}\n"[..].into();
+        let (label, section) = script_sections(&bytes, RHAI_SECTION_MARKER).pop().unwrap();
+        assert_eq!(label, "goblin");
+
+        let goblin = RhaiFile {
+            bytes: bytes.clone(),
+            section: Some(section),
+        };
+        let whole = RhaiFile {
+            bytes,
+            section: None,
+        };
+        assert_eq!(goblin.bytes(), b"fn on_update() {}\n");
+        assert_eq!(whole.bytes().len(), whole.bytes.len());
+    }
+}
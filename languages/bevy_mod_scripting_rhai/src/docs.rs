@@ -1,17 +1,139 @@
+#[cfg(not(target_arch = "wasm32"))]
+use std::{
+    env,
+    fs::{self, File},
+    io::Write,
+};
+
+#[cfg(not(target_arch = "wasm32"))]
+use bevy::asset::FileAssetIo;
 use bevy_mod_scripting_core::prelude::*;
 
-pub struct RhaiDocFragment;
+/// Describes a custom statement scripts can use, see [`RhaiCustomSyntaxProvider`](crate::syntax::RhaiCustomSyntaxProvider)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RhaiSyntaxDoc {
+    /// the symbols making up the statement, as given to [`rhai::Engine::register_custom_syntax`]
+    pub symbols: Vec<String>,
+    pub description: String,
+}
+
+impl RhaiSyntaxDoc {
+    /// The statement as written in scripts with its placeholders spelled out,
+    /// e.g. `spawn enemy <string> at ( <expr> , <expr> , <expr> )`
+    pub fn signature(&self) -> String {
+        self.symbols
+            .iter()
+            .map(
+                |s| match s.strip_prefix('$').and_then(|s| s.strip_suffix('$')) {
+                    Some(placeholder) => format!("<{placeholder}>"),
+                    None => s.clone(),
+                },
+            )
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// A piece of rhai documentation, describing the custom syntax and the hooks available to scripts.
+///
+/// Fragments are merged into one markdown page for each, named after the first fragment.
+pub struct RhaiDocFragment {
+    name: &'static str,
+    syntax: Vec<RhaiSyntaxDoc>,
+    hooks: HookRegistry,
+}
+
+impl RhaiDocFragment {
+    pub fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            syntax: Vec::default(),
+            hooks: HookRegistry::default(),
+        }
+    }
+
+    /// Documents a custom statement
+    pub fn with_syntax(mut self, syntax: RhaiSyntaxDoc) -> Self {
+        self.syntax.push(syntax);
+        self
+    }
+
+    /// Renders the custom syntax as a markdown page
+    pub fn syntax_markdown(&self) -> String {
+        let mut out = format!(
+            "# {} syntax\n\nCustom statements available in scripts, in addition to the rhai language.\n",
+            self.name
+        );
+
+        for syntax in &self.syntax {
+            out.push_str(&format!(
+                "\n## `{}`\n\n{}\n",
+                syntax.signature(),
+                syntax.description
+            ));
+        }
+
+        out
+    }
+}
 
 impl DocFragment for RhaiDocFragment {
-    fn merge(self, _o: Self) -> Self {
-        todo!()
+    fn merge(mut self, o: Self) -> Self {
+        self.syntax.extend(o.syntax);
+        for hook in o.hooks.iter() {
+            self.hooks.register(hook.clone());
+        }
+        self
+    }
+
+    fn with_hooks(mut self, hooks: &HookRegistry) -> Self {
+        for hook in hooks.iter() {
+            self.hooks.register(hook.clone());
+        }
+        self
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn gen_docs(self) -> Result<(), ScriptError> {
+        // documentation is generated by native builds, there is no filesystem on the web
+        Ok(())
     }
 
+    #[cfg(not(target_arch = "wasm32"))]
     fn gen_docs(self) -> Result<(), ScriptError> {
-        todo!()
+        let script_doc_dir = &env::var("SCRIPT_DOC_DIR")
+            .map(|v| v.into())
+            .unwrap_or_else(|_e| {
+                FileAssetIo::get_base_path()
+                    .join("assets")
+                    .join("scripts")
+                    .join("doc")
+            });
+
+        fs::create_dir_all(script_doc_dir)
+            .expect("Could not create `.../assets/scripts/doc` directories");
+
+        let mut pages = Vec::default();
+        if !self.syntax.is_empty() {
+            pages.push((format!("{}_syntax.md", self.name), self.syntax_markdown()));
+        }
+        if !self.hooks.is_empty() {
+            pages.push((
+                format!("{}_hooks.md", self.name),
+                self.hooks.to_markdown(self.name),
+            ));
+        }
+
+        for (file_name, page) in pages {
+            File::create(script_doc_dir.join(file_name))
+                .and_then(|mut file| file.write_all(page.as_bytes()))
+                .map_err(|e| ScriptError::DocGenError(e.to_string()))?;
+        }
+
+        Ok(())
     }
 
     fn name(&self) -> &'static str {
-        todo!()
+        self.name
     }
 }
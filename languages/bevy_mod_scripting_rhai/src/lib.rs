@@ -2,18 +2,22 @@ use crate::{
     assets::{RhaiFile, RhaiLoader},
     docs::RhaiDocFragment,
 };
-use bevy::prelude::*;
+use bevy::{prelude::*, utils::Instant};
 use bevy_mod_scripting_core::{prelude::*, systems::*, world::WorldPointer};
 use rhai::*;
+use serde::{Deserialize, Serialize};
 use std::marker::PhantomData;
 
 pub mod assets;
 pub mod docs;
+pub mod repl;
+pub mod syntax;
 pub use rhai;
 pub mod prelude {
     pub use crate::{
         assets::{RhaiFile, RhaiLoader},
-        docs::RhaiDocFragment,
+        docs::{RhaiDocFragment, RhaiSyntaxDoc},
+        syntax::RhaiCustomSyntaxProvider,
         RhaiContext, RhaiEvent, RhaiScriptHost,
     };
     pub use rhai;
@@ -22,7 +26,13 @@ pub mod prelude {
 
 #[derive(Resource)]
 pub struct RhaiScriptHost<A: FuncArgs + Send> {
+    /// The engine used to compile and run all scripts, strict variables (`Engine::set_strict_variables`) and
+    /// shadowing (`Engine::set_allow_shadowing`) policies can be set here before any scripts are loaded.
     pub engine: Engine,
+    /// Variables which are pushed into the scope of each script, in addition to those API providers declare with
+    /// [`APIProvider::script_variables`], these count as declared when compiling scripts with strict variables enabled.
+    /// Only `state` by default, which the host sets itself.
+    pub scope_variables: Vec<String>,
     _ph: PhantomData<A>,
 }
 
@@ -37,21 +47,54 @@ impl<A: FuncArgs + Send> Default for RhaiScriptHost<A> {
 
         Self {
             engine: e,
+            scope_variables: vec!["state".to_owned()],
             _ph: Default::default(),
         }
     }
 }
 
 pub struct RhaiContext {
+    /// call [`RhaiContext::invalidate_hooks`] after changing the functions defined in the AST
     pub ast: AST,
     pub scope: Scope<'static>,
+    /// whether the AST defines a function for each hook, indexed by [`HookName::index`], resolved on first use
+    hooks: Vec<Option<bool>>,
 }
 
-#[derive(Clone)]
+impl RhaiContext {
+    pub fn new(ast: AST, scope: Scope<'static>) -> Self {
+        Self {
+            ast,
+            scope,
+            hooks: Vec::default(),
+        }
+    }
+
+    /// Returns true if the script defines a function for the given hook,
+    /// resolved once per hook so events for hooks a script does not define are skipped without a lookup
+    pub fn defines_hook(&mut self, hook: HookName) -> bool {
+        let index = hook.index();
+        if self.hooks.len() <= index {
+            self.hooks.resize(index + 1, None);
+        }
+        let ast = &self.ast;
+        *self.hooks[index]
+            .get_or_insert_with(|| ast.iter_functions().any(|f| f.name == hook.as_str()))
+    }
+
+    /// Forgets which hooks the script defines, so they are resolved again after functions were added to the AST
+    pub fn invalidate_hooks(&mut self) {
+        self.hooks.clear();
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
 /// A Rhai Hook. The result of creating this event will be
 /// a call to the lua script with the hook_name and the given arguments
+///
+/// Serializable whenever the arguments are, e.g. for forwarding events over the network with a [`ScriptEventMirror`]
 pub struct RhaiEvent<A: FuncArgs + Clone + 'static> {
-    pub hook_name: String,
+    pub hook_name: HookName,
     pub args: A,
     pub recipients: Recipients,
 }
@@ -62,6 +105,72 @@ impl<A: FuncArgs + Clone + Send + Sync + 'static> ScriptEvent for RhaiEvent<A> {
     }
 }
 
+/// Converts an error raised by a running script into a [`ScriptError`] of the matching category,
+/// classified by the error which originally caused it
+pub fn script_runtime_error(script: &str, error: &EvalAltResult) -> ScriptError {
+    let mut cause = error;
+    while let EvalAltResult::ErrorInFunctionCall(_, _, inner, _)
+    | EvalAltResult::ErrorInModule(_, inner, _) = cause
+    {
+        cause = inner.as_ref();
+    }
+
+    let script = script.to_owned();
+    let msg = error.to_string();
+    match cause {
+        EvalAltResult::ErrorParsing(..) => ScriptError::SyntaxError { script, msg },
+        EvalAltResult::ErrorMismatchDataType(..) | EvalAltResult::ErrorMismatchOutputType(..) => {
+            ScriptError::ConversionError { script, msg }
+        }
+        // raised by `on_progress` once the time limit set with `set_fuzz_limits` passed
+        EvalAltResult::ErrorTooManyOperations(_) | EvalAltResult::ErrorTerminated(..) => {
+            ScriptError::Timeout { script, msg }
+        }
+        EvalAltResult::ErrorDataTooLarge(..) | EvalAltResult::ErrorStackOverflow(_) => {
+            ScriptError::MemoryLimit { script, msg }
+        }
+        _ => ScriptError::RuntimeError { script, msg },
+    }
+}
+
+impl<A: FuncArgs + Send + Clone + Sync + 'static> FuzzTarget for RhaiScriptHost<A> {
+    /// Rhai has no allocation limit, the memory limit caps the size of strings, arrays and maps instead.
+    /// Call and expression nesting is limited as well so that deeply nested input cannot overflow the stack.
+    fn set_fuzz_limits(&mut self, limits: FuzzLimits) {
+        let deadline = Instant::now() + limits.max_duration;
+        let max_elements = limits.max_memory / std::mem::size_of::<Dynamic>();
+
+        self.engine
+            .set_max_call_levels(64)
+            .set_max_expr_depths(64, 32)
+            .set_max_string_size(limits.max_memory)
+            .set_max_array_size(max_elements)
+            .set_max_map_size(max_elements)
+            .on_progress(move |_| (Instant::now() > deadline).then_some(Dynamic::UNIT));
+    }
+}
+
+/// Rhai scripts are snapshotted by cloning their scope, the state of values shared between scopes is not copied
+impl<A: FuncArgs + Send + Clone + Sync + 'static> RollbackHost for RhaiScriptHost<A> {
+    type Snapshot = Scope<'static>;
+
+    fn snapshot_context(
+        &self,
+        ctx: &mut Self::ScriptContext,
+    ) -> Result<Self::Snapshot, ScriptError> {
+        Ok(ctx.scope.clone())
+    }
+
+    fn restore_context(
+        &self,
+        ctx: &mut Self::ScriptContext,
+        snapshot: &Self::Snapshot,
+    ) -> Result<(), ScriptError> {
+        ctx.scope = snapshot.clone();
+        Ok(())
+    }
+}
+
 impl<A: FuncArgs + Send + Clone + Sync + 'static> ScriptHost for RhaiScriptHost<A> {
     type ScriptContext = RhaiContext;
     type ScriptEvent = RhaiEvent<A>;
@@ -114,27 +223,63 @@ impl<A: FuncArgs + Send + Clone + Sync + 'static> ScriptHost for RhaiScriptHost<
         &mut self,
         script: &[u8],
         script_data: &ScriptData,
-        _: &mut APIProviders<Self>,
+        providers: &mut APIProviders<Self>,
     ) -> Result<Self::ScriptContext, ScriptError> {
         let mut scope = Scope::new();
+
+        // declare variables set later on by providers, so that strict variables mode accepts them
+        let declared = self
+            .scope_variables
+            .iter()
+            .map(String::as_str)
+            .chain(providers.script_variables(script_data.sid));
+        for name in declared {
+            if !scope.contains(name) {
+                scope.push_dynamic(name, Dynamic::UNIT);
+            }
+        }
+
+        // persistent state for scripts
+        scope.set_value("state", Map::new());
+
         let mut ast = self
             .engine
-            .compile(
-                std::str::from_utf8(script).map_err(|_| ScriptError::FailedToLoad {
+            .compile_with_scope(
+                &scope,
+                std::str::from_utf8(script).map_err(|e| ScriptError::FailedToLoad {
                     script: script_data.name.to_owned(),
+                    msg: e.to_string(),
                 })?,
             )
             .map_err(|e| ScriptError::SyntaxError {
                 script: script_data.name.to_owned(),
-                msg: e.to_string(),
+                msg: match e.err_type() {
+                    ParseErrorType::VariableUndefined(_) => format!(
+                        "{e} (strict variables are enabled, declare variables with `let` or `const` before using them)"
+                    ),
+                    _ => e.to_string(),
+                },
             })?;
 
         ast.set_source(script_data.name);
 
-        // persistent state for scripts
-        scope.push("state", Map::new());
+        Ok(RhaiContext::new(ast, scope))
+    }
 
-        Ok(RhaiContext { ast, scope })
+    /// Rhai contexts are cloned outright, the APIs attached to the engine are shared by all scripts anyway.
+    /// Global statements which already ran in the source script do not run again in the clone.
+    fn clone_context(
+        &mut self,
+        source: &mut Self::ScriptContext,
+        _script: &[u8],
+        _script_data: &ScriptData,
+        _providers: &mut APIProviders<Self>,
+    ) -> Option<Result<Self::ScriptContext, ScriptError>> {
+        Some(Ok(RhaiContext {
+            ast: source.ast.clone(),
+            scope: source.scope.clone(),
+            hooks: source.hooks.clone(),
+        }))
     }
 
     fn handle_events<'a>(
@@ -144,6 +289,12 @@ impl<A: FuncArgs + Send + Clone + Sync + 'static> ScriptHost for RhaiScriptHost<
         ctxs: impl Iterator<Item = (ScriptData<'a>, &'a mut Self::ScriptContext)>,
         providers: &mut APIProviders<Self>,
     ) {
+        // copied since the world is handed to the scripts while they handle events
+        let routes = world
+            .get_resource::<ScriptHookRoutes>()
+            .filter(|r| !r.is_empty())
+            .cloned();
+
         ctxs.for_each(|(fd, ctx)| {
             // safety:
             // - we have &mut World access
@@ -156,13 +307,21 @@ impl<A: FuncArgs + Send + Clone + Sync + 'static> ScriptHost for RhaiScriptHost<
             for event in events.iter() {
                 // check if this script should handle this event
                 if !event.recipients().is_recipient(&fd) {
-                    return;
+                    continue;
                 };
 
+                let hook = match &routes {
+                    Some(routes) => routes.function_for(fd.name, event.hook_name),
+                    None => event.hook_name,
+                };
+                if !ctx.defines_hook(hook) {
+                    continue; // not subscribed to this event
+                }
+
                 match self.engine.call_fn(
                     &mut ctx.scope,
                     &ctx.ast,
-                    &event.hook_name,
+                    hook.as_str(),
                     event.args.clone(),
                 ) {
                     Ok(v) => v,
@@ -172,12 +331,9 @@ impl<A: FuncArgs + Send + Clone + Sync + 'static> ScriptHost for RhaiScriptHost<
 
                         let (_, mut error_wrt, _) = state.event_state.get_mut(&mut world);
 
-                        let error = ScriptError::RuntimeError {
-                            script: fd.name.to_string(),
-                            msg: e.to_string(),
-                        };
+                        let error = script_runtime_error(fd.name, &e);
                         error!("{}", error);
-                        error_wrt.send(ScriptErrorEvent { error });
+                        error_wrt.send(ScriptErrorEvent { sid: fd.sid, error });
 
                         world.insert_resource(state);
                     }
@@ -187,6 +343,71 @@ impl<A: FuncArgs + Send + Clone + Sync + 'static> ScriptHost for RhaiScriptHost<
             // executing this at the end here means we execute global statements exactly once
             // all this method call does is set a variable on the AST to NONE so should not affect performance
             ctx.ast.clear_statements();
+            world_ptr.invalidate();
         });
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct PlayerAPIProvider;
+
+    impl APIProvider for PlayerAPIProvider {
+        type APITarget = Engine;
+        type ScriptContext = RhaiContext;
+        type DocTarget = RhaiDocFragment;
+
+        fn attach_api(&mut self, _: &mut Self::APITarget) -> Result<(), ScriptError> {
+            Ok(())
+        }
+
+        fn script_variables(&self) -> Vec<&'static str> {
+            vec!["player"]
+        }
+    }
+
+    fn load(
+        host: &mut RhaiScriptHost<()>,
+        providers: &mut APIProviders<RhaiScriptHost<()>>,
+        script: &str,
+    ) -> Result<RhaiContext, ScriptError> {
+        let script_data = ScriptData {
+            sid: 0,
+            entity: Entity::from_raw(0),
+            name: "strict.rhai",
+            groups: &[],
+        };
+        host.load_script(script.as_bytes(), &script_data, providers)
+    }
+
+    #[test]
+    fn strict_variables_accept_declared_scope_variables() {
+        let mut host = RhaiScriptHost::<()>::default();
+        host.engine.set_strict_variables(true);
+        let mut providers = APIProviders::default();
+        providers.providers.push(Box::new(PlayerAPIProvider));
+
+        let ctx = load(&mut host, &mut providers, "state.hits = 0; let p = player;").unwrap();
+        assert!(ctx.scope.contains("state"));
+        assert!(ctx.scope.contains("player"));
+
+        let err = load(&mut host, &mut providers, "let e = entity;").unwrap_err();
+        assert!(
+            matches!(&err, ScriptError::SyntaxError { msg, .. } if msg.contains("strict variables")),
+            "unexpected error: {err}"
+        );
+    }
+
+    #[test]
+    fn host_scope_variables_are_declared() {
+        let mut host = RhaiScriptHost::<()>::default();
+        host.engine.set_strict_variables(true);
+        host.scope_variables.push("entity".to_owned());
+        let mut providers = APIProviders::default();
+
+        assert!(load(&mut host, &mut providers, "let e = entity;").is_ok());
+        assert!(load(&mut host, &mut providers, "let p = player;").is_err());
+    }
+}
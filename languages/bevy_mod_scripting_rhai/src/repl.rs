@@ -0,0 +1,89 @@
+use bevy::prelude::World;
+use bevy_mod_scripting_core::{
+    prelude::*,
+    repl::{ReplEval, ReplHost},
+    world::WorldPointer,
+};
+use rhai::{Dynamic, FuncArgs, LexError, ParseErrorType};
+
+use crate::{script_runtime_error, RhaiScriptHost};
+
+/// Returns true if the code has more opening than closing brackets, ignoring brackets inside of string literals
+fn has_unclosed_brackets(code: &str) -> bool {
+    let mut depth = 0i32;
+    let mut in_string = None;
+    let mut escaped = false;
+
+    for c in code.chars() {
+        match in_string {
+            Some(quote) => {
+                if escaped {
+                    escaped = false;
+                } else if c == '\\' {
+                    escaped = true;
+                } else if c == quote {
+                    in_string = None;
+                }
+            }
+            None => match c {
+                '"' | '`' | '\'' => in_string = Some(c),
+                '{' | '(' | '[' => depth += 1,
+                '}' | ')' | ']' => depth -= 1,
+                _ => {}
+            },
+        }
+    }
+
+    depth > 0 || in_string.is_some()
+}
+
+impl<A: FuncArgs + Send + Clone + Sync + 'static> ReplHost for RhaiScriptHost<A> {
+    fn eval_snippet(
+        &mut self,
+        world: &mut World,
+        code: &str,
+        script_data: &ScriptData,
+        ctx: &mut Self::ScriptContext,
+        providers: &mut APIProviders<Self>,
+    ) -> ReplEval {
+        let ast = match self.engine.compile_with_scope(&ctx.scope, code) {
+            Ok(ast) => ast,
+            Err(e) => {
+                let incomplete = matches!(
+                    e.err_type(),
+                    ParseErrorType::MissingToken(..)
+                        | ParseErrorType::BadInput(LexError::UnterminatedString)
+                ) && has_unclosed_brackets(code);
+
+                if incomplete {
+                    return ReplEval::Incomplete;
+                }
+                return ReplEval::Done(Err(ScriptError::SyntaxError {
+                    script: script_data.name.to_owned(),
+                    msg: e.to_string(),
+                }));
+            }
+        };
+
+        // safety:
+        // - we have &mut World access
+        // - we do not use world_ptr after we use the original reference again anywhere in this function
+        let world_ptr = unsafe { WorldPointer::new(world) };
+        if let Err(e) = providers.setup_runtime_all(world_ptr.clone(), script_data, ctx) {
+            return ReplEval::Done(Err(e));
+        }
+
+        // functions defined by previous snippets stay callable
+        let runnable = ctx.ast.clone_functions_only().merge(&ast);
+        let result = self
+            .engine
+            .eval_ast_with_scope::<Dynamic>(&mut ctx.scope, &runnable)
+            .map(|value| (!value.is::<()>()).then(|| format!("{value:?}")))
+            .map_err(|e| script_runtime_error(script_data.name, &e));
+
+        ctx.ast += ast.clone_functions_only();
+        ctx.invalidate_hooks();
+        world_ptr.invalidate();
+        ReplEval::Done(result)
+    }
+}
@@ -0,0 +1,137 @@
+//! Custom statements, letting games define their own DSL for mod scripts
+use std::sync::Arc;
+
+use bevy_mod_scripting_core::prelude::*;
+use rhai::{Dynamic, Engine, EvalAltResult, EvalContext, Expression};
+
+use crate::{
+    docs::{RhaiDocFragment, RhaiSyntaxDoc},
+    RhaiContext,
+};
+
+/// Evaluates a custom statement given the expressions matched by its placeholders, in order
+pub type RhaiSyntaxFn =
+    dyn Fn(&mut EvalContext, &[Expression]) -> Result<Dynamic, Box<EvalAltResult>> + Send + Sync;
+
+struct CustomSyntax {
+    doc: RhaiSyntaxDoc,
+    scope_may_change: bool,
+    handler: Arc<RhaiSyntaxFn>,
+}
+
+/// Registers custom statements with the engine of the rhai host and documents them alongside the rest of the API,
+/// in a `<name>_syntax.md` page next to the other generated documentation:
+/// ```rust,ignore
+/// let dsl = RhaiCustomSyntaxProvider::new("game").with_syntax(
+///     &["spawn", "enemy", "$string$", "at", "(", "$expr$", ",", "$expr$", ",", "$expr$", ")"],
+///     "Spawns the enemy with the given name at a position",
+///     |ctx, inputs| {
+///         let name = inputs[0].get_string_value().unwrap_or_default().to_owned();
+///         let x = ctx.eval_expression_tree(&inputs[1])?.as_float()?;
+///         // ..
+///         Ok(Dynamic::UNIT)
+///     },
+/// );
+///
+/// app.add_plugin(
+///     ScriptingPlugin::new()
+///         .with_host::<RhaiScriptHost<MyArgs>, _>(CoreStage::PostUpdate)
+///         .with_api_provider::<RhaiScriptHost<MyArgs>>(Box::new(dsl)),
+/// );
+/// ```
+/// Symbols follow [`Engine::register_custom_syntax`]: keywords and operators are matched literally,
+/// while placeholders such as `$expr$`, `$ident$`, `$string$`, `$int$`, `$float$`, `$bool$` and `$block$` match the corresponding input.
+pub struct RhaiCustomSyntaxProvider {
+    name: &'static str,
+    syntax: Vec<CustomSyntax>,
+}
+
+impl RhaiCustomSyntaxProvider {
+    /// Creates a provider without any statements, its documentation is generated under the given name
+    pub fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            syntax: Vec::default(),
+        }
+    }
+
+    /// Adds a custom statement which does not declare any variables
+    pub fn with_syntax(
+        self,
+        symbols: &[&str],
+        description: &str,
+        handler: impl Fn(&mut EvalContext, &[Expression]) -> Result<Dynamic, Box<EvalAltResult>>
+            + Send
+            + Sync
+            + 'static,
+    ) -> Self {
+        self.push(symbols, description, false, Arc::new(handler))
+    }
+
+    /// Adds a custom statement which may declare variables in the scope of the calling script,
+    /// such as `let`-like statements, this makes the engine set up the scope before evaluating it
+    pub fn with_scoped_syntax(
+        self,
+        symbols: &[&str],
+        description: &str,
+        handler: impl Fn(&mut EvalContext, &[Expression]) -> Result<Dynamic, Box<EvalAltResult>>
+            + Send
+            + Sync
+            + 'static,
+    ) -> Self {
+        self.push(symbols, description, true, Arc::new(handler))
+    }
+
+    fn push(
+        mut self,
+        symbols: &[&str],
+        description: &str,
+        scope_may_change: bool,
+        handler: Arc<RhaiSyntaxFn>,
+    ) -> Self {
+        self.syntax.push(CustomSyntax {
+            doc: RhaiSyntaxDoc {
+                symbols: symbols.iter().map(|s| s.to_string()).collect(),
+                description: description.to_owned(),
+            },
+            scope_may_change,
+            handler,
+        });
+        self
+    }
+}
+
+impl APIProvider for RhaiCustomSyntaxProvider {
+    type APITarget = Engine;
+    type ScriptContext = RhaiContext;
+    type DocTarget = RhaiDocFragment;
+
+    fn attach_api(&mut self, engine: &mut Self::APITarget) -> Result<(), ScriptError> {
+        for syntax in &self.syntax {
+            let handler = syntax.handler.clone();
+            engine
+                .register_custom_syntax(
+                    syntax.doc.symbols.as_slice(),
+                    syntax.scope_may_change,
+                    move |ctx, inputs| handler(ctx, inputs),
+                )
+                .map_err(|e| {
+                    ScriptError::Other(format!(
+                        "Invalid custom syntax `{}`: {e}",
+                        syntax.doc.signature()
+                    ))
+                })?;
+        }
+        Ok(())
+    }
+
+    fn get_doc_fragment(&self) -> Option<Self::DocTarget> {
+        Some(
+            self.syntax
+                .iter()
+                .fold(RhaiDocFragment::new(self.name), |doc, syntax| {
+                    doc.with_syntax(syntax.doc.clone())
+                }),
+        )
+    }
+}
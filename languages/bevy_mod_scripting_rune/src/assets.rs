@@ -0,0 +1,65 @@
+use bevy::{
+    asset::{AssetLoader, Error, LoadedAsset},
+    reflect::TypeUuid,
+};
+use bevy_mod_scripting_core::asset::{script_sections, CodeAsset};
+
+use std::{ops::Range, sync::Arc};
+
+/// Lines of the form `//#script <label>` begin a new labeled script within a rune file,
+/// which can be loaded on its own as a sub-asset, e.g.: `scripts/enemies.rn#goblin`
+pub const RUNE_SECTION_MARKER: &str = "//#script";
+
+#[derive(Debug, TypeUuid)]
+#[uuid = "b4d8e3a7-1f62-4c5e-8a09-7d3e6f2c91b4"]
+/// A rune code file in bytes
+pub struct RuneFile {
+    pub bytes: Arc<[u8]>,
+    /// for labeled sub-assets, the byte range of the labeled script within the shared `bytes` of the whole file
+    pub section: Option<Range<usize>>,
+}
+
+impl CodeAsset for RuneFile {
+    fn bytes(&self) -> &[u8] {
+        match &self.section {
+            Some(section) => &self.bytes[section.clone()],
+            None => &self.bytes,
+        }
+    }
+}
+
+#[derive(Default)]
+/// Asset loader for rune scripts
+pub struct RuneLoader;
+
+impl AssetLoader for RuneLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut bevy::asset::LoadContext,
+    ) -> bevy::asset::BoxedFuture<'a, Result<(), Error>> {
+        let bytes: Arc<[u8]> = bytes.into();
+
+        // labeled scripts share the bytes of the whole file
+        for (label, section) in script_sections(&bytes, RUNE_SECTION_MARKER) {
+            load_context.set_labeled_asset(
+                &label,
+                LoadedAsset::new(RuneFile {
+                    bytes: bytes.clone(),
+                    section: Some(section),
+                }),
+            );
+        }
+
+        load_context.set_default_asset(LoadedAsset::new(RuneFile {
+            bytes,
+            section: None,
+        }));
+
+        Box::pin(async move { Ok(()) })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["rn"]
+    }
+}
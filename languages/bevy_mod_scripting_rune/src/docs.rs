@@ -0,0 +1,85 @@
+#[cfg(not(target_arch = "wasm32"))]
+use std::{
+    env,
+    fs::{self, File},
+    io::Write,
+};
+
+#[cfg(not(target_arch = "wasm32"))]
+use bevy::asset::FileAssetIo;
+use bevy_mod_scripting_core::prelude::*;
+
+/// A piece of rune documentation written in markdown, usually describing the module installed by an API provider.
+///
+/// Each piece is combined into one large markdown page, alongside a page documenting the hooks if any are registered.
+pub struct RuneDocFragment {
+    name: &'static str,
+    sections: Vec<String>,
+    hooks: HookRegistry,
+}
+
+impl RuneDocFragment {
+    pub fn new(name: &'static str, markdown: impl Into<String>) -> Self {
+        Self {
+            name,
+            sections: vec![markdown.into()],
+            hooks: HookRegistry::default(),
+        }
+    }
+}
+
+impl DocFragment for RuneDocFragment {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn merge(mut self, o: Self) -> Self {
+        self.sections.extend(o.sections.into_iter());
+        for hook in o.hooks.iter() {
+            self.hooks.register(hook.clone());
+        }
+        self
+    }
+
+    fn with_hooks(mut self, hooks: &HookRegistry) -> Self {
+        for hook in hooks.iter() {
+            self.hooks.register(hook.clone());
+        }
+        self
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn gen_docs(self) -> Result<(), ScriptError> {
+        // documentation is generated by native builds, there is no filesystem on the web
+        Ok(())
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn gen_docs(self) -> Result<(), ScriptError> {
+        let script_asset_path = &FileAssetIo::get_base_path().join("assets").join("scripts");
+
+        let script_doc_dir = &env::var("SCRIPT_DOC_DIR")
+            .map(|v| v.into())
+            .unwrap_or_else(|_e| script_asset_path.join("doc"));
+
+        fs::create_dir_all(script_doc_dir)
+            .expect("Could not create `.../assets/scripts/doc` directories");
+
+        let docs_name = self.name();
+
+        let page = format!("# {docs_name}\n\n{}\n", self.sections.join("\n\n"));
+        File::create(script_doc_dir.join(format!("{docs_name}.md")))
+            .and_then(|mut file| file.write_all(page.as_bytes()))
+            .map_err(|e| ScriptError::DocGenError(e.to_string()))?;
+
+        // document the callbacks scripts can implement next to the generated API docs
+        if !self.hooks.is_empty() {
+            let hooks_path = script_doc_dir.join(format!("{docs_name}_hooks.md"));
+            File::create(hooks_path)
+                .and_then(|mut file| file.write_all(self.hooks.to_markdown(docs_name).as_bytes()))
+                .map_err(|e| ScriptError::DocGenError(e.to_string()))?;
+        }
+
+        Ok(())
+    }
+}
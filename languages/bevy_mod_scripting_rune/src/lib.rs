@@ -0,0 +1,251 @@
+use crate::{
+    assets::{RuneFile, RuneLoader},
+    docs::RuneDocFragment,
+};
+use bevy::prelude::*;
+use bevy_mod_scripting_core::{prelude::*, systems::*, world::WorldPointer};
+use rune::{
+    runtime::{Args, RuntimeContext, VmError, VmErrorKind},
+    termcolor::Buffer,
+    Context, Diagnostics, Hash, Source, Sources, Unit, Vm,
+};
+use serde::{Deserialize, Serialize};
+use std::{fmt, marker::PhantomData, sync::Arc};
+
+pub mod assets;
+pub mod docs;
+pub use rune;
+pub mod prelude {
+    pub use crate::{
+        assets::{RuneFile, RuneLoader},
+        docs::RuneDocFragment,
+        RuneArg, RuneContext, RuneEvent, RuneScriptHost,
+    };
+    pub use rune;
+}
+
+pub trait RuneArg: Args + Clone + Sync + Send + 'static {}
+
+impl<T: Args + Clone + Sync + Send + 'static> RuneArg for T {}
+
+#[derive(Clone, Serialize, Deserialize)]
+/// A Rune Hook. The result of creating this event will be
+/// a call to the rune script with the hook_name and the given arguments
+///
+/// Serializable whenever the arguments are, e.g. for forwarding events over the network with a [`ScriptEventMirror`]
+pub struct RuneEvent<A: RuneArg> {
+    pub hook_name: HookName,
+    pub args: A,
+    pub recipients: Recipients,
+}
+
+impl<A: RuneArg> fmt::Debug for RuneEvent<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RuneEvent")
+            .field("hook_name", &self.hook_name)
+            .field("recipients", &self.recipients)
+            .finish()
+    }
+}
+
+impl<A: RuneArg> ScriptEvent for RuneEvent<A> {
+    fn recipients(&self) -> &crate::Recipients {
+        &self.recipients
+    }
+}
+
+/// A compiled rune script.
+///
+/// Virtual machines hold values which cannot be sent between threads, so each script gets a fresh [`Vm`]
+/// over its unit whenever it handles events, rune has no global variables which would need to persist in it.
+pub struct RuneContext {
+    pub unit: Arc<Unit>,
+}
+
+impl RuneContext {
+    /// A virtual machine running this script
+    pub fn vm(&self, runtime: Arc<RuntimeContext>) -> Vm {
+        Vm::new(runtime, self.unit.clone())
+    }
+}
+
+/// Converts an error raised by a script into a [`ScriptError`] of the matching category
+pub fn script_runtime_error(script: &str, error: &VmError) -> ScriptError {
+    let script = script.to_owned();
+    let msg = error.to_string();
+    match error.kind() {
+        VmErrorKind::Expected { .. }
+        | VmErrorKind::BadArgument { .. }
+        | VmErrorKind::BadArgumentCount { .. } => ScriptError::ConversionError { script, msg },
+        _ => ScriptError::RuntimeError { script, msg },
+    }
+}
+
+#[derive(Resource)]
+pub struct RuneScriptHost<A: RuneArg> {
+    /// The context all scripts are compiled against, API providers install their modules in it
+    /// when the app starts.
+    pub context: Context,
+    runtime: Arc<RuntimeContext>,
+    _ph: PhantomData<A>,
+}
+
+impl<A: RuneArg> Default for RuneScriptHost<A> {
+    fn default() -> Self {
+        let context = rune_modules::default_context().expect("Could not create the rune context");
+        Self {
+            runtime: Arc::new(context.runtime()),
+            context,
+            _ph: Default::default(),
+        }
+    }
+}
+
+impl<A: RuneArg> ScriptHost for RuneScriptHost<A> {
+    type ScriptContext = RuneContext;
+    type APITarget = Context;
+    type ScriptEvent = RuneEvent<A>;
+    type ScriptAsset = RuneFile;
+    type DocTarget = RuneDocFragment;
+
+    fn register_with_app(app: &mut App, stage: impl StageLabel) {
+        app.add_priority_event::<Self::ScriptEvent>()
+            .add_asset::<RuneFile>()
+            .init_asset_loader::<RuneLoader>()
+            .init_resource::<CachedScriptState<Self>>()
+            .init_resource::<ScriptContexts<Self::ScriptContext>>()
+            .init_resource::<APIProviders<Self>>()
+            .register_type::<ScriptCollection<Self::ScriptAsset>>()
+            .register_type::<Script<Self::ScriptAsset>>()
+            .register_type::<Handle<RuneFile>>()
+            .add_system_set_to_stage(
+                stage,
+                SystemSet::new()
+                    .with_system(
+                        script_add_synchronizer::<Self>.before(script_remove_synchronizer::<Self>),
+                    )
+                    .with_system(
+                        script_remove_synchronizer::<Self>
+                            .before(script_hot_reload_handler::<Self>),
+                    )
+                    .with_system(script_hot_reload_handler::<Self>),
+            )
+            // setup context, modules cannot be installed twice so this happens once for all scripts
+            .add_startup_system(
+                |mut providers: ResMut<APIProviders<Self>>, mut host: ResMut<Self>| {
+                    providers
+                        .attach_all(&mut host.context)
+                        .expect("Error in adding api's for rune");
+                    host.runtime = Arc::new(host.context.runtime());
+                },
+            );
+    }
+
+    fn load_script(
+        &mut self,
+        script: &[u8],
+        script_data: &ScriptData,
+        _: &mut APIProviders<Self>,
+    ) -> Result<Self::ScriptContext, ScriptError> {
+        let source = std::str::from_utf8(script).map_err(|e| ScriptError::FailedToLoad {
+            script: script_data.name.to_owned(),
+            msg: e.to_string(),
+        })?;
+
+        let mut sources = Sources::new();
+        sources.insert(Source::new(script_data.name, source));
+
+        let mut diagnostics = Diagnostics::new();
+        let unit = rune::prepare(&mut sources)
+            .with_context(&self.context)
+            .with_diagnostics(&mut diagnostics)
+            .build();
+
+        let unit = unit.map_err(|e| {
+            // the diagnostics point at the offending code, the build error only says that it failed
+            let mut buffer = Buffer::no_color();
+            let msg = match diagnostics.emit(&mut buffer, &sources) {
+                Ok(()) => String::from_utf8_lossy(buffer.as_slice()).into_owned(),
+                Err(_) => e.to_string(),
+            };
+            ScriptError::SyntaxError {
+                script: script_data.name.to_owned(),
+                msg,
+            }
+        })?;
+
+        Ok(RuneContext {
+            unit: Arc::new(unit),
+        })
+    }
+
+    fn setup_script(
+        &mut self,
+        script_data: &ScriptData,
+        ctx: &mut Self::ScriptContext,
+        providers: &mut APIProviders<Self>,
+    ) -> Result<(), ScriptError> {
+        providers.setup_all(script_data, ctx)
+    }
+
+    fn handle_events<'a>(
+        &self,
+        world: &mut World,
+        events: &[Self::ScriptEvent],
+        ctxs: impl Iterator<Item = (ScriptData<'a>, &'a mut Self::ScriptContext)>,
+        providers: &mut APIProviders<Self>,
+    ) {
+        // copied since the world is handed to the scripts while they handle events
+        let routes = world
+            .get_resource::<ScriptHookRoutes>()
+            .filter(|r| !r.is_empty())
+            .cloned();
+
+        // safety:
+        // - we have &mut World access
+        // - we do not use world_ptr after using the world reference which it's derived from
+        let world_ptr = unsafe { WorldPointer::new(world) };
+
+        ctxs.for_each(|(script_data, ctx)| {
+            providers
+                .setup_runtime_all(world_ptr.clone(), &script_data, ctx)
+                .expect("Could not setup script runtime");
+
+            let mut vm = ctx.vm(self.runtime.clone());
+
+            for event in events {
+                // check if this script should handle this event
+                if !event.recipients().is_recipient(&script_data) {
+                    continue;
+                }
+
+                let hook = match &routes {
+                    Some(routes) => routes.function_for(script_data.name, event.hook_name),
+                    None => event.hook_name,
+                };
+                let hash = Hash::type_hash([hook.as_str()]);
+                if vm.unit().function(hash).is_none() {
+                    continue; // not subscribed to this event
+                }
+
+                if let Err(error) = vm.call(hash, event.args.clone()) {
+                    let mut world = world_ptr.write();
+                    let mut state: CachedScriptState<Self> = world.remove_resource().unwrap();
+
+                    let (_, mut error_wrt, _) = state.event_state.get_mut(&mut world);
+
+                    let error = script_runtime_error(script_data.name, &error);
+
+                    error!("{}", error);
+                    error_wrt.send(ScriptErrorEvent {
+                        sid: script_data.sid,
+                        error,
+                    });
+                    world.insert_resource(state);
+                }
+            }
+        });
+
+        world_ptr.invalidate();
+    }
+}
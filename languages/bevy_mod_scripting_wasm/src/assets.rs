@@ -0,0 +1,41 @@
+use bevy::{
+    asset::Error,
+    asset::{AssetLoader, LoadedAsset},
+    reflect::TypeUuid,
+};
+use bevy_mod_scripting_core::prelude::*;
+use std::sync::Arc;
+
+#[derive(Debug, TypeUuid)]
+#[uuid = "2a7a7fd2-6c1f-4b0e-9d3b-8e4f3c1a5b60"]
+/// A WebAssembly module in the binary or text format, compiled when a script using it is loaded
+pub struct WasmFile {
+    pub bytes: Arc<[u8]>,
+}
+
+impl CodeAsset for WasmFile {
+    fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+#[derive(Default)]
+/// Asset loader for WebAssembly modules, modules cannot be split into labeled scripts
+pub struct WasmLoader;
+
+impl AssetLoader for WasmLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut bevy::asset::LoadContext,
+    ) -> bevy::asset::BoxedFuture<'a, Result<(), Error>> {
+        load_context.set_default_asset(LoadedAsset::new(WasmFile {
+            bytes: bytes.into(),
+        }));
+        Box::pin(async move { Ok(()) })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["wasm", "wat"]
+    }
+}
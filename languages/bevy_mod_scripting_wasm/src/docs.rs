@@ -0,0 +1,18 @@
+use bevy_mod_scripting_core::prelude::*;
+
+/// WebAssembly modules are not documented yet, generating documentation does nothing
+pub struct WasmDocFragment;
+
+impl DocFragment for WasmDocFragment {
+    fn merge(self, _o: Self) -> Self {
+        self
+    }
+
+    fn gen_docs(self) -> Result<(), ScriptError> {
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "wasm"
+    }
+}
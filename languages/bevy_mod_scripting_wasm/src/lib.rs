@@ -0,0 +1,306 @@
+use crate::{
+    assets::{WasmFile, WasmLoader},
+    docs::WasmDocFragment,
+};
+use bevy::prelude::*;
+use bevy_mod_scripting_core::{prelude::*, systems::*, world::WorldPointer};
+use serde::{Deserialize, Serialize};
+use wasmtime::{Engine, Instance, Linker, Module, Store, Trap, Val, ValType};
+
+pub mod assets;
+pub mod docs;
+pub use wasmtime;
+pub mod prelude {
+    pub use crate::{
+        assets::{WasmFile, WasmLoader},
+        docs::WasmDocFragment,
+        WasmContext, WasmEvent, WasmScriptHost, WasmState, WasmValue,
+    };
+    pub use wasmtime;
+}
+
+/// A value passed to a hook exported by a WebAssembly module, which only understands numbers
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum WasmValue {
+    I32(i32),
+    I64(i64),
+    F32(f32),
+    F64(f64),
+}
+
+impl WasmValue {
+    fn ty(self) -> ValType {
+        match self {
+            WasmValue::I32(_) => ValType::I32,
+            WasmValue::I64(_) => ValType::I64,
+            WasmValue::F32(_) => ValType::F32,
+            WasmValue::F64(_) => ValType::F64,
+        }
+    }
+}
+
+impl From<WasmValue> for Val {
+    fn from(value: WasmValue) -> Self {
+        match value {
+            WasmValue::I32(v) => v.into(),
+            WasmValue::I64(v) => v.into(),
+            WasmValue::F32(v) => v.into(),
+            WasmValue::F64(v) => v.into(),
+        }
+    }
+}
+
+macro_rules! impl_from_wasm_value {
+    ($($ty:ty => $variant:ident),*) => {
+        $(impl From<$ty> for WasmValue {
+            fn from(value: $ty) -> Self {
+                WasmValue::$variant(value)
+            }
+        })*
+    };
+}
+
+impl_from_wasm_value!(i32 => I32, i64 => I64, f32 => F32, f64 => F64);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+/// A WebAssembly Hook. The result of creating this event will be
+/// a call to the function exported by the module under the hook_name with the given arguments,
+/// which must match the parameters of the function exactly
+///
+/// Serializable, e.g. for forwarding events over the network with a [`ScriptEventMirror`]
+pub struct WasmEvent {
+    pub hook_name: HookName,
+    pub args: Vec<WasmValue>,
+    pub recipients: Recipients,
+}
+
+impl ScriptEvent for WasmEvent {
+    fn recipients(&self) -> &crate::Recipients {
+        &self.recipients
+    }
+}
+
+/// The host state of each module instance, available to imported functions through their `Caller`
+#[derive(Default)]
+pub struct WasmState {
+    /// The world, only set while the instance is handling events
+    pub world: Option<WorldPointer>,
+}
+
+/// A WebAssembly module instantiated for a single script
+pub struct WasmContext {
+    pub store: Store<WasmState>,
+    pub instance: Instance,
+}
+
+/// Converts an error raised by a module into a [`ScriptError`] of the matching category
+pub fn script_runtime_error(script: &str, error: &wasmtime::Error) -> ScriptError {
+    let script = script.to_owned();
+    let msg = format!("{error:#}");
+    match error.downcast_ref::<Trap>() {
+        Some(Trap::Interrupt | Trap::OutOfFuel) => ScriptError::Timeout { script, msg },
+        Some(Trap::StackOverflow) => ScriptError::MemoryLimit { script, msg },
+        _ => ScriptError::RuntimeError { script, msg },
+    }
+}
+
+/// Script host running precompiled WebAssembly modules with wasmtime.
+///
+/// API providers attach their API by defining the functions modules import in the [`Linker`] of each script,
+/// modules importing anything not defined fail to load.
+#[derive(Resource, Default)]
+pub struct WasmScriptHost {
+    /// The engine compiling all modules, fuel or epoch interruption must be enabled in its config here
+    /// before any scripts are loaded, together with [`WasmScriptHost::fuel`] or [`WasmScriptHost::epoch_deadline`].
+    pub engine: Engine,
+    /// The fuel each module may consume running its start function, and again each time it handles events.
+    ///
+    /// Must be set if the engine was configured with `Config::consume_fuel`, otherwise every module runs out of fuel
+    /// straight away.
+    pub fuel: Option<u64>,
+    /// The number of epochs each module may run for running its start function, and again each time it handles events.
+    ///
+    /// Must be set if the engine was configured with `Config::epoch_interruption`. Epochs only pass when
+    /// [`Engine::increment_epoch`] is called, which has to happen on another thread, e.g. on a timer,
+    /// since a module stuck in a loop blocks the app.
+    pub epoch_deadline: Option<u64>,
+}
+
+impl WasmScriptHost {
+    /// Resets the fuel and epoch deadline of the store to the budgets of the host, if any
+    fn refill_budget(&self, store: &mut Store<WasmState>) -> Result<(), ScriptError> {
+        if let Some(fuel) = self.fuel {
+            let remaining = store.consume_fuel(0).map_err(|e| {
+                ScriptError::Other(format!(
+                    "Cannot refill the fuel of WebAssembly modules, fuel consumption is not enabled in the engine: {e:#}"
+                ))
+            })?;
+            store
+                .add_fuel(fuel.saturating_sub(remaining))
+                .map_err(|e| ScriptError::Other(format!("{e:#}")))?;
+        }
+        if let Some(ticks) = self.epoch_deadline {
+            store.set_epoch_deadline(ticks);
+        }
+        Ok(())
+    }
+
+    /// Calls the function exported under the given name if the module exports one
+    fn call_hook(
+        ctx: &mut WasmContext,
+        hook: &str,
+        args: &[WasmValue],
+        script_data: &ScriptData,
+    ) -> Result<(), ScriptError> {
+        let f = match ctx.instance.get_func(&mut ctx.store, hook) {
+            Some(f) => f,
+            None => return Ok(()), // not subscribed to this event
+        };
+
+        let ty = f.ty(&ctx.store);
+        if !ty.params().eq(args.iter().map(|a| a.ty())) {
+            return Err(ScriptError::ConversionError {
+                script: script_data.name.to_owned(),
+                msg: format!(
+                    "The arguments {args:?} do not match the parameters of `{hook}`: {:?}",
+                    ty.params().collect::<Vec<_>>()
+                ),
+            });
+        }
+
+        let args: Vec<Val> = args.iter().copied().map(Val::from).collect();
+        // the results are overwritten by the call
+        let mut results = vec![Val::I32(0); ty.results().len()];
+        f.call(&mut ctx.store, &args, &mut results)
+            .map_err(|e| script_runtime_error(script_data.name, &e))
+    }
+}
+
+impl ScriptHost for WasmScriptHost {
+    type ScriptContext = WasmContext;
+    type APITarget = Linker<WasmState>;
+    type ScriptEvent = WasmEvent;
+    type ScriptAsset = WasmFile;
+    type DocTarget = WasmDocFragment;
+
+    fn register_with_app(app: &mut App, stage: impl StageLabel) {
+        app.add_priority_event::<Self::ScriptEvent>()
+            .add_asset::<WasmFile>()
+            .init_asset_loader::<WasmLoader>()
+            .init_resource::<CachedScriptState<Self>>()
+            .init_resource::<ScriptContexts<Self::ScriptContext>>()
+            .init_resource::<APIProviders<Self>>()
+            .register_type::<ScriptCollection<Self::ScriptAsset>>()
+            .register_type::<Script<Self::ScriptAsset>>()
+            .register_type::<Handle<WasmFile>>()
+            .add_system_set_to_stage(
+                stage,
+                SystemSet::new()
+                    .with_system(
+                        script_add_synchronizer::<Self>.before(script_remove_synchronizer::<Self>),
+                    )
+                    .with_system(
+                        script_remove_synchronizer::<Self>
+                            .before(script_hot_reload_handler::<Self>),
+                    )
+                    .with_system(script_hot_reload_handler::<Self>),
+            );
+    }
+
+    fn load_script(
+        &mut self,
+        script: &[u8],
+        script_data: &ScriptData,
+        providers: &mut APIProviders<Self>,
+    ) -> Result<Self::ScriptContext, ScriptError> {
+        let module = Module::new(&self.engine, script).map_err(|e| ScriptError::SyntaxError {
+            script: script_data.name.to_owned(),
+            msg: format!("{e:#}"),
+        })?;
+
+        // imports have to be defined before the module is instantiated
+        let mut linker = Linker::new(&self.engine);
+        providers.attach_all(&mut linker)?;
+
+        let mut store = Store::new(&self.engine, WasmState::default());
+        self.refill_budget(&mut store)?;
+        // runs the start function of the module if it has one
+        let instance = linker
+            .instantiate(&mut store, &module)
+            .map_err(|e| script_runtime_error(script_data.name, &e))?;
+
+        Ok(WasmContext { store, instance })
+    }
+
+    fn setup_script(
+        &mut self,
+        script_data: &ScriptData,
+        ctx: &mut Self::ScriptContext,
+        providers: &mut APIProviders<Self>,
+    ) -> Result<(), ScriptError> {
+        providers.setup_all(script_data, ctx)
+    }
+
+    fn handle_events<'a>(
+        &self,
+        world: &mut World,
+        events: &[Self::ScriptEvent],
+        ctxs: impl Iterator<Item = (ScriptData<'a>, &'a mut Self::ScriptContext)>,
+        providers: &mut APIProviders<Self>,
+    ) {
+        // copied since the world is handed to the scripts while they handle events
+        let routes = world
+            .get_resource::<ScriptHookRoutes>()
+            .filter(|r| !r.is_empty())
+            .cloned();
+
+        // safety:
+        // - we have &mut World access
+        // - we do not use world_ptr after using the world reference which it's derived from
+        let world_ptr = unsafe { WorldPointer::new(world) };
+
+        let report_error = |sid: ScriptId, error: ScriptError| {
+            let mut world = world_ptr.write();
+            let mut state: CachedScriptState<Self> = world.remove_resource().unwrap();
+
+            let (_, mut error_wrt, _) = state.event_state.get_mut(&mut world);
+
+            error!("{}", error);
+            error_wrt.send(ScriptErrorEvent { sid, error });
+            world.insert_resource(state);
+        };
+
+        ctxs.for_each(|(script_data, ctx)| {
+            providers
+                .setup_runtime_all(world_ptr.clone(), &script_data, ctx)
+                .expect("Could not setup script runtime");
+
+            if let Err(error) = self.refill_budget(&mut ctx.store) {
+                report_error(script_data.sid, error);
+                return;
+            }
+
+            ctx.store.data_mut().world = Some(world_ptr.clone());
+
+            for event in events {
+                // check if this script should handle this event
+                if !event.recipients().is_recipient(&script_data) {
+                    continue;
+                }
+
+                let hook = match &routes {
+                    Some(routes) => routes.function_for(script_data.name, event.hook_name),
+                    None => event.hook_name,
+                };
+
+                if let Err(error) = Self::call_hook(ctx, hook.as_str(), &event.args, &script_data) {
+                    report_error(script_data.sid, error);
+                }
+            }
+
+            ctx.store.data_mut().world = None;
+        });
+
+        world_ptr.invalidate();
+    }
+}
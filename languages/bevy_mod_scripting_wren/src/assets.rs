@@ -0,0 +1,65 @@
+use bevy::{
+    asset::{AssetLoader, Error, LoadedAsset},
+    reflect::TypeUuid,
+};
+use bevy_mod_scripting_core::asset::{script_sections, CodeAsset};
+
+use std::{ops::Range, sync::Arc};
+
+/// Lines of the form `//#script <label>` begin a new labeled script within a wren file,
+/// which can be loaded on its own as a sub-asset, e.g.: `scripts/enemies.wren#goblin`
+pub const WREN_SECTION_MARKER: &str = "//#script";
+
+#[derive(Debug, TypeUuid)]
+#[uuid = "5e2a9c71-d4b8-4f03-9e6a-28c1b7f4a0d5"]
+/// A wren code file in bytes
+pub struct WrenFile {
+    pub bytes: Arc<[u8]>,
+    /// for labeled sub-assets, the byte range of the labeled script within the shared `bytes` of the whole file
+    pub section: Option<Range<usize>>,
+}
+
+impl CodeAsset for WrenFile {
+    fn bytes(&self) -> &[u8] {
+        match &self.section {
+            Some(section) => &self.bytes[section.clone()],
+            None => &self.bytes,
+        }
+    }
+}
+
+#[derive(Default)]
+/// Asset loader for wren scripts
+pub struct WrenLoader;
+
+impl AssetLoader for WrenLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut bevy::asset::LoadContext,
+    ) -> bevy::asset::BoxedFuture<'a, Result<(), Error>> {
+        let bytes: Arc<[u8]> = bytes.into();
+
+        // labeled scripts share the bytes of the whole file
+        for (label, section) in script_sections(&bytes, WREN_SECTION_MARKER) {
+            load_context.set_labeled_asset(
+                &label,
+                LoadedAsset::new(WrenFile {
+                    bytes: bytes.clone(),
+                    section: Some(section),
+                }),
+            );
+        }
+
+        load_context.set_default_asset(LoadedAsset::new(WrenFile {
+            bytes,
+            section: None,
+        }));
+
+        Box::pin(async move { Ok(()) })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["wren"]
+    }
+}
@@ -0,0 +1,274 @@
+use crate::{
+    assets::{WrenFile, WrenLoader},
+    docs::WrenDocFragment,
+};
+use bevy::prelude::*;
+use bevy_mod_scripting_core::{prelude::*, systems::*, world::WorldPointer};
+use ruwren::{FunctionSignature, ModuleLibrary, VMConfig, VMError, VMWrapper, VM};
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+
+pub mod assets;
+pub mod docs;
+pub use ruwren;
+pub mod prelude {
+    pub use crate::{
+        assets::{WrenFile, WrenLoader},
+        docs::WrenDocFragment,
+        WrenContext, WrenEvent, WrenScriptHost, WrenValue, WREN_HOOK_CLASS,
+    };
+    pub use ruwren;
+}
+
+/// The class whose static methods are called by hooks, declared in the `main` module of each script
+pub const WREN_HOOK_CLASS: &str = "Game";
+
+/// The module each script is loaded as
+const MAIN_MODULE: &str = "main";
+
+/// A value passed to a hook of a Wren script
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum WrenValue {
+    Null,
+    Bool(bool),
+    Num(f64),
+    Str(String),
+}
+
+impl WrenValue {
+    fn set_slot(&self, vm: &VM, slot: usize) {
+        match self {
+            WrenValue::Null => vm.set_slot_null(slot),
+            WrenValue::Bool(v) => vm.set_slot_bool(slot, *v),
+            WrenValue::Num(v) => vm.set_slot_double(slot, *v),
+            WrenValue::Str(v) => vm.set_slot_string(slot, v),
+        }
+    }
+}
+
+macro_rules! impl_from_wren_value {
+    ($($ty:ty => $variant:ident),*) => {
+        $(impl From<$ty> for WrenValue {
+            fn from(value: $ty) -> Self {
+                WrenValue::$variant(value.into())
+            }
+        })*
+    };
+}
+
+impl_from_wren_value!(bool => Bool, f64 => Num, f32 => Num, i32 => Num, u32 => Num, String => Str, &str => Str);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+/// A Wren Hook. The result of creating this event will be
+/// a call to the static method of the `Game` class named after the hook_name, taking as many parameters as there are arguments
+///
+/// Serializable, e.g. for forwarding events over the network with a [`ScriptEventMirror`]
+pub struct WrenEvent {
+    pub hook_name: HookName,
+    pub args: Vec<WrenValue>,
+    pub recipients: Recipients,
+}
+
+impl ScriptEvent for WrenEvent {
+    fn recipients(&self) -> &crate::Recipients {
+        &self.recipients
+    }
+}
+
+struct WrenVm(VMWrapper);
+
+// safety: the virtual machine does not rely on thread local state, and it is owned by a single
+// context which only hands out access to it behind a mutex
+unsafe impl Send for WrenVm {}
+
+/// The virtual machine running a single script
+pub struct WrenContext {
+    vm: Mutex<WrenVm>,
+}
+
+impl WrenContext {
+    /// The virtual machine of the script
+    pub fn vm(&mut self) -> &VMWrapper {
+        &self.vm.get_mut().expect("Could not lock the wren vm").0
+    }
+}
+
+/// Converts an error raised by a script into a [`ScriptError`] of the matching category
+pub fn script_error(script: &str, error: &VMError) -> ScriptError {
+    let script = script.to_owned();
+    let msg = error.to_string();
+    match error {
+        VMError::Compile { .. } => ScriptError::SyntaxError { script, msg },
+        _ => ScriptError::RuntimeError { script, msg },
+    }
+}
+
+/// Script host running Wren scripts with ruwren.
+///
+/// API providers attach their API by publishing modules of foreign classes to the [`ModuleLibrary`] each script's
+/// virtual machine is built with, setup of the scripts can then run wren code importing them.
+#[derive(Resource, Default)]
+pub struct WrenScriptHost;
+
+/// The runtime error wren raises when the hook class has no static method for the hook with the given arity
+fn missing_hook_error(hook: &str, arity: usize) -> String {
+    format!(
+        "{WREN_HOOK_CLASS} metaclass does not implement '{hook}({})'",
+        vec!["_"; arity].join(",")
+    )
+}
+
+impl WrenScriptHost {
+    /// Calls the static method of the hook class named after the hook, unless the script does not declare it
+    fn call_hook(
+        vm: &VMWrapper,
+        hook: &str,
+        args: &[WrenValue],
+        script_data: &ScriptData,
+    ) -> Result<(), ScriptError> {
+        let has_class = vm.execute(|vm| {
+            vm.ensure_slots(args.len() + 1);
+            let has_class = vm.get_variable(MAIN_MODULE, WREN_HOOK_CLASS, 0);
+            for (slot, arg) in args.iter().enumerate() {
+                arg.set_slot(vm, slot + 1);
+            }
+            has_class
+        });
+        if !has_class {
+            return Ok(()); // not subscribed to any events
+        }
+
+        let handle = vm.make_call_handle(FunctionSignature::new_function(hook, args.len()));
+        match vm.call_handle(&handle) {
+            Ok(()) => Ok(()),
+            // not subscribed to this event, wren cannot tell whether a method exists before calling it.
+            // Only the error for the hook method itself counts, not those of methods the hook calls in turn
+            Err(VMError::Runtime { error, .. })
+                if error.trim_end_matches('.') == missing_hook_error(hook, args.len()) =>
+            {
+                Ok(())
+            }
+            Err(e) => Err(script_error(script_data.name, &e)),
+        }
+    }
+}
+
+impl ScriptHost for WrenScriptHost {
+    type ScriptContext = WrenContext;
+    type APITarget = ModuleLibrary;
+    type ScriptEvent = WrenEvent;
+    type ScriptAsset = WrenFile;
+    type DocTarget = WrenDocFragment;
+
+    fn register_with_app(app: &mut App, stage: impl StageLabel) {
+        app.add_priority_event::<Self::ScriptEvent>()
+            .add_asset::<WrenFile>()
+            .init_asset_loader::<WrenLoader>()
+            .init_resource::<CachedScriptState<Self>>()
+            .init_resource::<ScriptContexts<Self::ScriptContext>>()
+            .init_resource::<APIProviders<Self>>()
+            .register_type::<ScriptCollection<Self::ScriptAsset>>()
+            .register_type::<Script<Self::ScriptAsset>>()
+            .register_type::<Handle<WrenFile>>()
+            .add_system_set_to_stage(
+                stage,
+                SystemSet::new()
+                    .with_system(
+                        script_add_synchronizer::<Self>.before(script_remove_synchronizer::<Self>),
+                    )
+                    .with_system(
+                        script_remove_synchronizer::<Self>
+                            .before(script_hot_reload_handler::<Self>),
+                    )
+                    .with_system(script_hot_reload_handler::<Self>),
+            );
+    }
+
+    fn load_script(
+        &mut self,
+        script: &[u8],
+        script_data: &ScriptData,
+        providers: &mut APIProviders<Self>,
+    ) -> Result<Self::ScriptContext, ScriptError> {
+        let source = std::str::from_utf8(script).map_err(|e| ScriptError::FailedToLoad {
+            script: script_data.name.to_owned(),
+            msg: e.to_string(),
+        })?;
+
+        // foreign classes have to be published before the virtual machine is built
+        let mut library = ModuleLibrary::new();
+        providers.attach_all(&mut library)?;
+
+        let vm = VMConfig::new().library(&library).build();
+        vm.interpret(MAIN_MODULE, source)
+            .map_err(|e| script_error(script_data.name, &e))?;
+
+        Ok(WrenContext {
+            vm: Mutex::new(WrenVm(vm)),
+        })
+    }
+
+    fn setup_script(
+        &mut self,
+        script_data: &ScriptData,
+        ctx: &mut Self::ScriptContext,
+        providers: &mut APIProviders<Self>,
+    ) -> Result<(), ScriptError> {
+        providers.setup_all(script_data, ctx)
+    }
+
+    fn handle_events<'a>(
+        &self,
+        world: &mut World,
+        events: &[Self::ScriptEvent],
+        ctxs: impl Iterator<Item = (ScriptData<'a>, &'a mut Self::ScriptContext)>,
+        providers: &mut APIProviders<Self>,
+    ) {
+        // copied since the world is handed to the scripts while they handle events
+        let routes = world
+            .get_resource::<ScriptHookRoutes>()
+            .filter(|r| !r.is_empty())
+            .cloned();
+
+        // safety:
+        // - we have &mut World access
+        // - we do not use world_ptr after using the world reference which it's derived from
+        let world_ptr = unsafe { WorldPointer::new(world) };
+
+        ctxs.for_each(|(script_data, ctx)| {
+            providers
+                .setup_runtime_all(world_ptr.clone(), &script_data, ctx)
+                .expect("Could not setup script runtime");
+
+            let vm = ctx.vm();
+
+            for event in events {
+                // check if this script should handle this event
+                if !event.recipients().is_recipient(&script_data) {
+                    continue;
+                }
+
+                let hook = match &routes {
+                    Some(routes) => routes.function_for(script_data.name, event.hook_name),
+                    None => event.hook_name,
+                };
+
+                if let Err(error) = Self::call_hook(vm, hook.as_str(), &event.args, &script_data) {
+                    let mut world = world_ptr.write();
+                    let mut state: CachedScriptState<Self> = world.remove_resource().unwrap();
+
+                    let (_, mut error_wrt, _) = state.event_state.get_mut(&mut world);
+
+                    error!("{}", error);
+                    error_wrt.send(ScriptErrorEvent {
+                        sid: script_data.sid,
+                        error,
+                    });
+                    world.insert_resource(state);
+                }
+            }
+        });
+
+        world_ptr.invalidate();
+    }
+}
@@ -17,7 +17,7 @@ fn main() {
         "lua" => {
             #[cfg(all(feature = "lua", feature = "lua_script_api"))]
             app.add_script_host::<LuaScriptHost<()>, _>(CoreStage::PostUpdate)
-                .add_api_provider::<LuaScriptHost<()>>(Box::new(LuaBevyAPIProvider))
+                .add_api_provider::<LuaScriptHost<()>>(Box::new(lua_core_api()))
                 .update_documentation::<LuaScriptHost<()>>();
 
             #[cfg(any(not(feature = "lua"), not(feature = "lua_script_api")))]
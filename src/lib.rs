@@ -24,6 +24,36 @@ pub mod rhai {
     }
 }
 
+#[cfg(feature = "python")]
+pub mod python {
+    pub use bevy_mod_scripting_python::*;
+}
+
+#[cfg(feature = "js")]
+pub mod js {
+    pub use bevy_mod_scripting_js::*;
+}
+
+#[cfg(feature = "wasm")]
+pub mod wasm {
+    pub use bevy_mod_scripting_wasm::*;
+}
+
+#[cfg(feature = "rune")]
+pub mod rune {
+    pub use bevy_mod_scripting_rune::*;
+}
+
+#[cfg(feature = "wren")]
+pub mod wren {
+    pub use bevy_mod_scripting_wren::*;
+}
+
+#[cfg(feature = "graph")]
+pub mod graph {
+    pub use bevy_mod_scripting_graph::*;
+}
+
 #[cfg(any(feature = "lua_script_api", feature = "rhai_script_api"))]
 pub mod api {
     pub use bevy_script_api::*;
@@ -32,12 +62,33 @@ pub mod api {
 pub mod prelude {
     pub use bevy_mod_scripting_core::prelude::*;
 
+    #[cfg(feature = "auto_register")]
+    pub use bevy_mod_scripting_derive::script_api_provider;
+
     #[cfg(feature = "lua")]
     pub use bevy_mod_scripting_lua::prelude::*;
 
     #[cfg(feature = "rhai")]
     pub use bevy_mod_scripting_rhai::prelude::*;
 
+    #[cfg(feature = "python")]
+    pub use bevy_mod_scripting_python::prelude::*;
+
+    #[cfg(feature = "js")]
+    pub use bevy_mod_scripting_js::prelude::*;
+
+    #[cfg(feature = "wasm")]
+    pub use bevy_mod_scripting_wasm::prelude::*;
+
+    #[cfg(feature = "rune")]
+    pub use bevy_mod_scripting_rune::prelude::*;
+
+    #[cfg(feature = "wren")]
+    pub use bevy_mod_scripting_wren::prelude::*;
+
+    #[cfg(feature = "graph")]
+    pub use bevy_mod_scripting_graph::prelude::*;
+
     #[cfg(any(feature = "lua_script_api", feature = "rhai_script_api"))]
     pub use bevy_script_api::prelude::*;
 }